@@ -0,0 +1,44 @@
+//! TechCrunch 形式の記事ページで「全体を掃除してから再パースして選択」する旧方式と，
+//! 1回の DOM パースで選択と除去を行う `select_clean_fragments` を比較する．
+//!
+//! `cargo bench --bench clean_select`
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use news_clipper::models::web_article::{
+    clean_html, exclude_selectors_with, select_clean_fragments,
+};
+
+const FIXTURE: &str = include_str!("fixtures/techcrunch_article.html");
+const SELECTOR: &str = "main div.entry-content p";
+
+fn clean_then_select(html: &str) -> String {
+    let cleaned = clean_html(html);
+    let document = scraper::Html::parse_document(&cleaned);
+    let selector = scraper::Selector::parse(SELECTOR).unwrap();
+    document
+        .select(&selector)
+        .map(|x| x.html())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn single_pass(html: &str, excludes: &[&str]) -> String {
+    select_clean_fragments(html, SELECTOR, excludes)
+        .unwrap()
+        .join("\n")
+}
+
+fn bench_clean_select(c: &mut Criterion) {
+    let excludes = exclude_selectors_with(&[]);
+    let mut group = c.benchmark_group("techcrunch_parse_article");
+    group.bench_function("clean_then_select", |b| {
+        b.iter(|| clean_then_select(black_box(FIXTURE)))
+    });
+    group.bench_function("single_pass", |b| {
+        b.iter(|| single_pass(black_box(FIXTURE), &excludes))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_clean_select);
+criterion_main!(benches);
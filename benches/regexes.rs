@@ -0,0 +1,61 @@
+//! 1回の巡回で数千件の記事を処理する場合に，正規表現を呼び出しごとにコンパイルする旧方式と，
+//! `shared::regexes` のコンパイル済みの正規表現を使う方式を比較する．
+//!
+//! `cargo bench --bench regexes`
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use news_clipper::shared::regexes;
+use regex::Regex;
+
+const ARTICLES: usize = 2_000;
+const TITLE: &str = "<![CDATA[Rust 1.90 リリース：新しい  lint と   高速化]]>";
+const TEXT: &str = "本文の1段落目です．  \n\n\n   2段落目   は\tここから．\n\n\n\n以上．";
+
+fn per_call(title: &str, text: &str) -> (String, String) {
+    let cdata = Regex::new(r"<!\[CDATA\[(?<text>.+?)\]\]>").unwrap();
+    let title = cdata
+        .captures(title)
+        .and_then(|cap| cap.name("text").map(|m| m.as_str().to_string()))
+        .unwrap_or_else(|| title.to_string());
+    let blank_lines = Regex::new(r"\n\s*\n\s*\n").unwrap();
+    let text = blank_lines.replace_all(text, "\n\n").to_string();
+    let whitespace = Regex::new(r"\s\s+").unwrap();
+    (title, whitespace.replace_all(&text, "\n").to_string())
+}
+
+fn precompiled(title: &str, text: &str) -> (String, String) {
+    let title = regexes::cdata()
+        .captures(title)
+        .and_then(|cap| cap.name("text").map(|m| m.as_str().to_string()))
+        .unwrap_or_else(|| title.to_string());
+    let text = regexes::blank_lines().replace_all(text, "\n\n").to_string();
+    (
+        title,
+        regexes::repeated_whitespace()
+            .replace_all(&text, "\n")
+            .to_string(),
+    )
+}
+
+fn bench_regexes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("regexes_per_run");
+    group.sample_size(10);
+    group.bench_function("compile_per_call", |b| {
+        b.iter(|| {
+            for _ in 0..ARTICLES {
+                black_box(per_call(black_box(TITLE), black_box(TEXT)));
+            }
+        })
+    });
+    group.bench_function("precompiled", |b| {
+        b.iter(|| {
+            for _ in 0..ARTICLES {
+                black_box(precompiled(black_box(TITLE), black_box(TEXT)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_regexes);
+criterion_main!(benches);
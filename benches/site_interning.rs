@@ -0,0 +1,71 @@
+//! 数万件の記事を読み込む場合に，記事ごとにサイト名・URL の文字列を複製する旧方式と，
+//! `WebSite::intern` で1つの `Arc<WebSite>` を共有する方式を比較する．
+//! 実行時に，両方式でサイトの文字列が確保するヒープのバイト数も表示する．
+//!
+//! `cargo bench --bench site_interning`
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use news_clipper::models::web_article::WebSite;
+use std::sync::Arc;
+
+const ARTICLES: usize = 20_000;
+const SITES: usize = 80;
+
+fn site(i: usize) -> (String, String) {
+    let n = i % SITES;
+    (
+        format!("Example Tech Blog {}", n),
+        format!("https://blog{}.example.com/feed.xml", n),
+    )
+}
+
+fn owned() -> Vec<WebSite> {
+    (0..ARTICLES)
+        .map(|i| {
+            let (name, url) = site(i);
+            WebSite::new(name, url)
+        })
+        .collect()
+}
+
+fn interned() -> Vec<Arc<WebSite>> {
+    (0..ARTICLES)
+        .map(|i| {
+            let (name, url) = site(i);
+            WebSite::intern(name, url)
+        })
+        .collect()
+}
+
+/// サイトの文字列が確保しているヒープのバイト数（`Arc` は同じものを1回だけ数える）
+fn report_heap_bytes() {
+    let strings = |site: &WebSite| site.name.capacity() + site.url.capacity();
+    let owned_bytes: usize = owned().iter().map(strings).sum();
+    let sites = interned();
+    let mut unique: Vec<&Arc<WebSite>> = sites.iter().collect();
+    unique.sort_by_key(|site| Arc::as_ptr(site));
+    unique.dedup_by(|a, b| Arc::ptr_eq(a, b));
+    let interned_bytes: usize = unique
+        .iter()
+        .map(|site| strings(site) + std::mem::size_of::<WebSite>())
+        .sum();
+    println!(
+        "site strings for {} articles: owned = {} bytes, interned = {} bytes (+ {} bytes of Arc pointers)",
+        ARTICLES,
+        owned_bytes,
+        interned_bytes,
+        ARTICLES * std::mem::size_of::<Arc<WebSite>>()
+    );
+}
+
+fn bench_site_interning(c: &mut Criterion) {
+    report_heap_bytes();
+    let mut group = c.benchmark_group("site_metadata_per_load");
+    group.sample_size(10);
+    group.bench_function("owned_strings", |b| b.iter(|| black_box(owned())));
+    group.bench_function("interned", |b| b.iter(|| black_box(interned())));
+    group.finish();
+}
+
+criterion_group!(benches, bench_site_interning);
+criterion_main!(benches);
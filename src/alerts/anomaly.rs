@@ -0,0 +1,245 @@
+//! サイトごとの記事数・言語・概要の長さの急な変化を検知する．
+//!
+//! スクレイピングの破損（セレクタの変更で概要が空になる，別言語のページを拾う）や
+//! 上流のインシデント（記事の急増・急減）の兆候として，アラートルールと同じ `Alert` を出力する．
+
+use crate::alerts::{Alert, Severity};
+use crate::models::report::FetchReport;
+use crate::storage::FetchRun;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AnomalyKind {
+    VolumeSpike,
+    VolumeDrop,
+    LanguageDrift,
+    SummaryCollapse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub site: String,
+    pub kind: AnomalyKind,
+    pub observed: f64,
+    /// 過去の巡回における平均値
+    pub baseline: f64,
+    pub message: String,
+}
+
+impl Anomaly {
+    /// アラートとして通知できる形にする（記事の URL の代わりにサイト名を使う）
+    pub fn to_alert(&self) -> Alert {
+        let severity = match self.kind {
+            AnomalyKind::VolumeDrop | AnomalyKind::SummaryCollapse => Severity::High,
+            AnomalyKind::VolumeSpike | AnomalyKind::LanguageDrift => Severity::Medium,
+        };
+        Alert {
+            rule: format!("anomaly:{}", self.kind),
+            severity,
+            keyword: self.kind.to_string(),
+            article_url: String::new(),
+            title: format!("{}: {}", self.site, self.message),
+            site: self.site.clone(),
+            snippets: vec![format!("observed {:.2}, baseline {:.2}", self.observed, self.baseline)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyDetector {
+    /// 判定に必要な過去の巡回数
+    pub min_history: usize,
+    /// 記事数の急増・急減と判定する z スコア
+    pub volume_z_threshold: f64,
+    /// 日本語比率の変化量がこれを超えたら言語の変化と判定する
+    pub language_drift_threshold: f64,
+    /// 概要の平均文字数が過去の平均のこの比率を下回ったら概要の欠落と判定する
+    pub summary_collapse_ratio: f64,
+    /// 概要の欠落を判定する過去の平均文字数の下限（もともと概要の短いサイトは対象外）
+    pub min_summary_chars: f64,
+}
+
+impl Default for AnomalyDetector {
+    fn default() -> Self {
+        Self {
+            min_history: 5,
+            volume_z_threshold: 3.0,
+            language_drift_threshold: 0.3,
+            summary_collapse_ratio: 0.3,
+            min_summary_chars: 40.0,
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn std_dev(values: &[f64], mean: f64) -> f64 {
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+impl AnomalyDetector {
+    /// `current` を同じサイトの過去の成功した巡回結果と比較する
+    pub fn detect(&self, history: &[&FetchReport], current: &FetchReport) -> Vec<Anomaly> {
+        let history: Vec<&FetchReport> = history
+            .iter()
+            .copied()
+            .filter(|r| r.site == current.site && r.is_ok() && !r.budget_exhausted)
+            .collect();
+        if !current.is_ok() || current.budget_exhausted || history.len() < self.min_history {
+            return Vec::new();
+        }
+        let anomaly = |kind, observed: f64, baseline: f64, message: String| Anomaly {
+            site: current.site.clone(),
+            kind,
+            observed,
+            baseline,
+            message,
+        };
+        let mut anomalies = Vec::new();
+
+        let counts: Vec<f64> = history.iter().map(|r| r.fetched as f64).collect();
+        let count_mean = mean(&counts);
+        // 記事数が毎回同じサイトでも極端な変化を拾えるよう，標準偏差に下限を設ける
+        let count_std = std_dev(&counts, count_mean).max(1.0).max(count_mean * 0.1);
+        let observed = current.fetched as f64;
+        let z = (observed - count_mean) / count_std;
+        if z > self.volume_z_threshold {
+            anomalies.push(anomaly(
+                AnomalyKind::VolumeSpike,
+                observed,
+                count_mean,
+                format!("article count spiked to {} (usually {:.1})", current.fetched, count_mean),
+            ));
+        } else if z < -self.volume_z_threshold || (current.fetched == 0 && count_mean >= 3.0) {
+            anomalies.push(anomaly(
+                AnomalyKind::VolumeDrop,
+                observed,
+                count_mean,
+                format!("article count dropped to {} (usually {:.1})", current.fetched, count_mean),
+            ));
+        }
+        if current.fetched == 0 {
+            return anomalies;
+        }
+
+        let with_articles: Vec<&&FetchReport> = history.iter().filter(|r| r.fetched > 0).collect();
+        if with_articles.is_empty() {
+            return anomalies;
+        }
+        let ratios: Vec<f64> = with_articles.iter().map(|r| r.content.japanese_ratio).collect();
+        let ratio_mean = mean(&ratios);
+        let observed = current.content.japanese_ratio;
+        if (observed - ratio_mean).abs() > self.language_drift_threshold {
+            anomalies.push(anomaly(
+                AnomalyKind::LanguageDrift,
+                observed,
+                ratio_mean,
+                format!(
+                    "Japanese text ratio changed from {:.0}% to {:.0}%",
+                    ratio_mean * 100.0,
+                    observed * 100.0
+                ),
+            ));
+        }
+
+        let lengths: Vec<f64> = with_articles.iter().map(|r| r.content.mean_description_chars).collect();
+        let length_mean = mean(&lengths);
+        let observed = current.content.mean_description_chars;
+        if length_mean >= self.min_summary_chars && observed < length_mean * self.summary_collapse_ratio {
+            anomalies.push(anomaly(
+                AnomalyKind::SummaryCollapse,
+                observed,
+                length_mean,
+                format!(
+                    "mean description length collapsed to {:.0} chars (usually {:.0})",
+                    observed, length_mean
+                ),
+            ));
+        }
+        anomalies
+    }
+
+    /// 今回の巡回結果の全サイトを過去の履歴と比較する
+    pub fn detect_all(&self, history: &[FetchRun], current: &[FetchReport]) -> Vec<Anomaly> {
+        let past: Vec<&FetchReport> = history.iter().map(|run| &run.report).collect();
+        current.iter().flat_map(|report| self.detect(&past, report)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::report::ContentStats;
+
+    fn report(fetched: usize, japanese_ratio: f64, description_chars: f64) -> FetchReport {
+        FetchReport {
+            fetched,
+            content: ContentStats {
+                japanese_ratio,
+                mean_description_chars: description_chars,
+            },
+            ..FetchReport::new("Example")
+        }
+    }
+
+    fn history() -> Vec<FetchReport> {
+        [20, 22, 18, 21, 19]
+            .into_iter()
+            .map(|n| report(n, 0.8, 120.0))
+            .collect()
+    }
+
+    fn kinds(current: &FetchReport) -> Vec<AnomalyKind> {
+        let history = history();
+        let past: Vec<&FetchReport> = history.iter().collect();
+        AnomalyDetector::default()
+            .detect(&past, current)
+            .into_iter()
+            .map(|a| a.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_normal_run() {
+        assert!(kinds(&report(23, 0.75, 110.0)).is_empty());
+    }
+
+    #[test]
+    fn test_detect_volume_anomalies() {
+        assert_eq!(kinds(&report(80, 0.8, 120.0)), vec![AnomalyKind::VolumeSpike]);
+        assert_eq!(kinds(&report(0, 0.0, 0.0)), vec![AnomalyKind::VolumeDrop]);
+        let skipped = FetchReport {
+            budget_exhausted: true,
+            ..report(0, 0.0, 0.0)
+        };
+        assert!(kinds(&skipped).is_empty());
+    }
+
+    #[test]
+    fn test_detect_content_drift() {
+        assert_eq!(
+            kinds(&report(20, 0.1, 10.0)),
+            vec![AnomalyKind::LanguageDrift, AnomalyKind::SummaryCollapse]
+        );
+    }
+
+    #[test]
+    fn test_detect_requires_history_and_converts_to_alert() {
+        let past = [report(20, 0.8, 120.0)];
+        let past: Vec<&FetchReport> = past.iter().collect();
+        assert!(AnomalyDetector::default().detect(&past, &report(80, 0.8, 120.0)).is_empty());
+
+        let history = history();
+        let past: Vec<&FetchReport> = history.iter().collect();
+        let anomaly = &AnomalyDetector::default().detect(&past, &report(0, 0.0, 0.0))[0];
+        let alert = anomaly.to_alert();
+        assert_eq!(alert.rule, "anomaly:volume_drop");
+        assert_eq!(alert.severity, Severity::High);
+        assert!(alert.title.starts_with("Example: article count dropped to 0"));
+    }
+}
@@ -0,0 +1,579 @@
+//! 一定以上の重要度のアラートが発火したときに，GitHub の Issue か Jira のチケットを作成する．
+//!
+//! 同じ話題（同じ記事の URL，共通の CVE 番号，似たタイトル）の Issue を作成済みの場合は新たに作成せず，
+//! 作成済みの Issue に記事を紐付けるだけにする．作成した Issue は `$CLIPPER_DATA_DIR/issues.json` に記録する．
+
+use crate::alerts::{Alert, Severity};
+use crate::models::dedup::{DEFAULT_TITLE_SIMILARITY, normalize_url, title_similarity};
+use crate::models::web_article::WebArticle;
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::regexes::cve_id;
+use crate::shared::template::{load_template, render_template};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// GitHub のリポジトリ（`owner/repo`）
+pub const GITHUB_REPO_ENV: &str = "CLIPPER_GITHUB_REPO";
+/// Issues の書き込み権限のあるトークン
+pub const GITHUB_TOKEN_ENV: &str = "CLIPPER_GITHUB_TOKEN";
+/// Issue に付けるラベル（カンマ区切り）
+pub const GITHUB_LABELS_ENV: &str = "CLIPPER_GITHUB_LABELS";
+/// GitHub Enterprise Server の API の URL
+pub const GITHUB_API_URL_ENV: &str = "CLIPPER_GITHUB_API_URL";
+/// Jira の URL（例: `https://example.atlassian.net`）
+pub const JIRA_URL_ENV: &str = "CLIPPER_JIRA_URL";
+/// チケットを作成するプロジェクトのキー
+pub const JIRA_PROJECT_ENV: &str = "CLIPPER_JIRA_PROJECT";
+/// API トークンを発行したアカウントのメールアドレス
+pub const JIRA_EMAIL_ENV: &str = "CLIPPER_JIRA_EMAIL";
+pub const JIRA_TOKEN_ENV: &str = "CLIPPER_JIRA_TOKEN";
+/// 課題タイプ（デフォルト `Task`）
+pub const JIRA_ISSUE_TYPE_ENV: &str = "CLIPPER_JIRA_ISSUE_TYPE";
+/// Issue を作成するアラートの重要度の下限（デフォルト `high`）
+pub const ISSUE_MIN_SEVERITY_ENV: &str = "CLIPPER_ISSUE_MIN_SEVERITY";
+/// タイトルのテンプレート（minijinja）
+pub const ISSUE_TITLE_TEMPLATE_ENV: &str = "CLIPPER_ISSUE_TITLE_TEMPLATE";
+/// 本文のテンプレート（minijinja）のファイルパス
+pub const ISSUE_BODY_TEMPLATE_ENV: &str = "CLIPPER_ISSUE_BODY_TEMPLATE";
+
+/// 作成した Issue の記録
+pub const ISSUE_LEDGER_FILE: &str = "issues.json";
+const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+const DEFAULT_JIRA_ISSUE_TYPE: &str = "Task";
+/// これより前に作成した Issue とは重複を判定しない（同じ CVE の続報は新しい Issue にする）
+const DEDUP_WINDOW_DAYS: i64 = 30;
+
+pub const DEFAULT_TITLE_TEMPLATE: &str =
+    "[{{ severity }}] {{ title }}{% if cves %} ({{ cves | join(', ') }}){% endif %}";
+pub const DEFAULT_BODY_TEMPLATE: &str = r#"**Rule:** {{ rule }} ({{ severity }}, keyword: `{{ keyword }}`)
+**Source:** {{ site }}
+
+## Summary
+
+{{ summary }}
+{% if snippets %}
+
+## Excerpts
+
+{% for snippet in snippets %}
+> {{ snippet }}
+{% endfor %}
+{% endif %}
+{% if cves %}
+
+## CVEs
+
+{% for cve in cves %}
+- [{{ cve }}](https://nvd.nist.gov/vuln/detail/{{ cve }})
+{% endfor %}
+{% endif %}
+
+## Links
+
+{% for link in links %}
+- {{ link }}
+{% endfor %}
+"#;
+
+/// テキスト中の CVE 番号（大文字にして重複を除き，出現順）
+pub fn extract_cves(text: &str) -> Vec<String> {
+    let mut cves: Vec<String> = Vec::new();
+    for m in cve_id().find_iter(text) {
+        let cve = m.as_str().to_uppercase();
+        if !cves.contains(&cve) {
+            cves.push(cve);
+        }
+    }
+    cves
+}
+
+/// Issue の作成先
+#[derive(Clone, PartialEq, Eq)]
+pub enum IssueTracker {
+    GitHub {
+        api_url: String,
+        repo: String,
+        token: String,
+        labels: Vec<String>,
+    },
+    Jira {
+        base_url: String,
+        project: String,
+        email: String,
+        token: String,
+        issue_type: String,
+    },
+}
+
+impl std::fmt::Debug for IssueTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GitHub { repo, .. } => f.debug_struct("GitHub").field("repo", repo).finish_non_exhaustive(),
+            Self::Jira { project, .. } => f.debug_struct("Jira").field("project", project).finish_non_exhaustive(),
+        }
+    }
+}
+
+impl IssueTracker {
+    /// `CLIPPER_GITHUB_REPO` か `CLIPPER_JIRA_URL` の設定から作成する（どちらも未設定の場合は `None`）
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let var = |key: &str| std::env::var(key).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let required = |key: &str| {
+            var(key).ok_or_else(|| AppError::InternalError(format!("{} is required for the issue sink", key)))
+        };
+        match (var(GITHUB_REPO_ENV), var(JIRA_URL_ENV)) {
+            (Some(_), Some(_)) => Err(AppError::InternalError(format!(
+                "Set either {} or {}, not both",
+                GITHUB_REPO_ENV, JIRA_URL_ENV
+            ))),
+            (Some(repo), None) => Ok(Some(Self::GitHub {
+                api_url: var(GITHUB_API_URL_ENV).unwrap_or_else(|| DEFAULT_GITHUB_API_URL.to_string()),
+                repo,
+                token: required(GITHUB_TOKEN_ENV)?,
+                labels: var(GITHUB_LABELS_ENV)
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })),
+            (None, Some(base_url)) => Ok(Some(Self::Jira {
+                base_url,
+                project: required(JIRA_PROJECT_ENV)?,
+                email: required(JIRA_EMAIL_ENV)?,
+                token: required(JIRA_TOKEN_ENV)?,
+                issue_type: var(JIRA_ISSUE_TYPE_ENV).unwrap_or_else(|| DEFAULT_JIRA_ISSUE_TYPE.to_string()),
+            })),
+            (None, None) => Ok(None),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::GitHub { .. } => "GitHub",
+            Self::Jira { .. } => "Jira",
+        }
+    }
+
+    /// Issue を作成するリクエスト
+    pub fn request(&self, client: &request::Client, title: &str, body: &str) -> request::RequestBuilder {
+        match self {
+            Self::GitHub {
+                api_url,
+                repo,
+                token,
+                labels,
+            } => client
+                .post(format!("{}/repos/{}/issues", api_url.trim_end_matches('/'), repo))
+                .bearer_auth(token)
+                .header(request::header::ACCEPT, "application/vnd.github+json")
+                .header(request::header::USER_AGENT, env!("CARGO_PKG_NAME"))
+                .json(&json!({ "title": title, "body": body, "labels": labels })),
+            Self::Jira {
+                base_url,
+                project,
+                email,
+                token,
+                issue_type,
+            } => client
+                .post(format!("{}/rest/api/2/issue", base_url.trim_end_matches('/')))
+                .basic_auth(email, Some(token))
+                .json(&json!({
+                    "fields": {
+                        "project": { "key": project },
+                        "summary": title,
+                        "description": body,
+                        "issuetype": { "name": issue_type },
+                    }
+                })),
+        }
+    }
+
+    /// 作成した Issue のキー（`#12`，`SEC-34`）と URL をレスポンスから読み取る
+    pub fn parse_created(&self, response: &Value) -> AppResult<(String, String)> {
+        let missing = || AppError::InternalError(format!("Unexpected {} response: {}", self.name(), response));
+        match self {
+            Self::GitHub { .. } => {
+                let number = response["number"].as_u64().ok_or_else(missing)?;
+                let url = response["html_url"].as_str().ok_or_else(missing)?;
+                Ok((format!("#{}", number), url.to_string()))
+            }
+            Self::Jira { base_url, .. } => {
+                let key = response["key"].as_str().ok_or_else(missing)?;
+                Ok((key.to_string(), format!("{}/browse/{}", base_url.trim_end_matches('/'), key)))
+            }
+        }
+    }
+
+    pub async fn create(&self, title: &str, body: &str) -> AppResult<(String, String)> {
+        let response = self.request(&request::Client::new(), title, body).send().await?;
+        if !response.status().is_success() {
+            return Err(AppError::InternalError(format!(
+                "{} returned {}: {}",
+                self.name(),
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )));
+        }
+        self.parse_created(&response.json().await?)
+    }
+}
+
+/// 作成済みの Issue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssueRecord {
+    pub key: String,
+    pub url: String,
+    /// Issue を作成したきっかけの記事のタイトル（類似タイトルの判定に使う）
+    pub story_title: String,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    /// 紐付けた記事の URL（正規化済み）
+    #[serde(default)]
+    pub article_urls: Vec<String>,
+    pub created_at: DateTime<Local>,
+}
+
+/// 作成済みの Issue の記録
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IssueLedger {
+    #[serde(default)]
+    pub issues: Vec<IssueRecord>,
+}
+
+impl IssueLedger {
+    /// ファイルが無い場合は空の記録
+    pub fn load(path: &Path) -> AppResult<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(AppError::InternalError(format!("Failed to read {:?}: {}", path, e))),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> AppResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", parent, e)))?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e)))
+    }
+
+    /// `now` から `DEDUP_WINDOW_DAYS` 日以内に作成した，同じ話題の Issue の位置
+    pub fn find_story(&self, article: &WebArticle, cves: &[String], now: DateTime<Local>) -> Option<usize> {
+        let url = normalize_url(&article.article_url);
+        self.issues.iter().position(|issue| {
+            now - issue.created_at <= Duration::days(DEDUP_WINDOW_DAYS)
+                && (issue.article_urls.contains(&url)
+                    || cves.iter().any(|cve| issue.cves.contains(cve))
+                    || title_similarity(&issue.story_title, &article.title) >= DEFAULT_TITLE_SIMILARITY)
+        })
+    }
+
+    /// 作成済みの Issue に記事を紐付ける
+    pub fn link(&mut self, index: usize, article: &WebArticle, cves: &[String]) {
+        let issue = &mut self.issues[index];
+        let url = normalize_url(&article.article_url);
+        if !issue.article_urls.contains(&url) {
+            issue.article_urls.push(url);
+        }
+        for cve in cves {
+            if !issue.cves.contains(cve) {
+                issue.cves.push(cve.clone());
+            }
+        }
+    }
+}
+
+/// `run` の結果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IssueReport {
+    /// 作成した Issue の URL
+    pub created: Vec<String>,
+    /// 作成済みの Issue に紐付けたアラートの数
+    pub linked: usize,
+    pub failed: usize,
+}
+
+/// アラートから Issue を作成する
+#[derive(Debug, Clone)]
+pub struct IssueSink {
+    pub tracker: IssueTracker,
+    pub min_severity: Severity,
+    pub title_template: String,
+    pub body_template: String,
+    pub ledger_path: PathBuf,
+}
+
+impl IssueSink {
+    /// Issue の作成先が設定されていれば作成する（未設定の場合は `None`）．作成した Issue は `data_dir` に記録する．
+    pub fn from_env(data_dir: &Path) -> AppResult<Option<Self>> {
+        let Some(tracker) = IssueTracker::from_env()? else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            tracker,
+            min_severity: parse_env_or(ISSUE_MIN_SEVERITY_ENV, Severity::High)?,
+            title_template: std::env::var(ISSUE_TITLE_TEMPLATE_ENV)
+                .ok()
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_TITLE_TEMPLATE.to_string()),
+            body_template: load_template(ISSUE_BODY_TEMPLATE_ENV, DEFAULT_BODY_TEMPLATE)?,
+            ledger_path: data_dir.join(ISSUE_LEDGER_FILE),
+        }))
+    }
+
+    /// テンプレートで描画したタイトルと本文
+    pub fn render(&self, alert: &Alert, article: &WebArticle, cves: &[String]) -> AppResult<(String, String)> {
+        let summary = article
+            .properties
+            .summary
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(article.description.trim());
+        let context = json!({
+            "rule": alert.rule,
+            "severity": alert.severity.to_string(),
+            "keyword": alert.keyword,
+            "title": article.title.trim(),
+            "site": article.site.name,
+            "url": article.article_url,
+            "summary": summary,
+            "snippets": alert.snippets,
+            "cves": cves,
+            "links": [article.article_url.clone()],
+        });
+        let title = render_template(&self.title_template, &context)?;
+        let body = render_template(&self.body_template, &context)?;
+        Ok((title.trim().to_string(), body))
+    }
+
+    /// `min_severity` 以上のアラートごとに Issue を作成する．同じ話題の Issue が作成済みなら紐付けだけにする．
+    pub async fn run(
+        &self,
+        alerts: &[Alert],
+        articles: &[WebArticle],
+        now: DateTime<Local>,
+    ) -> AppResult<IssueReport> {
+        let by_url: HashMap<&str, &WebArticle> = articles.iter().map(|a| (a.article_url.as_str(), a)).collect();
+        let mut alerts: Vec<&Alert> = alerts.iter().filter(|a| a.severity >= self.min_severity).collect();
+        // 1つの記事で複数のルールが発火した場合は，重要度の高いルールで作成する
+        alerts.sort_by(|a, b| b.severity.cmp(&a.severity));
+
+        let mut ledger = IssueLedger::load(&self.ledger_path)?;
+        let mut report = IssueReport::default();
+        for alert in alerts {
+            let Some(article) = by_url.get(alert.article_url.as_str()) else {
+                continue;
+            };
+            let cves = extract_cves(&format!("{}\n{}\n{}", article.title, article.description, article.text));
+            if let Some(index) = ledger.find_story(article, &cves, now) {
+                ledger.link(index, article, &cves);
+                report.linked += 1;
+                continue;
+            }
+            let (title, body) = self.render(alert, article, &cves)?;
+            match self.tracker.create(&title, &body).await {
+                Ok((key, url)) => {
+                    tracing::info!("Created {} issue {} for {}", self.tracker.name(), key, article.article_url);
+                    ledger.issues.push(IssueRecord {
+                        key,
+                        url: url.clone(),
+                        story_title: article.title.clone(),
+                        cves,
+                        article_urls: vec![normalize_url(&article.article_url)],
+                        created_at: now,
+                    });
+                    report.created.push(url);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to create {} issue for {}: {}",
+                        self.tracker.name(),
+                        article.article_url,
+                        e
+                    );
+                    report.failed += 1;
+                }
+            }
+        }
+        ledger.save(&self.ledger_path)?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    fn article(title: &str, url: &str, text: &str) -> WebArticle {
+        ArticleBuilder::new(title)
+            .with_site("JPCERT", "https://www.jpcert.or.jp")
+            .with_url(url)
+            .with_description("Apache Tomcat の脆弱性に関する注意喚起")
+            .with_text(text)
+            .build()
+    }
+
+    fn alert(article: &WebArticle, severity: Severity) -> Alert {
+        Alert {
+            rule: "middleware".into(),
+            severity,
+            keyword: "tomcat".into(),
+            article_url: article.article_url.clone(),
+            title: article.title.clone(),
+            site: article.site.name.clone(),
+            snippets: vec!["Apache **Tomcat** にリモートコード実行の脆弱性".into()],
+        }
+    }
+
+    fn github() -> IssueTracker {
+        IssueTracker::GitHub {
+            api_url: DEFAULT_GITHUB_API_URL.into(),
+            repo: "example/security".into(),
+            token: "token".into(),
+            labels: vec!["security".into()],
+        }
+    }
+
+    fn sink(tracker: IssueTracker, ledger_path: PathBuf) -> IssueSink {
+        IssueSink {
+            tracker,
+            min_severity: Severity::High,
+            title_template: DEFAULT_TITLE_TEMPLATE.into(),
+            body_template: DEFAULT_BODY_TEMPLATE.into(),
+            ledger_path,
+        }
+    }
+
+    #[test]
+    fn test_extract_cves() {
+        assert_eq!(
+            extract_cves("cve-2025-1234 と CVE-2025-12345，再掲 CVE-2025-1234．CVE-25-1 は無効"),
+            vec!["CVE-2025-1234", "CVE-2025-12345"]
+        );
+    }
+
+    #[test]
+    fn test_render_issue() {
+        let article = article(
+            "Apache Tomcat の脆弱性について",
+            "https://www.jpcert.or.jp/at/2025/0001.html",
+            "CVE-2025-1234 が公開されました．",
+        );
+        let cves = extract_cves(&article.text);
+        let sink = sink(github(), PathBuf::from("issues.json"));
+        let (title, body) = sink.render(&alert(&article, Severity::High), &article, &cves).unwrap();
+        assert_eq!(title, "[high] Apache Tomcat の脆弱性について (CVE-2025-1234)");
+        assert!(body.contains("**Rule:** middleware (high, keyword: `tomcat`)"));
+        assert!(body.contains("Apache Tomcat の脆弱性に関する注意喚起"));
+        assert!(body.contains("> Apache **Tomcat**"));
+        assert!(body.contains("- [CVE-2025-1234](https://nvd.nist.gov/vuln/detail/CVE-2025-1234)"));
+        assert!(body.contains("- https://www.jpcert.or.jp/at/2025/0001.html"));
+    }
+
+    #[test]
+    fn test_tracker_requests() {
+        let client = request::Client::new();
+        let request = github().request(&client, "Title", "Body").build().unwrap();
+        assert_eq!(request.url().as_str(), "https://api.github.com/repos/example/security/issues");
+        assert_eq!(request.headers()[request::header::AUTHORIZATION], "Bearer token");
+        let payload: Value = serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(payload["labels"], json!(["security"]));
+        let created = github()
+            .parse_created(&json!({ "number": 12, "html_url": "https://github.com/example/security/issues/12" }))
+            .unwrap();
+        assert_eq!(created, ("#12".into(), "https://github.com/example/security/issues/12".into()));
+
+        let jira = IssueTracker::Jira {
+            base_url: "https://example.atlassian.net/".into(),
+            project: "SEC".into(),
+            email: "bot@example.com".into(),
+            token: "token".into(),
+            issue_type: DEFAULT_JIRA_ISSUE_TYPE.into(),
+        };
+        let request = jira.request(&client, "Title", "Body").build().unwrap();
+        assert_eq!(request.url().as_str(), "https://example.atlassian.net/rest/api/2/issue");
+        let payload: Value = serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(payload["fields"]["project"]["key"], "SEC");
+        assert_eq!(payload["fields"]["summary"], "Title");
+        let created = jira.parse_created(&json!({ "id": "10001", "key": "SEC-34" })).unwrap();
+        assert_eq!(created, ("SEC-34".into(), "https://example.atlassian.net/browse/SEC-34".into()));
+        assert!(jira.parse_created(&json!({ "errors": {} })).is_err());
+    }
+
+    #[test]
+    fn test_ledger_dedup() {
+        let now = Local::now();
+        let first = article(
+            "Apache Tomcat の脆弱性について",
+            "https://www.jpcert.or.jp/at/2025/0001.html",
+            "CVE-2025-1234",
+        );
+        let mut ledger = IssueLedger {
+            issues: vec![IssueRecord {
+                key: "#12".into(),
+                url: "https://github.com/example/security/issues/12".into(),
+                story_title: first.title.clone(),
+                cves: vec!["CVE-2025-1234".into()],
+                article_urls: vec![normalize_url(&first.article_url)],
+                created_at: now - Duration::days(1),
+            }],
+        };
+
+        let same_url = article("別のタイトル", "https://www.jpcert.or.jp/at/2025/0001.html?utm_source=rss", "");
+        assert_eq!(ledger.find_story(&same_url, &[], now), Some(0));
+        let same_cve = article("OpenSSL ではない別の話題", "https://example.com/a", "");
+        assert_eq!(ledger.find_story(&same_cve, &["CVE-2025-1234".into()], now), Some(0));
+        let similar = article("Apache Tomcat の脆弱性について（更新）", "https://example.com/b", "");
+        assert_eq!(ledger.find_story(&similar, &[], now), Some(0));
+        let other = article("Rust 1.90.0 をリリースしました", "https://example.com/c", "");
+        assert_eq!(ledger.find_story(&other, &[], now), None);
+        assert_eq!(ledger.find_story(&same_url, &[], now + Duration::days(DEDUP_WINDOW_DAYS + 1)), None);
+
+        ledger.link(0, &same_cve, &["CVE-2025-5678".into()]);
+        assert_eq!(ledger.issues[0].article_urls.len(), 2);
+        assert_eq!(ledger.issues[0].cves, vec!["CVE-2025-1234", "CVE-2025-5678"]);
+
+        let dir = std::env::temp_dir().join(format!("clipper-issues-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("issues.json");
+        assert_eq!(IssueLedger::load(&path).unwrap(), IssueLedger::default());
+        ledger.save(&path).unwrap();
+        assert_eq!(IssueLedger::load(&path).unwrap(), ledger);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_links_known_stories_without_request() {
+        let now = Local::now();
+        let dir = std::env::temp_dir().join(format!("clipper-issues-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("issues.json");
+        let known = article("Apache Tomcat の脆弱性について", "https://www.jpcert.or.jp/at/2025/0001.html", "");
+        IssueLedger {
+            issues: vec![IssueRecord {
+                key: "#12".into(),
+                url: "https://github.com/example/security/issues/12".into(),
+                story_title: known.title.clone(),
+                cves: Vec::new(),
+                article_urls: vec![normalize_url(&known.article_url)],
+                created_at: now,
+            }],
+        }
+        .save(&path)
+        .unwrap();
+
+        let low = article("低い重要度の記事", "https://example.com/low", "");
+        let alerts = vec![alert(&known, Severity::Critical), alert(&low, Severity::Medium)];
+        let report = sink(github(), path.clone()).run(&alerts, &[known, low], now).await.unwrap();
+        assert_eq!(report, IssueReport { created: Vec::new(), linked: 1, failed: 0 });
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
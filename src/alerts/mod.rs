@@ -0,0 +1,186 @@
+//! キーワードに基づくアラートルール．
+
+pub mod anomaly;
+pub mod issues;
+pub mod triage;
+
+use crate::enrichment::snippets::{SnippetOptions, keyword_snippets};
+use crate::models::web_article::WebArticle;
+use crate::shared::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use strum::{Display, EnumString};
+
+/// アラートルールの設定ファイル（TOML）のパスを指定する環境変数
+pub const ALERT_RULES_ENV: &str = "CLIPPER_ALERT_RULES";
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Display, EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+/// タイトル・概要・本文にいずれかのキーワードが含まれると発火するルール
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// 発火したアラート．通知にはタイトルだけでなくキーワード周辺の文（スニペット）を含める．
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub rule: String,
+    pub severity: Severity,
+    pub keyword: String,
+    pub article_url: String,
+    pub title: String,
+    pub site: String,
+    pub snippets: Vec<String>,
+}
+
+impl AlertRule {
+    /// 最初にマッチしたキーワードでアラートを作成する
+    pub fn evaluate(&self, article: &WebArticle, options: &SnippetOptions) -> Option<Alert> {
+        let haystack = format!("{}\n{}\n{}", article.title, article.description, article.text).to_lowercase();
+        let keyword = self
+            .keywords
+            .iter()
+            .find(|k| !k.trim().is_empty() && haystack.contains(&k.to_lowercase()))?;
+
+        // 本文が未取得の場合は概要から抜き出す
+        let source = if article.text.is_empty() { &article.description } else { &article.text };
+        Some(Alert {
+            rule: self.name.clone(),
+            severity: self.severity,
+            keyword: keyword.clone(),
+            article_url: article.article_url.clone(),
+            title: article.title.clone(),
+            site: article.site.name.clone(),
+            snippets: keyword_snippets(source, keyword, options),
+        })
+    }
+}
+
+/// アラートルールの設定ファイルの内容．
+///
+/// ```toml
+/// [[rules]]
+/// name = "middleware"
+/// keywords = ["nginx", "tomcat"]
+/// severity = "high"                  # low | medium | high | critical（デフォルト medium）
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRulesConfig {
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+}
+
+impl AlertRulesConfig {
+    pub fn from_toml_str(content: &str) -> AppResult<Self> {
+        toml::from_str(content).map_err(|e| AppError::InternalError(format!("Invalid alert rules: {}", e)))
+    }
+
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            AppError::InternalError(format!("Failed to read alert rules {:?}: {}", path, e))
+        })?;
+        Self::from_toml_str(&content)
+    }
+
+    /// `CLIPPER_ALERT_RULES` のルールを読み込む．未設定の場合はルール無し．
+    pub fn load_default() -> AppResult<Self> {
+        match std::env::var(ALERT_RULES_ENV) {
+            Ok(path) if !path.trim().is_empty() => Self::load(Path::new(path.trim())),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+/// 全ルールを記事一覧に適用する
+pub fn evaluate_rules(
+    rules: &[AlertRule],
+    articles: &[WebArticle],
+    options: &SnippetOptions,
+) -> Vec<Alert> {
+    articles
+        .iter()
+        .flat_map(|article| rules.iter().filter_map(move |rule| rule.evaluate(article, options)))
+        .collect()
+}
+
+/// 通知用のテキスト（タイトル，リンク，スニペット）
+pub fn render_alert_text(alert: &Alert) -> String {
+    let mut out = format!(
+        "[{}] {} ({})\n{}\n{}",
+        alert.severity, alert.rule, alert.site, alert.title, alert.article_url
+    );
+    for snippet in &alert.snippets {
+        out.push_str("\n> ");
+        out.push_str(snippet);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    #[test]
+    fn test_alert_rule_includes_snippets() {
+        let article = ArticleBuilder::new("複数製品の脆弱性について")
+            .with_site("JPCERT", "https://www.jpcert.or.jp")
+            .with_url("https://www.jpcert.or.jp/at/2025/0001.html")
+            .with_text("概要。Apache Tomcat にリモートコード実行の脆弱性があります。対策を実施してください。")
+            .build();
+        let rule = AlertRule {
+            name: "middleware".into(),
+            keywords: vec!["nginx".into(), "tomcat".into()],
+            severity: Severity::High,
+        };
+
+        let alerts = evaluate_rules(&[rule], &[article], &SnippetOptions::default());
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].keyword, "tomcat");
+        assert_eq!(
+            alerts[0].snippets,
+            vec!["Apache **Tomcat** にリモートコード実行の脆弱性があります。"]
+        );
+        let text = render_alert_text(&alerts[0]);
+        assert!(text.starts_with("[high] middleware (JPCERT)"));
+        assert!(text.contains("> Apache **Tomcat**"));
+    }
+
+    #[test]
+    fn test_alert_rules_config() {
+        let config = AlertRulesConfig::from_toml_str(
+            r#"
+[[rules]]
+name = "middleware"
+keywords = ["nginx", "tomcat"]
+severity = "critical"
+
+[[rules]]
+name = "openssl"
+keywords = ["OpenSSL"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules[0].severity, Severity::Critical);
+        assert_eq!(config.rules[1].severity, Severity::Medium);
+        assert!(AlertRulesConfig::from_toml_str("[[rules]]\nname = \"no keywords\"").is_err());
+        assert_eq!("high".parse::<Severity>().unwrap(), Severity::High);
+    }
+}
@@ -0,0 +1,282 @@
+//! セキュリティ関連の記事（アラート）の担当者とトリアージの状態．
+//!
+//! 担当者と状態は `PUT /articles/{id}/triage` や Slack のボタン（`POST /slack/actions`）で更新する．
+//! 担当者の決まらないまま一定時間が経ったアラートは，Slack のセキュリティのチャンネルに催促を送る．
+
+use crate::models::fairness::SiteCategory;
+use crate::models::web_article::WebArticle;
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::{ArticleQuery, ArticleStore, StoredArticle};
+use chrono::{DateTime, Duration, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::Path;
+use strum::{Display, EnumString};
+
+/// 担当者の決まらないアラートを催促するまでの時間（催促の間隔も兼ねる）
+pub const TRIAGE_REMINDER_HOURS_ENV: &str = "CLIPPER_TRIAGE_REMINDER_HOURS";
+const DEFAULT_TRIAGE_REMINDER_HOURS: i64 = 4;
+/// 催促の対象にする期間（これより古いアラートは催促しない）
+const REMINDER_LOOKBACK_DAYS: i64 = 14;
+/// 1回の催促に含めるアラートの上限（Slack のブロック数の上限 50 を超えないように）
+const MAX_REMINDER_ALERTS: usize = 15;
+
+/// Slack のボタンの `action_id`（`triage:<状態>` は状態の変更）
+pub const ASSIGN_TO_ME_ACTION: &str = "triage:assign_to_me";
+const TRIAGE_ACTION_PREFIX: &str = "triage:";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TriageState {
+    #[default]
+    Untriaged,
+    Acknowledged,
+    InProgress,
+    Resolved,
+    Dismissed,
+}
+
+impl TriageState {
+    /// 対応が終わっていないか
+    pub fn is_open(self) -> bool {
+        !matches!(self, TriageState::Resolved | TriageState::Dismissed)
+    }
+}
+
+/// 記事のトリアージの状態と担当者
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Triage {
+    #[serde(default)]
+    pub state: TriageState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+}
+
+/// トリアージの変更（未指定の項目は変えない）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TriageUpdate {
+    pub state: Option<TriageState>,
+    /// 担当者（空文字列で担当を外す）
+    pub assignee: Option<String>,
+}
+
+impl TriageUpdate {
+    pub fn apply(&self, triage: &Triage) -> Triage {
+        let mut triage = triage.clone();
+        if let Some(state) = self.state {
+            triage.state = state;
+        }
+        if let Some(assignee) = &self.assignee {
+            let assignee = assignee.trim();
+            triage.assignee = (!assignee.is_empty()).then(|| assignee.to_string());
+        }
+        // 担当者が決まれば未トリアージではなくなる
+        if triage.state == TriageState::Untriaged && triage.assignee.is_some() {
+            triage.state = TriageState::Acknowledged;
+        }
+        triage
+    }
+
+    /// Slack のボタンの `action_id` から変更を作る（`user` は押した利用者）
+    pub fn from_slack_action(action_id: &str, user: &str) -> Option<Self> {
+        if action_id == ASSIGN_TO_ME_ACTION {
+            return Some(Self {
+                state: None,
+                assignee: Some(user.to_string()),
+            });
+        }
+        let state = action_id.strip_prefix(TRIAGE_ACTION_PREFIX)?.parse().ok()?;
+        Some(Self {
+            state: Some(state),
+            assignee: None,
+        })
+    }
+}
+
+/// トリアージの対象（セキュリティ関連の記事）か
+pub fn is_alert(article: &WebArticle) -> bool {
+    article.properties.is_security_related == Some(true)
+        || SiteCategory::from_site_name(&article.site.name) == SiteCategory::Security
+}
+
+/// 保存から `min_age` 以上経っても担当者の決まらない，対応中のアラート（古い順）
+pub fn unassigned_alerts(articles: &[StoredArticle], now: DateTime<Local>, min_age: Duration) -> Vec<&StoredArticle> {
+    let mut alerts: Vec<&StoredArticle> = articles
+        .iter()
+        .filter(|stored| is_alert(&stored.article))
+        .filter(|stored| stored.triage.state.is_open() && stored.triage.assignee.is_none())
+        .filter(|stored| now - stored.created_at >= min_age)
+        .collect();
+    alerts.sort_by_key(|stored| stored.created_at);
+    alerts
+}
+
+/// 担当者を決めるボタン付きの Slack のメッセージ
+pub fn reminder_message(alerts: &[&StoredArticle]) -> Value {
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": { "type": "mrkdwn", "text": format!("*{} alerts have no assignee*", alerts.len()) },
+    })];
+    for stored in alerts.iter().take(MAX_REMINDER_ALERTS) {
+        let article = &stored.article;
+        let text = format!(
+            "*<{}|{}>*\n_{}_ ・ {}",
+            article.article_url,
+            escape(article.title.trim()),
+            escape(&article.site.name),
+            stored.triage.state
+        );
+        let button = |label: &str, action_id: String| {
+            json!({
+                "type": "button",
+                "text": { "type": "plain_text", "text": label },
+                "action_id": action_id,
+                "value": article.article_url,
+            })
+        };
+        blocks.push(json!({ "type": "section", "text": { "type": "mrkdwn", "text": text } }));
+        blocks.push(json!({
+            "type": "actions",
+            "elements": [
+                button("Assign to me", ASSIGN_TO_ME_ACTION.to_string()),
+                button("Resolve", format!("{}{}", TRIAGE_ACTION_PREFIX, TriageState::Resolved)),
+                button("Dismiss", format!("{}{}", TRIAGE_ACTION_PREFIX, TriageState::Dismissed)),
+            ],
+        }));
+    }
+    json!({
+        "text": format!("{} alerts have no assignee", alerts.len()),
+        "blocks": blocks,
+    })
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// 前回の催促から `CLIPPER_TRIAGE_REMINDER_HOURS`（デフォルト 4）時間経っていれば，
+/// 担当者の決まらないアラートを `webhook` に催促する．催促したアラートの数を返す．
+pub async fn send_unassigned_reminder_if_due(
+    store: &dyn ArticleStore,
+    webhook: &str,
+    marker: &Path,
+    now: DateTime<Local>,
+) -> AppResult<usize> {
+    let interval = Duration::hours(parse_env_or(TRIAGE_REMINDER_HOURS_ENV, DEFAULT_TRIAGE_REMINDER_HOURS)?);
+    let last_sent = std::fs::read_to_string(marker)
+        .ok()
+        .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())
+        .map(|t| t.with_timezone(&Local));
+    if last_sent.is_some_and(|last_sent| now - last_sent < interval) {
+        return Ok(0);
+    }
+
+    let articles = store
+        .query(&ArticleQuery {
+            since: Some(now - Duration::days(REMINDER_LOOKBACK_DAYS)),
+            ..Default::default()
+        })
+        .await?;
+    let alerts = unassigned_alerts(&articles, now, interval);
+    if !alerts.is_empty() {
+        let response = request::Client::new()
+            .post(webhook)
+            .json(&reminder_message(&alerts))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(AppError::InternalError(format!("Slack webhook returned {}", response.status())));
+        }
+    }
+    write_marker(marker, now)?;
+    Ok(alerts.len())
+}
+
+fn write_marker(marker: &Path, now: DateTime<Local>) -> AppResult<()> {
+    if let Some(parent) = marker.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", parent, e)))?;
+    }
+    std::fs::write(marker, now.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", marker, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    fn stored(site: &str, hours_ago: i64, triage: Triage) -> StoredArticle {
+        let now = Local::now();
+        let mut stored = ArticleBuilder::new(format!("{} advisory", site))
+            .with_site(site, "https://example.com")
+            .with_url(format!("https://example.com/{}/{}", site, hours_ago))
+            .with_timestamp(now)
+            .stored();
+        stored.triage = triage;
+        stored.created_at = now - Duration::hours(hours_ago);
+        stored
+    }
+
+    #[test]
+    fn test_triage_update() {
+        let assigned = TriageUpdate {
+            state: None,
+            assignee: Some(" alice ".into()),
+        }
+        .apply(&Triage::default());
+        assert_eq!(assigned.assignee.as_deref(), Some("alice"));
+        assert_eq!(assigned.state, TriageState::Acknowledged);
+
+        let unassigned = TriageUpdate {
+            state: Some(TriageState::Resolved),
+            assignee: Some(String::new()),
+        }
+        .apply(&assigned);
+        assert_eq!(unassigned, Triage { state: TriageState::Resolved, assignee: None });
+    }
+
+    #[test]
+    fn test_from_slack_action() {
+        let update = TriageUpdate::from_slack_action(ASSIGN_TO_ME_ACTION, "alice").unwrap();
+        assert_eq!(update.assignee.as_deref(), Some("alice"));
+        let update = TriageUpdate::from_slack_action("triage:in_progress", "alice").unwrap();
+        assert_eq!(update.state, Some(TriageState::InProgress));
+        assert!(TriageUpdate::from_slack_action("triage:unknown", "alice").is_none());
+        assert!(TriageUpdate::from_slack_action("other", "alice").is_none());
+    }
+
+    #[test]
+    fn test_unassigned_alerts_and_reminder() {
+        let assigned = Triage {
+            state: TriageState::InProgress,
+            assignee: Some("alice".into()),
+        };
+        let resolved = Triage {
+            state: TriageState::Resolved,
+            assignee: None,
+        };
+        let articles = vec![
+            stored("JPCERT", 5, Triage::default()),
+            stored("JPCERT", 10, Triage::default()),
+            stored("JPCERT", 1, Triage::default()),
+            stored("JPCERT", 8, assigned),
+            stored("JPCERT", 8, resolved),
+            stored("Gigazine", 8, Triage::default()),
+        ];
+        let alerts = unassigned_alerts(&articles, Local::now(), Duration::hours(4));
+        assert_eq!(
+            alerts.iter().map(|s| s.article.article_url.as_str()).collect::<Vec<_>>(),
+            vec!["https://example.com/JPCERT/10", "https://example.com/JPCERT/5"]
+        );
+
+        let message = reminder_message(&alerts);
+        assert_eq!(message["text"], "2 alerts have no assignee");
+        let buttons = message["blocks"][2]["elements"].as_array().unwrap();
+        assert_eq!(buttons[0]["action_id"], ASSIGN_TO_ME_ACTION);
+        assert_eq!(buttons[1]["action_id"], "triage:resolved");
+        assert_eq!(buttons[2]["value"], "https://example.com/JPCERT/10");
+    }
+}
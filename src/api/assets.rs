@@ -0,0 +1,69 @@
+//! キャッシュした記事の画像（[`crate::assets`]）の配信．
+
+use crate::assets::content_type_for;
+use crate::shared::errors::{AppError, AppResult};
+use axum::Router;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// `GET /assets/{name}` を提供するルーター
+pub fn assets_router(dir: PathBuf) -> Router {
+    Router::new()
+        .route("/assets/{name}", get(get_asset))
+        .with_state(Arc::new(dir))
+}
+
+/// `<キー>.<拡張子>` 形式（キーは16進数）の名前のみ受け付ける（パスの走査を防ぐ）
+fn content_type_of(name: &str) -> Option<&'static str> {
+    let (key, extension) = name.split_once('.')?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    content_type_for(extension)
+}
+
+async fn get_asset(State(dir): State<Arc<PathBuf>>, Path(name): Path<String>) -> AppResult<Response> {
+    let not_found = || AppError::NotFound(format!("asset {}", name));
+    let content_type = content_type_of(&name).ok_or_else(not_found)?;
+    let bytes = std::fs::read(dir.join(&name)).map_err(|_| not_found())?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_of_rejects_unsafe_names() {
+        assert_eq!(content_type_of("0a1b.png"), Some("image/png"));
+        assert_eq!(content_type_of("0a1b.svg"), None);
+        assert_eq!(content_type_of("../secret.png"), None);
+        assert_eq!(content_type_of("0a1b.png.exe"), None);
+        assert_eq!(content_type_of(".png"), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_asset() {
+        let dir = std::env::temp_dir().join(format!("clipper-api-assets-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("0a1b.jpg"), b"jpeg").unwrap();
+        let state = State(Arc::new(dir.clone()));
+        let response = get_asset(state.clone(), Path("0a1b.jpg".into())).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/jpeg");
+        let missing = get_asset(state, Path("ffff.jpg".into())).await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,802 @@
+//! 保存済みの記事とサイト一覧を JSON で返す HTTP API．
+//!
+//! | メソッド | パス | 内容 |
+//! |---|---|---|
+//! | `GET` | `/sites` | 有効なサイト一覧 |
+//! | `GET` | `/articles` | 記事一覧（`site`，`since`，`until`，`status`，`lang`，`triage`，`assignee`，`limit` で絞り込み） |
+//! | `DELETE` | `/articles` | ドメイン・条件に一致する記事を論理削除する（`{"domain": ..., "filter": {...}}`） |
+//! | `POST` | `/articles/purge` | 論理削除した記事とそのキャッシュを物理削除する |
+//! | `GET` | `/articles/{id}` | 記事1件 |
+//! | `GET` | `/articles/{id}/frame` | 記事の HTML（サニタイズ済み，`sandbox` 付きの CSP） |
+//! | `POST` | `/refresh` | 次の巡回を待たずに記事を取得する |
+//! | `POST` | `/clip` | 任意の URL の記事を取得して保存する（`{"url": ...}`） |
+//! | `POST` | `/feedback` | 記事の分類を修正し，few-shot の例として保存する（`{"url": ..., "classification": {...}}`） |
+//! | `POST` | `/interactions` | 記事への行動を記録して関心の重みを更新する（`{"url": ..., "interaction": "starred"}`） |
+//! | `GET` | `/interests` | 学習した関心の重み |
+//! | `PUT` | `/interests/{key}` | 関心の重みを固定する（`{"weight": 2.0}`） |
+//! | `DELETE` | `/interests/{key}/pin` | 関心の重みの固定を解除する |
+//! | `PUT` | `/articles/{id}/triage` | 担当者とトリアージの状態を更新する（`{"state": "in_progress", "assignee": "alice"}`） |
+//! | `GET` | `/articles/{id}/annotations` | 記事の注釈（共有と本人の個人用） |
+//! | `POST` | `/articles/{id}/annotations` | 注釈を追加する（`{"start": 0, "end": 4, "note": ..., "visibility": "shared"}`） |
+//! | `PATCH` | `/annotations/{id}` | 注釈のメモを更新する（`{"note": ...}`） |
+//! | `DELETE` | `/annotations/{id}` | 注釈を削除する |
+//! | `PUT` | `/articles/{id}/star` | スターを付ける（`{"visibility": "personal"}`） |
+//! | `DELETE` | `/articles/{id}/star` | スターを外す（`visibility` で公開範囲を指定） |
+//! | `GET` | `/stars` | スターを付けた記事（`visibility` で絞り込み） |
+//! | `GET` | `/deadlines` | 保存済みの記事から検出した今日以降の論文投稿などの締切（日付順） |
+//!
+//! 注釈とスターの利用者はリバースプロキシなどが付ける `X-Clipper-User` ヘッダーで識別する．
+//! 共有（`shared`）の注釈・スターはチーム全員に見え，個人用（`personal`）のものは本人にだけ見える．
+
+pub mod assets;
+pub mod sandbox;
+pub mod share;
+pub mod slack;
+
+use crate::alerts::triage::{TriageState, TriageUpdate};
+use crate::archive::warc::global_warc_writer;
+use crate::assets::{AssetStore, LocalAssetStore, cache_article_images};
+use crate::enrichment::classifier::Classification;
+use crate::enrichment::deadlines::{Deadline, detect_deadlines, query_upcoming_deadlines};
+use crate::enrichment::few_shot::{ExampleStore, LabeledExample};
+use crate::enrichment::interests::{Interaction, InterestModel, LearnedInterest};
+use crate::enrichment::language::{detect_article_language, primary_language};
+use crate::enrichment::license::detect_license;
+use crate::enrichment::relevance::{RELEVANCE_CACHE_FILE, RelevanceCache};
+use crate::enrichment::robots::RobotsDirectives;
+use crate::models::get_all_sites;
+use crate::models::annotation::{Annotation, Annotations, Star, Visibility};
+use crate::models::extraction::{ExtractedArticle, extract_article};
+use crate::models::web_article::{Status, WebArticle, clean_html};
+use crate::shared::config::data_dir;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::fetch_cache::global_fetch_cache;
+use crate::shared::id::{AnnotationId, WebArticleId};
+use sandbox::{ARTICLE_FRAME_CSP, html_with_csp, render_article_frame};
+use crate::shared::ssrf::{MAX_UNTRUSTED_BODY_BYTES, fetch_untrusted_text};
+use crate::shared::text::global_text_normalizer;
+use crate::storage::removal::{RemovalRequest, purge_deleted, soft_delete_matching};
+use crate::storage::{AnnotationStore, ArticleQuery, ArticleStore, StoredArticle};
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use axum::routing::{delete, get, patch, post, put};
+use axum::{Json, Router};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// `GET /articles` で一度に返す件数の上限
+const MAX_ARTICLES_LIMIT: usize = 1000;
+const DEFAULT_ARTICLES_LIMIT: usize = 100;
+
+/// 注釈・スターの利用者を識別するヘッダー
+pub const USER_HEADER: &str = "x-clipper-user";
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub store: Arc<dyn ArticleStore>,
+    /// 巡回ジョブを起動するハンドル（`Scheduler::trigger`）
+    pub refresh: Arc<Notify>,
+    /// `POST /feedback` で修正された分類の例
+    pub examples: Arc<ExampleStore>,
+    /// `POST /interactions` で学習する関心の重み
+    pub interests: Arc<InterestModel>,
+    /// 記事の注釈とスター
+    pub annotations: Arc<dyn AnnotationStore>,
+}
+
+pub fn api_router(state: ApiState) -> Router {
+    Router::new()
+        .route("/sites", get(list_sites))
+        .route("/articles", get(list_articles).delete(remove_articles))
+        .route("/articles/purge", post(purge_articles))
+        .route("/articles/{id}", get(get_article))
+        .route("/articles/{id}/frame", get(get_article_frame))
+        .route("/articles/{id}/triage", put(update_triage))
+        .route("/refresh", post(refresh))
+        .route("/clip", post(clip))
+        .route("/feedback", post(feedback))
+        .route("/interactions", post(record_interaction))
+        .route("/interests", get(list_interests))
+        .route("/interests/{key}", put(pin_interest))
+        .route("/interests/{key}/pin", delete(unpin_interest))
+        .route("/articles/{id}/annotations", get(list_annotations).post(add_annotation))
+        .route("/annotations/{id}", patch(update_annotation).delete(remove_annotation))
+        .route("/articles/{id}/star", put(star_article).delete(unstar_article))
+        .route("/stars", get(list_stars))
+        .route("/deadlines", get(list_deadlines))
+        .with_state(state)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SiteInfo {
+    pub name: String,
+    pub url: String,
+    pub domain: String,
+}
+
+async fn list_sites() -> AppResult<Json<Vec<SiteInfo>>> {
+    let sites = get_all_sites().await?;
+    Ok(Json(
+        sites
+            .iter()
+            .map(|site| SiteInfo {
+                name: site.site_name(),
+                url: site.site_url().to_string(),
+                domain: site.domain(),
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ArticlesParams {
+    pub site: Option<String>,
+    pub since: Option<DateTime<Local>>,
+    pub until: Option<DateTime<Local>>,
+    pub status: Option<Status>,
+    pub lang: Option<String>,
+    pub triage: Option<TriageState>,
+    pub assignee: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl From<ArticlesParams> for ArticleQuery {
+    fn from(params: ArticlesParams) -> Self {
+        Self {
+            site: params.site,
+            since: params.since,
+            until: params.until,
+            status: params.status,
+            lang: params.lang.as_deref().and_then(primary_language),
+            triage: params.triage,
+            assignee: params.assignee,
+            limit: Some(params.limit.unwrap_or(DEFAULT_ARTICLES_LIMIT).min(MAX_ARTICLES_LIMIT)),
+        }
+    }
+}
+
+async fn list_articles(
+    State(state): State<ApiState>,
+    Query(params): Query<ArticlesParams>,
+) -> AppResult<Json<Vec<StoredArticle>>> {
+    Ok(Json(state.store.query(&params.into()).await?))
+}
+
+async fn get_article(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> AppResult<Json<StoredArticle>> {
+    let id = WebArticleId::from_str(&id)?;
+    state
+        .store
+        .get(id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("article {}", id.to_string())))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemovalResult {
+    pub count: usize,
+}
+
+async fn remove_articles(
+    State(state): State<ApiState>,
+    Json(request): Json<RemovalRequest>,
+) -> AppResult<Json<RemovalResult>> {
+    let count = soft_delete_matching(state.store.as_ref(), &request).await?;
+    Ok(Json(RemovalResult { count }))
+}
+
+async fn purge_articles(State(state): State<ApiState>) -> AppResult<Json<RemovalResult>> {
+    let assets = LocalAssetStore::from_env()?;
+    let assets = assets.as_ref().map(|store| store as &dyn AssetStore);
+    let relevance = RelevanceCache::open(data_dir().join(RELEVANCE_CACHE_FILE))?;
+    let count = purge_deleted(
+        state.store.as_ref(),
+        global_fetch_cache(),
+        global_warc_writer(),
+        assets,
+        Some(state.examples.as_ref()),
+        Some(&relevance),
+    )
+    .await?;
+    Ok(Json(RemovalResult { count }))
+}
+
+/// 記事の HTML を `<iframe sandbox>` で読み込むための単体のドキュメントとして返す
+async fn get_article_frame(State(state): State<ApiState>, Path(id): Path<String>) -> AppResult<Response> {
+    let Json(stored) = get_article(State(state), Path(id)).await?;
+    Ok(html_with_csp(ARTICLE_FRAME_CSP, render_article_frame(&stored.article)))
+}
+
+/// 記事の担当者とトリアージの状態を更新する（未指定の項目は変えない）
+async fn update_triage(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(update): Json<TriageUpdate>,
+) -> AppResult<Json<StoredArticle>> {
+    let mut stored = stored_article(&state, &id).await?;
+    stored.triage = update.apply(&stored.triage);
+    state.store.set_triage(&stored.article.article_url, &stored.triage).await?;
+    Ok(Json(stored))
+}
+
+/// 巡回を予約して `202 Accepted` を返す（完了は待たない）
+async fn refresh(State(state): State<ApiState>) -> StatusCode {
+    state.refresh.notify_one();
+    StatusCode::ACCEPTED
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClipRequest {
+    pub url: String,
+}
+
+/// 利用者が指定した URL を SSRF 対策を施したクライアントで取得し，本文を抽出して保存する
+async fn clip(
+    State(state): State<ApiState>,
+    Json(request): Json<ClipRequest>,
+) -> AppResult<(StatusCode, Json<StoredArticle>)> {
+    // リダイレクト後の URL を記事の URL とする
+    let (url, raw_html) = fetch_untrusted_text(&request.url, MAX_UNTRUSTED_BODY_BYTES).await?;
+    let mut article = clipped_article(&url, &raw_html);
+    if let Some(assets) = LocalAssetStore::from_env()? {
+        cache_article_images(&assets, &mut article).await;
+    }
+    let id = state.store.upsert(&article).await?;
+    let stored = state
+        .store
+        .get(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("article {}", id.to_string())))?;
+    Ok((StatusCode::CREATED, Json(stored)))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedbackRequest {
+    pub url: String,
+    pub classification: Classification,
+}
+
+/// 保存済みの記事の分類を利用者の回答で上書きし，以降の LLM の分類で使う例として保存する
+async fn feedback(
+    State(state): State<ApiState>,
+    Json(request): Json<FeedbackRequest>,
+) -> AppResult<(StatusCode, Json<LabeledExample>)> {
+    let mut stored = state
+        .store
+        .get_by_url(&request.url)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("article {}", request.url)))?;
+    // 利用者の回答を優先し，取得元のサイトが付けたタグも外せるようにする
+    stored.article.properties.source_tags.clear();
+    request.classification.apply(&mut stored.article.properties);
+    state.store.upsert(&stored.article).await?;
+    let example = LabeledExample::new(&stored.article, request.classification);
+    state.examples.add(example.clone())?;
+    Ok((StatusCode::CREATED, Json(example)))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InteractionRequest {
+    pub url: String,
+    pub interaction: Interaction,
+}
+
+/// 保存済みの記事への行動（開いた，スターを付けた，非表示にした）を記録し，変化した関心を返す
+async fn record_interaction(
+    State(state): State<ApiState>,
+    Json(request): Json<InteractionRequest>,
+) -> AppResult<Json<Vec<LearnedInterest>>> {
+    let stored = state
+        .store
+        .get_by_url(&request.url)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("article {}", request.url)))?;
+    Ok(Json(state.interests.record(&stored.article, request.interaction, Local::now())?))
+}
+
+async fn list_interests(State(state): State<ApiState>) -> Json<Vec<LearnedInterest>> {
+    Json(state.interests.interests(Local::now()))
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PinRequest {
+    pub weight: f64,
+}
+
+async fn pin_interest(
+    State(state): State<ApiState>,
+    Path(key): Path<String>,
+    Json(request): Json<PinRequest>,
+) -> AppResult<Json<LearnedInterest>> {
+    Ok(Json(state.interests.pin(&key, request.weight, Local::now())?))
+}
+
+async fn unpin_interest(
+    State(state): State<ApiState>,
+    Path(key): Path<String>,
+) -> AppResult<Json<LearnedInterest>> {
+    state
+        .interests
+        .unpin(&key, Local::now())?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("interest {}", key)))
+}
+
+/// `X-Clipper-User` ヘッダーの利用者（無ければ匿名）
+pub(crate) fn request_user(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(USER_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 個人用の注釈・スターは利用者が分からなければ扱えない
+fn require_user(visibility: Visibility, user: &Option<String>) -> AppResult<()> {
+    if visibility == Visibility::Personal && user.is_none() {
+        return Err(AppError::UserRequired(format!("{} header is required for personal items", USER_HEADER)));
+    }
+    Ok(())
+}
+
+async fn stored_article(state: &ApiState, id: &str) -> AppResult<StoredArticle> {
+    let id = WebArticleId::from_str(id)?;
+    state
+        .store
+        .get(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("article {}", id.to_string())))
+}
+
+async fn list_annotations(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> AppResult<Json<Annotations>> {
+    let stored = stored_article(&state, &id).await?;
+    let user = request_user(&headers);
+    Ok(Json(state.annotations.annotations(&stored.article.article_url, user.as_deref()).await?))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnnotationRequest {
+    pub start: usize,
+    pub end: usize,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub visibility: Visibility,
+}
+
+/// 記事本文の `[start, end)`（文字単位）をハイライトする注釈を追加する
+async fn add_annotation(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<AnnotationRequest>,
+) -> AppResult<(StatusCode, Json<Annotation>)> {
+    let user = request_user(&headers);
+    require_user(request.visibility, &user)?;
+    let stored = stored_article(&state, &id).await?;
+    let article = &stored.article;
+    let annotation = Annotation::new(&article.article_url, &article.text, request.start, request.end, request.note)?
+        .with_visibility(request.visibility, user);
+    state.annotations.save_annotation(&annotation).await?;
+    Ok((StatusCode::CREATED, Json(annotation)))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoteRequest {
+    pub note: Option<String>,
+}
+
+async fn update_annotation(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<NoteRequest>,
+) -> AppResult<Json<Annotation>> {
+    let id = AnnotationId::from_str(&id)?;
+    let user = request_user(&headers);
+    let mut annotation = state
+        .annotations
+        .get_annotation(id, user.as_deref())
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("annotation {}", id.to_string())))?;
+    annotation.set_note(request.note);
+    state.annotations.save_annotation(&annotation).await?;
+    Ok(Json(annotation))
+}
+
+async fn remove_annotation(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> AppResult<StatusCode> {
+    let id = AnnotationId::from_str(&id)?;
+    let user = request_user(&headers);
+    if !state.annotations.remove_annotation(id, user.as_deref()).await? {
+        return Err(AppError::NotFound(format!("annotation {}", id.to_string())));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct StarRequest {
+    #[serde(default)]
+    pub visibility: Visibility,
+}
+
+/// 記事にスターを付ける．共有のスターはチームで1つだけで，最初に付けた利用者を記録する．
+async fn star_article(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<StarRequest>,
+) -> AppResult<Json<Star>> {
+    let user = request_user(&headers);
+    require_user(request.visibility, &user)?;
+    let stored = stored_article(&state, &id).await?;
+    let star = Star::new(&stored.article.article_url, request.visibility, user);
+    state.annotations.star(&star).await?;
+    Ok(Json(star))
+}
+
+async fn unstar_article(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Query(request): Query<StarRequest>,
+) -> AppResult<StatusCode> {
+    let user = request_user(&headers);
+    require_user(request.visibility, &user)?;
+    let stored = stored_article(&state, &id).await?;
+    let url = &stored.article.article_url;
+    if !state.annotations.unstar(url, request.visibility, user.as_deref()).await? {
+        return Err(AppError::NotFound(format!("star {}", url)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct StarsParams {
+    pub visibility: Option<Visibility>,
+}
+
+async fn list_stars(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(params): Query<StarsParams>,
+) -> AppResult<Json<Vec<Star>>> {
+    let user = request_user(&headers);
+    Ok(Json(state.annotations.stars(params.visibility, user.as_deref()).await?))
+}
+
+/// 今日以降の締切を日付順に返す
+async fn list_deadlines(State(state): State<ApiState>) -> AppResult<Json<Vec<Deadline>>> {
+    let today = Local::now().date_naive();
+    Ok(Json(query_upcoming_deadlines(state.store.as_ref(), today).await?))
+}
+
+fn clipped_article(url: &request::Url, raw_html: &str) -> WebArticle {
+    let extracted = extract_article(raw_html).unwrap_or_else(|| {
+        let normalizer = global_text_normalizer();
+        let html = clean_html(raw_html);
+        let text = normalizer.normalize(&html2md::rewrite_html(&html, false));
+        ExtractedArticle::from_page(raw_html, normalizer.normalize(&html), text)
+    });
+    let host = url.host_str().unwrap_or_default().to_string();
+    let mut article = WebArticle::new(
+        host,
+        url.origin().ascii_serialization(),
+        extracted.title.unwrap_or_else(|| url.to_string()),
+        url.to_string(),
+        String::new(),
+        extracted.published.unwrap_or_else(Local::now),
+    );
+    article.text = extracted.text;
+    article.html = extracted.html;
+    article.properties.license = detect_license(raw_html);
+    article.properties.deadlines = detect_deadlines(&article);
+    RobotsDirectives::from_html(raw_html).apply(&mut article);
+    // ページが宣言している言語はサイト共通の既定値のことがあるため，本文から判定できればそちらを使う
+    article.lang = detect_article_language(&article)
+        .or_else(|| extracted.lang.as_deref().and_then(primary_language));
+    article
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::sqlite::SqliteStore;
+    use crate::testing::article::ArticleBuilder;
+
+    async fn state() -> ApiState {
+        let store = SqliteStore::open_in_memory().unwrap();
+        for i in 0..3 {
+            store
+                .upsert(
+                    &ArticleBuilder::new(format!("title {}", i))
+                        .with_url(format!("https://example.com/{}", i))
+                        .build(),
+                )
+                .await
+                .unwrap();
+        }
+        let dir = std::env::temp_dir().join(format!("clipper-api-{}", uuid::Uuid::new_v4()));
+        let examples = dir.join(crate::enrichment::few_shot::FEW_SHOT_EXAMPLES_FILE);
+        let interests = dir.join(crate::enrichment::interests::INTEREST_WEIGHTS_FILE);
+        let store = Arc::new(store);
+        ApiState {
+            store: store.clone(),
+            refresh: Arc::new(Notify::new()),
+            examples: Arc::new(ExampleStore::open(examples, None).unwrap()),
+            interests: Arc::new(InterestModel::open(interests).unwrap()),
+            annotations: store,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_and_get_articles() {
+        let state = state().await;
+        let params = ArticlesParams {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let Json(articles) = list_articles(State(state.clone()), Query(params)).await.unwrap();
+        assert_eq!(articles.len(), 2);
+
+        let id = articles[0].id;
+        let Json(article) = get_article(State(state.clone()), Path(id.to_string())).await.unwrap();
+        assert_eq!(article.article.article_url, articles[0].article.article_url);
+
+        let missing = get_article(State(state.clone()), Path(WebArticleId::new().to_string())).await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+        let invalid = get_article(State(state.clone()), Path("not-a-uuid".into())).await;
+        assert!(matches!(invalid, Err(AppError::ConvertToUuidError(_))));
+
+        let frame = get_article_frame(State(state), Path(id.to_string())).await.unwrap();
+        assert!(frame.headers().contains_key(axum::http::header::CONTENT_SECURITY_POLICY));
+    }
+
+    #[test]
+    fn test_articles_params_limit_is_capped() {
+        let query: ArticleQuery = ArticlesParams {
+            limit: Some(100_000),
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(query.limit, Some(MAX_ARTICLES_LIMIT));
+        assert_eq!(ArticleQuery::from(ArticlesParams::default()).limit, Some(DEFAULT_ARTICLES_LIMIT));
+    }
+
+    #[tokio::test]
+    async fn test_update_triage() {
+        let state = state().await;
+        let stored = state.store.get_by_url("https://example.com/2").await.unwrap().unwrap();
+        let update = TriageUpdate {
+            state: None,
+            assignee: Some("alice".into()),
+        };
+        let Json(updated) = update_triage(State(state.clone()), Path(stored.id.to_string()), Json(update))
+            .await
+            .unwrap();
+        assert_eq!(updated.triage.state, TriageState::Acknowledged);
+
+        let params = ArticlesParams {
+            assignee: Some("alice".into()),
+            ..Default::default()
+        };
+        let Json(assigned) = list_articles(State(state.clone()), Query(params)).await.unwrap();
+        assert_eq!(assigned.len(), 1);
+        assert_eq!(assigned[0].triage.assignee.as_deref(), Some("alice"));
+
+        let missing_id = Path(WebArticleId::new().to_string());
+        let missing = update_triage(State(state), missing_id, Json(TriageUpdate::default())).await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_triggers_scheduler() {
+        let state = state().await;
+        assert_eq!(refresh(State(state.clone())).await, StatusCode::ACCEPTED);
+        // 予約された通知はすぐに受け取れる
+        tokio::time::timeout(std::time::Duration::from_secs(1), state.refresh.notified())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_deadlines() {
+        let state = state().await;
+        let mut article = ArticleBuilder::new("Call for Papers")
+            .with_site("Conference", "https://conf.example.com")
+            .with_url("https://conf.example.com/cfp")
+            .build();
+        let date = Local::now().date_naive() + chrono::Duration::days(30);
+        article.properties.deadlines = vec![Deadline {
+            article_url: article.article_url.clone(),
+            title: article.title.clone(),
+            site: article.site.name.clone(),
+            kind: crate::enrichment::deadlines::DeadlineKind::Submission,
+            date,
+            venue: None,
+        }];
+        state.store.upsert(&article).await.unwrap();
+
+        let Json(deadlines) = list_deadlines(State(state)).await.unwrap();
+        assert_eq!(deadlines.len(), 1);
+        assert_eq!(deadlines[0].date, date);
+        assert_eq!(deadlines[0].article_url, "https://conf.example.com/cfp");
+    }
+
+    #[tokio::test]
+    async fn test_clip_rejects_internal_urls() {
+        for url in ["http://127.0.0.1:8080/admin", "file:///etc/passwd", "http://169.254.169.254/"] {
+            let result = clip(State(state().await), Json(ClipRequest { url: url.into() })).await;
+            assert!(matches!(result, Err(AppError::BlockedUrl(_))), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_feedback_updates_article_and_stores_example() {
+        let state = state().await;
+        let classification = Classification {
+            is_security_related: true,
+            is_it_related: true,
+            ..Default::default()
+        };
+        let request = FeedbackRequest {
+            url: "https://example.com/1".into(),
+            classification,
+        };
+        let (status, Json(example)) = feedback(State(state.clone()), Json(request)).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(example.classification, classification);
+        assert_eq!(state.examples.len(), 1);
+        let stored = state.store.get_by_url("https://example.com/1").await.unwrap().unwrap();
+        assert_eq!(stored.article.properties.is_security_related, Some(true));
+
+        let missing = FeedbackRequest {
+            url: "https://example.com/missing".into(),
+            classification,
+        };
+        let result = feedback(State(state), Json(missing)).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_interactions_update_interests() {
+        let state = state().await;
+        let request = InteractionRequest {
+            url: "https://example.com/1".into(),
+            interaction: Interaction::Starred,
+        };
+        let Json(changed) = record_interaction(State(state.clone()), Json(request)).await.unwrap();
+        assert!(changed.iter().any(|i| i.key == "site:Example" && i.weight == 3.0));
+
+        let Json(pinned) = pin_interest(
+            State(state.clone()),
+            Path("site:Example".into()),
+            Json(PinRequest { weight: -1.0 }),
+        )
+        .await
+        .unwrap();
+        assert!(pinned.pinned);
+        let Json(interests) = list_interests(State(state.clone())).await;
+        assert_eq!(interests.last().map(|i| (i.key.as_str(), i.weight)), Some(("site:Example", -1.0)));
+
+        let Json(unpinned) = unpin_interest(State(state.clone()), Path("site:Example".into())).await.unwrap();
+        assert!(!unpinned.pinned);
+        let missing = unpin_interest(State(state), Path("site:Missing".into())).await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+    }
+
+    fn user_headers(user: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_HEADER, user.parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_shared_and_personal_annotations() {
+        let state = state().await;
+        let mut stored = state.store.get_by_url("https://example.com/1").await.unwrap().unwrap();
+        stored.article.text = "title 1 の本文".into();
+        state.store.upsert(&stored.article).await.unwrap();
+        let id = stored.id.to_string();
+        let request = |visibility| AnnotationRequest {
+            start: 0,
+            end: 5,
+            note: None,
+            visibility,
+        };
+
+        let anonymous = add_annotation(
+            State(state.clone()),
+            Path(id.clone()),
+            HeaderMap::new(),
+            Json(request(Visibility::Personal)),
+        )
+        .await;
+        assert!(matches!(anonymous, Err(AppError::UserRequired(_))));
+        let (_, Json(shared)) = add_annotation(
+            State(state.clone()),
+            Path(id.clone()),
+            user_headers("alice"),
+            Json(request(Visibility::Shared)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(shared.quote, "title");
+        let (_, Json(personal)) = add_annotation(
+            State(state.clone()),
+            Path(id.clone()),
+            user_headers("alice"),
+            Json(request(Visibility::Personal)),
+        )
+        .await
+        .unwrap();
+
+        let Json(seen) = list_annotations(State(state.clone()), Path(id.clone()), user_headers("bob")).await.unwrap();
+        assert_eq!(seen.iter().map(|a| a.id).collect::<Vec<_>>(), vec![shared.id]);
+        let Json(updated) = update_annotation(
+            State(state.clone()),
+            Path(shared.id.to_string()),
+            user_headers("bob"),
+            Json(NoteRequest {
+                note: Some("triaged".into()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.note.as_deref(), Some("triaged"));
+        let hidden = remove_annotation(State(state.clone()), Path(personal.id.to_string()), user_headers("bob")).await;
+        assert!(matches!(hidden, Err(AppError::NotFound(_))));
+
+        let star = |user, visibility| {
+            star_article(State(state.clone()), Path(id.clone()), user_headers(user), Json(StarRequest { visibility }))
+        };
+        star("alice", Visibility::Shared).await.unwrap();
+        star("bob", Visibility::Personal).await.unwrap();
+        let Json(stars) = list_stars(State(state.clone()), user_headers("alice"), Query(StarsParams::default()))
+            .await
+            .unwrap();
+        assert_eq!(stars.len(), 1);
+        assert_eq!(stars[0].visibility, Visibility::Shared);
+        let status = unstar_article(
+            State(state.clone()),
+            Path(id.clone()),
+            user_headers("bob"),
+            Query(StarRequest {
+                visibility: Visibility::Personal,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let params = StarsParams {
+            visibility: Some(Visibility::Personal),
+        };
+        let Json(stars) = list_stars(State(state), user_headers("bob"), Query(params)).await.unwrap();
+        assert!(stars.is_empty());
+    }
+
+    #[test]
+    fn test_clipped_article() {
+        let url = request::Url::parse("https://example.com/posts/1").unwrap();
+        let html = "<html><head><title> Hello </title></head><body><article><p>本文です</p></article></body></html>";
+        let article = clipped_article(&url, html);
+        assert_eq!(article.title, "Hello");
+        assert_eq!(article.site.name, "example.com");
+        assert_eq!(article.article_url, "https://example.com/posts/1");
+        assert!(article.text.contains("本文です"));
+        assert_eq!(article.lang.as_deref(), Some("ja"));
+    }
+}
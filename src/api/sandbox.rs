@@ -0,0 +1,187 @@
+//! スクレイピングした記事の HTML を閲覧用に提供する際の多層防御．
+//!
+//! 記事の HTML はスクリプト・埋め込み要素・イベントハンドラ属性・危険なスキームの URL を取り除いた上で，
+//! `sandbox` 付きの厳格な CSP を持つ単体のドキュメントとして返し，閲覧ページからは `<iframe sandbox>` で読み込む．
+
+use crate::models::web_article::{WebArticle, escape_html_attr, escape_html_text};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+
+/// 記事本文のドキュメント（iframe の中身）に付ける CSP．スクリプトは一切実行させない．
+pub const ARTICLE_FRAME_CSP: &str = "default-src 'none'; img-src 'self' https: data:; media-src https:; \
+     style-src 'unsafe-inline'; base-uri 'none'; form-action 'none'; frame-ancestors 'self'; sandbox";
+
+/// 記事本文を iframe で読み込む閲覧ページに付ける CSP
+pub const READER_VIEW_CSP: &str = "default-src 'none'; style-src 'unsafe-inline'; frame-src 'self'; \
+     base-uri 'none'; form-action 'none'; frame-ancestors 'none'";
+
+/// 要素ごと（子孫を含めて）取り除く要素
+const DROPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "frame", "frameset", "object", "embed",
+    "applet", "form", "input", "button", "textarea", "select", "base", "meta", "link", "svg", "math",
+];
+
+/// URL を値に取る属性
+const URL_ATTRIBUTES: &[&str] = &[
+    "href", "src", "action", "formaction", "poster", "cite", "background", "xlink:href",
+];
+
+/// 取り除く属性（イベントハンドラ `on*` とは別に）
+const DROPPED_ATTRIBUTES: &[&str] = &["style", "srcdoc", "srcset"];
+
+/// 終了タグを持たない void 要素
+const VOID_ELEMENTS: &[&str] = &["area", "br", "col", "hr", "img", "source", "track", "wbr"];
+
+/// 記事の HTML から実行可能な内容を取り除く
+pub fn sanitize_article_html(html: &str) -> String {
+    let fragment = scraper::Html::parse_fragment(html);
+    let mut out = String::with_capacity(html.len());
+    for child in fragment.root_element().children() {
+        write_sanitized(child, &mut out);
+    }
+    out
+}
+
+fn write_sanitized(node: ego_tree::NodeRef<'_, scraper::Node>, out: &mut String) {
+    match node.value() {
+        scraper::Node::Element(elem) => {
+            let name = elem.name();
+            if DROPPED_ELEMENTS.contains(&name) {
+                return;
+            }
+            out.push('<');
+            out.push_str(name);
+            for (attr, value) in elem.attrs() {
+                let attr = attr.to_ascii_lowercase();
+                if attr.starts_with("on") || DROPPED_ATTRIBUTES.contains(&attr.as_str()) {
+                    continue;
+                }
+                if URL_ATTRIBUTES.contains(&attr.as_str()) && !is_safe_url(&attr, value) {
+                    continue;
+                }
+                out.push(' ');
+                out.push_str(&attr);
+                out.push_str("=\"");
+                escape_html_attr(value, out);
+                out.push('"');
+            }
+            out.push('>');
+            if VOID_ELEMENTS.contains(&name) {
+                return;
+            }
+            for child in node.children() {
+                write_sanitized(child, out);
+            }
+            out.push_str("</");
+            out.push_str(name);
+            out.push('>');
+        }
+        scraper::Node::Text(text) => escape_html_text(text, out),
+        _ => {}
+    }
+}
+
+/// 相対 URL と `http` / `https` / `mailto`，画像の `data:` URL だけを許可する
+fn is_safe_url(attr: &str, value: &str) -> bool {
+    // `java\tscript:` のように制御文字・空白を挟んだスキームも検出する
+    let normalized: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match normalized.split_once(':') {
+        Some((scheme, _)) if !scheme.contains(['/', '?', '#']) => match scheme {
+            "http" | "https" | "mailto" => true,
+            "data" => {
+                attr == "src"
+                    && normalized.starts_with("data:image/")
+                    && !normalized.starts_with("data:image/svg")
+            }
+            _ => false,
+        },
+        _ => true,
+    }
+}
+
+/// iframe で読み込む記事本文のドキュメント
+pub fn render_article_frame(article: &WebArticle) -> String {
+    let mut title = String::new();
+    escape_html_text(&article.title, &mut title);
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ja">
+<head>
+<meta charset="utf-8">
+<meta name="robots" content="noindex, nofollow">
+<title>{title}</title>
+<style>body{{margin:0;font-family:sans-serif;line-height:1.7}}img{{max-width:100%}}</style>
+</head>
+<body>
+<article>
+{body}
+</article>
+</body>
+</html>
+"#,
+        title = title,
+        body = sanitize_article_html(&article.html),
+    )
+}
+
+/// CSP などのセキュリティヘッダを付けた HTML レスポンス
+pub fn html_with_csp(csp: &'static str, body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+            (header::CONTENT_SECURITY_POLICY, csp),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+            (header::REFERRER_POLICY, "no-referrer"),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    #[test]
+    fn test_sanitize_article_html_strips_active_content() {
+        let html = concat!(
+            r#"<p onclick="steal()" class="lead">Hello <b>world</b></p>"#,
+            r#"<script>alert(1)</script><iframe src="https://evil.example"></iframe>"#,
+            r#"<a href="javascript:alert(1)">x</a><a href=" JaVa&#x09;Script:alert(1)">y</a>"#,
+            r#"<a href="/relative">ok</a><img src="data:image/png;base64,AAAA" onerror="steal()">"#,
+            r#"<img src="data:text/html,<script>alert(1)</script>"><svg><script>alert(1)</script></svg>"#,
+            r#"<form action="https://evil.example"><input name="q"></form>"#,
+        );
+        let sanitized = sanitize_article_html(html);
+        assert_eq!(
+            sanitized,
+            concat!(
+                r#"<p class="lead">Hello <b>world</b></p>"#,
+                r#"<a>x</a><a>y</a><a href="/relative">ok</a>"#,
+                r#"<img src="data:image/png;base64,AAAA"><img>"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_article_frame() {
+        let article = ArticleBuilder::new("<Title>")
+            .with_url("https://example.com/a")
+            .with_html(r#"<p>Body</p><script>alert(1)</script>"#)
+            .build();
+        let frame = render_article_frame(&article);
+        assert!(frame.contains("<p>Body</p>"));
+        assert!(frame.contains("<title>&lt;Title&gt;</title>"));
+        assert!(!frame.contains("alert"));
+
+        let response = html_with_csp(ARTICLE_FRAME_CSP, frame);
+        let csp = response.headers()[header::CONTENT_SECURITY_POLICY].to_str().unwrap();
+        assert!(csp.ends_with("sandbox"));
+        assert!(!csp.contains("script-src"));
+    }
+}
@@ -0,0 +1,348 @@
+//! 記事の共有リンク．
+//!
+//! トークンは `base64url(JSON{url, exp}).base64url(HMAC-SHA256)` 形式で，
+//! アカウントを持たない相手でも期限内であれば記事のリーダービューを閲覧できる．
+//! リンクは `X-Clipper-User` ヘッダーで識別できる利用者が `POST /articles/{id}/share` で発行する．
+
+use crate::api::sandbox::{ARTICLE_FRAME_CSP, READER_VIEW_CSP, html_with_csp, render_article_frame};
+use crate::api::{USER_HEADER, request_user};
+use crate::cli::all_in_one::ArticleCache;
+use crate::models::web_article::{WebArticle, escape_html_text};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::id::WebArticleId;
+use crate::storage::ArticleStore;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::str::FromStr;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 署名鍵を指定する環境変数
+pub const SHARE_SECRET_ENV: &str = "CLIPPER_SHARE_SECRET";
+const MIN_SECRET_BYTES: usize = 16;
+/// 共有リンクの有効期間（時間）の既定値と上限
+pub const DEFAULT_SHARE_TTL_HOURS: i64 = 7 * 24;
+pub const MAX_SHARE_TTL_HOURS: i64 = 30 * 24;
+
+/// トークンに埋め込む共有対象と有効期限
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharePayload {
+    #[serde(rename = "u")]
+    pub article_url: String,
+    /// 有効期限（UNIX 秒）
+    #[serde(rename = "e")]
+    pub expires_at: i64,
+}
+
+/// 共有リンクの署名と検証
+#[derive(Clone)]
+pub struct ShareSigner {
+    key: Vec<u8>,
+}
+
+impl std::fmt::Debug for ShareSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShareSigner").finish_non_exhaustive()
+    }
+}
+
+impl ShareSigner {
+    pub fn new(secret: &[u8]) -> AppResult<Self> {
+        if secret.len() < MIN_SECRET_BYTES {
+            return Err(AppError::InternalError(format!(
+                "{} must be at least {} bytes",
+                SHARE_SECRET_ENV, MIN_SECRET_BYTES
+            )));
+        }
+        Ok(Self {
+            key: secret.to_vec(),
+        })
+    }
+
+    /// `CLIPPER_SHARE_SECRET` から作成する．未設定の場合は共有リンクを無効にする（`None`）．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        match std::env::var(SHARE_SECRET_ENV) {
+            Ok(secret) if !secret.is_empty() => Self::new(secret.as_bytes()).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// 現在から `ttl` の間有効なトークンを発行する
+    pub fn sign(&self, article_url: &str, ttl: Duration) -> String {
+        self.sign_until(article_url, Utc::now() + ttl)
+    }
+
+    pub fn sign_until(&self, article_url: &str, expires_at: DateTime<Utc>) -> String {
+        let payload = SharePayload {
+            article_url: article_url.to_string(),
+            expires_at: expires_at.timestamp(),
+        };
+        let encoded = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&payload).unwrap());
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(encoded.as_bytes()).finalize().into_bytes());
+        format!("{}.{}", encoded, signature)
+    }
+
+    /// 署名と有効期限を検証してトークンの内容を返す
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> AppResult<SharePayload> {
+        let invalid = |reason: &str| AppError::InvalidShareLink(reason.to_string());
+        let (encoded, signature) = token.split_once('.').ok_or_else(|| invalid("malformed token"))?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| invalid("malformed signature"))?;
+        self.mac(encoded.as_bytes())
+            .verify_slice(&signature)
+            .map_err(|_| invalid("bad signature"))?;
+
+        let payload: SharePayload = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| invalid("malformed payload"))?;
+        if payload.expires_at <= now.timestamp() {
+            return Err(invalid("expired"));
+        }
+        Ok(payload)
+    }
+
+    fn mac(&self, data: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(data);
+        mac
+    }
+}
+
+/// 共有リンクのパス
+pub fn share_path(token: &str) -> String {
+    format!("/share/{}", token)
+}
+
+/// 共有リンクの記事本文（iframe の中身）のパス
+pub fn share_frame_path(token: &str) -> String {
+    format!("/share/{}/frame", token)
+}
+
+/// 記事のリーダービューのページ．本文は `frame_src` から `<iframe sandbox>` で読み込む．
+pub fn render_reader_view(article: &WebArticle, frame_src: &str) -> String {
+    let title = escape_html(&article.title);
+    let site = escape_html(&article.site.name);
+    let url = escape_html(&article.article_url);
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ja">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex, nofollow">
+<title>{title}</title>
+<style>body{{max-width:42rem;margin:2rem auto;padding:0 1rem;font-family:sans-serif;line-height:1.7}}iframe{{width:100%;height:80vh;border:0}}</style>
+</head>
+<body>
+<header><p>{site} ・ {timestamp}</p><h1>{title}</h1><p><a href="{url}" rel="noopener noreferrer">{url}</a></p></header>
+<iframe sandbox src="{frame_src}" title="{title}" referrerpolicy="no-referrer"></iframe>
+</body>
+</html>
+"#,
+        title = title,
+        site = site,
+        timestamp = article.timestamp.format("%Y-%m-%d %H:%M"),
+        url = url,
+        frame_src = frame_src,
+    )
+}
+
+/// 本文中と属性値のどちらに埋め込んでもよいようにエスケープする
+fn escape_html(value: &str) -> String {
+    let mut text = String::new();
+    escape_html_text(value, &mut text);
+    text.replace('"', "&quot;")
+}
+
+/// 共有リンクの提供に必要な状態
+#[derive(Clone)]
+pub struct ShareState {
+    pub signer: Arc<ShareSigner>,
+    /// 直近の巡回で取得した記事（無ければ `store` から探す）
+    pub articles: ArticleCache,
+    pub store: Arc<dyn ArticleStore>,
+}
+
+/// `POST /articles/{id}/share` と `GET /share/{token}` を提供するルーター
+pub fn share_router(state: ShareState) -> Router {
+    Router::new()
+        .route("/articles/{id}/share", post(create_share_link))
+        .route("/share/{token}", get(get_shared_article))
+        .route("/share/{token}/frame", get(get_shared_article_frame))
+        .with_state(state)
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct ShareRequest {
+    /// 有効期間（時間）．省略時は [`DEFAULT_SHARE_TTL_HOURS`]，[`MAX_SHARE_TTL_HOURS`] を超える値は切り詰める．
+    #[serde(default)]
+    pub ttl_hours: Option<i64>,
+}
+
+/// 発行した共有リンク
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareLink {
+    /// `/share/{token}`（サーバの URL を前に付けて共有する）
+    pub path: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 保存済みの記事の共有リンクを発行する．利用者が分からないリクエストには発行しない．
+async fn create_share_link(
+    State(state): State<ShareState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(request): Json<ShareRequest>,
+) -> AppResult<(StatusCode, Json<ShareLink>)> {
+    if request_user(&headers).is_none() {
+        return Err(AppError::UserRequired(format!("{} header is required to share articles", USER_HEADER)));
+    }
+    let id = WebArticleId::from_str(&id)?;
+    let stored = state
+        .store
+        .get(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("article {}", id.to_string())))?;
+    let ttl_hours = request.ttl_hours.unwrap_or(DEFAULT_SHARE_TTL_HOURS).clamp(1, MAX_SHARE_TTL_HOURS);
+    // トークンの有効期限は秒単位のため，返す期限もそれに合わせる
+    let expires_at = DateTime::from_timestamp((Utc::now() + Duration::hours(ttl_hours)).timestamp(), 0)
+        .ok_or_else(|| AppError::InternalError("Invalid share link expiry".into()))?;
+    let token = state.signer.sign_until(&stored.article.article_url, expires_at);
+    Ok((
+        StatusCode::CREATED,
+        Json(ShareLink {
+            path: share_path(&token),
+            expires_at,
+        }),
+    ))
+}
+
+/// トークンを検証して共有対象の記事を探す（直近の巡回の記事に無ければ保存済みの記事から探す）
+async fn shared_article(state: &ShareState, token: &str) -> AppResult<WebArticle> {
+    let payload = state.signer.verify(token, Utc::now())?;
+    let cached = state
+        .articles
+        .read()
+        .await
+        .iter()
+        .find(|a| a.article_url == payload.article_url)
+        .cloned();
+    if let Some(article) = cached {
+        return Ok(article);
+    }
+    state
+        .store
+        .get_by_url(&payload.article_url)
+        .await?
+        .map(|stored| stored.article)
+        .ok_or_else(|| AppError::NotFound(payload.article_url.clone()))
+}
+
+async fn get_shared_article(
+    State(state): State<ShareState>,
+    Path(token): Path<String>,
+) -> Result<Response, AppError> {
+    let article = shared_article(&state, &token).await?;
+    let page = render_reader_view(&article, &share_frame_path(&token));
+    Ok(html_with_csp(READER_VIEW_CSP, page))
+}
+
+async fn get_shared_article_frame(
+    State(state): State<ShareState>,
+    Path(token): Path<String>,
+) -> Result<Response, AppError> {
+    let article = shared_article(&state, &token).await?;
+    Ok(html_with_csp(ARTICLE_FRAME_CSP, render_article_frame(&article)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::sqlite::SqliteStore;
+    use crate::testing::article::ArticleBuilder;
+    use tokio::sync::RwLock;
+
+    fn signer() -> ShareSigner {
+        ShareSigner::new(b"0123456789abcdef0123456789abcdef").unwrap()
+    }
+
+    #[test]
+    fn test_share_token_roundtrip() {
+        let signer = signer();
+        let token = signer.sign("https://example.com/a", Duration::hours(1));
+        let payload = signer.verify(&token, Utc::now()).unwrap();
+        assert_eq!(payload.article_url, "https://example.com/a");
+    }
+
+    #[test]
+    fn test_share_token_rejects_expired_and_tampered() {
+        let signer = signer();
+        let now = Utc::now();
+        let token = signer.sign_until("https://example.com/a", now + Duration::minutes(5));
+        assert!(signer.verify(&token, now + Duration::minutes(6)).is_err());
+
+        let other = signer.sign_until("https://example.com/b", now + Duration::minutes(5));
+        let (_, signature) = other.split_once('.').unwrap();
+        let (payload, _) = token.split_once('.').unwrap();
+        assert!(signer.verify(&format!("{}.{}", payload, signature), now).is_err());
+
+        let foreign = ShareSigner::new(b"another-secret-key-0000").unwrap();
+        assert!(foreign.verify(&token, now).is_err());
+        assert!(signer.verify("garbage", now).is_err());
+        assert!(ShareSigner::new(b"short").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_share_link_and_open_stored_article() {
+        let store = Arc::new(SqliteStore::open_in_memory().unwrap());
+        let article = ArticleBuilder::new("Shared").with_url("https://example.com/shared").build();
+        let id = store.upsert(&article).await.unwrap();
+        let state = ShareState {
+            signer: Arc::new(signer()),
+            articles: Arc::new(RwLock::new(Vec::new())),
+            store,
+        };
+        let request = ShareRequest {
+            ttl_hours: Some(10_000),
+        };
+        let anonymous = create_share_link(State(state.clone()), HeaderMap::new(), Path(id.to_string()), Json(request));
+        assert!(matches!(anonymous.await, Err(AppError::UserRequired(_))));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_HEADER, "alice".parse().unwrap());
+        let (status, Json(link)) = create_share_link(State(state.clone()), headers, Path(id.to_string()), Json(request))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(link.expires_at <= Utc::now() + Duration::hours(MAX_SHARE_TTL_HOURS));
+        // 直近の巡回で取得していない記事も保存済みの記事から開ける
+        let token = link.path.strip_prefix("/share/").unwrap();
+        assert_eq!(shared_article(&state, token).await.unwrap().article_url, "https://example.com/shared");
+    }
+
+    #[test]
+    fn test_render_reader_view_escapes_metadata() {
+        let article = ArticleBuilder::new("<script>alert(1)</script>")
+            .with_url("https://example.com/a")
+            .with_html("<p>Body</p>")
+            .build();
+        let page = render_reader_view(&article, &share_frame_path("token"));
+        assert!(page.contains("&lt;script&gt;"));
+        assert!(page.contains(r#"<iframe sandbox src="/share/token/frame""#));
+        // 本文はリーダービューに直接埋め込まない
+        assert!(!page.contains("<p>Body</p>"));
+    }
+}
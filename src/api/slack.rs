@@ -0,0 +1,230 @@
+//! Slack のインタラクティブ機能（催促のメッセージのボタン）によるトリアージの更新．
+//!
+//! Slack のアプリの Interactivity の Request URL に `/slack/actions` を設定する．
+//! リクエストは `X-Slack-Signature`（`v0=` + `HMAC-SHA256("v0:{timestamp}:{body}")` の16進数）で検証する．
+
+use crate::alerts::triage::TriageUpdate;
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::ArticleStore;
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Slack アプリの Signing Secret を指定する環境変数
+pub const SLACK_SIGNING_SECRET_ENV: &str = "CLIPPER_SLACK_SIGNING_SECRET";
+/// リプレイ攻撃を防ぐため，これより古いリクエストは受け付けない
+const MAX_REQUEST_AGE_SECS: i64 = 300;
+
+/// Slack からのリクエストの署名の検証
+#[derive(Clone)]
+pub struct SlackVerifier {
+    secret: Vec<u8>,
+}
+
+impl std::fmt::Debug for SlackVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlackVerifier").finish_non_exhaustive()
+    }
+}
+
+impl SlackVerifier {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            secret: secret.to_vec(),
+        }
+    }
+
+    /// `CLIPPER_SLACK_SIGNING_SECRET` から作成する．未設定の場合は Slack のボタンを無効にする（`None`）．
+    pub fn from_env() -> Option<Self> {
+        match std::env::var(SLACK_SIGNING_SECRET_ENV) {
+            Ok(secret) if !secret.is_empty() => Some(Self::new(secret.as_bytes())),
+            _ => None,
+        }
+    }
+
+    pub fn sign(&self, timestamp: i64, body: &[u8]) -> String {
+        let signature: String = self
+            .mac(timestamp, body)
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!("v0={}", signature)
+    }
+
+    /// `X-Slack-Request-Timestamp` と `X-Slack-Signature` を検証する
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8], now: DateTime<Utc>) -> AppResult<()> {
+        let invalid = |reason: &str| AppError::InvalidSignature(reason.to_string());
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let timestamp: i64 = header("x-slack-request-timestamp")
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| invalid("missing timestamp"))?;
+        if (now.timestamp() - timestamp).abs() > MAX_REQUEST_AGE_SECS {
+            return Err(invalid("stale request"));
+        }
+        let signature = header("x-slack-signature")
+            .and_then(|value| value.strip_prefix("v0="))
+            .and_then(decode_hex)
+            .ok_or_else(|| invalid("malformed signature"))?;
+        self.mac(timestamp, body)
+            .verify_slice(&signature)
+            .map_err(|_| invalid("bad signature"))
+    }
+
+    fn mac(&self, timestamp: i64, body: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(format!("v0:{}:", timestamp).as_bytes());
+        mac.update(body);
+        mac
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct SlackActionsState {
+    pub store: Arc<dyn ArticleStore>,
+    pub verifier: SlackVerifier,
+}
+
+/// `POST /slack/actions` を提供するルーター
+pub fn slack_actions_router(state: SlackActionsState) -> Router {
+    Router::new()
+        .route("/slack/actions", post(slack_actions))
+        .with_state(state)
+}
+
+/// `block_actions` のペイロードのうち使う項目
+#[derive(Debug, Clone, Deserialize)]
+struct ActionPayload {
+    user: SlackUser,
+    #[serde(default)]
+    actions: Vec<Action>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SlackUser {
+    id: String,
+    username: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Action {
+    action_id: String,
+    /// 記事の URL
+    #[serde(default)]
+    value: String,
+}
+
+/// ボタンが押された記事のトリアージを更新する（Slack には3秒以内に `200` を返す）
+async fn slack_actions(
+    State(state): State<SlackActionsState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<StatusCode> {
+    state.verifier.verify(&headers, &body, Utc::now())?;
+    let payload = url::form_urlencoded::parse(&body)
+        .find(|(key, _)| key == "payload")
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or_default();
+    let payload: ActionPayload = serde_json::from_str(&payload)?;
+    let user = payload.user.username.unwrap_or(payload.user.id);
+    for action in &payload.actions {
+        let Some(update) = TriageUpdate::from_slack_action(&action.action_id, &user) else {
+            continue;
+        };
+        let Some(stored) = state.store.get_by_url(&action.value).await? else {
+            tracing::warn!("Slack action for unknown article: {}", action.value);
+            continue;
+        };
+        state
+            .store
+            .set_triage(&action.value, &update.apply(&stored.triage))
+            .await?;
+    }
+    Ok(StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::triage::{ASSIGN_TO_ME_ACTION, TriageState};
+    use crate::storage::sqlite::SqliteStore;
+    use crate::testing::article::ArticleBuilder;
+
+    fn signed_headers(verifier: &SlackVerifier, timestamp: i64, body: &[u8]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-slack-request-timestamp", timestamp.to_string().parse().unwrap());
+        headers.insert("x-slack-signature", verifier.sign(timestamp, body).parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let verifier = SlackVerifier::new(b"8f742231b10e8888abcd99yyyzzz85a5");
+        let now = Utc::now();
+        let body = b"payload=%7B%7D";
+        let headers = signed_headers(&verifier, now.timestamp(), body);
+        assert!(verifier.verify(&headers, body, now).is_ok());
+        assert!(verifier.verify(&headers, b"payload=tampered", now).is_err());
+        let stale = signed_headers(&verifier, now.timestamp() - 600, body);
+        assert!(matches!(verifier.verify(&stale, body, now), Err(AppError::InvalidSignature(_))));
+        assert!(SlackVerifier::new(b"other").verify(&headers, body, now).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slack_actions_assign() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let url = "https://www.jpcert.or.jp/at/2025/0001.html";
+        store
+            .upsert(
+                &ArticleBuilder::new("注意喚起")
+                    .with_site("JPCERT", "https://www.jpcert.or.jp")
+                    .with_url(url)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let verifier = SlackVerifier::new(b"secret");
+        let state = SlackActionsState {
+            store: Arc::new(store),
+            verifier: verifier.clone(),
+        };
+        let payload = serde_json::json!({
+            "type": "block_actions",
+            "user": { "id": "U123", "username": "alice" },
+            "actions": [{ "action_id": ASSIGN_TO_ME_ACTION, "value": url }],
+        });
+        let body: String = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("payload", &payload.to_string())
+            .finish();
+        let headers = signed_headers(&verifier, Utc::now().timestamp(), body.as_bytes());
+
+        let status = slack_actions(State(state.clone()), headers, Bytes::from(body)).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        let stored = state.store.get_by_url(url).await.unwrap().unwrap();
+        assert_eq!(stored.triage.assignee.as_deref(), Some("alice"));
+        assert_eq!(stored.triage.state, TriageState::Acknowledged);
+
+        let unsigned = slack_actions(State(state), HeaderMap::new(), Bytes::from_static(b"payload=%7B%7D")).await;
+        assert!(matches!(unsigned, Err(AppError::InvalidSignature(_))));
+    }
+}
@@ -0,0 +1,3 @@
+//! 取得した記事の生のレスポンスの保存．
+
+pub mod warc;
@@ -0,0 +1,463 @@
+//! WARC（WARC/1.1）形式のアーカイブ．
+//!
+//! `CLIPPER_WARC_ARCHIVE=true` の場合，巡回で取得した HTTP レスポンスを `response` レコードとして
+//! 日ごとのファイル（`$CLIPPER_DATA_DIR/warc/clipper-YYYYMMDD.warc`）に追記する．
+//! 本文の抽出方法を改良したときに，保存したレスポンスから記事を作り直せる．
+//! reqwest は圧縮を展開した本文を返すため，`Content-Encoding` / `Content-Length` を除いた展開済みの本文を保存する．
+//! セッションを保存しないよう `Set-Cookie` も除く．
+//! `CLIPPER_STORAGE_KEY` を設定している場合，`response` レコードの内容（HTTP ヘッダーと本文）は
+//! データベースの本文と同じ鍵で暗号化して保存する（`WarcRecord::open` で復号する）．
+
+use crate::shared::config::{data_dir, parse_env_or};
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::encryption::{STORAGE_KEY_ENV, StorageCipher};
+use chrono::{DateTime, Local, SecondsFormat, SubsecRound, Utc};
+use request::header::{CONTENT_ENCODING, CONTENT_LENGTH, HeaderMap, SET_COOKIE};
+use request::{Response, ResponseBuilderExt, StatusCode, Url};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// `true` で取得したレスポンスを WARC に保存する（デフォルトは `false`）
+pub const WARC_ARCHIVE_ENV: &str = "CLIPPER_WARC_ARCHIVE";
+/// `$CLIPPER_DATA_DIR` 内の保存先
+pub const WARC_DIR: &str = "warc";
+
+const WARC_VERSION: &str = "WARC/1.1";
+const RESPONSE_CONTENT_TYPE: &str = "application/http;msgtype=response";
+/// 暗号化した `response` レコードの Content-Type
+const SEALED_CONTENT_TYPE: &str = "application/x-clipper-sealed";
+/// 保存しないレスポンスヘッダ
+const DROPPED_HEADERS: [request::header::HeaderName; 3] = [CONTENT_ENCODING, CONTENT_LENGTH, SET_COOKIE];
+
+static GLOBAL_WRITER: OnceLock<Option<WarcWriter>> = OnceLock::new();
+
+/// WARC のレコード
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarcRecord {
+    /// `warcinfo`，`response` など
+    pub record_type: String,
+    pub record_id: String,
+    pub date: DateTime<Utc>,
+    pub target_uri: Option<String>,
+    pub content_type: String,
+    pub block: Vec<u8>,
+}
+
+impl WarcRecord {
+    fn new(record_type: &str, target_uri: Option<String>, content_type: &str, block: Vec<u8>) -> Self {
+        Self {
+            record_type: record_type.to_string(),
+            record_id: format!("<urn:uuid:{}>", uuid::Uuid::new_v4()),
+            // WARC-Date は秒単位で保存する
+            date: Utc::now().trunc_subsecs(0),
+            target_uri,
+            content_type: content_type.to_string(),
+            block,
+        }
+    }
+
+    /// ファイルの先頭に置く `warcinfo` レコード
+    pub fn warcinfo() -> Self {
+        let fields = format!(
+            "software: {}/{}\r\nformat: WARC File Format 1.1\r\n",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+        Self::new("warcinfo", None, "application/warc-fields", fields.into_bytes())
+    }
+
+    /// HTTP レスポンスの `response` レコード
+    pub fn response(url: &str, status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        let mut block = format!(
+            "HTTP/1.1 {} {}\r\n",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default()
+        )
+        .into_bytes();
+        for (name, value) in headers.iter().filter(|(name, _)| !DROPPED_HEADERS.contains(*name)) {
+            block.extend_from_slice(name.as_str().as_bytes());
+            block.extend_from_slice(b": ");
+            block.extend_from_slice(value.as_bytes());
+            block.extend_from_slice(b"\r\n");
+        }
+        block.extend_from_slice(b"\r\n");
+        block.extend_from_slice(body);
+        Self::new("response", Some(url.to_string()), RESPONSE_CONTENT_TYPE, block)
+    }
+
+    /// レコードの内容を暗号化する
+    pub fn seal(mut self, cipher: &StorageCipher) -> AppResult<Self> {
+        self.block = cipher.encrypt_bytes(&self.block)?.into_bytes();
+        self.content_type = SEALED_CONTENT_TYPE.to_string();
+        Ok(self)
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.content_type == SEALED_CONTENT_TYPE
+    }
+
+    /// `seal` したレコードを復号する（暗号化されていないレコードはそのまま返す）
+    pub fn open(mut self, cipher: Option<&StorageCipher>) -> AppResult<Self> {
+        if !self.is_sealed() {
+            return Ok(self);
+        }
+        let cipher = cipher.ok_or_else(|| {
+            AppError::EncryptionError(format!("WARC record is encrypted but {} is not set", STORAGE_KEY_ENV))
+        })?;
+        let sealed = std::str::from_utf8(&self.block)
+            .map_err(|_| AppError::EncryptionError("malformed encrypted WARC record".into()))?;
+        self.block = cipher.decrypt_bytes(sealed)?;
+        self.content_type = RESPONSE_CONTENT_TYPE.to_string();
+        Ok(self)
+    }
+
+    /// `response` レコードの HTTP ステータスコードと本文（暗号化したレコードは `open` してから読む）
+    pub fn http_response(&self) -> Option<(u16, &[u8])> {
+        if self.record_type != "response" {
+            return None;
+        }
+        let head_end = find(&self.block, b"\r\n\r\n")?;
+        let head = std::str::from_utf8(&self.block[..head_end]).ok()?;
+        let status = head.lines().next()?.split_whitespace().nth(1)?.parse().ok()?;
+        Some((status, &self.block[head_end + 4..]))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut header = format!(
+            "{}\r\nWARC-Type: {}\r\nWARC-Record-ID: {}\r\nWARC-Date: {}\r\n",
+            WARC_VERSION,
+            self.record_type,
+            self.record_id,
+            self.date.to_rfc3339_opts(SecondsFormat::Secs, true)
+        );
+        if let Some(target_uri) = &self.target_uri {
+            header.push_str(&format!("WARC-Target-URI: {}\r\n", target_uri));
+        }
+        header.push_str(&format!(
+            "Content-Type: {}\r\nContent-Length: {}\r\n\r\n",
+            self.content_type,
+            self.block.len()
+        ));
+        let mut bytes = header.into_bytes();
+        bytes.extend_from_slice(&self.block);
+        bytes.extend_from_slice(b"\r\n\r\n");
+        bytes
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// WARC ファイルのレコードを先頭から読み込む
+pub fn read_records(path: &Path) -> AppResult<Vec<WarcRecord>> {
+    let bytes = std::fs::read(path).map_err(|e| io_error(path, e))?;
+    let invalid = |message: &str| AppError::InternalError(format!("Invalid WARC file {:?}: {}", path, message));
+    let mut records = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let head_end = find(&bytes[pos..], b"\r\n\r\n").ok_or_else(|| invalid("missing record header"))? + pos;
+        let head = std::str::from_utf8(&bytes[pos..head_end]).map_err(|_| invalid("non UTF-8 header"))?;
+        let mut lines = head.split("\r\n");
+        if !lines.next().is_some_and(|version| version.starts_with("WARC/")) {
+            return Err(invalid("missing version line"));
+        }
+        let fields: Vec<(&str, &str)> = lines.filter_map(|line| line.split_once(':')).collect();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.trim().to_string())
+        };
+        let length: usize = field("Content-Length")
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| invalid("missing Content-Length"))?;
+        let block_start = head_end + 4;
+        let block = bytes
+            .get(block_start..block_start + length)
+            .ok_or_else(|| invalid("truncated record"))?;
+        records.push(WarcRecord {
+            record_type: field("WARC-Type").ok_or_else(|| invalid("missing WARC-Type"))?,
+            record_id: field("WARC-Record-ID").unwrap_or_default(),
+            date: field("WARC-Date")
+                .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+                .map(|date| date.with_timezone(&Utc))
+                .ok_or_else(|| invalid("missing WARC-Date"))?,
+            target_uri: field("WARC-Target-URI"),
+            content_type: field("Content-Type").unwrap_or_default(),
+            block: block.to_vec(),
+        });
+        pos = block_start + length + 4;
+    }
+    Ok(records)
+}
+
+/// 日ごとの WARC ファイルにレコードを追記する
+#[derive(Debug)]
+pub struct WarcWriter {
+    dir: PathBuf,
+    cipher: Option<StorageCipher>,
+    lock: Mutex<()>,
+}
+
+impl WarcWriter {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            cipher: None,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// `response` レコードを暗号化する鍵を設定する
+    pub fn with_cipher(mut self, cipher: Option<StorageCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// `CLIPPER_WARC_ARCHIVE=true` の場合に `$CLIPPER_DATA_DIR/warc` に保存する
+    pub fn from_env() -> AppResult<Option<Self>> {
+        if !parse_env_or(WARC_ARCHIVE_ENV, false)? {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(data_dir().join(WARC_DIR)).with_cipher(StorageCipher::from_env()?)))
+    }
+
+    pub fn cipher(&self) -> Option<&StorageCipher> {
+        self.cipher.as_ref()
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path_for(&self, date: DateTime<Local>) -> PathBuf {
+        self.dir.join(format!("clipper-{}.warc", date.format("%Y%m%d")))
+    }
+
+    /// レコードを当日のファイルに追記し，そのパスを返す（新しいファイルには `warcinfo` を先に書く）
+    pub fn write(&self, record: &WarcRecord) -> AppResult<PathBuf> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        std::fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+        let path = self.path_for(record.date.with_timezone(&Local));
+        let mut bytes = Vec::new();
+        if !path.exists() {
+            bytes.extend(WarcRecord::warcinfo().to_bytes());
+        }
+        bytes.extend(record.to_bytes());
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| io_error(&path, e))?;
+        file.write_all(&bytes).map_err(|e| io_error(&path, e))?;
+        Ok(path)
+    }
+
+    /// `urls` を対象とするレコードを全てのファイルから取り除き，取り除いたレコード数を返す
+    pub fn remove_urls(&self, urls: &[String]) -> AppResult<usize> {
+        if urls.is_empty() {
+            return Ok(0);
+        }
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(io_error(&self.dir, e)),
+        };
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry.map_err(|e| io_error(&self.dir, e))?.path();
+            if path.extension().is_none_or(|ext| ext != "warc") {
+                continue;
+            }
+            let records = read_records(&path)?;
+            let total = records.len();
+            let kept: Vec<WarcRecord> = records
+                .into_iter()
+                .filter(|record| record.target_uri.as_ref().is_none_or(|uri| !urls.contains(uri)))
+                .collect();
+            if kept.len() == total {
+                continue;
+            }
+            removed += total - kept.len();
+            // 書き込み途中で失敗しても元のファイルが壊れないよう，一時ファイルに書いてから置き換える
+            let tmp = path.with_extension("warc.tmp");
+            let bytes: Vec<u8> = kept.iter().flat_map(WarcRecord::to_bytes).collect();
+            std::fs::write(&tmp, bytes).map_err(|e| io_error(&tmp, e))?;
+            std::fs::rename(&tmp, &path).map_err(|e| io_error(&path, e))?;
+        }
+        Ok(removed)
+    }
+
+    /// レスポンスの本文を読み込んで保存し，同じ内容のレスポンスを返す．
+    /// 保存に失敗しても記事の取得は続けられるよう，警告を出すだけにする．
+    pub async fn archive(&self, url: &Url, response: Response) -> AppResult<Response> {
+        self.archive_if(url, response, |_, _| true).await
+    }
+
+    /// 本文を読み込んだ後，`allow` が `true` を返した場合だけ保存する．
+    /// ページ自身の robots meta タグのように，本文を見るまで保存してよいか分からない場合に使う．
+    pub async fn archive_if(
+        &self,
+        url: &Url,
+        response: Response,
+        allow: impl FnOnce(&HeaderMap, &[u8]) -> bool,
+    ) -> AppResult<Response> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        if allow(&headers, &body) {
+            self.archive_body(url, status, &headers, &body);
+        }
+        let mut builder = axum::http::Response::builder().status(status.as_u16()).url(url.clone());
+        for (name, value) in headers.iter().filter(|(name, _)| !DROPPED_HEADERS.contains(*name)) {
+            builder = builder.header(name, value);
+        }
+        Ok(Response::from(builder.body(body.to_vec()).expect("response headers are valid")))
+    }
+
+    fn archive_body(&self, url: &Url, status: StatusCode, headers: &HeaderMap, body: &[u8]) {
+        let record = WarcRecord::response(url.as_str(), status, headers, body);
+        let record = match &self.cipher {
+            Some(cipher) => record.seal(cipher),
+            None => Ok(record),
+        };
+        if let Err(e) = record.and_then(|record| self.write(&record)) {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> AppError {
+    AppError::InternalError(format!("Failed to access WARC archive {:?}: {}", path, e))
+}
+
+/// プロセス全体で共有する書き込み先（無効化されている場合は `None`）
+pub fn global_warc_writer() -> Option<&'static WarcWriter> {
+    GLOBAL_WRITER
+        .get_or_init(|| {
+            WarcWriter::from_env().unwrap_or_else(|e| {
+                tracing::warn!("{}; WARC archive disabled", e);
+                None
+            })
+        })
+        .as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_writer() -> (WarcWriter, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("clipper-warc-{}", uuid::Uuid::new_v4()));
+        (WarcWriter::new(dir.clone()), dir)
+    }
+
+    #[test]
+    fn test_write_and_read_records() {
+        let (writer, dir) = temp_writer();
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "text/html; charset=utf-8".parse().unwrap());
+        headers.insert(SET_COOKIE, "session=secret".parse().unwrap());
+        let body = "<html><body>記事の本文\r\n\r\n続き</body></html>".as_bytes();
+        let record = WarcRecord::response("https://example.com/a", StatusCode::OK, &headers, body);
+        let path = writer.write(&record).unwrap();
+        writer
+            .write(&WarcRecord::response("https://example.com/b", StatusCode::NOT_FOUND, &HeaderMap::new(), b""))
+            .unwrap();
+
+        let records = read_records(&path).unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.record_type.as_str()).collect::<Vec<_>>(),
+            vec!["warcinfo", "response", "response"]
+        );
+        assert_eq!(records[1], record);
+        assert_eq!(records[1].http_response(), Some((200, body)));
+        assert_eq!(records[2].http_response(), Some((404, &b""[..])));
+        assert!(!String::from_utf8_lossy(&records[1].block).contains("secret"));
+        assert_eq!(records[0].http_response(), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_archive_returns_same_response() {
+        let (writer, dir) = temp_writer();
+        let url = Url::parse("https://example.com/feed.xml").unwrap();
+        let response = Response::from(
+            axum::http::Response::builder()
+                .status(200)
+                .header("content-type", "application/xml")
+                .body("<rss/>".to_string())
+                .unwrap(),
+        );
+        let archived = writer.archive(&url, response).await.unwrap();
+        assert_eq!(archived.url(), &url);
+        assert_eq!(archived.headers()["content-type"], "application/xml");
+        assert_eq!(archived.text().await.unwrap(), "<rss/>");
+
+        let records = read_records(&writer.path_for(Local::now())).unwrap();
+        assert_eq!(records[1].target_uri.as_deref(), Some("https://example.com/feed.xml"));
+        assert_eq!(records[1].http_response().map(|(_, body)| body), Some(&b"<rss/>"[..]));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_archive_if_skips_rejected_body() {
+        let (writer, dir) = temp_writer();
+        let url = Url::parse("https://example.com/noarchive").unwrap();
+        let body = r#"<head><meta name="robots" content="noarchive"></head>"#;
+        let response =
+            Response::from(axum::http::Response::builder().status(200).body(body.to_string()).unwrap());
+        let archived = writer
+            .archive_if(&url, response, |_, body| !String::from_utf8_lossy(body).contains("noarchive"))
+            .await
+            .unwrap();
+        assert_eq!(archived.text().await.unwrap(), body);
+        assert!(!writer.path_for(Local::now()).exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_archive_seals_records_with_cipher() {
+        let (writer, dir) = temp_writer();
+        let writer = writer.with_cipher(Some(StorageCipher::new(&[5u8; 32]).unwrap()));
+        let url = Url::parse("https://example.com/paid").unwrap();
+        let response = Response::from(
+            axum::http::Response::builder()
+                .status(200)
+                .header("content-type", "text/html")
+                .body("<p>有料記事の本文</p>".to_string())
+                .unwrap(),
+        );
+        let archived = writer.archive(&url, response).await.unwrap();
+        assert_eq!(archived.text().await.unwrap(), "<p>有料記事の本文</p>");
+
+        let path = writer.path_for(Local::now());
+        let raw = std::fs::read(&path).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("有料記事"));
+        let record = read_records(&path).unwrap().remove(1);
+        assert!(record.is_sealed());
+        assert_eq!(record.target_uri.as_deref(), Some("https://example.com/paid"));
+        assert!(record.clone().open(None).is_err());
+        let opened = record.open(writer.cipher()).unwrap();
+        assert_eq!(opened.content_type, RESPONSE_CONTENT_TYPE);
+        assert_eq!(opened.http_response(), Some((200, "<p>有料記事の本文</p>".as_bytes())));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_urls() {
+        let (writer, dir) = temp_writer();
+        assert_eq!(writer.remove_urls(&["https://example.com/a".into()]).unwrap(), 0);
+        for url in ["https://example.com/a", "https://example.com/b"] {
+            writer.write(&WarcRecord::response(url, StatusCode::OK, &HeaderMap::new(), b"body")).unwrap();
+        }
+        assert_eq!(writer.remove_urls(&["https://example.com/a".into()]).unwrap(), 1);
+        let records = read_records(&writer.path_for(Local::now())).unwrap();
+        let uris: Vec<Option<&str>> = records.iter().map(|r| r.target_uri.as_deref()).collect();
+        assert_eq!(uris, vec![None, Some("https://example.com/b")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
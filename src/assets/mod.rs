@@ -0,0 +1,392 @@
+//! 記事の画像（リード画像と本文中の画像）のキャッシュ．
+//!
+//! 画像をダウンロードして `$CLIPPER_DATA_DIR/assets` に保存し，保存する HTML の `<img src>` をキャッシュの URL
+//! （デフォルトは API サーバが配信する `/assets/<名前>`）に書き換える．元のページや画像が消えても記事をオフラインで読めるようにする．
+//! 保存先は `AssetStore` を実装すれば S3 などのオブジェクトストレージに差し替えられる．
+
+use crate::models::web_article::WebArticle;
+use crate::shared::config::{data_dir, parse_env_or};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::regexes;
+use crate::shared::selectors::selector;
+use crate::shared::ssrf::fetch_untrusted;
+use request::Url;
+use request::header::CONTENT_TYPE;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// 画像のキャッシュを有効にする環境変数（デフォルト `false`）
+pub const CACHE_IMAGES_ENV: &str = "CLIPPER_CACHE_IMAGES";
+/// 書き換えた `<img src>` の URL の前半を指定する環境変数（デフォルト `/assets`）
+pub const ASSETS_BASE_URL_ENV: &str = "CLIPPER_ASSETS_BASE_URL";
+/// データディレクトリ内の保存先
+pub const ASSETS_DIR: &str = "assets";
+
+const DEFAULT_BASE_URL: &str = "/assets";
+/// 1枚あたりの最大サイズ
+pub const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+/// 1記事あたりに保存する画像の最大数
+const MAX_IMAGES_PER_ARTICLE: usize = 30;
+
+/// 保存する画像の Content-Type と拡張子．SVG はスクリプトを含められるため保存しない．
+pub const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/avif", "avif"),
+];
+
+/// 拡張子に対応する Content-Type
+pub fn content_type_for(extension: &str) -> Option<&'static str> {
+    IMAGE_TYPES
+        .iter()
+        .find(|(_, ext)| ext.eq_ignore_ascii_case(extension))
+        .map(|(content_type, _)| *content_type)
+}
+
+/// 画像の元の URL から求めるキー（SHA-256 の先頭32桁）
+pub fn asset_key(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .take(16)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 画像の保存先
+#[async_trait::async_trait]
+pub trait AssetStore: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// 保存済みであれば記事から参照する URL を返す
+    async fn lookup(&self, key: &str) -> AppResult<Option<String>>;
+    /// 画像を保存し，記事から参照する URL を返す
+    async fn put(&self, key: &str, extension: &str, bytes: &[u8]) -> AppResult<String>;
+    /// 保存した画像を削除する（保存されていなかった場合は `false`）
+    async fn remove(&self, key: &str) -> AppResult<bool>;
+}
+
+/// ローカルのディレクトリに `<キー>.<拡張子>` として保存する
+#[derive(Debug, Clone)]
+pub struct LocalAssetStore {
+    dir: PathBuf,
+    base_url: String,
+}
+
+impl LocalAssetStore {
+    pub fn new(dir: impl Into<PathBuf>, base_url: &str) -> Self {
+        Self {
+            dir: dir.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// `CLIPPER_CACHE_IMAGES=true` の場合に `$CLIPPER_DATA_DIR/assets` に保存する
+    pub fn from_env() -> AppResult<Option<Self>> {
+        if !parse_env_or(CACHE_IMAGES_ENV, false)? {
+            return Ok(None);
+        }
+        let base_url: String = parse_env_or(ASSETS_BASE_URL_ENV, DEFAULT_BASE_URL.to_string())?;
+        Ok(Some(Self::new(data_dir().join(ASSETS_DIR), &base_url)))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn url_for(&self, file_name: &str) -> String {
+        format!("{}/{}", self.base_url, file_name)
+    }
+}
+
+#[async_trait::async_trait]
+impl AssetStore for LocalAssetStore {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn lookup(&self, key: &str) -> AppResult<Option<String>> {
+        Ok(IMAGE_TYPES
+            .iter()
+            .map(|(_, ext)| format!("{}.{}", key, ext))
+            .find(|file_name| self.dir.join(file_name).is_file())
+            .map(|file_name| self.url_for(&file_name)))
+    }
+
+    async fn put(&self, key: &str, extension: &str, bytes: &[u8]) -> AppResult<String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", self.dir, e)))?;
+        let file_name = format!("{}.{}", key, extension);
+        let path = self.dir.join(&file_name);
+        std::fs::write(&path, bytes)
+            .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e)))?;
+        Ok(self.url_for(&file_name))
+    }
+
+    async fn remove(&self, key: &str) -> AppResult<bool> {
+        let mut removed = false;
+        for (_, ext) in IMAGE_TYPES {
+            let path = self.dir.join(format!("{}.{}", key, ext));
+            match std::fs::remove_file(&path) {
+                Ok(()) => removed = true,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(AppError::InternalError(format!("Failed to remove {:?}: {}", path, e)));
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// 記事が参照しているキャッシュした画像のキー（書き換えた `<img src>` と `properties.cached_image` のファイル名から求める）
+pub fn cached_asset_keys(article: &WebArticle) -> HashSet<String> {
+    let fragment = scraper::Html::parse_fragment(&article.html);
+    fragment
+        .select(selector!("img[src]"))
+        .filter_map(|e| e.value().attr("src"))
+        .chain(article.properties.cached_image.as_deref())
+        .filter_map(|src| {
+            let path = src.split(['?', '#']).next().unwrap_or_default();
+            let (stem, _) = path.rsplit('/').next()?.split_once('.')?;
+            (stem.len() == 32 && stem.bytes().all(|b| b.is_ascii_hexdigit())).then(|| stem.to_string())
+        })
+        .collect()
+}
+
+/// 画像のキャッシュの結果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AssetReport {
+    /// 新たにダウンロードした画像
+    pub cached: usize,
+    /// 保存済みの画像を使った数
+    pub reused: usize,
+    pub failed: usize,
+}
+
+/// `&amp;` などを含む属性値を URL として解決する（`data:` や `http(s)` 以外は `None`）
+fn resolve(base: Option<&Url>, value: &str) -> Option<Url> {
+    let value = value.trim().replace("&amp;", "&");
+    if value.is_empty() || value.starts_with("data:") {
+        return None;
+    }
+    let url = match base {
+        Some(base) => base.join(&value).ok()?,
+        None => Url::parse(&value).ok()?,
+    };
+    matches!(url.scheme(), "http" | "https").then_some(url)
+}
+
+/// リード画像と本文中の画像の URL（重複を除き，出現順）
+pub fn image_urls(article: &WebArticle) -> Vec<Url> {
+    let base = Url::parse(&article.article_url).ok();
+    let fragment = scraper::Html::parse_fragment(&article.html);
+    let sources = article
+        .properties
+        .image
+        .iter()
+        .map(String::as_str)
+        .chain(fragment.select(selector!("img[src]")).filter_map(|e| e.value().attr("src")));
+    let mut urls: Vec<Url> = Vec::new();
+    for url in sources.filter_map(|src| resolve(base.as_ref(), src)) {
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+        if urls.len() >= MAX_IMAGES_PER_ARTICLE {
+            break;
+        }
+    }
+    urls
+}
+
+/// キャッシュした画像（元の URL → キャッシュの URL）に `<img src>` を書き換え，書き換えた画像の `srcset` を取り除く
+pub fn rewrite_image_sources(html: &str, base: Option<&Url>, cached: &HashMap<String, String>) -> String {
+    regexes::img_tag()
+        .replace_all(html, |caps: &regex::Captures| {
+            let tag = &caps[0];
+            let Some(src) = regexes::src_attribute().captures(tag) else {
+                return tag.to_string();
+            };
+            let value = src["value"].trim_matches(|c| c == '"' || c == '\'');
+            let Some(local) = resolve(base, value).and_then(|url| cached.get(url.as_str())) else {
+                return tag.to_string();
+            };
+            let range = src.name("value").expect("value group always matches").range();
+            let local = local.replace('"', "%22");
+            let rewritten = format!("{}\"{}\"{}", &tag[..range.start], local, &tag[range.end..]);
+            regexes::srcset_attribute().replace_all(&rewritten, "").into_owned()
+        })
+        .into_owned()
+}
+
+/// 画像をダウンロードし，本文と拡張子を返す．内部ネットワークの URL や画像以外のレスポンスは拒否する．
+async fn download_image(url: &Url) -> AppResult<(Vec<u8>, &'static str)> {
+    let response = fetch_untrusted(url.as_str()).await?;
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    let extension = IMAGE_TYPES
+        .iter()
+        .find(|(t, _)| *t == content_type)
+        .map(|(_, ext)| *ext)
+        .ok_or_else(|| AppError::InternalError(format!("Unsupported image type {:?}: {}", content_type, url)))?;
+    if response.content_length().is_some_and(|len| len as usize > MAX_IMAGE_BYTES) {
+        return Err(AppError::InternalError(format!("Image too large: {}", url)));
+    }
+    let bytes = response.bytes().await?;
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(AppError::InternalError(format!("Image too large: {}", url)));
+    }
+    Ok((bytes.to_vec(), extension))
+}
+
+/// 記事の画像を保存し，本文の HTML をキャッシュの URL に書き換える．リード画像のキャッシュの URL は
+/// `properties.cached_image` に設定する．保存に失敗した画像は元の URL のまま残す．
+pub async fn cache_article_images(store: &dyn AssetStore, article: &mut WebArticle) -> AssetReport {
+    let mut report = AssetReport::default();
+    let mut cached: HashMap<String, String> = HashMap::new();
+    for url in image_urls(article) {
+        let key = asset_key(url.as_str());
+        match store.lookup(&key).await {
+            Ok(Some(local)) => {
+                report.reused += 1;
+                cached.insert(url.to_string(), local);
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to look up {} in {} asset store: {}", url, store.name(), e),
+        }
+        let stored = match download_image(&url).await {
+            Ok((bytes, extension)) => store.put(&key, extension, &bytes).await,
+            Err(e) => Err(e),
+        };
+        match stored {
+            Ok(local) => {
+                report.cached += 1;
+                cached.insert(url.to_string(), local);
+            }
+            Err(e) => {
+                report.failed += 1;
+                tracing::warn!("Failed to cache image {}: {}", url, e);
+            }
+        }
+    }
+    let base = Url::parse(&article.article_url).ok();
+    if !article.html.is_empty() {
+        article.html = rewrite_image_sources(&article.html, base.as_ref(), &cached);
+    }
+    article.properties.cached_image = article
+        .properties
+        .image
+        .as_deref()
+        .and_then(|image| resolve(base.as_ref(), image))
+        .and_then(|url| cached.get(url.as_str()).cloned());
+    report
+}
+
+/// 複数の記事の画像を保存する
+pub async fn cache_images<'a>(
+    store: &dyn AssetStore,
+    articles: impl IntoIterator<Item = &'a mut WebArticle>,
+) -> AssetReport {
+    let mut total = AssetReport::default();
+    for article in articles {
+        let report = cache_article_images(store, article).await;
+        total.cached += report.cached;
+        total.reused += report.reused;
+        total.failed += report.failed;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    fn article(html: &str) -> WebArticle {
+        let mut article = ArticleBuilder::new("タイトル")
+            .with_url("https://example.com/posts/1")
+            .with_html(html)
+            .build();
+        article.properties.image = Some("https://cdn.example.com/lead.png".into());
+        article
+    }
+
+    #[test]
+    fn test_image_urls() {
+        let article = article(
+            r#"<p><img src="/a.png"><img src="data:image/gif;base64,AA"><img src="https://cdn.example.com/lead.png">
+            <img src="b.jpg?w=1&amp;h=2"><img src="javascript:alert(1)"></p>"#,
+        );
+        let urls: Vec<String> = image_urls(&article).iter().map(Url::to_string).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://cdn.example.com/lead.png",
+                "https://example.com/a.png",
+                "https://example.com/posts/b.jpg?w=1&h=2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_image_sources() {
+        let base = Url::parse("https://example.com/posts/1").unwrap();
+        let cached = HashMap::from([
+            ("https://example.com/a.png".to_string(), "/assets/1.png".to_string()),
+            ("https://example.com/posts/b.jpg?w=1&h=2".to_string(), "/assets/2.jpg".to_string()),
+        ]);
+        let html = concat!(
+            r#"<img alt="a" src='/a.png' srcset="/a@2x.png 2x">"#,
+            r#"<IMG SRC=b.jpg?w=1&amp;h=2><img src="/c.png">"#
+        );
+        assert_eq!(
+            rewrite_image_sources(html, Some(&base), &cached),
+            r#"<img alt="a" src="/assets/1.png"><IMG SRC="/assets/2.jpg"><img src="/c.png">"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_asset_store() {
+        let dir = std::env::temp_dir().join(format!("clipper-assets-{}", uuid::Uuid::new_v4()));
+        let store = LocalAssetStore::new(&dir, "/assets/");
+        let key = asset_key("https://example.com/a.png");
+        assert_eq!(key.len(), 32);
+        assert_eq!(store.lookup(&key).await.unwrap(), None);
+        let url = store.put(&key, "png", b"\x89PNG").await.unwrap();
+        assert_eq!(url, format!("/assets/{}.png", key));
+        assert_eq!(store.lookup(&key).await.unwrap(), Some(url));
+        assert!(store.remove(&key).await.unwrap());
+        assert!(!store.remove(&key).await.unwrap());
+        assert_eq!(store.lookup(&key).await.unwrap(), None);
+        assert_eq!(content_type_for("PNG"), Some("image/png"));
+        assert_eq!(content_type_for("svg"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cache_article_images_reuses_stored_images() {
+        let dir = std::env::temp_dir().join(format!("clipper-assets-{}", uuid::Uuid::new_v4()));
+        let store = LocalAssetStore::new(&dir, "/assets");
+        let lead = asset_key("https://cdn.example.com/lead.png");
+        let inline = asset_key("https://example.com/a.png");
+        store.put(&lead, "png", b"lead").await.unwrap();
+        store.put(&inline, "jpg", b"inline").await.unwrap();
+
+        let mut article = article(r#"<p><img src="/a.png"></p>"#);
+        let report = cache_article_images(&store, &mut article).await;
+        assert_eq!(report, AssetReport { cached: 0, reused: 2, failed: 0 });
+        assert_eq!(article.html, format!(r#"<p><img src="/assets/{}.jpg"></p>"#, inline));
+        assert_eq!(article.properties.cached_image, Some(format!("/assets/{}.png", lead)));
+        assert_eq!(article.properties.image.as_deref(), Some("https://cdn.example.com/lead.png"));
+        assert_eq!(cached_asset_keys(&article), HashSet::from([lead, inline]));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
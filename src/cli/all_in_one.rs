@@ -0,0 +1,559 @@
+use crate::alerts::anomaly::AnomalyDetector;
+use crate::alerts::triage::send_unassigned_reminder_if_due;
+use crate::api::assets::assets_router;
+use crate::api::share::{ShareSigner, ShareState, share_router};
+use crate::api::slack::{SlackActionsState, SlackVerifier, slack_actions_router};
+use crate::api::{ApiState, api_router};
+use crate::alerts::issues::IssueSink;
+use crate::alerts::{AlertRulesConfig, evaluate_rules, render_alert_text};
+use crate::assets::{LocalAssetStore, cache_images};
+use crate::enrichment::snippets::SnippetOptions;
+use crate::enrichment::classifier::{Classifier, classifier_from_env};
+use crate::enrichment::deadlines::detect_deadlines;
+use crate::enrichment::few_shot::{ExampleStore, FEW_SHOT_EXAMPLES_FILE};
+use crate::enrichment::interests::{InterestModel, interest_weights};
+use crate::enrichment::relevance::{relevance_weight, score_articles_from_env};
+use crate::enrichment::sampling::{SamplingProfile, classify_with_budget};
+use crate::models::fairness::SiteCategory;
+use crate::models::fetch_all_articles;
+use crate::models::report::{Degradation, FetchReport, mark_degraded};
+use crate::models::reliability::send_weekly_report_if_due;
+use crate::models::web_article::WebArticle;
+use crate::monitoring::HealthMonitor;
+use crate::notifiers::email::{EmailNotifier, send_daily_digest_if_due};
+use crate::notifiers::notifiers_from_env;
+use crate::notifiers::outbox::{Delivery, Outbox};
+use crate::notifiers::slack::{SlackNotifier, send_morning_brief_if_due};
+use crate::scheduler::{Scheduler, shutdown_signal};
+use crate::shared::config::{ServeConfig, env_file_path, read_env_file};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::sink::ReportSink;
+use crate::storage::buffer::WriteBuffer;
+use crate::storage::{ArticleStore, FetchHistoryStore};
+use crate::storage::encryption::StorageCipher;
+use crate::storage::sqlite::SqliteStore;
+use axum::Router;
+use chrono::{DateTime, Duration, Local};
+use std::collections::HashMap;
+use std::future::IntoFuture;
+use std::sync::Arc;
+use tokio::sync::{Notify, RwLock, watch};
+use tracing::{Level, event};
+
+/// 直近の巡回で取得した記事
+pub type ArticleCache = Arc<RwLock<Vec<WebArticle>>>;
+/// SIGHUP で差し替え可能な設定
+pub type SharedConfig = Arc<RwLock<ServeConfig>>;
+
+/// スケジューラ・ワーカー・API サーバを1プロセスで起動し，シグナルを受け取るまで動作し続ける
+pub async fn run_all_in_one(config: ServeConfig) -> AppResult<()> {
+    std::fs::create_dir_all(&config.data_dir).map_err(|e| {
+        AppError::InternalError(format!(
+            "Failed to create data directory {:?}: {}",
+            config.data_dir, e
+        ))
+    })?;
+    event!(
+        Level::INFO,
+        "Starting all-in-one mode: bind={}, database={:?}, interval={:?}",
+        config.bind_addr,
+        config.database_path,
+        config.fetch_interval
+    );
+
+    let store = Arc::new(SqliteStore::open(&config.database_path)?);
+    let examples = Arc::new(ExampleStore::open(
+        config.data_dir.join(FEW_SHOT_EXAMPLES_FILE),
+        StorageCipher::from_env()?,
+    )?);
+    let interests = Arc::new(InterestModel::from_env(&config.data_dir)?);
+    let cache: ArticleCache = Arc::new(RwLock::new(Vec::new()));
+    let buffer = Arc::new(WriteBuffer::default());
+    let scheduler = Scheduler::new(config.fetch_interval, config.run_on_start);
+    let router = build_router(
+        store.clone(),
+        examples.clone(),
+        interests.clone(),
+        cache.clone(),
+        scheduler.trigger(),
+    )?;
+    let listener = bind(&config).await?;
+    let shared_config: SharedConfig = Arc::new(RwLock::new(config));
+    spawn_reload_handler(shared_config.clone());
+
+    // シグナルを受け取ったら API サーバとスケジューラの両方を停止する
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
+    let wait_shutdown = |mut rx: watch::Receiver<bool>| async move {
+        let _ = rx.wait_for(|stopped| *stopped).await;
+    };
+    let server = tokio::spawn(
+        axum::serve(listener, router)
+            .with_graceful_shutdown(wait_shutdown(shutdown_rx.clone()))
+            .into_future(),
+    );
+
+    scheduler
+        .run(
+            || {
+                let cache = cache.clone();
+                let store = store.clone();
+                let examples = examples.clone();
+                let interests = interests.clone();
+                let buffer = buffer.clone();
+                let shared_config = shared_config.clone();
+                async move {
+                    // 実行中のジョブは開始時点の設定を使い続ける（SIGHUP の影響を受けない）
+                    let config = shared_config.read().await.clone();
+                    let job = run_fetch_job(
+                        &config,
+                        store.as_ref(),
+                        store.as_ref(),
+                        &buffer,
+                        examples,
+                        interests,
+                        cache,
+                    );
+                    if let Err(e) = job.await {
+                        event!(Level::ERROR, "Fetch job failed: {}", e);
+                    }
+                }
+            },
+            wait_shutdown(shutdown_rx),
+        )
+        .await;
+
+    match server.await {
+        Ok(Err(e)) => event!(Level::ERROR, "API server failed: {}", e),
+        Err(e) => event!(Level::ERROR, "API server task panicked: {}", e),
+        Ok(Ok(())) => {}
+    }
+    event!(Level::INFO, "All-in-one mode stopped");
+    Ok(())
+}
+
+/// スケジューラを使わずに API サーバだけを起動する．巡回は `POST /refresh` を受けたときだけ行う．
+pub async fn run_serve(config: ServeConfig) -> AppResult<()> {
+    let store = Arc::new(SqliteStore::open(&config.database_path)?);
+    let examples = Arc::new(ExampleStore::open(
+        config.data_dir.join(FEW_SHOT_EXAMPLES_FILE),
+        StorageCipher::from_env()?,
+    )?);
+    let interests = Arc::new(InterestModel::from_env(&config.data_dir)?);
+    let cache: ArticleCache = Arc::new(RwLock::new(Vec::new()));
+    let buffer = WriteBuffer::default();
+    let trigger = Arc::new(Notify::new());
+    let router = build_router(
+        store.clone(),
+        examples.clone(),
+        interests.clone(),
+        cache.clone(),
+        trigger.clone(),
+    )?;
+    let listener = bind(&config).await?;
+    event!(Level::INFO, "Serving API on {}", config.bind_addr);
+
+    let worker = tokio::spawn(async move {
+        loop {
+            trigger.notified().await;
+            let job = run_fetch_job(
+                &config,
+                store.as_ref(),
+                store.as_ref(),
+                &buffer,
+                examples.clone(),
+                interests.clone(),
+                cache.clone(),
+            );
+            if let Err(e) = job.await {
+                event!(Level::ERROR, "Fetch job failed: {}", e);
+            }
+        }
+    });
+    let served = axum::serve(listener, router)
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .map_err(|e| AppError::InternalError(format!("API server failed: {}", e)));
+    worker.abort();
+    served
+}
+
+async fn bind(config: &ServeConfig) -> AppResult<tokio::net::TcpListener> {
+    tokio::net::TcpListener::bind(config.bind_addr)
+        .await
+        .map_err(|e| AppError::InternalError(format!("Failed to bind {}: {}", config.bind_addr, e)))
+}
+
+/// 記事 API と（`CLIPPER_SHARE_SECRET` が設定されていれば）共有リンク，（`CLIPPER_CACHE_IMAGES=true` なら）画像，
+/// （`CLIPPER_SLACK_SIGNING_SECRET` が設定されていれば）Slack のボタンのルーター
+fn build_router(
+    store: Arc<SqliteStore>,
+    examples: Arc<ExampleStore>,
+    interests: Arc<InterestModel>,
+    cache: ArticleCache,
+    refresh: Arc<Notify>,
+) -> AppResult<Router> {
+    let mut router = api_router(ApiState {
+        store: store.clone(),
+        refresh,
+        examples,
+        interests,
+        annotations: store.clone(),
+    });
+    if let Some(signer) = ShareSigner::from_env()? {
+        router = router.merge(share_router(ShareState {
+            signer: Arc::new(signer),
+            articles: cache,
+            store: store.clone(),
+        }));
+    }
+    if let Some(verifier) = SlackVerifier::from_env() {
+        router = router.merge(slack_actions_router(SlackActionsState { store, verifier }));
+    }
+    if let Some(assets) = LocalAssetStore::from_env()? {
+        router = router.merge(assets_router(assets.dir().to_path_buf()));
+    }
+    Ok(router)
+}
+
+/// SIGHUP を受け取るたびに，起動時に指定された環境変数ファイル（`CLIPPER_ENV_FILE`，未指定の場合は `.env`）
+/// を読み直して `ServeConfig` を差し替える．読み込みに失敗した場合は現在の設定を維持する．
+///
+/// プロキシ・リトライ・レート制限・ドメイン例外などプロセス全体で一度だけ初期化される設定は
+/// 差し替えられないため，これらの変更には再起動が必要．
+#[cfg(unix)]
+fn spawn_reload_handler(shared_config: SharedConfig) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let env_file = env_file_path();
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(sig) => sig,
+            Err(e) => {
+                event!(Level::WARN, "Failed to install SIGHUP handler: {}", e);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            // 実行中に `set_var` すると他のスレッドの環境変数の読み込みと競合するため，
+            // ファイルの値はプロセスの環境変数に反映せず，設定の組み立てにだけ使う
+            let values = match read_env_file(env_file.as_deref()) {
+                Some((path, values)) => {
+                    event!(Level::INFO, "Reloaded environment from {:?}", path);
+                    values
+                }
+                None => {
+                    event!(Level::WARN, "Environment file not found; using current environment");
+                    HashMap::new()
+                }
+            };
+            match ServeConfig::from_env_file(&values) {
+                Ok(new_config) => {
+                    let mut current = shared_config.write().await;
+                    if new_config.fetch_interval != current.fetch_interval
+                        || new_config.bind_addr != current.bind_addr
+                    {
+                        event!(
+                            Level::WARN,
+                            "fetch interval / bind address changes take effect after restart"
+                        );
+                    }
+                    *current = new_config;
+                    event!(
+                        Level::INFO,
+                        "Configuration reloaded (proxy / retry / rate limit settings take effect after restart)"
+                    );
+                }
+                Err(e) => event!(Level::ERROR, "Failed to reload configuration: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_reload_handler(_shared_config: SharedConfig) {}
+
+/// 全サイトから記事を取得して保存し，キャッシュを置き換えるワーカージョブ．
+/// サイト一覧はジョブごとに読み込むため，リロード後の次回ジョブから反映される．
+///
+/// LLM・ストア・通知先が使えない場合もジョブは止めず，それぞれ抽出型の要約，`buffer` への保持，
+/// アウトボックスへの蓄積に切り替えて，切り替えたことを巡回結果（`FetchReport::degraded`）に記録する．
+async fn run_fetch_job(
+    config: &ServeConfig,
+    store: &dyn ArticleStore,
+    history: &dyn FetchHistoryStore,
+    buffer: &WriteBuffer,
+    examples: Arc<ExampleStore>,
+    interests: Arc<InterestModel>,
+    cache: ArticleCache,
+) -> AppResult<()> {
+    event!(Level::INFO, "Fetch job started (concurrency={})", config.concurrency);
+    let started_at = Local::now();
+    let outcome = fetch_all_articles(config.concurrency).await?;
+    for (site, error) in outcome.errors() {
+        event!(Level::WARN, "Failed to fetch {}: {}", site, error);
+    }
+    let mut reports = outcome.reports;
+    // 過去30日の巡回結果と比べて，記事数や内容の急な変化を警告する
+    match history.runs_between(started_at - Duration::days(30), started_at).await {
+        Ok(past) => {
+            for anomaly in AnomalyDetector::default().detect_all(&past, &reports) {
+                event!(Level::WARN, "{}", render_alert_text(&anomaly.to_alert()));
+            }
+        }
+        Err(e) => event!(Level::WARN, "Failed to load fetch history; skipping anomaly detection: {}", e),
+    }
+    let mut articles = outcome.articles;
+    let classifier = classifier_from_env(Some(examples));
+    let (new_articles, unavailable) =
+        classify_new_articles(config, store, classifier.as_ref(), &interests, &mut articles).await;
+    mark_degraded(&mut reports, unavailable.iter().map(|&i| &articles[i]), Degradation::ExtractiveSummary);
+    match LocalAssetStore::from_env() {
+        Ok(Some(assets)) => {
+            let targets = articles.iter_mut().enumerate().filter(|(i, _)| new_articles.contains(i)).map(|(_, a)| a);
+            let report = cache_images(&assets, targets).await;
+            event!(
+                Level::INFO,
+                "Cached images: {} downloaded, {} reused, {} failed",
+                report.cached,
+                report.reused,
+                report.failed
+            );
+        }
+        Ok(None) => {}
+        Err(e) => event!(Level::WARN, "Failed to configure image cache: {}", e),
+    }
+    match buffer.write(store, &articles).await {
+        Ok(saved) => event!(Level::INFO, "Fetch job finished: {} articles saved", saved),
+        Err(e) => {
+            event!(
+                Level::ERROR,
+                "Failed to save articles; keeping {} in memory until the next fetch: {}",
+                buffer.len(),
+                e
+            );
+            mark_degraded(&mut reports, &articles, Degradation::BufferedInMemory);
+        }
+    }
+    let new_articles: Vec<WebArticle> = new_articles.into_iter().map(|i| articles[i].clone()).collect();
+    if notify_new_articles(config, &new_articles).await {
+        mark_degraded(&mut reports, &new_articles, Degradation::QueuedInOutbox);
+    }
+    record_run(config, history, started_at, &reports).await;
+    if let Err(e) = open_alert_issues(config, &new_articles).await {
+        event!(Level::WARN, "Failed to open alert issues: {}", e);
+    }
+    if let Err(e) = send_email_digest(config, store).await {
+        event!(Level::WARN, "Failed to send email digest: {}", e);
+    }
+    if let Err(e) = send_morning_brief(config, store).await {
+        event!(Level::WARN, "Failed to send morning brief: {}", e);
+    }
+    if let Err(e) = send_triage_reminder(config, store).await {
+        event!(Level::WARN, "Failed to send triage reminder: {}", e);
+    }
+    *cache.write().await = articles;
+    Ok(())
+}
+
+/// 巡回結果を履歴に記録し，信頼性レポートを配信する．失敗は巡回の失敗にしない．
+async fn record_run(
+    config: &ServeConfig,
+    history: &dyn FetchHistoryStore,
+    started_at: DateTime<Local>,
+    reports: &[FetchReport],
+) {
+    for report in reports.iter().filter(|r| !r.degraded.is_empty()) {
+        event!(Level::WARN, "{} fetched in degraded mode: {:?}", report.site, report.degraded);
+    }
+    if let Err(e) = history.record_run(started_at, reports).await {
+        event!(Level::WARN, "Failed to record fetch history: {}", e);
+        return;
+    }
+    // 今回の巡回を含む過去30日の推移から，セレクタの破損の兆候を警告する
+    match history.runs_between(started_at - Duration::days(30), Local::now()).await {
+        Ok(runs) => {
+            for trend in HealthMonitor::default().evaluate(&runs) {
+                for drift in trend.drift {
+                    event!(
+                        Level::WARN,
+                        "Possible selector drift on {} ({}): {}",
+                        trend.site,
+                        drift.signal,
+                        drift.message
+                    );
+                }
+            }
+        }
+        Err(e) => event!(Level::WARN, "Failed to load fetch history; skipping drift detection: {}", e),
+    }
+    if let Err(e) = send_reliability_report(config, history).await {
+        event!(Level::WARN, "Failed to send reliability report: {}", e);
+    }
+}
+
+/// 新着記事を設定された通知先へ送る．通知の失敗は巡回の失敗にせず，送れなかった記事はアウトボックスに溜めて
+/// 次の巡回で送り直す．アウトボックスに溜めた通知先があれば `true`．
+async fn notify_new_articles(config: &ServeConfig, articles: &[WebArticle]) -> bool {
+    let notifiers = match notifiers_from_env() {
+        Ok(notifiers) => notifiers,
+        Err(e) => {
+            event!(Level::WARN, "Invalid notifier configuration: {}", e);
+            return false;
+        }
+    };
+    let outbox = Outbox::in_data_dir(&config.data_dir);
+    let mut queued = false;
+    for notifier in notifiers {
+        match outbox.deliver(notifier.as_ref(), articles).await {
+            Ok(Delivery::Sent(0)) => {}
+            Ok(Delivery::Sent(count)) => event!(Level::INFO, "Notified {} articles via {}", count, notifier.name()),
+            Ok(Delivery::Queued(count)) => {
+                event!(Level::WARN, "Queued {} articles in the outbox of {}", count, notifier.name());
+                queued = true;
+            }
+            Err(e) => event!(Level::WARN, "Failed to notify via {}: {}", notifier.name(), e),
+        }
+    }
+    queued
+}
+
+/// 新着記事で発火したアラートから GitHub の Issue / Jira のチケットを作成する（作成先が未設定の場合は何もしない）
+async fn open_alert_issues(config: &ServeConfig, articles: &[WebArticle]) -> AppResult<()> {
+    let Some(sink) = IssueSink::from_env(&config.data_dir)? else {
+        return Ok(());
+    };
+    let rules = AlertRulesConfig::load_default()?.rules;
+    let alerts = evaluate_rules(&rules, articles, &SnippetOptions::default());
+    if alerts.is_empty() {
+        return Ok(());
+    }
+    let report = sink.run(&alerts, articles, Local::now()).await?;
+    event!(
+        Level::INFO,
+        "Alert issues via {}: {} created, {} linked to existing, {} failed",
+        sink.tracker.name(),
+        report.created.len(),
+        report.linked,
+        report.failed
+    );
+    Ok(())
+}
+
+/// 1週間ごとにサイトの信頼性レポートを `CLIPPER_REPORT_SINK` へ配信する
+async fn send_reliability_report(config: &ServeConfig, history: &dyn FetchHistoryStore) -> AppResult<()> {
+    let sink = ReportSink::from_env()?;
+    let marker = config.data_dir.join("reports").join(".last-reliability-report");
+    if send_weekly_report_if_due(history, &sink, &marker, Local::now()).await? {
+        event!(Level::INFO, "Weekly reliability report sent");
+    }
+    Ok(())
+}
+
+/// 日付が変わったら前日分のダイジェストをメールで配信する（`CLIPPER_SMTP_URL` が未設定の場合は何もしない）
+async fn send_email_digest(config: &ServeConfig, store: &dyn ArticleStore) -> AppResult<()> {
+    let Some(notifier) = EmailNotifier::from_env()? else {
+        return Ok(());
+    };
+    let marker = config.data_dir.join("reports").join(".last-email-digest");
+    if send_daily_digest_if_due(store, &notifier, &marker, Local::now()).await? {
+        event!(Level::INFO, "Daily email digest sent");
+    }
+    Ok(())
+}
+
+/// 毎朝，前日の記事のモーニングブリーフを Slack のデフォルトの Webhook へ投稿する（未設定の場合は何もしない）
+async fn send_morning_brief(config: &ServeConfig, store: &dyn ArticleStore) -> AppResult<()> {
+    let Some(webhook) = SlackNotifier::from_env()?
+        .and_then(|slack| slack.default_webhook().map(str::to_string))
+    else {
+        return Ok(());
+    };
+    let marker = config.data_dir.join("reports").join(".last-morning-brief");
+    if send_morning_brief_if_due(store, &webhook, &marker, Local::now()).await? {
+        event!(Level::INFO, "Morning brief posted to Slack");
+    }
+    Ok(())
+}
+
+/// 担当者の決まらないセキュリティのアラートを Slack（セキュリティのチャンネル，無ければデフォルト）で催促する
+async fn send_triage_reminder(config: &ServeConfig, store: &dyn ArticleStore) -> AppResult<()> {
+    let Some(webhook) = SlackNotifier::from_env()?
+        .and_then(|slack| slack.webhook_for(SiteCategory::Security).map(str::to_string))
+    else {
+        return Ok(());
+    };
+    let marker = config.data_dir.join("reports").join(".last-triage-reminder");
+    let reminded = send_unassigned_reminder_if_due(store, &webhook, &marker, Local::now()).await?;
+    if reminded > 0 {
+        event!(Level::INFO, "Reminded {} unassigned alerts", reminded);
+    }
+    Ok(())
+}
+
+/// 未保存の記事だけを分類し，本文から論文投稿などの締切を検出する．
+/// 保存済みの記事は前回の分類結果を引き継ぐ（upsert で上書きされないように）．
+/// 未保存だった記事の位置と，そのうち LLM に接続できずに抽出型の要約で代用した記事の位置を返す．
+async fn classify_new_articles(
+    config: &ServeConfig,
+    store: &dyn ArticleStore,
+    classifier: &dyn Classifier,
+    interests: &InterestModel,
+    articles: &mut [WebArticle],
+) -> (Vec<usize>, Vec<usize>) {
+    let mut new_articles = Vec::new();
+    let mut store_available = true;
+    for (index, article) in articles.iter_mut().enumerate() {
+        if !store_available {
+            new_articles.push(index);
+            continue;
+        }
+        match store.get_by_url(&article.article_url).await {
+            Ok(Some(stored)) => article.properties = stored.article.properties,
+            Ok(None) => new_articles.push(index),
+            // ストアに接続できない間は保存済みか判定できないため，取りこぼさないよう残りを全て新着として扱う
+            Err(e) => {
+                event!(Level::WARN, "Failed to look up saved articles; treating the rest as new: {}", e);
+                store_available = false;
+                new_articles.push(index);
+            }
+        }
+    }
+    let mut pending: Vec<WebArticle> = new_articles.iter().map(|&i| articles[i].clone()).collect();
+    let profile = SamplingProfile::from_env().unwrap_or_else(|e| {
+        event!(Level::WARN, "Invalid LLM sampling profile: {}", e);
+        SamplingProfile::default()
+    });
+    let now = Local::now();
+    // 関連度の採点にも LLM を使うため，予算で記事を絞り込む場合だけ採点する
+    let weights = if profile.llm_budget.is_some_and(|budget| budget < pending.len()) {
+        let relevance = score_articles_from_env(&config.data_dir, &pending).await.unwrap_or_else(|e| {
+            event!(Level::WARN, "Failed to score relevance: {}", e);
+            HashMap::new()
+        });
+        let mut weights = interest_weights(interests, &pending, now).await;
+        for (url, weight) in weights.iter_mut() {
+            *weight *= relevance.get(url).map_or(1.0, |&score| relevance_weight(score));
+        }
+        weights
+    } else {
+        HashMap::new()
+    };
+    let outcome = classify_with_budget(classifier, &mut pending, &profile, &weights, now).await;
+    for (&index, mut article) in new_articles.iter().zip(pending) {
+        article.properties.deadlines = detect_deadlines(&article);
+        articles[index] = article;
+    }
+    event!(
+        Level::INFO,
+        "Classified {} new articles with {} classifier ({} with extractive fallback)",
+        outcome.llm,
+        classifier.name(),
+        outcome.fallback
+    );
+    let unavailable = outcome.unavailable.iter().map(|&i| new_articles[i]).collect();
+    (new_articles, unavailable)
+}
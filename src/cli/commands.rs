@@ -0,0 +1,551 @@
+//! `list-sites` / `fetch` / `parse` / `export` / `evaluate` サブコマンドの実装．
+
+use crate::api::SiteInfo;
+use crate::cli::output::{OutputFormat, print_output};
+use crate::discovery::autodiscover::autodiscover;
+use crate::enrichment::brief::{BriefFacts, TemplateBriefWriter};
+use crate::enrichment::deadlines::{Deadline, query_upcoming_deadlines, upcoming_deadlines};
+use crate::enrichment::evaluation::{
+    CandidateSummary, EmbeddingClient, ReferenceSummary, evaluate as evaluate_summaries, load_jsonl,
+    render_evaluation_text,
+};
+use crate::enrichment::language::primary_language;
+use crate::export::feed::{FeedFormat, FeedOptions, render_feed};
+use crate::export::ics::{render_ics, upcoming_events};
+use crate::export::jsonl::render_jsonl;
+use crate::export::markdown::{DigestGrouping, DigestOptions, render_markdown, render_markdown_digest};
+use crate::export::retrospective::{Retrospective, RetrospectiveSection};
+use crate::export::sections::DigestProfile;
+use crate::export::notion::NotionExporter;
+use crate::export::obsidian::write_vault;
+use crate::export::org::{render_org_articles, render_org_digest};
+use crate::models::extraction::ExtractedArticle;
+use crate::models::report::{FailureKind, render_quality_report};
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::web_article::{self, Status, WebArticle, WebSiteInterface};
+use crate::models::{FetchSchedule, fetch_and_parse_all, fetch_articles_fairly, get_all_sites};
+use crate::shared::config::ServeConfig;
+use crate::shared::credentials::{credentials_path, decrypt_file, encrypt_file};
+use crate::shared::domain_guard::{ANY_DOMAIN, base_domain, is_allowed_host};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::progress::FetchProgress;
+use crate::storage::encryption::{STORAGE_KEY_ENV, StorageCipher};
+use crate::storage::sqlite::SqliteStore;
+use crate::monitoring::{HealthMonitor, render_trends};
+use crate::storage::{ArticleQuery, ArticleStore, FetchHistoryStore, StoredArticle, load_highlights};
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use strum::Display;
+
+/// `export` の出力形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    /// 1行1件の JSON（`schema_version` 付き）
+    Jsonl,
+    Md,
+    /// 1日分（`--since` の日付，省略時は今日）の記事をカテゴリごとにまとめた Markdown ダイジェスト（先頭に1日の概要）
+    Digest,
+    /// イベント告知のみを iCalendar として書き出す
+    Ics,
+    Org,
+    /// 記事（要約があれば要約）を1つにまとめた RSS 2.0 フィード
+    Rss,
+    /// 記事（要約があれば要約）を1つにまとめた Atom フィード
+    Atom,
+    /// Notion のデータベースのページを作成・更新する（`--file` は使わない）
+    Notion,
+    /// `--file` に指定した Obsidian の Vault に1記事1ノートで書き出す
+    Obsidian,
+}
+
+/// 日付の 00:00（ローカル時刻）
+fn start_of_day(date: NaiveDate) -> Option<DateTime<Local>> {
+    date.and_hms_opt(0, 0, 0)?.and_local_timezone(Local).earliest()
+}
+
+/// サイト名が一致する（大文字・小文字は区別しない）サイトだけを残す．見つからない名前はエラーにする．
+pub(crate) fn select_sites(
+    sites: Vec<Box<dyn WebSiteInterface>>,
+    names: &[String],
+) -> AppResult<Vec<Box<dyn WebSiteInterface>>> {
+    if names.is_empty() {
+        return Ok(sites);
+    }
+    for name in names {
+        if !sites.iter().any(|s| s.site_name().eq_ignore_ascii_case(name)) {
+            return Err(AppError::NotFound(format!("site \"{}\"", name)));
+        }
+    }
+    Ok(sites
+        .into_iter()
+        .filter(|s| names.iter().any(|n| s.site_name().eq_ignore_ascii_case(n)))
+        .collect())
+}
+
+pub async fn list_sites(format: OutputFormat) -> AppResult<()> {
+    let sites: Vec<SiteInfo> = get_all_sites()
+        .await?
+        .iter()
+        .map(|site| SiteInfo {
+            name: site.site_name(),
+            url: site.site_url().to_string(),
+            domain: site.domain(),
+        })
+        .collect();
+    print_output(format, &sites, |sites| {
+        sites
+            .iter()
+            .map(|s| format!("{}\t{}", s.name, s.url))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct SiteError {
+    pub site: String,
+    pub kind: FailureKind,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FetchOutput {
+    pub articles: Vec<WebArticle>,
+    pub errors: Vec<SiteError>,
+}
+
+pub struct FetchOptions {
+    pub sites: Vec<String>,
+    pub since: Option<NaiveDate>,
+    /// この日付より前の記事のみ
+    pub until: Option<NaiveDate>,
+    /// サイトごとの最大件数
+    pub limit: Option<usize>,
+    pub concurrency: usize,
+    /// 取得した記事をデータベースに保存する
+    pub save: bool,
+    /// 記事の本文もパースする
+    pub parse: bool,
+}
+
+pub async fn fetch(format: OutputFormat, options: FetchOptions) -> AppResult<()> {
+    let sites = select_sites(get_all_sites().await?, &options.sites)?;
+    // JSON 出力時やパイプ・cron からの実行では進捗表示を出さない
+    let progress = (format != OutputFormat::Json && std::io::stderr().is_terminal()).then(|| {
+        let names: Vec<String> = sites.iter().map(|site| site.site_name()).collect();
+        Arc::new(FetchProgress::new(&names))
+    });
+    let schedule = FetchSchedule {
+        window: web_article::FetchOptions {
+            since: options.since.and_then(start_of_day),
+            until: options.until.and_then(start_of_day),
+            limit: options.limit,
+        },
+        progress: progress.clone(),
+        ..FetchSchedule::from_env()
+    };
+    let started_at = Local::now();
+    let outcome = fetch_articles_fairly(sites, options.concurrency, schedule).await;
+    let errors = outcome
+        .errors()
+        .map(|(site, error)| SiteError {
+            site: site.to_string(),
+            kind: error.kind,
+            error: error.to_string(),
+        })
+        .collect();
+    let mut articles = outcome.articles;
+    let mut reports = outcome.reports;
+    if options.parse {
+        let report = fetch_and_parse_all(&mut articles, options.concurrency).await?;
+        tracing::info!("Parsed {} articles", report.parsed);
+        for (url, error) in &report.errors {
+            tracing::warn!("Failed to parse {}: {}", url, error);
+        }
+        report.apply_to(&mut reports);
+    }
+    if let Some(progress) = &progress {
+        progress.finish();
+    }
+    // セレクタの見直しが必要なサイトがあれば，抽出品質の低い順の一覧を標準エラー出力に出す
+    if format != OutputFormat::Json && reports.iter().any(|r| r.quality.needs_attention()) {
+        eprint!("{}", render_quality_report(&reports));
+    }
+    if options.save {
+        let config = ServeConfig::from_env()?;
+        let store = SqliteStore::open(&config.database_path)?;
+        let saved = store.upsert_many(&articles).await?;
+        tracing::info!("Saved {} articles to {:?}", saved, config.database_path);
+        // `sites health` で推移を見られるよう，巡回結果（本文の長さを含む）も履歴に残す
+        store.record_run(started_at, &reports).await?;
+    }
+
+    let output = FetchOutput { articles, errors };
+    print_output(format, &output, |output| {
+        let mut lines: Vec<String> = output
+            .articles
+            .iter()
+            .map(|a| {
+                format!(
+                    "{} [{}] {}\n    {}",
+                    a.timestamp.format("%Y-%m-%d %H:%M"),
+                    a.site.name,
+                    a.title,
+                    a.article_url
+                )
+            })
+            .collect();
+        for e in &output.errors {
+            lines.push(format!("Error: {}: {}", e.site, e.error));
+        }
+        lines.join("\n")
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParseOutput {
+    pub url: String,
+    pub site: String,
+    #[serde(flatten)]
+    pub article: ExtractedArticle,
+}
+
+/// `--site` が無ければ URL のホスト名からサイトを推定して本文をパースする
+pub async fn parse(format: OutputFormat, url: &str, site_name: Option<&str>) -> AppResult<()> {
+    let host = request::Url::parse(url)?
+        .host_str()
+        .map(str::to_string)
+        .unwrap_or_default();
+    let sites = get_all_sites().await?;
+    let mut site = match site_name {
+        Some(name) => select_sites(sites, &[name.to_string()])?.into_iter().next(),
+        None => sites.into_iter().find(|s| {
+            // ドメインを制限しない集約サイトは推定に使わない
+            let domains: Vec<String> = s.allowed_domains().into_iter().filter(|d| d != ANY_DOMAIN).collect();
+            is_allowed_host(&host, &domains)
+        }),
+    }
+    .ok_or_else(|| AppError::NotFound(format!("site for {}", url)))?;
+
+    let article = site.extract_article(url).await?;
+    let output = ParseOutput {
+        url: url.to_string(),
+        site: site.site_name(),
+        article,
+    };
+    print_output(format, &output, |output| output.article.text.clone())
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiscoverOutput {
+    pub name: String,
+    pub url: String,
+    pub feed: FeedKind,
+    /// フィードのドメインと異なる場合のトップページのドメイン
+    pub allowed_domains: Vec<String>,
+}
+
+/// サイト設定ファイルに追加する `[[feeds]]` の TOML
+fn render_feed_config(output: &DiscoverOutput) -> String {
+    let quote = |s: &str| toml::Value::String(s.to_string()).to_string();
+    let mut lines = vec![
+        "[[feeds]]".to_string(),
+        format!("name = {}", quote(&output.name)),
+        format!("url = {}", quote(&output.url)),
+        format!("feed = {}", quote(&output.feed.to_string())),
+    ];
+    if !output.allowed_domains.is_empty() {
+        let domains: Vec<String> = output.allowed_domains.iter().map(|d| quote(d)).collect();
+        lines.push(format!("allowed_domains = [{}]", domains.join(", ")));
+    }
+    lines.join("\n")
+}
+
+/// トップページの URL からフィードを探し，サイト設定ファイルに追加する `[[feeds]]` を表示する
+pub async fn discover_site(format: OutputFormat, url: &str) -> AppResult<()> {
+    let site = autodiscover(url).await?;
+    let homepage = request::Url::parse(url)?;
+    let homepage_domain = base_domain(homepage.domain().unwrap_or_default());
+    let allowed_domains = if homepage_domain == base_domain(&site.domain()) {
+        Vec::new()
+    } else {
+        vec![homepage_domain]
+    };
+    let output = DiscoverOutput {
+        name: site.site_name(),
+        url: site.site_url().to_string(),
+        feed: site.feed_kind(),
+        allowed_domains,
+    };
+    print_output(format, &output, render_feed_config)
+}
+
+/// 過去 `days` 日の巡回履歴からサイトごとの推移とセレクタの破損の兆候を表示する
+pub async fn site_health(format: OutputFormat, days: i64) -> AppResult<()> {
+    let config = ServeConfig::from_env()?;
+    let store = SqliteStore::open(&config.database_path)?;
+    let now = Local::now();
+    let runs = store.runs_between(now - Duration::days(days), now).await?;
+    let trends = HealthMonitor::default().evaluate(&runs);
+    print_output(format, &trends, |trends| render_trends(trends))
+}
+
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub site: Option<String>,
+    pub since: Option<NaiveDate>,
+    pub status: Option<Status>,
+    pub lang: Option<String>,
+    pub limit: Option<usize>,
+    /// `Org` をサイトごとにまとめたダイジェストにする
+    pub digest: bool,
+    /// 省略時は標準出力
+    pub file: Option<PathBuf>,
+}
+
+/// 保存済みの記事を指定した形式で書き出す．共有の注釈は記事と一緒に書き出す（形式が対応している場合）．
+pub async fn export(output: OutputFormat, options: ExportOptions) -> AppResult<()> {
+    if options.digest && options.format != ExportFormat::Org {
+        return Err(AppError::InternalError(format!(
+            "--digest is only supported with --format org (use --format digest for Markdown): {}",
+            options.format
+        )));
+    }
+    let config = ServeConfig::from_env()?;
+    let store = SqliteStore::open(&config.database_path)?;
+    let mut articles = store
+        .query(&ArticleQuery {
+            site: options.site,
+            since: options.since.and_then(start_of_day),
+            until: None,
+            status: options.status,
+            lang: options.lang.as_deref().and_then(primary_language),
+            limit: options.limit,
+            ..Default::default()
+        })
+        .await?;
+    load_highlights(&store, &mut articles).await?;
+    if options.format == ExportFormat::Notion {
+        let summary = NotionExporter::from_env()?.export(&articles).await?;
+        return print_output(output, &summary, |s| format!("Notion: {} created, {} updated", s.created, s.updated));
+    }
+    if options.format == ExportFormat::Obsidian {
+        let vault = options
+            .file
+            .ok_or_else(|| AppError::InternalError("--file <vault directory> is required for obsidian".into()))?;
+        let summary = write_vault(&vault, &articles)?;
+        return print_output(output, &summary, |s| {
+            format!("Obsidian: {} written, {} unchanged", s.written, s.unchanged)
+        });
+    }
+    let rendered = if options.format == ExportFormat::Digest {
+        // 振り返りのセクションと締切は，書き出す記事の範囲によらず保存済みの記事から取り出す
+        let date = options.since.unwrap_or_else(|| Local::now().date_naive());
+        let retrospective = match Retrospective::from_env()? {
+            Some(retrospective) => retrospective.query(&store, date).await?,
+            None => Vec::new(),
+        };
+        let deadlines = query_upcoming_deadlines(&store, date).await?;
+        render_digest(date, &articles, retrospective, deadlines)?
+    } else if options.digest {
+        let date = options.since.unwrap_or_else(|| Local::now().date_naive());
+        render_org_digest("News Digest", date, &articles)
+    } else {
+        render_export(options.format, &articles, options.since)?
+    };
+    match options.file {
+        Some(path) => std::fs::write(&path, rendered)
+            .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e))),
+        None => {
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+/// 参照要約と比べて要約を採点する．`candidates` が無ければ保存済みの記事の要約を使う．
+pub async fn evaluate(
+    format: OutputFormat,
+    references: &Path,
+    candidates: Option<&Path>,
+    embeddings: bool,
+) -> AppResult<()> {
+    let references: Vec<ReferenceSummary> = load_jsonl(references)?;
+    let mut summaries: HashMap<String, String> = HashMap::new();
+    match candidates {
+        Some(path) => {
+            for candidate in load_jsonl::<CandidateSummary>(path)? {
+                summaries.insert(candidate.url, candidate.summary);
+            }
+        }
+        None => {
+            let config = ServeConfig::from_env()?;
+            let store = SqliteStore::open(&config.database_path)?;
+            for reference in &references {
+                if let Some(summary) = store
+                    .get_by_url(&reference.url)
+                    .await?
+                    .and_then(|stored| stored.article.properties.summary)
+                {
+                    summaries.insert(reference.url.clone(), summary);
+                }
+            }
+        }
+    }
+    let embedder = if embeddings {
+        Some(EmbeddingClient::from_env()?.ok_or_else(|| {
+            AppError::InternalError("OPENAI_API_KEY is required for --embeddings".into())
+        })?)
+    } else {
+        None
+    };
+    let report = evaluate_summaries(&references, &summaries, embedder.as_ref()).await?;
+    print_output(format, &report, render_evaluation_text)
+}
+
+/// `date` のダイジェスト（`--format digest`）
+fn render_digest(
+    date: NaiveDate,
+    articles: &[StoredArticle],
+    retrospective: Vec<RetrospectiveSection>,
+    deadlines: Vec<Deadline>,
+) -> AppResult<String> {
+    let articles: Vec<WebArticle> = articles.iter().map(|s| s.article.clone()).collect();
+    // 書き出しはオフラインで完結させるため，ブリーフは LLM を使わず定型文で作る
+    let facts = BriefFacts::collect(date, &articles);
+    let options = DigestOptions {
+        grouping: DigestGrouping::Category,
+        sections: DigestProfile::from_env()?,
+        brief: (facts.total > 0).then(|| TemplateBriefWriter.compose(&facts)),
+        deadlines,
+        retrospective,
+        ..Default::default()
+    };
+    Ok(render_markdown_digest(date, &articles, &options))
+}
+
+/// `since` はダイジェストの対象日（省略時は今日）
+pub fn render_export(
+    format: ExportFormat,
+    articles: &[StoredArticle],
+    since: Option<NaiveDate>,
+) -> AppResult<String> {
+    Ok(match format {
+        ExportFormat::Json => serde_json::to_string_pretty(articles)? + "\n",
+        ExportFormat::Jsonl => render_jsonl(articles)?,
+        ExportFormat::Md => render_markdown("News Clipper", articles),
+        ExportFormat::Digest => {
+            let date = since.unwrap_or_else(|| Local::now().date_naive());
+            let deadlines = upcoming_deadlines(articles.iter().map(|s| &s.article), date);
+            render_digest(date, articles, Vec::new(), deadlines)?
+        }
+        ExportFormat::Ics => {
+            let articles: Vec<WebArticle> = articles.iter().map(|s| s.article.clone()).collect();
+            render_ics(&upcoming_events(&articles))
+        }
+        ExportFormat::Org => render_org_articles(articles),
+        ExportFormat::Rss | ExportFormat::Atom => {
+            let feed_format = if format == ExportFormat::Rss {
+                FeedFormat::Rss
+            } else {
+                FeedFormat::Atom
+            };
+            let articles: Vec<WebArticle> = articles.iter().map(|s| s.article.clone()).collect();
+            render_feed(feed_format, &articles, &FeedOptions::default())
+        }
+        ExportFormat::Notion => {
+            return Err(AppError::InternalError("notion export writes to the Notion API, not to a file".into()));
+        }
+        ExportFormat::Obsidian => {
+            return Err(AppError::InternalError("obsidian export writes one file per article".into()));
+        }
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct CredentialsOutput {
+    pub path: PathBuf,
+    pub encrypted: bool,
+    /// `false` の場合はファイルが既に目的の状態だった
+    pub changed: bool,
+}
+
+/// 認証情報のファイルを `CLIPPER_STORAGE_KEY` で暗号化（`encrypt` が `false` の場合は復号）する
+pub fn credentials(format: OutputFormat, encrypt: bool) -> AppResult<()> {
+    let cipher = StorageCipher::from_env()?
+        .ok_or_else(|| AppError::EncryptionError(format!("{} is not set", STORAGE_KEY_ENV)))?;
+    let path = credentials_path();
+    let changed = if encrypt {
+        encrypt_file(&path, &cipher)?
+    } else {
+        decrypt_file(&path, &cipher)?
+    };
+    let output = CredentialsOutput {
+        path,
+        encrypted: encrypt,
+        changed,
+    };
+    print_output(format, &output, |o| {
+        let state = if o.encrypted { "encrypted" } else { "decrypted" };
+        if o.changed {
+            format!("{}: {}", state, o.path.display())
+        } else {
+            format!("{}: already {}", o.path.display(), state)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_feed_config() {
+        let mut output = DiscoverOutput {
+            name: "Example \"Engineering\" Blog".to_string(),
+            url: "https://blog.example.com/feed.xml".to_string(),
+            feed: FeedKind::Atom,
+            allowed_domains: Vec::new(),
+        };
+        let lines: Vec<String> = render_feed_config(&output).lines().map(str::to_string).collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "[[feeds]]");
+        assert_eq!(lines[2], "url = \"https://blog.example.com/feed.xml\"");
+        assert_eq!(lines[3], "feed = \"atom\"");
+        output.allowed_domains = vec!["example.com".to_string()];
+        let config = render_feed_config(&output);
+        assert!(config.ends_with("allowed_domains = [\"example.com\"]"));
+        let parsed: toml::Value = toml::from_str(&config).unwrap();
+        assert_eq!(parsed["feeds"][0]["name"].as_str(), Some("Example \"Engineering\" Blog"));
+    }
+
+    #[test]
+    fn test_start_of_day() {
+        let date = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+        let start = start_of_day(date).unwrap();
+        assert_eq!(start.date_naive(), date);
+        assert_eq!(start.format("%H:%M:%S").to_string(), "00:00:00");
+    }
+
+    #[test]
+    fn test_render_export_formats() {
+        assert_eq!(render_export(ExportFormat::Json, &[], None).unwrap(), "[]\n");
+        assert_eq!(render_export(ExportFormat::Jsonl, &[], None).unwrap(), "");
+        assert!(render_export(ExportFormat::Ics, &[], None).unwrap().starts_with("BEGIN:VCALENDAR"));
+        assert!(render_export(ExportFormat::Org, &[], None).unwrap().starts_with("#+TODO:"));
+        assert!(render_export(ExportFormat::Rss, &[], None).unwrap().contains("<rss version=\"2.0\">"));
+        assert!(render_export(ExportFormat::Atom, &[], None).unwrap().contains("<feed xmlns="));
+        assert!(render_export(ExportFormat::Notion, &[], None).is_err());
+        assert!(render_export(ExportFormat::Obsidian, &[], None).is_err());
+        let date = NaiveDate::from_ymd_opt(2025, 10, 1);
+        assert!(render_export(ExportFormat::Digest, &[], date).unwrap().starts_with("# News Digest 2025-10-01"));
+    }
+}
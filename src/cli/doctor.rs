@@ -0,0 +1,322 @@
+//! `sites doctor` サブコマンドの実装．
+//!
+//! 登録されている全サイトについて，フィードの取得と記事1件のパース（フィクスチャがあればその HTML，無ければ実際のページ）を
+//! 並列に試し，OK / WARN / FAIL の表にまとめる．依存クレートの更新後やリリース前に実行することを想定している．
+//! ページ監視のスナップショットは更新しないため，診断しても次回の巡回の結果は変わらない．
+
+use crate::cli::output::{OutputFormat, print_output};
+use crate::models::fixture::with_fixture_page;
+use crate::models::metadata::has_missing_timestamp;
+use crate::models::quality::{SiteQuality, collect_metrics};
+use crate::models::report::is_blocked;
+use crate::models::sites::page_watcher::without_snapshot_updates;
+use crate::models::web_article::WebSiteInterface;
+use crate::shared::errors::{AppError, AppResult};
+use request::Url;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use strum::Display;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// サイトの診断結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Display)]
+#[serde(rename_all = "UPPERCASE")]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum HealthStatus {
+    Ok,
+    /// 取得はできたが確認が必要（記事が無い，ログインが必要，抽出品質が低いなど）
+    Warn,
+    Fail,
+}
+
+/// 1サイトの診断結果
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteHealth {
+    pub site: String,
+    pub status: HealthStatus,
+    /// フィード・一覧ページから取得した記事数
+    pub articles: usize,
+    /// パースに使った記事（フィクスチャの場合はファイルのパス）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probed: Option<String>,
+    pub reasons: Vec<String>,
+    pub duration: Duration,
+}
+
+impl SiteHealth {
+    fn new(site: String) -> Self {
+        Self {
+            site,
+            status: HealthStatus::Ok,
+            articles: 0,
+            probed: None,
+            reasons: Vec::new(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    fn warn(&mut self, reason: impl Into<String>) {
+        self.status = self.status.max(HealthStatus::Warn);
+        self.reasons.push(reason.into());
+    }
+
+    fn fail(&mut self, reason: impl Into<String>) {
+        self.status = HealthStatus::Fail;
+        self.reasons.push(reason.into());
+    }
+}
+
+/// 診断の設定
+#[derive(Debug, Clone)]
+pub struct DoctorOptions {
+    /// `<サイト名>.html` の記事ページを置いたディレクトリ．該当するファイルのあるサイトは，
+    /// 実際のページの代わりにこの HTML をサイトの `parse_article` に渡す．
+    pub fixtures: Option<PathBuf>,
+    pub concurrency: usize,
+    /// 1サイトあたりの制限時間
+    pub timeout: Duration,
+}
+
+/// フィクスチャのパス（ファイルが無ければ `None`）
+fn fixture_path(dir: &Path, site_name: &str) -> Option<PathBuf> {
+    let path = dir.join(format!("{}.html", site_name));
+    path.is_file().then_some(path)
+}
+
+/// フィードを取得し，最初の記事（またはフィクスチャ）をパースして結果を記録する
+async fn probe(site: &mut dyn WebSiteInterface, fixtures: Option<&Path>, health: &mut SiteHealth) {
+    let articles = match site.get_articles().await {
+        Ok(articles) => articles,
+        Err(e) => return health.fail(format!("feed: {}", e)),
+    };
+    health.articles = articles.len();
+    if articles.is_empty() {
+        health.warn("feed: no articles");
+    } else if articles.iter().all(has_missing_timestamp) {
+        health.warn("feed: no publish dates");
+    }
+
+    let fixture = fixtures.and_then(|dir| fixture_path(dir, &site.site_name()));
+    let (result, metrics) = match &fixture {
+        Some(path) => {
+            health.probed = Some(path.display().to_string());
+            let body = match std::fs::read_to_string(path) {
+                Ok(body) => body,
+                Err(e) => return health.fail(format!("fixture {}: {}", path.display(), e)),
+            };
+            // サイト固有のセレクタで抽出させるため，最初の記事の URL へのリクエストにフィクスチャで応答する
+            let url = match articles.first() {
+                Some(article) => Url::parse(&article.article_url),
+                None => Ok(site.site_url()),
+            };
+            let url = match url {
+                Ok(url) => url,
+                Err(e) => return health.fail(format!("article: {}", e)),
+            };
+            collect_metrics(with_fixture_page(url.clone(), body, site.parse_article(url.as_str()))).await
+        }
+        None => {
+            let Some(article) = articles.first() else {
+                return;
+            };
+            health.probed = Some(article.article_url.clone());
+            collect_metrics(site.parse_article(&article.article_url)).await
+        }
+    };
+    match result {
+        Ok((_, text)) if text.trim().is_empty() => health.fail("article: empty text"),
+        Ok(_) => {
+            let mut quality = SiteQuality::default();
+            metrics.iter().for_each(|m| quality.record(m));
+            if quality.needs_attention() {
+                health.warn(format!(
+                    "article: low extraction quality (text ratio {:.3}, boilerplate {:.3})",
+                    quality.mean_text_ratio, quality.mean_boilerplate_ratio
+                ));
+            }
+        }
+        Err(e) if is_blocked(&e) => health.warn(format!("article: {}", e)),
+        Err(e) => health.fail(format!("article: {}", e)),
+    }
+}
+
+/// サイトを並列に診断する（結果は FAIL，WARN，OK の順，同じ状態はサイト名順）
+pub async fn diagnose_sites(sites: Vec<Box<dyn WebSiteInterface>>, options: &DoctorOptions) -> Vec<SiteHealth> {
+    let semaphore = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+    for mut site in sites {
+        let semaphore = semaphore.clone();
+        let fixtures = options.fixtures.clone();
+        let timeout = options.timeout;
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("semaphore is never closed");
+            let mut health = SiteHealth::new(site.site_name());
+            let started = Instant::now();
+            // 診断で監視対象のページのスナップショットを更新すると，次回の巡回で変化を見逃すため更新しない
+            let probe = without_snapshot_updates(probe(site.as_mut(), fixtures.as_deref(), &mut health));
+            if tokio::time::timeout(timeout, probe).await.is_err() {
+                health.fail(format!("timed out after {}s", timeout.as_secs()));
+            }
+            health.duration = started.elapsed();
+            health
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(health) => results.push(health),
+            Err(e) => tracing::error!("Doctor task panicked: {}", e),
+        }
+    }
+    results.sort_by(|a, b| b.status.cmp(&a.status).then_with(|| a.site.cmp(&b.site)));
+    results
+}
+
+/// 診断結果の表（タブ区切り）と件数のまとめ
+pub fn render_health_table(results: &[SiteHealth]) -> String {
+    let mut out = String::from("site\tstatus\tarticles\tseconds\treasons\n");
+    for health in results {
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{:.1}\t{}\n",
+            health.site,
+            health.status,
+            health.articles,
+            health.duration.as_secs_f64(),
+            health.reasons.join("; ")
+        ));
+    }
+    let count = |status| results.iter().filter(|h| h.status == status).count();
+    out.push_str(&format!(
+        "\n{} OK, {} WARN, {} FAIL",
+        count(HealthStatus::Ok),
+        count(HealthStatus::Warn),
+        count(HealthStatus::Fail)
+    ));
+    out
+}
+
+/// 診断結果を表示し，FAIL のサイトがあればエラー（終了コード 1）にする
+pub async fn sites_doctor(
+    format: OutputFormat,
+    sites: Vec<Box<dyn WebSiteInterface>>,
+    options: DoctorOptions,
+) -> AppResult<()> {
+    let results = diagnose_sites(sites, &options).await;
+    print_output(format, &results, |results| render_health_table(results))?;
+    let failed = results.iter().filter(|h| h.status == HealthStatus::Fail).count();
+    if failed > 0 {
+        return Err(AppError::InternalError(format!("{} sites failed the health check", failed)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::web_article::{Cookie, Html, Text, WebArticle};
+    use crate::shared::selectors::cached_selector;
+    use crate::testing::article::ArticleBuilder;
+    use async_trait::async_trait;
+
+    struct DoctorStubSite {
+        name: &'static str,
+        /// `None` の場合はフィードの取得に失敗する
+        articles: Option<usize>,
+        /// 本文のセレクタ（`None` の場合は記事ページがログインを要求する）
+        content: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl WebSiteInterface for DoctorStubSite {
+        fn site_name(&self) -> String {
+            self.name.to_string()
+        }
+        fn site_url(&self) -> Url {
+            Url::parse("https://example.com").unwrap()
+        }
+        async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+            let Some(count) = self.articles else {
+                return Err(AppError::ScrapeError("feed is gone".into()));
+            };
+            Ok((0..count)
+                .map(|i| {
+                    ArticleBuilder::new(format!("Title {}", i))
+                        .with_site(self.site_name(), "https://example.com")
+                        .with_url(format!("https://example.com/{}", i))
+                        .build()
+                })
+                .collect())
+        }
+        async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+            let Some(content) = self.content else {
+                return Err(AppError::LoginRequired);
+            };
+            let body = self.request(url, "").await?.text().await?;
+            let document = scraper::Html::parse_document(&body);
+            match document.select(cached_selector(content)?).next() {
+                Some(elem) => {
+                    let html = elem.html();
+                    let text = elem.text().collect::<String>();
+                    Ok((html, text))
+                }
+                None => Err(AppError::SelectorNotFound {
+                    site: self.site_name(),
+                    selector: content.to_string(),
+                }),
+            }
+        }
+        async fn login(&mut self) -> AppResult<Cookie> {
+            Ok(Cookie::default())
+        }
+        fn domain(&self) -> String {
+            "example.com".into()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_sites_statuses() {
+        let dir = std::env::temp_dir().join(format!("clipper-doctor-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paragraph = "<p>Fixture body text that is long enough to be taken as the article content.</p>";
+        let page = format!("<html><body><article>{}</article></body></html>", paragraph.repeat(4));
+        std::fs::write(dir.join("Fixture.html"), &page).unwrap();
+        // 本文は十分にあるが，サイトのセレクタが一致しない
+        std::fs::write(dir.join("Mismatch.html"), &page).unwrap();
+
+        let sites: Vec<Box<dyn WebSiteInterface>> = vec![
+            Box::new(DoctorStubSite { name: "Fixture", articles: Some(2), content: Some("article") }),
+            Box::new(DoctorStubSite { name: "Mismatch", articles: Some(1), content: Some("div.entry-content") }),
+            Box::new(DoctorStubSite { name: "Blocked", articles: Some(1), content: None }),
+            Box::new(DoctorStubSite { name: "Broken", articles: None, content: None }),
+        ];
+        let options = DoctorOptions {
+            fixtures: Some(dir.clone()),
+            concurrency: 2,
+            timeout: Duration::from_secs(10),
+        };
+        let results = diagnose_sites(sites, &options).await;
+        let statuses: Vec<(&str, HealthStatus)> = results.iter().map(|h| (h.site.as_str(), h.status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                ("Broken", HealthStatus::Fail),
+                ("Mismatch", HealthStatus::Fail),
+                ("Blocked", HealthStatus::Warn),
+                ("Fixture", HealthStatus::Ok)
+            ]
+        );
+        assert!(results[0].reasons[0].starts_with("feed: "));
+        assert!(results[1].reasons[0].contains("div.entry-content"));
+        assert_eq!(results[1].probed.as_deref(), Some(dir.join("Mismatch.html").to_str().unwrap()));
+        assert_eq!(results[3].articles, 2);
+
+        let table = render_health_table(&results);
+        assert!(table.lines().nth(1).unwrap().starts_with("Broken\tFAIL\t0\t"));
+        assert!(table.ends_with("1 OK, 1 WARN, 2 FAIL"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,402 @@
+pub mod all_in_one;
+pub mod commands;
+pub mod doctor;
+pub mod output;
+pub mod service;
+
+use crate::models::web_article::Status;
+use crate::shared::config::ServeConfig;
+use crate::shared::errors::AppResult;
+use chrono::NaiveDate;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use commands::{ExportFormat, ExportOptions, FetchOptions};
+use doctor::DoctorOptions;
+pub use output::OutputFormat;
+use output::print_output;
+use service::ServiceManager;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const BIN_NAME: &str = "clipper";
+
+#[derive(Debug, Parser)]
+#[command(name = BIN_NAME, version, about = "News clipper for tech blogs and news sites")]
+pub struct Cli {
+    /// 出力形式（text / json）
+    #[arg(long, short, value_enum, global = true, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// ログレベル（OFF / ERROR / WARN / INFO / DEBUG / TRACE）
+    #[arg(long, global = true, default_value = "WARN", env = "CLIPPER_LOG_LEVEL")]
+    pub log_level: String,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// 有効なサイト一覧を表示する
+    ListSites,
+    /// サイトの管理
+    Sites {
+        #[command(subcommand)]
+        command: SitesCommand,
+    },
+    /// 記事一覧を取得して表示する
+    Fetch {
+        /// 対象のサイト名（複数指定可，省略時は全サイト）
+        #[arg(long = "site")]
+        sites: Vec<String>,
+        /// この日付（YYYY-MM-DD）以降の記事のみ表示する
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// この日付（YYYY-MM-DD）より前の記事のみ表示する
+        #[arg(long)]
+        until: Option<NaiveDate>,
+        /// サイトごとに新しい順で最大この件数まで表示する
+        #[arg(long)]
+        limit: Option<usize>,
+        /// 同時取得数
+        #[arg(long, default_value_t = 8)]
+        concurrency: usize,
+        /// 取得した記事をデータベースに保存する
+        #[arg(long)]
+        save: bool,
+        /// 記事ページを取得して本文（`html` / `text`）も埋める（サイトごとに `--concurrency` 件まで並列）
+        #[arg(long)]
+        parse: bool,
+    },
+    /// 記事の本文を取得して表示する
+    Parse {
+        url: String,
+        /// サイト名（省略時は URL のドメインから推定）
+        #[arg(long)]
+        site: Option<String>,
+    },
+    /// 保存済みの記事を書き出す
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        #[arg(long)]
+        site: Option<String>,
+        /// この日付（YYYY-MM-DD）以降の記事のみ書き出す
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// new / archived
+        #[arg(long)]
+        status: Option<Status>,
+        /// 記事の言語（ja / en など）
+        #[arg(long)]
+        lang: Option<String>,
+        #[arg(long)]
+        limit: Option<usize>,
+        /// `--format org` で，1日分（`--since` の日付，省略時は今日）の記事をサイトごとにまとめたダイジェストにする
+        #[arg(long)]
+        digest: bool,
+        /// 書き出し先のファイル（省略時は標準出力）
+        #[arg(long, short = 'f')]
+        file: Option<PathBuf>,
+    },
+    /// 要約を人手の参照要約と比べて採点する（ROUGE / 埋め込みの類似度）
+    Evaluate {
+        /// 参照要約の JSONL（`{"url": ..., "reference": ...}`）
+        #[arg(long)]
+        references: PathBuf,
+        /// 評価する要約の JSONL（`{"url": ..., "summary": ...}`．省略時は保存済みの要約）
+        #[arg(long)]
+        candidates: Option<PathBuf>,
+        /// OpenAI の埋め込みによるコサイン類似度も求める（`OPENAI_API_KEY` が必要）
+        #[arg(long)]
+        embeddings: bool,
+    },
+    /// API サーバのみを起動する（巡回は `POST /refresh` で行う）
+    Serve,
+    /// スケジューラ・ワーカー・API サーバを1プロセスで起動する（設定は環境変数から読み込む）
+    AllInOne,
+    /// シェル補完スクリプトを標準出力に書き出す
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// all-in-one モードを常駐させる systemd ユニット / launchd plist を書き出す
+    InstallService {
+        /// サービスマネージャ（省略時は OS から自動判定）
+        #[arg(long, value_enum)]
+        manager: Option<ServiceManager>,
+        /// サービスに読み込ませる環境変数ファイル
+        #[arg(long)]
+        env_file: Option<PathBuf>,
+        /// ファイルを書き込まずに内容を表示する
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 認証情報のファイル（`credentials.toml`）を `CLIPPER_STORAGE_KEY` で暗号化・復号する
+    Credentials {
+        #[command(subcommand)]
+        command: CredentialsCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CredentialsCommand {
+    /// ファイル全体を暗号化する
+    Encrypt,
+    /// 編集するためにファイルを平文に戻す
+    Decrypt,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SitesCommand {
+    /// 全サイトのフィードの取得と記事1件のパースを並列に試し，OK / WARN / FAIL の表を表示する（FAIL があれば終了コード 1）
+    Doctor {
+        /// 対象のサイト名（複数指定可，省略時は全サイト）
+        #[arg(long = "site")]
+        sites: Vec<String>,
+        /// `<サイト名>.html` の記事ページを置いたディレクトリ（ファイルの無いサイトは実際の記事ページをパースする）
+        #[arg(long)]
+        fixtures: Option<PathBuf>,
+        /// 同時に診断するサイト数
+        #[arg(long, default_value_t = 8)]
+        concurrency: usize,
+        /// 1サイトあたりの制限時間（秒）
+        #[arg(long, default_value_t = 120)]
+        timeout: u64,
+    },
+    /// トップページの URL からフィードを探し，サイト設定ファイルに追加する `[[feeds]]` を表示する
+    Discover {
+        /// ブログ・ニュースサイトのトップページの URL
+        url: String,
+    },
+    /// 巡回履歴からサイトごとの成功率・記事数・本文の長さの推移を集計し，セレクタの破損の兆候を表示する
+    Health {
+        /// 集計する日数
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+    },
+}
+
+/// パース済みの CLI 引数に従ってサブコマンドを実行する
+pub async fn run(cli: Cli) -> AppResult<()> {
+    match cli.command {
+        Commands::ListSites => commands::list_sites(cli.output).await,
+        Commands::Sites {
+            command:
+                SitesCommand::Doctor {
+                    sites,
+                    fixtures,
+                    concurrency,
+                    timeout,
+                },
+        } => {
+            let sites = commands::select_sites(crate::models::get_all_sites().await?, &sites)?;
+            let options = DoctorOptions {
+                fixtures,
+                concurrency,
+                timeout: Duration::from_secs(timeout),
+            };
+            doctor::sites_doctor(cli.output, sites, options).await
+        }
+        Commands::Sites {
+            command: SitesCommand::Discover { url },
+        } => commands::discover_site(cli.output, &url).await,
+        Commands::Sites {
+            command: SitesCommand::Health { days },
+        } => commands::site_health(cli.output, days).await,
+        Commands::Fetch {
+            sites,
+            since,
+            until,
+            limit,
+            concurrency,
+            save,
+            parse,
+        } => {
+            let options = FetchOptions {
+                sites,
+                since,
+                until,
+                limit,
+                concurrency,
+                save,
+                parse,
+            };
+            commands::fetch(cli.output, options).await
+        }
+        Commands::Parse { url, site } => commands::parse(cli.output, &url, site.as_deref()).await,
+        Commands::Export {
+            format,
+            site,
+            since,
+            status,
+            lang,
+            limit,
+            digest,
+            file,
+        } => {
+            let options = ExportOptions {
+                format,
+                site,
+                since,
+                status,
+                lang,
+                limit,
+                digest,
+                file,
+            };
+            commands::export(cli.output, options).await
+        }
+        Commands::Evaluate {
+            references,
+            candidates,
+            embeddings,
+        } => commands::evaluate(cli.output, &references, candidates.as_deref(), embeddings).await,
+        Commands::Serve => all_in_one::run_serve(ServeConfig::from_env()?).await,
+        Commands::AllInOne => all_in_one::run_all_in_one(ServeConfig::from_env()?).await,
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), BIN_NAME, &mut std::io::stdout());
+            Ok(())
+        }
+        Commands::InstallService {
+            manager,
+            env_file,
+            dry_run,
+        } => {
+            let manager = match manager {
+                Some(m) => m,
+                None => ServiceManager::detect()?,
+            };
+            let installed = service::install_service(manager, env_file.as_deref(), dry_run)?;
+            print_output(cli.output, &installed, |s| {
+                if s.written {
+                    format!("Wrote {}\nEnable it with: {}", s.path.display(), s.activation)
+                } else {
+                    format!("# {}\n{}", s.path.display(), s.contents)
+                }
+            })
+        }
+        Commands::Credentials { command } => {
+            commands::credentials(cli.output, matches!(command, CredentialsCommand::Encrypt))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_credentials_arguments() {
+        let cli = Cli::try_parse_from([BIN_NAME, "credentials", "encrypt"]).unwrap();
+        assert!(matches!(cli.command, Commands::Credentials { command: CredentialsCommand::Encrypt }));
+    }
+
+    #[test]
+    fn test_output_flag_is_global() {
+        let cli = Cli::try_parse_from([BIN_NAME, "completions", "zsh", "--output", "json"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(matches!(cli.command, Commands::Completions { shell: Shell::Zsh }));
+    }
+
+    #[test]
+    fn test_fetch_and_export_arguments() {
+        let cli = Cli::try_parse_from([
+            BIN_NAME, "fetch", "--site", "Gigazine", "--site", "JPCert", "--since", "2025-10-01",
+        ])
+        .unwrap();
+        let Commands::Fetch { sites, since, .. } = cli.command else {
+            panic!("expected fetch");
+        };
+        assert_eq!(sites, vec!["Gigazine", "JPCert"]);
+        assert_eq!(since, NaiveDate::from_ymd_opt(2025, 10, 1));
+
+        let cli = Cli::try_parse_from([BIN_NAME, "export", "--format", "md", "--status", "archived"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Export {
+                format: ExportFormat::Md,
+                status: Some(Status::Archived),
+                ..
+            }
+        ));
+        assert!(Cli::try_parse_from([BIN_NAME, "export", "--format", "pdf"]).is_err());
+        let cli = Cli::try_parse_from([BIN_NAME, "export", "--format", "org", "--digest"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Export {
+                format: ExportFormat::Org,
+                digest: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_sites_doctor_arguments() {
+        let cli = Cli::try_parse_from([BIN_NAME, "sites", "doctor", "--fixtures", "fixtures", "--timeout", "30"])
+            .unwrap();
+        let Commands::Sites {
+            command:
+                SitesCommand::Doctor {
+                    sites,
+                    fixtures,
+                    concurrency,
+                    timeout,
+                },
+        } = cli.command
+        else {
+            panic!("expected sites doctor");
+        };
+        assert!(sites.is_empty());
+        assert_eq!(fixtures, Some(PathBuf::from("fixtures")));
+        assert_eq!((concurrency, timeout), (8, 30));
+    }
+
+    #[test]
+    fn test_sites_discover_arguments() {
+        let cli = Cli::try_parse_from([BIN_NAME, "sites", "discover", "https://blog.example.com/"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Sites {
+                command: SitesCommand::Discover { url }
+            } if url == "https://blog.example.com/"
+        ));
+        assert!(Cli::try_parse_from([BIN_NAME, "sites", "discover"]).is_err());
+    }
+
+    #[test]
+    fn test_sites_health_arguments() {
+        let cli = Cli::try_parse_from([BIN_NAME, "sites", "health"]).unwrap();
+        assert!(matches!(cli.command, Commands::Sites { command: SitesCommand::Health { days: 30 } }));
+        let cli = Cli::try_parse_from([BIN_NAME, "sites", "health", "--days", "7"]).unwrap();
+        assert!(matches!(cli.command, Commands::Sites { command: SitesCommand::Health { days: 7 } }));
+    }
+
+    #[test]
+    fn test_evaluate_arguments() {
+        let cli = Cli::try_parse_from([BIN_NAME, "evaluate", "--references", "refs.jsonl", "--embeddings"]).unwrap();
+        let Commands::Evaluate {
+            references,
+            candidates,
+            embeddings,
+        } = cli.command
+        else {
+            panic!("expected evaluate");
+        };
+        assert_eq!(references, PathBuf::from("refs.jsonl"));
+        assert!(candidates.is_none() && embeddings);
+        assert!(Cli::try_parse_from([BIN_NAME, "evaluate"]).is_err());
+    }
+
+    #[test]
+    fn test_all_in_one_subcommand_name() {
+        let cli = Cli::try_parse_from([BIN_NAME, "all-in-one"]).unwrap();
+        assert!(matches!(cli.command, Commands::AllInOne));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::shared::errors::AppResult;
+use clap::ValueEnum;
+use serde::Serialize;
+use strum::Display;
+
+/// CLI の出力形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum OutputFormat {
+    /// 人間向けのテキスト出力
+    #[default]
+    Text,
+    /// スクリプト向けの JSON 出力（1回の実行につき1つの JSON ドキュメント）
+    Json,
+}
+
+/// 出力形式に応じて値を標準出力へ書き出す．
+/// `Text` の場合は `render_text` の結果を，`Json` の場合は `value` をシリアライズして出力する．
+pub fn print_output<T, F>(format: OutputFormat, value: &T, render_text: F) -> AppResult<()>
+where
+    T: Serialize,
+    F: FnOnce(&T) -> String,
+{
+    println!("{}", render_output(format, value, render_text)?);
+    Ok(())
+}
+
+pub fn render_output<T, F>(format: OutputFormat, value: &T, render_text: F) -> AppResult<String>
+where
+    T: Serialize,
+    F: FnOnce(&T) -> String,
+{
+    match format {
+        OutputFormat::Text => Ok(render_text(value)),
+        OutputFormat::Json => Ok(serde_json::to_string(value)?),
+    }
+}
+
+/// エラーを出力形式に応じて整形する．JSON の場合は `{"error": "..."}` を返す．
+pub fn render_error(format: OutputFormat, message: &str) -> String {
+    match format {
+        OutputFormat::Text => format!("Error: {}", message),
+        OutputFormat::Json => serde_json::json!({ "error": message }).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Item {
+        name: String,
+        count: usize,
+    }
+
+    #[test]
+    fn test_render_output_text_and_json() {
+        let item = Item {
+            name: "Gigazine".to_string(),
+            count: 3,
+        };
+        let text = render_output(OutputFormat::Text, &item, |i| format!("{}: {}", i.name, i.count)).unwrap();
+        assert_eq!(text, "Gigazine: 3");
+
+        let json = render_output(OutputFormat::Json, &item, |_| unreachable!()).unwrap();
+        assert_eq!(json, r#"{"name":"Gigazine","count":3}"#);
+    }
+
+    #[test]
+    fn test_render_error_json_is_parseable() {
+        let json = render_error(OutputFormat::Json, "site \"x\" not found");
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["error"], "site \"x\" not found");
+    }
+}
@@ -0,0 +1,228 @@
+use crate::cli::BIN_NAME;
+use crate::export::feed::escape_xml;
+use crate::shared::config::ENV_FILE_VAR;
+use crate::shared::errors::{AppError, AppResult};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use strum::Display;
+
+const LAUNCHD_LABEL: &str = "com.akitenkrad.clipper";
+
+/// サービス定義ファイルの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Display, Serialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceManager {
+    Systemd,
+    Launchd,
+}
+
+impl ServiceManager {
+    /// 実行中の OS に対応するサービスマネージャを返す
+    pub fn detect() -> AppResult<Self> {
+        if cfg!(target_os = "macos") {
+            Ok(ServiceManager::Launchd)
+        } else if cfg!(target_os = "linux") {
+            Ok(ServiceManager::Systemd)
+        } else {
+            Err(AppError::InternalError(
+                "install-service is only supported on Linux (systemd) and macOS (launchd)".into(),
+            ))
+        }
+    }
+
+    /// ユーザー単位のサービス定義ファイルの配置先
+    pub fn unit_path(&self) -> AppResult<PathBuf> {
+        let home = dirs::home_dir()
+            .ok_or_else(|| AppError::InternalError("home directory not available".into()))?;
+        Ok(match self {
+            ServiceManager::Systemd => home
+                .join(".config/systemd/user")
+                .join(format!("{}.service", BIN_NAME)),
+            ServiceManager::Launchd => home
+                .join("Library/LaunchAgents")
+                .join(format!("{}.plist", LAUNCHD_LABEL)),
+        })
+    }
+
+    pub fn render(&self, exe: &Path, env_file: Option<&Path>) -> String {
+        match self {
+            ServiceManager::Systemd => render_systemd_unit(exe, env_file),
+            ServiceManager::Launchd => render_launchd_plist(exe, env_file),
+        }
+    }
+
+    /// サービスを有効化するために利用者が実行するコマンド
+    pub fn activation_hint(&self, unit_path: &Path) -> String {
+        match self {
+            ServiceManager::Systemd => format!(
+                "systemctl --user daemon-reload && systemctl --user enable --now {}.service",
+                BIN_NAME
+            ),
+            ServiceManager::Launchd => format!("launchctl load -w {}", unit_path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstalledService {
+    pub manager: ServiceManager,
+    pub path: PathBuf,
+    pub written: bool,
+    pub activation: String,
+    pub contents: String,
+}
+
+/// systemd のユーザーユニットを生成する．`ExecReload` で SIGHUP を送り，`CLIPPER_ENV_FILE` で渡した
+/// 環境変数ファイルを再読み込みさせる．
+pub fn render_systemd_unit(exe: &Path, env_file: Option<&Path>) -> String {
+    let env_line = env_file
+        .map(|p| {
+            format!(
+                "Environment=\"{}={}\"\nEnvironmentFile=-{}\n",
+                ENV_FILE_VAR,
+                p.display(),
+                p.display()
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        "[Unit]\n\
+         Description=news-clipper scheduler\n\
+         After=network-online.target\n\
+         Wants=network-online.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={exe} all-in-one\n\
+         ExecReload=/bin/kill -HUP $MAINPID\n\
+         {env_line}\
+         Restart=on-failure\n\
+         RestartSec=30\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        exe = exe.display(),
+        env_line = env_line,
+    )
+}
+
+/// launchd の LaunchAgent 定義を生成する．launchd は環境変数ファイルを読まないため，
+/// `EnvironmentVariables` の `CLIPPER_ENV_FILE` でファイルのパスを渡し，起動時と SIGHUP 時に読み込ませる．
+pub fn render_launchd_plist(exe: &Path, env_file: Option<&Path>) -> String {
+    let env_vars = env_file
+        .map(|p| {
+            let path = escape_xml(&p.display().to_string());
+            let working_dir = p
+                .parent()
+                .map(|dir| {
+                    format!(
+                        "    <key>WorkingDirectory</key>\n    <string>{}</string>\n",
+                        escape_xml(&dir.display().to_string())
+                    )
+                })
+                .unwrap_or_default();
+            format!(
+                "    <key>EnvironmentVariables</key>\n    <dict>\n        <key>{}</key>\n        \
+                 <string>{}</string>\n    </dict>\n{}",
+                ENV_FILE_VAR, path, working_dir
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+        <string>all-in-one</string>
+    </array>
+{env_vars}    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+    <key>StandardOutPath</key>
+    <string>/tmp/{bin}.out.log</string>
+    <key>StandardErrorPath</key>
+    <string>/tmp/{bin}.err.log</string>
+</dict>
+</plist>
+"#,
+        label = LAUNCHD_LABEL,
+        exe = escape_xml(&exe.display().to_string()),
+        env_vars = env_vars,
+        bin = BIN_NAME,
+    )
+}
+
+/// サービス定義ファイルを書き出す．`dry_run` の場合は書き込まずに内容だけを返す．
+pub fn install_service(
+    manager: ServiceManager,
+    env_file: Option<&Path>,
+    dry_run: bool,
+) -> AppResult<InstalledService> {
+    let exe = std::env::current_exe()
+        .map_err(|e| AppError::InternalError(format!("Failed to resolve executable: {}", e)))?;
+    let path = manager.unit_path()?;
+    let contents = manager.render(&exe, env_file);
+
+    if !dry_run {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                AppError::InternalError(format!("Failed to create {:?}: {}", parent, e))
+            })?;
+        }
+        std::fs::write(&path, &contents)
+            .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e)))?;
+    }
+
+    Ok(InstalledService {
+        manager,
+        activation: manager.activation_hint(&path),
+        path,
+        written: !dry_run,
+        contents,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_systemd_unit() {
+        let unit = render_systemd_unit(Path::new("/usr/local/bin/clipper"), Some(Path::new("/etc/clipper.env")));
+        assert!(unit.contains("ExecStart=/usr/local/bin/clipper all-in-one"));
+        assert!(unit.contains("ExecReload=/bin/kill -HUP $MAINPID"));
+        assert!(unit.contains("EnvironmentFile=-/etc/clipper.env"));
+        assert!(unit.contains("Environment=\"CLIPPER_ENV_FILE=/etc/clipper.env\""));
+    }
+
+    #[test]
+    fn test_render_launchd_plist() {
+        let plist = render_launchd_plist(Path::new("/opt/bin/clipper"), None);
+        assert!(plist.contains("<string>com.akitenkrad.clipper</string>"));
+        assert!(plist.contains("<string>/opt/bin/clipper</string>"));
+        assert!(!plist.contains("WorkingDirectory"));
+        assert!(!plist.contains("EnvironmentVariables"));
+    }
+
+    #[test]
+    fn test_render_launchd_plist_passes_env_file_and_escapes_paths() {
+        let plist = render_launchd_plist(
+            Path::new("/Users/a&b/bin/clipper"),
+            Some(Path::new("/Users/a&b/<clipper>/clipper.env")),
+        );
+        assert!(plist.contains("<string>/Users/a&amp;b/bin/clipper</string>"));
+        assert!(plist.contains("<key>EnvironmentVariables</key>"));
+        assert!(plist.contains("<key>CLIPPER_ENV_FILE</key>"));
+        assert!(plist.contains("<string>/Users/a&amp;b/&lt;clipper&gt;/clipper.env</string>"));
+        assert!(plist.contains("<string>/Users/a&amp;b/&lt;clipper&gt;</string>"));
+        assert!(!plist.contains("a&b"));
+    }
+}
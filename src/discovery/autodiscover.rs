@@ -0,0 +1,132 @@
+//! トップページの URL だけからフィードを探す（RSS / Atom のオートディスカバリー）．
+//!
+//! `<link rel="alternate" type="application/rss+xml">` など（`atom+xml`，`rdf+xml` を含む）のうち最初のものを使い，
+//! サイト名は `og:site_name`，無ければ `<title>`，それも無ければホスト名にする．
+//! フィードが別のドメイン（フィード配信サービスなど）にある場合も，トップページのドメインの記事を取得できるようにする．
+
+use crate::models::metadata::PageMetadata;
+use crate::models::sites::generic_feed::{FeedKind, GenericFeedSite};
+use crate::models::web_article::WebSiteInterface;
+use crate::shared::domain_guard::base_domain;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::selectors::selector;
+use request::Url;
+
+/// トップページで見つかったフィード
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredFeed {
+    pub url: Url,
+    pub kind: FeedKind,
+    /// `<link>` の `title`
+    pub title: Option<String>,
+}
+
+/// `<link>` の `type` に対応するフィードの形式
+fn feed_kind(mime: &str) -> Option<FeedKind> {
+    match mime.trim().to_ascii_lowercase().as_str() {
+        "application/rss+xml" => Some(FeedKind::Rss2),
+        "application/atom+xml" => Some(FeedKind::Atom),
+        "application/rdf+xml" => Some(FeedKind::Rss1),
+        _ => None,
+    }
+}
+
+/// ページの `<link rel="alternate">` のフィードを出現順に返す（相対 URL は `base` で解決する）
+pub fn discover_feeds(html: &str, base: &Url) -> Vec<DiscoveredFeed> {
+    let doc = scraper::Html::parse_document(html);
+    doc.select(selector!("link[rel][type][href]"))
+        .filter(|link| {
+            let rel = link.value().attr("rel").unwrap_or_default();
+            rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("alternate"))
+        })
+        .filter_map(|link| {
+            let kind = feed_kind(link.value().attr("type")?)?;
+            let url = base.join(link.value().attr("href")?.trim()).ok()?;
+            let title = link
+                .value()
+                .attr("title")
+                .map(|title| title.trim().to_string())
+                .filter(|title| !title.is_empty());
+            Some(DiscoveredFeed { url, kind, title })
+        })
+        .collect()
+}
+
+/// サイト名（`og:site_name`，`<title>`，ホスト名の順）
+fn site_name(html: &str, url: &Url) -> String {
+    let doc = scraper::Html::parse_document(html);
+    PageMetadata::from_document(&doc)
+        .site_name
+        .or_else(|| {
+            doc.select(selector!("head title"))
+                .next()
+                .map(|title| title.text().collect::<String>().trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| url.host_str().unwrap_or_default().to_string())
+}
+
+/// トップページとそこで見つけたフィードからサイトを組み立てる
+pub fn build_site(html: &str, url: &Url) -> AppResult<GenericFeedSite> {
+    let feed = discover_feeds(html, url)
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::NotFound(format!("feed linked from {}", url)))?;
+    let domain = url
+        .domain()
+        .ok_or_else(|| AppError::InternalError(format!("Homepage URL must have a domain: {}", url)))?;
+    Ok(GenericFeedSite::new(&site_name(html, url), feed.url.as_str(), feed.kind)?
+        .with_allowed_domains(vec![base_domain(domain)]))
+}
+
+/// `url` のトップページを取得し，リンクされたフィードのサイトを組み立てる
+pub async fn autodiscover(url: &str) -> AppResult<GenericFeedSite> {
+    let url = Url::parse(url)?;
+    let homepage = GenericFeedSite::new(url.host_str().unwrap_or_default(), url.as_str(), FeedKind::default())?;
+    let html = homepage.request_text(url.as_str(), "").await?;
+    build_site(&html, &url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOMEPAGE: &str = r#"<!DOCTYPE html>
+<html><head>
+<title>Example Engineering Blog | Example Inc.</title>
+<link rel="stylesheet" type="text/css" href="/style.css">
+<link rel="alternate" type="application/json" href="/feed.json">
+<link rel="alternate" type="application/rss+xml" title="Example RSS" href="/feed.xml">
+<link rel="alternate" type="application/atom+xml" href="https://feeds.example.net/example/atom">
+</head><body></body></html>"#;
+
+    #[test]
+    fn test_discover_feeds() {
+        let base = Url::parse("https://blog.example.com/").unwrap();
+        let feeds = discover_feeds(HOMEPAGE, &base);
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0].url.as_str(), "https://blog.example.com/feed.xml");
+        assert_eq!(feeds[0].kind, FeedKind::Rss2);
+        assert_eq!(feeds[0].title.as_deref(), Some("Example RSS"));
+        assert_eq!(feeds[1].kind, FeedKind::Atom);
+        assert!(discover_feeds("<html><head></head></html>", &base).is_empty());
+    }
+
+    #[test]
+    fn test_build_site() {
+        let url = Url::parse("https://www.example.com/blog/").unwrap();
+        let site = build_site(HOMEPAGE, &url).unwrap();
+        assert_eq!(site.site_name(), "Example Engineering Blog | Example Inc.");
+        assert_eq!(site.site_url().as_str(), "https://www.example.com/feed.xml");
+        assert_eq!(site.feed_kind(), FeedKind::Rss2);
+
+        let og = r#"<html><head><meta property="og:site_name" content="Example"><title>Top</title>
+<link rel="alternate" type="application/atom+xml" href="https://feeds.example.net/example/atom"></head></html>"#;
+        let site = build_site(og, &url).unwrap();
+        assert_eq!(site.site_name(), "Example");
+        let article = Url::parse("https://www.example.com/blog/2025/10/post").unwrap();
+        assert!(site.ensure_allowed_url(&article).is_ok());
+
+        assert!(matches!(build_site("<html></html>", &url), Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,4 @@
+//! フィードの URL が分からないサイト・フィードを提供していないサイトの記事一覧を探す．
+
+pub mod autodiscover;
+pub mod sitemap;
@@ -0,0 +1,256 @@
+//! サイトマップ（`sitemap.xml`，Google ニュースサイトマップ）から記事一覧を組み立てる．
+//!
+//! `<url>` の `<lastmod>`（ニュースサイトマップでは `<news:publication_date>`）が取得範囲（`FetchOptions`）に
+//! 収まるものを記事にする．日付の無い URL は投稿時刻が分からないため使わない．
+//! タイトルは `<news:title>`，無ければ URL の末尾のスラッグから作る．
+//! サイトマップインデックスの場合は，範囲内に更新された子のサイトマップを新しい順に [`MAX_CHILD_SITEMAPS`] 件まで読む．
+
+use crate::models::ordering::newest_first;
+use crate::models::web_article::{FetchOptions, WebArticle, WebSiteInterface};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::regexes;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeZone};
+use request::Url;
+
+/// サイトマップインデックスから読む子のサイトマップの最大数
+pub const MAX_CHILD_SITEMAPS: usize = 5;
+/// 件数の指定が無い場合に返す記事数の上限（サイトマップは過去の全記事を含むことが多い）
+pub const DEFAULT_LIMIT: usize = 50;
+
+/// サイトマップの `<url>` / `<sitemap>` の1件
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapEntry {
+    pub url: String,
+    /// `<news:publication_date>`，無ければ `<lastmod>`
+    pub date: Option<DateTime<Local>>,
+    /// `<news:title>`
+    pub title: Option<String>,
+}
+
+/// サイトマップの内容
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// 記事の URL の一覧（`<urlset>`）
+    UrlSet(Vec<SitemapEntry>),
+    /// 子のサイトマップの一覧（`<sitemapindex>`）
+    Index(Vec<SitemapEntry>),
+}
+
+/// W3C Datetime（`2025-10-14`，`2025-10-14T09:00:00+09:00` など）の日時．日付のみの場合はその日の 0 時とする．
+fn parse_w3c_date(value: &str) -> Option<DateTime<Local>> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::<FixedOffset>::parse_from_rfc3339(value) {
+        return Some(date.into());
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn parse_entry(element: &str) -> Option<SitemapEntry> {
+    let url = regexes::sitemap_loc().captures(element)?.name("url")?.as_str();
+    let date = |re: &regex::Regex| {
+        re.captures(element)
+            .and_then(|c| c.name("date"))
+            .and_then(|date| parse_w3c_date(date.as_str()))
+    };
+    let title = regexes::news_title()
+        .captures(element)
+        .and_then(|c| c.name("title"))
+        .map(|title| unescape_xml(title.as_str().trim()))
+        .filter(|title| !title.is_empty());
+    Some(SitemapEntry {
+        url: unescape_xml(url),
+        date: date(regexes::news_publication_date()).or_else(|| date(regexes::sitemap_lastmod())),
+        title,
+    })
+}
+
+/// サイトマップの XML を読む
+pub fn parse_sitemap(xml: &str) -> AppResult<Sitemap> {
+    if xml.contains("<sitemapindex") {
+        let children = regexes::sitemap_child().find_iter(xml).filter_map(|m| parse_entry(m.as_str()));
+        return Ok(Sitemap::Index(children.collect()));
+    }
+    if xml.contains("<urlset") {
+        let entries = regexes::sitemap_url().find_iter(xml).filter_map(|m| parse_entry(m.as_str()));
+        return Ok(Sitemap::UrlSet(entries.collect()));
+    }
+    Err(AppError::ScrapeError("Not a sitemap: missing <urlset> or <sitemapindex>".into()))
+}
+
+/// URL の末尾のスラッグから作るタイトル（`/blog/hello-world.html` → `hello world`）
+fn title_from_url(url: &Url) -> Option<String> {
+    let slug = url.path_segments()?.rfind(|s| !s.is_empty())?;
+    let slug = slug.rsplit_once('.').map_or(slug, |(stem, _)| stem);
+    let title = slug.replace(['-', '_'], " ").trim().to_string();
+    (!title.is_empty()).then_some(title)
+}
+
+/// 取得範囲に収まる URL を記事にする（新しい順に `limit`，指定が無ければ [`DEFAULT_LIMIT`] 件まで）
+pub fn to_articles(
+    site_name: &str,
+    site_url: &str,
+    entries: &[SitemapEntry],
+    options: &FetchOptions,
+) -> Vec<WebArticle> {
+    let mut articles: Vec<WebArticle> = entries
+        .iter()
+        .filter_map(|entry| {
+            let date = entry.date?;
+            if !options.contains(&date) {
+                return None;
+            }
+            let url = Url::parse(&entry.url).ok()?;
+            let title = entry.title.clone().or_else(|| title_from_url(&url))?;
+            Some(WebArticle::new(
+                site_name.to_string(),
+                site_url.to_string(),
+                title,
+                url.to_string(),
+                String::new(),
+                date,
+            ))
+        })
+        .collect();
+    articles.sort_by(newest_first);
+    articles.truncate(options.limit.unwrap_or(DEFAULT_LIMIT));
+    articles
+}
+
+/// 範囲内に更新された子のサイトマップ（更新日時の無いものを含む）を新しい順に選ぶ
+fn select_children(children: &[SitemapEntry], options: &FetchOptions) -> Vec<SitemapEntry> {
+    let mut children: Vec<SitemapEntry> = children
+        .iter()
+        .filter(|child| child.date.is_none_or(|date| options.since.is_none_or(|since| date >= since)))
+        .cloned()
+        .collect();
+    children.sort_by(|a, b| b.date.cmp(&a.date));
+    children.truncate(MAX_CHILD_SITEMAPS);
+    children
+}
+
+/// `site` のリクエスト設定（許可されたドメイン，レート制限など）でサイトマップを取得し，記事一覧を組み立てる
+pub async fn fetch_articles<S>(site: &S, sitemap_url: &Url, options: &FetchOptions) -> AppResult<Vec<WebArticle>>
+where
+    S: WebSiteInterface + ?Sized,
+{
+    let response = site.request(sitemap_url.as_str(), "").await?;
+    let entries = match parse_sitemap(&response.text().await?)? {
+        Sitemap::UrlSet(entries) => entries,
+        Sitemap::Index(children) => {
+            let mut entries = Vec::new();
+            for child in select_children(&children, options) {
+                let response = site.request(&child.url, "").await?;
+                match parse_sitemap(&response.text().await?)? {
+                    Sitemap::UrlSet(child_entries) => entries.extend(child_entries),
+                    // 入れ子のインデックスは読まない
+                    Sitemap::Index(_) => tracing::debug!("Nested sitemap index is ignored: {}", child.url),
+                }
+            }
+            entries
+        }
+    };
+    Ok(to_articles(&site.site_name(), site.site_url().as_str(), &entries, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEWS_SITEMAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:news="http://www.google.com/schemas/sitemap-news/0.9">
+  <url>
+    <loc>https://example.com/news/launch?id=1&amp;lang=ja</loc>
+    <lastmod>2025-10-14T12:00:00+09:00</lastmod>
+    <news:news>
+      <news:publication_date>2025-10-14T09:00:00+09:00</news:publication_date>
+      <news:title><![CDATA[新製品を発表]]></news:title>
+    </news:news>
+  </url>
+  <url>
+    <loc>https://example.com/blog/hello-world.html</loc>
+    <lastmod>2025-10-13</lastmod>
+  </url>
+  <url>
+    <loc>https://example.com/about</loc>
+  </url>
+  <url>
+    <loc>https://example.com/blog/old-post/</loc>
+    <lastmod>2020-01-01</lastmod>
+  </url>
+</urlset>"#;
+
+    const INDEX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>https://example.com/sitemap-2025-10.xml</loc><lastmod>2025-10-14</lastmod></sitemap>
+  <sitemap><loc>https://example.com/sitemap-2020-01.xml</loc><lastmod>2020-01-31</lastmod></sitemap>
+  <sitemap><loc>https://example.com/sitemap-pages.xml</loc></sitemap>
+</sitemapindex>"#;
+
+    fn window() -> FetchOptions {
+        FetchOptions {
+            since: parse_w3c_date("2025-10-01"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_sitemap() {
+        let Sitemap::UrlSet(entries) = parse_sitemap(NEWS_SITEMAP).unwrap() else {
+            panic!("expected <urlset>");
+        };
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].url, "https://example.com/news/launch?id=1&lang=ja");
+        assert_eq!(entries[0].title.as_deref(), Some("新製品を発表"));
+        assert_eq!(entries[0].date, parse_w3c_date("2025-10-14T09:00:00+09:00"));
+        assert_eq!(entries[1].date, parse_w3c_date("2025-10-13"));
+        assert_eq!(entries[2].date, None);
+
+        assert!(matches!(parse_sitemap(INDEX).unwrap(), Sitemap::Index(children) if children.len() == 3));
+        assert!(parse_sitemap("<html><body>Not found</body></html>").is_err());
+    }
+
+    #[test]
+    fn test_to_articles_filters_by_window() {
+        let Sitemap::UrlSet(entries) = parse_sitemap(NEWS_SITEMAP).unwrap() else {
+            panic!("expected <urlset>");
+        };
+        let articles = to_articles("Example", "https://example.com/sitemap.xml", &entries, &window());
+        let titles: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["新製品を発表", "hello world"]);
+        assert_eq!(articles[1].article_url, "https://example.com/blog/hello-world.html");
+
+        let all = to_articles("Example", "https://example.com/sitemap.xml", &entries, &FetchOptions::default());
+        assert_eq!(all.len(), 3);
+        let limited = FetchOptions {
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(to_articles("Example", "https://example.com/sitemap.xml", &entries, &limited).len(), 1);
+    }
+
+    #[test]
+    fn test_select_children() {
+        let Sitemap::Index(children) = parse_sitemap(INDEX).unwrap() else {
+            panic!("expected <sitemapindex>");
+        };
+        let urls: Vec<String> = select_children(&children, &window()).into_iter().map(|c| c.url).collect();
+        assert_eq!(urls, ["https://example.com/sitemap-2025-10.xml", "https://example.com/sitemap-pages.xml"]);
+        assert_eq!(select_children(&children, &FetchOptions::default()).len(), 3);
+    }
+
+    #[test]
+    fn test_parse_w3c_date() {
+        assert!(parse_w3c_date("2025-10-14T09:00:00Z").is_some());
+        assert!(parse_w3c_date("2025-10-14").is_some());
+        assert!(parse_w3c_date("yesterday").is_none());
+    }
+}
@@ -0,0 +1,529 @@
+//! 1日分の記事を1段落（5文）にまとめた「モーニングブリーフ」．
+//!
+//! よく取り上げられた話題（タイトルの似た記事のまとまり），最も大きなセキュリティの問題，注目のリリースを記事から集計し，
+//! `OPENAI_API_KEY` が設定されていれば LLM で文章にする．設定されていない場合や LLM が失敗した場合は，
+//! 集計結果を定型文に当てはめる．ダイジェストの先頭と Slack の朝の投稿に使う．
+
+use crate::alerts::issues::extract_cves;
+use crate::enrichment::classifier::{OPENAI_CHAT_COMPLETIONS_URL, parse_structured_completion};
+use crate::models::tag::ArticleTag;
+use crate::models::dedup::title_similarity;
+use crate::models::ordering::{oldest_first, sort_newest_first};
+use crate::models::web_article::WebArticle;
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::{ArticleQuery, ArticleStore};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// ブリーフの文の数
+pub const BRIEF_SENTENCES: usize = 5;
+/// 同じ話題とみなすタイトルの類似度（重複の判定より緩くする）
+const CLUSTER_SIMILARITY: f64 = 0.5;
+/// ブリーフに含める話題・リリースの数
+const MAX_CLUSTERS: usize = 3;
+const MAX_RELEASES: usize = 3;
+/// LLM に渡す記事タイトルの数
+const MAX_PROMPT_TITLES: usize = 40;
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+const SYSTEM_PROMPT: &str = "You write a morning brief of a day's technology news for busy engineers. \
+Write exactly five sentences in English as one paragraph: the overall volume, the most covered stories, \
+the biggest security issue, notable releases, and one closing observation. \
+Use only the facts and titles given; do not invent details.";
+
+/// 記事の1件（タイトルと掲載サイト）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BriefItem {
+    pub title: String,
+    pub site: String,
+    pub url: String,
+}
+
+impl BriefItem {
+    fn new(article: &WebArticle) -> Self {
+        Self {
+            title: article.title.trim().to_string(),
+            site: article.site.name.clone(),
+            url: article.article_url.clone(),
+        }
+    }
+}
+
+/// タイトルの似た記事のまとまり（代表は最初に公開された記事）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoryCluster {
+    pub title: String,
+    pub articles: usize,
+    pub sites: Vec<String>,
+}
+
+/// ブリーフの材料となる集計結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BriefFacts {
+    pub date: NaiveDate,
+    pub total: usize,
+    pub sites: usize,
+    /// 最も記事の多いサイトと件数
+    pub busiest_site: Option<(String, usize)>,
+    /// 2件以上の記事で取り上げられた話題（多い順）
+    pub clusters: Vec<StoryCluster>,
+    /// タグごとの記事数（多い順．話題のまとまりが無い日に使う）
+    pub tags: Vec<(ArticleTag, usize)>,
+    pub security: Option<BriefItem>,
+    /// `security` の記事が参照する CVE
+    pub cves: Vec<String>,
+    pub releases: Vec<BriefItem>,
+}
+
+impl BriefFacts {
+    /// `date`（ローカル時刻）に公開された記事を集計する
+    pub fn collect(date: NaiveDate, articles: &[WebArticle]) -> Self {
+        let mut articles: Vec<&WebArticle> = articles.iter().filter(|a| a.timestamp.date_naive() == date).collect();
+        articles.sort_by(|a, b| oldest_first(a, b));
+
+        let mut per_site: HashMap<&str, usize> = HashMap::new();
+        for article in &articles {
+            *per_site.entry(article.site.name.as_str()).or_default() += 1;
+        }
+        let busiest_site = per_site
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(site, count)| (site.to_string(), *count));
+
+        // 話題のまとまり（記事の位置の一覧）
+        let groups = cluster_articles(&articles);
+        let coverage = |index: usize| groups.iter().find(|g| g.contains(&index)).map_or(1, Vec::len);
+
+        let mut clusters: Vec<StoryCluster> = groups
+            .iter()
+            .filter(|group| group.len() > 1)
+            .map(|group| {
+                let mut sites: Vec<String> = Vec::new();
+                for &i in group {
+                    if !sites.contains(&articles[i].site.name) {
+                        sites.push(articles[i].site.name.clone());
+                    }
+                }
+                StoryCluster {
+                    title: articles[group[0]].title.trim().to_string(),
+                    articles: group.len(),
+                    sites,
+                }
+            })
+            .collect();
+        clusters.sort_by(|a, b| b.sites.len().cmp(&a.sites.len()).then_with(|| b.articles.cmp(&a.articles)));
+        clusters.truncate(MAX_CLUSTERS);
+
+        let mut tags: Vec<(ArticleTag, usize)> = [
+            ArticleTag::Ai,
+            ArticleTag::Security,
+            ArticleTag::NewProduct,
+            ArticleTag::AcademicPaper,
+            ArticleTag::NewTechnology,
+        ]
+        .into_iter()
+        .map(|tag| (tag, articles.iter().filter(|a| tag.matches(a)).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1));
+
+        // セキュリティの記事は，取り上げた記事の数，参照する CVE の数，新しさの順に重視する
+        let security = (0..articles.len())
+            .filter(|&i| ArticleTag::Security.matches(articles[i]))
+            .max_by_key(|&i| (coverage(i), cves_of(articles[i]).len(), articles[i].timestamp));
+        let cves = security.map(|i| cves_of(articles[i])).unwrap_or_default();
+
+        let mut releases: Vec<usize> = (0..articles.len())
+            .filter(|&i| ArticleTag::NewProduct.matches(articles[i]))
+            .collect();
+        releases.sort_by_key(|&i| std::cmp::Reverse((coverage(i), articles[i].timestamp)));
+        // 同じ話題のリリースは1件にまとめる
+        let mut seen_groups: Vec<usize> = Vec::new();
+        releases.retain(|&i| {
+            let group = groups.iter().position(|g| g.contains(&i));
+            match group {
+                Some(g) if seen_groups.contains(&g) => false,
+                Some(g) => {
+                    seen_groups.push(g);
+                    true
+                }
+                None => true,
+            }
+        });
+
+        Self {
+            date,
+            total: articles.len(),
+            sites: per_site.len(),
+            busiest_site,
+            clusters,
+            tags,
+            security: security.map(|i| BriefItem::new(articles[i])),
+            cves,
+            releases: releases.into_iter().take(MAX_RELEASES).map(|i| BriefItem::new(articles[i])).collect(),
+        }
+    }
+}
+
+fn cves_of(article: &WebArticle) -> Vec<String> {
+    extract_cves(&format!("{}\n{}\n{}", article.title, article.description, article.text))
+}
+
+/// タイトルが似た記事をまとめる（各まとまりは記事の位置の昇順）
+fn cluster_articles(articles: &[&WebArticle]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, article) in articles.iter().enumerate() {
+        let matched = groups
+            .iter_mut()
+            .map(|group| {
+                let similarity = title_similarity(&articles[group[0]].title, &article.title);
+                (similarity, group)
+            })
+            .filter(|(similarity, _)| *similarity >= CLUSTER_SIMILARITY)
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        match matched {
+            Some((_, group)) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+    groups
+}
+
+/// 1日の概要（5文）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MorningBrief {
+    pub sentences: Vec<String>,
+}
+
+impl MorningBrief {
+    /// 文を1段落につなげる
+    pub fn paragraph(&self) -> String {
+        self.sentences.iter().map(|s| s.trim()).filter(|s| !s.is_empty()).collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[async_trait::async_trait]
+pub trait BriefWriter: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn write(&self, facts: &BriefFacts, articles: &[WebArticle]) -> AppResult<MorningBrief>;
+}
+
+/// 集計結果を定型文に当てはめる（API キーが無い場合のフォールバック）
+#[derive(Debug, Clone, Default)]
+pub struct TemplateBriefWriter;
+
+fn join_list(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [one] => one.clone(),
+        [init @ .., last] => format!("{} and {}", init.join(", "), last),
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 { format!("1 {}", noun) } else { format!("{} {}s", count, noun) }
+}
+
+impl TemplateBriefWriter {
+    pub fn compose(&self, facts: &BriefFacts) -> MorningBrief {
+        let overview = format!(
+            "On {}, {} were clipped from {}.",
+            facts.date.format("%Y-%m-%d"),
+            plural(facts.total, "article"),
+            plural(facts.sites, "site")
+        );
+
+        let stories = if !facts.clusters.is_empty() {
+            let clusters: Vec<String> = facts
+                .clusters
+                .iter()
+                .map(|c| {
+                    let coverage = format!("{} from {}", plural(c.articles, "article"), plural(c.sites.len(), "site"));
+                    format!("\"{}\" ({})", c.title, coverage)
+                })
+                .collect();
+            format!("The most covered stories were {}.", join_list(&clusters))
+        } else if !facts.tags.is_empty() {
+            let tags: Vec<String> =
+                facts.tags.iter().take(MAX_CLUSTERS).map(|(tag, count)| format!("{} ({})", tag, count)).collect();
+            format!("No story was picked up twice; coverage leaned toward {}.", join_list(&tags))
+        } else {
+            "No story was picked up by more than one article.".to_string()
+        };
+
+        let security = match &facts.security {
+            Some(item) if facts.cves.is_empty() => {
+                format!("The biggest security issue was \"{}\" ({}).", item.title, item.site)
+            }
+            Some(item) => format!(
+                "The biggest security issue was \"{}\" ({}), referencing {}.",
+                item.title,
+                item.site,
+                join_list(&facts.cves)
+            ),
+            None => "No security issues were reported.".to_string(),
+        };
+
+        let releases = if facts.releases.is_empty() {
+            "No notable product releases were reported.".to_string()
+        } else {
+            let releases: Vec<String> =
+                facts.releases.iter().map(|r| format!("\"{}\" ({})", r.title, r.site)).collect();
+            format!("Notable releases included {}.", join_list(&releases))
+        };
+
+        let closing = match &facts.busiest_site {
+            Some((site, count)) => format!("The busiest source was {} with {}.", site, plural(*count, "article")),
+            None => "There was nothing else to report.".to_string(),
+        };
+
+        MorningBrief {
+            sentences: vec![overview, stories, security, releases, closing],
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BriefWriter for TemplateBriefWriter {
+    fn name(&self) -> &'static str {
+        "template"
+    }
+    async fn write(&self, facts: &BriefFacts, _articles: &[WebArticle]) -> AppResult<MorningBrief> {
+        Ok(self.compose(facts))
+    }
+}
+
+/// OpenAI の Chat Completions API（Structured Outputs）で文章にする
+#[derive(Debug, Clone)]
+pub struct LlmBriefWriter {
+    client: request::Client,
+    api_key: String,
+    model: String,
+    endpoint: String,
+}
+
+impl LlmBriefWriter {
+    pub fn new(api_key: &str, model: &str) -> Self {
+        Self {
+            client: request::Client::new(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            endpoint: OPENAI_CHAT_COMPLETIONS_URL.to_string(),
+        }
+    }
+
+    /// OpenAI 互換の別エンドポイントを使う
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    /// `OPENAI_API_KEY` が設定されていれば作成する．モデルは `CLIPPER_BRIEF_MODEL`（デフォルト `gpt-4o-mini`）．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let Some(api_key) = std::env::var("OPENAI_API_KEY").ok().filter(|k| !k.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let model: String = parse_env_or("CLIPPER_BRIEF_MODEL", DEFAULT_MODEL.to_string())?;
+        Ok(Some(Self::new(api_key.trim(), &model)))
+    }
+
+    fn response_format() -> serde_json::Value {
+        json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "morning_brief",
+                "strict": true,
+                "schema": {
+                    "type": "object",
+                    "properties": {
+                        "sentences": { "type": "array", "items": { "type": "string" } },
+                    },
+                    "required": ["sentences"],
+                    "additionalProperties": false,
+                },
+            },
+        })
+    }
+
+    /// 集計結果と，新しい順の記事タイトルを渡す
+    fn request_body(&self, facts: &BriefFacts, articles: &[WebArticle]) -> serde_json::Value {
+        let mut titles: Vec<&WebArticle> =
+            articles.iter().filter(|a| a.timestamp.date_naive() == facts.date).collect();
+        sort_newest_first(&mut titles);
+        let titles: Vec<String> = titles
+            .iter()
+            .take(MAX_PROMPT_TITLES)
+            .map(|a| format!("- {} ({})", a.title.trim(), a.site.name))
+            .collect();
+        let input = format!("Facts: {}\nTitles:\n{}", json!(facts), titles.join("\n"));
+        json!({
+            "model": self.model,
+            "temperature": 0.3,
+            "messages": [
+                { "role": "system", "content": SYSTEM_PROMPT },
+                { "role": "user", "content": input },
+            ],
+            "response_format": Self::response_format(),
+        })
+    }
+}
+
+/// 文の数が `BRIEF_SENTENCES` でない回答はエラーにする（定型文にフォールバックさせる）
+fn parse_brief(body: &str) -> AppResult<MorningBrief> {
+    let brief: MorningBrief = parse_structured_completion(body)?;
+    let sentences = brief.sentences.iter().filter(|s| !s.trim().is_empty()).count();
+    if sentences != BRIEF_SENTENCES {
+        return Err(AppError::LlmError(format!(
+            "Expected {} sentences in morning brief, got {}",
+            BRIEF_SENTENCES, sentences
+        )));
+    }
+    Ok(brief)
+}
+
+#[async_trait::async_trait]
+impl BriefWriter for LlmBriefWriter {
+    fn name(&self) -> &'static str {
+        "llm"
+    }
+    async fn write(&self, facts: &BriefFacts, articles: &[WebArticle]) -> AppResult<MorningBrief> {
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&self.request_body(facts, articles))
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(AppError::LlmError(format!("{}: {}", status, body)));
+        }
+        parse_brief(&body)
+    }
+}
+
+/// 環境に応じたブリーフの作成方法を返す（API キーが無い，または設定が不正な場合は定型文）
+pub fn brief_writer_from_env() -> Box<dyn BriefWriter> {
+    match LlmBriefWriter::from_env() {
+        Ok(Some(llm)) => Box::new(llm),
+        Ok(None) => Box::new(TemplateBriefWriter),
+        Err(e) => {
+            tracing::warn!("{}; falling back to template morning brief", e);
+            Box::new(TemplateBriefWriter)
+        }
+    }
+}
+
+/// `date` の記事のブリーフを作成する（記事が無い場合は `None`）．LLM が失敗した場合は定型文にする．
+pub async fn write_morning_brief(
+    writer: &dyn BriefWriter,
+    date: NaiveDate,
+    articles: &[WebArticle],
+) -> Option<MorningBrief> {
+    let facts = BriefFacts::collect(date, articles);
+    if facts.total == 0 {
+        return None;
+    }
+    match writer.write(&facts, articles).await {
+        Ok(brief) => Some(brief),
+        Err(e) => {
+            tracing::warn!("Failed to write morning brief with {} writer: {}", writer.name(), e);
+            Some(TemplateBriefWriter.compose(&facts))
+        }
+    }
+}
+
+/// `date`（ローカル時刻）の 00:00 から翌日の 00:00 までに公開された記事
+pub async fn articles_of_day(store: &dyn ArticleStore, date: NaiveDate) -> AppResult<Vec<WebArticle>> {
+    let start_of_day =
+        |date: NaiveDate| date.and_hms_opt(0, 0, 0).and_then(|t| t.and_local_timezone(Local).earliest());
+    Ok(store
+        .query(&ArticleQuery {
+            since: start_of_day(date),
+            until: date.succ_opt().and_then(start_of_day),
+            ..Default::default()
+        })
+        .await?
+        .into_iter()
+        .map(|stored| stored.article)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::TimeZone;
+
+    fn article(site: &str, title: &str, hour: u32) -> WebArticle {
+        ArticleBuilder::new(title)
+            .with_site(site, format!("https://{}.example.com", site.to_lowercase()))
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, hour, 0, 0).unwrap())
+            .build()
+    }
+
+    fn articles() -> Vec<WebArticle> {
+        let mut security = article("JPCERT", "OpenSSL の脆弱性 CVE-2025-1234 について", 9);
+        security.properties.is_security_related = Some(true);
+        let mut minor = article("Security NEXT", "小さな設定ミス", 10);
+        minor.properties.is_security_related = Some(true);
+        let mut release = article("Gigazine", "Rust 1.90 がリリース", 11);
+        release.properties.is_new_product_related = Some(true);
+        let mut release_again = article("Publickey", "Rust 1.90 がリリース，新機能まとめ", 12);
+        release_again.properties.is_new_product_related = Some(true);
+        let mut previous_day = article("Gigazine", "前日の記事", 8);
+        previous_day.timestamp = Local.with_ymd_and_hms(2025, 9, 30, 8, 0, 0).unwrap();
+        vec![security, minor, release, release_again, article("Gigazine", "猫の写真", 13), previous_day]
+    }
+
+    #[test]
+    fn test_collect_facts() {
+        let facts = BriefFacts::collect(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &articles());
+        assert_eq!(facts.total, 5);
+        assert_eq!(facts.sites, 4);
+        assert_eq!(facts.busiest_site, Some(("Gigazine".to_string(), 2)));
+        assert_eq!(facts.clusters.len(), 1);
+        assert_eq!(facts.clusters[0].title, "Rust 1.90 がリリース");
+        assert_eq!(facts.clusters[0].sites, ["Gigazine", "Publickey"]);
+        assert_eq!(facts.security.as_ref().unwrap().site, "JPCERT");
+        assert_eq!(facts.cves, ["CVE-2025-1234"]);
+        // 同じ話題のリリースは1件にまとめる
+        assert_eq!(facts.releases.len(), 1);
+    }
+
+    #[test]
+    fn test_template_brief_has_five_sentences() {
+        let facts = BriefFacts::collect(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &articles());
+        let brief = TemplateBriefWriter.compose(&facts);
+        assert_eq!(brief.sentences.len(), BRIEF_SENTENCES);
+        let paragraph = brief.paragraph();
+        assert!(paragraph.starts_with("On 2025-10-01, 5 articles were clipped from 4 sites."));
+        assert!(paragraph.contains("\"Rust 1.90 がリリース\" (2 articles from 2 sites)"));
+        assert!(paragraph.contains("referencing CVE-2025-1234."));
+        assert!(paragraph.contains("The busiest source was Gigazine with 2 articles."));
+
+        let empty = BriefFacts::collect(NaiveDate::from_ymd_opt(2025, 10, 2).unwrap(), &articles());
+        let brief = TemplateBriefWriter.compose(&empty);
+        assert_eq!(brief.sentences.len(), BRIEF_SENTENCES);
+        assert!(brief.paragraph().contains("No security issues were reported."));
+    }
+
+    #[tokio::test]
+    async fn test_write_morning_brief_skips_empty_days() {
+        let date = NaiveDate::from_ymd_opt(2025, 10, 2).unwrap();
+        assert!(write_morning_brief(&TemplateBriefWriter, date, &articles()).await.is_none());
+    }
+
+    #[test]
+    fn test_parse_brief_requires_five_sentences() {
+        let body = r#"{"choices":[{"message":{"content":"{\"sentences\":[\"a.\",\"b.\",\"c.\",\"d.\",\"e.\"]}","refusal":null}}]}"#;
+        assert_eq!(parse_brief(body).unwrap().paragraph(), "a. b. c. d. e.");
+        let short = r#"{"choices":[{"message":{"content":"{\"sentences\":[\"a.\",\"b.\"]}","refusal":null}}]}"#;
+        assert!(parse_brief(short).is_err());
+    }
+}
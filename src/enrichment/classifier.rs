@@ -0,0 +1,419 @@
+//! 記事のトピック分類（`WebArticleProperty` の `is_ai_related` などのフラグ）．
+//!
+//! `OPENAI_API_KEY` が設定されていれば Structured Outputs（JSON Schema）を使う LLM で分類し，
+//! 設定されていなければキーワードによる分類にフォールバックする．
+//! LLM には利用者が修正した分類結果（[`crate::enrichment::few_shot`]）から近いものを例として渡す．
+
+use crate::enrichment::few_shot::{ExampleStore, FewShotSelector, LabeledExample};
+use crate::models::web_article::{WebArticle, WebArticleProperty};
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+pub(crate) const OPENAI_CHAT_COMPLETIONS_URL: &str = "https://api.openai.com/v1/chat/completions";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+/// LLM に渡す本文の最大文字数
+const MAX_INPUT_CHARS: usize = 3000;
+
+const SYSTEM_PROMPT: &str = "You classify Japanese and English technology news articles. \
+For each flag, answer true only if the article is primarily about that topic.";
+
+/// 分類結果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Classification {
+    pub is_new_technology_related: bool,
+    pub is_new_product_related: bool,
+    pub is_new_academic_paper_related: bool,
+    pub is_ai_related: bool,
+    pub is_security_related: bool,
+    pub is_it_related: bool,
+}
+
+impl Classification {
+    /// 分類結果を記事のプロパティに書き込む（要約などの他の項目は変更しない）．
+    /// 取得元のサイトが付けたタグ（`source_tags`）のフラグは分類結果に関わらず立てたままにする．
+    pub fn apply(&self, properties: &mut WebArticleProperty) {
+        properties.is_new_technology_related = Some(self.is_new_technology_related);
+        properties.is_new_product_related = Some(self.is_new_product_related);
+        properties.is_new_academic_paper_related = Some(self.is_new_academic_paper_related);
+        properties.is_ai_related = Some(self.is_ai_related);
+        properties.is_security_related = Some(self.is_security_related);
+        properties.is_it_related = Some(self.is_it_related);
+        for tag in properties.source_tags.clone() {
+            *tag.flag_mut(properties) = Some(true);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait Classifier: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn classify(&self, article: &WebArticle) -> AppResult<Classification>;
+}
+
+/// 分類に使うテキスト（タイトル，概要，本文の先頭）
+pub(crate) fn classification_input(article: &WebArticle) -> String {
+    let text: String = article.text.chars().take(MAX_INPUT_CHARS).collect();
+    format!(
+        "Title: {}\nDescription: {}\nText: {}",
+        article.title.trim(),
+        article.description.trim(),
+        text.trim()
+    )
+}
+
+/// キーワードの出現で分類する（API キーが無い場合のフォールバック）
+#[derive(Debug, Clone, Default)]
+pub struct KeywordClassifier;
+
+const AI_KEYWORDS: &[&str] = &[
+    "ai", "人工知能", "機械学習", "深層学習", "ディープラーニング", "llm", "大規模言語モデル",
+    "生成ai", "chatgpt", "gpt", "openai", "claude", "gemini", "machine learning", "deep learning",
+    "neural", "ニューラル", "transformer",
+];
+const SECURITY_KEYWORDS: &[&str] = &[
+    "セキュリティ", "脆弱性", "マルウェア", "ランサムウェア", "不正アクセス", "サイバー攻撃",
+    "フィッシング", "情報漏えい", "情報漏洩", "security", "vulnerability", "cve-", "malware",
+    "ransomware", "phishing", "exploit",
+];
+const PRODUCT_KEYWORDS: &[&str] = &[
+    "発売", "提供開始", "リリース", "新製品", "新サービス", "発表", "launch", "released",
+    "now available", "announces",
+];
+const PAPER_KEYWORDS: &[&str] = &[
+    "論文", "arxiv", "学会", "研究チーム", "研究グループ", "paper", "preprint", "neurips", "icml",
+    "acl", "cvpr",
+];
+const TECHNOLOGY_KEYWORDS: &[&str] = &[
+    "新技術", "技術を開発", "手法を開発", "世界初", "breakthrough", "novel", "state-of-the-art",
+];
+const IT_KEYWORDS: &[&str] = &[
+    "クラウド", "ソフトウェア", "プログラミング", "開発者", "エンジニア", "データベース", "api",
+    "rust", "python", "kubernetes", "aws", "azure", "software", "developer", "cloud",
+];
+
+/// ASCII のキーワードは単語の境界でのみマッチさせる（`ai` が `said` にマッチしないように）
+fn contains_keyword(haystack: &str, keyword: &str) -> bool {
+    if !keyword.is_ascii() {
+        return haystack.contains(keyword);
+    }
+    haystack.match_indices(keyword).any(|(i, _)| {
+        let before = haystack[..i].chars().next_back();
+        let after = haystack[i + keyword.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_alphanumeric())
+            && !after.is_some_and(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+fn matches_any(haystack: &str, keywords: &[&str]) -> bool {
+    keywords.iter().any(|k| contains_keyword(haystack, k))
+}
+
+impl KeywordClassifier {
+    pub fn classify_text(&self, text: &str) -> Classification {
+        let haystack = text.to_lowercase();
+        let is_ai_related = matches_any(&haystack, AI_KEYWORDS);
+        let is_security_related = matches_any(&haystack, SECURITY_KEYWORDS);
+        Classification {
+            is_new_technology_related: matches_any(&haystack, TECHNOLOGY_KEYWORDS),
+            is_new_product_related: matches_any(&haystack, PRODUCT_KEYWORDS),
+            is_new_academic_paper_related: matches_any(&haystack, PAPER_KEYWORDS),
+            is_ai_related,
+            is_security_related,
+            is_it_related: is_ai_related || is_security_related || matches_any(&haystack, IT_KEYWORDS),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Classifier for KeywordClassifier {
+    fn name(&self) -> &'static str {
+        "keyword"
+    }
+    async fn classify(&self, article: &WebArticle) -> AppResult<Classification> {
+        Ok(self.classify_text(&classification_input(article)))
+    }
+}
+
+/// OpenAI の Chat Completions API（Structured Outputs）で分類する
+#[derive(Debug, Clone)]
+pub struct LlmClassifier {
+    client: request::Client,
+    api_key: String,
+    model: String,
+    endpoint: String,
+    few_shot: Option<Arc<FewShotSelector>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+    refusal: Option<String>,
+}
+
+impl LlmClassifier {
+    pub fn new(api_key: &str, model: &str) -> Self {
+        Self {
+            client: request::Client::new(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            endpoint: OPENAI_CHAT_COMPLETIONS_URL.to_string(),
+            few_shot: None,
+        }
+    }
+
+    /// OpenAI 互換の別エンドポイントを使う
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    /// 記事に近い分類の例をプロンプトに含める
+    pub fn with_few_shot(mut self, selector: Arc<FewShotSelector>) -> Self {
+        self.few_shot = Some(selector);
+        self
+    }
+
+    /// `OPENAI_API_KEY` が設定されていれば作成する．モデルは `CLIPPER_CLASSIFIER_MODEL`（デフォルト `gpt-4o-mini`）．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let Some(api_key) = std::env::var("OPENAI_API_KEY").ok().filter(|k| !k.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let model: String = parse_env_or("CLIPPER_CLASSIFIER_MODEL", DEFAULT_MODEL.to_string())?;
+        Ok(Some(Self::new(api_key.trim(), &model)))
+    }
+
+    /// 分類結果の JSON Schema（全フラグ必須，追加のプロパティ不可）
+    fn response_format() -> serde_json::Value {
+        let flags = [
+            "is_new_technology_related",
+            "is_new_product_related",
+            "is_new_academic_paper_related",
+            "is_ai_related",
+            "is_security_related",
+            "is_it_related",
+        ];
+        let properties: serde_json::Map<String, serde_json::Value> = flags
+            .iter()
+            .map(|flag| (flag.to_string(), json!({ "type": "boolean" })))
+            .collect();
+        json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "article_classification",
+                "strict": true,
+                "schema": {
+                    "type": "object",
+                    "properties": properties,
+                    "required": flags,
+                    "additionalProperties": false,
+                },
+            },
+        })
+    }
+
+    /// 例は利用者の入力とアシスタントの回答の組としてシステムプロンプトの後に並べる
+    fn request_body(&self, article: &WebArticle, examples: &[LabeledExample]) -> serde_json::Value {
+        let mut messages = vec![json!({ "role": "system", "content": SYSTEM_PROMPT })];
+        for example in examples {
+            messages.push(json!({ "role": "user", "content": example.input }));
+            messages.push(json!({ "role": "assistant", "content": json!(example.classification).to_string() }));
+        }
+        messages.push(json!({ "role": "user", "content": classification_input(article) }));
+        json!({
+            "model": self.model,
+            "temperature": 0,
+            "messages": messages,
+            "response_format": Self::response_format(),
+        })
+    }
+}
+
+/// API のレスポンスから分類結果を取り出す
+fn parse_completion(body: &str) -> AppResult<Classification> {
+    parse_structured_completion(body)
+}
+
+/// Structured Outputs のレスポンスの本文（JSON）を `T` として取り出す
+pub(crate) fn parse_structured_completion<T: serde::de::DeserializeOwned>(body: &str) -> AppResult<T> {
+    let response: ChatCompletionResponse = serde_json::from_str(body)?;
+    let message = response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message)
+        .ok_or_else(|| AppError::LlmError("Empty completion".into()))?;
+    if let Some(refusal) = message.refusal {
+        return Err(AppError::LlmError(format!("Completion refused: {}", refusal)));
+    }
+    let content = message
+        .content
+        .ok_or_else(|| AppError::LlmError("Completion has no content".into()))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+#[async_trait::async_trait]
+impl Classifier for LlmClassifier {
+    fn name(&self) -> &'static str {
+        "llm"
+    }
+    async fn classify(&self, article: &WebArticle) -> AppResult<Classification> {
+        // 例を選べなくても分類は続ける
+        let examples = match &self.few_shot {
+            Some(selector) => selector.select(article).await.unwrap_or_else(|e| {
+                tracing::warn!("Failed to select few-shot examples: {}", e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&self.request_body(article, &examples))
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(AppError::LlmError(format!("{}: {}", status, body)));
+        }
+        parse_completion(&body)
+    }
+}
+
+/// 環境に応じた分類器を返す（API キーが無い，または設定が不正な場合はキーワード分類）．
+/// `examples` を渡すと，LLM で分類するときに few-shot の例として使う．
+pub fn classifier_from_env(examples: Option<Arc<ExampleStore>>) -> Box<dyn Classifier> {
+    match LlmClassifier::from_env() {
+        Ok(Some(mut llm)) => {
+            if let Some(store) = examples {
+                match FewShotSelector::from_env(store) {
+                    Ok(Some(selector)) => llm = llm.with_few_shot(Arc::new(selector)),
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("{}; classifying without few-shot examples", e),
+                }
+            }
+            Box::new(llm)
+        }
+        Ok(None) => Box::new(KeywordClassifier),
+        Err(e) => {
+            tracing::warn!("{}; falling back to keyword classifier", e);
+            Box::new(KeywordClassifier)
+        }
+    }
+}
+
+/// 記事を分類してプロパティを更新する．分類に失敗した記事はそのままにし，成功した件数を返す．
+pub async fn classify_articles(classifier: &dyn Classifier, articles: &mut [WebArticle]) -> usize {
+    let mut classified = 0;
+    for article in articles.iter_mut() {
+        match classifier.classify(article).await {
+            Ok(classification) => {
+                classification.apply(&mut article.properties);
+                classified += 1;
+            }
+            Err(e) => tracing::warn!(
+                "Failed to classify {} with {} classifier: {}",
+                article.article_url,
+                classifier.name(),
+                e
+            ),
+        }
+    }
+    classified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tag::ArticleTag;
+    use crate::testing::article::ArticleBuilder;
+
+    #[test]
+    fn test_keyword_classifier() {
+        let classifier = KeywordClassifier;
+        let c = classifier.classify_text("OpenSSL に深刻な脆弱性，CVE-2025-1234 が公開");
+        assert!(c.is_security_related && c.is_it_related && !c.is_ai_related);
+
+        let c = classifier.classify_text("新しい LLM の論文が arXiv で公開");
+        assert!(c.is_ai_related && c.is_new_academic_paper_related && !c.is_security_related);
+
+        // `ai` は単語としてのみマッチする
+        let c = classifier.classify_text("He said the train was late");
+        assert_eq!(c, Classification::default());
+    }
+
+    #[test]
+    fn test_apply_keeps_source_tags() {
+        let mut properties = WebArticleProperty::default();
+        properties.tag_by_source(ArticleTag::NewProduct);
+        properties.tag_by_source(ArticleTag::NewProduct);
+        assert_eq!(properties.source_tags, [ArticleTag::NewProduct]);
+
+        let classification = Classification {
+            is_ai_related: true,
+            ..Default::default()
+        };
+        classification.apply(&mut properties);
+        assert_eq!(properties.is_ai_related, Some(true));
+        assert_eq!(properties.is_new_product_related, Some(true));
+        assert_eq!(properties.is_security_related, Some(false));
+    }
+
+    #[test]
+    fn test_response_format_requires_all_flags() {
+        let format = LlmClassifier::response_format();
+        let schema = &format["json_schema"]["schema"];
+        assert_eq!(schema["required"].as_array().unwrap().len(), 6);
+        assert_eq!(schema["properties"]["is_ai_related"]["type"], "boolean");
+        assert_eq!(schema["additionalProperties"], false);
+    }
+
+    #[test]
+    fn test_request_body_includes_few_shot_examples() {
+        let article = ArticleBuilder::new("新しい LLM").with_url("https://example.com/2").build();
+        let classification = Classification {
+            is_ai_related: true,
+            ..Default::default()
+        };
+        let example = LabeledExample::new(&article, classification);
+        let body = LlmClassifier::new("key", "model").request_body(&article, &[example]);
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[1]["role"], "user");
+        assert_eq!(messages[2]["role"], "assistant");
+        let answer: Classification = serde_json::from_str(messages[2]["content"].as_str().unwrap()).unwrap();
+        assert_eq!(answer, classification);
+        assert_eq!(messages[3]["content"], messages[1]["content"]);
+    }
+
+    #[test]
+    fn test_parse_completion() {
+        let body = r#"{"choices":[{"message":{"role":"assistant","refusal":null,"content":"{\"is_new_technology_related\":false,\"is_new_product_related\":true,\"is_new_academic_paper_related\":false,\"is_ai_related\":true,\"is_security_related\":false,\"is_it_related\":true}"}}]}"#;
+        let c = parse_completion(body).unwrap();
+        assert!(c.is_new_product_related && c.is_ai_related && c.is_it_related);
+
+        let mut properties = WebArticleProperty::default();
+        properties.summary = Some("要約".into());
+        c.apply(&mut properties);
+        assert_eq!(properties.is_ai_related, Some(true));
+        assert_eq!(properties.summary.as_deref(), Some("要約"));
+
+        let refused = r#"{"choices":[{"message":{"content":null,"refusal":"no"}}]}"#;
+        assert!(parse_completion(refused).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! 学会・カンファレンスの告知から論文投稿（CFP）などの締切日と開催地を抽出する．
+//!
+//! 抽出した締切は新着記事の分類と同時に `WebArticleProperty::deadlines` に記録し，
+//! ダイジェストの「Upcoming Deadlines」セクションと `GET /deadlines` で日付順に一覧できる．
+
+use crate::export::ics::{CalendarEvent, extract_dates};
+use crate::models::web_article::WebArticle;
+use crate::shared::errors::AppResult;
+use crate::shared::regexes;
+use crate::storage::{ArticleQuery, ArticleStore};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+/// 締切の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DeadlineKind {
+    Abstract,
+    Submission,
+    Notification,
+    Registration,
+    /// 種類を特定できない締切
+    Other,
+}
+
+/// 締切の種類を判定するキーワード（先にマッチしたものを採用）
+const KIND_KEYWORDS: &[(DeadlineKind, &[&str])] = &[
+    (DeadlineKind::Abstract, &["abstract", "アブストラクト", "概要投稿", "概要締切"]),
+    (DeadlineKind::Notification, &["notification", "acceptance", "採否通知", "採択通知"]),
+    (DeadlineKind::Registration, &["registration", "参加登録", "申込締切", "申し込み締切"]),
+    (DeadlineKind::Submission, &["submission", "paper", "cfp", "call for", "論文投稿", "投稿締切", "原稿"]),
+];
+
+/// 締切を探す記事の範囲（今日から遡る公開日の日数）
+pub const DEADLINE_LOOKBACK_DAYS: i64 = 180;
+
+/// 締切に言及していると判定するキーワード
+const DEADLINE_KEYWORDS: &[&str] = &[
+    "deadline", "due", "締切", "締め切り", "〆切", "期限", "submission", "cfp",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deadline {
+    pub article_url: String,
+    pub title: String,
+    pub site: String,
+    pub kind: DeadlineKind,
+    pub date: NaiveDate,
+    pub venue: Option<String>,
+}
+
+impl Deadline {
+    /// 締切日を終日イベントとして ICS に書き出せる形にする
+    pub fn to_calendar_event(&self) -> CalendarEvent {
+        CalendarEvent {
+            uid: format!("{}#{}@news-clipper", self.article_url, self.kind),
+            summary: format!("[{} deadline] {}", self.kind, self.title),
+            date: self.date,
+            url: self.article_url.clone(),
+            description: self.venue.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// 記事の本文・概要から締切を抽出する．同じ種類の締切は最も早い日付のみを残す．
+pub fn detect_deadlines(article: &WebArticle) -> Vec<Deadline> {
+    let body = format!("{}\n{}\n{}", article.title, article.description, article.text);
+    let published = article.timestamp.date_naive();
+    let venue = extract_venue(&body);
+
+    let mut deadlines: Vec<Deadline> = Vec::new();
+    for sentence in split_sentences(&body) {
+        let lower = sentence.to_lowercase();
+        if !DEADLINE_KEYWORDS.iter().any(|k| lower.contains(k)) {
+            continue;
+        }
+        let kind = KIND_KEYWORDS
+            .iter()
+            .find(|(_, keywords)| keywords.iter().any(|k| lower.contains(k)))
+            .map(|(kind, _)| *kind)
+            .unwrap_or(DeadlineKind::Other);
+        for date in extract_dates(sentence, published.year()) {
+            if date < published {
+                continue;
+            }
+            match deadlines.iter_mut().find(|d| d.kind == kind) {
+                Some(existing) if existing.date <= date => {}
+                Some(existing) => existing.date = date,
+                None => deadlines.push(Deadline {
+                    article_url: article.article_url.clone(),
+                    title: article.title.clone(),
+                    site: article.site.name.clone(),
+                    kind,
+                    date,
+                    venue: venue.clone(),
+                }),
+            }
+        }
+    }
+    deadlines.sort_by_key(|d| (d.date, d.kind));
+    deadlines
+}
+
+/// 記事に記録した締切（`properties.deadlines`）から `today` 以降のものを日付順に返す
+pub fn upcoming_deadlines<'a>(articles: impl IntoIterator<Item = &'a WebArticle>, today: NaiveDate) -> Vec<Deadline> {
+    let mut deadlines: Vec<Deadline> = articles
+        .into_iter()
+        .flat_map(|article| article.properties.deadlines.iter().cloned())
+        .filter(|d| d.date >= today)
+        .collect();
+    deadlines.sort_by(|a, b| {
+        a.date
+            .cmp(&b.date)
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| a.article_url.cmp(&b.article_url))
+    });
+    deadlines
+}
+
+/// 直近 [`DEADLINE_LOOKBACK_DAYS`] 日に公開された保存済みの記事から，`today` 以降の締切を日付順に返す
+pub async fn query_upcoming_deadlines(store: &dyn ArticleStore, today: NaiveDate) -> AppResult<Vec<Deadline>> {
+    let since = (today - Duration::days(DEADLINE_LOOKBACK_DAYS))
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest());
+    let articles: Vec<WebArticle> = store
+        .query(&ArticleQuery {
+            since,
+            ..Default::default()
+        })
+        .await?
+        .into_iter()
+        .map(|stored| stored.article)
+        .collect();
+    Ok(upcoming_deadlines(&articles, today))
+}
+
+/// ダイジェスト用の「今後の締切」セクション
+pub fn render_deadlines_markdown(deadlines: &[Deadline]) -> String {
+    if deadlines.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("## Upcoming Deadlines\n\n");
+    for d in deadlines {
+        out.push_str(&format!(
+            "- **{}** ({}) [{}]({})",
+            d.date.format("%Y-%m-%d"),
+            d.kind,
+            d.title,
+            d.article_url
+        ));
+        if let Some(venue) = &d.venue {
+            out.push_str(&format!(" — {}", venue));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn split_sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.split(['\n', '。', '．'])
+        .flat_map(|line| line.split(". "))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// 「会場: ○○」「Venue: ○○」「開催地：○○」形式の記述から開催地を取り出す
+pub fn extract_venue(text: &str) -> Option<String> {
+    regexes::venue()
+        .captures(text)
+        .map(|cap| cap[1].trim().trim_end_matches(['.', ',']).to_string())
+        .filter(|venue| !venue.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::sqlite::SqliteStore;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::TimeZone;
+
+    fn article(text: &str) -> WebArticle {
+        ArticleBuilder::new("ACL 2026 Call for Papers")
+            .with_site("Conference News", "https://conf.example.com")
+            .with_url("https://conf.example.com/cfp")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap())
+            .with_text(text)
+            .build()
+    }
+
+    #[test]
+    fn test_detect_deadlines() {
+        let article = article(
+            "Abstract deadline: November 10, 2025. Paper submission deadline: 2025-11-17\n\
+             採否通知は2026年1月20日です。会場: 東京国際フォーラム\n\
+             前回は2024年7月1日に開催されました。",
+        );
+        let deadlines = detect_deadlines(&article);
+        let kinds: Vec<_> = deadlines.iter().map(|d| (d.kind, d.date)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DeadlineKind::Abstract, NaiveDate::from_ymd_opt(2025, 11, 10).unwrap()),
+                (DeadlineKind::Submission, NaiveDate::from_ymd_opt(2025, 11, 17).unwrap()),
+            ]
+        );
+        assert_eq!(deadlines[0].venue.as_deref(), Some("東京国際フォーラム"));
+    }
+
+    #[test]
+    fn test_upcoming_deadlines_and_render() {
+        let mut cfp = article("Submission deadline: 2025-11-17. Venue: Vienna, Austria");
+        let today = NaiveDate::from_ymd_opt(2025, 11, 1).unwrap();
+        // 記録していない記事の締切は一覧に出ない
+        assert!(upcoming_deadlines(std::slice::from_ref(&cfp), today).is_empty());
+        cfp.properties.deadlines = detect_deadlines(&cfp);
+        let articles = vec![cfp];
+        let deadlines = upcoming_deadlines(&articles, today);
+        assert_eq!(deadlines.len(), 1);
+        assert!(upcoming_deadlines(&articles, NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()).is_empty());
+
+        let rendered = render_deadlines_markdown(&deadlines);
+        assert!(rendered.contains("- **2025-11-17** (submission) [ACL 2026 Call for Papers](https://conf.example.com/cfp) — Vienna, Austria"));
+        assert_eq!(deadlines[0].to_calendar_event().date, deadlines[0].date);
+    }
+
+    #[tokio::test]
+    async fn test_query_upcoming_deadlines() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let mut cfp = article("Submission deadline: 2026-06-01.");
+        cfp.properties.deadlines = detect_deadlines(&cfp);
+        store.upsert(&cfp).await.unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2025, 11, 1).unwrap();
+        let deadlines = query_upcoming_deadlines(&store, today).await.unwrap();
+        assert_eq!(deadlines.len(), 1);
+        assert_eq!(deadlines[0].kind, DeadlineKind::Submission);
+        // 締切が先でも，告知が古すぎる記事は探さない
+        let later = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap() + Duration::days(DEADLINE_LOOKBACK_DAYS + 1);
+        assert!(query_upcoming_deadlines(&store, later).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+//! 要約の品質のオフライン評価．
+//!
+//! 人手で書いた（または修正した）参照要約を JSONL（`{"url": ..., "reference": ...}`）で用意し，
+//! 保存済みの要約や別のプロンプト・モデルで作った要約（`{"url": ..., "summary": ...}`）を
+//! ROUGE-1 / ROUGE-2 / ROUGE-L と埋め込みのコサイン類似度で採点する．
+//! 日本語は分かち書きしないため，ASCII の英数字は単語単位，それ以外は1文字単位で比較する．
+
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+
+const OPENAI_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// 参照要約
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceSummary {
+    pub url: String,
+    pub reference: String,
+}
+
+/// 評価する要約
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CandidateSummary {
+    pub url: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct RougeScore {
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+impl RougeScore {
+    fn new(overlap: usize, candidate: usize, reference: usize) -> Self {
+        let precision = if candidate == 0 { 0.0 } else { overlap as f64 / candidate as f64 };
+        let recall = if reference == 0 { 0.0 } else { overlap as f64 / reference as f64 };
+        let f1 = if precision + recall == 0.0 {
+            0.0
+        } else {
+            2.0 * precision * recall / (precision + recall)
+        };
+        Self { precision, recall, f1 }
+    }
+}
+
+/// 記事1件の採点結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SummaryScore {
+    pub url: String,
+    pub rouge1: RougeScore,
+    pub rouge2: RougeScore,
+    pub rouge_l: RougeScore,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_similarity: Option<f64>,
+}
+
+/// 評価の結果（平均は F1 の平均）
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EvaluationReport {
+    pub evaluated: usize,
+    /// 参照要約はあるが評価する要約が無い記事
+    pub missing: Vec<String>,
+    pub rouge1_f1: f64,
+    pub rouge2_f1: f64,
+    pub rouge_l_f1: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_similarity: Option<f64>,
+    pub scores: Vec<SummaryScore>,
+}
+
+/// ASCII の英数字の並びは小文字の単語，それ以外の文字（空白・記号を除く）は1文字ずつのトークンにする
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            word.push(c.to_ascii_lowercase());
+            continue;
+        }
+        if !word.is_empty() {
+            tokens.push(std::mem::take(&mut word));
+        }
+        if !c.is_whitespace() && !c.is_ascii_punctuation() && !is_cjk_punctuation(c) {
+            tokens.push(c.to_string());
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+fn is_cjk_punctuation(c: char) -> bool {
+    matches!(c, '\u{3000}'..='\u{303F}' | '\u{FF01}'..='\u{FF0F}' | '\u{FF1A}'..='\u{FF20}' | '・')
+}
+
+fn ngrams(tokens: &[String], n: usize) -> HashMap<&[String], usize> {
+    let mut counts = HashMap::new();
+    if n > 0 {
+        for gram in tokens.windows(n) {
+            *counts.entry(gram).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// ROUGE-N（n-gram の一致数）
+pub fn rouge_n(candidate: &str, reference: &str, n: usize) -> RougeScore {
+    let (candidate, reference) = (tokenize(candidate), tokenize(reference));
+    let (c, r) = (ngrams(&candidate, n), ngrams(&reference, n));
+    let overlap: usize = c.iter().map(|(gram, count)| (*count).min(r.get(gram).copied().unwrap_or(0))).sum();
+    RougeScore::new(overlap, c.values().sum(), r.values().sum())
+}
+
+/// ROUGE-L（最長共通部分列）
+pub fn rouge_l(candidate: &str, reference: &str) -> RougeScore {
+    let (candidate, reference) = (tokenize(candidate), tokenize(reference));
+    let mut row = vec![0usize; reference.len() + 1];
+    for c in &candidate {
+        let mut diagonal = 0;
+        for (j, r) in reference.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if c == r { diagonal + 1 } else { above.max(row[j]) };
+            diagonal = above;
+        }
+    }
+    RougeScore::new(row[reference.len()], candidate.len(), reference.len())
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| *x as f64 * *y as f64).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 { 0.0 } else { dot / denominator }
+}
+
+/// OpenAI の Embeddings API で埋め込みを求める
+#[derive(Debug, Clone)]
+pub struct EmbeddingClient {
+    client: request::Client,
+    api_key: String,
+    model: String,
+    endpoint: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl EmbeddingClient {
+    pub fn new(api_key: &str, model: &str) -> Self {
+        Self {
+            client: request::Client::new(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            endpoint: OPENAI_EMBEDDINGS_URL.to_string(),
+        }
+    }
+
+    /// `OPENAI_API_KEY` が設定されていれば作成する．モデルは `CLIPPER_EMBEDDING_MODEL`（デフォルト `text-embedding-3-small`）．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let Some(api_key) = std::env::var("OPENAI_API_KEY").ok().filter(|k| !k.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let model: String = parse_env_or("CLIPPER_EMBEDDING_MODEL", DEFAULT_EMBEDDING_MODEL.to_string())?;
+        Ok(Some(Self::new(api_key.trim(), &model)))
+    }
+
+    pub async fn embed(&self, texts: &[&str]) -> AppResult<Vec<Vec<f32>>> {
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&json!({ "model": self.model, "input": texts }))
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(AppError::LlmError(format!("{}: {}", status, body)));
+        }
+        let mut data = serde_json::from_str::<EmbeddingResponse>(&body)?.data;
+        data.sort_by_key(|d| d.index);
+        if data.len() != texts.len() {
+            return Err(AppError::LlmError(format!(
+                "expected {} embeddings, got {}",
+                texts.len(),
+                data.len()
+            )));
+        }
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// 1行1件の JSON を読み込む（空行は無視する）
+pub fn load_jsonl<T: DeserializeOwned>(path: &Path) -> AppResult<Vec<T>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::InternalError(format!("Failed to read {:?}: {}", path, e)))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| AppError::InternalError(format!("{:?} line {}: {}", path, i + 1, e)))
+        })
+        .collect()
+}
+
+/// 参照要約ごとに `candidates`（URL → 要約）を採点する
+pub async fn evaluate(
+    references: &[ReferenceSummary],
+    candidates: &HashMap<String, String>,
+    embedder: Option<&EmbeddingClient>,
+) -> AppResult<EvaluationReport> {
+    let mut report = EvaluationReport::default();
+    for reference in references {
+        let Some(candidate) = candidates.get(&reference.url).filter(|c| !c.trim().is_empty()) else {
+            report.missing.push(reference.url.clone());
+            continue;
+        };
+        let embedding_similarity = match embedder {
+            Some(embedder) => {
+                let vectors = embedder.embed(&[candidate.as_str(), reference.reference.as_str()]).await?;
+                Some(cosine_similarity(&vectors[0], &vectors[1]))
+            }
+            None => None,
+        };
+        report.scores.push(SummaryScore {
+            url: reference.url.clone(),
+            rouge1: rouge_n(candidate, &reference.reference, 1),
+            rouge2: rouge_n(candidate, &reference.reference, 2),
+            rouge_l: rouge_l(candidate, &reference.reference),
+            embedding_similarity,
+        });
+    }
+
+    report.evaluated = report.scores.len();
+    if report.evaluated > 0 {
+        let mean = |f: fn(&SummaryScore) -> f64| report.scores.iter().map(f).sum::<f64>() / report.evaluated as f64;
+        report.rouge1_f1 = mean(|s| s.rouge1.f1);
+        report.rouge2_f1 = mean(|s| s.rouge2.f1);
+        report.rouge_l_f1 = mean(|s| s.rouge_l.f1);
+        if embedder.is_some() {
+            report.embedding_similarity = Some(mean(|s| s.embedding_similarity.unwrap_or_default()));
+        }
+    }
+    Ok(report)
+}
+
+/// 評価結果のテキスト表示
+pub fn render_evaluation_text(report: &EvaluationReport) -> String {
+    let mut out = format!(
+        "Evaluated: {} (missing: {})\nROUGE-1 F1: {:.4}\nROUGE-2 F1: {:.4}\nROUGE-L F1: {:.4}\n",
+        report.evaluated,
+        report.missing.len(),
+        report.rouge1_f1,
+        report.rouge2_f1,
+        report.rouge_l_f1
+    );
+    if let Some(similarity) = report.embedding_similarity {
+        out.push_str(&format!("Embedding similarity: {:.4}\n", similarity));
+    }
+    for url in &report.missing {
+        out.push_str(&format!("missing: {}\n", url));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Rust 1.90 を公開。"), vec!["rust", "1", "90", "を", "公", "開"]);
+    }
+
+    #[test]
+    fn test_rouge_scores() {
+        let score = rouge_n("the cat sat", "the cat sat down", 1);
+        assert_eq!(score.precision, 1.0);
+        assert_eq!(score.recall, 0.75);
+        assert!((score.f1 - 6.0 / 7.0).abs() < 1e-9);
+
+        assert_eq!(rouge_n("the cat sat", "the cat sat down", 2).recall, 2.0 / 3.0);
+        assert_eq!(rouge_l("a b c d", "a c d").f1, 2.0 * 0.75 / 1.75);
+        assert_eq!(rouge_n("", "abc", 1).f1, 0.0);
+        assert_eq!(rouge_l("新モデルを発表", "新モデルを発表").f1, 1.0);
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_reports_missing_and_means() {
+        let references = vec![
+            ReferenceSummary {
+                url: "https://example.com/1".into(),
+                reference: "新しい LLM を公開".into(),
+            },
+            ReferenceSummary {
+                url: "https://example.com/2".into(),
+                reference: "脆弱性を修正".into(),
+            },
+        ];
+        let candidates = HashMap::from([("https://example.com/1".to_string(), "新しい LLM を公開".to_string())]);
+        let report = evaluate(&references, &candidates, None).await.unwrap();
+        assert_eq!(report.evaluated, 1);
+        assert_eq!(report.missing, vec!["https://example.com/2"]);
+        assert_eq!(report.rouge1_f1, 1.0);
+        assert!(report.embedding_similarity.is_none());
+        assert!(render_evaluation_text(&report).contains("ROUGE-L F1: 1.0000"));
+    }
+}
@@ -0,0 +1,286 @@
+//! トピック分類の few-shot 例の管理．
+//!
+//! フィードバック API（`POST /feedback`）で利用者が修正した分類結果を例として保存し，
+//! LLM で分類するときに埋め込みの近い例をプロンプトに含める．ファインチューニングをせずに
+//! 日本語の技術記事での分類の精度を上げるためのもの．例には記事の本文の先頭を含むため，
+//! `CLIPPER_STORAGE_KEY` を設定している場合は1行ずつ暗号化して保存する．
+
+use crate::enrichment::classifier::{Classification, classification_input};
+use crate::enrichment::evaluation::{EmbeddingClient, cosine_similarity};
+use crate::models::web_article::WebArticle;
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::encryption::{self, StorageCipher};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// プロンプトに含める例の数を指定する環境変数（`0` で無効）
+pub const FEW_SHOT_COUNT_ENV: &str = "CLIPPER_FEW_SHOT_EXAMPLES";
+/// データディレクトリ内の例のファイル
+pub const FEW_SHOT_EXAMPLES_FILE: &str = "few_shot_examples.jsonl";
+
+const DEFAULT_FEW_SHOT_COUNT: usize = 3;
+/// 保存する例の上限（超えた場合は古い例から捨てる）
+const MAX_STORED_EXAMPLES: usize = 500;
+/// 例として保存する入力の最大文字数（プロンプトが長くなりすぎないように）
+const MAX_EXAMPLE_INPUT_CHARS: usize = 1000;
+
+/// 利用者が正解を付けた分類の例
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabeledExample {
+    pub article_url: String,
+    /// 分類器に渡すテキスト（タイトル，概要，本文の先頭）
+    pub input: String,
+    pub classification: Classification,
+    /// `input` の埋め込み（初めて例を選ぶときに計算する）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    pub created_at: DateTime<Local>,
+}
+
+impl LabeledExample {
+    pub fn new(article: &WebArticle, classification: Classification) -> Self {
+        Self {
+            article_url: article.article_url.clone(),
+            input: classification_input(article).chars().take(MAX_EXAMPLE_INPUT_CHARS).collect(),
+            classification,
+            embedding: None,
+            created_at: Local::now(),
+        }
+    }
+}
+
+/// 1行1件の JSONL ファイルに保存する例の一覧
+#[derive(Debug)]
+pub struct ExampleStore {
+    path: PathBuf,
+    cipher: Option<StorageCipher>,
+    examples: Mutex<Vec<LabeledExample>>,
+}
+
+impl ExampleStore {
+    /// `path` から例を読み込む（ファイルが無ければ空）．`cipher` を指定すると保存する行を暗号化する．
+    pub fn open(path: impl Into<PathBuf>, cipher: Option<StorageCipher>) -> AppResult<Self> {
+        let path = path.into();
+        let examples = match std::fs::read_to_string(&path) {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    let line = encryption::open(cipher.as_ref(), line.to_string())?;
+                    Ok(serde_json::from_str(&line)?)
+                })
+                .collect::<AppResult<Vec<LabeledExample>>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(AppError::InternalError(format!("Failed to read {:?}: {}", path, e))),
+        };
+        Ok(Self {
+            path,
+            cipher,
+            examples: Mutex::new(examples),
+        })
+    }
+
+    pub fn examples(&self) -> Vec<LabeledExample> {
+        self.lock().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// 例を追加して保存する．同じ記事の例は置き換える．
+    pub fn add(&self, example: LabeledExample) -> AppResult<()> {
+        let mut examples = self.lock();
+        examples.retain(|e| e.article_url != example.article_url);
+        examples.push(example);
+        if examples.len() > MAX_STORED_EXAMPLES {
+            let excess = examples.len() - MAX_STORED_EXAMPLES;
+            examples.drain(..excess);
+        }
+        save(&self.path, self.cipher.as_ref(), &examples)
+    }
+
+    /// 指定した記事の例（入力のテキストと埋め込み）を削除し，削除した件数を返す
+    pub fn remove_urls(&self, urls: &[String]) -> AppResult<usize> {
+        let mut examples = self.lock();
+        let before = examples.len();
+        examples.retain(|e| !urls.contains(&e.article_url));
+        let removed = before - examples.len();
+        if removed > 0 {
+            save(&self.path, self.cipher.as_ref(), &examples)?;
+        }
+        Ok(removed)
+    }
+
+    /// 計算した埋め込みを保存する（その間に置き換えられた例には書き込まない）
+    fn set_embeddings(&self, computed: &[LabeledExample]) -> AppResult<()> {
+        let mut examples = self.lock();
+        for example in examples.iter_mut().filter(|e| e.embedding.is_none()) {
+            if let Some(embedding) = computed
+                .iter()
+                .find(|c| c.article_url == example.article_url && c.input == example.input)
+                .and_then(|c| c.embedding.clone())
+            {
+                example.embedding = Some(embedding);
+            }
+        }
+        save(&self.path, self.cipher.as_ref(), &examples)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<LabeledExample>> {
+        self.examples.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn save(path: &Path, cipher: Option<&StorageCipher>, examples: &[LabeledExample]) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", parent, e)))?;
+    }
+    let mut content = String::new();
+    for example in examples {
+        content.push_str(&encryption::seal(cipher, &serde_json::to_string(example)?)?);
+        content.push('\n');
+    }
+    std::fs::write(path, content)
+        .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e)))
+}
+
+/// `query` とのコサイン類似度が高い順に `count` 件の例を返す（埋め込みの無い例は除く）
+pub fn most_similar(examples: Vec<LabeledExample>, query: &[f32], count: usize) -> Vec<LabeledExample> {
+    let mut scored: Vec<(f64, LabeledExample)> = examples
+        .into_iter()
+        .filter_map(|e| Some((cosine_similarity(e.embedding.as_deref()?, query), e)))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(count).map(|(_, e)| e).collect()
+}
+
+/// 分類する記事に近い例を埋め込みの類似度で選ぶ
+#[derive(Debug)]
+pub struct FewShotSelector {
+    store: Arc<ExampleStore>,
+    embedder: EmbeddingClient,
+    count: usize,
+}
+
+impl FewShotSelector {
+    pub fn new(store: Arc<ExampleStore>, embedder: EmbeddingClient, count: usize) -> Self {
+        Self { store, embedder, count }
+    }
+
+    /// `OPENAI_API_KEY` が設定され，`CLIPPER_FEW_SHOT_EXAMPLES`（デフォルト `3`）が `0` でなければ作成する
+    pub fn from_env(store: Arc<ExampleStore>) -> AppResult<Option<Self>> {
+        let count: usize = parse_env_or(FEW_SHOT_COUNT_ENV, DEFAULT_FEW_SHOT_COUNT)?;
+        if count == 0 {
+            return Ok(None);
+        }
+        Ok(EmbeddingClient::from_env()?.map(|embedder| Self::new(store, embedder, count)))
+    }
+
+    /// 記事に近い例を類似度の高い順に返す．分類する記事自身の例は含めない．
+    pub async fn select(&self, article: &WebArticle) -> AppResult<Vec<LabeledExample>> {
+        let mut examples = self.store.examples();
+        examples.retain(|e| e.article_url != article.article_url);
+        if examples.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 埋め込みが未計算の例はまとめて計算して保存する
+        let missing: Vec<usize> = (0..examples.len()).filter(|&i| examples[i].embedding.is_none()).collect();
+        if !missing.is_empty() {
+            let texts: Vec<&str> = missing.iter().map(|&i| examples[i].input.as_str()).collect();
+            let vectors = self.embedder.embed(&texts).await?;
+            for (&i, vector) in missing.iter().zip(vectors) {
+                examples[i].embedding = Some(vector);
+            }
+            let computed: Vec<LabeledExample> = missing.iter().map(|&i| examples[i].clone()).collect();
+            self.store.set_embeddings(&computed)?;
+        }
+
+        let input = classification_input(article);
+        let query = self
+            .embedder
+            .embed(&[input.as_str()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::LlmError("Empty embedding response".into()))?;
+        Ok(most_similar(examples, &query, self.count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    fn example(url: &str, embedding: Option<Vec<f32>>) -> LabeledExample {
+        let article = ArticleBuilder::new("タイトル").with_url(url).with_description("概要").build();
+        let mut example = LabeledExample::new(
+            &article,
+            Classification {
+                is_ai_related: true,
+                ..Default::default()
+            },
+        );
+        example.embedding = embedding;
+        example
+    }
+
+    #[test]
+    fn test_most_similar() {
+        let examples = vec![
+            example("https://example.com/1", Some(vec![1.0, 0.0])),
+            example("https://example.com/2", Some(vec![0.0, 1.0])),
+            example("https://example.com/3", None),
+            example("https://example.com/4", Some(vec![0.7, 0.7])),
+        ];
+        let selected = most_similar(examples, &[0.0, 1.0], 2);
+        let urls: Vec<&str> = selected.iter().map(|e| e.article_url.as_str()).collect();
+        assert_eq!(urls, ["https://example.com/2", "https://example.com/4"]);
+    }
+
+    #[test]
+    fn test_example_store_replaces_and_persists() {
+        let dir = std::env::temp_dir().join(format!("clipper-few-shot-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(FEW_SHOT_EXAMPLES_FILE);
+        let store = ExampleStore::open(&path, None).unwrap();
+        assert!(store.is_empty());
+        store.add(example("https://example.com/1", None)).unwrap();
+        store.add(example("https://example.com/2", None)).unwrap();
+        store.add(example("https://example.com/1", Some(vec![1.0]))).unwrap();
+
+        let reopened = ExampleStore::open(&path, None).unwrap();
+        let examples = reopened.examples();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[1].article_url, "https://example.com/1");
+        assert_eq!(examples[1].embedding, Some(vec![1.0]));
+        assert!(examples[0].input.starts_with("Title: タイトル\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_example_store_encrypts_lines() {
+        let dir = std::env::temp_dir().join(format!("clipper-few-shot-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(FEW_SHOT_EXAMPLES_FILE);
+        let cipher = StorageCipher::new(&[7u8; 32]).unwrap();
+        let store = ExampleStore::open(&path, Some(cipher.clone())).unwrap();
+        store.add(example("https://example.com/1", Some(vec![1.0]))).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.lines().all(encryption::is_encrypted));
+        assert!(!content.contains("タイトル"));
+        let reopened = ExampleStore::open(&path, Some(cipher)).unwrap();
+        assert_eq!(reopened.examples()[0].article_url, "https://example.com/1");
+        assert!(ExampleStore::open(&path, None).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,350 @@
+//! 利用者の行動（記事を開いた，スターを付けた，非表示にした）から学習する関心の重み．
+//!
+//! 関心はトピック（`topic:ai`），サイトのカテゴリ（`category:security`），サイト（`site:Gigazine`）ごとの重みで，
+//! 行動のたびに加減算する．重みは半減期（デフォルト30日）で 0 に近づくため，最近の行動ほど強く効く．
+//! 固定（pin）した重みは減衰せず，行動でも変わらない．重みは LLM にかける記事の順位付けに使う．
+
+use crate::enrichment::classifier::{Classifier, KeywordClassifier};
+use crate::models::fairness::SiteCategory;
+use crate::models::web_article::WebArticle;
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use strum::{Display, EnumString};
+
+/// 重みの半減期（日）を指定する環境変数
+pub const INTEREST_HALF_LIFE_ENV: &str = "CLIPPER_INTEREST_HALF_LIFE_DAYS";
+/// データディレクトリ内の重みのファイル
+pub const INTEREST_WEIGHTS_FILE: &str = "interest_weights.json";
+
+const DEFAULT_HALF_LIFE_DAYS: f64 = 30.0;
+/// 重みの絶対値の上限
+pub const MAX_INTEREST_WEIGHT: f64 = 10.0;
+/// 減衰してこれより小さくなった（固定していない）重みは捨てる
+const NEGLIGIBLE_WEIGHT: f64 = 0.01;
+/// 重みの合計がこの値だけ増えると順位付けのスコアが2倍になる
+const DOUBLING_WEIGHT: f64 = 5.0;
+
+/// 利用者の行動
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Interaction {
+    Opened,
+    Starred,
+    Dismissed,
+}
+
+impl Interaction {
+    /// 行動1回あたりの重みの増減
+    pub fn delta(self) -> f64 {
+        match self {
+            Interaction::Opened => 1.0,
+            Interaction::Starred => 3.0,
+            Interaction::Dismissed => -2.0,
+        }
+    }
+}
+
+/// 記事に対応する関心のキー
+pub fn interest_keys(article: &WebArticle) -> Vec<String> {
+    let p = &article.properties;
+    let mut keys = Vec::new();
+    for (flag, topic) in [
+        (p.is_ai_related, "ai"),
+        (p.is_security_related, "security"),
+        (p.is_it_related, "it"),
+        (p.is_new_technology_related, "technology"),
+        (p.is_new_product_related, "product"),
+        (p.is_new_academic_paper_related, "paper"),
+    ] {
+        if flag.unwrap_or(false) {
+            keys.push(format!("topic:{}", topic));
+        }
+    }
+    keys.push(format!("category:{}", SiteCategory::from_site_name(&article.site.name)));
+    keys.push(format!("site:{}", article.site.name));
+    keys
+}
+
+/// 保存する重み（`weight` は `updated_at` 時点の値）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredWeight {
+    weight: f64,
+    #[serde(default)]
+    pinned: bool,
+    updated_at: DateTime<Local>,
+}
+
+impl StoredWeight {
+    /// `now` 時点の減衰後の重み
+    fn current(&self, now: DateTime<Local>, half_life_days: f64) -> f64 {
+        if self.pinned {
+            return self.weight;
+        }
+        let age_days = (now - self.updated_at).num_seconds().max(0) as f64 / 86_400.0;
+        self.weight * 0.5f64.powf(age_days / half_life_days)
+    }
+}
+
+/// 学習した関心（`GET /interests` で返す）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LearnedInterest {
+    pub key: String,
+    /// 減衰後の重み
+    pub weight: f64,
+    pub pinned: bool,
+    pub updated_at: DateTime<Local>,
+}
+
+/// JSON ファイルに保存する関心の重み
+#[derive(Debug)]
+pub struct InterestModel {
+    path: PathBuf,
+    half_life_days: f64,
+    weights: Mutex<BTreeMap<String, StoredWeight>>,
+}
+
+impl InterestModel {
+    /// `path` から重みを読み込む（ファイルが無ければ空）
+    pub fn open(path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+        let weights = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(AppError::InternalError(format!("Failed to read {:?}: {}", path, e))),
+        };
+        Ok(Self {
+            path,
+            half_life_days: DEFAULT_HALF_LIFE_DAYS,
+            weights: Mutex::new(weights),
+        })
+    }
+
+    /// `data_dir` の重みを読み込み，半減期を `CLIPPER_INTEREST_HALF_LIFE_DAYS`（デフォルト `30`）にする
+    pub fn from_env(data_dir: &Path) -> AppResult<Self> {
+        let half_life_days: f64 = parse_env_or(INTEREST_HALF_LIFE_ENV, DEFAULT_HALF_LIFE_DAYS)?;
+        if half_life_days.is_nan() || half_life_days <= 0.0 {
+            return Err(AppError::InternalError(format!(
+                "Invalid {}: {}",
+                INTEREST_HALF_LIFE_ENV, half_life_days
+            )));
+        }
+        Ok(Self::open(data_dir.join(INTEREST_WEIGHTS_FILE))?.with_half_life(half_life_days))
+    }
+
+    pub fn with_half_life(mut self, half_life_days: f64) -> Self {
+        self.half_life_days = half_life_days;
+        self
+    }
+
+    /// 記事への行動を記録し，変化した関心を返す．固定した関心は変えない．
+    pub fn record(
+        &self,
+        article: &WebArticle,
+        interaction: Interaction,
+        now: DateTime<Local>,
+    ) -> AppResult<Vec<LearnedInterest>> {
+        let mut weights = self.lock();
+        let mut changed = Vec::new();
+        for key in interest_keys(article) {
+            let current = weights.get(&key).map(|w| (w.current(now, self.half_life_days), w.pinned));
+            if current.is_some_and(|(_, pinned)| pinned) {
+                continue;
+            }
+            let weight = current.map_or(0.0, |(w, _)| w) + interaction.delta();
+            let stored = StoredWeight {
+                weight: weight.clamp(-MAX_INTEREST_WEIGHT, MAX_INTEREST_WEIGHT),
+                pinned: false,
+                updated_at: now,
+            };
+            changed.push(learned(&key, &stored, now, self.half_life_days));
+            weights.insert(key, stored);
+        }
+        // 減衰しきった関心は捨てる
+        let half_life_days = self.half_life_days;
+        weights.retain(|_, w| w.pinned || w.current(now, half_life_days).abs() >= NEGLIGIBLE_WEIGHT);
+        save(&self.path, &weights)?;
+        Ok(changed)
+    }
+
+    /// 重みの大きい順の関心の一覧
+    pub fn interests(&self, now: DateTime<Local>) -> Vec<LearnedInterest> {
+        let mut interests: Vec<LearnedInterest> = self
+            .lock()
+            .iter()
+            .map(|(key, w)| learned(key, w, now, self.half_life_days))
+            .collect();
+        interests.sort_by(|a, b| b.weight.total_cmp(&a.weight).then_with(|| a.key.cmp(&b.key)));
+        interests
+    }
+
+    /// 関心の重みを固定する
+    pub fn pin(&self, key: &str, weight: f64, now: DateTime<Local>) -> AppResult<LearnedInterest> {
+        if !weight.is_finite() {
+            return Err(AppError::InternalError(format!("Invalid interest weight: {}", weight)));
+        }
+        let stored = StoredWeight {
+            weight: weight.clamp(-MAX_INTEREST_WEIGHT, MAX_INTEREST_WEIGHT),
+            pinned: true,
+            updated_at: now,
+        };
+        let mut weights = self.lock();
+        weights.insert(key.to_string(), stored.clone());
+        save(&self.path, &weights)?;
+        Ok(learned(key, &stored, now, self.half_life_days))
+    }
+
+    /// 固定を解除する．重みは解除した時点から減衰する．関心が無ければ `None`．
+    pub fn unpin(&self, key: &str, now: DateTime<Local>) -> AppResult<Option<LearnedInterest>> {
+        let mut weights = self.lock();
+        let Some(stored) = weights.get_mut(key) else {
+            return Ok(None);
+        };
+        *stored = StoredWeight {
+            weight: stored.current(now, self.half_life_days),
+            pinned: false,
+            updated_at: now,
+        };
+        let interest = learned(key, stored, now, self.half_life_days);
+        save(&self.path, &weights)?;
+        Ok(Some(interest))
+    }
+
+    /// 記事に対応する関心の重みの合計
+    pub fn score(&self, article: &WebArticle, now: DateTime<Local>) -> f64 {
+        let weights = self.lock();
+        interest_keys(article)
+            .iter()
+            .filter_map(|key| weights.get(key))
+            .map(|w| w.current(now, self.half_life_days))
+            .sum()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, StoredWeight>> {
+        self.weights.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn learned(key: &str, stored: &StoredWeight, now: DateTime<Local>, half_life_days: f64) -> LearnedInterest {
+    LearnedInterest {
+        key: key.to_string(),
+        weight: stored.current(now, half_life_days),
+        pinned: stored.pinned,
+        updated_at: stored.updated_at,
+    }
+}
+
+fn save(path: &Path, weights: &BTreeMap<String, StoredWeight>) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", parent, e)))?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(weights)?)
+        .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e)))
+}
+
+/// 順位付けのスコアに掛ける重み（関心の合計が 0 で 1，+5 で 2 倍，-5 で半分．0.25〜4 に収める）
+pub fn interest_weight(score: f64) -> f64 {
+    2f64.powf(score / DOUBLING_WEIGHT).clamp(0.25, 4.0)
+}
+
+/// 分類前の記事の URL ごとの順位付けの重み．トピックはキーワード分類で推定してから重みを求める．
+pub async fn interest_weights(
+    model: &InterestModel,
+    articles: &[WebArticle],
+    now: DateTime<Local>,
+) -> HashMap<String, f64> {
+    let mut weights = HashMap::new();
+    for article in articles {
+        let mut article = article.clone();
+        if let Ok(classification) = KeywordClassifier.classify(&article).await {
+            classification.apply(&mut article.properties);
+        }
+        weights.insert(article.article_url.clone(), interest_weight(model.score(&article, now)));
+    }
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap()
+    }
+
+    fn article(site: &str, ai: bool) -> WebArticle {
+        let mut article = ArticleBuilder::new("タイトル")
+            .with_site(site, "https://example.com")
+            .with_url(format!("https://example.com/{}", site))
+            .with_timestamp(now())
+            .build();
+        article.properties.is_ai_related = Some(ai);
+        article
+    }
+
+    fn model() -> (PathBuf, InterestModel) {
+        let dir = std::env::temp_dir().join(format!("clipper-interests-{}", uuid::Uuid::new_v4()));
+        let model = InterestModel::open(dir.join(INTEREST_WEIGHTS_FILE)).unwrap();
+        (dir, model)
+    }
+
+    #[test]
+    fn test_interest_keys() {
+        assert_eq!(
+            interest_keys(&article("JPCERT", true)),
+            ["topic:ai", "category:security", "site:JPCERT"]
+        );
+    }
+
+    #[test]
+    fn test_record_decays_and_persists() {
+        let (dir, model) = model();
+        model.record(&article("Gigazine", true), Interaction::Starred, now()).unwrap();
+        model.record(&article("Gigazine", false), Interaction::Dismissed, now()).unwrap();
+        let weight = |model: &InterestModel, key: &str, at| {
+            model.interests(at).into_iter().find(|i| i.key == key).map(|i| i.weight)
+        };
+        assert_eq!(weight(&model, "topic:ai", now()), Some(3.0));
+        assert_eq!(weight(&model, "site:Gigazine", now()), Some(1.0));
+
+        // 半減期（30日）が経つと重みは半分になる
+        let later = now() + Duration::days(30);
+        let reopened = InterestModel::open(dir.join(INTEREST_WEIGHTS_FILE)).unwrap();
+        assert!((weight(&reopened, "topic:ai", later).unwrap() - 1.5).abs() < 1e-9);
+        assert_eq!(reopened.interests(later)[0].key, "topic:ai");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pinned_interest_does_not_change() {
+        let (dir, model) = model();
+        model.pin("site:Gigazine", -4.0, now()).unwrap();
+        let later = now() + Duration::days(90);
+        model.record(&article("Gigazine", false), Interaction::Starred, later).unwrap();
+        let score = model.score(&article("Gigazine", false), later);
+        // site:Gigazine は固定した -4，category:news は +3
+        assert!((score + 1.0).abs() < 1e-9);
+
+        let unpinned = model.unpin("site:Gigazine", later).unwrap().unwrap();
+        assert!(!unpinned.pinned);
+        assert_eq!(model.unpin("site:Unknown", later).unwrap(), None);
+        assert!(model.pin("topic:ai", f64::NAN, later).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_interest_weight() {
+        assert!((interest_weight(0.0) - 1.0).abs() < 1e-9);
+        assert!((interest_weight(5.0) - 2.0).abs() < 1e-9);
+        assert!((interest_weight(-5.0) - 0.5).abs() < 1e-9);
+        assert!((interest_weight(100.0) - 4.0).abs() < 1e-9);
+    }
+}
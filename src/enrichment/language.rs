@@ -0,0 +1,111 @@
+//! 記事の言語の判定．
+//!
+//! ひらがな・カタカナを含むテキストは日本語とし，それ以外は whatlang の判定が信頼できる場合だけ採用する．
+//! 言語は ISO 639-1 の2文字のコード（`ja`，`en` など，対応が無い言語は ISO 639-3）で `WebArticle.lang` に保存する．
+
+use crate::models::web_article::WebArticle;
+
+/// 判定に使う最大文字数
+const MAX_DETECTION_CHARS: usize = 2000;
+
+/// テキストの言語（判定できなければ `None`）
+pub fn detect_language(text: &str) -> Option<String> {
+    let text: String = text.chars().take(MAX_DETECTION_CHARS).collect();
+    if text.chars().any(is_kana) {
+        return Some("ja".to_string());
+    }
+    let info = whatlang::detect(&text).filter(|info| info.is_reliable())?;
+    Some(iso_639_1(info.lang().code()).to_string())
+}
+
+/// 記事のタイトル・概要・本文から言語を判定する
+pub fn detect_article_language(article: &WebArticle) -> Option<String> {
+    detect_language(&format!("{}\n{}\n{}", article.title, article.description, article.text))
+}
+
+/// 言語が未設定の記事の言語を判定して設定し，設定した件数を返す
+pub fn detect_languages(articles: &mut [WebArticle]) -> usize {
+    let mut detected = 0;
+    for article in articles.iter_mut().filter(|a| a.lang.is_none()) {
+        article.lang = detect_article_language(article);
+        detected += usize::from(article.lang.is_some());
+    }
+    detected
+}
+
+/// `ja-JP` や `en_US` などの言語タグの主言語（小文字）
+pub fn primary_language(tag: &str) -> Option<String> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_lowercase();
+    (!primary.is_empty()).then_some(primary)
+}
+
+/// 文を空白で区切らない言語か（日本語・中国語）
+pub fn is_unspaced_language(lang: &str) -> bool {
+    matches!(primary_language(lang).as_deref(), Some("ja" | "zh"))
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c as u32, 0x3041..=0x309F | 0x30A0..=0x30FF | 0xFF66..=0xFF9F)
+}
+
+/// whatlang の ISO 639-3 のコードを ISO 639-1 に変換する（対応が無ければそのまま）
+fn iso_639_1(code: &str) -> &str {
+    match code {
+        "eng" => "en",
+        "jpn" => "ja",
+        "cmn" => "zh",
+        "kor" => "ko",
+        "fra" => "fr",
+        "deu" => "de",
+        "spa" => "es",
+        "por" => "pt",
+        "ita" => "it",
+        "rus" => "ru",
+        "ukr" => "uk",
+        "nld" => "nl",
+        "pol" => "pl",
+        "tur" => "tr",
+        "vie" => "vi",
+        "tha" => "th",
+        "ind" => "id",
+        "ara" => "ar",
+        "hin" => "hi",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("Rust の新しいバージョンがリリースされた").as_deref(), Some("ja"));
+        let english = "The new version of the compiler was released today with many improvements to performance.";
+        assert_eq!(detect_language(english).as_deref(), Some("en"));
+        assert_eq!(detect_language(""), None);
+        assert_eq!(detect_language("2025-10-01"), None);
+    }
+
+    #[test]
+    fn test_detect_languages_keeps_existing() {
+        let mut articles = vec![
+            ArticleBuilder::new("新しいモデルを発表").build(),
+            ArticleBuilder::new("新しいモデルを発表").build(),
+        ];
+        articles[1].lang = Some("en".into());
+        assert_eq!(detect_languages(&mut articles), 1);
+        assert_eq!(articles[0].lang.as_deref(), Some("ja"));
+        assert_eq!(articles[1].lang.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn test_primary_language() {
+        assert_eq!(primary_language("en-US").as_deref(), Some("en"));
+        assert_eq!(primary_language("ja_JP").as_deref(), Some("ja"));
+        assert_eq!(primary_language(" "), None);
+        assert!(is_unspaced_language("ja"));
+        assert!(!is_unspaced_language("en-us"));
+    }
+}
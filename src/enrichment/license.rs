@@ -0,0 +1,206 @@
+//! 記事のライセンス（Creative Commons などの meta タグ，著作権表示）の検出と，転載時に必要な帰属表示．
+//!
+//! 検出したライセンスは `WebArticleProperty::license` に保存し，エクスポートやダイジェストでは
+//! パブリックドメイン以外の記事に出典・ライセンス・著作権表示を添える．
+
+use crate::models::web_article::WebArticle;
+use crate::shared::regexes;
+use crate::shared::selectors::cached_selector;
+use crate::shared::text::truncate_text;
+use serde::{Deserialize, Serialize};
+
+/// ライセンスの URL を示す要素
+const LICENSE_LINK_SELECTORS: &[&str] = &["link[rel~='license']", "a[rel~='license']"];
+/// ライセンス・権利を示す meta タグの `name`（小文字）
+const LICENSE_META_NAMES: &[&str] =
+    &["license", "dcterms.license", "dc.license", "dc.rights", "dcterms.rights", "rights"];
+/// 著作権表示を探す要素（本文中の「Copyright」の誤検出を避けるためフッターなどに限る）
+const COPYRIGHT_SELECTORS: &[&str] = &["footer", ".copyright", "#copyright", "small"];
+/// 著作権表示の最大文字数
+const MAX_COPYRIGHT_CHARS: usize = 120;
+
+/// 記事のライセンス情報
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArticleLicense {
+    /// ライセンス名（例: `CC BY 4.0`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// 著作権表示（例: `© 2025 Example Inc.`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+}
+
+impl ArticleLicense {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.url.is_none() && self.copyright.is_none()
+    }
+
+    /// CC0 やパブリックドメインマークなど，帰属表示が不要なライセンスか
+    pub fn is_public_domain(&self) -> bool {
+        self.copyright.is_none()
+            && self
+                .name
+                .as_deref()
+                .is_some_and(|name| name.starts_with("CC0") || name.starts_with("Public Domain"))
+    }
+
+    /// 転載時に出典の表示が必要か
+    pub fn requires_attribution(&self) -> bool {
+        !self.is_empty() && !self.is_public_domain()
+    }
+}
+
+/// ページの HTML からライセンス情報を検出する（見つからない場合は `None`）
+pub fn detect_license(raw_html: &str) -> Option<ArticleLicense> {
+    let doc = scraper::Html::parse_document(raw_html);
+    let mut license = ArticleLicense::default();
+
+    for css in LICENSE_LINK_SELECTORS {
+        let Ok(selector) = cached_selector(css) else { continue };
+        if let Some(href) = doc.select(selector).find_map(|e| e.value().attr("href")).map(str::trim)
+            && !href.is_empty()
+        {
+            license.url = Some(href.to_string());
+            break;
+        }
+    }
+
+    if let Ok(selector) = cached_selector("meta[name][content]") {
+        for meta in doc.select(selector) {
+            let name = meta.value().attr("name").unwrap_or_default().to_lowercase();
+            let content = meta.value().attr("content").unwrap_or_default().trim();
+            if content.is_empty() {
+                continue;
+            }
+            if name == "copyright"
+                || (LICENSE_META_NAMES.contains(&name.as_str()) && regexes::copyright_line().is_match(content))
+            {
+                license.copyright.get_or_insert_with(|| truncate(content));
+            } else if LICENSE_META_NAMES.contains(&name.as_str()) {
+                if content.starts_with("http://") || content.starts_with("https://") {
+                    license.url.get_or_insert_with(|| content.to_string());
+                } else {
+                    license.name.get_or_insert_with(|| truncate(content));
+                }
+            }
+        }
+    }
+
+    if license.copyright.is_none() {
+        license.copyright = COPYRIGHT_SELECTORS.iter().find_map(|css| {
+            let selector = cached_selector(css).ok()?;
+            doc.select(selector).find_map(|e| {
+                let text = e.text().collect::<Vec<_>>().join(" ");
+                regexes::copyright_line().find(&text).map(|m| truncate(m.as_str()))
+            })
+        });
+    }
+
+    if let Some(name) = license.url.as_deref().and_then(creative_commons_name) {
+        license.name = Some(name);
+    }
+    (!license.is_empty()).then_some(license)
+}
+
+/// Creative Commons の URL をライセンス名にする（例: `.../licenses/by-sa/4.0/` → `CC BY-SA 4.0`）
+pub fn creative_commons_name(url: &str) -> Option<String> {
+    let url = request::Url::parse(url).ok()?;
+    if !url.host_str()?.ends_with("creativecommons.org") {
+        return None;
+    }
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        ["licenses", code, version, ..] => Some(format!("CC {} {}", code.to_uppercase(), version)),
+        ["licenses", code] => Some(format!("CC {}", code.to_uppercase())),
+        ["publicdomain", "zero", version, ..] => Some(format!("CC0 {}", version)),
+        ["publicdomain", "mark", version, ..] => Some(format!("Public Domain Mark {}", version)),
+        _ => None,
+    }
+}
+
+/// 空白をまとめて最大文字数で切り詰める
+fn truncate(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    truncate_text(&text, MAX_COPYRIGHT_CHARS)
+}
+
+/// 帰属表示の各行（出典，ライセンス，著作権表示）．帰属表示が不要な記事は空．
+pub fn attribution_lines(article: &WebArticle) -> Vec<String> {
+    let Some(license) = article.properties.license.as_ref().filter(|l| l.requires_attribution()) else {
+        return Vec::new();
+    };
+    let mut lines = vec![format!(
+        "出典: {}「{}」 {}",
+        article.site.name,
+        article.title.trim(),
+        article.article_url
+    )];
+    match (&license.name, &license.url) {
+        (Some(name), Some(url)) => lines.push(format!("ライセンス: {} ({})", name, url)),
+        (Some(name), None) => lines.push(format!("ライセンス: {}", name)),
+        (None, Some(url)) => lines.push(format!("ライセンス: {}", url)),
+        (None, None) => {}
+    }
+    if let Some(copyright) = &license.copyright {
+        lines.push(copyright.clone());
+    }
+    lines
+}
+
+/// 帰属表示を1行にまとめたもの
+pub fn attribution_text(article: &WebArticle) -> Option<String> {
+    let lines = attribution_lines(article);
+    (!lines.is_empty()).then(|| lines.join(" / "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    #[test]
+    fn test_detect_creative_commons_license() {
+        let html = r#"<html><head>
+            <link rel="license" href="https://creativecommons.org/licenses/by-sa/4.0/">
+            </head><body><p>Copyright is discussed in this article.</p>
+            <footer><p>© 2025 Example Inc. All rights reserved.</p></footer></body></html>"#;
+        let license = detect_license(html).unwrap();
+        assert_eq!(license.name.as_deref(), Some("CC BY-SA 4.0"));
+        assert_eq!(license.url.as_deref(), Some("https://creativecommons.org/licenses/by-sa/4.0/"));
+        assert_eq!(license.copyright.as_deref(), Some("© 2025 Example Inc. All rights reserved."));
+        assert!(license.requires_attribution());
+    }
+
+    #[test]
+    fn test_detect_license_from_meta_and_public_domain() {
+        let html = r#"<html><head>
+            <meta name="dcterms.license" content="https://creativecommons.org/publicdomain/zero/1.0/">
+            </head><body><p>本文</p></body></html>"#;
+        let license = detect_license(html).unwrap();
+        assert_eq!(license.name.as_deref(), Some("CC0 1.0"));
+        assert!(license.is_public_domain());
+        assert!(!license.requires_attribution());
+
+        let html = r#"<meta name="copyright" content="Copyright ITmedia, Inc.">"#;
+        assert_eq!(detect_license(html).unwrap().copyright.as_deref(), Some("Copyright ITmedia, Inc."));
+        assert!(detect_license("<p>Copyright law in Japan</p>").is_none());
+    }
+
+    #[test]
+    fn test_attribution_lines() {
+        let mut article = ArticleBuilder::new("Title ").with_url("https://example.com/1").build();
+        assert!(attribution_lines(&article).is_empty());
+
+        article.properties.license = Some(ArticleLicense {
+            name: Some("CC BY 4.0".into()),
+            url: Some("https://creativecommons.org/licenses/by/4.0/".into()),
+            copyright: None,
+        });
+        assert_eq!(
+            attribution_text(&article).unwrap(),
+            "出典: Example「Title」 https://example.com/1 / ライセンス: CC BY 4.0 (https://creativecommons.org/licenses/by/4.0/)"
+        );
+    }
+}
@@ -0,0 +1,12 @@
+pub mod brief;
+pub mod classifier;
+pub mod deadlines;
+pub mod evaluation;
+pub mod few_shot;
+pub mod interests;
+pub mod language;
+pub mod license;
+pub mod relevance;
+pub mod robots;
+pub mod sampling;
+pub mod snippets;
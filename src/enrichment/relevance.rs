@@ -0,0 +1,409 @@
+//! 利用者の関心（プロファイル）に対する記事の関連度の採点．
+//!
+//! プロファイルは自由記述の関心の説明（例:「LLM の推論の最適化と Rust のツールに関心がある」）で，
+//! LLM が各記事を 0〜10 の整数で採点する．点数は記事の URL とプロファイルのバージョン（説明のハッシュ）ごとに
+//! キャッシュするため，説明を変えない限り同じ記事を採点し直さない．採点の結果は LLM にかける記事の順位付けに使う．
+
+use crate::enrichment::classifier::{OPENAI_CHAT_COMPLETIONS_URL, parse_structured_completion};
+use crate::models::web_article::WebArticle;
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// 関心の説明を指定する環境変数（未設定の場合は採点しない）
+pub const INTEREST_PROFILE_ENV: &str = "CLIPPER_INTEREST_PROFILE";
+/// プロファイルの名前を指定する環境変数
+pub const INTEREST_PROFILE_NAME_ENV: &str = "CLIPPER_INTEREST_PROFILE_NAME";
+/// データディレクトリ内の採点結果のキャッシュ
+pub const RELEVANCE_CACHE_FILE: &str = "relevance_cache.jsonl";
+
+const DEFAULT_PROFILE_NAME: &str = "default";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+/// LLM に渡す本文の最大文字数
+const MAX_INPUT_CHARS: usize = 1500;
+pub const MAX_RELEVANCE: u8 = 10;
+
+const SYSTEM_PROMPT: &str = "You rate how relevant a Japanese or English technology news article is to a reader. \
+The reader describes their interests below. Answer with an integer score from 0 (unrelated) to 10 \
+(exactly what the reader is looking for) and a one-sentence reason.";
+
+/// 利用者の関心
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterestProfile {
+    pub name: String,
+    /// 関心の説明（自由記述）
+    pub interests: String,
+}
+
+impl InterestProfile {
+    pub fn new(name: impl Into<String>, interests: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            interests: interests.into(),
+        }
+    }
+
+    /// `CLIPPER_INTEREST_PROFILE` が設定されていれば作成する．名前は `CLIPPER_INTEREST_PROFILE_NAME`（デフォルト `default`）．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let Some(interests) = std::env::var(INTEREST_PROFILE_ENV).ok().filter(|v| !v.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let name: String = parse_env_or(INTEREST_PROFILE_NAME_ENV, DEFAULT_PROFILE_NAME.to_string())?;
+        Ok(Some(Self::new(name.trim(), interests.trim())))
+    }
+
+    /// プロファイルのバージョン（名前と説明の SHA-256 の先頭16桁）．説明を変えると別のバージョンになる．
+    pub fn version(&self) -> String {
+        let digest = Sha256::digest(format!("{}\n{}", self.name, self.interests).as_bytes());
+        digest.iter().take(8).map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// 採点の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelevanceScore {
+    /// 0〜10
+    pub score: u8,
+    pub reason: String,
+}
+
+#[async_trait::async_trait]
+pub trait RelevanceScorer: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn score(&self, profile: &InterestProfile, article: &WebArticle) -> AppResult<RelevanceScore>;
+}
+
+/// OpenAI の Chat Completions API（Structured Outputs）で採点する
+#[derive(Debug, Clone)]
+pub struct LlmRelevanceScorer {
+    client: request::Client,
+    api_key: String,
+    model: String,
+    endpoint: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoreResponse {
+    score: i64,
+    reason: String,
+}
+
+impl LlmRelevanceScorer {
+    pub fn new(api_key: &str, model: &str) -> Self {
+        Self {
+            client: request::Client::new(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            endpoint: OPENAI_CHAT_COMPLETIONS_URL.to_string(),
+        }
+    }
+
+    /// OpenAI 互換の別エンドポイントを使う
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    /// `OPENAI_API_KEY` が設定されていれば作成する．モデルは `CLIPPER_RELEVANCE_MODEL`（デフォルト `gpt-4o-mini`）．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let Some(api_key) = std::env::var("OPENAI_API_KEY").ok().filter(|k| !k.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let model: String = parse_env_or("CLIPPER_RELEVANCE_MODEL", DEFAULT_MODEL.to_string())?;
+        Ok(Some(Self::new(api_key.trim(), &model)))
+    }
+
+    fn request_body(&self, profile: &InterestProfile, article: &WebArticle) -> serde_json::Value {
+        let source = if article.text.trim().is_empty() {
+            &article.description
+        } else {
+            &article.text
+        };
+        let text: String = source.chars().take(MAX_INPUT_CHARS).collect();
+        let system = format!("{}\n\nReader's interests: {}", SYSTEM_PROMPT, profile.interests);
+        json!({
+            "model": self.model,
+            "temperature": 0,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": format!("Title: {}\nText: {}", article.title.trim(), text.trim()) },
+            ],
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "article_relevance",
+                    "strict": true,
+                    "schema": {
+                        "type": "object",
+                        "properties": {
+                            "score": { "type": "integer" },
+                            "reason": { "type": "string" },
+                        },
+                        "required": ["score", "reason"],
+                        "additionalProperties": false,
+                    },
+                },
+            },
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl RelevanceScorer for LlmRelevanceScorer {
+    fn name(&self) -> &'static str {
+        "llm"
+    }
+    async fn score(&self, profile: &InterestProfile, article: &WebArticle) -> AppResult<RelevanceScore> {
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&self.request_body(profile, article))
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(AppError::LlmError(format!("{}: {}", status, body)));
+        }
+        let response: ScoreResponse = parse_structured_completion(&body)?;
+        Ok(RelevanceScore {
+            score: response.score.clamp(0, MAX_RELEVANCE as i64) as u8,
+            reason: response.reason,
+        })
+    }
+}
+
+/// キャッシュの1行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedScore {
+    article_url: String,
+    profile: String,
+    version: String,
+    #[serde(flatten)]
+    score: RelevanceScore,
+    scored_at: DateTime<Local>,
+}
+
+/// 記事の URL とプロファイルのバージョンごとの採点結果（追記のみの JSONL ファイル）
+#[derive(Debug)]
+pub struct RelevanceCache {
+    path: PathBuf,
+    scores: Mutex<HashMap<(String, String), RelevanceScore>>,
+}
+
+impl RelevanceCache {
+    /// `path` から読み込む（ファイルが無ければ空）．同じ記事・バージョンの行は後のものを使う．
+    pub fn open(path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+        let mut scores = HashMap::new();
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                    let cached: CachedScore = serde_json::from_str(line)?;
+                    scores.insert((cached.article_url, cached.version), cached.score);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(AppError::InternalError(format!("Failed to read {:?}: {}", path, e))),
+        }
+        Ok(Self {
+            path,
+            scores: Mutex::new(scores),
+        })
+    }
+
+    pub fn get(&self, article_url: &str, profile: &InterestProfile) -> Option<RelevanceScore> {
+        self.lock().get(&(article_url.to_string(), profile.version())).cloned()
+    }
+
+    /// 採点結果を追加してファイルに追記する
+    pub fn insert(&self, article_url: &str, profile: &InterestProfile, score: RelevanceScore) -> AppResult<()> {
+        let cached = CachedScore {
+            article_url: article_url.to_string(),
+            profile: profile.name.clone(),
+            version: profile.version(),
+            score,
+            scored_at: Local::now(),
+        };
+        let mut scores = self.lock();
+        append_line(&self.path, &serde_json::to_string(&cached)?)?;
+        scores.insert((cached.article_url, cached.version), cached.score);
+        Ok(())
+    }
+
+    /// 指定した記事の採点結果をファイルからも削除し，削除した行数を返す
+    pub fn remove_urls(&self, urls: &[String]) -> AppResult<usize> {
+        let mut scores = self.lock();
+        scores.retain(|(article_url, _), _| !urls.contains(article_url));
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(AppError::InternalError(format!("Failed to read {:?}: {}", self.path, e))),
+        };
+        let mut kept = String::new();
+        let mut removed = 0;
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let cached: CachedScore = serde_json::from_str(line)?;
+            if urls.contains(&cached.article_url) {
+                removed += 1;
+            } else {
+                kept.push_str(line);
+                kept.push('\n');
+            }
+        }
+        if removed > 0 {
+            // 書き込み途中で失敗しても残りの採点結果が壊れないよう，一時ファイルに書いてから置き換える
+            let tmp = self.path.with_extension("jsonl.tmp");
+            std::fs::write(&tmp, kept)
+                .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", tmp, e)))?;
+            std::fs::rename(&tmp, &self.path)
+                .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", self.path, e)))?;
+        }
+        Ok(removed)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<(String, String), RelevanceScore>> {
+        self.scores.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", parent, e)))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| AppError::InternalError(format!("Failed to open {:?}: {}", path, e)))?;
+    writeln!(file, "{}", line).map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e)))
+}
+
+/// 記事を採点し，URL ごとの点数を返す．キャッシュにある記事は採点し直さない．採点に失敗した記事は含めない．
+pub async fn score_articles(
+    scorer: &dyn RelevanceScorer,
+    profile: &InterestProfile,
+    cache: &RelevanceCache,
+    articles: &[WebArticle],
+) -> HashMap<String, u8> {
+    let mut scores = HashMap::new();
+    for article in articles {
+        if let Some(cached) = cache.get(&article.article_url, profile) {
+            scores.insert(article.article_url.clone(), cached.score);
+            continue;
+        }
+        match scorer.score(profile, article).await {
+            Ok(score) => {
+                scores.insert(article.article_url.clone(), score.score);
+                if let Err(e) = cache.insert(&article.article_url, profile, score) {
+                    tracing::warn!("Failed to cache relevance score: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Failed to score {} with {} scorer: {}",
+                article.article_url,
+                scorer.name(),
+                e
+            ),
+        }
+    }
+    scores
+}
+
+/// 環境変数のプロファイルで記事を採点する（プロファイルまたは API キーが無い場合は空）
+pub async fn score_articles_from_env(data_dir: &Path, articles: &[WebArticle]) -> AppResult<HashMap<String, u8>> {
+    let Some(profile) = InterestProfile::from_env()? else {
+        return Ok(HashMap::new());
+    };
+    let Some(scorer) = LlmRelevanceScorer::from_env()? else {
+        return Ok(HashMap::new());
+    };
+    let cache = RelevanceCache::open(data_dir.join(RELEVANCE_CACHE_FILE))?;
+    Ok(score_articles(&scorer, &profile, &cache, articles).await)
+}
+
+/// 順位付けのスコアに掛ける重み（0点で 0.25，5点で 1，10点で 1.75）
+pub fn relevance_weight(score: u8) -> f64 {
+    0.25 + 1.5 * f64::from(score.min(MAX_RELEVANCE)) / f64::from(MAX_RELEVANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// タイトルに `Rust` を含む記事を高く採点するテスト用の採点器
+    #[derive(Default)]
+    struct StubScorer {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl RelevanceScorer for StubScorer {
+        fn name(&self) -> &'static str {
+            "stub"
+        }
+        async fn score(&self, _profile: &InterestProfile, article: &WebArticle) -> AppResult<RelevanceScore> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let score = if article.title.contains("Rust") { 9 } else { 1 };
+            Ok(RelevanceScore {
+                score,
+                reason: String::new(),
+            })
+        }
+    }
+
+    fn article(title: &str) -> WebArticle {
+        ArticleBuilder::new(title).with_url(format!("https://example.com/{}", title)).build()
+    }
+
+    #[test]
+    fn test_profile_version_changes_with_interests() {
+        let profile = InterestProfile::new("default", "Rust のツール");
+        assert_eq!(profile.version().len(), 16);
+        assert_eq!(profile.version(), InterestProfile::new("default", "Rust のツール").version());
+        assert_ne!(profile.version(), InterestProfile::new("default", "LLM の推論").version());
+    }
+
+    #[tokio::test]
+    async fn test_score_articles_uses_cache_per_profile_version() {
+        let dir = std::env::temp_dir().join(format!("clipper-relevance-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(RELEVANCE_CACHE_FILE);
+        let scorer = StubScorer::default();
+        let profile = InterestProfile::new("default", "Rust のツール");
+        let articles = vec![article("Rust 1.90"), article("Weather")];
+
+        let cache = RelevanceCache::open(&path).unwrap();
+        let scores = score_articles(&scorer, &profile, &cache, &articles).await;
+        assert_eq!(scores["https://example.com/Rust 1.90"], 9);
+        assert_eq!(scores["https://example.com/Weather"], 1);
+        assert_eq!(scorer.calls.load(Ordering::SeqCst), 2);
+
+        // 再起動後もキャッシュを使い，説明を変えると採点し直す
+        let cache = RelevanceCache::open(&path).unwrap();
+        score_articles(&scorer, &profile, &cache, &articles).await;
+        assert_eq!(scorer.calls.load(Ordering::SeqCst), 2);
+        let changed = InterestProfile::new("default", "LLM の推論");
+        score_articles(&scorer, &changed, &cache, &articles).await;
+        assert_eq!(scorer.calls.load(Ordering::SeqCst), 4);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_relevance_weight() {
+        assert!((relevance_weight(0) - 0.25).abs() < 1e-9);
+        assert!((relevance_weight(5) - 1.0).abs() < 1e-9);
+        assert!((relevance_weight(10) - 1.75).abs() < 1e-9);
+        assert!((relevance_weight(42) - 1.75).abs() < 1e-9);
+    }
+}
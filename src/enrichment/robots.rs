@@ -0,0 +1,320 @@
+//! 記事ページの robots meta タグ・`X-Robots-Tag` ヘッダーの `noarchive` / `nosnippet` / `max-snippet` の反映．
+//!
+//! 記事ページで見つけた指定はサイト（ソース）単位で記録し，同じサイトの記事には保存する概要（`description`）を
+//! 切り詰め，`noarchive` のサイトは WARC に生の HTML を保存しない．適用した指定は `WebArticleProperty::robots` に残す．
+//! 記事ページ自身の meta タグは，WARC に保存する前に本文から読み取る．
+//! サイトごとの指定は `$CLIPPER_DATA_DIR/robots_policies.json` に保存し，再起動後も引き継ぐ．
+
+use crate::models::web_article::WebArticle;
+use crate::shared::config::data_dir;
+use crate::shared::selectors::selector;
+use crate::shared::text::truncate_text;
+use request::header::{CONTENT_TYPE, HeaderMap};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// 指定を読み取る meta タグの `name`（小文字）
+const ROBOTS_META_NAMES: &[&str] = &["robots", "googlebot"];
+const X_ROBOTS_TAG: &str = "x-robots-tag";
+/// サイトごとの指定を保存するファイル（`$CLIPPER_DATA_DIR` 直下）
+pub const ROBOTS_POLICIES_FILE: &str = "robots_policies.json";
+
+static GLOBAL_POLICIES: OnceLock<RobotsPolicies> = OnceLock::new();
+
+/// robots の指定のうち，保存する内容に関わるもの
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RobotsDirectives {
+    /// 生の HTML をアーカイブしない
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub noarchive: bool,
+    /// 概要を保存しない
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub nosnippet: bool,
+    /// 概要の最大文字数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_snippet: Option<usize>,
+}
+
+impl RobotsDirectives {
+    /// `noarchive, max-snippet:50` のようなカンマ区切りの指定を読み取る．
+    /// `X-Robots-Tag` の `googlebot: nosnippet` のようなクローラ名の接頭辞は無視する．
+    pub fn parse(content: &str) -> Self {
+        let mut directives = Self::default();
+        for token in content.split(',') {
+            let token = token.trim().to_ascii_lowercase();
+            let (key, value) = match token.split_once(':') {
+                Some((key, value)) if key.trim() == "max-snippet" => (key.trim(), value.trim()),
+                Some((_, directive)) => (directive.trim(), ""),
+                None => (token.as_str(), ""),
+            };
+            match key {
+                "noarchive" => directives.noarchive = true,
+                "nosnippet" => directives.nosnippet = true,
+                // `-1` は無制限，`0` は `nosnippet` と同じ
+                "max-snippet" => match value.parse::<i64>() {
+                    Ok(0) => directives.nosnippet = true,
+                    Ok(n) if n > 0 => directives.limit_snippet(n as usize),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        directives
+    }
+
+    /// ページの `<head>` の robots meta タグから読み取る
+    pub fn from_html(raw_html: &str) -> Self {
+        // 大きなページでも DOM 全体を構築しないよう，`<head>` の範囲だけをパースする
+        let head = head_section(raw_html);
+        let doc = scraper::Html::parse_document(head);
+        let mut directives = Self::default();
+        for meta in doc.select(selector!("meta[name][content]")) {
+            let name = meta.value().attr("name").unwrap_or_default().trim().to_ascii_lowercase();
+            if ROBOTS_META_NAMES.contains(&name.as_str()) {
+                directives.merge(&Self::parse(meta.value().attr("content").unwrap_or_default()));
+            }
+        }
+        directives
+    }
+
+    /// レスポンスの本文の robots meta タグから読み取る．`Content-Type` が HTML 以外なら指定無しとする．
+    pub fn from_html_body(headers: &HeaderMap, body: &[u8]) -> Self {
+        if !is_html(headers) {
+            return Self::default();
+        }
+        Self::from_html(&String::from_utf8_lossy(&body[..head_len(body)]))
+    }
+
+    /// `X-Robots-Tag` ヘッダー（複数ある場合は全て）から読み取る
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut directives = Self::default();
+        for value in headers.get_all(X_ROBOTS_TAG).iter().filter_map(|v| v.to_str().ok()) {
+            directives.merge(&Self::parse(value));
+        }
+        directives
+    }
+
+    /// 制限の強い方を採る
+    pub fn merge(&mut self, other: &Self) {
+        self.noarchive |= other.noarchive;
+        self.nosnippet |= other.nosnippet;
+        if let Some(limit) = other.max_snippet {
+            self.limit_snippet(limit);
+        }
+    }
+
+    fn limit_snippet(&mut self, limit: usize) {
+        self.max_snippet = Some(self.max_snippet.map_or(limit, |current| current.min(limit)));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 保存してよい概要の文字数（制限が無ければ `None`）
+    pub fn snippet_limit(&self) -> Option<usize> {
+        if self.nosnippet { Some(0) } else { self.max_snippet }
+    }
+
+    /// 記事の概要を切り詰め，適用した指定を記事に記録する．指定が無ければ何もしない．
+    pub fn apply(&self, article: &mut WebArticle) {
+        if self.is_empty() {
+            return;
+        }
+        if let Some(limit) = self.snippet_limit() {
+            article.description = truncate_text(&article.description, limit);
+        }
+        match article.properties.robots.as_mut() {
+            Some(applied) => applied.merge(self),
+            None => article.properties.robots = Some(self.clone()),
+        }
+    }
+}
+
+/// サイトごとに見つけた robots の指定
+#[derive(Debug, Default)]
+pub struct RobotsPolicies {
+    sites: Mutex<HashMap<String, RobotsDirectives>>,
+    /// 記録した指定の保存先（`None` ならメモリ上だけ）
+    path: Option<PathBuf>,
+}
+
+impl RobotsPolicies {
+    /// `path` に保存済みの指定を読み込み，以降に記録した指定もそこへ保存する
+    pub fn open(path: PathBuf) -> Self {
+        let sites = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring broken robots policies {:?}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self { sites: Mutex::new(sites), path: Some(path) }
+    }
+
+    /// サイトの記事ページで見つけた指定を記録する（既存の指定とは制限の強い方を採る）
+    pub fn record(&self, site: &str, directives: &RobotsDirectives) {
+        if directives.is_empty() {
+            return;
+        }
+        let mut sites = self.sites.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = sites.entry(site.to_string()).or_default();
+        let previous = entry.clone();
+        entry.merge(directives);
+        if *entry != previous {
+            tracing::info!("Honoring robots directives of {}: {:?}", site, entry);
+            if let Err(e) = self.save(&sites) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+
+    fn save(&self, sites: &HashMap<String, RobotsDirectives>) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let raw = serde_json::to_string_pretty(sites).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+        }
+        // 書き込み途中で失敗しても保存済みの指定が壊れないよう，一時ファイルに書いてから置き換える
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, raw).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+        std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    pub fn get(&self, site: &str) -> Option<RobotsDirectives> {
+        self.sites.lock().unwrap_or_else(|e| e.into_inner()).get(site).cloned()
+    }
+
+    /// 生の HTML をアーカイブしてよいか
+    pub fn allows_archive(&self, site: &str) -> bool {
+        self.get(site).is_none_or(|d| !d.noarchive)
+    }
+
+    /// 記録済みの指定を各記事のサイトに応じて適用する
+    pub fn apply(&self, articles: &mut [WebArticle]) {
+        for article in articles {
+            if let Some(directives) = self.get(&article.site.name) {
+                directives.apply(article);
+            }
+        }
+    }
+}
+
+/// プロセス全体で共有するサイトごとの指定
+pub fn global_robots_policies() -> &'static RobotsPolicies {
+    GLOBAL_POLICIES.get_or_init(|| RobotsPolicies::open(data_dir().join(ROBOTS_POLICIES_FILE)))
+}
+
+/// `Content-Type` が HTML か（指定が無ければ HTML とみなす）
+pub fn is_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_none_or(|content_type| content_type.to_ascii_lowercase().contains("html"))
+}
+
+/// `<head>` の終わりまで（見つからなければ全体）
+fn head_section(raw_html: &str) -> &str {
+    &raw_html[..head_len(raw_html.as_bytes())]
+}
+
+/// `</head>` の終わりまでのバイト数（見つからなければ全体）
+fn head_len(body: &[u8]) -> usize {
+    body.windows(7).position(|w| w.eq_ignore_ascii_case(b"</head>")).map_or(body.len(), |i| i + 7)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    fn article(site: &str, description: &str) -> WebArticle {
+        ArticleBuilder::new("Title")
+            .with_site(site, "https://example.com")
+            .with_url("https://example.com/1")
+            .with_description(description)
+            .build()
+    }
+
+    #[test]
+    fn test_parse_directives() {
+        let directives = RobotsDirectives::parse("index, NOARCHIVE, max-snippet:40, max-image-preview:large");
+        assert!(directives.noarchive);
+        assert!(!directives.nosnippet);
+        assert_eq!(directives.snippet_limit(), Some(40));
+
+        assert_eq!(RobotsDirectives::parse("googlebot: nosnippet").snippet_limit(), Some(0));
+        assert_eq!(RobotsDirectives::parse("max-snippet:0").snippet_limit(), Some(0));
+        assert!(RobotsDirectives::parse("max-snippet:-1, noindex").is_empty());
+    }
+
+    #[test]
+    fn test_from_html_and_headers() {
+        let html = r#"<html><head>
+            <meta name="robots" content="max-snippet:80">
+            <meta name="googlebot" content="noarchive, max-snippet:20">
+            <meta name="description" content="nosnippet">
+            </head><body><meta name="robots" content="nosnippet"></body></html>"#;
+        let directives = RobotsDirectives::from_html(html);
+        assert!(directives.noarchive);
+        assert!(!directives.nosnippet);
+        assert_eq!(directives.max_snippet, Some(20));
+
+        let mut headers = HeaderMap::new();
+        headers.append(X_ROBOTS_TAG, "noarchive".parse().unwrap());
+        headers.append(X_ROBOTS_TAG, "bingbot: nosnippet".parse().unwrap());
+        let directives = RobotsDirectives::from_headers(&headers);
+        assert!(directives.noarchive && directives.nosnippet);
+    }
+
+    #[test]
+    fn test_from_html_body() {
+        let body = br#"<html><head><meta name="robots" content="noarchive"></head><body></body></html>"#;
+        let mut headers = HeaderMap::new();
+        assert!(RobotsDirectives::from_html_body(&headers, body).noarchive);
+        headers.insert(CONTENT_TYPE, "text/html; charset=utf-8".parse().unwrap());
+        assert!(RobotsDirectives::from_html_body(&headers, body).noarchive);
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        assert!(RobotsDirectives::from_html_body(&headers, body).is_empty());
+    }
+
+    #[test]
+    fn test_policies_persist_across_restarts() {
+        let dir = std::env::temp_dir().join(format!("clipper-robots-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(ROBOTS_POLICIES_FILE);
+        let policies = RobotsPolicies::open(path.clone());
+        assert!(policies.allows_archive("Restricted"));
+        policies.record("Restricted", &RobotsDirectives::parse("noarchive"));
+        policies.record("Open", &RobotsDirectives::default());
+
+        let reopened = RobotsPolicies::open(path);
+        assert!(!reopened.allows_archive("Restricted"));
+        assert!(reopened.get("Open").is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_policies_apply_per_site() {
+        let policies = RobotsPolicies::default();
+        policies.record("Restricted", &RobotsDirectives::parse("noarchive, max-snippet:5"));
+        policies.record("Open", &RobotsDirectives::default());
+        assert!(!policies.allows_archive("Restricted"));
+        assert!(policies.allows_archive("Open"));
+
+        let mut articles = vec![article("Restricted", "あいうえおかきくけこ"), article("Open", "概要はそのまま")];
+        policies.apply(&mut articles);
+        assert_eq!(articles[0].description, "あいうえ…");
+        assert_eq!(articles[0].properties.robots.as_ref().unwrap().max_snippet, Some(5));
+        assert_eq!(articles[1].description, "概要はそのまま");
+        assert!(articles[1].properties.robots.is_none());
+
+        policies.record("Restricted", &RobotsDirectives::parse("nosnippet"));
+        policies.apply(&mut articles);
+        assert!(articles[0].description.is_empty());
+        assert!(articles[0].properties.robots.as_ref().unwrap().nosnippet);
+    }
+}
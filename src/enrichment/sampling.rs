@@ -0,0 +1,434 @@
+//! 1回の巡回で LLM にかける記事数の予算と，予算を超えた場合の記事の選び方．
+//!
+//! 記事は優先度と新しさ（関心のプロファイルとの関連度や学習した関心があればその重み）から求めたスコアの高い順に選び，
+//! カテゴリごとの枠（`security=5` など）はスコアに関係なく先に確保する．選ばれなかった記事はキーワード分類と本文の先頭の文による
+//! 抽出型の要約で代用する．LLM に接続できずに分類に失敗した記事も同じ方法で代用する．
+
+use crate::enrichment::classifier::{Classifier, KeywordClassifier};
+use crate::enrichment::language::is_unspaced_language;
+use crate::enrichment::snippets::split_sentences;
+use crate::models::fairness::SiteCategory;
+use crate::models::priority::{PriorityOverrides, SitePriority};
+use crate::models::web_article::WebArticle;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::text::truncate_text;
+use chrono::{DateTime, Local};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use strum::{Display, EnumString};
+
+/// 組み込みのプロファイルを選ぶ環境変数（`unlimited` / `balanced` / `economy`）
+pub const LLM_PROFILE_ENV: &str = "CLIPPER_LLM_PROFILE";
+/// プロファイルの予算（1回の巡回で LLM にかける記事数）を上書きする環境変数
+pub const LLM_BUDGET_ENV: &str = "CLIPPER_LLM_BUDGET";
+/// プロファイルのカテゴリごとの枠を上書きする環境変数（例: `security=5,ai=10`）
+pub const LLM_CATEGORY_QUOTAS_ENV: &str = "CLIPPER_LLM_CATEGORY_QUOTAS";
+
+/// スコアの新しさの半減期（時間）
+const RECENCY_HALF_LIFE_HOURS: f64 = 24.0;
+/// 抽出型の要約に使う文の数と最大文字数
+const EXTRACTIVE_SENTENCES: usize = 2;
+const EXTRACTIVE_MAX_CHARS: usize = 200;
+/// 続けて失敗したら LLM に接続できないとみなし，残りの記事を LLM にかけない回数
+const MAX_CONSECUTIVE_FAILURES: usize = 3;
+
+/// 組み込みのプロファイル
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum SamplingPreset {
+    /// 全ての記事を LLM にかける（従来の動作）
+    #[default]
+    Unlimited,
+    /// 100件まで．セキュリティ・AI の記事を優先する．
+    Balanced,
+    /// 20件まで．セキュリティの記事を優先する．
+    Economy,
+}
+
+/// LLM にかける記事の選び方
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SamplingProfile {
+    /// 1回の巡回で LLM にかける記事数の上限（`None` は無制限）
+    pub llm_budget: Option<usize>,
+    /// カテゴリごとに予算から先に確保する記事数
+    pub category_quotas: BTreeMap<SiteCategory, usize>,
+}
+
+impl SamplingProfile {
+    pub fn preset(preset: SamplingPreset) -> Self {
+        match preset {
+            SamplingPreset::Unlimited => Self::default(),
+            SamplingPreset::Balanced => Self {
+                llm_budget: Some(100),
+                category_quotas: BTreeMap::from([(SiteCategory::Security, 20), (SiteCategory::Ai, 20)]),
+            },
+            SamplingPreset::Economy => Self {
+                llm_budget: Some(20),
+                category_quotas: BTreeMap::from([(SiteCategory::Security, 5)]),
+            },
+        }
+    }
+
+    /// `CLIPPER_LLM_PROFILE` のプロファイルに `CLIPPER_LLM_BUDGET` / `CLIPPER_LLM_CATEGORY_QUOTAS` を上書きする
+    pub fn from_env() -> AppResult<Self> {
+        let preset = match std::env::var(LLM_PROFILE_ENV) {
+            Ok(value) if !value.trim().is_empty() => SamplingPreset::from_str(value.trim())
+                .map_err(|e| AppError::EnumParseError(format!("{}: {}", value.trim(), e)))?,
+            _ => SamplingPreset::default(),
+        };
+        let mut profile = Self::preset(preset);
+        if let Ok(value) = std::env::var(LLM_BUDGET_ENV)
+            && !value.trim().is_empty()
+        {
+            let budget: usize = value
+                .trim()
+                .parse()
+                .map_err(|_| AppError::InternalError(format!("Invalid {}: {}", LLM_BUDGET_ENV, value)))?;
+            // 0 は無制限
+            profile.llm_budget = (budget > 0).then_some(budget);
+        }
+        if let Ok(value) = std::env::var(LLM_CATEGORY_QUOTAS_ENV)
+            && !value.trim().is_empty()
+        {
+            profile.category_quotas = parse_quotas(&value)?;
+        }
+        Ok(profile)
+    }
+
+    /// LLM にかける記事の位置（スコアの高い順）．予算が無制限の場合は全ての記事．
+    pub fn select(
+        &self,
+        articles: &[WebArticle],
+        priorities: &PriorityOverrides,
+        now: DateTime<Local>,
+    ) -> Vec<usize> {
+        self.select_weighted(articles, priorities, now, &HashMap::new())
+    }
+
+    /// `select` と同じく選ぶが，スコアに記事の URL ごとの重み（関連度や学習した関心から求めたもの）を掛ける
+    pub fn select_weighted(
+        &self,
+        articles: &[WebArticle],
+        priorities: &PriorityOverrides,
+        now: DateTime<Local>,
+        weights: &HashMap<String, f64>,
+    ) -> Vec<usize> {
+        let Some(budget) = self.llm_budget.filter(|&b| b < articles.len()) else {
+            return (0..articles.len()).collect();
+        };
+        let mut ranked: Vec<(usize, f64)> = articles
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let weight = weights.get(&a.article_url).copied().unwrap_or(1.0);
+                (i, ranking_score(a, priorities, now) * weight)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut selected: Vec<usize> = Vec::with_capacity(budget);
+        for (&category, &quota) in &self.category_quotas {
+            let picks = ranked
+                .iter()
+                .filter(|(i, _)| SiteCategory::from_site_name(&articles[*i].site.name) == category)
+                .filter(|(i, _)| !selected.contains(i))
+                .take(quota.min(budget - selected.len()))
+                .map(|(i, _)| *i)
+                .collect::<Vec<_>>();
+            selected.extend(picks);
+        }
+        for (i, _) in &ranked {
+            if selected.len() >= budget {
+                break;
+            }
+            if !selected.contains(i) {
+                selected.push(*i);
+            }
+        }
+        ranked
+            .into_iter()
+            .map(|(i, _)| i)
+            .filter(|i| selected.contains(i))
+            .collect()
+    }
+}
+
+/// `security=5,ai=10` 形式のカテゴリごとの枠
+fn parse_quotas(value: &str) -> AppResult<BTreeMap<SiteCategory, usize>> {
+    let mut quotas = BTreeMap::new();
+    for pair in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let invalid = || AppError::InternalError(format!("Invalid category quota: {}", pair));
+        let (category, quota) = pair.split_once('=').ok_or_else(invalid)?;
+        let category = SiteCategory::from_str(category.trim())
+            .map_err(|e| AppError::EnumParseError(format!("{}: {}", category.trim(), e)))?;
+        quotas.insert(category, quota.trim().parse().map_err(|_| invalid())?);
+    }
+    Ok(quotas)
+}
+
+/// 記事の重要度．サイトの優先度（critical 3，standard 1，low 0.5）に新しさ（24時間で半減）を掛ける．
+pub fn ranking_score(article: &WebArticle, priorities: &PriorityOverrides, now: DateTime<Local>) -> f64 {
+    let priority = priorities.resolve(&article.site.name, SitePriority::from_site_name(&article.site.name));
+    let weight = match priority {
+        SitePriority::Critical => 3.0,
+        SitePriority::Standard => 1.0,
+        SitePriority::Low => 0.5,
+    };
+    let age_hours = (now - article.timestamp).num_minutes().max(0) as f64 / 60.0;
+    weight * 0.5f64.powf(age_hours / RECENCY_HALF_LIFE_HOURS)
+}
+
+/// 本文（無ければ概要）の先頭の文を抜き出した要約．日本語・中国語の記事は文を空白で区切らずに繋げる．
+pub fn extractive_summary(article: &WebArticle) -> Option<String> {
+    let source = if article.text.trim().is_empty() {
+        &article.description
+    } else {
+        &article.text
+    };
+    let separator = if article.lang.as_deref().is_some_and(is_unspaced_language) { "" } else { " " };
+    let summary = split_sentences(source)
+        .into_iter()
+        .filter(|s| !s.starts_with('#'))
+        .take(EXTRACTIVE_SENTENCES)
+        .collect::<Vec<_>>()
+        .join(separator);
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    if summary.is_empty() {
+        return None;
+    }
+    Some(truncate_text(&summary, EXTRACTIVE_MAX_CHARS))
+}
+
+/// 予算を適用した分類の件数
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SamplingOutcome {
+    pub llm: usize,
+    pub fallback: usize,
+    /// LLM にかける予定だったが，分類に失敗したため代用した記事の位置
+    pub unavailable: Vec<usize>,
+}
+
+/// キーワード分類と抽出型の要約で処理する
+async fn apply_fallback(article: &mut WebArticle) {
+    if let Ok(classification) = KeywordClassifier.classify(article).await {
+        classification.apply(&mut article.properties);
+    }
+    if article.properties.summary.as_deref().is_none_or(|s| s.trim().is_empty()) {
+        article.properties.summary = extractive_summary(article);
+    }
+}
+
+/// `indices` の記事を `classifier` で分類し，成功した件数と失敗した記事の位置を返す．
+/// 続けて失敗した場合は LLM に接続できないとみなし，残りの記事は LLM にかけずに失敗として扱う．
+async fn classify_selected(
+    classifier: &dyn Classifier,
+    articles: &mut [WebArticle],
+    indices: &[usize],
+) -> (usize, Vec<usize>) {
+    let mut classified = 0;
+    let mut failed = Vec::new();
+    let mut consecutive_failures = 0;
+    for &index in indices {
+        if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+            failed.push(index);
+            continue;
+        }
+        let article = &mut articles[index];
+        match classifier.classify(article).await {
+            Ok(classification) => {
+                classification.apply(&mut article.properties);
+                classified += 1;
+                consecutive_failures = 0;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to classify {} with {} classifier: {}",
+                    article.article_url,
+                    classifier.name(),
+                    e
+                );
+                failed.push(index);
+                consecutive_failures += 1;
+            }
+        }
+    }
+    if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+        tracing::warn!(
+            "{} classifier looks unavailable; {} articles fall back to extractive summaries",
+            classifier.name(),
+            failed.len()
+        );
+    }
+    (classified, failed)
+}
+
+/// 選ばれた記事は `classifier` で，それ以外と分類に失敗した記事はキーワード分類と抽出型の要約で処理する．
+/// `weights` は記事の URL ごとの順位付けの重み（無い記事は 1 として扱う）．
+pub async fn classify_with_budget(
+    classifier: &dyn Classifier,
+    articles: &mut [WebArticle],
+    profile: &SamplingProfile,
+    weights: &HashMap<String, f64>,
+    now: DateTime<Local>,
+) -> SamplingOutcome {
+    let priorities = PriorityOverrides::from_env().unwrap_or_default();
+    let selected = profile.select_weighted(articles, &priorities, now, weights);
+    let (llm, unavailable) = classify_selected(classifier, articles, &selected).await;
+
+    let mut fallback = 0;
+    for (index, article) in articles.iter_mut().enumerate() {
+        if !selected.contains(&index) || unavailable.contains(&index) {
+            apply_fallback(article).await;
+            fallback += 1;
+        }
+    }
+    SamplingOutcome {
+        llm,
+        fallback,
+        unavailable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::classifier::Classification;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 10, 2, 9, 0, 0).unwrap()
+    }
+
+    fn article(site: &str, hours_ago: i64) -> WebArticle {
+        ArticleBuilder::new(format!("{} {}", site, hours_ago))
+            .with_site(site, "https://example.com")
+            .with_url(format!("https://example.com/{}/{}", site, hours_ago))
+            .with_description("最初の文です。次の文です。三つ目の文です。")
+            .with_timestamp(now() - Duration::hours(hours_ago))
+            .build()
+    }
+
+    #[test]
+    fn test_select_by_score_and_quota() {
+        let articles = vec![
+            article("Gigazine", 1),
+            article("Gigazine", 2),
+            article("JPCERT", 30),
+            article("Trend Micro Security", 48),
+            article("Gigazine", 3),
+        ];
+        let priorities = PriorityOverrides::default();
+        let unlimited = SamplingProfile::default();
+        assert_eq!(unlimited.select(&articles, &priorities, now()).len(), 5);
+
+        let by_score = SamplingProfile {
+            llm_budget: Some(2),
+            category_quotas: BTreeMap::new(),
+        };
+        assert_eq!(by_score.select(&articles, &priorities, now()), vec![2, 0]);
+
+        let with_quota = SamplingProfile {
+            llm_budget: Some(3),
+            category_quotas: BTreeMap::from([(SiteCategory::Security, 2)]),
+        };
+        // JPCERT と Trend Micro（security）は枠で確保し，残りの1件はスコア順
+        assert_eq!(with_quota.select(&articles, &priorities, now()), vec![2, 0, 3]);
+    }
+
+    #[test]
+    fn test_select_weighted() {
+        let articles = vec![article("Gigazine", 1), article("Gigazine", 2), article("Gigazine", 3)];
+        let profile = SamplingProfile {
+            llm_budget: Some(1),
+            category_quotas: BTreeMap::new(),
+        };
+        let priorities = PriorityOverrides::default();
+        assert_eq!(profile.select(&articles, &priorities, now()), vec![0]);
+        // 少し古くても関心に近い記事を優先する
+        let weights = HashMap::from([
+            (articles[0].article_url.clone(), 0.25),
+            (articles[2].article_url.clone(), 1.75),
+        ]);
+        assert_eq!(profile.select_weighted(&articles, &priorities, now(), &weights), vec![2]);
+    }
+
+    #[test]
+    fn test_ranking_score_and_quotas() {
+        let priorities = PriorityOverrides::default().with_priority("Gigazine", SitePriority::Low);
+        let score = ranking_score(&article("Gigazine", 24), &priorities, now());
+        assert!((score - 0.25).abs() < 1e-9);
+        assert_eq!(
+            parse_quotas("security=5, ai=10").unwrap(),
+            BTreeMap::from([(SiteCategory::Security, 5), (SiteCategory::Ai, 10)])
+        );
+        assert!(parse_quotas("unknown=1").is_err());
+        assert!(parse_quotas("security").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_classify_with_budget_uses_extractive_fallback() {
+        let mut articles = vec![article("Gigazine", 1), article("Gigazine", 50)];
+        let profile = SamplingProfile {
+            llm_budget: Some(1),
+            category_quotas: BTreeMap::new(),
+        };
+        let outcome =
+            classify_with_budget(&KeywordClassifier, &mut articles, &profile, &HashMap::new(), now()).await;
+        assert_eq!(
+            outcome,
+            SamplingOutcome {
+                llm: 1,
+                fallback: 1,
+                unavailable: vec![]
+            }
+        );
+        assert_eq!(articles[0].properties.summary.as_deref(), Some(""));
+        assert_eq!(articles[1].properties.summary.as_deref(), Some("最初の文です。 次の文です。"));
+    }
+
+    /// 全ての記事で失敗する（接続できない）LLM の代わり
+    struct UnreachableClassifier(std::sync::atomic::AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl Classifier for UnreachableClassifier {
+        fn name(&self) -> &'static str {
+            "unreachable"
+        }
+        async fn classify(&self, _article: &WebArticle) -> AppResult<Classification> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(AppError::InternalError("connection refused".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_classify_with_budget_falls_back_when_llm_is_unavailable() {
+        let mut articles: Vec<WebArticle> = (1..=5).map(|hours| article("Gigazine", hours)).collect();
+        let classifier = UnreachableClassifier(Default::default());
+        let outcome = classify_with_budget(
+            &classifier,
+            &mut articles,
+            &SamplingProfile::default(),
+            &HashMap::new(),
+            now(),
+        )
+        .await;
+        assert_eq!(outcome.llm, 0);
+        assert_eq!(outcome.fallback, 5);
+        assert_eq!(outcome.unavailable, vec![0, 1, 2, 3, 4]);
+        // 続けて失敗したら残りの記事は LLM にかけない
+        assert_eq!(classifier.0.load(std::sync::atomic::Ordering::SeqCst), MAX_CONSECUTIVE_FAILURES);
+        assert!(
+            articles
+                .iter()
+                .all(|a| a.properties.summary.as_deref() == Some("最初の文です。 次の文です。"))
+        );
+    }
+
+    #[test]
+    fn test_extractive_summary_joins_by_language() {
+        let mut japanese = article("Gigazine", 1);
+        japanese.lang = Some("ja".into());
+        assert_eq!(extractive_summary(&japanese).as_deref(), Some("最初の文です。次の文です。"));
+    }
+}
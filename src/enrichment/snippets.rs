@@ -0,0 +1,172 @@
+//! キーワード周辺の文を抜き出す KWIC（keyword in context）スニペット．
+
+use serde::{Deserialize, Serialize};
+
+/// 文の区切り（日本語の句点と英語の終止符，改行）
+const SENTENCE_TERMINATORS: &[char] = &['。', '．', '！', '？', '!', '?', '\n'];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetOptions {
+    /// キーワードを含む文の前後に含める文の数
+    pub context_sentences: usize,
+    pub max_snippets: usize,
+    /// スニペット1件の最大文字数（超える場合はキーワードを中心に切り詰める）
+    pub max_chars: usize,
+    pub highlight_start: String,
+    pub highlight_end: String,
+}
+
+impl Default for SnippetOptions {
+    fn default() -> Self {
+        Self {
+            context_sentences: 0,
+            max_snippets: 3,
+            max_chars: 200,
+            highlight_start: "**".to_string(),
+            highlight_end: "**".to_string(),
+        }
+    }
+}
+
+/// テキストを文単位に分割する（区切り文字は前の文に含める）．英語の `. ` も区切りとして扱う．
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = i + c.len_utf8();
+        let is_period = c == '.' && chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        if SENTENCE_TERMINATORS.contains(&c) || is_period {
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+/// `keyword` を含む文（と前後の文）をハイライト付きで返す．大文字・小文字は区別しない．
+pub fn keyword_snippets(text: &str, keyword: &str, options: &SnippetOptions) -> Vec<String> {
+    if keyword.trim().is_empty() {
+        return Vec::new();
+    }
+    let sentences = split_sentences(text);
+    let keyword_lower = keyword.to_lowercase();
+    let mut snippets = Vec::new();
+    let mut covered_until = 0;
+    for (i, sentence) in sentences.iter().enumerate() {
+        if snippets.len() >= options.max_snippets {
+            break;
+        }
+        if i < covered_until || !sentence.to_lowercase().contains(&keyword_lower) {
+            continue;
+        }
+        let from = i.saturating_sub(options.context_sentences);
+        let to = (i + options.context_sentences + 1).min(sentences.len());
+        covered_until = to;
+        let context = sentences[from..to].join(" ");
+        let context = truncate_around(&context, &keyword_lower, options.max_chars);
+        snippets.push(highlight(&context, &keyword_lower, options));
+    }
+    snippets
+}
+
+/// キーワードの出現箇所をマーカーで囲む
+fn highlight(text: &str, keyword_lower: &str, options: &SnippetOptions) -> String {
+    let mut out = String::with_capacity(text.len() + 8);
+    let mut rest = text;
+    while let Some((start, end)) = find_case_insensitive(rest, keyword_lower) {
+        out.push_str(&rest[..start]);
+        out.push_str(&options.highlight_start);
+        out.push_str(&rest[start..end]);
+        out.push_str(&options.highlight_end);
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 大文字・小文字を区別せずに検索し，元の文字列上のバイト範囲を返す
+fn find_case_insensitive(haystack: &str, needle_lower: &str) -> Option<(usize, usize)> {
+    let needle_len = needle_lower.chars().count();
+    let indices: Vec<usize> = haystack
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(haystack.len()))
+        .collect();
+    (0..indices.len().saturating_sub(needle_len)).find_map(|i| {
+        let (start, end) = (indices[i], indices[i + needle_len]);
+        (haystack[start..end].to_lowercase() == needle_lower).then_some((start, end))
+    })
+}
+
+/// `max_chars` を超える場合，キーワードが中央付近に来るように前後を「…」で切り詰める
+fn truncate_around(text: &str, keyword_lower: &str, max_chars: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= max_chars {
+        return text.to_string();
+    }
+    let keyword_pos = find_case_insensitive(text, keyword_lower)
+        .map(|(start, _)| text[..start].chars().count())
+        .unwrap_or(0);
+    let start = keyword_pos.saturating_sub(max_chars / 2).min(chars.len() - max_chars);
+    let end = start + max_chars;
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.extend(&chars[start..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sentences() {
+        let sentences = split_sentences("脆弱性が公開されました。影響は限定的です．Patch now. Version 1.2 is fixed");
+        assert_eq!(
+            sentences,
+            vec!["脆弱性が公開されました。", "影響は限定的です．", "Patch now.", "Version 1.2 is fixed"]
+        );
+    }
+
+    #[test]
+    fn test_keyword_snippets_with_context_and_highlight() {
+        let text = "前置き。OpenSSL に深刻な脆弱性が見つかりました。すぐに更新してください。無関係な文。";
+        let options = SnippetOptions {
+            context_sentences: 1,
+            ..Default::default()
+        };
+        let snippets = keyword_snippets(text, "openssl", &options);
+        assert_eq!(
+            snippets,
+            vec!["前置き。 **OpenSSL** に深刻な脆弱性が見つかりました。 すぐに更新してください。"]
+        );
+        assert!(keyword_snippets(text, "Rust", &options).is_empty());
+    }
+
+    #[test]
+    fn test_keyword_snippets_truncates_long_sentences() {
+        let text = format!("{}CVE-2025-0001{}", "あ".repeat(300), "い".repeat(300));
+        let options = SnippetOptions {
+            max_chars: 40,
+            highlight_start: "[".into(),
+            highlight_end: "]".into(),
+            ..Default::default()
+        };
+        let snippet = &keyword_snippets(&text, "cve-2025-0001", &options)[0];
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("[CVE-2025-0001]"));
+    }
+}
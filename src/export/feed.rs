@@ -0,0 +1,223 @@
+//! 取得した記事（要約があれば要約も）を1つの RSS 2.0 / Atom フィードにまとめ，普段使いのフィードリーダーで購読できるようにする．
+
+use crate::enrichment::license::attribution_text;
+use crate::models::ordering::sort_newest_first;
+use crate::models::web_article::WebArticle;
+use chrono::{DateTime, Local};
+use std::collections::HashSet;
+use strum::{Display, EnumString};
+
+/// 出力するフィードの形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum FeedFormat {
+    #[default]
+    Rss,
+    Atom,
+}
+
+/// フィード全体の情報
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedOptions {
+    pub title: String,
+    /// フィードの提供元の URL（Atom の `id` にも使う）
+    pub link: String,
+    pub description: String,
+    /// `true` の場合は LLM の要約がある記事だけを含める
+    pub summarized_only: bool,
+}
+
+impl Default for FeedOptions {
+    fn default() -> Self {
+        Self {
+            title: "News Clipper".to_string(),
+            link: "https://github.com/akitenkrad/rs-news-clipper".to_string(),
+            description: "Articles clipped by News Clipper".to_string(),
+            summarized_only: false,
+        }
+    }
+}
+
+fn summary(article: &WebArticle) -> Option<&str> {
+    article
+        .properties
+        .summary
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// 要約があれば要約を，無ければ記事の概要を本文にする．帰属表示が必要な記事は末尾に出典を添える．
+fn content(article: &WebArticle) -> String {
+    let content = summary(article).unwrap_or_else(|| article.description.trim());
+    match attribution_text(article) {
+        Some(attribution) => format!("{}\n\n{}", content, attribution),
+        None => content.to_string(),
+    }
+}
+
+/// フィードに含める記事を新しい順に並べる（同じ URL は1件にする）
+fn entries<'a>(articles: &'a [WebArticle], options: &FeedOptions) -> Vec<&'a WebArticle> {
+    let mut entries: Vec<&WebArticle> = articles
+        .iter()
+        .filter(|a| !options.summarized_only || summary(a).is_some())
+        .collect();
+    sort_newest_first(&mut entries);
+    let mut seen = HashSet::new();
+    entries.retain(|a| seen.insert(a.article_url.as_str()));
+    entries
+}
+
+pub fn render_feed(format: FeedFormat, articles: &[WebArticle], options: &FeedOptions) -> String {
+    match format {
+        FeedFormat::Rss => render_rss(articles, options),
+        FeedFormat::Atom => render_atom(articles, options),
+    }
+}
+
+/// RSS 2.0 のフィードを生成する
+pub fn render_rss(articles: &[WebArticle], options: &FeedOptions) -> String {
+    let entries = entries(articles, options);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<rss version=\"2.0\">\n<channel>\n");
+    out.push_str(&format!("<title>{}</title>\n", escape_xml(&options.title)));
+    out.push_str(&format!("<link>{}</link>\n", escape_xml(&options.link)));
+    out.push_str(&format!("<description>{}</description>\n", escape_xml(&options.description)));
+    if let Some(latest) = entries.first() {
+        out.push_str(&format!("<lastBuildDate>{}</lastBuildDate>\n", latest.timestamp.to_rfc2822()));
+    }
+    for article in entries {
+        out.push_str("<item>\n");
+        out.push_str(&format!("<title>{}</title>\n", escape_xml(&article.title)));
+        out.push_str(&format!("<link>{}</link>\n", escape_xml(&article.article_url)));
+        out.push_str(&format!(
+            "<guid isPermaLink=\"true\">{}</guid>\n",
+            escape_xml(&article.article_url)
+        ));
+        out.push_str(&format!("<pubDate>{}</pubDate>\n", article.timestamp.to_rfc2822()));
+        out.push_str(&format!(
+            "<source url=\"{}\">{}</source>\n",
+            escape_xml(&article.site.url),
+            escape_xml(&article.site.name)
+        ));
+        out.push_str(&format!("<description>{}</description>\n", escape_xml(&content(article))));
+        out.push_str("</item>\n");
+    }
+    out.push_str("</channel>\n</rss>\n");
+    out
+}
+
+/// Atom（RFC 4287）のフィードを生成する
+pub fn render_atom(articles: &[WebArticle], options: &FeedOptions) -> String {
+    let entries = entries(articles, options);
+    let updated = entries.first().map(|a| a.timestamp).unwrap_or_else(Local::now);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    out.push_str(&format!("<title>{}</title>\n", escape_xml(&options.title)));
+    out.push_str(&format!("<subtitle>{}</subtitle>\n", escape_xml(&options.description)));
+    out.push_str(&format!("<link href=\"{}\"/>\n", escape_xml(&options.link)));
+    out.push_str(&format!("<id>{}</id>\n", escape_xml(&options.link)));
+    out.push_str(&format!("<updated>{}</updated>\n", rfc3339(updated)));
+    for article in entries {
+        out.push_str("<entry>\n");
+        out.push_str(&format!("<title>{}</title>\n", escape_xml(&article.title)));
+        out.push_str(&format!("<link href=\"{}\"/>\n", escape_xml(&article.article_url)));
+        out.push_str(&format!("<id>{}</id>\n", escape_xml(&article.article_url)));
+        out.push_str(&format!("<updated>{}</updated>\n", rfc3339(article.timestamp)));
+        let author = article.author.as_deref().unwrap_or(&article.site.name);
+        out.push_str(&format!("<author><name>{}</name></author>\n", escape_xml(author)));
+        out.push_str(&format!("<summary>{}</summary>\n", escape_xml(&content(article))));
+        out.push_str("</entry>\n");
+    }
+    out.push_str("</feed>\n");
+    out
+}
+
+fn rfc3339(timestamp: DateTime<Local>) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
+
+/// XML の文字データ・属性値のエスケープ（XML 1.0 で使えない制御文字は取り除く）
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::license::ArticleLicense;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::TimeZone;
+
+    fn article(title: &str, url: &str, day: u32, summary: &str) -> WebArticle {
+        let mut article = ArticleBuilder::new(title)
+            .with_url(url)
+            .with_description("概要")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, day, 9, 0, 0).unwrap())
+            .build();
+        article.properties.summary = Some(summary.into());
+        article
+    }
+
+    #[test]
+    fn test_render_rss() {
+        let articles = vec![
+            article("Old", "https://example.com/1", 1, ""),
+            article("New & <Shiny>", "https://example.com/2", 2, "LLM の要約"),
+            article("New & <Shiny>", "https://example.com/2", 2, "LLM の要約"),
+        ];
+        let rss = render_rss(&articles, &FeedOptions::default());
+        assert!(rss.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">"));
+        assert_eq!(rss.matches("<item>").count(), 2);
+        assert!(rss.find("New &amp; &lt;Shiny&gt;").unwrap() < rss.find("<title>Old</title>").unwrap());
+        assert!(rss.contains("<description>LLM の要約</description>"));
+        assert!(rss.contains("<description>概要</description>"));
+
+        let summarized = FeedOptions {
+            summarized_only: true,
+            ..Default::default()
+        };
+        assert_eq!(render_rss(&articles, &summarized).matches("<item>").count(), 1);
+    }
+
+    #[test]
+    fn test_render_atom() {
+        let mut authored = article("Authored", "https://example.com/2", 1, "");
+        authored.author = Some("山田 太郎".into());
+        let articles = vec![article("Title", "https://example.com/1?a=1&b=2", 1, "要約"), authored];
+        let atom = render_feed(FeedFormat::Atom, &articles, &FeedOptions::default());
+        assert!(atom.contains("<author><name>Example</name></author>"));
+        assert!(atom.contains("<author><name>山田 太郎</name></author>"));
+        assert!(atom.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+        assert!(atom.contains("<link href=\"https://example.com/1?a=1&amp;b=2\"/>"));
+        assert!(atom.contains("<updated>2025-10-01T09:00:00"));
+        assert!(atom.contains("<summary>要約</summary>"));
+        assert_eq!(escape_xml("a\u{0}b"), "ab");
+    }
+
+    #[test]
+    fn test_feed_includes_attribution() {
+        let mut licensed = article("Title", "https://example.com/1", 1, "要約");
+        licensed.properties.license = Some(ArticleLicense {
+            name: Some("CC BY-SA 4.0".into()),
+            ..Default::default()
+        });
+        let rss = render_rss(&[licensed], &FeedOptions::default());
+        assert!(rss.contains(
+            "<description>要約\n\n出典: Example「Title」 https://example.com/1 / ライセンス: CC BY-SA 4.0</description>"
+        ));
+    }
+}
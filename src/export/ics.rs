@@ -0,0 +1,224 @@
+//! イベント告知（勉強会，カンファレンス，CFP の締切など）を iCalendar（RFC 5545）形式で書き出す．
+
+use crate::models::web_article::WebArticle;
+use crate::shared::regexes;
+use chrono::{Datelike, Local, NaiveDate, Utc};
+
+/// イベント告知と判定するキーワード（タイトルと概要から探す）
+const EVENT_KEYWORDS: &[&str] = &[
+    "開催",
+    "イベント",
+    "勉強会",
+    "カンファレンス",
+    "セミナー",
+    "ウェビナー",
+    "締切",
+    "締め切り",
+    "募集",
+    "CFP",
+    "Call for Papers",
+    "conference",
+    "meetup",
+    "webinar",
+    "summit",
+    "deadline",
+];
+
+const MONTHS: &[&str] = &[
+    "january", "february", "march", "april", "may", "june", "july", "august", "september",
+    "october", "november", "december",
+];
+
+/// 終日イベント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub date: NaiveDate,
+    pub url: String,
+    pub description: String,
+}
+
+impl CalendarEvent {
+    /// 記事がイベント告知であれば，本文・概要中の最初の未来の日付をイベント日とする
+    pub fn from_article(article: &WebArticle) -> Option<Self> {
+        let haystack = format!("{} {}", article.title, article.description).to_lowercase();
+        if !EVENT_KEYWORDS
+            .iter()
+            .any(|k| haystack.contains(&k.to_lowercase()))
+        {
+            return None;
+        }
+        let published = article.timestamp.date_naive();
+        let date = [&article.title, &article.description, &article.text]
+            .into_iter()
+            .flat_map(|text| extract_dates(text, published.year()))
+            .find(|date| *date >= published)?;
+        Some(Self {
+            uid: format!("{}@news-clipper", article.article_url),
+            summary: article.title.clone(),
+            date,
+            url: article.article_url.clone(),
+            description: format!("{}\n{}", article.site.name, article.description.trim()),
+        })
+    }
+}
+
+/// テキストに含まれる日付を出現順に返す（年の無い表記は `default_year` を使う）
+pub fn extract_dates(text: &str, default_year: i32) -> Vec<NaiveDate> {
+    let (ja, iso, en) = (regexes::ja_date(), regexes::iso_date(), regexes::en_date());
+
+    let mut found: Vec<(usize, NaiveDate)> = Vec::new();
+    for cap in ja.captures_iter(text) {
+        let year = cap.get(1).and_then(|m| m.as_str().parse().ok()).unwrap_or(default_year);
+        if let Some(date) = ymd(year, &cap[2], &cap[3]) {
+            found.push((cap.get(0).unwrap().start(), date));
+        }
+    }
+    for cap in iso.captures_iter(text) {
+        if let Some(date) = cap[1].parse().ok().and_then(|y| ymd(y, &cap[2], &cap[3])) {
+            found.push((cap.get(0).unwrap().start(), date));
+        }
+    }
+    for cap in en.captures_iter(text) {
+        let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(&cap[1])).unwrap() + 1;
+        if let Some(date) = cap[3].parse().ok().and_then(|y| ymd(y, &month.to_string(), &cap[2])) {
+            found.push((cap.get(0).unwrap().start(), date));
+        }
+    }
+    found.sort_by_key(|(pos, _)| *pos);
+    found.into_iter().map(|(_, date)| date).collect()
+}
+
+fn ymd(year: i32, month: &str, day: &str) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
+
+/// 記事一覧から今日以降のイベントを抽出し，日付順に並べる
+pub fn upcoming_events(articles: &[WebArticle]) -> Vec<CalendarEvent> {
+    let today = Local::now().date_naive();
+    let mut events: Vec<CalendarEvent> = articles
+        .iter()
+        .filter_map(CalendarEvent::from_article)
+        .filter(|event| event.date >= today)
+        .collect();
+    events.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.url.cmp(&b.url)));
+    events.dedup_by(|a, b| a.uid == b.uid);
+    events
+}
+
+/// カレンダーアプリで購読できる iCalendar 文字列を生成する
+pub fn render_ics(events: &[CalendarEvent]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//news-clipper//events//JA".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:News Clipper Events".to_string(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape_text(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")));
+        lines.push(format!(
+            "DTEND;VALUE=DATE:{}",
+            event.date.succ_opt().unwrap_or(event.date).format("%Y%m%d")
+        ));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        lines.push(format!("URL:{}", event.url));
+        lines.push(format!("DESCRIPTION:{}", escape_text(&event.description)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+}
+
+/// TEXT 型の値のエスケープ（RFC 5545 3.3.11）
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// 1行を 75 オクテット以内に折り返す（マルチバイト文字の途中では折り返さない）
+fn fold_line(line: &str) -> String {
+    const LIMIT: usize = 75;
+    let mut out = String::with_capacity(line.len() + line.len() / LIMIT * 3);
+    let mut current = 0;
+    for c in line.chars() {
+        if current + c.len_utf8() > LIMIT {
+            out.push_str("\r\n ");
+            current = 1;
+        }
+        out.push(c);
+        current += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::TimeZone;
+
+    fn article(title: &str, description: &str) -> WebArticle {
+        ArticleBuilder::new(title)
+            .with_site("connpass", "https://connpass.com")
+            .with_url("https://example.connpass.com/event/1/")
+            .with_description(description)
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_extract_dates() {
+        let dates = extract_dates("11月20日(木) 開催，CFP 締切は2025/10/31, final on December 3rd, 2025", 2025);
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd_opt(2025, 11, 20).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 10, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 12, 3).unwrap(),
+            ]
+        );
+        assert!(extract_dates("13月40日", 2025).is_empty());
+    }
+
+    #[test]
+    fn test_calendar_event_from_article() {
+        let event = CalendarEvent::from_article(&article(
+            "Rust 勉強会 #12",
+            "2025年11月20日 19:00 から開催します",
+        ))
+        .unwrap();
+        assert_eq!(event.date, NaiveDate::from_ymd_opt(2025, 11, 20).unwrap());
+
+        // イベント告知でない記事や，過去の日付しか無い記事は対象外
+        assert!(CalendarEvent::from_article(&article("新製品を発表", "2025年11月20日に発売")).is_none());
+        assert!(CalendarEvent::from_article(&article("勉強会レポート", "2025年9月1日に開催しました")).is_none());
+    }
+
+    #[test]
+    fn test_render_ics() {
+        let event = CalendarEvent {
+            uid: "https://example.com/e@news-clipper".into(),
+            summary: "CFP 締切; RustConf, 2025".into(),
+            date: NaiveDate::from_ymd_opt(2025, 11, 20).unwrap(),
+            url: "https://example.com/e".into(),
+            description: "あ".repeat(40),
+        };
+        let ics = render_ics(&[event]);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20251120\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20251121\r\n"));
+        assert!(ics.contains("SUMMARY:CFP 締切\\; RustConf\\, 2025\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+}
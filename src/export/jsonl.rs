@@ -0,0 +1,203 @@
+//! 記事を1行1件の JSON（JSON Lines）として書き出す．
+//!
+//! 各行は `ArticleRecord` の形で，先頭に `schema_version` を持つ．`WebArticle` の内部表現が変わっても
+//! 出力の形は変えず，フィールドの削除・意味の変更を行う場合は `SCHEMA_VERSION` を上げる（追加のみなら上げない）．
+
+use crate::models::annotation::Annotation;
+use crate::models::web_article::{Status, WebArticle, WebArticleProperty};
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::StoredArticle;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+/// 出力する JSON Lines のスキーマのバージョン
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON Lines の1行分
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleRecord {
+    pub schema_version: u32,
+    /// 保存済みの記事の ID（データベースから書き出した場合のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    pub site: String,
+    pub site_url: String,
+    pub title: String,
+    pub url: String,
+    pub description: String,
+    /// RFC 3339 形式の公開日時
+    pub published_at: DateTime<FixedOffset>,
+    pub text: String,
+    pub html: String,
+    pub requires_login: bool,
+    pub properties: WebArticleProperty,
+    /// 共有の注釈（ハイライトした箇所とメモ．データベースから書き出した場合のみ）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<Annotation>,
+}
+
+impl From<&WebArticle> for ArticleRecord {
+    fn from(article: &WebArticle) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            id: None,
+            status: None,
+            site: article.site.name.clone(),
+            site_url: article.site.url.clone(),
+            title: article.title.clone(),
+            url: article.article_url.clone(),
+            description: article.description.clone(),
+            published_at: article.timestamp.fixed_offset(),
+            text: article.text.clone(),
+            html: article.html.clone(),
+            requires_login: article.requires_login,
+            properties: article.properties.clone(),
+            highlights: Vec::new(),
+        }
+    }
+}
+
+impl From<&StoredArticle> for ArticleRecord {
+    fn from(stored: &StoredArticle) -> Self {
+        Self {
+            id: Some(stored.id.to_string()),
+            status: Some(stored.status),
+            highlights: stored.highlights.clone(),
+            ..Self::from(&stored.article)
+        }
+    }
+}
+
+/// 記事を JSON Lines として書き出し，書き出した件数を返す
+pub fn write_jsonl<'a, W, I, T>(writer: &mut W, records: I) -> AppResult<usize>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a T>,
+    T: 'a,
+    ArticleRecord: From<&'a T>,
+{
+    let mut count = 0;
+    for record in records {
+        serde_json::to_writer(&mut *writer, &ArticleRecord::from(record))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| AppError::InternalError(format!("Failed to write JSON Lines: {}", e)))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// 記事を JSON Lines の文字列にする
+pub fn render_jsonl<'a, I, T>(records: I) -> AppResult<String>
+where
+    I: IntoIterator<Item = &'a T>,
+    T: 'a,
+    ArticleRecord: From<&'a T>,
+{
+    let mut out = Vec::new();
+    write_jsonl(&mut out, records)?;
+    String::from_utf8(out).map_err(|e| AppError::InternalError(e.to_string()))
+}
+
+/// JSON Lines を読み込む．空行は無視し，対応していないスキーマのバージョンはエラーにする．
+pub fn read_jsonl<R: BufRead>(reader: R) -> AppResult<Vec<ArticleRecord>> {
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| AppError::InternalError(format!("Failed to read JSON Lines: {}", e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: ArticleRecord = serde_json::from_str(&line)?;
+        if record.schema_version > SCHEMA_VERSION {
+            return Err(AppError::InternalError(format!(
+                "Unsupported schema_version {} at line {}",
+                record.schema_version,
+                index + 1
+            )));
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Local, TimeZone};
+
+    fn article() -> WebArticle {
+        let mut article = ArticleBuilder::new("Title")
+            .with_site("Example", "https://example.com/feed")
+            .with_url("https://example.com/posts/1")
+            .with_description("Summary")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap())
+            .build();
+        article.properties.is_ai_related = Some(true);
+        article
+    }
+
+    #[test]
+    fn test_render_jsonl_contract() {
+        let articles = vec![article(), article()];
+        let jsonl = render_jsonl(&articles).unwrap();
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(jsonl.ends_with('\n'));
+
+        let value: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert!(lines[0].starts_with("{\"schema_version\":1,"));
+        assert_eq!(value["site"], "Example");
+        assert_eq!(value["url"], "https://example.com/posts/1");
+        assert_eq!(value["properties"]["is_ai_related"], true);
+        assert!(value.get("id").is_none());
+        let keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        let mut expected = vec![
+            "schema_version",
+            "site",
+            "site_url",
+            "title",
+            "url",
+            "description",
+            "published_at",
+            "text",
+            "html",
+            "requires_login",
+            "properties",
+        ];
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_render_jsonl_includes_highlights() {
+        let article = article();
+        let highlight = Annotation::new(&article.article_url, "本文です", 0, 2, Some("メモ".into())).unwrap();
+        let stored = StoredArticle {
+            id: crate::shared::id::WebArticleId::new(),
+            status: Status::New,
+            article,
+            triage: Default::default(),
+            created_at: Local::now(),
+            updated_at: Local::now(),
+            highlights: vec![highlight],
+        };
+        let record = read_jsonl(render_jsonl(&[stored]).unwrap().as_bytes()).unwrap().remove(0);
+        assert_eq!(record.highlights.len(), 1);
+        assert_eq!(record.highlights[0].quote, "本文");
+        assert_eq!(record.highlights[0].note.as_deref(), Some("メモ"));
+    }
+
+    #[test]
+    fn test_read_jsonl_round_trip_and_version_check() {
+        let jsonl = render_jsonl(&[article()]).unwrap();
+        let records = read_jsonl(format!("\n{}\n", jsonl).as_bytes()).unwrap();
+        assert_eq!(records, vec![ArticleRecord::from(&article())]);
+
+        let future = jsonl.replacen("\"schema_version\":1", "\"schema_version\":99", 1);
+        assert!(read_jsonl(future.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,438 @@
+//! 保存済みの記事をサイトごとにまとめた Markdown として書き出す．
+//! 1日分の記事をサイト別・カテゴリ別，またはプロファイルのセクション別にまとめたダイジェストも作成できる．
+//! ダイジェストには今後の論文投稿などの締切の一覧も載せられる．
+
+use crate::enrichment::brief::MorningBrief;
+use crate::enrichment::deadlines::{Deadline, render_deadlines_markdown};
+use crate::enrichment::license::attribution_text;
+use crate::export::retrospective::RetrospectiveSection;
+use crate::export::sections::DigestProfile;
+use crate::models::annotation::render_highlights_markdown;
+use crate::models::fairness::SiteCategory;
+use crate::models::ordering::{newest_first, sort_newest_first};
+use crate::models::web_article::WebArticle;
+use crate::shared::text::truncate_text;
+use crate::storage::StoredArticle;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// ダイジェストの本文の抜粋の最大文字数
+pub const DEFAULT_EXCERPT_CHARS: usize = 200;
+
+/// ダイジェストの見出しの単位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DigestGrouping {
+    #[default]
+    Site,
+    Category,
+}
+
+/// ダイジェストの設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestOptions {
+    pub grouping: DigestGrouping,
+    /// 本文の抜粋の最大文字数（`0` の場合は抜粋を含めない）
+    pub excerpt_chars: usize,
+    /// 指定した場合は `grouping` の代わりにプロファイルのセクションごとにまとめる
+    pub sections: Option<DigestProfile>,
+    /// 指定した場合は先頭に1日の概要（モーニングブリーフ）を載せる
+    pub brief: Option<MorningBrief>,
+    /// 記事の前に載せる今後の締切（日付順）
+    pub deadlines: Vec<Deadline>,
+    /// 末尾に載せる「1週間前」「1年前の今日」の記事
+    pub retrospective: Vec<RetrospectiveSection>,
+}
+
+impl Default for DigestOptions {
+    fn default() -> Self {
+        Self {
+            grouping: DigestGrouping::default(),
+            excerpt_chars: DEFAULT_EXCERPT_CHARS,
+            sections: None,
+            brief: None,
+            deadlines: Vec::new(),
+            retrospective: Vec::new(),
+        }
+    }
+}
+
+/// リンクテキストに使えない角括弧をエスケープする
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]").replace('\n', " ")
+}
+
+/// サイトごと（最初に出現した順）に記事を並べた Markdown．注釈があればハイライトした箇所とメモも載せる．
+pub fn render_markdown(title: &str, articles: &[StoredArticle]) -> String {
+    let mut sites: Vec<(&str, Vec<&StoredArticle>)> = Vec::new();
+    for stored in articles {
+        let site = stored.article.site.name.as_str();
+        match sites.iter_mut().find(|(name, _)| *name == site) {
+            Some((_, entries)) => entries.push(stored),
+            None => sites.push((site, vec![stored])),
+        }
+    }
+
+    let mut out = format!("# {}\n", title);
+    for (site, entries) in sites {
+        out.push_str(&format!("\n## {}\n\n", site));
+        for stored in entries {
+            let article = &stored.article;
+            out.push_str(&format!(
+                "- [{}]({}) — {}\n",
+                escape_link_text(article.title.trim()),
+                article.article_url,
+                article.timestamp.format("%Y-%m-%d %H:%M")
+            ));
+            if let Some(line) = summary(article).lines().map(str::trim).find(|l| !l.is_empty()) {
+                out.push_str(&format!("  > {}\n", line));
+            }
+            if let Some(attribution) = attribution_text(article) {
+                out.push_str(&format!("  — {}\n", attribution));
+            }
+            // ハイライトは箇条書きの項目の続きとして字下げする
+            if !stored.highlights.is_empty() {
+                out.push_str("\n  **Highlights**\n\n");
+                for line in render_highlights_markdown(&stored.highlights).lines() {
+                    match line {
+                        "" => out.push('\n'),
+                        line => out.push_str(&format!("  {}\n", line)),
+                    }
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// 要約（無ければ概要）
+fn summary(article: &WebArticle) -> &str {
+    article
+        .properties
+        .summary
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(&article.description)
+}
+
+/// 本文の先頭から `max_chars` 文字までを1行にまとめた抜粋
+fn excerpt(text: &str, max_chars: usize) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() || max_chars == 0 {
+        return None;
+    }
+    Some(truncate_text(&text, max_chars))
+}
+
+/// `date`（ローカル時刻）に公開された記事を，サイトまたはカテゴリごとにまとめた Markdown ダイジェスト．
+/// 見出しは最初に出現した順（カテゴリの場合は宣言順），見出しの中は新しい順に並べる．
+/// プロファイルのセクションを指定した場合は，セクションの宣言順・セクションごとの並び順と件数の上限に従う．
+pub fn render_markdown_digest(date: NaiveDate, articles: &[WebArticle], options: &DigestOptions) -> String {
+    let articles: Vec<&WebArticle> = articles.iter().filter(|a| a.timestamp.date_naive() == date).collect();
+    // (見出し, 上限で切り詰める前の件数, 記事)
+    let groups: Vec<(String, usize, Vec<&WebArticle>)> = match &options.sections {
+        Some(profile) => profile
+            .assign(&articles)
+            .into_iter()
+            .map(|section| (section.title, section.total, section.articles))
+            .collect(),
+        None => group_articles(&articles, options.grouping),
+    };
+    let with_site = options.sections.is_some() || options.grouping == DigestGrouping::Category;
+
+    let mut out = format!("# News Digest {}\n\n{} articles\n", date.format("%Y-%m-%d"), articles.len());
+    if let Some(brief) = &options.brief {
+        out.push_str(&format!("\n## Morning Brief\n\n{}\n", brief.paragraph()));
+    }
+    if !options.deadlines.is_empty() {
+        out.push('\n');
+        out.push_str(&render_deadlines_markdown(&options.deadlines));
+    }
+    for (name, count, entries) in groups {
+        if count > entries.len() {
+            out.push_str(&format!("\n## {} ({} of {})\n", name, entries.len(), count));
+        } else {
+            out.push_str(&format!("\n## {} ({})\n", name, count));
+        }
+        for article in entries {
+            out.push_str(&format!(
+                "\n### [{}]({})\n\n",
+                escape_link_text(article.title.trim()),
+                article.article_url
+            ));
+            let mut meta = article.timestamp.format("%H:%M").to_string();
+            if with_site {
+                meta = format!("{} · {}", article.site.name, meta);
+            }
+            out.push_str(&format!("*{}*\n", meta));
+            if let Some(line) = summary(article).lines().map(str::trim).find(|l| !l.is_empty()) {
+                out.push_str(&format!("\n> {}\n", line));
+            }
+            if let Some(excerpt) = excerpt(&article.text, options.excerpt_chars) {
+                out.push_str(&format!("\n{}\n", excerpt));
+            }
+            if let Some(attribution) = attribution_text(article) {
+                out.push_str(&format!("\n— {}\n", attribution));
+            }
+        }
+    }
+    for section in &options.retrospective {
+        out.push_str(&format!("\n## {} ({})\n\n", section.lookback.title(), section.date.format("%Y-%m-%d")));
+        for item in &section.items {
+            out.push_str(&format!("- [{}]({}) — {}\n", escape_link_text(&item.title), item.url, item.site));
+            if let Some(summary) = &item.summary {
+                out.push_str(&format!("  > {}\n", summary));
+            }
+        }
+    }
+    out
+}
+
+/// サイトまたはカテゴリごとにまとめ，見出しの中を新しい順に並べる
+fn group_articles<'a>(
+    articles: &[&'a WebArticle],
+    grouping: DigestGrouping,
+) -> Vec<(String, usize, Vec<&'a WebArticle>)> {
+    // 見出しの順（最初に出現した順）が入力の順序によらないよう，先に新しい順に並べる
+    let mut articles = articles.to_vec();
+    sort_newest_first(&mut articles);
+    let mut groups: Vec<(String, Vec<&WebArticle>)> = Vec::new();
+    for article in articles {
+        let key = match grouping {
+            DigestGrouping::Site => article.site.name.clone(),
+            DigestGrouping::Category => SiteCategory::from_site_name(&article.site.name).to_string(),
+        };
+        match groups.iter_mut().find(|(name, _)| *name == key) {
+            Some((_, entries)) => entries.push(article),
+            None => groups.push((key, vec![article])),
+        }
+    }
+    if grouping == DigestGrouping::Category {
+        groups.sort_by_key(|(_, entries)| SiteCategory::from_site_name(&entries[0].site.name));
+    }
+    groups
+        .into_iter()
+        .map(|(name, mut entries)| {
+            entries.sort_by(|a, b| newest_first(a, b));
+            (name, entries.len(), entries)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::license::ArticleLicense;
+    use crate::export::retrospective::{Lookback, RetrospectiveItem};
+    use crate::models::annotation::Annotation;
+    use crate::models::web_article::WebArticle;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Local, TimeZone};
+
+    fn article(site: &str, title: &str, hour: u32, day: u32) -> WebArticle {
+        ArticleBuilder::new(title)
+            .with_site(site, "https://example.com")
+            .with_description("概要です")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, day, hour, 0, 0).unwrap())
+            .build()
+    }
+
+    fn stored(site: &str, title: &str, description: &str) -> StoredArticle {
+        ArticleBuilder::new(title)
+            .with_site(site, format!("https://{}.example.com", site))
+            .with_description(description)
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap())
+            .stored()
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let articles = [
+            stored("a", "[PR] First", "\n  Summary line\nsecond"),
+            stored("b", "Second", ""),
+            stored("a", "Third", ""),
+        ];
+        let md = render_markdown("Export", &articles);
+        assert!(md.starts_with("# Export\n\n## a\n\n"));
+        assert!(md.contains(&format!(
+            "- [\\[PR\\] First]({}) — 2025-10-01 09:00\n  > Summary line\n",
+            articles[0].article.article_url
+        )));
+        let headings: Vec<_> = md.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(headings, vec!["## a", "## b"]);
+    }
+
+    #[test]
+    fn test_render_markdown_with_highlights() {
+        let mut first = stored("a", "First", "");
+        let text = "生成AIの推論を高速化する";
+        first.highlights = vec![Annotation::new(&first.article.article_url, text, 0, 4, Some("重要".into())).unwrap()];
+        let url = first.article.article_url.clone();
+        let md = render_markdown("Export", &[first, stored("a", "Second", "")]);
+        assert!(md.contains(&format!(
+            "- [First]({}) — 2025-10-01 09:00\n\n  \
+             **Highlights**\n\n  > 生成AI\n\n  重要\n\n- [Second]",
+            url
+        )));
+    }
+
+    #[test]
+    fn test_render_markdown_digest_by_site() {
+        let mut first = article("Gigazine", "Morning news", 8, 1);
+        first.text = "本文の 1 行目．\n\n".to_string() + &"あ".repeat(300);
+        let morning = first.article_url.clone();
+        let articles = vec![
+            first,
+            article("JPCERT", "注意喚起", 10, 1),
+            article("Gigazine", "Evening news", 18, 1),
+            article("Gigazine", "Next day", 9, 2),
+        ];
+        let date = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+        let md = render_markdown_digest(date, &articles, &DigestOptions::default());
+
+        assert!(md.starts_with("# News Digest 2025-10-01\n\n3 articles\n"));
+        let headings: Vec<_> = md.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(headings, vec!["## Gigazine (2)", "## JPCERT (1)"]);
+        assert!(!md.contains("Next day"));
+        let evening = md.find("Evening news").unwrap();
+        assert!(evening < md.find("Morning news").unwrap());
+        assert!(md.contains(&format!("### [Morning news]({})\n\n*08:00*\n\n> 概要です\n", morning)));
+        let excerpt = md.lines().find(|l| l.starts_with("本文の")).unwrap();
+        assert!(excerpt.starts_with("本文の 1 行目． ああ"));
+        assert!(excerpt.ends_with('…'));
+        assert_eq!(excerpt.chars().count(), DEFAULT_EXCERPT_CHARS);
+
+        let mut reversed = articles.clone();
+        reversed.reverse();
+        assert_eq!(render_markdown_digest(date, &reversed, &DigestOptions::default()), md);
+    }
+
+    #[test]
+    fn test_render_markdown_digest_by_category() {
+        let articles = vec![
+            article("Gigazine", "Gadget", 8, 1),
+            article("JPCERT", "注意喚起", 10, 1),
+            article("JPCERT", "Other day", 10, 2),
+        ];
+        let options = DigestOptions {
+            grouping: DigestGrouping::Category,
+            excerpt_chars: 0,
+            sections: None,
+            brief: None,
+            deadlines: Vec::new(),
+            retrospective: Vec::new(),
+        };
+        let md = render_markdown_digest(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &articles, &options);
+        let headings: Vec<_> = md.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(headings, vec!["## security (1)", "## news (1)"]);
+        assert!(md.contains("*JPCERT · 10:00*"));
+        assert!(!md.contains("Other day"));
+    }
+
+    #[test]
+    fn test_render_markdown_digest_by_sections() {
+        let mut articles = vec![article("Gigazine", "Announcing Rust 1.90", 12, 1), article("Gigazine", "Gadget", 8, 1)];
+        articles.extend((0..12).map(|i| article("JPCERT", &format!("注意喚起 {}", "x".repeat(i)), 9, 1)));
+        let options = DigestOptions {
+            excerpt_chars: 0,
+            sections: Some(DigestProfile::editorial()),
+            ..Default::default()
+        };
+        let md = render_markdown_digest(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &articles, &options);
+        let headings: Vec<_> = md.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(headings, vec!["## Security (10 of 12)", "## Rust (1)", "## Other (1)"]);
+        assert!(md.starts_with("# News Digest 2025-10-01\n\n14 articles\n"));
+        assert!(md.contains("*Gigazine · 12:00*"));
+        // 同じ時刻の記事も毎回同じ順に並ぶ
+        assert_eq!(md, render_markdown_digest(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &articles, &options));
+    }
+
+    #[test]
+    fn test_render_markdown_digest_with_brief() {
+        let articles = vec![article("Gigazine", "Gadget", 8, 1)];
+        let options = DigestOptions {
+            brief: Some(MorningBrief {
+                sentences: vec!["One.".into(), "Two.".into()],
+            }),
+            ..Default::default()
+        };
+        let md = render_markdown_digest(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &articles, &options);
+        assert!(md.starts_with(
+            "# News Digest 2025-10-01\n\n1 articles\n\n## Morning Brief\n\nOne. Two.\n\n## Gigazine (1)\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_markdown_digest_with_deadlines() {
+        use crate::enrichment::deadlines::DeadlineKind;
+
+        let articles = vec![article("Gigazine", "Gadget", 8, 1)];
+        let options = DigestOptions {
+            deadlines: vec![Deadline {
+                article_url: "https://conf.example.com/cfp".into(),
+                title: "ACL 2026 Call for Papers".into(),
+                site: "Conference".into(),
+                kind: DeadlineKind::Submission,
+                date: NaiveDate::from_ymd_opt(2025, 11, 17).unwrap(),
+                venue: None,
+            }],
+            ..Default::default()
+        };
+        let md = render_markdown_digest(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &articles, &options);
+        assert!(md.starts_with(
+            "# News Digest 2025-10-01\n\n1 articles\n\n## Upcoming Deadlines\n\n\
+             - **2025-11-17** (submission) [ACL 2026 Call for Papers](https://conf.example.com/cfp)\n\n\
+             ## Gigazine (1)\n"
+        ));
+        assert!(!render_markdown_digest(
+            NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(),
+            &articles,
+            &DigestOptions::default()
+        )
+        .contains("Upcoming Deadlines"));
+    }
+
+    #[test]
+    fn test_render_markdown_digest_with_retrospective() {
+        let articles = vec![article("Gigazine", "Gadget", 8, 8)];
+        let options = DigestOptions {
+            retrospective: vec![RetrospectiveSection {
+                lookback: Lookback::Week,
+                date: NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(),
+                items: vec![RetrospectiveItem {
+                    title: "Old [news]".into(),
+                    url: "https://example.com/old".into(),
+                    site: "JPCERT".into(),
+                    summary: Some("要約".into()),
+                }],
+            }],
+            ..Default::default()
+        };
+        let md = render_markdown_digest(NaiveDate::from_ymd_opt(2025, 10, 8).unwrap(), &articles, &options);
+        assert!(md.ends_with(
+            "\n## One week ago (2025-10-01)\n\n- [Old \\[news\\]](https://example.com/old) — JPCERT\n  > 要約\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_markdown_digest_with_attribution() {
+        let mut licensed = article("Example", "Licensed", 8, 1);
+        licensed.properties.license = Some(ArticleLicense {
+            name: Some("CC BY 4.0".into()),
+            url: None,
+            copyright: Some("© 2025 Example".into()),
+        });
+        let url = licensed.article_url.clone();
+        let articles = vec![licensed, article("Gigazine", "Unlicensed", 9, 1)];
+        let date = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+        let md = render_markdown_digest(date, &articles, &DigestOptions::default());
+        assert!(md.contains(&format!(
+            "\n— 出典: Example「Licensed」 {} / ライセンス: CC BY 4.0 / © 2025 Example\n",
+            url
+        )));
+        assert_eq!(md.matches("出典:").count(), 1);
+    }
+}
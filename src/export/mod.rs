@@ -0,0 +1,9 @@
+pub mod feed;
+pub mod ics;
+pub mod jsonl;
+pub mod markdown;
+pub mod notion;
+pub mod obsidian;
+pub mod org;
+pub mod retrospective;
+pub mod sections;
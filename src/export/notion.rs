@@ -0,0 +1,271 @@
+//! 保存済みの記事を Notion のデータベースへ書き出す．
+//!
+//! 記事の URL をキーにデータベースを検索し，既にページがあれば更新，無ければ作成する．
+//! データベースには次のプロパティを用意しておく（名前は完全一致）．
+//!
+//! | プロパティ | 種類 | 内容 |
+//! |---|---|---|
+//! | `Name` | タイトル | 記事のタイトル |
+//! | `URL` | URL | 記事の URL（重複判定のキー） |
+//! | `Site` | セレクト | サイト名 |
+//! | `Published` | 日付 | 公開日時 |
+//! | `Status` | セレクト | `new` / `archived` |
+//! | `Summary` | テキスト | LLM の要約（無ければ概要） |
+//! | `AI` / `Security` / `IT` / `Technology` / `Product` / `Paper` | チェックボックス | 分類の結果 |
+//! | `Highlights` | テキスト | ハイライトした箇所とメモ（共有の注釈がある記事のみ書き込む） |
+
+use crate::models::annotation::render_highlights_markdown;
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::StoredArticle;
+use chrono::SecondsFormat;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// インテグレーションのトークンを指定する環境変数
+pub const NOTION_TOKEN_ENV: &str = "CLIPPER_NOTION_TOKEN";
+/// 書き出し先のデータベースの ID を指定する環境変数
+pub const NOTION_DATABASE_ENV: &str = "CLIPPER_NOTION_DATABASE_ID";
+
+const NOTION_API_BASE: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// リッチテキスト1要素あたりの上限（文字数）
+const MAX_RICH_TEXT_CHARS: usize = 2000;
+/// リクエストの間隔（Notion API の上限は平均で毎秒3回）
+const REQUEST_INTERVAL: Duration = Duration::from_millis(350);
+/// `429 Too Many Requests` を受けたときの再送回数
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// 書き出した結果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct NotionExportSummary {
+    pub created: usize,
+    pub updated: usize,
+}
+
+#[derive(Clone)]
+pub struct NotionExporter {
+    token: String,
+    database_id: String,
+    api_base: String,
+}
+
+impl std::fmt::Debug for NotionExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotionExporter")
+            .field("database_id", &self.database_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NotionExporter {
+    pub fn new(token: impl Into<String>, database_id: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            database_id: database_id.into(),
+            api_base: NOTION_API_BASE.to_string(),
+        }
+    }
+
+    /// `CLIPPER_NOTION_TOKEN` / `CLIPPER_NOTION_DATABASE_ID` から作成する
+    pub fn from_env() -> AppResult<Self> {
+        let var = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| AppError::InternalError(format!("{} is not set", key)))
+        };
+        Ok(Self::new(var(NOTION_TOKEN_ENV)?, var(NOTION_DATABASE_ENV)?))
+    }
+
+    /// URL で既存のページを検索するリクエストの本文
+    pub fn query_body(url: &str) -> Value {
+        json!({
+            "filter": { "property": "URL", "url": { "equals": url } },
+            "page_size": 1,
+        })
+    }
+
+    /// 記事に対応するページのプロパティ
+    pub fn page_properties(stored: &StoredArticle) -> Value {
+        let article = &stored.article;
+        let p = &article.properties;
+        let summary = p
+            .summary
+            .as_deref()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&article.description)
+            .trim();
+        let mut properties = json!({
+            "Name": { "title": rich_text(article.title.trim()) },
+            "URL": { "url": article.article_url },
+            "Site": { "select": { "name": select_name(&article.site.name) } },
+            "Published": { "date": { "start": article.timestamp.to_rfc3339_opts(SecondsFormat::Secs, false) } },
+            "Status": { "select": { "name": stored.status.to_string() } },
+            "Summary": { "rich_text": rich_text(summary) },
+        });
+        for (name, flag) in [
+            ("AI", p.is_ai_related),
+            ("Security", p.is_security_related),
+            ("IT", p.is_it_related),
+            ("Technology", p.is_new_technology_related),
+            ("Product", p.is_new_product_related),
+            ("Paper", p.is_new_academic_paper_related),
+        ] {
+            properties[name] = json!({ "checkbox": flag.unwrap_or(false) });
+        }
+        if !stored.highlights.is_empty() {
+            let highlights = render_highlights_markdown(&stored.highlights);
+            properties["Highlights"] = json!({ "rich_text": rich_text(&highlights) });
+        }
+        properties
+    }
+
+    /// 記事をデータベースに書き出す．同じ URL の記事は最初の1件だけを書き出す．
+    pub async fn export(&self, articles: &[StoredArticle]) -> AppResult<NotionExportSummary> {
+        let client = request::Client::new();
+        let mut summary = NotionExportSummary::default();
+        let mut seen = HashSet::new();
+        for stored in articles.iter().filter(|s| seen.insert(s.article.article_url.as_str())) {
+            let properties = Self::page_properties(stored);
+            let found = self
+                .send(
+                    &client,
+                    request::Method::POST,
+                    &format!("{}/databases/{}/query", self.api_base, self.database_id),
+                    &Self::query_body(&stored.article.article_url),
+                )
+                .await?;
+            match found["results"][0]["id"].as_str() {
+                Some(page_id) => {
+                    self.send(
+                        &client,
+                        request::Method::PATCH,
+                        &format!("{}/pages/{}", self.api_base, page_id),
+                        &json!({ "properties": properties }),
+                    )
+                    .await?;
+                    summary.updated += 1;
+                }
+                None => {
+                    self.send(
+                        &client,
+                        request::Method::POST,
+                        &format!("{}/pages", self.api_base),
+                        &json!({ "parent": { "database_id": self.database_id }, "properties": properties }),
+                    )
+                    .await?;
+                    summary.created += 1;
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    async fn send(
+        &self,
+        client: &request::Client,
+        method: request::Method,
+        url: &str,
+        body: &Value,
+    ) -> AppResult<Value> {
+        for _ in 0..=MAX_RATE_LIMIT_RETRIES {
+            tokio::time::sleep(REQUEST_INTERVAL).await;
+            let response = client
+                .request(method.clone(), url)
+                .bearer_auth(&self.token)
+                .header("Notion-Version", NOTION_VERSION)
+                .json(body)
+                .send()
+                .await?;
+            if response.status() == request::StatusCode::TOO_MANY_REQUESTS {
+                let wait = response
+                    .headers()
+                    .get(request::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .unwrap_or(1);
+                tokio::time::sleep(Duration::from_secs(wait.min(60))).await;
+                continue;
+            }
+            let status = response.status();
+            let value: Value = response.json().await.unwrap_or_default();
+            if !status.is_success() {
+                return Err(AppError::InternalError(format!(
+                    "Notion API returned {}: {}",
+                    status,
+                    value["message"].as_str().unwrap_or_default()
+                )));
+            }
+            return Ok(value);
+        }
+        Err(AppError::InternalError("Notion API is rate limited".into()))
+    }
+}
+
+/// 2000文字ごとに分けたリッチテキスト
+fn rich_text(text: &str) -> Vec<Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(MAX_RICH_TEXT_CHARS)
+        .map(|chunk| json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect()
+}
+
+/// セレクトの選択肢に使えないカンマを置き換える
+fn select_name(name: &str) -> String {
+    name.replace(',', " ").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::web_article::Status;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Local, TimeZone};
+
+    fn stored() -> StoredArticle {
+        let mut stored = ArticleBuilder::new(" Title ")
+            .with_site("Example, Inc", "https://example.com")
+            .with_url("https://example.com/1")
+            .with_description("概要")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap())
+            .stored();
+        stored.status = Status::Archived;
+        stored.article.properties.summary = Some("あ".repeat(MAX_RICH_TEXT_CHARS + 10));
+        stored.article.properties.is_ai_related = Some(true);
+        stored.article.properties.is_security_related = None;
+        stored
+    }
+
+    #[test]
+    fn test_page_properties() {
+        let properties = NotionExporter::page_properties(&stored());
+        assert_eq!(properties["Name"]["title"][0]["text"]["content"], "Title");
+        assert_eq!(properties["URL"]["url"], "https://example.com/1");
+        assert_eq!(properties["Site"]["select"]["name"], "Example  Inc");
+        assert_eq!(properties["Status"]["select"]["name"], "archived");
+        assert!(properties["Published"]["date"]["start"].as_str().unwrap().starts_with("2025-10-01T09:00:00"));
+        assert_eq!(properties["Summary"]["rich_text"].as_array().unwrap().len(), 2);
+        assert_eq!(properties["AI"]["checkbox"], true);
+        assert_eq!(properties["Security"]["checkbox"], false);
+        assert!(properties.get("Highlights").is_none());
+
+        let mut annotated = stored();
+        annotated.highlights = vec![
+            crate::models::annotation::Annotation::new(&annotated.article.article_url, "Title", 0, 5, None).unwrap(),
+        ];
+        let properties = NotionExporter::page_properties(&annotated);
+        assert_eq!(properties["Highlights"]["rich_text"][0]["text"]["content"], "> Title");
+    }
+
+    #[test]
+    fn test_query_body_and_debug_hides_token() {
+        let body = NotionExporter::query_body("https://example.com/1");
+        assert_eq!(body["filter"]["url"]["equals"], "https://example.com/1");
+        let exporter = NotionExporter::new("secret_token", "db");
+        assert!(!format!("{:?}", exporter).contains("secret_token"));
+    }
+}
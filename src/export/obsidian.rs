@@ -0,0 +1,238 @@
+//! 保存済みの記事を Obsidian の Vault に1記事1ノートの Markdown として書き出す．
+//!
+//! ノートは `<vault>/Clippings/<サイト名>/<公開日> <タイトル>.md` に置き，front-matter にサイト・URL・タグ・公開日時を書く．
+//! 本文からサイト名のノート（`[[Gigazine]]`）へリンクするため，サイトのノートのバックリンクに記事が並ぶ．
+//! 共有の注釈がある記事は，ハイライトした箇所とメモを `## Highlights` に書く．
+
+use crate::enrichment::license::attribution_text;
+use crate::models::annotation::render_highlights_markdown;
+use crate::models::fairness::SiteCategory;
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::StoredArticle;
+use chrono::SecondsFormat;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// ノートを置く Vault 内のフォルダ
+pub const CLIPPINGS_FOLDER: &str = "Clippings";
+/// ファイル名に使うタイトルの最大文字数
+const MAX_FILE_TITLE_CHARS: usize = 80;
+
+/// 書き出した結果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct VaultExportSummary {
+    pub written: usize,
+    /// 内容が変わらないため書き込まなかったノート
+    pub unchanged: usize,
+}
+
+/// ファイル名・リンクに使えない文字（`\ / : * ? " < > |` と Obsidian のリンク記法の `# ^ [ ]`）を取り除く
+pub fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let cleaned: String = cleaned.chars().take(MAX_FILE_TITLE_CHARS).collect();
+    let cleaned = cleaned.trim().trim_start_matches('.').trim();
+    if cleaned.is_empty() { "untitled".to_string() } else { cleaned.to_string() }
+}
+
+/// Vault のルートからのノートのパス
+pub fn note_path(stored: &StoredArticle) -> PathBuf {
+    let article = &stored.article;
+    Path::new(CLIPPINGS_FOLDER)
+        .join(sanitize_file_name(&article.site.name))
+        .join(format!(
+            "{} {}.md",
+            article.timestamp.format("%Y-%m-%d"),
+            sanitize_file_name(&article.title)
+        ))
+}
+
+/// タグに使えない文字をハイフンにした小文字のタグ
+fn tag(name: &str) -> String {
+    let tag: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' || c == '/' { c } else { '-' })
+        .collect();
+    tag.split('-').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("-")
+}
+
+fn tags(stored: &StoredArticle) -> Vec<String> {
+    let article = &stored.article;
+    let p = &article.properties;
+    let mut tags = vec![
+        "clipping".to_string(),
+        tag(&SiteCategory::from_site_name(&article.site.name).to_string()),
+        tag(&article.site.name),
+    ];
+    for (flag, name) in [
+        (p.is_ai_related, "ai"),
+        (p.is_security_related, "security"),
+        (p.is_it_related, "it"),
+        (p.is_new_technology_related, "technology"),
+        (p.is_new_product_related, "product"),
+        (p.is_new_academic_paper_related, "paper"),
+    ] {
+        if flag.unwrap_or(false) {
+            tags.push(name.to_string());
+        }
+    }
+    tags.retain(|t| !t.is_empty());
+    tags.dedup();
+    tags
+}
+
+/// YAML のダブルクォート文字列
+fn yaml_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', " ")
+        .replace('\r', " ");
+    format!("\"{}\"", escaped)
+}
+
+/// front-matter 付きのノート
+pub fn render_note(stored: &StoredArticle) -> String {
+    let article = &stored.article;
+    let mut out = String::from("---\n");
+    out.push_str(&format!("title: {}\n", yaml_string(article.title.trim())));
+    out.push_str(&format!("site: {}\n", yaml_string(&article.site.name)));
+    out.push_str(&format!("url: {}\n", yaml_string(&article.article_url)));
+    out.push_str(&format!(
+        "published: {}\n",
+        article.timestamp.to_rfc3339_opts(SecondsFormat::Secs, false)
+    ));
+    out.push_str(&format!(
+        "clipped: {}\n",
+        stored.created_at.to_rfc3339_opts(SecondsFormat::Secs, false)
+    ));
+    out.push_str(&format!("status: {}\n", stored.status));
+    out.push_str("tags:\n");
+    for tag in tags(stored) {
+        out.push_str(&format!("  - {}\n", yaml_string(&tag)));
+    }
+    if let Some(license) = article.properties.license.as_ref().and_then(|l| l.name.as_deref()) {
+        out.push_str(&format!("license: {}\n", yaml_string(license)));
+    }
+    out.push_str("---\n\n");
+
+    out.push_str(&format!("# {}\n\n", article.title.trim()));
+    out.push_str(&format!(
+        "[[{}]] · [元の記事]({})\n",
+        sanitize_file_name(&article.site.name),
+        article.article_url
+    ));
+    let summary = article
+        .properties
+        .summary
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(&article.description)
+        .trim();
+    if !summary.is_empty() {
+        out.push_str("\n## Summary\n\n");
+        out.push_str(summary);
+        out.push('\n');
+    }
+    if !article.text.trim().is_empty() {
+        out.push_str("\n## Text\n\n");
+        out.push_str(article.text.trim());
+        out.push('\n');
+    }
+    if !stored.highlights.is_empty() {
+        out.push_str("\n## Highlights\n\n");
+        out.push_str(&render_highlights_markdown(&stored.highlights));
+        out.push('\n');
+    }
+    if let Some(attribution) = attribution_text(article) {
+        out.push_str(&format!("\n---\n\n{}\n", attribution));
+    }
+    out
+}
+
+/// `vault` に記事のノートを書き出す．同じパスのノートは上書きし，内容が同じ場合は書き込まない．
+pub fn write_vault(vault: &Path, articles: &[StoredArticle]) -> AppResult<VaultExportSummary> {
+    let mut summary = VaultExportSummary::default();
+    for stored in articles {
+        let path = vault.join(note_path(stored));
+        let note = render_note(stored);
+        if std::fs::read_to_string(&path).is_ok_and(|existing| existing == note) {
+            summary.unchanged += 1;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", parent, e)))?;
+        }
+        std::fs::write(&path, note)
+            .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e)))?;
+        summary.written += 1;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::annotation::Annotation;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Local, TimeZone};
+
+    fn stored(title: &str) -> StoredArticle {
+        let mut stored = ArticleBuilder::new(title)
+            .with_site("Gigazine", "https://gigazine.net")
+            .with_url("https://gigazine.net/news/1")
+            .with_description("概要")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap())
+            .with_text("本文")
+            .stored();
+        stored.article.properties.is_ai_related = Some(true);
+        stored
+    }
+
+    #[test]
+    fn test_note_path_and_sanitize() {
+        assert_eq!(
+            note_path(&stored("AI: \"新モデル\" [速報] / 2025")),
+            Path::new("Clippings/Gigazine/2025-10-01 AI 新モデル 速報 2025.md")
+        );
+        assert_eq!(sanitize_file_name("..."), "untitled");
+        assert_eq!(sanitize_file_name(&"あ".repeat(100)).chars().count(), MAX_FILE_TITLE_CHARS);
+    }
+
+    #[test]
+    fn test_render_note_front_matter() {
+        let note = render_note(&stored("Say \"hi\""));
+        assert!(note.starts_with("---\ntitle: \"Say \\\"hi\\\"\"\nsite: \"Gigazine\"\n"));
+        assert!(note.contains("url: \"https://gigazine.net/news/1\"\n"));
+        assert!(note.contains("published: 2025-10-01T09:00:00"));
+        assert!(note.contains("tags:\n  - \"clipping\"\n  - \"news\"\n  - \"gigazine\"\n  - \"ai\"\n"));
+        assert!(note.contains("[[Gigazine]] · [元の記事](https://gigazine.net/news/1)\n"));
+        assert!(note.contains("## Summary\n\n概要\n"));
+        assert!(note.contains("## Text\n\n本文\n"));
+        assert!(!note.contains("## Highlights"));
+
+        let mut annotated = stored("Annotated");
+        annotated.highlights = vec![Annotation::new(&annotated.article.article_url, "本文", 0, 2, None).unwrap()];
+        assert!(render_note(&annotated).contains("## Text\n\n本文\n\n## Highlights\n\n> 本文\n"));
+    }
+
+    #[test]
+    fn test_write_vault_skips_unchanged_notes() {
+        let vault = std::env::temp_dir().join(format!("clipper-vault-{}", uuid::Uuid::new_v4()));
+        let articles = vec![stored("First")];
+        assert_eq!(write_vault(&vault, &articles).unwrap().written, 1);
+        assert_eq!(write_vault(&vault, &articles).unwrap().unchanged, 1);
+        assert!(vault.join("Clippings/Gigazine/2025-10-01 First.md").exists());
+        std::fs::remove_dir_all(&vault).unwrap();
+    }
+}
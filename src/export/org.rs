@@ -0,0 +1,222 @@
+//! 記事やダイジェストを Emacs の Org-mode 形式で書き出す．
+//!
+//! 記事のステータスは TODO キーワード（`new` → `TODO`，`archived` → `DONE`）に，
+//! メタデータは PROPERTIES ドロワーに，カテゴリ判定の結果はタグに，共有の注釈は `Highlights` の見出しに対応させる．
+
+use crate::enrichment::license::attribution_text;
+use crate::models::web_article::{Status, WebArticle};
+use crate::storage::StoredArticle;
+use chrono::{DateTime, Local, NaiveDate};
+
+/// ステータスに対応する TODO キーワード
+pub fn todo_keyword(status: Status) -> &'static str {
+    match status {
+        Status::New => "TODO",
+        Status::Archived => "DONE",
+    }
+}
+
+/// 非アクティブなタイムスタンプ（`[2025-10-01 Wed 09:00]`）
+fn org_timestamp(time: &DateTime<Local>) -> String {
+    format!("[{}]", time.format("%Y-%m-%d %a %H:%M"))
+}
+
+/// リンクの説明文に使えない角括弧を置き換える
+fn link_description(text: &str) -> String {
+    text.replace('[', "(").replace(']', ")").replace('\n', " ")
+}
+
+/// `[[url][title]]` 形式のリンク
+pub fn org_link(url: &str, title: &str) -> String {
+    format!("[[{}][{}]]", url.replace(' ', "%20"), link_description(title.trim()))
+}
+
+/// カテゴリ判定の結果をタグ（`:ai:security:`）にする
+fn tags(article: &WebArticle) -> String {
+    let p = &article.properties;
+    let tags: Vec<&str> = [
+        (p.is_ai_related, "ai"),
+        (p.is_security_related, "security"),
+        (p.is_it_related, "it"),
+        (p.is_new_technology_related, "technology"),
+        (p.is_new_product_related, "product"),
+        (p.is_new_academic_paper_related, "paper"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| flag.unwrap_or(false))
+    .map(|(_, tag)| tag)
+    .collect();
+    if tags.is_empty() {
+        String::new()
+    } else {
+        format!(" :{}:", tags.join(":"))
+    }
+}
+
+/// 本文中の行が見出しやキーワード行として解釈されないようにインデントする
+fn indent_body(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("  {}", line.trim_end())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 記事1件分の見出しを `level` の深さで書き出す．注釈があればハイライトした箇所を1段深い見出しにまとめる．
+pub fn render_org_entry(stored: &StoredArticle, level: usize) -> String {
+    let article = &stored.article;
+    let mut out = format!(
+        "{} {} {}{}\n",
+        "*".repeat(level.max(1)),
+        todo_keyword(stored.status),
+        org_link(&article.article_url, &article.title),
+        tags(article)
+    );
+    out.push_str(":PROPERTIES:\n");
+    out.push_str(&format!(":ID:       {}\n", stored.id.to_string()));
+    out.push_str(&format!(":URL:      {}\n", article.article_url));
+    out.push_str(&format!(":SITE:     {}\n", article.site.name));
+    out.push_str(&format!(":PUBLISHED: {}\n", org_timestamp(&article.timestamp)));
+    out.push_str(&format!(":CLIPPED:  {}\n", org_timestamp(&stored.created_at)));
+    if let Some(license) = &article.properties.license {
+        if let Some(name) = license.name.as_deref().or(license.url.as_deref()) {
+            out.push_str(&format!(":LICENSE:  {}\n", name));
+        }
+        if let Some(copyright) = &license.copyright {
+            out.push_str(&format!(":COPYRIGHT: {}\n", copyright));
+        }
+    }
+    out.push_str(":END:\n");
+
+    let summary = article
+        .properties
+        .summary
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(&article.description);
+    if !summary.trim().is_empty() {
+        out.push_str(&indent_body(summary));
+        out.push('\n');
+    }
+    if let Some(attribution) = attribution_text(article) {
+        out.push_str(&indent_body(&attribution));
+        out.push('\n');
+    }
+    if !stored.highlights.is_empty() {
+        out.push_str(&format!("{} Highlights\n", "*".repeat(level.max(1) + 1)));
+        for annotation in &stored.highlights {
+            out.push_str("  #+BEGIN_QUOTE\n");
+            out.push_str(&indent_body(annotation.quote.trim()));
+            out.push_str("\n  #+END_QUOTE\n");
+            if let Some(note) = annotation.note.as_deref().filter(|n| !n.trim().is_empty()) {
+                out.push_str(&indent_body(note));
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// 記事の一覧をトップレベルの見出しとして書き出す
+pub fn render_org_articles(articles: &[StoredArticle]) -> String {
+    let mut out = String::from("#+TODO: TODO | DONE\n\n");
+    for stored in articles {
+        out.push_str(&render_org_entry(stored, 1));
+    }
+    out
+}
+
+/// サイトごとにまとめたダイジェストを書き出す（サイトは最初に出現した順）
+pub fn render_org_digest(title: &str, date: NaiveDate, articles: &[StoredArticle]) -> String {
+    let mut sites: Vec<(&str, Vec<&StoredArticle>)> = Vec::new();
+    for stored in articles {
+        let site = stored.article.site.name.as_str();
+        match sites.iter_mut().find(|(name, _)| *name == site) {
+            Some((_, entries)) => entries.push(stored),
+            None => sites.push((site, vec![stored])),
+        }
+    }
+
+    let mut out = format!(
+        "#+TITLE: {}\n#+DATE: <{}>\n#+TODO: TODO | DONE\n\n",
+        title.replace('\n', " "),
+        date.format("%Y-%m-%d %a")
+    );
+    for (site, entries) in sites {
+        out.push_str(&format!("* {} [{}]\n", site, entries.len()));
+        for stored in entries {
+            out.push_str(&render_org_entry(stored, 2));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::annotation::Annotation;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::TimeZone;
+
+    fn stored(site: &str, slug: &str, title: &str, status: Status) -> StoredArticle {
+        let mut stored = ArticleBuilder::new(title)
+            .with_site(site, format!("https://{}.example.com", site))
+            .with_url(format!("https://{}.example.com/{}", site, slug))
+            .with_description("概要の1行目\n* 見出しに見える行")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap())
+            .stored();
+        stored.status = status;
+        stored.article.properties.is_ai_related = Some(true);
+        stored.article.properties.is_security_related = Some(true);
+        stored
+    }
+
+    #[test]
+    fn test_render_org_entry() {
+        let entry = render_org_entry(&stored("Gigazine", "news/1", "[速報] 新モデル発表", Status::Archived), 1);
+        let mut lines = entry.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "* DONE [[https://Gigazine.example.com/news/1][(速報) 新モデル発表]] :ai:security:"
+        );
+        assert!(entry.contains(":SITE:     Gigazine\n"));
+        assert!(entry.contains(":PUBLISHED: [2025-10-01 Wed 09:00]\n"));
+        // 本文中の `*` 始まりの行は見出しにならない
+        assert!(entry.contains("\n  * 見出しに見える行"));
+        assert!(!entry.lines().skip(1).any(|line| line.starts_with('*')));
+    }
+
+    #[test]
+    fn test_render_org_entry_with_highlights() {
+        let mut stored = stored("Gigazine", "news/1", "新モデル発表", Status::New);
+        stored.highlights = vec![
+            Annotation::new(&stored.article.article_url, "* 見出しに見える行", 0, 10, Some("要確認".into())).unwrap(),
+        ];
+        let entry = render_org_entry(&stored, 2);
+        assert!(entry.ends_with(
+            "*** Highlights\n  #+BEGIN_QUOTE\n  * 見出しに見える行\n  #+END_QUOTE\n  要確認\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_org_digest_groups_by_site() {
+        let articles = vec![
+            stored("Gigazine", "a", "A", Status::New),
+            stored("ITmedia", "b", "B", Status::New),
+            stored("Gigazine", "c", "C", Status::Archived),
+        ];
+        let digest = render_org_digest("Daily Digest", NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &articles);
+        assert!(digest.starts_with("#+TITLE: Daily Digest\n#+DATE: <2025-10-01 Wed>\n"));
+        let headings: Vec<&str> = digest
+            .lines()
+            .filter(|l| l.starts_with('*'))
+            .map(|l| l.split(" [[").next().unwrap())
+            .collect();
+        assert_eq!(headings, vec!["* Gigazine [2]", "** TODO", "** DONE", "* ITmedia [1]", "** TODO"]);
+    }
+}
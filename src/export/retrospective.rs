@@ -0,0 +1,210 @@
+//! ダイジェストの「1週間前」「1年前の今日」のセクション．
+//!
+//! 保存済みの記事から，ダイジェストの対象日のちょうど1週間前・1年前に公開された記事を日付の範囲で取り出し，
+//! 重要度の高いものを数件だけ載せる．長く運用しているほど過去の記事を読み返す機会が増える．
+
+use crate::enrichment::brief::articles_of_day;
+use crate::enrichment::sampling::ranking_score;
+use crate::models::priority::PriorityOverrides;
+use crate::models::web_article::WebArticle;
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::ArticleStore;
+use chrono::{Days, Months, NaiveDate};
+use std::str::FromStr;
+use strum::{Display, EnumString};
+
+/// 載せる期間（カンマ区切りの `week` / `year`）を指定する環境変数．未設定の場合は載せない．
+pub const RETROSPECTIVE_ENV: &str = "CLIPPER_DIGEST_RETROSPECTIVE";
+/// 期間ごとに載せる記事数
+pub const RETROSPECTIVE_ITEMS_ENV: &str = "CLIPPER_DIGEST_RETROSPECTIVE_ITEMS";
+const DEFAULT_MAX_ITEMS: usize = 3;
+
+/// 振り返る期間
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Lookback {
+    Week,
+    Year,
+}
+
+impl Lookback {
+    /// `date` のちょうど1週間前・1年前（2月29日の1年前は2月28日）
+    pub fn date(self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Lookback::Week => date.checked_sub_days(Days::new(7)),
+            Lookback::Year => date.checked_sub_months(Months::new(12)),
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Lookback::Week => "One week ago",
+            Lookback::Year => "On this day last year",
+        }
+    }
+}
+
+/// セクションに載せる記事
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetrospectiveItem {
+    pub title: String,
+    pub url: String,
+    pub site: String,
+    pub summary: Option<String>,
+}
+
+/// 1つの期間のセクション
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetrospectiveSection {
+    pub lookback: Lookback,
+    pub date: NaiveDate,
+    pub items: Vec<RetrospectiveItem>,
+}
+
+/// 振り返りのセクションの設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Retrospective {
+    pub lookbacks: Vec<Lookback>,
+    pub max_items: usize,
+    /// 重要度の計算に使うサイトの優先度
+    pub priorities: PriorityOverrides,
+}
+
+impl Retrospective {
+    pub fn new(lookbacks: Vec<Lookback>) -> Self {
+        Self {
+            lookbacks,
+            max_items: DEFAULT_MAX_ITEMS,
+            priorities: PriorityOverrides::default(),
+        }
+    }
+
+    /// `CLIPPER_DIGEST_RETROSPECTIVE`（例: `week,year`）が設定されていれば作成する
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let value = std::env::var(RETROSPECTIVE_ENV).unwrap_or_default();
+        let lookbacks = value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| Lookback::from_str(v).map_err(|e| AppError::EnumParseError(format!("{}: {}", v, e))))
+            .collect::<AppResult<Vec<_>>>()?;
+        if lookbacks.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            lookbacks,
+            max_items: parse_env_or(RETROSPECTIVE_ITEMS_ENV, DEFAULT_MAX_ITEMS)?.max(1),
+            priorities: PriorityOverrides::from_env()?,
+        }))
+    }
+
+    /// `articles` のうち `lookback` の日に公開された記事から，重要度の高いものを選ぶ（該当が無ければ `None`）．
+    /// 重要度はサイトの優先度（公開時点の `ranking_score`）で，同じ場合は要約のある記事（LLM の予算内で選ばれた記事），
+    /// 新しい記事，URL の順に並べる．
+    pub fn select(
+        &self,
+        lookback: Lookback,
+        date: NaiveDate,
+        articles: &[WebArticle],
+    ) -> Option<RetrospectiveSection> {
+        let target = lookback.date(date)?;
+        let mut candidates: Vec<&WebArticle> =
+            articles.iter().filter(|a| a.timestamp.date_naive() == target).collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let score = |article: &WebArticle| ranking_score(article, &self.priorities, article.timestamp);
+        candidates.sort_by(|a, b| {
+            score(b)
+                .total_cmp(&score(a))
+                .then_with(|| summary(b).is_some().cmp(&summary(a).is_some()))
+                .then_with(|| b.timestamp.cmp(&a.timestamp))
+                .then_with(|| a.article_url.cmp(&b.article_url))
+        });
+        let items = candidates
+            .into_iter()
+            .take(self.max_items)
+            .map(|article| RetrospectiveItem {
+                title: article.title.trim().to_string(),
+                url: article.article_url.clone(),
+                site: article.site.name.clone(),
+                summary: summary(article),
+            })
+            .collect();
+        Some(RetrospectiveSection {
+            lookback,
+            date: target,
+            items,
+        })
+    }
+
+    /// 期間ごとに保存済みの記事をその日の範囲で取り出してセクションを作る（記事の無い期間は含めない）
+    pub async fn query(&self, store: &dyn ArticleStore, date: NaiveDate) -> AppResult<Vec<RetrospectiveSection>> {
+        let mut sections = Vec::new();
+        for &lookback in &self.lookbacks {
+            let Some(target) = lookback.date(date) else {
+                continue;
+            };
+            let articles = articles_of_day(store, target).await?;
+            sections.extend(self.select(lookback, date, &articles));
+        }
+        Ok(sections)
+    }
+}
+
+/// 要約（LLM の要約が無ければ `None`）を1行にしたもの
+fn summary(article: &WebArticle) -> Option<String> {
+    let summary = article.properties.summary.as_deref()?.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!summary.is_empty()).then_some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::priority::SitePriority;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Local, TimeZone};
+
+    fn article(site: &str, path: &str, month: u32, day: u32, hour: u32) -> WebArticle {
+        ArticleBuilder::new(format!("Title {}", path))
+            .with_site(site, "https://example.com")
+            .with_url(format!("https://example.com/{}", path))
+            .with_timestamp(Local.with_ymd_and_hms(2024, month, day, hour, 0, 0).unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_lookback_dates() {
+        let date = NaiveDate::from_ymd_opt(2024, 10, 1).unwrap();
+        assert_eq!(Lookback::Week.date(date), NaiveDate::from_ymd_opt(2024, 9, 24));
+        assert_eq!(Lookback::Year.date(date), NaiveDate::from_ymd_opt(2023, 10, 1));
+        let leap_day = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert_eq!(Lookback::Year.date(leap_day), NaiveDate::from_ymd_opt(2023, 2, 28));
+        assert_eq!("year".parse::<Lookback>().unwrap(), Lookback::Year);
+    }
+
+    #[test]
+    fn test_select_ranks_by_priority_and_summary() {
+        let mut summarized = article("Gigazine", "summarized", 9, 24, 8);
+        summarized.properties.summary = Some("要約  です".into());
+        let articles = vec![
+            article("Gigazine", "plain", 9, 24, 12),
+            summarized,
+            article("JPCERT", "critical", 9, 24, 7),
+            article("Gigazine", "other-day", 9, 25, 9),
+        ];
+        let mut retrospective = Retrospective::new(vec![Lookback::Week]);
+        retrospective.max_items = 2;
+        retrospective.priorities = PriorityOverrides::default().with_priority("JPCERT", SitePriority::Critical);
+
+        let date = NaiveDate::from_ymd_opt(2024, 10, 1).unwrap();
+        let section = retrospective.select(Lookback::Week, date, &articles).unwrap();
+        assert_eq!(section.date, NaiveDate::from_ymd_opt(2024, 9, 24).unwrap());
+        let urls: Vec<&str> = section.items.iter().map(|i| i.url.as_str()).collect();
+        assert_eq!(urls, ["https://example.com/critical", "https://example.com/summarized"]);
+        assert_eq!(section.items[1].summary.as_deref(), Some("要約 です"));
+
+        assert!(retrospective.select(Lookback::Year, date, &articles).is_none());
+    }
+}
@@ -0,0 +1,308 @@
+//! ダイジェストの編集上の構成（セクションの見出し・順番・件数の上限）．
+//!
+//! プロファイルはセクションを宣言順に並べたもので，記事は条件（サイトのカテゴリ，分類のタグ，タイトルのキーワード）に
+//! 最初に当てはまったセクションに入る．どのセクションにも当てはまらない記事は `other` のセクションにまとめる．
+
+use crate::models::fairness::SiteCategory;
+use crate::models::ordering::{newest_first, oldest_first};
+use crate::models::tag::ArticleTag;
+use crate::models::web_article::WebArticle;
+use crate::shared::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use strum::{Display, EnumString};
+
+/// ダイジェストのプロファイルを指定する環境変数（組み込みのプロファイル名，または TOML ファイルのパス）
+pub const DIGEST_PROFILE_ENV: &str = "CLIPPER_DIGEST_PROFILE";
+/// 組み込みのプロファイル名
+pub const EDITORIAL_PROFILE: &str = "editorial";
+
+const DEFAULT_OTHER_TITLE: &str = "Other";
+
+/// セクション内の記事の並び順
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum SectionOrder {
+    #[default]
+    Newest,
+    Oldest,
+    /// サイト名順（同じサイトの中は新しい順）
+    Site,
+}
+
+/// ダイジェストのセクション．条件を1つも指定しない場合は全ての記事が当てはまる．
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DigestSection {
+    pub title: String,
+    #[serde(default)]
+    pub categories: Vec<SiteCategory>,
+    #[serde(default)]
+    pub tags: Vec<ArticleTag>,
+    /// タイトルに含まれる語（大文字小文字を区別しない．英数字の語は単語単位で照合する）
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// セクションに載せる記事数の上限
+    #[serde(default)]
+    pub max_items: Option<usize>,
+    #[serde(default)]
+    pub order: SectionOrder,
+}
+
+impl DigestSection {
+    pub fn matches(&self, article: &WebArticle) -> bool {
+        if self.categories.is_empty() && self.tags.is_empty() && self.keywords.is_empty() {
+            return true;
+        }
+        let category = SiteCategory::from_site_name(&article.site.name);
+        self.categories.contains(&category)
+            || self.tags.iter().any(|tag| tag.matches(article))
+            || self.keywords.iter().any(|keyword| contains_word(&article.title, keyword))
+    }
+}
+
+/// セクションの構成．
+///
+/// ```toml
+/// other = "Other"          # どのセクションにも当てはまらない記事の見出し（"" の場合は載せない）
+///
+/// [[sections]]
+/// title = "Security"
+/// categories = ["security"]
+/// tags = ["security"]
+/// max_items = 10
+///
+/// [[sections]]
+/// title = "Rust"
+/// keywords = ["rust", "cargo"]
+/// order = "site"           # newest | oldest | site（デフォルト newest）
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DigestProfile {
+    #[serde(default)]
+    pub sections: Vec<DigestSection>,
+    #[serde(default = "default_other_title")]
+    pub other: String,
+}
+
+fn default_other_title() -> String {
+    DEFAULT_OTHER_TITLE.to_string()
+}
+
+/// セクションに振り分けた記事
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionEntries<'a> {
+    pub title: String,
+    /// 上限で切り詰める前の記事数
+    pub total: usize,
+    pub articles: Vec<&'a WebArticle>,
+}
+
+impl DigestProfile {
+    /// 組み込みのプロファイル（Security，AI research，Company blogs，Rust）
+    pub fn editorial() -> Self {
+        let section = |title: &str, categories: Vec<SiteCategory>, tags: Vec<ArticleTag>, keywords: &[&str]| {
+            DigestSection {
+                title: title.to_string(),
+                categories,
+                tags,
+                keywords: keywords.iter().map(|k| k.to_string()).collect(),
+                max_items: Some(10),
+                order: SectionOrder::Newest,
+            }
+        };
+        Self {
+            sections: vec![
+                section("Security", vec![SiteCategory::Security], vec![ArticleTag::Security], &[]),
+                section(
+                    "AI research",
+                    vec![SiteCategory::Ai, SiteCategory::Academic],
+                    vec![ArticleTag::AcademicPaper],
+                    &[],
+                ),
+                section("Company blogs", vec![SiteCategory::TechBlog], Vec::new(), &[]),
+                section("Rust", Vec::new(), Vec::new(), &["rust", "cargo", "crates.io"]),
+            ],
+            other: default_other_title(),
+        }
+    }
+
+    pub fn from_toml_str(content: &str) -> AppResult<Self> {
+        let profile: Self = toml::from_str(content)
+            .map_err(|e| AppError::InternalError(format!("Invalid digest profile: {}", e)))?;
+        if profile.sections.iter().any(|s| s.title.trim().is_empty()) {
+            return Err(AppError::InternalError("Digest section title must not be empty".into()));
+        }
+        Ok(profile)
+    }
+
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            AppError::InternalError(format!("Failed to read digest profile {:?}: {}", path, e))
+        })?;
+        Self::from_toml_str(&content)
+    }
+
+    /// `CLIPPER_DIGEST_PROFILE` のプロファイル（未設定の場合は `None`）
+    pub fn from_env() -> AppResult<Option<Self>> {
+        match std::env::var(DIGEST_PROFILE_ENV) {
+            Ok(value) if value.trim() == EDITORIAL_PROFILE => Ok(Some(Self::editorial())),
+            Ok(value) if !value.trim().is_empty() => Self::load(Path::new(value.trim())).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// 記事をセクションに振り分ける．セクションは宣言順（`other` は最後），空のセクションは含めない．
+    pub fn assign<'a>(&self, articles: &[&'a WebArticle]) -> Vec<SectionEntries<'a>> {
+        let mut buckets: Vec<Vec<&WebArticle>> = vec![Vec::new(); self.sections.len() + 1];
+        for &article in articles {
+            let index = self
+                .sections
+                .iter()
+                .position(|section| section.matches(article))
+                .unwrap_or(self.sections.len());
+            buckets[index].push(article);
+        }
+        let other = DigestSection {
+            title: self.other.clone(),
+            categories: Vec::new(),
+            tags: Vec::new(),
+            keywords: Vec::new(),
+            max_items: None,
+            order: SectionOrder::Newest,
+        };
+        self.sections
+            .iter()
+            .chain(std::iter::once(&other).filter(|other| !other.title.trim().is_empty()))
+            .zip(buckets)
+            .filter(|(_, articles)| !articles.is_empty())
+            .map(|(section, mut articles)| {
+                sort_articles(&mut articles, section.order);
+                let total = articles.len();
+                if let Some(max_items) = section.max_items {
+                    articles.truncate(max_items);
+                }
+                SectionEntries {
+                    title: section.title.clone(),
+                    total,
+                    articles,
+                }
+            })
+            .collect()
+    }
+}
+
+/// 同じ時刻の記事も毎回同じ順になるよう，サイト名，URL の順で並べる（`models::ordering`）
+fn sort_articles(articles: &mut [&WebArticle], order: SectionOrder) {
+    articles.sort_by(|a, b| match order {
+        SectionOrder::Newest => newest_first(a, b),
+        SectionOrder::Oldest => oldest_first(a, b),
+        SectionOrder::Site => a.site.name.cmp(&b.site.name).then_with(|| newest_first(a, b)),
+    });
+}
+
+/// `text` に `word` が含まれるか．英数字で始まる（終わる）語は，前（後）が英数字でない位置だけを数える．
+fn contains_word(text: &str, word: &str) -> bool {
+    let text = text.to_lowercase();
+    let word = word.trim().to_lowercase();
+    if word.is_empty() {
+        return false;
+    }
+    let is_word_char = |c: char| c.is_ascii_alphanumeric();
+    text.match_indices(&word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        let starts_word = word.chars().next().is_some_and(is_word_char);
+        let ends_word = word.chars().next_back().is_some_and(is_word_char);
+        !(starts_word && before.is_some_and(is_word_char)) && !(ends_word && after.is_some_and(is_word_char))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Local, TimeZone};
+
+    fn article(site: &str, title: &str, hour: u32) -> WebArticle {
+        ArticleBuilder::new(title)
+            .with_site(site, "https://example.com")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, hour, 0, 0).unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_contains_word() {
+        assert!(contains_word("Announcing Rust 1.90", "rust"));
+        assert!(contains_word("Rustで書くCLI", "rust"));
+        assert!(!contains_word("Zero trust の実践", "rust"));
+        assert!(contains_word("crates.io の障害", "crates.io"));
+        assert!(!contains_word("Anything", ""));
+    }
+
+    #[test]
+    fn test_editorial_profile_assign() {
+        let mut paper = article("Gigazine", "新しい論文", 9);
+        paper.properties.is_new_academic_paper_related = Some(true);
+        let articles = [
+            article("JPCERT", "注意喚起", 10),
+            article("JPCERT", "注意喚起 2", 11),
+            paper,
+            article("Cookpad Tech Blog", "Rust で書き直した", 8),
+            article("Gigazine", "Announcing Rust 1.90", 12),
+            article("Gigazine", "Zero trust の実践", 13),
+        ];
+        let refs: Vec<&WebArticle> = articles.iter().collect();
+        let sections = DigestProfile::editorial().assign(&refs);
+        let titles: Vec<(&str, Vec<&str>)> = sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.articles.iter().map(|a| a.title.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("Security", vec!["注意喚起 2", "注意喚起"]),
+                ("AI research", vec!["新しい論文"]),
+                ("Company blogs", vec!["Rust で書き直した"]),
+                ("Rust", vec!["Announcing Rust 1.90"]),
+                ("Other", vec!["Zero trust の実践"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_profile_caps_and_order() {
+        let profile = DigestProfile::from_toml_str(
+            r#"
+other = ""
+
+[[sections]]
+title = "News"
+categories = ["news"]
+max_items = 2
+order = "oldest"
+"#,
+        )
+        .unwrap();
+        let articles = [
+            article("Gigazine", "c", 12),
+            article("Gigazine", "a", 8),
+            article("Gigazine", "b", 10),
+            article("JPCERT", "dropped", 9),
+        ];
+        let refs: Vec<&WebArticle> = articles.iter().collect();
+        let sections = profile.assign(&refs);
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].total, 3);
+        assert_eq!(sections[0].articles.iter().map(|a| a.title.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_profile_rejects_invalid_config() {
+        assert!(DigestProfile::from_toml_str("[[sections]]\ntitle = \"\"").is_err());
+        assert!(DigestProfile::from_toml_str("[[sections]]\ntitle = \"X\"\ntags = [\"unknown\"]").is_err());
+        assert!(DigestProfile::from_toml_str("[[sections]]\ntitle = \"X\"\nlimit = 3").is_err());
+    }
+}
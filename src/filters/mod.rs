@@ -0,0 +1,358 @@
+//! 取得した記事を分類・エクスポートの前に絞り込むフィルタ．
+//!
+//! ```ignore
+//! let filter = AllOf::new()
+//!     .with(KeywordFilter::new(["rust", "llm"], ["PR", "広告"]))
+//!     .with(MinTextLength(200));
+//! let articles = apply_filter(articles, &filter);
+//! ```
+
+use crate::enrichment::language::primary_language;
+use crate::models::web_article::WebArticle;
+use crate::shared::errors::{AppError, AppResult};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 記事を残すかどうかを判定するフィルタ
+pub trait ArticleFilter: Send + Sync {
+    /// `true` なら記事を残す
+    fn keep(&self, article: &WebArticle) -> bool;
+}
+
+impl<F: Fn(&WebArticle) -> bool + Send + Sync> ArticleFilter for F {
+    fn keep(&self, article: &WebArticle) -> bool {
+        self(article)
+    }
+}
+
+/// フィルタを通った記事だけを返す（順序は保つ）
+pub fn apply_filter(articles: Vec<WebArticle>, filter: &dyn ArticleFilter) -> Vec<WebArticle> {
+    let before = articles.len();
+    let kept: Vec<WebArticle> = articles.into_iter().filter(|a| filter.keep(a)).collect();
+    tracing::debug!("Filtered articles: {} -> {}", before, kept.len());
+    kept
+}
+
+/// タイトル・概要・本文を小文字にして連結した検索対象
+fn haystack(article: &WebArticle) -> String {
+    format!("{}\n{}\n{}", article.title, article.description, article.text).to_lowercase()
+}
+
+/// 全てのフィルタを満たす記事を残す（フィルタが無ければ全て残す）
+#[derive(Default)]
+pub struct AllOf(Vec<Box<dyn ArticleFilter>>);
+
+impl AllOf {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, filter: impl ArticleFilter + 'static) -> Self {
+        self.0.push(Box::new(filter));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl ArticleFilter for AllOf {
+    fn keep(&self, article: &WebArticle) -> bool {
+        self.0.iter().all(|f| f.keep(article))
+    }
+}
+
+/// いずれかのフィルタを満たす記事を残す（フィルタが無ければ全て除外する）
+#[derive(Default)]
+pub struct AnyOf(Vec<Box<dyn ArticleFilter>>);
+
+impl AnyOf {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, filter: impl ArticleFilter + 'static) -> Self {
+        self.0.push(Box::new(filter));
+        self
+    }
+}
+
+impl ArticleFilter for AnyOf {
+    fn keep(&self, article: &WebArticle) -> bool {
+        self.0.iter().any(|f| f.keep(article))
+    }
+}
+
+/// フィルタの判定を反転する
+pub struct Not<F>(pub F);
+
+impl<F: ArticleFilter> ArticleFilter for Not<F> {
+    fn keep(&self, article: &WebArticle) -> bool {
+        !self.0.keep(article)
+    }
+}
+
+/// キーワードによる絞り込み（大文字・小文字を区別しない）．
+/// `include` が空でなければいずれかを含む記事だけを残し，`exclude` のいずれかを含む記事は除外する．
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeywordFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl KeywordFilter {
+    pub fn new<I, E>(include: I, exclude: E) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        E: IntoIterator,
+        E::Item: AsRef<str>,
+    {
+        let normalize = |k: &str| Some(k.trim().to_lowercase()).filter(|k| !k.is_empty());
+        Self {
+            include: include.into_iter().filter_map(|k| normalize(k.as_ref())).collect(),
+            exclude: exclude.into_iter().filter_map(|k| normalize(k.as_ref())).collect(),
+        }
+    }
+}
+
+impl ArticleFilter for KeywordFilter {
+    fn keep(&self, article: &WebArticle) -> bool {
+        let haystack = haystack(article);
+        (self.include.is_empty() || self.include.iter().any(|k| haystack.contains(k)))
+            && !self.exclude.iter().any(|k| haystack.contains(k))
+    }
+}
+
+/// 正規表現にマッチする記事を残す（タイトル・概要・本文のいずれか）
+#[derive(Debug, Clone)]
+pub struct RegexFilter(Regex);
+
+impl RegexFilter {
+    pub fn new(pattern: &str) -> AppResult<Self> {
+        Regex::new(pattern)
+            .map(Self)
+            .map_err(|e| AppError::InternalError(format!("Invalid filter regex ({}): {}", pattern, e)))
+    }
+}
+
+impl ArticleFilter for RegexFilter {
+    fn keep(&self, article: &WebArticle) -> bool {
+        [&article.title, &article.description, &article.text]
+            .iter()
+            .any(|field| self.0.is_match(field))
+    }
+}
+
+/// 指定したサイトの記事だけを残す
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SiteAllowlist(HashSet<String>);
+
+impl SiteAllowlist {
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(sites: I) -> Self {
+        Self(sites.into_iter().map(Into::into).collect())
+    }
+}
+
+impl ArticleFilter for SiteAllowlist {
+    fn keep(&self, article: &WebArticle) -> bool {
+        self.0.contains(&article.site.name)
+    }
+}
+
+/// 指定した言語（`ja`，`en` など）の記事だけを残す（言語が不明な記事は除外する）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageFilter(HashSet<String>);
+
+impl LanguageFilter {
+    pub fn new<I: IntoIterator<Item = S>, S: AsRef<str>>(languages: I) -> Self {
+        Self(languages.into_iter().filter_map(|l| primary_language(l.as_ref())).collect())
+    }
+}
+
+impl ArticleFilter for LanguageFilter {
+    fn keep(&self, article: &WebArticle) -> bool {
+        article
+            .lang
+            .as_deref()
+            .and_then(primary_language)
+            .is_some_and(|lang| self.0.contains(&lang))
+    }
+}
+
+/// 本文（未取得の場合は概要）が指定した文字数以上の記事を残す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinTextLength(pub usize);
+
+impl ArticleFilter for MinTextLength {
+    fn keep(&self, article: &WebArticle) -> bool {
+        let text = if article.text.is_empty() { &article.description } else { &article.text };
+        text.trim().chars().count() >= self.0
+    }
+}
+
+/// いいね数が指定した数以上の記事を残す（いいね数を取得できないサイトの記事は残す）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinLikes(pub u64);
+
+impl ArticleFilter for MinLikes {
+    fn keep(&self, article: &WebArticle) -> bool {
+        article.properties.likes.is_none_or(|likes| likes >= self.0)
+    }
+}
+
+/// 設定ファイルから組み立てるフィルタ．指定した条件を全て満たす記事を残す．
+///
+/// ```toml
+/// include = ["rust", "llm"]
+/// exclude = ["PR"]
+/// regex = "(?i)cve-\\d{4}-\\d+"
+/// sites = ["JPCERT", "Rust Blog"]
+/// min_text_length = 200
+/// min_likes = 10
+/// languages = ["ja"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilterConfig {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// 空の場合は全てのサイトを許可する
+    #[serde(default)]
+    pub sites: Vec<String>,
+    #[serde(default)]
+    pub min_text_length: Option<usize>,
+    #[serde(default)]
+    pub min_likes: Option<u64>,
+    /// 空の場合は全ての言語を許可する
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+impl FilterConfig {
+    pub fn build(&self) -> AppResult<AllOf> {
+        let mut filter = AllOf::new();
+        if !self.include.is_empty() || !self.exclude.is_empty() {
+            filter = filter.with(KeywordFilter::new(&self.include, &self.exclude));
+        }
+        if let Some(pattern) = &self.regex {
+            filter = filter.with(RegexFilter::new(pattern)?);
+        }
+        if !self.sites.is_empty() {
+            filter = filter.with(SiteAllowlist::new(self.sites.iter().cloned()));
+        }
+        if let Some(min) = self.min_text_length {
+            filter = filter.with(MinTextLength(min));
+        }
+        if let Some(min) = self.min_likes {
+            filter = filter.with(MinLikes(min));
+        }
+        if !self.languages.is_empty() {
+            filter = filter.with(LanguageFilter::new(&self.languages));
+        }
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    fn article(site: &str, title: &str, text: &str) -> WebArticle {
+        ArticleBuilder::new(title).with_site(site, "https://example.com").with_text(text).build()
+    }
+
+    fn titles(articles: &[WebArticle]) -> Vec<&str> {
+        articles.iter().map(|a| a.title.as_str()).collect()
+    }
+
+    fn sample() -> Vec<WebArticle> {
+        vec![
+            article("Rust Blog", "Announcing Rust 1.90", "A long release note about the compiler."),
+            article("Gigazine", "【PR】おすすめガジェット", "広告記事"),
+            article("JPCERT", "CVE-2025-1234 の注意喚起", "Apache Tomcat の脆弱性"),
+        ]
+    }
+
+    #[test]
+    fn test_keyword_filter() {
+        let filter = KeywordFilter::new(["rust", "tomcat"], ["【pr】"]);
+        assert_eq!(
+            titles(&apply_filter(sample(), &filter)),
+            vec!["Announcing Rust 1.90", "CVE-2025-1234 の注意喚起"]
+        );
+        let exclude_only = KeywordFilter::new(Vec::<String>::new(), ["広告"]);
+        assert_eq!(apply_filter(sample(), &exclude_only).len(), 2);
+    }
+
+    #[test]
+    fn test_regex_site_and_length_filters() {
+        let regex = RegexFilter::new(r"CVE-\d{4}-\d+").unwrap();
+        assert_eq!(titles(&apply_filter(sample(), &regex)), vec!["CVE-2025-1234 の注意喚起"]);
+        assert!(RegexFilter::new("(").is_err());
+
+        let sites = SiteAllowlist::new(["Rust Blog", "Gigazine"]);
+        assert_eq!(apply_filter(sample(), &sites).len(), 2);
+
+        assert_eq!(
+            titles(&apply_filter(sample(), &MinTextLength(10))),
+            vec!["Announcing Rust 1.90", "CVE-2025-1234 の注意喚起"]
+        );
+    }
+
+    #[test]
+    fn test_min_likes() {
+        let mut articles = sample();
+        articles[0].properties.likes = Some(3);
+        articles[1].properties.likes = Some(42);
+        assert_eq!(
+            titles(&apply_filter(articles, &MinLikes(10))),
+            vec!["【PR】おすすめガジェット", "CVE-2025-1234 の注意喚起"]
+        );
+    }
+
+    #[test]
+    fn test_language_filter() {
+        let mut articles = sample();
+        articles[0].lang = Some("en".into());
+        articles[2].lang = Some("ja".into());
+        let filter = LanguageFilter::new(["ja-JP"]);
+        assert_eq!(titles(&apply_filter(articles, &filter)), vec!["CVE-2025-1234 の注意喚起"]);
+    }
+
+    #[test]
+    fn test_composed_filters() {
+        let compiler_or_jpcert = AnyOf::new()
+            .with(KeywordFilter::new(["compiler"], Vec::<String>::new()))
+            .with(|a: &WebArticle| a.site.name == "JPCERT");
+        let filter = AllOf::new()
+            .with(Not(SiteAllowlist::new(["Gigazine"])))
+            .with(compiler_or_jpcert);
+        assert_eq!(apply_filter(sample(), &filter).len(), 2);
+        assert_eq!(apply_filter(sample(), &AllOf::new()).len(), 3);
+        assert!(apply_filter(sample(), &AnyOf::new()).is_empty());
+    }
+
+    #[test]
+    fn test_filter_config() {
+        let config: FilterConfig = toml::from_str(
+            r#"
+exclude = ["広告"]
+sites = ["Gigazine", "JPCERT"]
+min_text_length = 5
+"#,
+        )
+        .unwrap();
+        let filter = config.build().unwrap();
+        assert_eq!(titles(&apply_filter(sample(), &filter)), vec!["CVE-2025-1234 の注意喚起"]);
+        assert!(FilterConfig::default().build().unwrap().is_empty());
+        assert!(toml::from_str::<FilterConfig>("unknown = 1").is_err());
+    }
+}
@@ -1,2 +1,17 @@
+pub mod alerts;
+pub mod api;
+pub mod archive;
+pub mod assets;
+pub mod cli;
+pub mod discovery;
+pub mod enrichment;
+pub mod export;
+pub mod filters;
 pub mod models;
+pub mod monitoring;
+pub mod notifiers;
+pub mod scheduler;
 pub mod shared;
+pub mod storage;
+#[cfg(test)]
+pub(crate) mod testing;
@@ -0,0 +1,29 @@
+use clap::Parser;
+use news_clipper::cli::{self, Cli, output::render_error};
+use news_clipper::shared::config::{env_file_path, load_env_file};
+use news_clipper::shared::logger::init_logger;
+use std::process::ExitCode;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    load_env_file(env_file_path().as_deref());
+    let cli = Cli::parse();
+    let format = cli.output;
+
+    if let Err(e) = init_logger(cli.log_level.to_uppercase()) {
+        eprintln!("{}", render_error(format, &e.to_string()));
+        return ExitCode::FAILURE;
+    }
+
+    match cli::run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            // JSON 出力時はスクリプトから扱えるようエラーも標準出力に JSON で書き出す
+            match format {
+                cli::OutputFormat::Json => println!("{}", render_error(format, &e.to_string())),
+                cli::OutputFormat::Text => eprintln!("{}", render_error(format, &e.to_string())),
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::id::AnnotationId;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// 注釈・スターの公開範囲
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Visibility {
+    /// チーム全員に見える（トリアージ済みの記録など）
+    #[default]
+    Shared,
+    /// 作成したユーザーだけに見える（個人の読書リストなど）
+    Personal,
+}
+
+/// 記事本文（クリーン済みテキスト `WebArticle.text`）上のハイライトとメモ．
+///
+/// 範囲は文字単位のオフセット `[start, end)` で表す．本文が再パースで変化しても
+/// `quote` を手掛かりに位置を復元できるよう，ハイライトした文字列も保持する．
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: AnnotationId,
+    pub article_url: String,
+    pub start: usize,
+    pub end: usize,
+    pub quote: String,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// 作成したユーザー（`Personal` の注釈では必須）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl Annotation {
+    /// `text` の `[start, end)` をハイライトする注釈を作成する
+    pub fn new(
+        article_url: impl Into<String>,
+        text: &str,
+        start: usize,
+        end: usize,
+        note: Option<String>,
+    ) -> AppResult<Self> {
+        let quote = slice_chars(text, start, end)?;
+        let now = Local::now();
+        Ok(Self {
+            id: AnnotationId::new(),
+            article_url: article_url.into(),
+            start,
+            end,
+            quote,
+            note,
+            visibility: Visibility::Shared,
+            owner: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// 公開範囲と作成したユーザーを設定する
+    pub fn with_visibility(mut self, visibility: Visibility, owner: Option<String>) -> Self {
+        self.visibility = visibility;
+        self.owner = owner;
+        self
+    }
+
+    /// `user`（`None` は匿名）から見えるか．個人用の注釈は作成したユーザーだけに見える．
+    pub fn is_visible_to(&self, user: Option<&str>) -> bool {
+        is_visible(self.visibility, self.owner.as_deref(), user)
+    }
+
+    pub fn set_note(&mut self, note: Option<String>) {
+        self.note = note;
+        self.updated_at = Local::now();
+    }
+
+    /// 本文が変化した場合に `quote` の位置へ範囲を合わせ直す．見つからなければ `false`．
+    pub fn reanchor(&mut self, text: &str) -> bool {
+        if slice_chars(text, self.start, self.end).is_ok_and(|q| q == self.quote) {
+            return true;
+        }
+        match text.find(&self.quote) {
+            Some(byte_pos) => {
+                self.start = text[..byte_pos].chars().count();
+                self.end = self.start + self.quote.chars().count();
+                self.updated_at = Local::now();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 記事のスター．共有のスターは記事ごとに1つ，個人用のスターはユーザーごとに1つ付けられる．
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Star {
+    pub article_url: String,
+    pub visibility: Visibility,
+    /// スターを付けたユーザー
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub created_at: DateTime<Local>,
+}
+
+impl Star {
+    pub fn new(article_url: impl Into<String>, visibility: Visibility, owner: Option<String>) -> Self {
+        Self {
+            article_url: article_url.into(),
+            visibility,
+            owner,
+            created_at: Local::now(),
+        }
+    }
+
+    pub fn is_visible_to(&self, user: Option<&str>) -> bool {
+        is_visible(self.visibility, self.owner.as_deref(), user)
+    }
+}
+
+fn is_visible(visibility: Visibility, owner: Option<&str>, user: Option<&str>) -> bool {
+    match visibility {
+        Visibility::Shared => true,
+        Visibility::Personal => owner.is_some() && owner == user,
+    }
+}
+
+/// 文字単位のオフセットで部分文字列を取り出す（マルチバイト文字を壊さない）
+fn slice_chars(text: &str, start: usize, end: usize) -> AppResult<String> {
+    if start >= end {
+        return Err(AppError::InternalError(format!(
+            "Invalid annotation range: {}..{}",
+            start, end
+        )));
+    }
+    let quote: String = text.chars().skip(start).take(end - start).collect();
+    if quote.chars().count() != end - start {
+        return Err(AppError::InternalError(format!(
+            "Annotation range {}..{} is out of bounds",
+            start, end
+        )));
+    }
+    Ok(quote)
+}
+
+/// 1記事分の注釈一覧（作成・取得・更新・削除）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Annotations {
+    items: Vec<Annotation>,
+}
+
+impl Annotations {
+    pub fn new(items: Vec<Annotation>) -> Self {
+        let mut annotations = Self { items };
+        annotations.sort();
+        annotations
+    }
+
+    pub fn add(&mut self, annotation: Annotation) -> AnnotationId {
+        let id = annotation.id;
+        self.items.push(annotation);
+        self.sort();
+        id
+    }
+
+    pub fn get(&self, id: AnnotationId) -> Option<&Annotation> {
+        self.items.iter().find(|a| a.id == id)
+    }
+
+    pub fn update_note(&mut self, id: AnnotationId, note: Option<String>) -> AppResult<&Annotation> {
+        let annotation = self
+            .items
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| AppError::InternalError(format!("Annotation not found: {}", id)))?;
+        annotation.set_note(note);
+        Ok(annotation)
+    }
+
+    pub fn remove(&mut self, id: AnnotationId) -> Option<Annotation> {
+        let index = self.items.iter().position(|a| a.id == id)?;
+        Some(self.items.remove(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Annotation> {
+        self.items.iter()
+    }
+
+    /// 範囲の順に並んだ注釈
+    pub fn as_slice(&self) -> &[Annotation] {
+        &self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn sort(&mut self) {
+        self.items.sort_by_key(|a| (a.start, a.end));
+    }
+}
+
+/// エクスポート用にハイライトした箇所とメモを Markdown の引用として書き出す
+pub fn render_highlights_markdown(annotations: &[Annotation]) -> String {
+    annotations
+        .iter()
+        .map(|a| {
+            let quote = a
+                .quote
+                .lines()
+                .map(|line| format!("> {}", line))
+                .collect::<Vec<_>>()
+                .join("\n");
+            match &a.note {
+                Some(note) if !note.is_empty() => format!("{}\n\n{}", quote, note),
+                _ => quote,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "生成AIの推論を高速化する手法について解説します．";
+
+    #[test]
+    fn test_annotation_new_uses_char_offsets() {
+        let annotation = Annotation::new("https://example.com/a", TEXT, 0, 4, None).unwrap();
+        assert_eq!(annotation.quote, "生成AI");
+        assert!(Annotation::new("https://example.com/a", TEXT, 3, 3, None).is_err());
+        assert!(Annotation::new("https://example.com/a", TEXT, 0, 1000, None).is_err());
+    }
+
+    #[test]
+    fn test_annotation_reanchor() {
+        let mut annotation = Annotation::new("https://example.com/a", TEXT, 5, 7, None).unwrap();
+        assert_eq!(annotation.quote, "推論");
+        let edited = format!("【更新】{}", TEXT);
+        assert!(annotation.reanchor(&edited));
+        assert_eq!(annotation.start, 9);
+        assert!(!annotation.reanchor("全く別の本文"));
+    }
+
+    #[test]
+    fn test_annotations_crud_and_render() {
+        let mut annotations = Annotations::default();
+        let second = annotations.add(Annotation::new("u", TEXT, 5, 7, None).unwrap());
+        let first = annotations.add(
+            Annotation::new("u", TEXT, 0, 4, Some("重要".into())).unwrap(),
+        );
+        assert_eq!(annotations.iter().next().unwrap().id, first);
+
+        annotations.update_note(second, Some("要確認".into())).unwrap();
+        assert_eq!(annotations.get(second).unwrap().note.as_deref(), Some("要確認"));
+
+        let rendered = render_highlights_markdown(annotations.as_slice());
+        assert_eq!(rendered, "> 生成AI\n\n重要\n\n> 推論\n\n要確認");
+
+        assert!(annotations.remove(first).is_some());
+        assert_eq!(annotations.len(), 1);
+        assert!(annotations.update_note(first, None).is_err());
+    }
+
+    #[test]
+    fn test_visibility() {
+        let shared = Annotation::new("u", TEXT, 0, 4, None).unwrap();
+        assert!(shared.is_visible_to(None));
+        let personal = shared.with_visibility(Visibility::Personal, Some("alice".into()));
+        assert!(personal.is_visible_to(Some("alice")));
+        assert!(!personal.is_visible_to(Some("bob")));
+        assert!(!personal.is_visible_to(None));
+        assert!(!Star::new("u", Visibility::Personal, None).is_visible_to(None));
+        assert_eq!("personal".parse::<Visibility>().unwrap(), Visibility::Personal);
+    }
+}
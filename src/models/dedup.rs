@@ -0,0 +1,232 @@
+//! 複数サイトに掲載された同じ記事（Gigazine，Yahoo!ニュース，ITmedia など）をまとめる．
+//!
+//! 1. URL の正規化（トラッキング用パラメータやフラグメントの除去）による完全一致
+//! 2. 正規化したタイトルのハッシュによる完全一致
+//! 3. 文字 bigram の SimHash で候補を絞り込み，Jaccard 係数でタイトルの類似を判定
+
+use crate::models::web_article::WebArticle;
+use request::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// 除去するトラッキング用クエリパラメータ（`utm_` で始まるものは全て除去する）
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "yclid", "msclkid", "mc_cid", "mc_eid", "ref", "ref_src", "source", "via",
+    "cmpid", "igshid", "_hsenc", "_hsmi",
+];
+
+/// 類似タイトルと判定する Jaccard 係数の既定値
+pub const DEFAULT_TITLE_SIMILARITY: f64 = 0.8;
+/// SimHash のハミング距離がこれを超える組み合わせは比較しない
+const SIMHASH_MAX_DISTANCE: u32 = 20;
+/// これより短いタイトルは類似判定をしない（短いタイトル同士は偶然似やすい）
+const MIN_FUZZY_TITLE_CHARS: usize = 10;
+
+/// まとめられた重複記事
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Duplicate {
+    pub kept_url: String,
+    pub dropped_url: String,
+    pub reason: DuplicateReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateReason {
+    SameUrl,
+    SameTitle,
+    SimilarTitle { similarity: f64 },
+}
+
+/// URL を比較用に正規化する（スキーム・ホストの小文字化，`www.` とフラグメント，トラッキング用パラメータ，末尾の `/` の除去）
+pub fn normalize_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.trim().to_string();
+    };
+    parsed.set_fragment(None);
+    let mut pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    pairs.sort();
+    if pairs.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    let host = parsed
+        .host_str()
+        .map(|h| h.trim_start_matches("www.").to_lowercase())
+        .unwrap_or_default();
+    let path = parsed.path().trim_end_matches('/');
+    match parsed.query() {
+        Some(query) => format!("{}://{}{}?{}", parsed.scheme(), host, path, query),
+        None => format!("{}://{}{}", parsed.scheme(), host, path),
+    }
+}
+
+/// タイトルを比較用に正規化する（全角英数の半角化，小文字化，空白・記号の除去）
+pub fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+fn bigrams(normalized: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = normalized.chars().collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// 文字 bigram の Jaccard 係数
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    jaccard(&bigrams(&normalize_title(a)), &bigrams(&normalize_title(b)))
+}
+
+fn jaccard(a: &HashSet<(char, char)>, b: &HashSet<(char, char)>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let intersection = a.intersection(b).count();
+    intersection as f64 / (a.len() + b.len() - intersection) as f64
+}
+
+/// bigram 集合の 64bit SimHash
+pub fn simhash(grams: &HashSet<(char, char)>) -> u64 {
+    let mut weights = [0i32; 64];
+    for gram in grams {
+        let mut hasher = DefaultHasher::new();
+        gram.hash(&mut hasher);
+        let h = hasher.finish();
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if h >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| **w > 0)
+        .fold(0u64, |acc, (bit, _)| acc | (1 << bit))
+}
+
+fn title_hash(normalized: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    normalized.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct Fingerprint {
+    index: usize,
+    grams: HashSet<(char, char)>,
+    simhash: u64,
+}
+
+/// 重複を除いた記事（最初に現れたものを残す）と，まとめた重複の一覧を返す
+pub fn dedup_articles(articles: Vec<WebArticle>, similarity: f64) -> (Vec<WebArticle>, Vec<Duplicate>) {
+    let mut kept: Vec<WebArticle> = Vec::new();
+    let mut duplicates = Vec::new();
+    let mut by_url: HashMap<String, usize> = HashMap::new();
+    let mut by_title: HashMap<u64, usize> = HashMap::new();
+    let mut fingerprints: Vec<Fingerprint> = Vec::new();
+
+    for article in articles {
+        let url = normalize_url(&article.article_url);
+        let title = normalize_title(&article.title);
+
+        let mut matched = by_url.get(&url).map(|&i| (i, DuplicateReason::SameUrl));
+        if matched.is_none() && !title.is_empty() {
+            matched = by_title.get(&title_hash(&title)).map(|&i| (i, DuplicateReason::SameTitle));
+        }
+        let grams = bigrams(&title);
+        let hash = simhash(&grams);
+        if matched.is_none() && title.chars().count() >= MIN_FUZZY_TITLE_CHARS {
+            matched = fingerprints
+                .iter()
+                .filter(|f| (f.simhash ^ hash).count_ones() <= SIMHASH_MAX_DISTANCE)
+                .map(|f| (f.index, jaccard(&f.grams, &grams)))
+                .filter(|(_, s)| *s >= similarity)
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, s)| (i, DuplicateReason::SimilarTitle { similarity: s }));
+        }
+
+        match matched {
+            Some((index, reason)) => duplicates.push(Duplicate {
+                kept_url: kept[index].article_url.clone(),
+                dropped_url: article.article_url.clone(),
+                reason,
+            }),
+            None => {
+                let index = kept.len();
+                by_url.insert(url, index);
+                if !title.is_empty() {
+                    by_title.insert(title_hash(&title), index);
+                }
+                if title.chars().count() >= MIN_FUZZY_TITLE_CHARS {
+                    fingerprints.push(Fingerprint {
+                        index,
+                        grams,
+                        simhash: hash,
+                    });
+                }
+                kept.push(article);
+            }
+        }
+    }
+    (kept, duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    fn article(site: &str, title: &str, url: &str) -> WebArticle {
+        ArticleBuilder::new(title)
+            .with_site(site, format!("https://{}.example.com", site))
+            .with_url(url)
+            .build()
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("https://WWW.Example.com/news/123/?utm_source=rss&id=5&fbclid=x#top"),
+            "https://example.com/news/123?id=5"
+        );
+        assert_eq!(normalize_url("https://example.com/a/"), "https://example.com/a");
+    }
+
+    #[test]
+    fn test_normalize_title_and_similarity() {
+        assert_eq!(normalize_title("ＯｐｅｎＡＩ、新モデル「GPT」を発表！"), "openai新モデルgptを発表");
+        assert!(title_similarity("OpenAIが新モデルを発表", "OpenAIが新モデルを発表 - ITmedia") > 0.7);
+        assert!(title_similarity("OpenAIが新モデルを発表", "Rust 1.90 リリース") < 0.2);
+    }
+
+    #[test]
+    fn test_dedup_articles() {
+        let articles = vec![
+            article("gigazine", "OpenAIが新しい推論モデル「o5」を発表", "https://gigazine.net/news/1/?utm_source=rss"),
+            article("gigazine", "OpenAIが新しい推論モデル「o5」を発表", "https://gigazine.net/news/1/"),
+            article("itmedia", "ＯｐｅｎＡＩが新しい推論モデル「o5」を発表", "https://www.itmedia.co.jp/news/2"),
+            article("yahoo", "OpenAIが新しい推論モデル「o5」を正式発表", "https://news.yahoo.co.jp/3"),
+            article("rust", "Rust 1.90.0 をリリースしました", "https://blog.rust-lang.org/4"),
+        ];
+        let (kept, duplicates) = dedup_articles(articles, DEFAULT_TITLE_SIMILARITY);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(duplicates.len(), 3);
+        assert_eq!(duplicates[0].reason, DuplicateReason::SameUrl);
+        assert_eq!(duplicates[1].reason, DuplicateReason::SameTitle);
+        assert!(matches!(duplicates[2].reason, DuplicateReason::SimilarTitle { .. }));
+        assert!(duplicates.iter().all(|d| d.kept_url == "https://gigazine.net/news/1/?utm_source=rss"));
+    }
+}
@@ -0,0 +1,415 @@
+//! 記事ページからの本文とメタデータ（タイトル，著者，公開日時，リード画像，言語，語数）の抽出．
+//!
+//! `extract_article` は Readability 風のヒューリスティックで本文を探し，ページの `<head>` などから
+//! メタデータを集める．サイト固有のセレクタで本文を抽出した場合は `ExtractedArticle::from_page` で
+//! メタデータだけを補う．
+//!
+//! 本文の抽出方法はサイトごとに `ExtractionStrategy` で宣言し，`WebSiteInterface::extract_content` が優先順に試す．
+
+use crate::models::metadata::{PageMetadata, json_ld_article_body, parse_published};
+use crate::models::web_article::{
+    Html, Text, WebSiteInterface, escape_html_text, extract_main_content, extract_main_content_from,
+};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::selectors::selector;
+use crate::shared::text::global_text_normalizer;
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 著者名として扱う最大文字数（本文の段落を誤って拾わないように）
+const MAX_BYLINE_CHARS: usize = 100;
+
+/// コードブロックに付く行番号とコピーボタン（Hugo の Chroma，WordPress のプラグインなど）．
+/// 除外セレクタに加えると，本文のコードに行番号やボタンの文言が混ざらない．
+pub const CODE_BLOCK_NOISE_SELECTORS: &[&str] = &[
+    "pre span.lnt",
+    "pre span.ln",
+    "td.lntd:first-child",
+    "button.copy-code-button",
+    "button.td-click-to-copy",
+    ".code-block-pro-copy-button",
+];
+
+/// 抽出した記事
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtractedArticle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// 著者
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byline: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<DateTime<Local>>,
+    /// リード画像の URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lead_image: Option<String>,
+    /// ページが宣言している言語（`ja`，`en-us` など，小文字）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    pub html: Html,
+    pub text: Text,
+    pub word_count: usize,
+}
+
+impl ExtractedArticle {
+    /// 本文だけから作成する（ページ全体が無いため，メタデータは本文中の見出し・`<time>`・画像から推定する）
+    pub fn from_content(html: Html, text: Text) -> Self {
+        let fragment = scraper::Html::parse_fragment(&html);
+        Self {
+            title: first_text(&fragment, selector!("h1")),
+            byline: byline(&fragment),
+            published: time_datetime(&fragment),
+            lead_image: first_image(&fragment),
+            lang: None,
+            word_count: word_count(&text),
+            html,
+            text,
+        }
+    }
+
+    /// サイト固有の方法で抽出した本文に，ページ全体（`raw_html`）のメタデータを補って作成する
+    pub fn from_page(raw_html: &str, html: Html, text: Text) -> Self {
+        let doc = scraper::Html::parse_document(raw_html);
+        let metadata = PageMetadata::from_document(&doc);
+        let content = Self::from_content(html, text);
+        Self {
+            title: metadata.title.or_else(|| page_title(&doc)).or(content.title),
+            byline: metadata.author.or_else(|| byline(&doc)).or(content.byline),
+            published: metadata
+                .published
+                .or_else(|| time_datetime(&doc))
+                .or(content.published),
+            lead_image: metadata.image.or(content.lead_image),
+            lang: page_lang(&doc),
+            ..content
+        }
+    }
+}
+
+/// ページの HTML から本文の HTML を取り出す関数（`ExtractionStrategy::Custom` 用）
+pub type CustomExtractor = fn(&str) -> Option<String>;
+
+/// 記事ページの本文の抽出方法．
+///
+/// 設定ファイルでは `type` で種類を指定する（`{ type = "css_selector", selectors = ["article .body"] }` など）．
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ExtractionStrategy {
+    /// CSS セレクタを優先順に試し，最初にマッチした要素を本文とする
+    CssSelector { selectors: Vec<String> },
+    /// Readability 風のヒューリスティック
+    Readability,
+    /// `start` と `end` の正規表現に挟まれた部分を本文とする（DOM が壊れていてセレクタで選べないページ向け）
+    RegexBlock { start: String, end: String },
+    /// JSON-LD の `articleBody`（プレーンテキストを段落ごとの `<p>` にする）
+    JsonLd,
+    /// サイト実装の関数（設定ファイルからは指定できない）
+    #[serde(skip)]
+    Custom(CustomExtractor),
+}
+
+impl ExtractionStrategy {
+    pub fn css(selectors: &[&str]) -> Self {
+        Self::CssSelector {
+            selectors: selectors.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// 不要要素を除去した本文の HTML（見つからなければ `None`）
+    pub fn extract<S: WebSiteInterface + ?Sized>(&self, site: &S, body: &str) -> AppResult<Option<Html>> {
+        match self {
+            Self::CssSelector { selectors } => {
+                for selector in selectors {
+                    if let Some(html) = site.select_clean(body, selector)?.into_iter().next() {
+                        return Ok(Some(html));
+                    }
+                }
+                Ok(None)
+            }
+            Self::Readability => Ok(extract_main_content(body).map(|html| site.clean_content(&html))),
+            Self::RegexBlock { start, end } => {
+                let compile = |pattern: &str| {
+                    Regex::new(pattern).map_err(|e| {
+                        AppError::InternalError(format!("Invalid extraction regex ({}): {}", pattern, e))
+                    })
+                };
+                let (start, end) = (compile(start)?, compile(end)?);
+                let Some(from) = start.find(body).map(|m| m.end()) else {
+                    return Ok(None);
+                };
+                Ok(end
+                    .find(&body[from..])
+                    .map(|m| &body[from..from + m.start()])
+                    .filter(|block| !block.trim().is_empty())
+                    .map(|block| site.clean_content(block)))
+            }
+            Self::JsonLd => Ok(json_ld_article_body(body).map(|text| paragraphs_to_html(&text))),
+            Self::Custom(extract) => Ok(extract(body).map(|html| site.clean_content(&html))),
+        }
+    }
+}
+
+impl fmt::Display for ExtractionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CssSelector { selectors } => write!(f, "css_selector({})", selectors.join(", ")),
+            Self::Readability => write!(f, "readability"),
+            Self::RegexBlock { start, end } => write!(f, "regex_block({} .. {})", start, end),
+            Self::JsonLd => write!(f, "json_ld"),
+            Self::Custom(_) => write!(f, "custom"),
+        }
+    }
+}
+
+/// `strategies` を順に試し，最初に見つかった本文を HTML とテキストで返す
+pub fn extract_with_strategies<S: WebSiteInterface + ?Sized>(
+    site: &S,
+    strategies: &[ExtractionStrategy],
+    body: &str,
+) -> AppResult<(Html, Text)> {
+    for strategy in strategies {
+        if let Some(html) = strategy.extract(site, body)? {
+            let text = html2md::rewrite_html(&html, false);
+            return Ok((site.trim_text(&html), site.trim_text(&text)));
+        }
+    }
+    let tried: Vec<String> = strategies.iter().map(ToString::to_string).collect();
+    Err(AppError::SelectorNotFound {
+        site: site.site_name(),
+        selector: tried.join(", "),
+    })
+}
+
+/// 空行で区切られたプレーンテキストを段落ごとの `<p>` にする
+fn paragraphs_to_html(text: &str) -> Html {
+    let mut html = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        html.push_str("<p>");
+        escape_html_text(paragraph, &mut html);
+        html.push_str("</p>");
+    }
+    html
+}
+
+/// Readability 風のヒューリスティックで本文を探し，メタデータと合わせて返す（本文が見つからなければ `None`）
+pub fn extract_article(raw_html: &str) -> Option<ExtractedArticle> {
+    let doc = scraper::Html::parse_document(raw_html);
+    let html = extract_main_content_from(&doc)?;
+    let normalizer = global_text_normalizer();
+    let text = normalizer.normalize(&html2md::rewrite_html(&html, false));
+    Some(ExtractedArticle::from_page(raw_html, normalizer.normalize(&html), text))
+}
+
+/// 語数．日本語・中国語・韓国語は1文字を1語，それ以外は英数字の連続を1語と数える．
+pub fn word_count(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                count += 1;
+            }
+            in_word = true;
+        } else {
+            in_word = false;
+        }
+    }
+    count
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0xFF66..=0xFF9F | 0xAC00..=0xD7AF
+    )
+}
+
+/// 空白をまとめた要素のテキスト（空なら `None`）
+fn element_text(elem: scraper::ElementRef) -> Option<String> {
+    let text = elem.text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+fn first_text(doc: &scraper::Html, selector: &scraper::Selector) -> Option<String> {
+    doc.select(selector).find_map(element_text)
+}
+
+fn meta_content(doc: &scraper::Html, selector: &scraper::Selector) -> Option<String> {
+    doc.select(selector)
+        .filter_map(|e| e.value().attr("content"))
+        .map(str::trim)
+        .find(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// メタデータにタイトルが無い場合に `<title>`，最初の `<h1>` の順に探す
+fn page_title(doc: &scraper::Html) -> Option<String> {
+    first_text(doc, selector!("title")).or_else(|| first_text(doc, selector!("h1")))
+}
+
+fn byline(doc: &scraper::Html) -> Option<String> {
+    doc.select(selector!("[rel~='author'], [itemprop='author'], .byline, .author"))
+        .filter_map(element_text)
+        .find(|text| text.chars().count() <= MAX_BYLINE_CHARS)
+}
+
+fn time_datetime(doc: &scraper::Html) -> Option<DateTime<Local>> {
+    doc.select(selector!("time[datetime]"))
+        .filter_map(|e| e.value().attr("datetime"))
+        .find_map(parse_published)
+}
+
+fn first_image(doc: &scraper::Html) -> Option<String> {
+    doc.select(selector!("img[src]"))
+        .filter_map(|e| e.value().attr("src"))
+        .map(str::trim)
+        .find(|src| !src.is_empty() && !src.starts_with("data:"))
+        .map(str::to_string)
+}
+
+/// `<html lang>`，`Content-Language`，`og:locale` の順に探す
+fn page_lang(doc: &scraper::Html) -> Option<String> {
+    doc.select(selector!("html[lang]"))
+        .filter_map(|e| e.value().attr("lang"))
+        .map(str::to_string)
+        .next()
+        .or_else(|| {
+            doc.select(selector!("meta[http-equiv][content]"))
+                .filter(|e| {
+                    e.value()
+                        .attr("http-equiv")
+                        .is_some_and(|v| v.eq_ignore_ascii_case("content-language"))
+                })
+                .find_map(|e| e.value().attr("content").map(str::to_string))
+        })
+        .or_else(|| meta_content(doc, selector!("meta[property='og:locale']")))
+        .map(|lang| lang.trim().replace('_', "-").to_lowercase())
+        .filter(|lang| !lang.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html lang="ja"><head>
+        <title>タイトル | Example</title>
+        <meta property="og:title" content="新しいモデルを発表">
+        <meta name="author" content="山田 太郎">
+        <meta property="article:published_time" content="2025-10-01T09:00:00+09:00">
+        <meta property="og:image" content="https://example.com/lead.png">
+        </head><body><nav><a href="/">Home</a></nav>
+        <article><h1>新しいモデルを発表</h1>
+        <p>Example 社は新しい言語モデルを発表した．性能は従来のモデルを大きく上回り，日本語の理解でも高い評価を得ている．</p>
+        <p>The model is available today for developers through the API and the web console, with more regions coming soon.</p>
+        <img src="https://example.com/inline.png"></article></body></html>"#;
+
+    #[test]
+    fn test_extract_article_metadata() {
+        let article = extract_article(PAGE).unwrap();
+        assert_eq!(article.title.as_deref(), Some("新しいモデルを発表"));
+        assert_eq!(article.byline.as_deref(), Some("山田 太郎"));
+        assert_eq!(article.published, parse_published("2025-10-01T09:00:00+09:00"));
+        assert_eq!(article.lead_image.as_deref(), Some("https://example.com/lead.png"));
+        assert_eq!(article.lang.as_deref(), Some("ja"));
+        assert!(article.html.contains("<article>"));
+        assert!(!article.text.contains("Home"));
+        assert_eq!(article.word_count, word_count(&article.text));
+    }
+
+    #[test]
+    fn test_from_content_uses_content_metadata() {
+        let html = r#"<div><h1> 見出し </h1><p class="byline">By Jane Doe</p>
+            <time datetime="2025-09-30">9月30日</time><img src="data:image/gif;base64,AA"><img src="/a.png"></div>"#;
+        let article = ExtractedArticle::from_content(html.into(), "本文".into());
+        assert_eq!(article.title.as_deref(), Some("見出し"));
+        assert_eq!(article.byline.as_deref(), Some("By Jane Doe"));
+        assert_eq!(article.published, parse_published("2025-09-30"));
+        assert_eq!(article.lead_image.as_deref(), Some("/a.png"));
+        assert_eq!(article.lang, None);
+        assert_eq!(article.word_count, 2);
+    }
+
+    #[test]
+    fn test_extraction_strategies() {
+        use crate::models::sites::generic_feed::{FeedKind, GenericFeedSite};
+        let site = GenericFeedSite::new("Example", "https://example.com/feed", FeedKind::Rss2).unwrap();
+        let page = r#"<html><head><script type="application/ld+json">
+            {"@type": "NewsArticle", "articleBody": "First paragraph.\n\nSecond <paragraph>."}
+            </script></head><body><!-- body:start --><p>Regex body</p><!-- body:end -->
+            <div class="content"><p>CSS body</p><aside>Related</aside></div></body></html>"#;
+
+        let css = ExtractionStrategy::css(&["div.missing", "div.content"]);
+        let html = css.extract(&site, page).unwrap().unwrap();
+        assert!(html.contains("CSS body"));
+        assert!(!html.contains("Related"));
+        assert_eq!(ExtractionStrategy::css(&["div.missing"]).extract(&site, page).unwrap(), None);
+
+        let regex = ExtractionStrategy::RegexBlock {
+            start: "<!-- body:start -->".into(),
+            end: "<!-- body:end -->".into(),
+        };
+        let html = regex.extract(&site, page).unwrap().unwrap();
+        assert!(html.contains("Regex body"));
+        assert!(!html.contains("CSS body"));
+
+        let json_ld = ExtractionStrategy::JsonLd.extract(&site, page).unwrap().unwrap();
+        assert_eq!(json_ld, "<p>First paragraph.</p><p>Second &lt;paragraph&gt;.</p>");
+
+        let custom = ExtractionStrategy::Custom(|body| body.contains("CSS").then(|| "<p>Custom</p>".to_string()));
+        assert!(custom.extract(&site, page).unwrap().unwrap().contains("Custom"));
+
+        let invalid = ExtractionStrategy::RegexBlock {
+            start: "(".into(),
+            end: "x".into(),
+        };
+        assert!(invalid.extract(&site, page).is_err());
+    }
+
+    #[test]
+    fn test_extract_with_strategies_falls_back() {
+        use crate::models::sites::generic_feed::{FeedKind, GenericFeedSite};
+        let site = GenericFeedSite::new("Example", "https://example.com/feed", FeedKind::Rss2).unwrap();
+        let strategies = [ExtractionStrategy::css(&["div.missing"]), ExtractionStrategy::Readability];
+        let (html, text) = extract_with_strategies(&site, &strategies, PAGE).unwrap();
+        assert!(html.contains("新しい言語モデル"));
+        assert!(text.contains("新しい言語モデル"));
+
+        let error = extract_with_strategies(&site, &strategies[..1], PAGE).unwrap_err();
+        assert!(error.to_string().contains("css_selector(div.missing)"));
+    }
+
+    #[test]
+    fn test_extraction_strategy_from_toml() {
+        #[derive(Deserialize)]
+        struct Config {
+            extraction: Vec<ExtractionStrategy>,
+        }
+        let config: Config = toml::from_str(
+            r#"extraction = [
+                { type = "css_selector", selectors = ["article .body"] },
+                { type = "json_ld" },
+                { type = "readability" },
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.extraction.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["css_selector(article .body)", "json_ld", "readability"]
+        );
+        assert!(toml::from_str::<Config>(r#"extraction = [{ type = "custom" }]"#).is_err());
+    }
+
+    #[test]
+    fn test_word_count() {
+        assert_eq!(word_count("Rust 1.90 is out"), 5);
+        assert_eq!(word_count("日本語の文章"), 6);
+        assert_eq!(word_count("LLM を使う"), 4);
+        assert_eq!(word_count(""), 0);
+    }
+}
@@ -0,0 +1,228 @@
+//! 並列数が限られているときに，サイトのカテゴリ間で取得の順番を公平にする．
+//!
+//! Zenn Topic のように同じ系統のサイトが多数あっても，セキュリティ系などの少数のサイトが
+//! 後回しにされないよう，カテゴリごとの重みに応じた重み付き公平キューでサイトを取り出す．
+
+use crate::shared::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::str::FromStr;
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+/// カテゴリごとの重みを指定する環境変数（例: `security=3,community=1`）
+pub const CATEGORY_WEIGHTS_ENV: &str = "CLIPPER_CATEGORY_WEIGHTS";
+
+/// サイトのカテゴリ（宣言順がキューの同点時の優先順）
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Display, EnumString, EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum SiteCategory {
+    Security,
+    Ai,
+    Academic,
+    TechBlog,
+    News,
+    Community,
+    #[default]
+    General,
+}
+
+impl SiteCategory {
+    /// 組み込みサイトのサイト名からカテゴリを推定する
+    pub fn from_site_name(site_name: &str) -> Self {
+        let name = site_name.to_lowercase();
+        let contains_any = |words: &[&str]| words.iter().any(|w| name.contains(w));
+        if contains_any(&["security", "jpcert", "malware", "crowdstrike", "sophos", "trend micro", "nvd"])
+            || contains_any(&["hacker news", "bleepingcomputer"])
+        {
+            SiteCategory::Security
+        } else if name.starts_with("ai ")
+            || contains_any(&["aismiley", "aizine", "mit ai", "google ai", "deepmind", "openai", "anthropic"])
+        {
+            SiteCategory::Ai
+        } else if contains_any(&["research", "university", "scholar"]) {
+            SiteCategory::Academic
+        } else if contains_any(&["tech blog", "engineering", "developers", "rust blog", "techblog"])
+            || contains_any(&["kubernetes", "cncf", "hatena developer", "crates.io", "pypi"])
+        {
+            SiteCategory::TechBlog
+        } else if contains_any(&["zenn", "qiita", "medium", "dev.to", "note topic", "mastodon"]) {
+            SiteCategory::Community
+        } else if contains_any(&["news", "itmedia", "gigazine", "gizmodo", "techcrunch", "nikkei", "business insider"])
+            || contains_any(&["ars technica", "the verge", "publickey"])
+        {
+            SiteCategory::News
+        } else {
+            SiteCategory::General
+        }
+    }
+}
+
+/// カテゴリごとの重み．指定の無いカテゴリは 1．
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CategoryWeights(HashMap<SiteCategory, u32>);
+
+impl CategoryWeights {
+    pub fn weight(&self, category: SiteCategory) -> u32 {
+        self.0.get(&category).copied().unwrap_or(1).max(1)
+    }
+
+    pub fn with_weight(mut self, category: SiteCategory, weight: u32) -> Self {
+        self.0.insert(category, weight);
+        self
+    }
+
+    /// `CLIPPER_CATEGORY_WEIGHTS` から読み込む．未設定の場合は全て 1．
+    pub fn from_env() -> AppResult<Self> {
+        match std::env::var(CATEGORY_WEIGHTS_ENV) {
+            Ok(value) if !value.trim().is_empty() => value.parse(),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+impl FromStr for CategoryWeights {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let invalid = || AppError::InternalError(format!("Invalid category weight: {}", pair));
+            let (category, weight) = pair.split_once('=').ok_or_else(invalid)?;
+            let category = SiteCategory::from_str(category.trim())
+                .map_err(|e| AppError::EnumParseError(format!("{}: {}", category.trim(), e)))?;
+            let weight: u32 = weight.trim().parse().map_err(|_| invalid())?;
+            if weight == 0 {
+                return Err(invalid());
+            }
+            weights = weights.with_weight(category, weight);
+        }
+        Ok(weights)
+    }
+}
+
+/// カテゴリ別のキューから，重みあたりの取り出し回数が最も少ないカテゴリを選んで取り出す
+#[derive(Debug)]
+pub struct FairQueue<T> {
+    queues: BTreeMap<SiteCategory, VecDeque<T>>,
+    served: HashMap<SiteCategory, u64>,
+    weights: CategoryWeights,
+}
+
+impl<T> FairQueue<T> {
+    pub fn new(weights: CategoryWeights) -> Self {
+        Self {
+            queues: BTreeMap::new(),
+            served: HashMap::new(),
+            weights,
+        }
+    }
+
+    pub fn push(&mut self, category: SiteCategory, item: T) {
+        self.queues.entry(category).or_default().push_back(item);
+    }
+
+    pub fn len(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.values().all(VecDeque::is_empty)
+    }
+
+    /// 次に処理する要素を取り出す（同じ比率のカテゴリが複数あれば宣言順）
+    pub fn pop(&mut self) -> Option<(SiteCategory, T)> {
+        let category = SiteCategory::iter()
+            .filter(|c| self.queues.get(c).is_some_and(|q| !q.is_empty()))
+            .min_by(|a, b| {
+                // served_a / weight_a と served_b / weight_b を整数で比較する
+                let lhs = self.served(*a) * self.weights.weight(*b) as u64;
+                let rhs = self.served(*b) * self.weights.weight(*a) as u64;
+                lhs.cmp(&rhs)
+            })?;
+        let item = self.queues.get_mut(&category)?.pop_front()?;
+        *self.served.entry(category).or_default() += 1;
+        Some((category, item))
+    }
+
+    fn served(&self, category: SiteCategory) -> u64 {
+        self.served.get(&category).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_site_name() {
+        assert_eq!(SiteCategory::from_site_name("JPCERT"), SiteCategory::Security);
+        assert_eq!(SiteCategory::from_site_name("Trend Micro Security News"), SiteCategory::Security);
+        assert_eq!(SiteCategory::from_site_name("Zenn Topic - rust"), SiteCategory::Community);
+        assert_eq!(SiteCategory::from_site_name("Dev.to - rust"), SiteCategory::Community);
+        assert_eq!(SiteCategory::from_site_name("note Topic - 機械学習"), SiteCategory::Community);
+        assert_eq!(SiteCategory::from_site_name("Mastodon - #rust"), SiteCategory::Community);
+        assert_eq!(SiteCategory::from_site_name("AI News"), SiteCategory::Ai);
+        assert_eq!(SiteCategory::from_site_name("Google AI Blog"), SiteCategory::Ai);
+        assert_eq!(SiteCategory::from_site_name("Google DeepMind Blog"), SiteCategory::Ai);
+        assert_eq!(SiteCategory::from_site_name("OpenAI News"), SiteCategory::Ai);
+        assert_eq!(SiteCategory::from_site_name("Anthropic News"), SiteCategory::Ai);
+        assert_eq!(SiteCategory::from_site_name("NVD CVE"), SiteCategory::Security);
+        assert_eq!(SiteCategory::from_site_name("The Hacker News"), SiteCategory::Security);
+        assert_eq!(SiteCategory::from_site_name("BleepingComputer"), SiteCategory::Security);
+        assert_eq!(SiteCategory::from_site_name("Ars Technica"), SiteCategory::News);
+        assert_eq!(SiteCategory::from_site_name("The Verge"), SiteCategory::News);
+        assert_eq!(SiteCategory::from_site_name("Publickey"), SiteCategory::News);
+        assert_eq!(SiteCategory::from_site_name("Hatena Developer Blog"), SiteCategory::TechBlog);
+        assert_eq!(SiteCategory::from_site_name("DevelopersIO - AWS"), SiteCategory::TechBlog);
+        assert_eq!(SiteCategory::from_site_name("Mercari Engineering Blog"), SiteCategory::TechBlog);
+        assert_eq!(SiteCategory::from_site_name("Kubernetes Blog"), SiteCategory::TechBlog);
+        assert_eq!(SiteCategory::from_site_name("crates.io - tokio"), SiteCategory::TechBlog);
+        assert_eq!(SiteCategory::from_site_name("PyPI - requests"), SiteCategory::TechBlog);
+        assert_eq!(SiteCategory::from_site_name("CNCF Blog"), SiteCategory::TechBlog);
+        assert_eq!(SiteCategory::from_site_name("MIT Research"), SiteCategory::Academic);
+        assert_eq!(SiteCategory::from_site_name("Gigazine"), SiteCategory::News);
+        assert_eq!(SiteCategory::from_site_name("Example"), SiteCategory::General);
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let weights: CategoryWeights = "security=3, community=1".parse().unwrap();
+        assert_eq!(weights.weight(SiteCategory::Security), 3);
+        assert_eq!(weights.weight(SiteCategory::News), 1);
+        assert!("security=0".parse::<CategoryWeights>().is_err());
+        assert!("unknown=2".parse::<CategoryWeights>().is_err());
+        assert!("security".parse::<CategoryWeights>().is_err());
+    }
+
+    #[test]
+    fn test_fair_queue_interleaves_categories() {
+        let mut queue = FairQueue::new(CategoryWeights::default());
+        for i in 0..7 {
+            queue.push(SiteCategory::Community, format!("zenn-{}", i));
+        }
+        queue.push(SiteCategory::Security, "jpcert".to_string());
+        queue.push(SiteCategory::Security, "ipa".to_string());
+
+        let order: Vec<String> = std::iter::from_fn(|| queue.pop().map(|(_, item)| item)).collect();
+        assert_eq!(&order[..4], &["jpcert", "zenn-0", "ipa", "zenn-1"]);
+        assert_eq!(order.len(), 9);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_fair_queue_respects_weights() {
+        let weights = CategoryWeights::default().with_weight(SiteCategory::Security, 2);
+        let mut queue = FairQueue::new(weights);
+        for i in 0..4 {
+            queue.push(SiteCategory::Security, i);
+            queue.push(SiteCategory::News, 10 + i);
+        }
+        let categories: Vec<SiteCategory> = (0..6).filter_map(|_| queue.pop().map(|(c, _)| c)).collect();
+        let security = categories.iter().filter(|c| **c == SiteCategory::Security).count();
+        assert_eq!(security, 4);
+        assert_eq!(queue.len(), 2);
+    }
+}
@@ -0,0 +1,60 @@
+//! 記事ページのフィクスチャ．
+//!
+//! `with_fixture_page` のスコープ内では，`WebSiteInterface::request` が指定した URL へのリクエストを
+//! ネットワークに出さず，保存済みの HTML で応答する．サイト固有の `parse_article`（セレクタや
+//! ログイン判定を含む）をそのまま実ページの代わりに試せるため，`sites doctor` のフィクスチャ診断に使う．
+
+use request::header::CONTENT_TYPE;
+use request::{Response, ResponseBuilderExt, StatusCode, Url};
+use std::future::Future;
+
+tokio::task_local! {
+    static FIXTURE: FixturePage;
+}
+
+struct FixturePage {
+    url: Url,
+    body: String,
+}
+
+/// `future` の実行中，`url` へのリクエストに `body` を返す
+pub async fn with_fixture_page<F: Future>(url: Url, body: String, future: F) -> F::Output {
+    FIXTURE.scope(FixturePage { url, body }, future).await
+}
+
+/// スコープ内で `url` のフィクスチャが設定されていれば，その HTML を本文とする `200 OK` のレスポンスを返す
+pub fn fixture_response(url: &Url) -> Option<Response> {
+    FIXTURE
+        .try_with(|page| {
+            (page.url == *url).then(|| {
+                let response = axum::http::Response::builder()
+                    .status(StatusCode::OK.as_u16())
+                    .header(CONTENT_TYPE.as_str(), "text/html; charset=utf-8")
+                    .url(url.clone())
+                    .body(page.body.clone())
+                    .expect("fixture headers are valid");
+                Response::from(response)
+            })
+        })
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixture_response_only_inside_scope_and_for_same_url() {
+        let url = Url::parse("https://example.com/articles/1").unwrap();
+        assert!(fixture_response(&url).is_none());
+
+        let body = with_fixture_page(url.clone(), "<p>fixture</p>".into(), async {
+            let other = Url::parse("https://example.com/articles/2").unwrap();
+            assert!(fixture_response(&other).is_none());
+            fixture_response(&url).unwrap().text().await.unwrap()
+        })
+        .await;
+        assert_eq!(body, "<p>fixture</p>");
+    }
+}
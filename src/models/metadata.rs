@@ -0,0 +1,317 @@
+//! 記事ページの OpenGraph（`og:*`），Twitter Card（`twitter:*`），JSON-LD（schema.org の `Article`）の読み取り．
+//!
+//! 一覧ページやフィードから公開日時・概要を取得できないサイトでは，記事ページのメタデータで補う．
+//! 複数の情報源がある場合は JSON-LD，OpenGraph，Twitter Card，通常の meta タグの順に優先する．
+
+use crate::enrichment::robots::{RobotsDirectives, global_robots_policies};
+use crate::models::web_article::{WebArticle, WebSiteInterface};
+use crate::shared::selectors::selector;
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 記事として扱う schema.org の型
+const ARTICLE_TYPES: &[&str] = &[
+    "Article",
+    "NewsArticle",
+    "BlogPosting",
+    "TechArticle",
+    "ScholarlyArticle",
+    "ReportageNewsArticle",
+    "AnalysisNewsArticle",
+    "Report",
+];
+
+/// ページのメタデータ
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 代表画像の URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<DateTime<Local>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Local>>,
+    /// 著者（複数の場合はカンマ区切り）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+}
+
+impl PageMetadata {
+    pub fn parse(raw_html: &str) -> Self {
+        Self::from_document(&scraper::Html::parse_document(raw_html))
+    }
+
+    pub fn from_document(doc: &scraper::Html) -> Self {
+        let ld = json_ld_article(doc).map(|v| Self::from_json_ld(&v)).unwrap_or_default();
+        let meta = |keys: &[&str]| keys.iter().find_map(|key| meta_content(doc, key));
+        Self {
+            title: ld.title.or_else(|| meta(&["og:title", "twitter:title"])),
+            description: ld
+                .description
+                .or_else(|| meta(&["og:description", "twitter:description", "description"])),
+            image: ld
+                .image
+                .or_else(|| meta(&["og:image", "og:image:url", "twitter:image", "twitter:image:src"])),
+            published: ld.published.or_else(|| {
+                meta(&["article:published_time", "og:published_time", "pubdate", "date"])
+                    .and_then(|v| parse_published(&v))
+            }),
+            modified: ld.modified.or_else(|| {
+                meta(&["article:modified_time", "og:updated_time"]).and_then(|v| parse_published(&v))
+            }),
+            author: ld.author.or_else(|| meta(&["author", "article:author", "twitter:creator"])),
+            site_name: ld.site_name.or_else(|| meta(&["og:site_name", "twitter:site"])),
+        }
+    }
+
+    /// schema.org の `Article` のオブジェクトから読み取る
+    pub fn from_json_ld(value: &Value) -> Self {
+        let text = |key: &str| value[key].as_str().map(clean).filter(|s| !s.is_empty());
+        Self {
+            title: text("headline").or_else(|| text("name")),
+            description: text("description"),
+            image: image_url(&value["image"]),
+            published: value["datePublished"].as_str().and_then(parse_published),
+            modified: value["dateModified"].as_str().and_then(parse_published),
+            author: names(&value["author"]),
+            site_name: names(&value["publisher"]),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 一覧・フィードで取得できなかった項目（空のタイトル・概要・画像，既定値のままの公開日時）を補う．
+    /// 補った項目があれば `true`．
+    pub fn fill(&self, article: &mut WebArticle) -> bool {
+        let mut filled = false;
+        if article.title.trim().is_empty()
+            && let Some(title) = &self.title
+        {
+            article.title = title.clone();
+            filled = true;
+        }
+        if article.description.trim().is_empty()
+            && let Some(description) = &self.description
+        {
+            article.description = description.clone();
+            filled = true;
+        }
+        if article.properties.image.is_none()
+            && let Some(image) = &self.image
+        {
+            article.properties.image = Some(image.clone());
+            filled = true;
+        }
+        if has_missing_timestamp(article)
+            && let Some(published) = self.published.or(self.modified)
+        {
+            article.timestamp = published;
+            filled = true;
+        }
+        filled
+    }
+}
+
+/// 公開日時を取得できずに既定値（1970-01-01）のままになっているか
+pub fn has_missing_timestamp(article: &WebArticle) -> bool {
+    article.timestamp == DateTime::<Local>::default()
+}
+
+/// 記事ページのメタデータで補う必要があるか（公開日時またはタイトルが無い）
+pub fn needs_metadata(article: &WebArticle) -> bool {
+    has_missing_timestamp(article) || article.title.trim().is_empty()
+}
+
+/// 公開日時・タイトルの無い記事のページを取得してメタデータで補い，補った件数を返す．
+/// 取得に失敗した記事はそのままにする．
+pub async fn fill_missing_metadata(site: &dyn WebSiteInterface, articles: &mut [WebArticle]) -> usize {
+    let mut filled = 0;
+    for article in articles.iter_mut().filter(|a| needs_metadata(a)) {
+        let body = match site.request_text(&article.article_url, "").await {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!("Failed to fetch metadata of {}: {}", article.article_url, e);
+                continue;
+            }
+        };
+        global_robots_policies().record(&site.site_name(), &RobotsDirectives::from_html(&body));
+        if PageMetadata::parse(&body).fill(article) {
+            filled += 1;
+        }
+    }
+    if filled > 0 {
+        tracing::info!("Filled metadata of {} articles from {}", filled, site.site_name());
+    }
+    filled
+}
+
+/// RFC 3339 の日時（`+0900` のようにコロンの無いオフセットを含む），または日付（その日の 0 時とする）
+pub fn parse_published(value: &str) -> Option<DateTime<Local>> {
+    let value = value.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.with_timezone(&Local));
+    }
+    if let Ok(datetime) = DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(datetime.with_timezone(&Local));
+    }
+    let date = NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()?;
+    Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()
+}
+
+/// `property` または `name` が `key` の meta タグの内容
+fn meta_content(doc: &scraper::Html, key: &str) -> Option<String> {
+    doc.select(selector!("meta[content]"))
+        .filter(|e| {
+            let attrs = e.value();
+            [attrs.attr("property"), attrs.attr("name"), attrs.attr("itemprop")]
+                .into_iter()
+                .flatten()
+                .any(|v| v.eq_ignore_ascii_case(key))
+        })
+        .filter_map(|e| e.value().attr("content"))
+        .map(clean)
+        .find(|v| !v.is_empty())
+}
+
+/// JSON-LD の記事の本文（`articleBody`，プレーンテキスト）
+pub fn json_ld_article_body(raw_html: &str) -> Option<String> {
+    let doc = scraper::Html::parse_document(raw_html);
+    json_ld_article(&doc)?["articleBody"]
+        .as_str()
+        .map(str::trim)
+        .filter(|body| !body.is_empty())
+        .map(str::to_string)
+}
+
+/// JSON-LD のうち最初の記事のオブジェクト（配列・`@graph` の中も探す）
+fn json_ld_article(doc: &scraper::Html) -> Option<Value> {
+    doc.select(selector!("script[type='application/ld+json']"))
+        .filter_map(|script| serde_json::from_str::<Value>(script.text().collect::<String>().trim()).ok())
+        .find_map(|value| find_article(&value).cloned())
+}
+
+fn find_article(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(find_article),
+        Value::Object(object) => {
+            if is_article_type(&value["@type"]) {
+                return Some(value);
+            }
+            object.get("@graph").and_then(find_article)
+        }
+        _ => None,
+    }
+}
+
+fn is_article_type(value: &Value) -> bool {
+    match value {
+        Value::String(t) => ARTICLE_TYPES.contains(&t.as_str()),
+        Value::Array(types) => types.iter().any(is_article_type),
+        _ => false,
+    }
+}
+
+/// `"https://..."`，`{"url": ...}`，またはその配列の最初の URL
+fn image_url(value: &Value) -> Option<String> {
+    match value {
+        Value::String(url) => Some(url.trim().to_string()).filter(|u| !u.is_empty()),
+        Value::Object(_) => value["url"].as_str().or(value["@id"].as_str()).map(|u| u.trim().to_string()),
+        Value::Array(items) => items.iter().find_map(image_url),
+        _ => None,
+    }
+}
+
+/// `"名前"`，`{"name": ...}`，またはその配列の名前をカンマ区切りにしたもの
+fn names(value: &Value) -> Option<String> {
+    let names: Vec<String> = match value {
+        Value::String(name) => vec![clean(name)],
+        Value::Object(_) => value["name"].as_str().map(clean).into_iter().collect(),
+        Value::Array(items) => items.iter().filter_map(names).collect(),
+        _ => Vec::new(),
+    };
+    let names: Vec<String> = names.into_iter().filter(|n| !n.is_empty()).collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+fn clean(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    #[test]
+    fn test_json_ld_takes_precedence() {
+        let html = r#"<html><head>
+            <meta property="og:title" content="OG title">
+            <meta property="og:description" content="OG description">
+            <meta name="twitter:image" content="https://example.com/twitter.png">
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@graph": [
+                {"@type": "WebSite", "name": "Example"},
+                {"@type": ["NewsArticle"], "headline": " LD  headline ", "datePublished": "2025-10-01T09:00:00+0900",
+                 "image": [{"@type": "ImageObject", "url": "https://example.com/ld.png"}],
+                 "author": [{"@type": "Person", "name": "Alice"}, {"@type": "Person", "name": "Bob"}],
+                 "publisher": {"@type": "Organization", "name": "Example News"}}
+            ]}
+            </script></head><body></body></html>"#;
+        let metadata = PageMetadata::parse(html);
+        assert_eq!(metadata.title.as_deref(), Some("LD headline"));
+        assert_eq!(metadata.description.as_deref(), Some("OG description"));
+        assert_eq!(metadata.image.as_deref(), Some("https://example.com/ld.png"));
+        assert_eq!(metadata.published, parse_published("2025-10-01T09:00:00+09:00"));
+        assert_eq!(metadata.author.as_deref(), Some("Alice, Bob"));
+        assert_eq!(metadata.site_name.as_deref(), Some("Example News"));
+    }
+
+    #[test]
+    fn test_open_graph_and_twitter_fallback() {
+        let html = r#"<head>
+            <script type="application/ld+json">{ invalid json</script>
+            <meta name="twitter:title" content="Twitter title">
+            <meta name="twitter:image" content="https://example.com/twitter.png">
+            <meta name="description" content="説明">
+            <meta property="article:published_time" content="2025-09-30">
+            </head>"#;
+        let metadata = PageMetadata::parse(html);
+        assert_eq!(metadata.title.as_deref(), Some("Twitter title"));
+        assert_eq!(metadata.description.as_deref(), Some("説明"));
+        assert_eq!(metadata.image.as_deref(), Some("https://example.com/twitter.png"));
+        assert_eq!(metadata.published, parse_published("2025-09-30"));
+        assert!(PageMetadata::parse("<p>no metadata</p>").is_empty());
+    }
+
+    #[test]
+    fn test_fill_only_missing_fields() {
+        let mut article = ArticleBuilder::new("一覧のタイトル")
+            .with_url("https://example.com/1")
+            .with_timestamp(DateTime::<Local>::default())
+            .build();
+        assert!(needs_metadata(&article));
+        let metadata = PageMetadata {
+            title: Some("ページのタイトル".into()),
+            description: Some("概要".into()),
+            image: Some("https://example.com/a.png".into()),
+            published: parse_published("2025-10-01T09:00:00+09:00"),
+            ..Default::default()
+        };
+        assert!(metadata.fill(&mut article));
+        assert_eq!(article.title, "一覧のタイトル");
+        assert_eq!(article.description, "概要");
+        assert_eq!(article.properties.image.as_deref(), Some("https://example.com/a.png"));
+        assert_eq!(Some(article.timestamp), metadata.published);
+        assert!(!needs_metadata(&article));
+        assert!(!metadata.fill(&mut article));
+    }
+}
@@ -1,11 +1,395 @@
+pub mod annotation;
+pub mod dedup;
+pub mod extraction;
+pub mod fairness;
+pub mod fixture;
+pub mod metadata;
+pub mod ordering;
+pub mod priority;
+pub mod quality;
+pub mod registry;
+pub mod reliability;
+pub mod report;
+pub mod request_policy;
 pub mod sites;
+pub mod tag;
 pub mod web_article;
 pub mod web_site;
+use crate::enrichment::language::detect_languages;
+use crate::enrichment::robots::global_robots_policies;
+use crate::models::dedup::{DEFAULT_TITLE_SIMILARITY, Duplicate, dedup_articles};
+use crate::models::fairness::{CategoryWeights, FairQueue};
+use crate::models::metadata::fill_missing_metadata;
+use crate::models::ordering::sort_newest_first;
+use crate::models::priority::{PriorityOverrides, fetch_budget_from_env};
+use crate::models::quality::{SiteQuality, collect_metrics};
+use crate::models::registry::SiteRegistry;
+use crate::models::report::{ContentStats, FetchReport, SiteError, TextStats};
 use crate::models::sites::*;
-use crate::models::web_article::WebSiteInterface;
-use crate::shared::errors::AppResult;
+use crate::models::web_article::{FetchOptions, WebArticle, WebSiteInterface};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::progress::{FetchProgress, SiteState};
+use crate::shared::text::truncate_text;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
+/// 有効なサイト一覧を返す．`CLIPPER_SITES_CONFIG` が設定されていればその設定ファイルを反映する．
 pub async fn get_all_sites() -> AppResult<Vec<Box<dyn WebSiteInterface>>> {
+    SiteRegistry::load_default()?.build_sites()
+}
+
+/// `fetch_all_articles` の結果．失敗したサイトがあっても取得できた記事は返す．
+#[derive(Debug, Default)]
+pub struct FetchAllOutcome {
+    pub articles: Vec<WebArticle>,
+    /// サイトごとの結果（サイト一覧と同じ順序）
+    pub reports: Vec<FetchReport>,
+    /// 他サイトの記事と重複していたため除外した記事
+    pub duplicates: Vec<Duplicate>,
+}
+
+impl FetchAllOutcome {
+    /// 記事一覧の取得に失敗したサイトとそのエラー
+    pub fn errors(&self) -> impl Iterator<Item = (&str, &SiteError)> {
+        self.reports
+            .iter()
+            .flat_map(|r| r.errors.iter().map(move |e| (r.site.as_str(), e)))
+    }
+
+    /// 取得に失敗した・時間予算で打ち切った・代替の処理に切り替えたサイトの結果
+    pub fn degraded(&self) -> impl Iterator<Item = &FetchReport> {
+        self.reports
+            .iter()
+            .filter(|r| !r.is_ok() || r.budget_exhausted || !r.degraded.is_empty())
+    }
+}
+
+/// 有効な全サイトの `get_articles()` を最大 `concurrency` 並列で実行し，記事をまとめて返す
+pub async fn fetch_all_articles(concurrency: usize) -> AppResult<FetchAllOutcome> {
+    let sites = get_all_sites().await?;
+    Ok(fetch_articles_from(sites, concurrency).await)
+}
+
+/// 取得順・時間予算・取得する記事の範囲の設定
+#[derive(Debug, Clone, Default)]
+pub struct FetchSchedule {
+    pub weights: CategoryWeights,
+    pub priorities: PriorityOverrides,
+    /// `critical` 以外のサイトにかける時間の予算（`None` は無制限）
+    pub budget: Option<Duration>,
+    /// 各サイトの `get_articles_with` に渡す期間・件数
+    pub window: FetchOptions,
+    /// サイトごとの取得状態を表示する進捗表示（`None` は表示しない）
+    pub progress: Option<Arc<FetchProgress>>,
+}
+
+impl FetchSchedule {
+    /// `CLIPPER_CATEGORY_WEIGHTS`，`CLIPPER_SITE_PRIORITIES`，`CLIPPER_FETCH_BUDGET_SECS` から読み込む．
+    /// 不正な値は警告を出して無視する．
+    pub fn from_env() -> Self {
+        let weights = CategoryWeights::from_env().unwrap_or_else(|e| {
+            tracing::warn!("Ignoring category weights: {}", e);
+            CategoryWeights::default()
+        });
+        let priorities = PriorityOverrides::from_env().unwrap_or_else(|e| {
+            tracing::warn!("Ignoring site priorities: {}", e);
+            PriorityOverrides::default()
+        });
+        let budget = fetch_budget_from_env().unwrap_or_else(|e| {
+            tracing::warn!("Ignoring fetch budget: {}", e);
+            None
+        });
+        Self {
+            weights,
+            priorities,
+            budget,
+            window: FetchOptions::default(),
+            progress: None,
+        }
+    }
+}
+
+/// 指定したサイトの `get_articles()` を最大 `concurrency` 並列で実行する．
+/// 起動順は優先度（`CLIPPER_SITE_PRIORITIES`）の高い順，同じ優先度の中ではカテゴリごとの
+/// 重み付き公平キュー（`CLIPPER_CATEGORY_WEIGHTS`）で決める．
+/// 複数サイトに掲載された同じ記事は，サイト一覧で先にあるサイトのものだけを残す．
+pub async fn fetch_articles_from(
+    sites: Vec<Box<dyn WebSiteInterface>>,
+    concurrency: usize,
+) -> FetchAllOutcome {
+    fetch_articles_fairly(sites, concurrency, FetchSchedule::from_env()).await
+}
+
+/// `fetch_articles_from` の本体．空きができるたびに優先度の高い公平キューから次のサイトを取り出して起動する．
+/// 時間予算を過ぎると `critical` 以外のサイトは起動せず，実行中の取得も打ち切る．
+pub async fn fetch_articles_fairly(
+    sites: Vec<Box<dyn WebSiteInterface>>,
+    concurrency: usize,
+    schedule: FetchSchedule,
+) -> FetchAllOutcome {
+    let mut queues = BTreeMap::new();
+    for (index, site) in sites.into_iter().enumerate() {
+        let priority = schedule.priorities.resolve(&site.site_name(), site.priority());
+        queues
+            .entry(priority)
+            .or_insert_with(|| FairQueue::new(schedule.weights.clone()))
+            .push(site.category(), (index, site));
+    }
+
+    let deadline = schedule.budget.map(|budget| tokio::time::Instant::now() + budget);
+    let window = schedule.window;
+    let progress = schedule.progress;
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+    let mut results = Vec::new();
+    for (priority, mut queue) in queues {
+        let deadline = deadline.filter(|_| !priority.is_budget_exempt());
+        while let Some((_, (index, mut site))) = queue.pop() {
+            let acquire = semaphore.clone().acquire_owned();
+            let permit = match deadline {
+                Some(deadline) if tokio::time::Instant::now() >= deadline => None,
+                Some(deadline) => tokio::time::timeout_at(deadline, acquire).await.ok(),
+                None => Some(acquire.await),
+            };
+            let Some(permit) = permit else {
+                tracing::info!("Skipping {}: fetch budget exhausted", site.site_name());
+                if let Some(progress) = &progress {
+                    progress.fail(&site.site_name(), "fetch budget exhausted");
+                }
+                let mut report = FetchReport::new(site.site_name());
+                report.budget_exhausted = true;
+                results.push((index, report, Vec::new()));
+                continue;
+            };
+            let permit = permit.expect("semaphore is never closed");
+            let progress = progress.clone();
+            tasks.spawn(async move {
+                let _permit = permit;
+                let mut report = FetchReport::new(site.site_name());
+                if let Some(progress) = &progress {
+                    progress.set_state(&report.site, SiteState::Fetching);
+                }
+                let started = Instant::now();
+                // 公開日時・タイトルを一覧から取得できなかった記事は，記事ページのメタデータで補う
+                let fetch = collect_metrics(async {
+                    let mut result = site.get_articles_with(&window).await;
+                    if let Ok(articles) = result.as_mut() {
+                        fill_missing_metadata(site.as_ref(), articles).await;
+                        global_robots_policies().apply(articles);
+                        detect_languages(articles);
+                    }
+                    result
+                });
+                let fetched = match deadline {
+                    Some(deadline) => tokio::time::timeout_at(deadline, fetch).await.ok(),
+                    None => Some(fetch.await),
+                };
+                report.duration = started.elapsed();
+                let Some((result, metrics)) = fetched else {
+                    tracing::info!("Cancelled {}: fetch budget exhausted", report.site);
+                    if let Some(progress) = &progress {
+                        progress.fail(&report.site, "fetch budget exhausted");
+                    }
+                    report.budget_exhausted = true;
+                    return (index, report, Vec::new());
+                };
+                metrics.iter().for_each(|m| report.quality.record(m));
+                let articles = match result {
+                    Ok(articles) => {
+                        report.fetched = articles.len();
+                        report.content = ContentStats::from_articles(&articles);
+                        articles
+                    }
+                    Err(e) => {
+                        report.record_error(&e);
+                        Vec::new()
+                    }
+                };
+                if let Some(progress) = &progress {
+                    match report.errors.first() {
+                        Some(error) => progress.fail(&report.site, &error.message),
+                        None => progress.set_counts(&report.site, SiteState::Done, report.fetched, 0),
+                    }
+                }
+                (index, report, articles)
+            });
+        }
+    }
+
+    let mut total = 0;
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => {
+                total += result.2.len();
+                if let Some(progress) = &progress {
+                    progress.set_total_articles(total);
+                }
+                results.push(result)
+            }
+            Err(e) => tracing::error!("Fetch task panicked: {}", e),
+        }
+    }
+    results.sort_by_key(|(index, _, _)| *index);
+
+    let mut outcome = FetchAllOutcome::default();
+    for (_, report, articles) in results {
+        outcome.articles.extend(articles);
+        outcome.reports.push(report);
+    }
+    let (mut articles, duplicates) = dedup_articles(outcome.articles, DEFAULT_TITLE_SIMILARITY);
+    if !duplicates.is_empty() {
+        tracing::info!("Collapsed {} duplicate articles", duplicates.len());
+    }
+    // 重複の除去はサイト一覧の順序で行い，返す記事はサイトの順序によらない順に並べる
+    sort_newest_first(&mut articles);
+    outcome.articles = articles;
+    outcome.duplicates = duplicates;
+    outcome
+}
+
+/// 有料会員限定の記事の概要に使う無料部分の最大文字数
+const PAYWALL_PREVIEW_CHARS: usize = 300;
+
+/// `fetch_and_parse_all` の結果
+#[derive(Debug, Default)]
+pub struct ParseAllReport {
+    /// 本文を埋めた記事の数
+    pub parsed: usize,
+    /// パースに失敗した記事の URL とエラー（URL 順）
+    pub errors: Vec<(String, String)>,
+    /// サイトごとの本文の統計
+    pub text: BTreeMap<String, TextStats>,
+    /// サイトごとの本文抽出の品質（パースに成功した記事のみ）
+    pub quality: BTreeMap<String, SiteQuality>,
+}
+
+impl ParseAllReport {
+    /// 本文の統計と抽出の品質を同じサイトの取得結果に加える
+    pub fn apply_to(&self, reports: &mut [FetchReport]) {
+        for report in reports {
+            if let Some(stats) = self.text.get(&report.site) {
+                report.text.merge(stats);
+                report.parsed += stats.parsed;
+            }
+            if let Some(quality) = self.quality.get(&report.site) {
+                report.quality.merge(quality);
+            }
+        }
+    }
+}
+
+/// 記事ページを取得して `WebArticle.html` / `text` を埋める（本文のある記事はそのままにする）．
+/// サイトごとに最大 `concurrency` 件を並列にパースし，リクエストの間隔はホストごとのレート制限に従う．
+pub async fn fetch_and_parse_all(articles: &mut [WebArticle], concurrency: usize) -> AppResult<ParseAllReport> {
+    let registry = SiteRegistry::load_default()?;
+    fetch_and_parse_with(articles, concurrency, || registry.build_sites()).await
+}
+
+/// `fetch_and_parse_all` の本体．`parse_article` は `&mut self` を取るため，`build_sites` を繰り返し呼んで
+/// サイトごとに並列数だけインスタンスを用意し，各インスタンスがサイトの記事のキューから順に取り出してパースする．
+/// サイト一覧に無いサイトの記事は失敗として返す．
+pub async fn fetch_and_parse_with<F>(
+    articles: &mut [WebArticle],
+    concurrency: usize,
+    build_sites: F,
+) -> AppResult<ParseAllReport>
+where
+    F: Fn() -> AppResult<Vec<Box<dyn WebSiteInterface>>>,
+{
+    let concurrency = concurrency.max(1);
+    let mut pending: BTreeMap<String, VecDeque<(usize, String)>> = BTreeMap::new();
+    for (index, article) in articles.iter().enumerate() {
+        if article.text.trim().is_empty() {
+            pending
+                .entry(article.site.name.clone())
+                .or_default()
+                .push_back((index, article.article_url.clone()));
+        }
+    }
+    let mut report = ParseAllReport::default();
+    let workers = pending.values().map(VecDeque::len).max().unwrap_or(0).min(concurrency);
+    let mut instances: HashMap<String, Vec<Box<dyn WebSiteInterface>>> = HashMap::new();
+    for _ in 0..workers {
+        for site in build_sites()? {
+            let name = site.site_name();
+            if let Some(queue) = pending.get(&name) {
+                let sites = instances.entry(name).or_default();
+                if sites.len() < queue.len().min(concurrency) {
+                    sites.push(site);
+                }
+            }
+        }
+    }
+
+    let mut tasks = JoinSet::new();
+    for (name, queue) in pending {
+        let Some(sites) = instances.remove(&name) else {
+            report
+                .errors
+                .extend(queue.into_iter().map(|(_, url)| (url, format!("Unknown site: {}", name))));
+            continue;
+        };
+        let queue = Arc::new(std::sync::Mutex::new(queue));
+        for mut site in sites {
+            let queue = queue.clone();
+            tasks.spawn(async move {
+                let mut results = Vec::new();
+                loop {
+                    let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                    let Some((index, url)) = next else {
+                        break;
+                    };
+                    let (result, metrics) = collect_metrics(site.parse_article(&url)).await;
+                    results.push((index, url, result, metrics));
+                }
+                results
+            });
+        }
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        let results = match joined {
+            Ok(results) => results,
+            Err(e) => {
+                tracing::error!("Parse task panicked: {}", e);
+                continue;
+            }
+        };
+        for (index, url, result, metrics) in results {
+            match result {
+                Ok((html, text)) => {
+                    let site = &articles[index].site.name;
+                    report.text.entry(site.clone()).or_default().record(&text);
+                    let quality = report.quality.entry(site.clone()).or_default();
+                    metrics.iter().for_each(|m| quality.record(m));
+                    articles[index].html = html;
+                    articles[index].text = text;
+                    report.parsed += 1;
+                }
+                // 有料会員限定の記事は全文の代わりに無料で読める部分を概要に使う（概要が無い場合のみ）
+                Err(AppError::PaywallError { preview }) => {
+                    let article = &mut articles[index];
+                    article.requires_login = true;
+                    if article.description.trim().is_empty() {
+                        article.description = truncate_text(&preview, PAYWALL_PREVIEW_CHARS);
+                    }
+                    report.errors.push((url, AppError::PaywallError { preview }.to_string()));
+                }
+                Err(e) => report.errors.push((url, e.to_string())),
+            }
+        }
+    }
+    // 記事ページの robots meta タグで見つけた指定を，同じサイトの記事に反映する
+    global_robots_policies().apply(articles);
+    report.errors.sort();
+    Ok(report)
+}
+
+/// クレートに組み込まれているサイト一覧
+pub fn builtin_sites() -> Vec<Box<dyn WebSiteInterface>> {
     let sites: Vec<Box<dyn WebSiteInterface>> = vec![
         Box::new(ai_db::AIDB::default()),
         Box::new(ai_it_now::AIItNow::default()),
@@ -13,48 +397,67 @@ pub async fn get_all_sites() -> AppResult<Vec<Box<dyn WebSiteInterface>>> {
         Box::new(ai_scholar::AIScholar::default()),
         Box::new(aismiley::AISmiley::default()),
         Box::new(aizine::AIZine::default()),
+        Box::new(anthropic_news::AnthropicNews::default()),
+        Box::new(ars_technica::ArsTechnica::default()),
         Box::new(ascii::Ascii::default()),
         Box::new(aws_security_blog::AWSSecurityBlog::default()),
+        Box::new(bleeping_computer::BleepingComputer::default()),
         Box::new(business_insider_science::BusinessInsiderScience::default()),
         Box::new(business_insider_technology::BusinessInsiderTechnology::default()),
         Box::new(canon_malware_center::CanonMalwareCenter::default()),
+        Box::new(cncf_blog::CNCFBlog::default()),
         Box::new(codezine::CodeZine::default()),
         Box::new(cookpad_techblog::CookpadTechBlog::default()),
         Box::new(crowdstrike_blog::CrowdStrikeBlog::default()),
-        Box::new(cyberagent_techblog::CyberAgentTechBlog::default()),
+        Box::new(feeds::CYBERAGENT_TECH_BLOG.build()),
         Box::new(cybozu_blog::CybozuBlog::default()),
         Box::new(dena_engineering_blog::DeNAEngineeringBlog::default()),
+        Box::new(developers_io::DevelopersIO::tagged("AWS")),
+        Box::new(developers_io::DevelopersIO::tagged("生成AI")),
+        Box::new(devto::DevTo::new("rust")),
+        Box::new(devto::DevTo::new("ai")),
+        Box::new(devto::DevTo::new("security")),
         Box::new(gigazine::Gigazine::default()),
         Box::new(github_developers_blog::GitHubDevelopersBlog::default()),
         Box::new(gizmodo::Gizmodo::default()),
         // TODO: investigate reqwest decoding error
         // Box::new(google_developers_blog::GoogleDevelopersBlog::default()),
-        Box::new(gree_techblog::GreeTechBlog::default()),
-        Box::new(gunosy_techblog::GunosyTechBlog::default()),
+        Box::new(feeds::GOOGLE_AI_BLOG.build()),
+        Box::new(feeds::GOOGLE_DEEPMIND_BLOG.build()),
+        Box::new(feeds::GREE_TECH_BLOG.build()),
+        Box::new(feeds::GUNOSY_TECH_BLOG.build()),
+        Box::new(hatena_developer_blog::HatenaDeveloperBlog::default()),
         Box::new(ipa_security_center::IPASecurityCenter::default()),
         Box::new(itmedia_at_it::ITMediaAtIt::default()),
         Box::new(itmedia_enterprise::ITMediaEnterprise::default()),
         Box::new(itmedia_marketing::ITMediaMarketing::default()),
         Box::new(itmedia_general::ITMediaGeneral::default()),
         Box::new(jpcert::JPCert::default()),
+        Box::new(kubernetes_blog::KubernetesBlog::default()),
         Box::new(line_techblog::LineTechBlog::default()),
-        // Box::new(medium::Medium::new(
-        //     "Artificial Intelligence",
-        //     "artificial-intelligence",
-        // )),
-        // Box::new(medium::Medium::new("AI", "ai")),
-        // Box::new(medium::Medium::new("Machine Learning", "machine-learning")),
-        // Box::new(medium::Medium::new("ChatGPT", "chatgpt")),
-        // Box::new(medium::Medium::new("Data Science", "data-science")),
-        // Box::new(medium::Medium::new("OpenAI", "openai")),
-        // Box::new(medium::Medium::new("LLM", "llm")),
-        Box::new(mercari_engineering_blog::MercariEngineeringBlog::default()),
+        Box::new(medium::Medium::new("Artificial Intelligence", "artificial-intelligence")),
+        Box::new(medium::Medium::new("AI", "ai")),
+        Box::new(medium::Medium::new("Machine Learning", "machine-learning")),
+        Box::new(medium::Medium::new("ChatGPT", "chatgpt")),
+        Box::new(medium::Medium::new("Data Science", "data-science")),
+        Box::new(medium::Medium::new("OpenAI", "openai")),
+        Box::new(medium::Medium::new("LLM", "llm")),
+        Box::new(feeds::MERCARI_ENGINEERING_BLOG.build()),
         Box::new(mit_ai::MITAI::default()),
         Box::new(mit_research::MITResearch::default()),
         Box::new(moneyforward_developers_blog::MoneyForwardDevelopersBlog::default()),
         Box::new(motex::MoTex::default()),
         Box::new(nikkei_xtech::NikkeiXTech::default()),
+        Box::new(note_topic::NoteTopic::new("機械学習")),
+        Box::new(note_topic::NoteTopic::new("生成AI")),
+        Box::new(note_topic::NoteTopic::new("LLM")),
+        Box::new(nvd_cve::NvdCve::default()),
+        Box::new(openai_news::OpenAINews::default()),
+        Box::new(publickey::Publickey::default()),
         Box::new(qiita_blog::QiitaBlog::default()),
+        Box::new(qiita_tag::QiitaTag::new("LLM")),
+        Box::new(qiita_tag::QiitaTag::new("Rust")),
+        Box::new(qiita_tag::QiitaTag::new("生成AI")),
         Box::new(rust_blog::RustBlog::default()),
         Box::new(sakura_internet_techblog::SakuraInternetTechBlog::default()),
         Box::new(sansan::Sansan::default()),
@@ -66,6 +469,8 @@ pub async fn get_all_sites() -> AppResult<Vec<Box<dyn WebSiteInterface>>> {
         Box::new(supership::Supership::default()),
         Box::new(tech_crunch::TechCrunch::default()),
         Box::new(techno_edge::TechnoEdge::default()),
+        Box::new(the_hacker_news::TheHackerNews::default()),
+        Box::new(the_verge::TheVerge::default()),
         Box::new(tokyo_univ_engineering::TokyoUniversityEngineering::default()),
         Box::new(trend_micro_security_news::TrendMicroSecurityNews::default()),
         Box::new(trend_micro_security_advisories::TrendMicroSecurityAdvisories::default()),
@@ -82,15 +487,297 @@ pub async fn get_all_sites() -> AppResult<Vec<Box<dyn WebSiteInterface>>> {
         Box::new(zenn_trend::ZennTrend::default()),
     ];
 
-    Ok(sites)
+    sites
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::priority::SitePriority;
+    use crate::models::report::FailureKind;
+    use crate::models::web_article::{Cookie, Html, Text};
     use crate::shared::logger::init_logger;
+    use crate::testing::article::ArticleBuilder;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use tracing::{Level, event};
 
+    /// ネットワークに接続しないテスト用のサイト
+    struct StubSite {
+        name: String,
+        fail: bool,
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+        started: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl WebSiteInterface for StubSite {
+        fn site_name(&self) -> String {
+            self.name.clone()
+        }
+        fn site_url(&self) -> request::Url {
+            request::Url::parse("https://stub.example.com/feed").unwrap()
+        }
+        fn domain(&self) -> String {
+            "stub.example.com".to_string()
+        }
+        async fn login(&mut self) -> AppResult<Cookie> {
+            Ok(Cookie::default())
+        }
+        async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+            self.started.lock().unwrap().push(self.name.clone());
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            if self.fail {
+                return Err(AppError::ScrapeError("stub failure".into()));
+            }
+            Ok(vec![
+                ArticleBuilder::new(format!("{} article", self.name))
+                    .with_site(self.site_name(), self.site_url().to_string())
+                    .with_url(format!("https://stub.example.com/{}", self.name))
+                    .build(),
+            ])
+        }
+        async fn parse_article(&mut self, _url: &str) -> AppResult<(Html, Text)> {
+            Ok((Html::new(), Text::new()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_articles_from_is_bounded_and_collects_errors() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let sites: Vec<Box<dyn WebSiteInterface>> = (0..6)
+            .map(|i| {
+                Box::new(StubSite {
+                    name: format!("site{}", i),
+                    fail: i == 3,
+                    running: running.clone(),
+                    max_running: max_running.clone(),
+                    started: Default::default(),
+                }) as Box<dyn WebSiteInterface>
+            })
+            .collect();
+
+        let outcome = fetch_articles_from(sites, 2).await;
+        assert_eq!(outcome.articles.len(), 5);
+        assert_eq!(outcome.reports.len(), 6);
+        assert_eq!(outcome.reports[3].site, "site3");
+        let errors: Vec<_> = outcome.errors().map(|(site, e)| (site, e.kind, e.message.as_str())).collect();
+        assert_eq!(errors, vec![("site3", FailureKind::Parse, "Scrape Error: stub failure")]);
+        let degraded: Vec<&str> = outcome.degraded().map(|r| r.site.as_str()).collect();
+        assert_eq!(degraded, vec!["site3"]);
+        assert!(max_running.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_articles_fairly_updates_progress() {
+        let names: Vec<String> = (0..3).map(|i| format!("site{}", i)).collect();
+        let sites: Vec<Box<dyn WebSiteInterface>> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                Box::new(StubSite {
+                    name: name.clone(),
+                    fail: i == 1,
+                    running: Default::default(),
+                    max_running: Default::default(),
+                    started: Default::default(),
+                }) as Box<dyn WebSiteInterface>
+            })
+            .collect();
+        let progress = Arc::new(FetchProgress::hidden(&names));
+        let schedule = FetchSchedule { progress: Some(progress.clone()), ..Default::default() };
+
+        let outcome = fetch_articles_fairly(sites, 2, schedule).await;
+        assert_eq!(outcome.reports.len(), 3);
+        assert_eq!(progress.finished_sites(), 3);
+    }
+
+    /// `parse_article` の同時実行数を数えるテスト用のサイト
+    struct ParseStubSite {
+        name: String,
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+    }
+
+    impl ParseStubSite {
+        fn boxed(
+            name: &str,
+            running: &Arc<AtomicUsize>,
+            max_running: &Arc<AtomicUsize>,
+        ) -> Box<dyn WebSiteInterface> {
+            Box::new(Self {
+                name: name.to_string(),
+                running: running.clone(),
+                max_running: max_running.clone(),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl WebSiteInterface for ParseStubSite {
+        fn site_name(&self) -> String {
+            self.name.clone()
+        }
+        fn site_url(&self) -> request::Url {
+            request::Url::parse("https://stub.example.com/feed").unwrap()
+        }
+        fn domain(&self) -> String {
+            "stub.example.com".to_string()
+        }
+        async fn login(&mut self) -> AppResult<Cookie> {
+            Ok(Cookie::default())
+        }
+        async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+            Ok(Vec::new())
+        }
+        async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            if url.ends_with("fail") {
+                return Err(AppError::ScrapeError("stub failure".into()));
+            }
+            if url.ends_with("paywall") {
+                return Err(AppError::PaywallError {
+                    preview: "無料で読める冒頭部分".into(),
+                });
+            }
+            Ok((format!("<p>{}</p>", url), url.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_parse_with_is_bounded_per_site() {
+        let article = |site: &str, path: &str| {
+            ArticleBuilder::new(path)
+                .with_site(site, "https://stub.example.com")
+                .with_url(format!("https://stub.example.com/{}", path))
+                .build()
+        };
+        let mut articles: Vec<WebArticle> = (0..5).map(|i| article("a", &format!("a{}", i))).collect();
+        articles.push(article("a", "fail"));
+        articles.push(article("b", "b0"));
+        articles.push(article("unknown", "u0"));
+        let mut parsed = article("b", "b1");
+        parsed.text = "already parsed".into();
+        articles.push(parsed);
+
+        let (running_a, max_running_a) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let (running_b, max_running_b) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let build_calls = AtomicUsize::new(0);
+        let build_sites = || {
+            build_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![
+                ParseStubSite::boxed("a", &running_a, &max_running_a),
+                ParseStubSite::boxed("b", &running_b, &max_running_b),
+            ])
+        };
+
+        let report = fetch_and_parse_with(&mut articles, 2, build_sites).await.unwrap();
+        assert_eq!(report.parsed, 6);
+        assert_eq!(
+            report.errors,
+            vec![
+                ("https://stub.example.com/fail".to_string(), "Scrape Error: stub failure".to_string()),
+                ("https://stub.example.com/u0".to_string(), "Unknown site: unknown".to_string()),
+            ]
+        );
+        assert_eq!(articles[0].text, "https://stub.example.com/a0");
+        assert_eq!(articles[6].html, "<p>https://stub.example.com/b0</p>");
+        assert_eq!(articles[8].text, "already parsed");
+        assert_eq!(build_calls.load(Ordering::SeqCst), 2);
+        assert!(max_running_a.load(Ordering::SeqCst) <= 2);
+        assert_eq!(max_running_b.load(Ordering::SeqCst), 1);
+
+        assert_eq!(report.text["a"].parsed, 5);
+        assert_eq!(report.text["a"].total_chars, 5 * "https://stub.example.com/a0".len());
+        let mut reports = vec![FetchReport::new("a"), FetchReport::new("c")];
+        report.apply_to(&mut reports);
+        assert_eq!(reports[0].text, report.text["a"]);
+        assert_eq!(reports[0].parsed, 5);
+        assert_eq!(reports[1].text, TextStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_parse_with_keeps_paywall_preview() {
+        let mut articles = vec![
+            ArticleBuilder::new("Paywalled")
+                .with_site("a", "https://stub.example.com")
+                .with_url("https://stub.example.com/paywall")
+                .build(),
+        ];
+        let (running, max_running) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let build_sites = || Ok(vec![ParseStubSite::boxed("a", &running, &max_running)]);
+
+        let report = fetch_and_parse_with(&mut articles, 1, build_sites).await.unwrap();
+        assert_eq!(report.parsed, 0);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].1.starts_with("Paywalled"));
+        assert!(articles[0].requires_login);
+        assert_eq!(articles[0].description, "無料で読める冒頭部分");
+        assert!(articles[0].text.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_articles_fairly_interleaves_categories() {
+        let started: Arc<std::sync::Mutex<Vec<String>>> = Default::default();
+        let names = (0..5)
+            .map(|i| format!("Zenn Topic - topic{}", i))
+            .chain(["JPCERT".to_string(), "IPA Security Center".to_string()]);
+        let sites: Vec<Box<dyn WebSiteInterface>> = names
+            .map(|name| {
+                Box::new(StubSite {
+                    name,
+                    fail: false,
+                    running: Default::default(),
+                    max_running: Default::default(),
+                    started: started.clone(),
+                }) as Box<dyn WebSiteInterface>
+            })
+            .collect();
+
+        let outcome = fetch_articles_fairly(sites, 1, FetchSchedule::default()).await;
+        assert_eq!(outcome.reports[0].site, "Zenn Topic - topic0");
+        let started = started.lock().unwrap();
+        assert_eq!(started[0], "JPCERT");
+        assert_eq!(started[2], "IPA Security Center");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_articles_fairly_honors_priority_and_budget() {
+        let started: Arc<std::sync::Mutex<Vec<String>>> = Default::default();
+        let names = ["Low Site", "Standard Site", "JPCERT", "IPA Security Center"];
+        let sites: Vec<Box<dyn WebSiteInterface>> = names
+            .iter()
+            .map(|name| {
+                Box::new(StubSite {
+                    name: name.to_string(),
+                    fail: false,
+                    running: Default::default(),
+                    max_running: Default::default(),
+                    started: started.clone(),
+                }) as Box<dyn WebSiteInterface>
+            })
+            .collect();
+        let schedule = FetchSchedule {
+            priorities: PriorityOverrides::default().with_priority("Low Site", SitePriority::Low),
+            budget: Some(Duration::from_millis(5)),
+            ..Default::default()
+        };
+
+        let outcome = fetch_articles_fairly(sites, 1, schedule).await;
+        assert_eq!(*started.lock().unwrap(), vec!["JPCERT", "IPA Security Center"]);
+        assert_eq!(outcome.articles.len(), 2);
+        let exhausted: Vec<bool> = outcome.reports.iter().map(|r| r.budget_exhausted).collect();
+        assert_eq!(exhausted, vec![true, true, false, false]);
+        assert!(outcome.errors().next().is_none());
+    }
+
     #[tokio::test]
     async fn test_all_sites() {
         init_logger("DEBUG").expect("Failed to initialize logger");
@@ -102,25 +789,25 @@ mod tests {
         });
 
         assert!(!sites.is_empty());
-        let mut articles = Vec::new();
-        for site in sites {
-            let mut site = site;
-            let result = site.get_articles().await;
-            match result {
-                Ok(site_articles) => {
-                    event!(
-                        Level::INFO,
-                        "Site Name:{} Articles Count:{}",
-                        site.site_name(),
-                        site_articles.len()
-                    );
-                    articles.extend(site_articles);
-                }
-                Err(e) => {
-                    println!("Error fetching articles from {}: {}", site.site_name(), e);
-                }
+        let outcome = fetch_articles_from(sites, 8).await;
+        for report in &outcome.reports {
+            event!(
+                Level::INFO,
+                "Site Name:{} Articles Count:{} Duration:{:?}",
+                report.site,
+                report.fetched,
+                report.duration
+            );
+        }
+        for report in outcome.degraded() {
+            for error in &report.errors {
+                event!(Level::WARN, "Site Name:{} Failure:{} {}", report.site, error.kind, error);
             }
         }
+        let failures = outcome.reports.iter().filter(|r| !r.is_ok()).count();
+        assert!(failures < outcome.reports.len(), "every site failed");
+        assert!(outcome.errors().all(|(_, e)| !e.message.is_empty()));
+        let articles = outcome.articles;
         assert!(!articles.is_empty());
     }
 
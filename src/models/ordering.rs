@@ -0,0 +1,100 @@
+//! 記事の並び順．
+//!
+//! パイプラインが返す記事，保存済みの記事の検索結果，書き出しはいずれも公開日時の新しい順に並べる．
+//! 同じ時刻の記事はサイト名，さらに URL の順に並べ，巡回したサイトの順序やマップの内部の順序に結果が左右されないようにする．
+
+use crate::models::web_article::WebArticle;
+use std::cmp::Ordering;
+
+impl AsRef<WebArticle> for WebArticle {
+    fn as_ref(&self) -> &WebArticle {
+        self
+    }
+}
+
+/// 新しい順（同じ時刻はサイト名，URL の順）
+pub fn newest_first(a: &WebArticle, b: &WebArticle) -> Ordering {
+    b.timestamp
+        .cmp(&a.timestamp)
+        .then_with(|| a.site.name.cmp(&b.site.name))
+        .then_with(|| a.article_url.cmp(&b.article_url))
+}
+
+/// 古い順（同じ時刻はサイト名，URL の順）
+pub fn oldest_first(a: &WebArticle, b: &WebArticle) -> Ordering {
+    a.timestamp
+        .cmp(&b.timestamp)
+        .then_with(|| a.site.name.cmp(&b.site.name))
+        .then_with(|| a.article_url.cmp(&b.article_url))
+}
+
+/// `newest_first` の順に並べ替える（`WebArticle`，`&WebArticle`，`StoredArticle` のいずれのスライスにも使える）
+pub fn sort_newest_first<T: AsRef<WebArticle>>(articles: &mut [T]) {
+    articles.sort_by(|a, b| newest_first(a.as_ref(), b.as_ref()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Duration, Local, TimeZone};
+    use proptest::prelude::*;
+
+    /// (基準時刻からの分，サイトの番号) から，URL が重複しない記事を作る
+    fn articles(specs: &[(i64, usize)]) -> Vec<WebArticle> {
+        let base = Local.with_ymd_and_hms(2025, 10, 14, 9, 0, 0).unwrap();
+        specs
+            .iter()
+            .enumerate()
+            .map(|(i, &(minutes, site))| {
+                ArticleBuilder::new(format!("Title {}", i))
+                    .with_site(format!("Site {}", site), "https://example.com")
+                    .with_url(format!("https://example.com/{}/{}", minutes, i))
+                    .with_timestamp(base + Duration::minutes(minutes))
+                    .build()
+            })
+            .collect()
+    }
+
+    fn urls(articles: &[WebArticle]) -> Vec<&str> {
+        articles.iter().map(|a| a.article_url.as_str()).collect()
+    }
+
+    /// 記事の指定と，その添字の並べ替え
+    fn specs_and_permutation() -> impl Strategy<Value = (Vec<(i64, usize)>, Vec<usize>)> {
+        prop::collection::vec((0i64..4, 0usize..3), 0..24).prop_flat_map(|specs| {
+            let indices: Vec<usize> = (0..specs.len()).collect();
+            (Just(specs), Just(indices).prop_shuffle())
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_sort_ignores_input_order((specs, permutation) in specs_and_permutation()) {
+            let mut original = articles(&specs);
+            let mut permuted: Vec<WebArticle> = permutation.iter().map(|&i| original[i].clone()).collect();
+            sort_newest_first(&mut original);
+            sort_newest_first(&mut permuted);
+            prop_assert_eq!(urls(&original), urls(&permuted));
+        }
+
+        #[test]
+        fn prop_sorted_by_timestamp_site_and_url((specs, _) in specs_and_permutation()) {
+            let mut sorted = articles(&specs);
+            sort_newest_first(&mut sorted);
+            for pair in sorted.windows(2) {
+                let (a, b) = (&pair[0], &pair[1]);
+                prop_assert!(a.timestamp >= b.timestamp);
+                if a.timestamp == b.timestamp {
+                    prop_assert!((&a.site.name, &a.article_url) < (&b.site.name, &b.article_url));
+                }
+            }
+
+            let mut oldest = articles(&specs);
+            oldest.sort_by(oldest_first);
+            let mut timestamps: Vec<_> = sorted.iter().map(|a| a.timestamp).collect();
+            timestamps.reverse();
+            prop_assert_eq!(oldest.iter().map(|a| a.timestamp).collect::<Vec<_>>(), timestamps);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! サイトの優先度と，1回の巡回にかける時間の予算．
+//!
+//! JPCERT のような重要な情報源は最初に取得し，予算を超えても必ず待つ．
+//! 優先度の低いサイトは，予算が残っている場合にだけ取得する．
+
+use crate::shared::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+use strum::{Display, EnumIter, EnumString};
+
+/// サイトごとの優先度を上書きする環境変数（例: `JPCERT=critical,Gigazine=low`）
+pub const SITE_PRIORITIES_ENV: &str = "CLIPPER_SITE_PRIORITIES";
+/// 1回の巡回にかける時間の予算（秒）を指定する環境変数
+pub const FETCH_BUDGET_ENV: &str = "CLIPPER_FETCH_BUDGET_SECS";
+
+/// サイトの優先度（宣言順が取得順）
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Display, EnumString, EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum SitePriority {
+    /// 最初に取得し，予算の対象外
+    Critical,
+    #[default]
+    Standard,
+    /// 標準のサイトを全て起動した後，予算が残っている場合にだけ取得する
+    Low,
+}
+
+impl SitePriority {
+    /// 組み込みサイトのサイト名から優先度を推定する
+    pub fn from_site_name(site_name: &str) -> Self {
+        let name = site_name.to_lowercase();
+        if ["jpcert", "cisa", "ipa security", "nvd cve"].iter().any(|w| name.contains(w)) {
+            SitePriority::Critical
+        } else {
+            SitePriority::Standard
+        }
+    }
+
+    /// 巡回の時間予算の対象外か
+    pub fn is_budget_exempt(self) -> bool {
+        self == SitePriority::Critical
+    }
+}
+
+/// サイト名ごとの優先度の上書き
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PriorityOverrides(HashMap<String, SitePriority>);
+
+impl PriorityOverrides {
+    pub fn get(&self, site_name: &str) -> Option<SitePriority> {
+        self.0.get(site_name).copied()
+    }
+
+    pub fn with_priority(mut self, site_name: impl Into<String>, priority: SitePriority) -> Self {
+        self.0.insert(site_name.into(), priority);
+        self
+    }
+
+    /// 上書きがあればそれを，無ければサイト自身の優先度を返す
+    pub fn resolve(&self, site_name: &str, default: SitePriority) -> SitePriority {
+        self.get(site_name).unwrap_or(default)
+    }
+
+    /// `CLIPPER_SITE_PRIORITIES` から読み込む．未設定の場合は上書き無し．
+    pub fn from_env() -> AppResult<Self> {
+        match std::env::var(SITE_PRIORITIES_ENV) {
+            Ok(value) if !value.trim().is_empty() => value.parse(),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+impl FromStr for PriorityOverrides {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut overrides = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (site, priority) = pair
+                .rsplit_once('=')
+                .ok_or_else(|| AppError::InternalError(format!("Invalid site priority: {}", pair)))?;
+            let priority = SitePriority::from_str(priority.trim())
+                .map_err(|e| AppError::EnumParseError(format!("{}: {}", priority.trim(), e)))?;
+            overrides = overrides.with_priority(site.trim(), priority);
+        }
+        Ok(overrides)
+    }
+}
+
+/// `CLIPPER_FETCH_BUDGET_SECS` から巡回の時間予算を読み込む．未設定または `0` の場合は無制限．
+pub fn fetch_budget_from_env() -> AppResult<Option<Duration>> {
+    match std::env::var(FETCH_BUDGET_ENV) {
+        Ok(value) if !value.trim().is_empty() => {
+            let secs: u64 = value
+                .trim()
+                .parse()
+                .map_err(|_| AppError::InternalError(format!("Invalid {}: {}", FETCH_BUDGET_ENV, value)))?;
+            Ok((secs > 0).then(|| Duration::from_secs(secs)))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_site_name() {
+        assert_eq!(SitePriority::from_site_name("JPCERT"), SitePriority::Critical);
+        assert_eq!(SitePriority::from_site_name("IPA Security Center"), SitePriority::Critical);
+        assert_eq!(SitePriority::from_site_name("NVD CVE"), SitePriority::Critical);
+        assert_eq!(SitePriority::from_site_name("CISA Alerts"), SitePriority::Critical);
+        assert_eq!(SitePriority::from_site_name("Gigazine"), SitePriority::Standard);
+        assert!(SitePriority::Critical < SitePriority::Standard);
+        assert!(SitePriority::Standard < SitePriority::Low);
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        let overrides: PriorityOverrides = "Gigazine=low, Zenn Topic - rust = critical".parse().unwrap();
+        assert_eq!(overrides.get("Gigazine"), Some(SitePriority::Low));
+        assert_eq!(overrides.get("Zenn Topic - rust"), Some(SitePriority::Critical));
+        assert_eq!(overrides.resolve("JPCERT", SitePriority::Critical), SitePriority::Critical);
+        assert!("Gigazine=urgent".parse::<PriorityOverrides>().is_err());
+        assert!("Gigazine".parse::<PriorityOverrides>().is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! 本文抽出の品質指標．
+//!
+//! `collect_metrics` のスコープ内で `select_clean_fragments` が呼ばれると，
+//! ページ全体に対する抽出結果の指標（`ExtractionMetrics`）が記録される．
+//! サイト実装側に変更は不要で，計測しない場合のオーバーヘッドも無い．
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::future::Future;
+
+tokio::task_local! {
+    static COLLECTOR: RefCell<Vec<ExtractionMetrics>>;
+}
+
+/// 抽出テキスト比率がこれを下回るサイトはセレクタの見直し候補
+const LOW_TEXT_RATIO: f64 = 0.05;
+/// 除去された定型要素の比率がこれを上回るサイトはセレクタの見直し候補
+const HIGH_BOILERPLATE_RATIO: f64 = 0.5;
+
+/// 1記事分の抽出品質
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionMetrics {
+    /// ページ全体のテキスト文字数
+    pub page_text_chars: usize,
+    /// セレクタで選択した要素（除去前）のテキスト文字数
+    pub selected_text_chars: usize,
+    /// 除去後に残ったテキスト文字数
+    pub extracted_text_chars: usize,
+    pub page_images: usize,
+    pub retained_images: usize,
+    /// セレクタではなく Readability 風ヒューリスティック（`extract_main_content`）で抽出したか
+    #[serde(default)]
+    pub used_fallback: bool,
+}
+
+impl ExtractionMetrics {
+    /// ページ全体のテキストに対する抽出テキストの比率
+    pub fn text_ratio(&self) -> f64 {
+        ratio(self.extracted_text_chars, self.page_text_chars)
+    }
+
+    /// 選択した要素のうち，除外セレクタで除去されたテキストの比率
+    pub fn boilerplate_ratio(&self) -> f64 {
+        ratio(
+            self.selected_text_chars.saturating_sub(self.extracted_text_chars),
+            self.selected_text_chars,
+        )
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        return 0.0;
+    }
+    numerator as f64 / denominator as f64
+}
+
+/// サイト単位で集計した抽出品質
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SiteQuality {
+    pub articles: usize,
+    pub mean_text_ratio: f64,
+    pub mean_boilerplate_ratio: f64,
+    pub page_images: usize,
+    pub retained_images: usize,
+    /// ヒューリスティック抽出にフォールバックした記事数
+    #[serde(default)]
+    pub fallback_articles: usize,
+}
+
+impl SiteQuality {
+    pub fn record(&mut self, metrics: &ExtractionMetrics) {
+        let n = self.articles as f64;
+        self.mean_text_ratio = (self.mean_text_ratio * n + metrics.text_ratio()) / (n + 1.0);
+        self.mean_boilerplate_ratio =
+            (self.mean_boilerplate_ratio * n + metrics.boilerplate_ratio()) / (n + 1.0);
+        self.page_images += metrics.page_images;
+        self.retained_images += metrics.retained_images;
+        if metrics.used_fallback {
+            self.fallback_articles += 1;
+        }
+        self.articles += 1;
+    }
+
+    /// 別に集計した品質を合わせる（平均は記事数で重み付けする）
+    pub fn merge(&mut self, other: &SiteQuality) {
+        let (n, m) = (self.articles as f64, other.articles as f64);
+        if other.articles == 0 {
+            return;
+        }
+        self.mean_text_ratio = (self.mean_text_ratio * n + other.mean_text_ratio * m) / (n + m);
+        self.mean_boilerplate_ratio = (self.mean_boilerplate_ratio * n + other.mean_boilerplate_ratio * m) / (n + m);
+        self.page_images += other.page_images;
+        self.retained_images += other.retained_images;
+        self.fallback_articles += other.fallback_articles;
+        self.articles += other.articles;
+    }
+
+    /// ヒューリスティック抽出にフォールバックした記事の比率（セレクタが壊れている兆候）
+    pub fn fallback_ratio(&self) -> f64 {
+        ratio(self.fallback_articles, self.articles)
+    }
+
+    /// ページ内の画像のうち本文に残った比率
+    pub fn image_retention(&self) -> f64 {
+        ratio(self.retained_images, self.page_images)
+    }
+
+    /// 抽出量が極端に少ない，または除去量が多すぎる場合はセレクタの見直しが必要
+    pub fn needs_attention(&self) -> bool {
+        self.articles > 0
+            && (self.mean_text_ratio < LOW_TEXT_RATIO
+                || self.mean_boilerplate_ratio > HIGH_BOILERPLATE_RATIO)
+    }
+}
+
+/// `future` の実行中に記録された抽出品質を出力と一緒に返す
+pub async fn collect_metrics<F: Future>(future: F) -> (F::Output, Vec<ExtractionMetrics>) {
+    COLLECTOR
+        .scope(RefCell::new(Vec::new()), async move {
+            let output = future.await;
+            let metrics = COLLECTOR.with(|c| c.take());
+            (output, metrics)
+        })
+        .await
+}
+
+/// 計測中かどうか（計測していない場合は指標の計算自体を省く）
+pub(crate) fn is_collecting() -> bool {
+    COLLECTOR.try_with(|_| ()).is_ok()
+}
+
+pub(crate) fn record(metrics: ExtractionMetrics) {
+    let _ = COLLECTOR.try_with(|c| c.borrow_mut().push(metrics));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::web_article::select_clean_fragments;
+
+    #[test]
+    fn test_extraction_metrics_ratios() {
+        let metrics = ExtractionMetrics {
+            page_text_chars: 1000,
+            selected_text_chars: 400,
+            extracted_text_chars: 300,
+            page_images: 4,
+            retained_images: 1,
+            used_fallback: false,
+        };
+        assert!((metrics.text_ratio() - 0.3).abs() < 1e-9);
+        assert!((metrics.boilerplate_ratio() - 0.25).abs() < 1e-9);
+        assert_eq!(ExtractionMetrics::default().text_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_site_quality_aggregation() {
+        let mut quality = SiteQuality::default();
+        quality.record(&ExtractionMetrics {
+            page_text_chars: 100,
+            selected_text_chars: 50,
+            extracted_text_chars: 50,
+            page_images: 2,
+            retained_images: 2,
+            used_fallback: false,
+        });
+        quality.record(&ExtractionMetrics {
+            page_text_chars: 100,
+            selected_text_chars: 50,
+            extracted_text_chars: 10,
+            page_images: 2,
+            retained_images: 0,
+            used_fallback: true,
+        });
+        assert_eq!(quality.articles, 2);
+        assert!((quality.mean_text_ratio - 0.3).abs() < 1e-9);
+        assert!((quality.mean_boilerplate_ratio - 0.4).abs() < 1e-9);
+        assert!((quality.image_retention() - 0.5).abs() < 1e-9);
+        assert!((quality.fallback_ratio() - 0.5).abs() < 1e-9);
+        assert!(!quality.needs_attention());
+        assert!(!SiteQuality::default().needs_attention());
+    }
+
+    #[tokio::test]
+    async fn test_collect_metrics_from_select_clean() {
+        let html = r#"<html><body><nav>Menu items</nav><article><p>Main body</p><img src="a.png"><aside>Ad text</aside></article><img src="b.png"></body></html>"#;
+        let (fragments, metrics) =
+            collect_metrics(async { select_clean_fragments(html, "article", &["aside"]).unwrap() })
+                .await;
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(metrics.len(), 1);
+        let m = &metrics[0];
+        assert_eq!(m.page_images, 2);
+        assert_eq!(m.retained_images, 1);
+        assert_eq!(m.extracted_text_chars, "Main body".chars().count());
+        assert!(m.boilerplate_ratio() > 0.0);
+
+        // スコープ外では記録されない
+        assert!(!is_collecting());
+    }
+}
@@ -0,0 +1,464 @@
+use crate::models::builtin_sites;
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::fairness::SiteCategory;
+use crate::models::priority::SitePriority;
+use crate::models::request_policy::RequestPolicy;
+use crate::models::sites::generic_feed::{FeedKind, GenericFeedSite};
+use crate::models::sites::mastodon_tag::MastodonTag;
+use crate::models::sites::package_releases::{PackageRegistry, PackageReleases};
+use crate::models::sites::page_watcher::PageWatcher;
+use crate::models::web_article::WebSiteInterface;
+use crate::shared::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::{Level, event};
+
+/// 設定ファイルのパスを指定する環境変数
+pub const SITES_CONFIG_ENV: &str = "CLIPPER_SITES_CONFIG";
+
+/// サイト設定ファイル（TOML）の内容．
+///
+/// ```toml
+/// # 組み込みサイトの有効・無効（キーはサイト名）
+/// [sites."Gizmodo"]
+/// enabled = false
+///
+/// # 再コンパイルせずに追加する RSS / Atom サイト
+/// [[feeds]]
+/// name = "Example Blog"
+/// url = "https://example.com/feed.xml"
+/// feed = "atom"                      # rss1 | rss2 | atom（デフォルト rss2）
+/// content_selector = "article .body" # 省略時は Readability 風の抽出
+/// # セレクタ以外の抽出方法（css_selector | readability | regex_block | json_ld，セレクタの後に優先順に試す）
+/// extraction = [{ type = "json_ld" }, { type = "regex_block", start = "<!-- body -->", end = "<!-- /body -->" }]
+/// exclude_selectors = [".promo"]
+/// exempt_selectors = ["aside"]
+/// category = "security"              # 省略時はサイト名から推定
+/// priority = "critical"              # critical | standard | low（省略時はサイト名から推定）
+/// allowed_domains = ["example.org"]  # フィードのドメイン以外にある記事を取得する場合
+/// # User-Agent や言語でマークアップが変わるサイト向けのリクエスト設定（省略時は共有クライアントの設定）
+/// request = { timeout_secs = 20, user_agent = "Mozilla/5.0", accept_language = "ja" }
+/// sitemap = "https://example.com/sitemap.xml" # フィードを取得・解析できない場合に使うサイトマップ
+///
+/// # 内容が変化したときだけ差分を記事にする監視ページ
+/// [[watchers]]
+/// name = "Vendor Security Bulletins"
+/// url = "https://vendor.example.com/security"
+/// selector = "main ul.bulletins"
+///
+/// # 新しいリリースを記事にする crates.io / PyPI のパッケージ（サイト名は「crates.io - tokio」など）
+/// [[packages]]
+/// registry = "crates"                # crates | pypi
+/// name = "tokio"
+/// repository = "tokio-rs/tokio"      # リリースノートの GitHub リポジトリ（省略時はメタデータから推定）
+///
+/// # リンクを含む投稿をリンク先の記事にする Mastodon のハッシュタグ（サイト名は「Mastodon - #rust」など）
+/// [[mastodon]]
+/// instance = "mastodon.social"
+/// hashtags = ["rust", "llm"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryConfig {
+    /// `false` の場合，組み込みサイトを全て無効にし `feeds`，`watchers`，`packages`，`mastodon` のみを使う
+    #[serde(default = "default_true")]
+    pub builtin: bool,
+    #[serde(default)]
+    pub sites: HashMap<String, SiteOptions>,
+    #[serde(default)]
+    pub feeds: Vec<FeedSiteConfig>,
+    #[serde(default)]
+    pub watchers: Vec<WatcherConfig>,
+    #[serde(default)]
+    pub packages: Vec<PackageConfig>,
+    #[serde(default)]
+    pub mastodon: Vec<MastodonConfig>,
+}
+
+/// 組み込みサイトごとの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteOptions {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 設定ファイルから追加するフィードサイト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeedSiteConfig {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub feed: FeedKind,
+    #[serde(default)]
+    pub content_selector: Option<String>,
+    /// 本文セレクタを優先順に複数指定する場合（`content_selector` より後に試す）
+    #[serde(default)]
+    pub content_selectors: Vec<String>,
+    /// 本文の抽出方法（`content_selector(s)` の後に優先順に試す）
+    #[serde(default)]
+    pub extraction: Vec<ExtractionStrategy>,
+    #[serde(default)]
+    pub exclude_selectors: Vec<String>,
+    #[serde(default)]
+    pub exempt_selectors: Vec<String>,
+    #[serde(default)]
+    pub category: Option<SiteCategory>,
+    #[serde(default)]
+    pub priority: Option<SitePriority>,
+    /// フィードのドメインに加えて記事の取得を許可するドメイン
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// タイムアウト・User-Agent・Accept-Language・追加ヘッダー
+    #[serde(default)]
+    pub request: RequestPolicy,
+    /// フィードを取得・解析できない場合に記事一覧を組み立てるサイトマップ
+    #[serde(default)]
+    pub sitemap: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 設定ファイルから追加する監視ページ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatcherConfig {
+    pub name: String,
+    pub url: String,
+    /// 監視する領域の CSS セレクタ
+    pub selector: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 設定ファイルから追加するリリースを監視するパッケージ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackageConfig {
+    pub registry: PackageRegistry,
+    pub name: String,
+    /// リリースノートを取得する GitHub のリポジトリ（`<owner>/<repo>`）
+    #[serde(default)]
+    pub repository: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl PackageConfig {
+    fn build(&self) -> PackageReleases {
+        PackageReleases::new(self.registry, &self.name).with_repository(self.repository.clone())
+    }
+}
+
+/// 設定ファイルから追加する Mastodon のハッシュタグのタイムライン（ハッシュタグごとに1サイト）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MastodonConfig {
+    /// インスタンスのドメイン（`mastodon.social` など）
+    pub instance: String,
+    /// `#` は不要
+    pub hashtags: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl MastodonConfig {
+    fn build(&self) -> AppResult<Vec<MastodonTag>> {
+        self.hashtags.iter().map(|hashtag| MastodonTag::new(&self.instance, hashtag)).collect()
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl FeedSiteConfig {
+    fn build(&self) -> AppResult<GenericFeedSite> {
+        let selectors: Vec<String> = self
+            .content_selector
+            .iter()
+            .chain(self.content_selectors.iter())
+            .cloned()
+            .collect();
+        let mut extraction = Vec::new();
+        if !selectors.is_empty() {
+            extraction.push(ExtractionStrategy::CssSelector { selectors });
+        }
+        extraction.extend(self.extraction.iter().cloned());
+        if extraction.is_empty() {
+            extraction.push(ExtractionStrategy::Readability);
+        }
+        // 不正なヘッダー・プロキシは取得時ではなく設定の読み込み時にエラーにする
+        self.request.header_map()?;
+        self.request.proxy_route()?;
+        let site = GenericFeedSite::new(&self.name, &self.url, self.feed)?
+            .with_extraction(extraction)
+            .with_exclude_selectors(self.exclude_selectors.clone())
+            .with_exempt_selectors(self.exempt_selectors.clone())
+            .with_allowed_domains(self.allowed_domains.clone())
+            .with_request_policy(self.request.clone());
+        let site = match &self.sitemap {
+            Some(sitemap) => site.with_sitemap(sitemap)?,
+            None => site,
+        };
+        let site = match self.category {
+            Some(category) => site.with_category(category),
+            None => site,
+        };
+        Ok(match self.priority {
+            Some(priority) => site.with_priority(priority),
+            None => site,
+        })
+    }
+}
+
+/// 実行時の設定で有効なサイトを決定するレジストリ．
+/// 設定ファイルが無い場合は組み込みサイトを全て有効にする．
+#[derive(Debug, Clone, Default)]
+pub struct SiteRegistry {
+    config: RegistryConfig,
+}
+
+impl SiteRegistry {
+    pub fn new(config: RegistryConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn from_toml_str(content: &str) -> AppResult<Self> {
+        let config: RegistryConfig = toml::from_str(content)
+            .map_err(|e| AppError::InternalError(format!("Invalid sites config: {}", e)))?;
+        Ok(Self::new(config))
+    }
+
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            AppError::InternalError(format!("Failed to read sites config {:?}: {}", path, e))
+        })?;
+        Self::from_toml_str(&content)
+    }
+
+    /// `CLIPPER_SITES_CONFIG` の設定ファイルを読み込む．未設定の場合はデフォルト（組み込みサイト全て）．
+    pub fn load_default() -> AppResult<Self> {
+        match std::env::var(SITES_CONFIG_ENV) {
+            Ok(path) if !path.is_empty() => Self::load(Path::new(&path)),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn config(&self) -> &RegistryConfig {
+        &self.config
+    }
+
+    /// 組み込みサイトが有効かどうか（設定に記載が無ければ有効）
+    pub fn is_enabled(&self, site_name: &str) -> bool {
+        if !self.config.builtin && !self.config.sites.contains_key(site_name) {
+            return false;
+        }
+        self.config
+            .sites
+            .get(site_name)
+            .map(|options| options.enabled)
+            .unwrap_or(true)
+    }
+
+    /// 有効な組み込みサイトと設定ファイルのフィードサイトを構築する
+    pub fn build_sites(&self) -> AppResult<Vec<Box<dyn WebSiteInterface>>> {
+        let builtin = builtin_sites();
+        let builtin_names: HashSet<String> = builtin.iter().map(|s| s.site_name()).collect();
+        for name in self.config.sites.keys() {
+            if !builtin_names.contains(name) {
+                event!(Level::WARN, "Unknown site in sites config: {}", name);
+            }
+        }
+
+        let mut sites: Vec<Box<dyn WebSiteInterface>> = builtin
+            .into_iter()
+            .filter(|site| self.is_enabled(&site.site_name()))
+            .collect();
+
+        let mut names: HashSet<String> = sites.iter().map(|s| s.site_name()).collect();
+        for feed in self.config.feeds.iter().filter(|f| f.enabled) {
+            if !names.insert(feed.name.clone()) {
+                return Err(AppError::InternalError(format!(
+                    "Duplicate site name in sites config: {}",
+                    feed.name
+                )));
+            }
+            sites.push(Box::new(feed.build()?));
+        }
+        for watcher in self.config.watchers.iter().filter(|w| w.enabled) {
+            if !names.insert(watcher.name.clone()) {
+                return Err(AppError::InternalError(format!(
+                    "Duplicate site name in sites config: {}",
+                    watcher.name
+                )));
+            }
+            sites.push(Box::new(PageWatcher::new(
+                &watcher.name,
+                &watcher.url,
+                &watcher.selector,
+            )?));
+        }
+        for package in self.config.packages.iter().filter(|p| p.enabled) {
+            let site = package.build();
+            if !names.insert(site.site_name()) {
+                return Err(AppError::InternalError(format!(
+                    "Duplicate site name in sites config: {}",
+                    site.site_name()
+                )));
+            }
+            sites.push(Box::new(site));
+        }
+        for mastodon in self.config.mastodon.iter().filter(|m| m.enabled) {
+            for site in mastodon.build()? {
+                if !names.insert(site.site_name()) {
+                    return Err(AppError::InternalError(format!(
+                        "Duplicate site name in sites config: {}",
+                        site.site_name()
+                    )));
+                }
+                sites.push(Box::new(site));
+            }
+        }
+        Ok(sites)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r##"
+[sites."Gizmodo"]
+enabled = false
+
+[[feeds]]
+name = "Example Blog"
+url = "https://example.com/feed.xml"
+feed = "atom"
+content_selector = "article .body"
+extraction = [{ type = "json_ld" }]
+exclude_selectors = [".promo"]
+priority = "low"
+
+[feeds.request]
+timeout_secs = 20
+accept_language = "ja"
+
+[[feeds]]
+name = "Disabled Feed"
+url = "https://disabled.example.com/rss"
+enabled = false
+
+[[watchers]]
+name = "Vendor Bulletins"
+url = "https://vendor.example.com/security"
+selector = "main ul.bulletins"
+
+[[packages]]
+registry = "crates"
+name = "tokio"
+
+[[packages]]
+registry = "pypi"
+name = "openai"
+repository = "openai/openai-python"
+
+[[mastodon]]
+instance = "mastodon.social"
+hashtags = ["rust", "#llm"]
+"##;
+
+    #[test]
+    fn test_registry_default_enables_all_builtin_sites() {
+        let registry = SiteRegistry::default();
+        let sites = registry.build_sites().unwrap();
+        assert_eq!(sites.len(), builtin_sites().len());
+    }
+
+    #[test]
+    fn test_registry_from_toml() {
+        let registry = SiteRegistry::from_toml_str(CONFIG).unwrap();
+        assert!(!registry.is_enabled("Gizmodo"));
+        assert!(registry.is_enabled("Gigazine"));
+
+        let names: Vec<String> = registry.build_sites().unwrap().iter().map(|s| s.site_name()).collect();
+        assert!(!names.contains(&"Gizmodo".to_string()));
+        assert!(names.contains(&"Example Blog".to_string()));
+        assert!(!names.contains(&"Disabled Feed".to_string()));
+        assert!(names.contains(&"Vendor Bulletins".to_string()));
+        assert!(names.contains(&"crates.io - tokio".to_string()));
+        assert!(names.contains(&"PyPI - openai".to_string()));
+        assert!(names.contains(&"Mastodon - #rust".to_string()));
+        assert!(names.contains(&"Mastodon - #llm".to_string()));
+        assert_eq!(registry.config().packages[1].build().repository.as_deref(), Some("openai/openai-python"));
+        assert_eq!(registry.config().feeds[0].feed, FeedKind::Atom);
+        assert_eq!(registry.config().feeds[1].feed, FeedKind::Rss2);
+        assert_eq!(registry.config().feeds[0].priority, Some(SitePriority::Low));
+        let policy = registry.config().feeds[0].build().unwrap().request_policy();
+        assert_eq!(policy.timeout_secs, Some(20));
+        assert_eq!(policy.accept_language.as_deref(), Some("ja"));
+        assert_eq!(registry.config().feeds[1].build().unwrap().request_policy(), RequestPolicy::default());
+
+        let extraction: Vec<String> = registry.config().feeds[0]
+            .build()
+            .unwrap()
+            .extraction_strategies()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(extraction, vec!["css_selector(article .body)", "json_ld"]);
+        let readability = registry.config().feeds[1].build().unwrap().extraction_strategies();
+        assert!(matches!(readability.as_slice(), [ExtractionStrategy::Readability]));
+    }
+
+    #[test]
+    fn test_registry_feeds_only() {
+        let registry = SiteRegistry::from_toml_str(
+            r#"
+builtin = false
+
+[[feeds]]
+name = "Only Feed"
+url = "https://only.example.com/rss"
+"#,
+        )
+        .unwrap();
+        let sites = registry.build_sites().unwrap();
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].site_name(), "Only Feed");
+    }
+
+    #[test]
+    fn test_registry_rejects_invalid_config() {
+        assert!(SiteRegistry::from_toml_str("[[feeds]]\nname = \"No URL\"").is_err());
+        assert!(SiteRegistry::from_toml_str("unknown_key = 1").is_err());
+
+        let duplicate = SiteRegistry::from_toml_str(
+            "[[feeds]]\nname = \"Gizmodo\"\nurl = \"https://example.com/rss\"",
+        )
+        .unwrap();
+        assert!(duplicate.build_sites().is_err());
+
+        let invalid_header = SiteRegistry::from_toml_str(
+            "[[feeds]]\nname = \"Bad\"\nurl = \"https://example.com/rss\"\n\
+             request = { headers = { \"Bad Name\" = \"x\" } }",
+        )
+        .unwrap();
+        assert!(invalid_header.build_sites().is_err());
+
+        assert!(SiteRegistry::from_toml_str("[[packages]]\nregistry = \"npm\"\nname = \"react\"").is_err());
+        let duplicate_package = SiteRegistry::from_toml_str(
+            "[[packages]]\nregistry = \"crates\"\nname = \"serde\"\n\n\
+             [[packages]]\nregistry = \"crates\"\nname = \"serde\"",
+        )
+        .unwrap();
+        assert!(duplicate_package.build_sites().is_err());
+
+        let invalid_instance =
+            SiteRegistry::from_toml_str("[[mastodon]]\ninstance = \"\"\nhashtags = [\"rust\"]").unwrap();
+        assert!(invalid_instance.build_sites().is_err());
+    }
+}
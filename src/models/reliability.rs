@@ -0,0 +1,293 @@
+//! 巡回履歴からサイトごとの信頼性（成功率，応答時間，ブロック率，フォールバック率）を集計する週次レポート．
+
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::sink::ReportSink;
+use crate::shared::template::{load_template, render_template};
+use crate::storage::{FetchHistoryStore, FetchRun};
+use chrono::{DateTime, Duration, Local, SecondsFormat};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// 成功率がこれを下回るサイトは要対応
+const MIN_SUCCESS_RATE: f64 = 0.8;
+/// ブロック率がこれを上回るサイトは要対応
+const MAX_BLOCKED_RATIO: f64 = 0.2;
+/// フォールバック率がこれを上回るサイトは要対応（セレクタが壊れている可能性）
+const MAX_FALLBACK_RATIO: f64 = 0.5;
+
+/// 組み込みのレポートテンプレート（`CLIPPER_RELIABILITY_TEMPLATE` で差し替え可能）
+pub const DEFAULT_RELIABILITY_TEMPLATE: &str = r#"# Site Reliability Report
+{{ period_start }} - {{ period_end }} ({{ runs }} runs)
+
+{% if attention %}
+## Needs Attention
+{% for site in attention %}
+- **{{ site.site }}**: {{ site.reasons }}
+{% endfor %}
+
+{% endif %}
+## All Sites
+| Site | Runs | Success | Avg Latency | Blocked | Fallback |
+|---|---|---|---|---|---|
+{% for site in sites %}
+| {{ site.site }} | {{ site.runs }} | {{ site.success_rate }} | {{ site.mean_latency_ms }} ms | {{ site.blocked_ratio }} | {{ site.fallback_ratio }} |
+{% endfor %}
+"#;
+
+/// サイトごとの集計結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SiteReliability {
+    pub site: String,
+    pub runs: usize,
+    pub successful_runs: usize,
+    pub total_latency_ms: u128,
+    /// ログイン・ペイウォール・アクセス拒否の回数
+    pub blocked: usize,
+    /// 取得を試みた回数（記事一覧の取得と本文のパース）
+    pub attempts: usize,
+    pub extracted_articles: usize,
+    pub fallback_articles: usize,
+}
+
+impl SiteReliability {
+    pub fn success_rate(&self) -> f64 {
+        ratio(self.successful_runs, self.runs)
+    }
+
+    pub fn mean_latency_ms(&self) -> u128 {
+        self.total_latency_ms.checked_div(self.runs as u128).unwrap_or(0)
+    }
+
+    pub fn blocked_ratio(&self) -> f64 {
+        ratio(self.blocked, self.attempts)
+    }
+
+    pub fn fallback_ratio(&self) -> f64 {
+        ratio(self.fallback_articles, self.extracted_articles)
+    }
+
+    /// 要対応と判断した理由（問題が無ければ空）
+    pub fn attention_reasons(&self) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if self.runs > 0 && self.success_rate() < MIN_SUCCESS_RATE {
+            reasons.push(format!("success rate {}", percent(self.success_rate())));
+        }
+        if self.blocked_ratio() > MAX_BLOCKED_RATIO {
+            reasons.push(format!("blocked {}", percent(self.blocked_ratio())));
+        }
+        if self.fallback_ratio() > MAX_FALLBACK_RATIO {
+            reasons.push(format!("selector fallback {}", percent(self.fallback_ratio())));
+        }
+        reasons
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        return 0.0;
+    }
+    numerator as f64 / denominator as f64
+}
+
+fn percent(value: f64) -> String {
+    format!("{:.1}%", value * 100.0)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReliabilityReport {
+    pub period_start: DateTime<Local>,
+    pub period_end: DateTime<Local>,
+    /// 期間中の巡回回数
+    pub runs: usize,
+    /// サイト名順
+    pub sites: Vec<SiteReliability>,
+}
+
+/// テンプレートに渡す1行分の値（比率は整形済み）
+#[derive(Debug, Serialize)]
+struct SiteRow<'a> {
+    site: &'a str,
+    runs: usize,
+    success_rate: String,
+    mean_latency_ms: u128,
+    blocked_ratio: String,
+    fallback_ratio: String,
+    reasons: String,
+}
+
+impl ReliabilityReport {
+    pub fn aggregate(period_start: DateTime<Local>, period_end: DateTime<Local>, runs: &[FetchRun]) -> Self {
+        let mut sites: BTreeMap<&str, SiteReliability> = BTreeMap::new();
+        let mut run_times: Vec<DateTime<Local>> = Vec::new();
+        for run in runs {
+            if !run_times.contains(&run.run_at) {
+                run_times.push(run.run_at);
+            }
+            let report = &run.report;
+            let site = sites.entry(report.site.as_str()).or_insert_with(|| SiteReliability {
+                site: report.site.clone(),
+                ..Default::default()
+            });
+            site.runs += 1;
+            if report.is_ok() {
+                site.successful_runs += 1;
+            }
+            site.total_latency_ms += report.duration.as_millis();
+            site.blocked += report.blocked;
+            site.attempts += (report.parsed + report.errors.len()).max(1);
+            site.extracted_articles += report.quality.articles;
+            site.fallback_articles += report.quality.fallback_articles;
+        }
+        Self {
+            period_start,
+            period_end,
+            runs: run_times.len(),
+            sites: sites.into_values().collect(),
+        }
+    }
+
+    pub fn needs_attention(&self) -> impl Iterator<Item = &SiteReliability> {
+        self.sites.iter().filter(|s| !s.attention_reasons().is_empty())
+    }
+
+    pub fn render(&self, template: &str) -> AppResult<String> {
+        let row = |s: &'_ SiteReliability| SiteRow {
+            site: &s.site,
+            runs: s.runs,
+            success_rate: percent(s.success_rate()),
+            mean_latency_ms: s.mean_latency_ms(),
+            blocked_ratio: percent(s.blocked_ratio()),
+            fallback_ratio: percent(s.fallback_ratio()),
+            reasons: s.attention_reasons().join(", "),
+        };
+        // 要対応のサイトは成功率の低い順
+        let mut attention: Vec<&SiteReliability> = self.needs_attention().collect();
+        attention.sort_by(|a, b| a.success_rate().total_cmp(&b.success_rate()));
+        render_template(
+            template,
+            serde_json::json!({
+                "period_start": self.period_start.format("%Y-%m-%d").to_string(),
+                "period_end": self.period_end.format("%Y-%m-%d").to_string(),
+                "runs": self.runs,
+                "attention": attention.into_iter().map(row).collect::<Vec<_>>(),
+                "sites": self.sites.iter().map(row).collect::<Vec<_>>(),
+            }),
+        )
+    }
+}
+
+/// 前回の送信から1週間以上経っていれば直近1週間のレポートを作成して配信する．
+/// 送信時刻は `marker` に記録し，初回は記録のみ行う．配信した場合は `true` を返す．
+pub async fn send_weekly_report_if_due(
+    history: &dyn FetchHistoryStore,
+    sink: &ReportSink,
+    marker: &Path,
+    now: DateTime<Local>,
+) -> AppResult<bool> {
+    let last_sent = std::fs::read_to_string(marker)
+        .ok()
+        .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())
+        .map(|t| t.with_timezone(&Local));
+    let Some(last_sent) = last_sent else {
+        write_marker(marker, now)?;
+        return Ok(false);
+    };
+    if now - last_sent < Duration::weeks(1) {
+        return Ok(false);
+    }
+
+    let since = now - Duration::weeks(1);
+    let runs = history.runs_between(since, now).await?;
+    let report = ReliabilityReport::aggregate(since, now, &runs);
+    let template = load_template("CLIPPER_RELIABILITY_TEMPLATE", DEFAULT_RELIABILITY_TEMPLATE)?;
+    let rendered = report.render(&template)?;
+    sink.deliver(&format!("reliability-{}", now.format("%Y-%m-%d")), &rendered).await?;
+    write_marker(marker, now)?;
+    Ok(true)
+}
+
+fn write_marker(marker: &Path, now: DateTime<Local>) -> AppResult<()> {
+    if let Some(parent) = marker.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", parent, e)))?;
+    }
+    std::fs::write(marker, now.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", marker, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::report::FetchReport;
+    use crate::shared::errors::AppError;
+    use crate::storage::sqlite::SqliteStore;
+    use chrono::TimeZone;
+
+    fn run(day: u32, site: &str, ok: bool, millis: u64, blocked: bool) -> FetchRun {
+        let mut report = FetchReport::new(site);
+        report.duration = std::time::Duration::from_millis(millis);
+        if blocked {
+            report.record_error(&AppError::LoginRequired);
+        } else if !ok {
+            report.record_error(&AppError::ScrapeError("selector not found".into()));
+        }
+        FetchRun {
+            run_at: Local.with_ymd_and_hms(2025, 10, day, 9, 0, 0).unwrap(),
+            report,
+        }
+    }
+
+    #[test]
+    fn test_reliability_report_aggregate_and_render() {
+        let runs = vec![
+            run(1, "Stable", true, 100, false),
+            run(1, "Flaky", false, 300, false),
+            run(2, "Stable", true, 300, false),
+            run(2, "Flaky", true, 100, false),
+            run(2, "Paywalled", false, 50, true),
+        ];
+        let start = Local.with_ymd_and_hms(2025, 10, 1, 0, 0, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2025, 10, 8, 0, 0, 0).unwrap();
+        let report = ReliabilityReport::aggregate(start, end, &runs);
+
+        assert_eq!(report.runs, 2);
+        let names: Vec<_> = report.sites.iter().map(|s| s.site.as_str()).collect();
+        assert_eq!(names, vec!["Flaky", "Paywalled", "Stable"]);
+        let stable = &report.sites[2];
+        assert_eq!(stable.success_rate(), 1.0);
+        assert_eq!(stable.mean_latency_ms(), 200);
+        assert!(stable.attention_reasons().is_empty());
+        assert_eq!(report.sites[1].blocked_ratio(), 1.0);
+
+        let attention: Vec<_> = report.needs_attention().map(|s| s.site.as_str()).collect();
+        assert_eq!(attention, vec!["Flaky", "Paywalled"]);
+
+        let rendered = report.render(DEFAULT_RELIABILITY_TEMPLATE).unwrap();
+        assert!(rendered.contains("2025-10-01 - 2025-10-08 (2 runs)"));
+        assert!(rendered.contains("- **Paywalled**: success rate 0.0%, blocked 100.0%"));
+        assert!(rendered.contains("| Stable | 2 | 100.0% | 200 ms | 0.0% | 0.0% |"));
+    }
+
+    #[tokio::test]
+    async fn test_send_weekly_report_if_due() {
+        let dir = std::env::temp_dir().join(format!("clipper-reliability-{}", uuid::Uuid::new_v4()));
+        let marker = dir.join("last-reliability-report");
+        let sink = ReportSink::Directory(dir.clone());
+        let store = SqliteStore::open_in_memory().unwrap();
+        let start = Local.with_ymd_and_hms(2025, 10, 1, 0, 0, 0).unwrap();
+        store.record_run(start + Duration::days(1), &[FetchReport::new("Stable")]).await.unwrap();
+
+        // 初回は送信時刻の記録のみ，1週間経つまでは送らない
+        assert!(!send_weekly_report_if_due(&store, &sink, &marker, start).await.unwrap());
+        assert!(!send_weekly_report_if_due(&store, &sink, &marker, start + Duration::days(3)).await.unwrap());
+
+        let now = start + Duration::weeks(1);
+        assert!(send_weekly_report_if_due(&store, &sink, &marker, now).await.unwrap());
+        let rendered = std::fs::read_to_string(dir.join("reliability-2025-10-08.md")).unwrap();
+        assert!(rendered.contains("| Stable | 1 | 100.0% |"));
+        assert!(!send_weekly_report_if_due(&store, &sink, &marker, now).await.unwrap());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
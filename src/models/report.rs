@@ -0,0 +1,413 @@
+use crate::models::quality::SiteQuality;
+use crate::models::web_article::WebArticle;
+use crate::shared::errors::AppError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use strum::Display;
+use std::time::Duration;
+
+/// サイトごとの取得結果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FetchReport {
+    pub site: String,
+    /// フィード・一覧ページから取得した記事数
+    pub fetched: usize,
+    /// 本文のパースに成功した記事数
+    pub parsed: usize,
+    pub skipped: usize,
+    /// ログイン・ペイウォール・アクセス拒否で取得できなかった回数
+    #[serde(default)]
+    pub blocked: usize,
+    pub errors: Vec<SiteError>,
+    /// 巡回の時間予算を使い切ったため取得しなかった（または打ち切った）
+    #[serde(default)]
+    pub budget_exhausted: bool,
+    pub duration: Duration,
+    pub quality: SiteQuality,
+    /// 取得した記事の内容の傾向（異常検知に使う）
+    #[serde(default)]
+    pub content: ContentStats,
+    /// パースした本文の長さ（セレクタの破損の検知に使う）
+    #[serde(default)]
+    pub text: TextStats,
+    /// LLM・ストア・通知先が使えなかったため代替の処理に切り替えたもの
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub degraded: Vec<Degradation>,
+}
+
+/// 任意のサブシステムが使えなかったときに切り替えた代替の処理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Degradation {
+    /// LLM に接続できなかったため，キーワード分類と抽出型の要約で代用した
+    ExtractiveSummary,
+    /// ストアに保存できなかったため，記事をメモリに保持して次の巡回で保存し直す
+    BufferedInMemory,
+    /// 通知先に送れなかったため，アウトボックスに溜めて次の巡回で送り直す
+    QueuedInOutbox,
+}
+
+/// エラーの種類（再試行・アラートの判断に使う）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FailureKind {
+    /// ログイン・ペイウォール・アクセス拒否（401/402/403/451）
+    Blocked,
+    /// 接続の失敗・タイムアウト・その他の HTTP エラー
+    Request,
+    /// フィード・JSON・HTML・日時の解析の失敗
+    Parse,
+    /// 本文のセレクタに一致しない・本文が空（ページの構造の変化の可能性が高い）
+    Selector,
+    /// 許可されていないドメインの URL
+    Disallowed,
+    /// 上記以外（種類を記録していない古い履歴を含む）
+    #[default]
+    Other,
+}
+
+impl FailureKind {
+    pub fn of(error: &AppError) -> Self {
+        if is_blocked(error) {
+            return Self::Blocked;
+        }
+        match error {
+            AppError::RequestError { .. } | AppError::ParseError(_) => Self::Request,
+            AppError::SelectorNotFound { .. } | AppError::EmptyContent(_) => Self::Selector,
+            AppError::RssParseError(_)
+            | AppError::JsonParseError(_)
+            | AppError::DateTimeParseError(_)
+            | AppError::FeedParse { .. }
+            | AppError::DateParse(_)
+            | AppError::ScrapeError(_) => Self::Parse,
+            AppError::DisallowedDomain(_) | AppError::BlockedUrl(_) => Self::Disallowed,
+            _ => Self::Other,
+        }
+    }
+
+    /// 時間をおけば成功する可能性がある（ブロック・解析の失敗は再試行しても変わらない）
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Request)
+    }
+}
+
+/// サイトの取得・パースで起きたエラー
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "SiteErrorRepr")]
+pub struct SiteError {
+    pub kind: FailureKind,
+    pub message: String,
+}
+
+/// 履歴にはエラーを文字列で保存していたため，どちらの形式も読めるようにする
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SiteErrorRepr {
+    Message(String),
+    Structured {
+        #[serde(default)]
+        kind: FailureKind,
+        message: String,
+    },
+}
+
+impl From<SiteErrorRepr> for SiteError {
+    fn from(repr: SiteErrorRepr) -> Self {
+        match repr {
+            SiteErrorRepr::Message(message) => Self {
+                kind: FailureKind::Other,
+                message,
+            },
+            SiteErrorRepr::Structured { kind, message } => Self { kind, message },
+        }
+    }
+}
+
+impl SiteError {
+    pub fn new(error: &AppError) -> Self {
+        Self {
+            kind: FailureKind::of(error),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SiteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// 1回の取得で得た記事の内容の統計
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContentStats {
+    /// タイトルと概要の文字（英数字・かな・漢字）のうち日本語の文字の比率
+    pub japanese_ratio: f64,
+    /// 概要の平均文字数
+    pub mean_description_chars: f64,
+}
+
+impl ContentStats {
+    pub fn from_articles(articles: &[WebArticle]) -> Self {
+        if articles.is_empty() {
+            return Self::default();
+        }
+        let (mut japanese, mut letters) = (0usize, 0usize);
+        for c in articles
+            .iter()
+            .flat_map(|a| a.title.chars().chain(a.description.chars()))
+            .filter(|c| c.is_alphanumeric())
+        {
+            letters += 1;
+            if is_japanese(c) {
+                japanese += 1;
+            }
+        }
+        let description_chars: usize = articles.iter().map(|a| a.description.trim().chars().count()).sum();
+        Self {
+            japanese_ratio: if letters == 0 { 0.0 } else { japanese as f64 / letters as f64 },
+            mean_description_chars: description_chars as f64 / articles.len() as f64,
+        }
+    }
+}
+
+/// 本文が取得できなかったときにサイトが返すプレースホルダー
+const EMPTY_BODY_PLACEHOLDERS: &[&str] = &["NO HTML", "NO TEXT"];
+
+/// パースした本文の統計
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextStats {
+    /// パースに成功した記事数（本文が空のものを含む）
+    pub parsed: usize,
+    /// 本文が空（またはプレースホルダー）だった記事数
+    pub empty: usize,
+    /// 空でない本文の合計文字数
+    pub total_chars: usize,
+}
+
+impl TextStats {
+    pub fn record(&mut self, text: &str) {
+        self.parsed += 1;
+        let text = text.trim();
+        if text.is_empty() || EMPTY_BODY_PLACEHOLDERS.contains(&text) {
+            self.empty += 1;
+        } else {
+            self.total_chars += text.chars().count();
+        }
+    }
+
+    pub fn merge(&mut self, other: &TextStats) {
+        self.parsed += other.parsed;
+        self.empty += other.empty;
+        self.total_chars += other.total_chars;
+    }
+
+    /// 空でない本文の平均文字数（本文が1件も無ければ `None`）
+    pub fn mean_chars(&self) -> Option<f64> {
+        let non_empty = self.parsed - self.empty;
+        (non_empty > 0).then(|| self.total_chars as f64 / non_empty as f64)
+    }
+
+    pub fn empty_ratio(&self) -> f64 {
+        if self.parsed == 0 {
+            return 0.0;
+        }
+        self.empty as f64 / self.parsed as f64
+    }
+}
+
+/// ひらがな・カタカナ・CJK 統合漢字
+fn is_japanese(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{4E00}'..='\u{9FFF}' | '\u{FF66}'..='\u{FF9F}')
+}
+
+impl FetchReport {
+    pub fn new(site: impl Into<String>) -> Self {
+        Self {
+            site: site.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// `kind` のエラーがあったか
+    pub fn has_failure(&self, kind: FailureKind) -> bool {
+        self.errors.iter().any(|e| e.kind == kind)
+    }
+
+    /// 代替の処理に切り替えたことを記録する（同じものは1回だけ）
+    pub fn record_degradation(&mut self, degradation: Degradation) {
+        if !self.degraded.contains(&degradation) {
+            self.degraded.push(degradation);
+            self.degraded.sort();
+        }
+    }
+
+    pub fn record_error(&mut self, error: &AppError) {
+        if is_blocked(error) {
+            self.blocked += 1;
+        }
+        self.errors.push(SiteError::new(error));
+    }
+}
+
+/// `articles` のサイトの取得結果に代替の処理に切り替えたことを記録する
+pub fn mark_degraded<'a>(
+    reports: &mut [FetchReport],
+    articles: impl IntoIterator<Item = &'a WebArticle>,
+    degradation: Degradation,
+) {
+    for article in articles {
+        if let Some(report) = reports.iter_mut().find(|r| r.site == article.site.name) {
+            report.record_degradation(degradation);
+        }
+    }
+}
+
+/// 会員限定・ペイウォール・アクセス拒否（401/402/403/451）によるエラーか
+pub fn is_blocked(error: &AppError) -> bool {
+    match error {
+        AppError::LoginRequired | AppError::PaywallError { .. } => true,
+        AppError::RequestError { source, .. } => source
+            .status()
+            .is_some_and(|status| matches!(status.as_u16(), 401 | 402 | 403 | 451)),
+        _ => false,
+    }
+}
+
+/// 抽出品質の低い順にサイトを並べたテキストのレポートを作成する
+pub fn render_quality_report(reports: &[FetchReport]) -> String {
+    let mut rows: Vec<&FetchReport> = reports.iter().filter(|r| r.quality.articles > 0).collect();
+    rows.sort_by(|a, b| a.quality.mean_text_ratio.total_cmp(&b.quality.mean_text_ratio));
+
+    let mut out = String::from("site\tarticles\ttext_ratio\tboilerplate_ratio\timage_retention\tstatus\n");
+    for report in rows {
+        let q = &report.quality;
+        out.push_str(&format!(
+            "{}\t{}\t{:.3}\t{:.3}\t{:.3}\t{}\n",
+            report.site,
+            q.articles,
+            q.mean_text_ratio,
+            q.mean_boilerplate_ratio,
+            q.image_retention(),
+            if q.needs_attention() { "CHECK" } else { "OK" }
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::quality::ExtractionMetrics;
+    use crate::testing::article::ArticleBuilder;
+
+    #[test]
+    fn test_fetch_report_errors_and_quality() {
+        let mut report = FetchReport::new("Example");
+        let metrics = ExtractionMetrics {
+            page_text_chars: 100,
+            selected_text_chars: 10,
+            extracted_text_chars: 2,
+            ..Default::default()
+        };
+        report.quality.record(&metrics);
+        report.record_error(&AppError::ScrapeError("missing".into()));
+        report.record_error(&AppError::LoginRequired);
+
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(report.errors[0].kind, FailureKind::Parse);
+        assert_eq!(report.errors[0].to_string(), "Scrape Error: missing");
+        assert!(report.has_failure(FailureKind::Blocked));
+        assert!(!report.has_failure(FailureKind::Request));
+        assert_eq!(report.blocked, 1);
+        assert!(!report.is_ok());
+        assert!(report.quality.needs_attention());
+
+        let rendered = render_quality_report(&[report]);
+        assert!(rendered.lines().nth(1).unwrap().starts_with("Example\t1\t0.020"));
+        assert!(rendered.contains("CHECK"));
+    }
+
+    #[test]
+    fn test_content_stats_from_articles() {
+        let article = |title: &str, description: &str| {
+            ArticleBuilder::new(title).with_url("https://example.com/1").with_description(description).build()
+        };
+        let stats = ContentStats::from_articles(&[article("日本語", "abcd"), article("abc", "")]);
+        assert!((stats.japanese_ratio - 0.3).abs() < 1e-9);
+        assert!((stats.mean_description_chars - 2.0).abs() < 1e-9);
+        assert_eq!(ContentStats::from_articles(&[]), ContentStats::default());
+    }
+
+    #[test]
+    fn test_site_error_deserializes_legacy_messages() {
+        let legacy: SiteError = serde_json::from_str(r#""timeout""#).unwrap();
+        assert_eq!(legacy.kind, FailureKind::Other);
+        assert_eq!(legacy.message, "timeout");
+
+        let error = SiteError::new(&AppError::DisallowedDomain("evil.example.com".into()));
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(json, r#"{"kind":"disallowed","message":"Disallowed Domain: evil.example.com"}"#);
+        assert_eq!(serde_json::from_str::<SiteError>(&json).unwrap(), error);
+        assert!(!error.kind.is_transient());
+
+        let missing = AppError::SelectorNotFound {
+            site: "Mercari".into(),
+            selector: "div.article-body".into(),
+        };
+        assert_eq!(FailureKind::of(&missing), FailureKind::Selector);
+        assert_eq!(missing.to_string(), "Scrape Error: selector not found on Mercari: div.article-body");
+        assert_eq!(FailureKind::of(&AppError::EmptyContent("https://example.com".into())), FailureKind::Selector);
+        assert_eq!(FailureKind::of(&AppError::DateParse("Missing date".into())), FailureKind::Parse);
+    }
+
+    #[test]
+    fn test_text_stats() {
+        let mut stats = TextStats::default();
+        assert_eq!(stats.mean_chars(), None);
+        stats.record("本文です");
+        stats.record("  ");
+        stats.record("NO TEXT");
+        stats.record("abcdef");
+        assert_eq!((stats.parsed, stats.empty, stats.total_chars), (4, 2, 10));
+        assert_eq!(stats.mean_chars(), Some(5.0));
+        assert_eq!(stats.empty_ratio(), 0.5);
+
+        let mut total = TextStats::default();
+        total.merge(&stats);
+        total.merge(&stats);
+        assert_eq!(total.parsed, 8);
+
+        // 以前の巡回履歴（`text` の無いレポート）も読み込める
+        let mut json = serde_json::to_value(FetchReport::new("Old")).unwrap();
+        json.as_object_mut().unwrap().remove("text");
+        assert_eq!(serde_json::from_value::<FetchReport>(json).unwrap().text, TextStats::default());
+    }
+
+    #[test]
+    fn test_mark_degraded() {
+        let article = |site: &str| {
+            ArticleBuilder::new("Title")
+                .with_site(site, "https://example.com")
+                .with_url(format!("https://example.com/{}", site))
+                .build()
+        };
+        let mut reports = vec![FetchReport::new("A"), FetchReport::new("B")];
+        let articles = [article("A"), article("A"), article("Unknown")];
+        mark_degraded(&mut reports, &articles, Degradation::QueuedInOutbox);
+        mark_degraded(&mut reports, &articles[..1], Degradation::ExtractiveSummary);
+        assert_eq!(reports[0].degraded, [Degradation::ExtractiveSummary, Degradation::QueuedInOutbox]);
+        assert!(reports[1].degraded.is_empty());
+
+        let json = serde_json::to_value(&reports[0]).unwrap();
+        assert_eq!(json["degraded"], serde_json::json!(["extractive_summary", "queued_in_outbox"]));
+        assert!(serde_json::to_value(&reports[1]).unwrap().get("degraded").is_none());
+        assert_eq!(Degradation::BufferedInMemory.to_string(), "buffered_in_memory");
+    }
+}
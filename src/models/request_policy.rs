@@ -0,0 +1,137 @@
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::proxy::ProxyRoute;
+use request::header::{ACCEPT_LANGUAGE, HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// サイトごとのリクエストの設定．指定しない項目は共有クライアントの既定値（タイムアウト 60 秒，
+/// `news-clipper/<version>` の User-Agent，`CLIPPER_PROXY` のプロキシ）を使う．
+///
+/// 日本のサイトには User-Agent や言語によって異なるマークアップを返すものがあるため，
+/// `WebSiteInterface::request_policy` をオーバーライドするか，設定ファイルの `[feeds.request]` で指定する．
+///
+/// ```toml
+/// [feeds.request]
+/// timeout_secs = 20
+/// user_agent = "Mozilla/5.0 (compatible; news-clipper)"
+/// accept_language = "ja,en;q=0.8"
+/// proxy = "socks5h://127.0.0.1:1080"   # http / https / socks5 / socks5h，または direct
+/// headers = { "X-Requested-With" = "XMLHttpRequest" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestPolicy {
+    /// リクエスト全体のタイムアウト（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept_language: Option<String>,
+    /// 経由するプロキシの URL（`direct` の場合は `CLIPPER_PROXY` を使わない）．
+    /// `CLIPPER_SITE_PROXIES` で指定したサイトはそちらを優先する．
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// 追加のヘッダー（`User-Agent` / `Accept-Language` より後に設定する）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl RequestPolicy {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_secs = Some(timeout.as_secs().max(1));
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn with_accept_language(mut self, accept_language: impl Into<String>) -> Self {
+        self.accept_language = Some(accept_language.into());
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+
+    /// 指定したプロキシの経路（未指定の場合は `None`）．URL が不正な場合はエラー．
+    pub fn proxy_route(&self) -> AppResult<Option<ProxyRoute>> {
+        self.proxy.as_deref().map(str::parse).transpose()
+    }
+
+    /// リクエストに設定するヘッダー．ヘッダー名・値が不正な場合はエラー．
+    pub fn header_map(&self) -> AppResult<HeaderMap> {
+        let value = |name: &str, value: &str| {
+            HeaderValue::from_str(value.trim())
+                .map_err(|e| AppError::InternalError(format!("Invalid value of {} header: {}", name, e)))
+        };
+        let mut headers = HeaderMap::new();
+        if let Some(user_agent) = &self.user_agent {
+            headers.insert(USER_AGENT, value("User-Agent", user_agent)?);
+        }
+        if let Some(accept_language) = &self.accept_language {
+            headers.insert(ACCEPT_LANGUAGE, value("Accept-Language", accept_language)?);
+        }
+        for (name, v) in &self.headers {
+            let header = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|e| AppError::InternalError(format!("Invalid header name {}: {}", name, e)))?;
+            headers.insert(header, value(name, v)?);
+        }
+        Ok(headers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_policy_header_map() {
+        let policy = RequestPolicy::default()
+            .with_timeout(Duration::from_secs(15))
+            .with_user_agent("Mozilla/5.0")
+            .with_accept_language("ja")
+            .with_header("X-Requested-With", "XMLHttpRequest");
+        let headers = policy.header_map().unwrap();
+        assert_eq!(headers[USER_AGENT], "Mozilla/5.0");
+        assert_eq!(headers[ACCEPT_LANGUAGE], "ja");
+        assert_eq!(headers["x-requested-with"], "XMLHttpRequest");
+        assert_eq!(policy.timeout(), Some(Duration::from_secs(15)));
+        assert_eq!(policy.proxy_route().unwrap(), None);
+        assert_eq!(policy.with_proxy("direct").proxy_route().unwrap(), Some(ProxyRoute::Direct));
+        assert!(RequestPolicy::default().with_proxy("ftp://proxy").proxy_route().is_err());
+
+        assert!(RequestPolicy::default().header_map().unwrap().is_empty());
+        assert!(RequestPolicy::default().with_header("Bad Header", "x").header_map().is_err());
+        assert!(RequestPolicy::default().with_user_agent("bad\nvalue").header_map().is_err());
+    }
+
+    #[test]
+    fn test_request_policy_from_toml() {
+        let policy: RequestPolicy = toml::from_str(
+            r#"
+timeout_secs = 20
+accept_language = "ja,en;q=0.8"
+headers = { "Referer" = "https://example.com/" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(policy.timeout(), Some(Duration::from_secs(20)));
+        assert_eq!(policy.headers["Referer"], "https://example.com/");
+        assert!(toml::from_str::<RequestPolicy>("timeout = 20").is_err());
+    }
+}
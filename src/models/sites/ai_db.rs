@@ -5,6 +5,7 @@ use dotenvy::dotenv;
 use feed_parser::parsers;
 use request::{Url, cookie::Jar};
 use std::sync::Arc;
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://ai-data-base.com/feed";
 
@@ -68,7 +69,7 @@ impl WebSiteInterface for AIDB {
         let response = match client.post(url).query(&param).send().await {
             Ok(response) => response,
             Err(e) => {
-                return Err(AppError::RequestError(e));
+                return Err(e.into());
             }
         };
         let cookies = response
@@ -92,7 +93,7 @@ impl WebSiteInterface for AIDB {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -107,11 +108,10 @@ impl WebSiteInterface for AIDB {
     }
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let cookies = self.login().await?;
-        let response = self.request(url, &cookies).await?;
-        let text = response.text().await?;
+        let text = self.request_text(url, &cookies).await?;
 
-        let document = scraper::Html::parse_document(text.as_str());
-        let selector = scraper::Selector::parse(".post_content").unwrap();
+        let document = scraper::Html::parse_document(&text);
+        let selector = selector!(".post_content");
         match document.select(&selector).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -119,7 +119,10 @@ impl WebSiteInterface for AIDB {
                 let text = html2md::rewrite_html(&html, false);
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+            None => Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: ".post_content".into(),
+            }),
         }
     }
 }
@@ -1,90 +1,17 @@
-use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
-use crate::shared::errors::{AppError, AppResult};
-use chrono::DateTime;
-use feed_parser::parsers;
-use request::Url;
-use scraper::Selector;
+use super::macros::define_rss_site;
 
-const URL: &str = "https://ai-news.dev/feeds/";
-
-#[derive(Debug, Clone)]
-pub struct AINews {
-    site_name: String,
-    url: Url,
-}
-
-impl AINews {
-    pub fn new() -> Self {
-        Self {
-            site_name: "AI News".to_string(),
-            url: Url::parse(URL).unwrap(),
-        }
-    }
-}
-
-impl Default for AINews {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[async_trait::async_trait]
-impl WebSiteInterface for AINews {
-    fn site_name(&self) -> String {
-        self.site_name.clone()
-    }
-    fn site_url(&self) -> Url {
-        self.url.clone()
-    }
-    fn domain(&self) -> String {
-        self.url.domain().unwrap().to_string()
-    }
-    async fn login(&mut self) -> AppResult<Cookie> {
-        Ok(String::default())
-    }
-    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
-        let cookies = self.login().await?;
-        let response = self.request(self.url.as_str(), &cookies).await?;
-
-        let feeds = match parsers::atom::parse(response.text().await?.as_str()) {
-            Ok(feeds) => feeds,
-            Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse Atom feed: {}", e)));
-            }
-        };
-        let articles = feeds
-            .iter()
-            .map(|feed| -> AppResult<WebArticle> {
-                let publish_date = feed
-                    .publish_date
-                    .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
-                Ok(WebArticle::new(
-                    self.site_name(),
-                    self.site_url().to_string(),
-                    feed.title.clone(),
-                    feed.link.clone(),
-                    feed.description.clone().unwrap_or("".to_string()),
-                    DateTime::parse_from_rfc3339(&publish_date)?.into(),
-                ))
-            })
-            .collect::<AppResult<Vec<WebArticle>>>()?;
-        Ok(articles)
-    }
-
-    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
-        let cookies = self.login().await?;
-        let response = self.request(url, &cookies).await?;
-        let doc = scraper::Html::parse_document(response.text().await?.as_str());
-        let sel = Selector::parse("body").unwrap();
-        match doc.select(&sel).next() {
-            Some(elem) => {
-                let raw_html = elem.html().to_string();
-                let html = self.clean_content(&raw_html);
-                let text = html2md::rewrite_html(&html, false);
-                Ok((self.trim_text(&html), self.trim_text(&text)))
-            }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+define_rss_site! {
+    pub struct AINews {
+        site_name: "AI News",
+        url: "https://ai-news.dev/feeds/",
+        feed: atom,
+        date: publish_date => parse_from_rfc3339,
+        content: first_match("body"),
+    }
+    impl {
+        /// 各社の記事へのリンクを集約するサイトのため，ドメインを制限しない
+        fn allowed_domains(&self) -> Vec<String> {
+            vec![crate::shared::domain_guard::ANY_DOMAIN.to_string()]
         }
     }
 }
@@ -2,7 +2,7 @@ use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterfac
 use crate::shared::errors::{AppError, AppResult};
 use chrono::{DateTime, Local};
 use request::Url;
-use scraper::Selector;
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://ai-scholar.tech/";
 
@@ -50,22 +50,21 @@ impl WebSiteInterface for AIScholar {
         // parse html
         let doc = scraper::Html::parse_document(response.text().await?.as_str());
         let sel =
-            Selector::parse("body div.content main.main section.indexlists article.list-item")
-                .unwrap();
+            selector!("body div.content main.main section.indexlists article.list-item");
         let articles = doc
             .select(&sel)
             .filter_map(|article| {
-                let a_sel = Selector::parse("a").unwrap();
+                let a_sel = selector!("a");
                 let a_elem = article.select(&a_sel).next()?;
                 let title_text = a_elem.text().collect::<Vec<_>>().join("");
                 let url = a_elem.value().attr("href")?;
-                let date_sel = Selector::parse("a div.list-item__description time").unwrap();
+                let date_sel = selector!("a div.list-item__description time");
                 let mut date_text = match article.select(&date_sel).next() {
                     Some(x) => x.value().attr("datetime").unwrap_or_default().to_string(),
                     None => String::default(),
                 };
                 date_text.push_str("+09:00");
-                let desc_sel = Selector::parse("a div.list-item__description span").unwrap();
+                let desc_sel = selector!("a div.list-item__description span");
                 let desc_text = match article.select(&desc_sel).next() {
                     Some(x) => x.text().collect::<Vec<_>>().join(""),
                     None => String::default(),
@@ -89,9 +88,9 @@ impl WebSiteInterface for AIScholar {
 
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let cookies = self.login().await?;
-        let response = self.request(url, &cookies).await?;
-        let doc = scraper::Html::parse_document(response.text().await?.as_str());
-        let sel = Selector::parse("article").unwrap();
+        let body = self.request_article_page(url, &cookies).await?;
+        let doc = scraper::Html::parse_document(&body);
+        let sel = selector!("article");
         match doc.select(&sel).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -99,7 +98,10 @@ impl WebSiteInterface for AIScholar {
                 let text = html2md::rewrite_html(&html, false);
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+            None => Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: "article".into(),
+            }),
         }
     }
 }
@@ -1,8 +1,10 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use crate::shared::errors::{AppError, AppResult};
 use chrono::DateTime;
 use feed_parser::parsers;
 use request::Url;
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://otafuku-lab.co/aizine/feed/";
 
@@ -45,10 +47,15 @@ impl WebSiteInterface for AIZine {
     async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
         let cookies = self.login().await?;
         let response = self.request(self.url.as_str(), &cookies).await?;
-        let mut feeds = if let Ok(r) = parsers::rss2::parse(response.text().await?.as_str()) {
-            r
-        } else {
-            return Err(AppError::ScrapeError("Failed to parse RSS".into()));
+        let mut feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
+            Ok(feeds) => feeds,
+            Err(e) => {
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
+            }
         };
         let articles = feeds
             .iter_mut()
@@ -56,7 +63,7 @@ impl WebSiteInterface for AIZine {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -71,9 +78,9 @@ impl WebSiteInterface for AIZine {
     }
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let cookies = self.login().await?;
-        let response = self.request(url, &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = scraper::Selector::parse("#main article div.entry-content").unwrap();
+        let body = self.request_article_page(url, &cookies).await?;
+        let document = scraper::Html::parse_document(&body);
+        let selector = selector!("#main article div.entry-content");
         match document.select(&selector).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -81,7 +88,10 @@ impl WebSiteInterface for AIZine {
                 let text = html2md::rewrite_html(&html, false);
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+            None => Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: "#main article div.entry-content".into(),
+            }),
         }
     }
 }
@@ -0,0 +1,158 @@
+//! Anthropic のニュース（製品・研究の発表）．
+//!
+//! RSS が提供されていないため，一覧ページ（`https://www.anthropic.com/news`）の `/news/<スラッグ>` へのリンクから記事を取得する．
+//! 日付はリンク内の「Oct 1, 2025」形式のテキストから読み取る．
+//! 発表の多くは製品のリリースのため，記事には取得元として `ArticleTag::NewProduct` のタグを付ける．
+
+use crate::models::tag::ArticleTag;
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::errors::AppResult;
+use crate::shared::selectors::selector;
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use request::Url;
+use std::collections::HashSet;
+
+const URL: &str = "https://www.anthropic.com/news";
+/// 一覧の日付の書式（「Oct 1, 2025」「October 1, 2025」）
+const DATE_FORMATS: &[&str] = &["%b %d, %Y", "%B %d, %Y"];
+
+/// リンク内のテキストから日付を探す
+fn parse_date(texts: &[&str]) -> Option<DateTime<Local>> {
+    texts.iter().find_map(|text| {
+        let text = text.trim();
+        let date = DATE_FORMATS.iter().find_map(|format| NaiveDate::parse_from_str(text, format).ok())?;
+        Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()
+    })
+}
+
+/// 一覧ページを記事にする．同じ記事へのリンク（注目記事など）は最初のものだけを使う．
+fn parse_listing(site_name: &str, site_url: &Url, html: &str) -> Vec<WebArticle> {
+    let doc = scraper::Html::parse_document(html);
+    let mut seen = HashSet::new();
+    let mut articles = Vec::new();
+    for link in doc.select(selector!("a[href*='/news/']")) {
+        let Some(url) = link.value().attr("href").and_then(|href| site_url.join(href).ok()) else {
+            continue;
+        };
+        let is_article = url.domain() == site_url.domain()
+            && url.path_segments().is_some_and(|s| s.filter(|s| !s.is_empty()).count() == 2);
+        if !is_article || !seen.insert(url.to_string()) {
+            continue;
+        }
+        let title = match link.select(selector!("h1, h2, h3, h4, [class*='title']")).next() {
+            Some(elem) => elem.text().collect::<String>(),
+            None => continue,
+        };
+        let texts: Vec<&str> = link.text().collect();
+        let Some(publish_date) = parse_date(&texts) else {
+            tracing::debug!("Missing publish date: {}", url);
+            continue;
+        };
+        let description = link
+            .select(selector!("p"))
+            .next()
+            .map(|elem| elem.text().collect::<String>().trim().to_string())
+            .unwrap_or_default();
+        let mut article = WebArticle::new(
+            site_name.to_string(),
+            site_url.to_string(),
+            title.trim().to_string(),
+            url.to_string(),
+            description,
+            publish_date,
+        );
+        article.properties.tag_by_source(ArticleTag::NewProduct);
+        articles.push(article);
+    }
+    articles
+}
+
+#[derive(Debug, Clone)]
+pub struct AnthropicNews {
+    site_name: String,
+    url: Url,
+}
+
+impl AnthropicNews {
+    pub fn new() -> Self {
+        Self {
+            site_name: "Anthropic News".to_string(),
+            url: Url::parse(URL).unwrap(),
+        }
+    }
+}
+
+impl Default for AnthropicNews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for AnthropicNews {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+        vec![
+            ExtractionStrategy::css(&["main article", "main div[class*='Body']"]),
+            ExtractionStrategy::Readability,
+        ]
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), "").await?;
+        Ok(parse_listing(&self.site_name(), &self.url, &response.text().await?))
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let url = Url::parse(url)?;
+        let body = self.request_article_page(url.as_str(), "").await?;
+        self.extract_content(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = r#"<html><body><main>
+<a href="/news/claude-new-model" class="FeaturedCard">
+  <h2 class="card-title">Introducing a new Claude model</h2>
+  <p>Our most capable model yet.</p>
+  <div class="detail"><span>Product</span><span>Oct 1, 2025</span></div>
+</a>
+<ul>
+  <li><a href="/news/claude-new-model"><h3>Introducing a new Claude model</h3><div>Oct 1, 2025</div></a></li>
+  <li>
+    <a href="https://www.anthropic.com/news/policy-update"><h3>A policy update</h3><div>September 30, 2025</div></a>
+  </li>
+  <li><a href="/news/no-date"><h3>Undated</h3></a></li>
+  <li><a href="/news"><h3>All news</h3></a></li>
+</ul>
+</main></body></html>"#;
+
+    #[test]
+    fn test_parse_listing() {
+        let site = AnthropicNews::default();
+        let articles = parse_listing(&site.site_name(), &site.site_url(), LISTING);
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].title, "Introducing a new Claude model");
+        assert_eq!(articles[0].article_url, "https://www.anthropic.com/news/claude-new-model");
+        assert_eq!(articles[0].description, "Our most capable model yet.");
+        assert_eq!(articles[0].timestamp.date_naive(), NaiveDate::from_ymd_opt(2025, 10, 1).unwrap());
+        assert_eq!(articles[0].properties.source_tags, [ArticleTag::NewProduct]);
+        assert_eq!(articles[1].article_url, "https://www.anthropic.com/news/policy-update");
+        assert_eq!(articles[1].timestamp.date_naive(), NaiveDate::from_ymd_opt(2025, 9, 30).unwrap());
+        assert_eq!(articles[1].properties.is_new_product_related, Some(true));
+    }
+}
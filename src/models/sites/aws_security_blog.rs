@@ -1,8 +1,10 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use crate::shared::errors::{AppError, AppResult};
 use chrono::DateTime;
 use feed_parser::parsers;
 use request::Url;
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://aws.amazon.com/jp/blogs/security/feed/";
 
@@ -48,7 +50,11 @@ impl WebSiteInterface for AWSSecurityBlog {
         let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
             Ok(r) => r,
             Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
             }
         };
         let articles = feeds
@@ -57,7 +63,7 @@ impl WebSiteInterface for AWSSecurityBlog {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -72,9 +78,9 @@ impl WebSiteInterface for AWSSecurityBlog {
     }
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let cookies = self.login().await?;
-        let response = self.request(url, &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = scraper::Selector::parse("article section.blog-post-content").unwrap();
+        let body = self.request_article_page(url, &cookies).await?;
+        let document = scraper::Html::parse_document(&body);
+        let selector = selector!("article section.blog-post-content");
         match document.select(&selector).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -82,7 +88,10 @@ impl WebSiteInterface for AWSSecurityBlog {
                 let text = html2md::rewrite_html(&html, false);
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+            None => Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: "article section.blog-post-content".into(),
+            }),
         }
     }
 }
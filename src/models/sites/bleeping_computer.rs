@@ -0,0 +1,52 @@
+//! BleepingComputer のセキュリティ・IT ニュース．
+//!
+//! 記事の末尾の関連記事と，本文中のソフトウェアのダウンロードの枠をサイト固有の除外セレクタで取り除く．
+
+use super::macros::define_rss_site;
+
+define_rss_site! {
+    pub struct BleepingComputer {
+        site_name: "BleepingComputer",
+        url: "https://www.bleepingcomputer.com/feed/",
+        feed: rss2,
+        date: publish_date => parse_from_rfc2822,
+        content: first_match("div.articleBody"),
+    }
+    impl {
+        /// BleepingComputer 固有の除外セレクタ
+        fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+            vec![
+                // 関連記事
+                ".cz-related-article-wrapp",
+                "div[class*='related-article']",
+                // ダウンロードの枠
+                ".cz-dwnld-wrapp",
+                "div[class*='download-box']",
+                // 記事の下の広告
+                ".ia_ad",
+                "div[id^='bc-ad']",
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::sites::macros::first_match;
+
+    #[test]
+    fn test_first_match_removes_related_articles_and_downloads() {
+        let page = r#"<html><body><article><div class="articleBody">
+<p>A new ransomware operation is targeting VMware ESXi servers.</p>
+<div class="cz-dwnld-wrapp"><a href="/download/example-decryptor/">Download Example Decryptor</a></div>
+<p>Administrators are advised to apply the latest updates.</p>
+<div class="cz-related-article-wrapp"><h2>Related Articles:</h2><ul><li>Another ransomware story</li></ul></div>
+</div></article></body></html>"#;
+        let (html, text) = first_match(&BleepingComputer::default(), page, "div.articleBody").unwrap();
+        assert!(text.contains("targeting VMware ESXi servers"));
+        assert!(text.contains("apply the latest updates"));
+        assert!(!html.contains("Download Example Decryptor"));
+        assert!(!text.contains("Related Articles"));
+    }
+}
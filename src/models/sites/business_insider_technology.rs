@@ -1,8 +1,9 @@
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use crate::shared::errors::{AppError, AppResult};
+use crate::shared::regexes;
 use chrono::{DateTime, Local};
 use request::Url;
-use scraper::Selector;
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://www.businessinsider.jp/tech-article/";
 
@@ -50,11 +51,11 @@ impl WebSiteInterface for BusinessInsiderTechnology {
         // parse html
         let doc = scraper::Html::parse_document(response.text().await?.as_str());
         let sel =
-            Selector::parse("#mainContent div.p-cardList-content div.p-cardList-card").unwrap();
+            selector!("#mainContent div.p-cardList-content div.p-cardList-card");
         let articles = doc
             .select(&sel)
             .map(|card| {
-                let a_sel = Selector::parse("h1 a").unwrap();
+                let a_sel = selector!("h1 a");
                 let title_text = card
                     .select(&a_sel)
                     .next()
@@ -70,13 +71,12 @@ impl WebSiteInterface for BusinessInsiderTechnology {
                     .attr("href")
                     .unwrap();
 
-                let date_sel = Selector::parse("ul li.p-cardList-cardDate").unwrap();
+                let date_sel = selector!("ul li.p-cardList-cardDate");
                 let mut date_text = match card.select(&date_sel).next() {
                     Some(x) => x.text().collect::<Vec<_>>().join(""),
                     None => String::default(),
                 };
-                let reg = regex::Regex::new(r"[\d]{1,2}h ago").unwrap();
-                if reg.is_match(&date_text) {
+                if regexes::hours_ago().is_match(&date_text) {
                     date_text = Local::now().format("%b. %d, %Y 00:00:00+09:00").to_string();
                 } else {
                     date_text = date_text + " 00:00:00+09:00";
@@ -102,9 +102,9 @@ impl WebSiteInterface for BusinessInsiderTechnology {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let doc = scraper::Html::parse_document(response.text().await?.as_str());
-        let sel = Selector::parse("article div.p-post-content").unwrap();
+        let body = self.request_article_page(url.as_str(), &cookies).await?;
+        let doc = scraper::Html::parse_document(&body);
+        let sel = selector!("article div.p-post-content");
         match doc.select(&sel).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -112,7 +112,10 @@ impl WebSiteInterface for BusinessInsiderTechnology {
                 let text = html2md::rewrite_html(&html, false);
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+            None => Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: "article div.p-post-content".into(),
+            }),
         }
     }
 }
@@ -0,0 +1,61 @@
+//! CNCF（Cloud Native Computing Foundation）のブログ．
+//!
+//! 記事のコードブロック（WordPress のコードブロック）は行番号とコピーボタンを除いてそのまま残す．
+
+use super::macros::define_rss_site;
+use crate::models::extraction::{CODE_BLOCK_NOISE_SELECTORS, ExtractionStrategy};
+use crate::models::web_article::{Html, Text, WebSiteInterface};
+use crate::shared::errors::AppResult;
+
+define_rss_site! {
+    pub struct CNCFBlog {
+        site_name: "CNCF Blog",
+        url: "https://www.cncf.io/blog/feed/",
+        feed: rss2,
+        date: publish_date => parse_from_rfc2822,
+    }
+    impl {
+        fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+            vec![
+                ExtractionStrategy::css(&["main article div.post-content", "main article div.entry-content"]),
+                ExtractionStrategy::Readability,
+            ]
+        }
+
+        /// CNCF ブログ固有の除外セレクタ
+        fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+            let mut selectors = CODE_BLOCK_NOISE_SELECTORS.to_vec();
+            selectors.extend([
+                // 記事末尾の著者紹介・購読の案内
+                ".post-author",
+                ".newsletter-signup",
+            ]);
+            selectors
+        }
+
+        async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+            let body = self.request_article_page(url, "").await?;
+            self.extract_content(&body)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_content_keeps_code_blocks() {
+        let page = r#"<html><body><main><article><div class="post-content">
+<p>Install the chart:</p>
+<div class="wp-block-code-block-pro"><span class="code-block-pro-copy-button">Copy</span>
+<pre class="wp-block-code"><code>helm repo add example https://charts.example.com
+helm install demo example/demo --set replicas=3</code></pre></div>
+<p>Then check the pods.</p>
+</div></article></main></body></html>"#;
+        let (html, text) = CNCFBlog::default().extract_content(page).unwrap();
+        assert!(!html.contains("Copy"));
+        assert!(text.contains("helm install demo example/demo --set replicas=3"));
+        assert!(text.contains("Then check the pods."));
+    }
+}
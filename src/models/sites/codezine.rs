@@ -1,10 +1,10 @@
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use chrono::{DateTime, Local};
 use request::Url;
-use scraper::Selector;
 use crate::shared::{
     errors::{AppError, AppResult},
 };
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://codezine.jp/news";
 
@@ -52,13 +52,13 @@ impl WebSiteInterface for CodeZine {
 
         // parse html
         let doc = scraper::Html::parse_document(response.text().await?.as_str());
-        let sel = Selector::parse("ul.c-articleindex_list").unwrap();
+        let sel = selector!("ul.c-articleindex_list");
         let mut articles = Vec::new();
         for ul in doc.select(&sel) {
-            let sel = Selector::parse("li.c-articleindex_listitem").unwrap();
+            let sel = selector!("li.c-articleindex_listitem");
             for item in ul.select(&sel) {
                 // title, url
-                let title_sel = Selector::parse("p.c-articleindex_item_heading a").unwrap();
+                let title_sel = selector!("p.c-articleindex_item_heading a");
                 let title = match item.select(&title_sel).next() {
                     Some(elem) => elem,
                     None => continue,
@@ -70,7 +70,7 @@ impl WebSiteInterface for CodeZine {
                 };
 
                 // date
-                let date_sel = Selector::parse("p.c-featureindex_item_date").unwrap();
+                let date_sel = selector!("p.c-featureindex_item_date");
                 let date_text = match item.select(&date_sel).next() {
                     Some(x) => x.text().collect::<Vec<_>>().join(""),
                     None => String::default(),
@@ -96,13 +96,12 @@ impl WebSiteInterface for CodeZine {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let raw = response.text().await?;
+        let raw = self.request_text(url.as_str(), &cookies).await?;
         if crate::models::web_article::detect_login_required(&raw) {
             return Err(AppError::LoginRequired);
         }
-        let doc = scraper::Html::parse_document(raw.as_str());
-        let sel = Selector::parse("main article div.detailBlock").unwrap();
+        let doc = scraper::Html::parse_document(&raw);
+        let sel = selector!("main article div.detailBlock");
         match doc.select(&sel).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -111,7 +110,10 @@ impl WebSiteInterface for CodeZine {
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
             None => {
-                Err(AppError::ScrapeError("Failed to parse article text".into()))
+                Err(AppError::SelectorNotFound {
+                    site: self.site_name(),
+                    selector: "main article div.detailBlock".into(),
+                })
             }
         }
     }
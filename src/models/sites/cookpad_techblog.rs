@@ -1,95 +1,11 @@
-use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
-use chrono::DateTime;
-use feed_parser::parsers;
-use request::Url;
-use scraper::Selector;
-use crate::shared::{
-    errors::{AppError, AppResult},
-};
-
-const URL: &str = "https://techlife.cookpad.com/rss";
-
-#[derive(Debug, Clone)]
-pub struct CookpadTechBlog {
-    site_name: String,
-    url: Url,
-}
-
-impl CookpadTechBlog {
-    pub fn new() -> Self {
-        let url = Url::parse(URL).unwrap();
-        CookpadTechBlog {
-            site_name: "Cookpad Tech Blog".to_string(),
-            url,
-        }
-    }
-}
-
-impl Default for CookpadTechBlog {
-    fn default() -> Self {
-        CookpadTechBlog::new()
-    }
-}
-
-#[async_trait::async_trait]
-impl WebSiteInterface for CookpadTechBlog {
-
-    fn site_name(&self) -> String {
-        self.site_name.clone()
-    }
-    fn site_url(&self) -> Url {
-        self.url.clone()
-    }
-    fn domain(&self) -> String {
-        self.url.domain().unwrap().to_string()
-    }
-
-    async fn login(&mut self) -> AppResult<Cookie> {
-        Ok(Cookie::default())
-    }
-    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
-        let cookies = self.login().await?;
-        let response = self.request(self.url.as_str(), &cookies).await?;
-        let feeds = match parsers::atom::parse(response.text().await?.as_str()) {
-            Ok(feeds) => feeds,
-            Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse Atom feed: {}", e)));
-            }
-        };
-        let articles = feeds
-            .iter()
-            .map(|feed| -> AppResult<WebArticle> {
-                let updated = feed
-                    .updated
-                    .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing updated".into()))?;
-                Ok(WebArticle::new(
-                    self.site_name(),
-                    self.site_url().to_string(),
-                    feed.title.clone(),
-                    feed.link.clone(),
-                    feed.description.clone().unwrap_or("".to_string()),
-                    DateTime::parse_from_rfc3339(&updated)?.into(),
-                ))
-            })
-            .collect::<AppResult<Vec<WebArticle>>>()?;
-        Ok(articles)
-    }
-
-    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
-        let url = Url::parse(url).unwrap();
-        let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let doc = scraper::Html::parse_document(response.text().await?.as_str());
-        let sel = Selector::parse("#main article div.entry-content").unwrap();
-        match doc.select(&sel).next() {
-            Some(elem) => {
-                let raw_html = elem.html().to_string();
-                let html = self.clean_content(&raw_html);
-                let text = html2md::rewrite_html(&html, false);
-                Ok((self.trim_text(&html), self.trim_text(&text)))
-            }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
-        }
+use super::macros::define_rss_site;
+
+define_rss_site! {
+    pub struct CookpadTechBlog {
+        site_name: "Cookpad Tech Blog",
+        url: "https://techlife.cookpad.com/rss",
+        feed: atom,
+        date: updated => parse_from_rfc3339,
+        content: first_match("#main article div.entry-content"),
     }
 }
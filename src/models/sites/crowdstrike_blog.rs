@@ -1,3 +1,4 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use chrono::DateTime;
 use feed_parser::parsers;
@@ -5,6 +6,7 @@ use request::Url;
 use crate::shared::{
     errors::{AppError, AppResult},
 };
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://www.crowdstrike.com/en-us/blog/feed";
 
@@ -52,7 +54,11 @@ impl WebSiteInterface for CrowdStrikeBlog {
         let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
             Ok(feeds) => feeds,
             Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
             }
         };
         let articles = feeds
@@ -61,7 +67,7 @@ impl WebSiteInterface for CrowdStrikeBlog {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -77,9 +83,9 @@ impl WebSiteInterface for CrowdStrikeBlog {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = scraper::Selector::parse("div.root div.cmp-container-wp").unwrap();
+        let body = self.request_article_page(url.as_str(), &cookies).await?;
+        let document = scraper::Html::parse_document(&body);
+        let selector = selector!("div.root div.cmp-container-wp");
         match document.select(&selector).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -87,7 +93,10 @@ impl WebSiteInterface for CrowdStrikeBlog {
                 let text = html2md::rewrite_html(&html, false);
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+            None => Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: "div.root div.cmp-container-wp".into(),
+            }),
         }
     }
 }
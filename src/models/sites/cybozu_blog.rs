@@ -1,93 +1,11 @@
-use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
-use chrono::DateTime;
-use feed_parser::parsers;
-use request::Url;
-use crate::shared::{
-    errors::{AppError, AppResult},
-};
-
-const URL: &str = "https://blog.cybozu.io/rss";
-
-#[derive(Debug, Clone)]
-pub struct CybozuBlog {
-    site_name: String,
-    url: Url,
-}
-
-impl CybozuBlog {
-    pub fn new() -> Self {
-        let url = Url::parse(URL).unwrap();
-        CybozuBlog {
-            site_name: "Cybozu Blog".to_string(),
-            url,
-        }
-    }
-}
-
-impl Default for CybozuBlog {
-    fn default() -> Self {
-        CybozuBlog::new()
-    }
-}
-
-#[async_trait::async_trait]
-impl WebSiteInterface for CybozuBlog {
-
-    fn site_name(&self) -> String {
-        self.site_name.clone()
-    }
-    fn site_url(&self) -> Url {
-        self.url.clone()
-    }
-    fn domain(&self) -> String {
-        self.url.domain().unwrap().to_string()
-    }
-
-    async fn login(&mut self) -> AppResult<Cookie> {
-        Ok(Cookie::default())
-    }
-    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
-        let cookie = self.login().await?;
-        let response = self.request(self.url.as_str(), &cookie).await?;
-        let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
-            Ok(feeds) => feeds,
-            Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
-            }
-        };
-        let articles = feeds
-            .iter()
-            .map(|feed| -> AppResult<WebArticle> {
-                let publish_date = feed
-                    .publish_date
-                    .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
-                Ok(WebArticle::new(
-                    self.site_name(),
-                    self.site_url().to_string(),
-                    feed.title.clone(),
-                    feed.link.clone(),
-                    feed.description.clone().unwrap_or("".to_string()),
-                    DateTime::parse_from_rfc2822(&publish_date)?.into(),
-                ))
-            })
-            .collect::<AppResult<Vec<WebArticle>>>()?;
-        Ok(articles)
-    }
-    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
-        let url = Url::parse(url).unwrap();
-        let cookie = self.login().await?;
-        let response = self.request(url.as_str(), &cookie).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = scraper::Selector::parse("#main article div.entry-inner div.entry-content").unwrap();
-        match document.select(&selector).next() {
-            Some(elem) => {
-                let raw_html = elem.html().to_string();
-                let html = self.clean_content(&raw_html);
-                let text = html2md::rewrite_html(&html, false);
-                Ok((self.trim_text(&html), self.trim_text(&text)))
-            }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
-        }
+use super::macros::define_rss_site;
+
+define_rss_site! {
+    pub struct CybozuBlog {
+        site_name: "Cybozu Blog",
+        url: "https://blog.cybozu.io/rss",
+        feed: rss2,
+        date: publish_date => parse_from_rfc2822,
+        content: first_match("#main article div.entry-inner div.entry-content"),
     }
 }
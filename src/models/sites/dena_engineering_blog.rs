@@ -1,3 +1,4 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use crate::shared::{
     errors::{AppError, AppResult},
@@ -5,6 +6,7 @@ use crate::shared::{
 use chrono::DateTime;
 use feed_parser::parsers;
 use request::Url;
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://engineering.dena.com/index.xml";
 
@@ -50,7 +52,11 @@ impl WebSiteInterface for DeNAEngineeringBlog {
         let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
             Ok(feeds) => feeds,
             Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
             }
         };
         let articles = feeds
@@ -60,7 +66,7 @@ impl WebSiteInterface for DeNAEngineeringBlog {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -76,9 +82,9 @@ impl WebSiteInterface for DeNAEngineeringBlog {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookie = self.login().await?;
-        let response = self.request(url.as_str(), &cookie).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = scraper::Selector::parse("main article section.content-box").unwrap();
+        let body = self.request_article_page(url.as_str(), &cookie).await?;
+        let document = scraper::Html::parse_document(&body);
+        let selector = selector!("main article section.content-box");
         match document.select(&selector).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -86,7 +92,10 @@ impl WebSiteInterface for DeNAEngineeringBlog {
                 let text = html2md::rewrite_html(&html, false);
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+            None => Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: "main article section.content-box".into(),
+            }),
         }
     }
 }
@@ -0,0 +1,225 @@
+//! クラスメソッドの DevelopersIO（dev.classmethod.jp）．
+//!
+//! 1日に数十本の記事が投稿されるため，タグ（RSS の `<category>`）で絞り込んだサイトとして使う．
+//! 期間・件数の指定（`FetchOptions`）が無い場合も新しい順に [`DEFAULT_LIMIT`] 件までに抑える．
+
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::web_article::{Cookie, FetchOptions, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::regexes;
+use chrono::DateTime;
+use feed_parser::parsers;
+use request::Url;
+use std::collections::HashMap;
+
+const URL: &str = "https://dev.classmethod.jp/feed/";
+/// 期間・件数の指定が無い場合に取得する記事数の上限
+pub const DEFAULT_LIMIT: usize = 30;
+
+/// RSS の各 `<item>` の記事の URL とカテゴリ（小文字）
+fn categories(xml: &str) -> HashMap<String, Vec<String>> {
+    regexes::rss_item()
+        .find_iter(xml)
+        .filter_map(|item| {
+            let item = item.as_str();
+            let url = regexes::rss_link().captures(item)?.name("url")?.as_str().trim().to_string();
+            let names = regexes::rss_category()
+                .captures_iter(item)
+                .filter_map(|c| c.name("name"))
+                .map(|name| name.as_str().trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect();
+            Some((url, names))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct DevelopersIO {
+    site_name: String,
+    url: Url,
+    /// 絞り込むタグ（`None` は全ての記事）
+    pub tag: Option<String>,
+}
+
+impl DevelopersIO {
+    /// 全ての新着記事を取得する
+    pub fn new() -> Self {
+        Self {
+            site_name: "DevelopersIO".to_string(),
+            url: Url::parse(URL).unwrap(),
+            tag: None,
+        }
+    }
+
+    /// `tag`（`AWS`，`生成AI` など．大文字小文字は区別しない）の記事だけを取得する
+    pub fn tagged(tag: &str) -> Self {
+        Self {
+            site_name: format!("DevelopersIO - {}", tag),
+            url: Url::parse(URL).unwrap(),
+            tag: Some(tag.to_string()),
+        }
+    }
+
+    /// フィードの記事のうちタグに当てはまるもの
+    fn parse_feed(&self, xml: &str) -> AppResult<Vec<WebArticle>> {
+        let feeds = match parsers::rss2::parse(xml) {
+            Ok(feeds) => feeds,
+            Err(e) => {
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
+            }
+        };
+        let categories = categories(xml);
+        let tag = self.tag.as_deref().map(str::to_lowercase);
+        feeds
+            .iter()
+            .filter(|feed| {
+                tag.as_ref().is_none_or(|tag| {
+                    categories.get(feed.link.trim()).is_some_and(|names| names.contains(tag))
+                })
+            })
+            .map(|feed| -> AppResult<WebArticle> {
+                let publish_date = feed
+                    .publish_date
+                    .clone()
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
+                Ok(WebArticle::new(
+                    self.site_name(),
+                    self.site_url().to_string(),
+                    feed.title.clone(),
+                    feed.link.clone(),
+                    feed.description.clone().unwrap_or_default(),
+                    DateTime::parse_from_rfc2822(&publish_date)?.into(),
+                ))
+            })
+            .collect()
+    }
+
+    async fn fetch_all(&self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), "").await?;
+        self.parse_feed(&response.text().await?)
+    }
+}
+
+impl Default for DevelopersIO {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 期間・件数の指定が無ければ新しい順に [`DEFAULT_LIMIT`] 件に抑える
+fn effective_options(options: &FetchOptions) -> FetchOptions {
+    if options.is_unbounded() {
+        FetchOptions {
+            limit: Some(DEFAULT_LIMIT),
+            ..*options
+        }
+    } else {
+        *options
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for DevelopersIO {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+        vec![
+            ExtractionStrategy::css(&["main article div.content", "main div.post-content"]),
+            ExtractionStrategy::Readability,
+        ]
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        self.get_articles_with(&FetchOptions::default()).await
+    }
+    async fn get_articles_with(&mut self, options: &FetchOptions) -> AppResult<Vec<WebArticle>> {
+        Ok(effective_options(options).apply(self.fetch_all().await?))
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let body = self.request_article_page(url, "").await?;
+        self.extract_content(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>DevelopersIO</title>
+<link>https://dev.classmethod.jp</link>
+<item>
+  <title>Amazon Bedrock で RAG を構築してみた</title>
+  <link>https://dev.classmethod.jp/articles/bedrock-rag/</link>
+  <pubDate>Wed, 01 Oct 2025 09:30:00 +0900</pubDate>
+  <category><![CDATA[AWS]]></category>
+  <category><![CDATA[生成AI]]></category>
+</item>
+<item>
+  <title>Terraform のモジュール設計</title>
+  <link>https://dev.classmethod.jp/articles/terraform-modules/</link>
+  <pubDate>Wed, 01 Oct 2025 10:00:00 +0900</pubDate>
+  <category><![CDATA[AWS]]></category>
+</item>
+<item>
+  <title>社内イベントのレポート</title>
+  <link>https://dev.classmethod.jp/articles/event-report/</link>
+  <pubDate>Wed, 01 Oct 2025 11:00:00 +0900</pubDate>
+</item>
+</channel>
+</rss>"#;
+
+    fn titles(articles: &[WebArticle]) -> Vec<&str> {
+        articles.iter().map(|a| a.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_categories() {
+        let categories = categories(RSS);
+        assert_eq!(categories["https://dev.classmethod.jp/articles/bedrock-rag/"], ["aws", "生成ai"]);
+        assert!(categories["https://dev.classmethod.jp/articles/event-report/"].is_empty());
+    }
+
+    #[test]
+    fn test_parse_feed_filters_by_tag() {
+        assert_eq!(DevelopersIO::new().parse_feed(RSS).unwrap().len(), 3);
+        let aws = DevelopersIO::tagged("aws").parse_feed(RSS).unwrap();
+        assert_eq!(titles(&aws), ["Amazon Bedrock で RAG を構築してみた", "Terraform のモジュール設計"]);
+        let generative_ai = DevelopersIO::tagged("生成AI");
+        assert_eq!(generative_ai.site_name(), "DevelopersIO - 生成AI");
+        assert_eq!(titles(&generative_ai.parse_feed(RSS).unwrap()), ["Amazon Bedrock で RAG を構築してみた"]);
+    }
+
+    #[test]
+    fn test_effective_options() {
+        assert_eq!(effective_options(&FetchOptions::default()).limit, Some(DEFAULT_LIMIT));
+        let options = FetchOptions {
+            limit: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(effective_options(&options), options);
+        let window = FetchOptions {
+            since: Some(chrono::Local::now()),
+            ..Default::default()
+        };
+        assert_eq!(effective_options(&window).limit, None);
+    }
+}
@@ -0,0 +1,220 @@
+//! DEV Community（dev.to）の公開 REST API（Forem API）から記事を取得する．
+//!
+//! 一覧は `GET /api/articles?tag=<タグ>`，本文は記事の URL のパス（`/<ユーザー名>/<スラッグ>`）から
+//! `GET /api/articles/<ユーザー名>/<スラッグ>` で取得し，レスポンスの `body_html` を使う（記事ページはスクレイピングしない）．
+//! API キーは不要だが，認証情報のファイルでサイトに `api-key` ヘッダーを設定すればレート制限が緩和される．
+
+use crate::models::request_policy::RequestPolicy;
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::errors::{AppError, AppResult};
+use chrono::DateTime;
+use request::Url;
+use serde::Deserialize;
+
+const API_URL: &str = "https://dev.to/api/articles";
+/// Forem API の v1 を指定する `Accept` ヘッダー
+const API_ACCEPT: &str = "application/vnd.forem.api-v1+json";
+/// 1回の一覧で取得する記事数
+const PER_PAGE: usize = 30;
+
+/// 一覧の記事
+#[derive(Debug, Clone, Deserialize)]
+struct ListedArticle {
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    url: String,
+    /// 公開日時（RFC 3339）
+    published_at: String,
+}
+
+/// 記事の詳細
+#[derive(Debug, Clone, Deserialize)]
+struct ArticleDetail {
+    #[serde(default)]
+    body_html: Option<String>,
+}
+
+/// 一覧のレスポンスを記事にする
+fn parse_listing(site_name: &str, site_url: &str, json: &str) -> AppResult<Vec<WebArticle>> {
+    let listed: Vec<ListedArticle> = serde_json::from_str(json)
+        .map_err(|e| AppError::FeedParse {
+            site: site_name.to_string(),
+            kind: FeedKind::Json,
+            message: format!("dev.to articles: {}", e),
+        })?;
+    listed
+        .into_iter()
+        .map(|article| {
+            Ok(WebArticle::new(
+                site_name.to_string(),
+                site_url.to_string(),
+                article.title,
+                article.url,
+                article.description.unwrap_or_default(),
+                DateTime::parse_from_rfc3339(&article.published_at)?.into(),
+            ))
+        })
+        .collect()
+}
+
+/// 記事の URL（`https://dev.to/<ユーザー名>/<スラッグ>`）に対応する API の URL
+fn api_url_for(article_url: &Url) -> AppResult<Url> {
+    let segments: Vec<&str> = article_url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    match segments.as_slice() {
+        [username, slug] => Ok(Url::parse(&format!("{}/{}/{}", API_URL, username, slug))?),
+        _ => Err(AppError::ScrapeError(format!("Not a dev.to article URL: {}", article_url))),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DevTo {
+    site_name: String,
+    url: Url,
+    /// 絞り込むタグ（`None` は全ての新着記事）
+    pub tag: Option<String>,
+}
+
+impl DevTo {
+    /// `tag`（`rust`，`ai`，`security` など）の記事を取得する
+    pub fn new(tag: &str) -> Self {
+        let mut url = Url::parse(API_URL).unwrap();
+        url.query_pairs_mut()
+            .append_pair("tag", tag)
+            .append_pair("per_page", &PER_PAGE.to_string());
+        Self {
+            site_name: format!("Dev.to - {}", tag),
+            url,
+            tag: Some(tag.to_string()),
+        }
+    }
+
+    /// タグで絞り込まずに新着記事を取得する
+    pub fn latest() -> Self {
+        let mut url = Url::parse(API_URL).unwrap();
+        url.query_pairs_mut().append_pair("per_page", &PER_PAGE.to_string());
+        Self {
+            site_name: "Dev.to".to_string(),
+            url,
+            tag: None,
+        }
+    }
+}
+
+impl Default for DevTo {
+    fn default() -> Self {
+        Self::latest()
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for DevTo {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    fn request_policy(&self) -> RequestPolicy {
+        RequestPolicy::default().with_header("Accept", API_ACCEPT)
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), "").await?;
+        parse_listing(&self.site_name(), self.site_url().as_str(), &response.text().await?)
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let api_url = api_url_for(&Url::parse(url)?)?;
+        let body = self.request_text(api_url.as_str(), "").await?;
+        let detail: ArticleDetail = serde_json::from_str(&body)
+            .map_err(|e| AppError::FeedParse {
+                site: self.site_name(),
+                kind: FeedKind::Json,
+                message: format!("dev.to article: {}", e),
+            })?;
+        let body = detail
+            .body_html
+            .filter(|body| !body.trim().is_empty())
+            .ok_or_else(|| AppError::EmptyContent(url.to_string()))?;
+        let html = self.clean_content(&body);
+        let text = html2md::rewrite_html(&html, false);
+        Ok((self.trim_text(&html), self.trim_text(&text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listing() {
+        let json = r#"[
+            {
+                "type_of": "article",
+                "id": 1,
+                "title": "Writing a tokio runtime from scratch",
+                "description": "What happens inside block_on",
+                "url": "https://dev.to/alice/writing-a-tokio-runtime-3k2a",
+                "published_at": "2025-10-01T09:30:00Z",
+                "tag_list": ["rust", "async"],
+                "user": {"name": "Alice", "username": "alice"}
+            },
+            {
+                "id": 2,
+                "title": "No description",
+                "description": null,
+                "url": "https://dev.to/bob/no-description-1a2b",
+                "published_at": "2025-10-01T10:00:00+09:00"
+            }
+        ]"#;
+        let site = DevTo::new("rust");
+        let articles = parse_listing(&site.site_name(), site.site_url().as_str(), json).unwrap();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].site.name, "Dev.to - rust");
+        assert_eq!(articles[0].title, "Writing a tokio runtime from scratch");
+        assert_eq!(articles[0].description, "What happens inside block_on");
+        assert_eq!(articles[0].timestamp, DateTime::parse_from_rfc3339("2025-10-01T09:30:00Z").unwrap());
+        assert_eq!(articles[1].description, "");
+
+        assert!(parse_listing("Dev.to", API_URL, r#"{"error": "not found"}"#).is_err());
+        let invalid_date = r#"[{"title": "x", "url": "u", "published_at": "yesterday"}]"#;
+        assert!(parse_listing("Dev.to", API_URL, invalid_date).is_err());
+    }
+
+    #[test]
+    fn test_parse_listing_invalid_json() {
+        let result = parse_listing("Dev.to", API_URL, "<html>rate limited</html>");
+        assert!(matches!(result, Err(AppError::FeedParse { kind: FeedKind::Json, .. })));
+    }
+
+    #[test]
+    fn test_api_url_for() {
+        let url = Url::parse("https://dev.to/alice/writing-a-tokio-runtime-3k2a").unwrap();
+        assert_eq!(
+            api_url_for(&url).unwrap().as_str(),
+            "https://dev.to/api/articles/alice/writing-a-tokio-runtime-3k2a"
+        );
+        assert!(api_url_for(&Url::parse("https://dev.to/alice").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_site_urls() {
+        assert_eq!(
+            DevTo::new("security").site_url().as_str(),
+            "https://dev.to/api/articles?tag=security&per_page=30"
+        );
+        assert_eq!(DevTo::latest().site_url().as_str(), "https://dev.to/api/articles?per_page=30");
+        assert_eq!(DevTo::default().site_name(), "Dev.to");
+        assert_eq!(DevTo::new("ai").request_policy().header_map().unwrap()["accept"], API_ACCEPT);
+    }
+}
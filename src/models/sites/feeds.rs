@@ -0,0 +1,84 @@
+//! フィード取得と本文セレクタだけで実装できるサイトの定義．
+//! 新しいフィードサイトはモジュールを追加せず，ここに `FeedSiteSpec` を追加する．
+
+use super::generic_feed::{FallbackExtraction, FeedKind, FeedSiteSpec};
+
+pub const CYBERAGENT_TECH_BLOG: FeedSiteSpec = FeedSiteSpec {
+    site_name: "CyberAgent Tech Blog",
+    url: "https://developers.cyberagent.co.jp/blog/rss",
+    feed: FeedKind::Rss2,
+    content_selectors: &["main div.notion-text", "#main article div.entry-content"],
+    fallback: &[],
+};
+
+pub const GOOGLE_AI_BLOG: FeedSiteSpec = FeedSiteSpec {
+    site_name: "Google AI Blog",
+    url: "https://blog.google/technology/ai/rss/",
+    feed: FeedKind::Rss2,
+    content_selectors: &["div.article-container__content", "article div.uni-blog-article-container"],
+    // 記事ごとにテンプレートが異なる（動画・インタビューなど）ため，JSON-LD と Readability 風の抽出も試す
+    fallback: &[FallbackExtraction::JsonLd, FallbackExtraction::Readability],
+};
+
+pub const GOOGLE_DEEPMIND_BLOG: FeedSiteSpec = FeedSiteSpec {
+    site_name: "Google DeepMind Blog",
+    url: "https://deepmind.google/blog/rss.xml",
+    feed: FeedKind::Rss2,
+    content_selectors: &["main article div.rich-text", "main div.article-cover__body", "main article"],
+    fallback: &[FallbackExtraction::Readability],
+};
+
+pub const GREE_TECH_BLOG: FeedSiteSpec = FeedSiteSpec {
+    site_name: "GREE Tech Blog",
+    url: "https://labs.gree.jp/blog/feed",
+    feed: FeedKind::Rss2,
+    content_selectors: &["div.site-body article div.entry-body"],
+    fallback: &[],
+};
+
+pub const GUNOSY_TECH_BLOG: FeedSiteSpec = FeedSiteSpec {
+    site_name: "Gunosy Tech Blog",
+    url: "https://tech.gunosy.io/feed",
+    feed: FeedKind::Atom,
+    content_selectors: &["#main article div.entry-content"],
+    fallback: &[],
+};
+
+pub const MERCARI_ENGINEERING_BLOG: FeedSiteSpec = FeedSiteSpec {
+    site_name: "Mercari Engineering Blog",
+    url: "https://engineering.mercari.com/blog/feed.xml",
+    feed: FeedKind::Rss2,
+    // Astro への移行でページ構造が変わったため複数のセレクタを順に試す
+    content_selectors: &[
+        "div.page-content",
+        "main div.page-content",
+        "main section div._body_5d9ad_19",
+    ],
+    // 生成されたクラス名は再ビルドで変わるため，見つからなければ JSON-LD と Readability 風の抽出を試す
+    fallback: &[FallbackExtraction::JsonLd, FallbackExtraction::Readability],
+};
+
+/// 組み込みのフィードサイト一覧
+pub const ALL: &[FeedSiteSpec] = &[
+    CYBERAGENT_TECH_BLOG,
+    GOOGLE_AI_BLOG,
+    GOOGLE_DEEPMIND_BLOG,
+    GREE_TECH_BLOG,
+    GUNOSY_TECH_BLOG,
+    MERCARI_ENGINEERING_BLOG,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::web_article::WebSiteInterface;
+
+    #[test]
+    fn test_builtin_feed_specs_build() {
+        for spec in ALL {
+            let site = spec.build();
+            assert_eq!(site.site_name(), spec.site_name);
+            assert!(!site.domain().is_empty());
+        }
+    }
+}
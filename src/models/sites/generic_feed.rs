@@ -0,0 +1,422 @@
+use crate::discovery::sitemap;
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::fairness::SiteCategory;
+use crate::models::priority::SitePriority;
+use crate::models::request_policy::RequestPolicy;
+use crate::models::web_article::{
+    Cookie, FetchOptions, Html, Text, WebArticle, WebSiteInterface, clean_html_with_rules,
+    effective_exclude_selectors, select_clean_fragments,
+};
+use crate::shared::domain_guard::default_allowed_domains;
+use crate::shared::errors::{AppError, AppResult};
+use chrono::{DateTime, FixedOffset};
+use feed_parser::parsers;
+use request::Url;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// フィードの形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum FeedKind {
+    Rss1,
+    #[default]
+    Rss2,
+    Atom,
+    /// サイト固有の JSON API（解析エラーの種類を示すためのもので，汎用サイトには指定できない）
+    #[serde(skip_deserializing)]
+    Json,
+}
+
+/// フィード URL と本文の抽出方法だけで定義できる汎用サイト．
+/// 設定ファイル（`SiteRegistry`）から追加されるサイトはこの型で表現する．
+/// サイトマップを指定すると，フィードを取得・解析できない場合にサイトマップから記事一覧を組み立てる．
+#[derive(Debug, Clone)]
+pub struct GenericFeedSite {
+    site_name: String,
+    url: Url,
+    feed_kind: FeedKind,
+    extraction: Vec<ExtractionStrategy>,
+    exclude_selectors: Vec<String>,
+    exempt_selectors: Vec<String>,
+    category: Option<SiteCategory>,
+    priority: Option<SitePriority>,
+    allowed_domains: Vec<String>,
+    request_policy: RequestPolicy,
+    sitemap: Option<Url>,
+}
+
+impl GenericFeedSite {
+    pub fn new(site_name: &str, url: &str, feed_kind: FeedKind) -> AppResult<Self> {
+        if feed_kind == FeedKind::Json {
+            return Err(AppError::InternalError(format!(
+                "JSON APIs are not supported as generic feeds: {}",
+                site_name
+            )));
+        }
+        let url = Url::parse(url)?;
+        if url.domain().is_none() {
+            return Err(AppError::InternalError(format!(
+                "Feed URL must have a domain: {}",
+                url
+            )));
+        }
+        Ok(Self {
+            site_name: site_name.to_string(),
+            url,
+            feed_kind,
+            extraction: vec![ExtractionStrategy::Readability],
+            exclude_selectors: Vec::new(),
+            exempt_selectors: Vec::new(),
+            category: None,
+            priority: None,
+            allowed_domains: Vec::new(),
+            request_policy: RequestPolicy::default(),
+            sitemap: None,
+        })
+    }
+
+    /// 本文を抽出する CSS セレクタ．未指定の場合は Readability 風の抽出を使う．
+    pub fn with_content_selector(mut self, selector: impl Into<String>) -> Self {
+        self.extraction = vec![ExtractionStrategy::CssSelector {
+            selectors: vec![selector.into()],
+        }];
+        self
+    }
+
+    /// 本文を抽出する CSS セレクタを優先順に指定する（ページ構造の変更に備えたフォールバック）．
+    /// 空の場合は Readability 風の抽出を使う．
+    pub fn with_content_selectors(self, selectors: Vec<String>) -> Self {
+        if selectors.is_empty() {
+            return self.with_extraction(vec![ExtractionStrategy::Readability]);
+        }
+        self.with_extraction(vec![ExtractionStrategy::CssSelector { selectors }])
+    }
+
+    /// 本文の抽出方法を優先順に指定する
+    pub fn with_extraction(mut self, strategies: Vec<ExtractionStrategy>) -> Self {
+        self.extraction = strategies;
+        self
+    }
+
+    /// 共通の除外セレクタに加えて除去するセレクタ
+    pub fn with_exclude_selectors(mut self, selectors: Vec<String>) -> Self {
+        self.exclude_selectors = selectors;
+        self
+    }
+
+    /// 共通の除外セレクタのうち，このサイトでは適用しないもの
+    pub fn with_exempt_selectors(mut self, selectors: Vec<String>) -> Self {
+        self.exempt_selectors = selectors;
+        self
+    }
+
+    /// 公平なスケジューリングに使うカテゴリ．未指定の場合はサイト名から推定する．
+    pub fn with_category(mut self, category: SiteCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// 取得順と時間予算の扱いを決める優先度．未指定の場合はサイト名から推定する．
+    pub fn with_priority(mut self, priority: SitePriority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// フィードのドメインに加えて記事の取得を許可するドメイン（フィード配信サービスを使う場合など）
+    pub fn with_allowed_domains(mut self, domains: Vec<String>) -> Self {
+        self.allowed_domains = domains;
+        self
+    }
+
+    /// 記事・フィードの取得に使うタイムアウト・User-Agent・Accept-Language・追加ヘッダー
+    pub fn with_request_policy(mut self, policy: RequestPolicy) -> Self {
+        self.request_policy = policy;
+        self
+    }
+
+    /// フィードを取得・解析できない場合に使うサイトマップ（`sitemap.xml` / ニュースサイトマップ）
+    pub fn with_sitemap(mut self, url: &str) -> AppResult<Self> {
+        self.sitemap = Some(Url::parse(url)?);
+        Ok(self)
+    }
+
+    pub fn feed_kind(&self) -> FeedKind {
+        self.feed_kind
+    }
+
+    fn article(&self, title: &str, link: &str, description: Option<&String>, date: DateTime<FixedOffset>) -> WebArticle {
+        WebArticle::new(
+            self.site_name(),
+            self.site_url().to_string(),
+            title.to_string(),
+            link.to_string(),
+            description.cloned().unwrap_or_default(),
+            date.into(),
+        )
+    }
+
+    async fn fetch_feed(&self, cookies: &str) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), cookies).await?;
+        self.parse_feed(response.text().await?.as_str())
+    }
+
+    /// フィード本文から記事一覧を組み立てる
+    pub fn parse_feed(&self, body: &str) -> AppResult<Vec<WebArticle>> {
+        let parse_error = |e: feed_parser::parsers::errors::ParseError| AppError::FeedParse {
+            site: self.site_name(),
+            kind: self.feed_kind,
+            message: e.to_string(),
+        };
+        match self.feed_kind {
+            FeedKind::Rss1 => parsers::rss1::parse(body)
+                .map_err(parse_error)?
+                .iter()
+                .map(|feed| {
+                    let date = parse_feed_date(feed.date.as_deref(), "date")?;
+                    Ok(self.article(&feed.title, &feed.link, feed.description.as_ref(), date))
+                })
+                .collect(),
+            FeedKind::Rss2 => parsers::rss2::parse(body)
+                .map_err(parse_error)?
+                .iter()
+                .map(|feed| {
+                    let date = parse_feed_date(feed.publish_date.as_deref(), "publish_date")?;
+                    Ok(self.article(&feed.title, &feed.link, feed.description.as_ref(), date))
+                })
+                .collect(),
+            FeedKind::Atom => parsers::atom::parse(body)
+                .map_err(parse_error)?
+                .iter()
+                .map(|feed| {
+                    let date = parse_feed_date(
+                        feed.publish_date.as_deref().or(feed.updated.as_deref()),
+                        "publish_date",
+                    )?;
+                    Ok(self.article(&feed.title, &feed.link, feed.description.as_ref(), date))
+                })
+                .collect(),
+            FeedKind::Json => Err(AppError::FeedParse {
+                site: self.site_name(),
+                kind: FeedKind::Json,
+                message: "JSON APIs are not supported as generic feeds".into(),
+            }),
+        }
+    }
+}
+
+/// 組み込みのフィードサイトをデータとして宣言するための定義
+#[derive(Debug, Clone, Copy)]
+pub struct FeedSiteSpec {
+    pub site_name: &'static str,
+    pub url: &'static str,
+    pub feed: FeedKind,
+    /// 本文セレクタ（優先順）
+    pub content_selectors: &'static [&'static str],
+    /// セレクタで見つからない場合に試す抽出方法
+    pub fallback: &'static [FallbackExtraction],
+}
+
+/// `FeedSiteSpec` で定数として宣言できる抽出方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackExtraction {
+    Readability,
+    JsonLd,
+}
+
+impl From<FallbackExtraction> for ExtractionStrategy {
+    fn from(fallback: FallbackExtraction) -> Self {
+        match fallback {
+            FallbackExtraction::Readability => ExtractionStrategy::Readability,
+            FallbackExtraction::JsonLd => ExtractionStrategy::JsonLd,
+        }
+    }
+}
+
+impl FeedSiteSpec {
+    pub fn build(&self) -> GenericFeedSite {
+        GenericFeedSite::new(self.site_name, self.url, self.feed)
+            .expect("built-in feed URL must be valid")
+            .with_extraction(self.extraction())
+    }
+
+    pub fn extraction(&self) -> Vec<ExtractionStrategy> {
+        let selectors = (!self.content_selectors.is_empty()).then(|| ExtractionStrategy::css(self.content_selectors));
+        selectors
+            .into_iter()
+            .chain(self.fallback.iter().map(|&fallback| fallback.into()))
+            .collect()
+    }
+}
+
+/// RFC 2822（RSS 2.0）と RFC 3339（RSS 1.0 / Atom）の両方の日付形式を受け付ける
+fn parse_feed_date(value: Option<&str>, field: &str) -> AppResult<DateTime<FixedOffset>> {
+    let value = value.ok_or_else(|| AppError::DateParse(format!("Missing {}", field)))?;
+    DateTime::parse_from_rfc2822(value)
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .map_err(AppError::DateTimeParseError)
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for GenericFeedSite {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    fn category(&self) -> SiteCategory {
+        self.category.unwrap_or_else(|| SiteCategory::from_site_name(&self.site_name))
+    }
+    fn allowed_domains(&self) -> Vec<String> {
+        let mut domains = default_allowed_domains(&self.site_name, &self.domain());
+        domains.extend(self.allowed_domains.iter().cloned());
+        domains
+    }
+    fn priority(&self) -> SitePriority {
+        self.priority.unwrap_or_else(|| SitePriority::from_site_name(&self.site_name))
+    }
+    fn request_policy(&self) -> RequestPolicy {
+        self.request_policy.clone()
+    }
+    fn clean_content(&self, html: &str) -> String {
+        let additional: Vec<&str> = self.exclude_selectors.iter().map(String::as_str).collect();
+        let exempt: Vec<&str> = self.exempt_selectors.iter().map(String::as_str).collect();
+        clean_html_with_rules(html, &additional, &exempt)
+    }
+    fn select_clean(&self, html: &str, selector: &str) -> AppResult<Vec<String>> {
+        let additional: Vec<&str> = self.exclude_selectors.iter().map(String::as_str).collect();
+        let exempt: Vec<&str> = self.exempt_selectors.iter().map(String::as_str).collect();
+        let excludes = effective_exclude_selectors(&additional, &exempt);
+        select_clean_fragments(html, selector, &excludes)
+    }
+    fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+        self.extraction.clone()
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        self.get_articles_with(&FetchOptions::default()).await
+    }
+    async fn get_articles_with(&mut self, options: &FetchOptions) -> AppResult<Vec<WebArticle>> {
+        let cookies = self.login().await?;
+        match (self.fetch_feed(&cookies).await, &self.sitemap) {
+            (Ok(articles), _) => Ok(options.apply(articles)),
+            (Err(e), Some(sitemap_url)) => {
+                tracing::warn!("Falling back to sitemap for {}: {}", self.site_name, e);
+                sitemap::fetch_articles(self, sitemap_url, options).await
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let cookies = self.login().await?;
+        let body = self.request_article_page(url, &cookies).await?;
+        self.extract_content(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS2: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"><channel><title>Example</title><link>https://example.com/</link><description>Example</description>
+<item><title>Hello</title><link>https://example.com/hello</link><description>First post</description><pubDate>Tue, 14 Oct 2025 09:00:00 +0900</pubDate></item>
+</channel></rss>"#;
+
+    #[test]
+    fn test_generic_feed_site_parse_rss2() {
+        let site = GenericFeedSite::new("Example", "https://example.com/feed", FeedKind::Rss2).unwrap();
+        let articles = site.parse_feed(RSS2).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].title, "Hello");
+        assert_eq!(articles[0].article_url, "https://example.com/hello");
+        assert_eq!(articles[0].site.name, "Example");
+    }
+
+    #[test]
+    fn test_generic_feed_site_extract_content() {
+        let site = GenericFeedSite::new("Example", "https://example.com/feed", FeedKind::Rss2)
+            .unwrap()
+            .with_content_selector("article")
+            .with_exclude_selectors(vec![".promo".to_string()]);
+        let html = r#"<html><body><article><p>Body text</p><div class="promo">Buy</div><nav>Menu</nav></article></body></html>"#;
+        let (html, text) = site.extract_content(html).unwrap();
+        assert!(html.contains("Body text"));
+        assert!(!html.contains("Buy"));
+        assert!(!html.contains("Menu"));
+        assert!(text.contains("Body text"));
+    }
+
+    #[test]
+    fn test_generic_feed_site_content_selector_fallback() {
+        let site = FeedSiteSpec {
+            site_name: "Example",
+            url: "https://example.com/feed",
+            feed: FeedKind::Rss2,
+            content_selectors: &["div.page-content", "main div.legacy-body"],
+            fallback: &[],
+        }
+        .build();
+        let html = r#"<html><body><main><div class="legacy-body"><p>Old layout</p></div></main></body></html>"#;
+        let (html, _) = site.extract_content(html).unwrap();
+        assert!(html.contains("Old layout"));
+        assert!(matches!(
+            site.extract_content("<html><body></body></html>"),
+            Err(AppError::SelectorNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_feed_site_spec_fallback_extraction() {
+        let spec = FeedSiteSpec {
+            site_name: "Example",
+            url: "https://example.com/feed",
+            feed: FeedKind::Rss2,
+            content_selectors: &["div.page-content"],
+            fallback: &[FallbackExtraction::JsonLd],
+        };
+        assert_eq!(
+            spec.extraction().iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["css_selector(div.page-content)", "json_ld"]
+        );
+        let html = r#"<html><head><script type="application/ld+json">{"@type": "BlogPosting", "articleBody": "From JSON-LD"}</script></head><body></body></html>"#;
+        let (html, text) = spec.build().extract_content(html).unwrap();
+        assert!(html.contains("<p>From JSON-LD</p>"));
+        assert_eq!(text.trim(), "From JSON-LD");
+    }
+
+    #[test]
+    fn test_generic_feed_site_allowed_domains() {
+        let site = GenericFeedSite::new("Example", "https://feeds.example.com/rss", FeedKind::Rss2)
+            .unwrap()
+            .with_allowed_domains(vec!["cdn.example.org".to_string()]);
+        let check = |url: &str| site.ensure_allowed_url(&Url::parse(url).unwrap());
+        assert!(check("https://www.example.com/posts/1").is_ok());
+        assert!(check("https://cdn.example.org/posts/2").is_ok());
+        assert!(matches!(check("https://evil.example.net/"), Err(AppError::DisallowedDomain(_))));
+    }
+
+    #[test]
+    fn test_generic_feed_site_invalid_url() {
+        assert!(GenericFeedSite::new("Broken", "not a url", FeedKind::Atom).is_err());
+        let site = GenericFeedSite::new("Example", "https://example.com/feed", FeedKind::Rss2).unwrap();
+        assert!(site.clone().with_sitemap("https://example.com/sitemap.xml").is_ok());
+        assert!(site.with_sitemap("sitemap.xml").is_err());
+        assert!(GenericFeedSite::new("Example", "https://example.com/api", FeedKind::Json).is_err());
+    }
+
+    #[test]
+    fn test_parse_feed_date_formats() {
+        assert!(parse_feed_date(Some("Tue, 14 Oct 2025 09:00:00 +0900"), "date").is_ok());
+        assert!(parse_feed_date(Some("2025-10-14T09:00:00+09:00"), "date").is_ok());
+        assert!(matches!(parse_feed_date(None, "date"), Err(AppError::DateParse(_))));
+    }
+}
@@ -0,0 +1,22 @@
+use super::macros::define_rss_site;
+
+define_rss_site! {
+    pub struct HatenaDeveloperBlog {
+        site_name: "Hatena Developer Blog",
+        url: "https://developer.hatenastaff.com/rss",
+        feed: rss2,
+        date: publish_date => parse_from_rfc2822,
+        content: first_match("#main article div.entry-content"),
+    }
+    impl {
+        /// はてなブログ固有の除外セレクタ
+        fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+            vec![
+                // 記事末尾のシェアボタン・関連記事
+                ".entry-footer-section",
+                ".hatena-star-container",
+                ".related-entries",
+            ]
+        }
+    }
+}
@@ -1,97 +1,11 @@
-use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
-use chrono::DateTime;
-use feed_parser::parsers;
-use request::Url;
-use crate::shared::{
-    errors::{AppError, AppResult},
-};
-
-const URL: &str = "https://www.ipa.go.jp/security/rss/alert.rdf";
-
-#[derive(Debug, Clone)]
-pub struct IPASecurityCenter {
-    site_name: String,
-    url: Url,
-}
-
-impl IPASecurityCenter {
-    pub fn new() -> Self {
-        Self {
-            site_name: "IPA Security Center".to_string(),
-            url: Url::parse(URL).unwrap(),
-        }
-    }
-}
-
-impl Default for IPASecurityCenter {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[async_trait::async_trait]
-impl WebSiteInterface for IPASecurityCenter {
-
-    fn site_name(&self) -> String {
-        self.site_name.clone()
-    }
-    fn site_url(&self) -> Url {
-        self.url.clone()
-    }
-    fn domain(&self) -> String {
-        self.url.domain().unwrap().to_string()
-    }
-
-    async fn login(&mut self) -> AppResult<Cookie> {
-        Ok(Cookie::default())
-    }
-    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
-        let cookies = self.login().await?;
-        let response = self.request(self.url.as_str(), &cookies).await?;
-        let feeds = match parsers::rss1::parse(response.text().await?.as_str()) {
-            Ok(feeds) => feeds,
-            Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS feed: {}", e)));
-            }
-        };
-        let articles = feeds
-            .iter()
-            .map(|feed| -> AppResult<WebArticle> {
-                let date = feed
-                    .date
-                    .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing date".into()))?;
-                Ok(WebArticle::new(
-                    self.site_name(),
-                    self.site_url().to_string(),
-                    feed.title.clone(),
-                    feed.link.clone(),
-                    feed.description.clone().unwrap_or("".to_string()),
-                    DateTime::parse_from_rfc3339(&date)?.into(),
-                ))
-            })
-            .collect::<AppResult<Vec<WebArticle>>>()?;
-        Ok(articles)
-    }
-    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
-        let url = Url::parse(url).unwrap();
-        let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector =
-            scraper::Selector::parse("div.news-detail main h1.ttl,h2.ttl,p.article-txt,span.list__item__txt").unwrap();
-        let article = match document.select(&selector).next() {
-            Some(article) => article,
-            None => {
-                return Err(AppError::ScrapeError(
-                    "Failed to parse article: div.news-detail main h1.ttl,h2.ttl,p.article-txt,span.list__item__txt"
-                        .to_string(),
-                ));
-            }
-        };
-        let raw_html = article.html().to_string();
-        let html = self.clean_content(&raw_html);
-        let text = html2md::rewrite_html(&html, false);
-        Ok((self.trim_text(&html), self.trim_text(&text)))
+use super::macros::define_rss_site;
+
+define_rss_site! {
+    pub struct IPASecurityCenter {
+        site_name: "IPA Security Center",
+        url: "https://www.ipa.go.jp/security/rss/alert.rdf",
+        feed: rss1,
+        date: date => parse_from_rfc3339,
+        content: first_match("div.news-detail main h1.ttl,h2.ttl,p.article-txt,span.list__item__txt"),
     }
 }
@@ -1,3 +1,4 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use chrono::DateTime;
 use feed_parser::parsers;
@@ -5,8 +6,11 @@ use request::Url;
 use crate::shared::{
     errors::{AppError, AppResult},
 };
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://rss.itmedia.co.jp/rss/2.0/enterprise.xml";
+/// 記事本文の段落
+const CONTENT_SELECTOR: &str = "#cmsBody div.inner p";
 
 #[derive(Debug, Clone)]
 pub struct ITMediaEnterprise {
@@ -62,7 +66,11 @@ impl WebSiteInterface for ITMediaEnterprise {
         let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
             Ok(feeds) => feeds,
             Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS feed: {}", e)));
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
             }
         };
         let articles = feeds
@@ -71,7 +79,7 @@ impl WebSiteInterface for ITMediaEnterprise {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -90,23 +98,16 @@ impl WebSiteInterface for ITMediaEnterprise {
         }
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = match scraper::Selector::parse("#cmsBody div.inner p") {
-            Ok(selector) => selector,
-            Err(e) => {
-                return Err(AppError::ScrapeError(format!(
-                    "Failed to parse selector (#cmsBody div.inner p): {}",
-                    e
-                )));
-            }
-        };
+        let body = self.request_article_page(url.as_str(), &cookies).await?;
+        let document = scraper::Html::parse_document(&body);
+        let selector = selector!(CONTENT_SELECTOR);
         let article = match document.select(&selector).next() {
             Some(article) => article,
             None => {
-                return Err(AppError::ScrapeError(format!(
-                    "Failed to find article with selector (#cmsBody div.inner p)"
-                )));
+                return Err(AppError::SelectorNotFound {
+                    site: self.site_name(),
+                    selector: CONTENT_SELECTOR.into(),
+                });
             }
         };
         let raw_html = article.html().to_string();
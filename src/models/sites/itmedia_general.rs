@@ -1,3 +1,4 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use chrono::DateTime;
 use feed_parser::parsers;
@@ -5,8 +6,11 @@ use request::Url;
 use crate::shared::{
     errors::{AppError, AppResult},
 };
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://rss.itmedia.co.jp/rss/2.0/itmedia_all.xml";
+/// 記事本文の段落
+const CONTENT_SELECTOR: &str = "#cmsBody div.inner p";
 
 #[derive(Debug, Clone)]
 pub struct ITMediaGeneral {
@@ -70,10 +74,11 @@ impl WebSiteInterface for ITMediaGeneral {
         let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
             Ok(feeds) => feeds,
             Err(e) => {
-                return Err(AppError::ScrapeError(format!(
-                    "Failed to parse RSS feed: {}",
-                    e
-                )));
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
             }
         };
         let articles = feeds
@@ -82,7 +87,7 @@ impl WebSiteInterface for ITMediaGeneral {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -98,28 +103,19 @@ impl WebSiteInterface for ITMediaGeneral {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let raw = response.text().await?;
+        let raw = self.request_text(url.as_str(), &cookies).await?;
         if crate::models::web_article::detect_login_required(&raw) {
             return Err(AppError::LoginRequired);
         }
-        let document = scraper::Html::parse_document(raw.as_str());
-        let selector = match scraper::Selector::parse("#cmsBody div.inner p") {
-            Ok(selector) => selector,
-            Err(e) => {
-                return Err(AppError::ScrapeError(format!(
-                    "Failed to parse selector (#cmsBody div.inner p): {}",
-                    e
-                )));
-            }
-        };
+        let document = scraper::Html::parse_document(&raw);
+        let selector = selector!(CONTENT_SELECTOR);
         let article = match document.select(&selector).next() {
             Some(article) => article,
             None => {
-                return Err(AppError::ScrapeError(format!(
-                    "Failed to find article element with selector: {:?}",
-                    selector
-                )));
+                return Err(AppError::SelectorNotFound {
+                    site: self.site_name(),
+                    selector: CONTENT_SELECTOR.into(),
+                });
             }
         };
         let raw_html = article.html().to_string();
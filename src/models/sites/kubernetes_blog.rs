@@ -0,0 +1,67 @@
+//! Kubernetes の公式ブログ．
+//!
+//! 記事の多くは YAML やコマンドのコードブロックを含むため，行番号とコピーボタンを除いてコードブロックをそのまま残す．
+
+use super::macros::define_rss_site;
+use crate::models::extraction::{CODE_BLOCK_NOISE_SELECTORS, ExtractionStrategy};
+use crate::models::web_article::{Html, Text, WebSiteInterface};
+use crate::shared::errors::AppResult;
+
+define_rss_site! {
+    pub struct KubernetesBlog {
+        site_name: "Kubernetes Blog",
+        url: "https://kubernetes.io/feed.xml",
+        feed: rss2,
+        date: publish_date => parse_from_rfc2822,
+    }
+    impl {
+        fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+            vec![ExtractionStrategy::css(&["main div.td-content", "div.td-content"]), ExtractionStrategy::Readability]
+        }
+
+        /// Kubernetes ブログ固有の除外セレクタ
+        fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+            let mut selectors = CODE_BLOCK_NOISE_SELECTORS.to_vec();
+            selectors.extend([
+                // 記事の日付・著者の見出し（メタデータとして別に取得する）
+                "div.td-byline",
+                // フィードバックの欄
+                ".feedback--prompt",
+                ".td-page-meta",
+            ]);
+            selectors
+        }
+
+        async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+            let body = self.request_article_page(url, "").await?;
+            self.extract_content(&body)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_content_keeps_code_blocks() {
+        let page = r#"<html><body><main><div class="td-content">
+<h1>Kubernetes v1.34: Example feature</h1>
+<div class="td-byline">By Example Author | Monday, October 06, 2025</div>
+<p>Apply the following manifest:</p>
+<div class="highlight"><pre class="chroma"><code class="language-yaml"><span class="line"><span class="ln">1</span><span class="cl">kind: Pod
+</span></span><span class="line"><span class="ln">2</span><span class="cl">metadata:
+</span></span><span class="line"><span class="ln">3</span><span class="cl">  name: demo
+</span></span></code></pre></div>
+<p>That's it.</p>
+</div></main></body></html>"#;
+        let (html, text) = KubernetesBlog::default().extract_content(page).unwrap();
+        assert!(html.contains("<pre"));
+        assert!(!html.contains(r#"class="ln""#));
+        assert!(!html.contains("td-byline"));
+        assert!(text.contains("kind: Pod"));
+        assert!(!text.contains("1kind: Pod"));
+        assert!(text.contains("  name: demo"));
+        assert!(text.contains("That's it."));
+    }
+}
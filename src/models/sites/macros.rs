@@ -0,0 +1,296 @@
+//! サイト実装の定型コード（コンストラクタ，`Default`，ゲッター，`login`，フィード変換）を生成するマクロ．
+//!
+//! ```ignore
+//! define_rss_site! {
+//!     pub struct Gizmodo {
+//!         site_name: "Gizmodo",
+//!         url: "https://www.gizmodo.jp/index.xml",
+//!         feed: rss2,
+//!         date: publish_date => parse_from_rfc2822,
+//!         content: first_match("main article div.p-post-content"),
+//!     }
+//! }
+//! ```
+//!
+//! `content` を省略した場合や，`get_articles` を持たない `define_scraped_site!` では，
+//! 末尾の `impl { ... }` に `WebSiteInterface` のメソッドを記述する．
+//! `impl { ... }` に書いたメソッドは生成される trait 実装にそのまま追加される．
+
+use crate::models::web_article::{Html, Text, WebSiteInterface};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::selectors::cached_selector;
+
+/// セレクタに最初にマッチした要素を本文として抽出し，不要要素を除去する（1回の DOM パースで行う）
+pub fn first_match<S: WebSiteInterface + ?Sized>(
+    site: &S,
+    body: &str,
+    selector: &str,
+) -> AppResult<(Html, Text)> {
+    match site.select_clean(body, selector)?.into_iter().next() {
+        Some(html) => {
+            let text = html2md::rewrite_html(&html, false);
+            Ok((site.trim_text(&html), site.trim_text(&text)))
+        }
+        None => Err(AppError::SelectorNotFound {
+            site: site.site_name(),
+            selector: selector.to_string(),
+        }),
+    }
+}
+
+/// セレクタにマッチした全要素（段落など）を連結して本文とする
+pub fn all_matches<S: WebSiteInterface + ?Sized>(
+    site: &S,
+    body: &str,
+    selector: &str,
+) -> AppResult<(Html, Text)> {
+    let document = scraper::Html::parse_document(body);
+    let sel = cached_selector(selector)?;
+    let html = document
+        .select(sel)
+        .map(|x| x.html())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = html2md::rewrite_html(&html, false);
+    Ok((site.trim_text(&html), site.trim_text(&text)))
+}
+
+/// 構造体・コンストラクタ・`Default` と `WebSiteInterface` の共通メソッドを生成する（内部用）
+macro_rules! define_site_common {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            site_name: $site_name:literal,
+            url: $url:literal,
+        }
+        { $($methods:tt)* }
+    ) => {
+        const URL: &str = $url;
+
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        $vis struct $name {
+            site_name: String,
+            url: ::request::Url,
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                Self {
+                    site_name: $site_name.to_string(),
+                    url: ::request::Url::parse(URL).unwrap(),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::models::web_article::WebSiteInterface for $name {
+            fn site_name(&self) -> String {
+                self.site_name.clone()
+            }
+            fn site_url(&self) -> ::request::Url {
+                self.url.clone()
+            }
+            fn domain(&self) -> String {
+                self.url.domain().unwrap().to_string()
+            }
+            $($methods)*
+        }
+    };
+}
+
+/// RSS / Atom フィードから記事一覧を取得するサイトを定義する
+macro_rules! define_rss_site {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            site_name: $site_name:literal,
+            url: $url:literal,
+            feed: $feed:ident,
+            date: $date_field:ident => $date_parser:ident,
+            $(content: $mode:ident($selector:literal),)?
+        }
+        $(impl { $($extra:tt)* })?
+    ) => {
+        $crate::models::sites::macros::define_site_common! {
+            $(#[$meta])*
+            $vis struct $name {
+                site_name: $site_name,
+                url: $url,
+            }
+            {
+                async fn login(&mut self) -> $crate::shared::errors::AppResult<$crate::models::web_article::Cookie> {
+                    Ok($crate::models::web_article::Cookie::default())
+                }
+                async fn get_articles(
+                    &mut self,
+                ) -> $crate::shared::errors::AppResult<Vec<$crate::models::web_article::WebArticle>> {
+                    use $crate::models::web_article::{WebArticle, WebSiteInterface as _};
+                    use $crate::shared::errors::{AppError, AppResult};
+
+                    let cookies = self.login().await?;
+                    let response = self.request(self.url.as_str(), &cookies).await?;
+                    let feeds = match feed_parser::parsers::$feed::parse(response.text().await?.as_str()) {
+                        Ok(feeds) => feeds,
+                        Err(e) => {
+                            return Err(AppError::FeedParse {
+                                site: self.site_name(),
+                                kind: stringify!($feed)
+                                    .parse()
+                                    .expect("feed parser modules are named after FeedKind"),
+                                message: e.to_string(),
+                            });
+                        }
+                    };
+                    feeds
+                        .iter()
+                        .map(|feed| -> AppResult<WebArticle> {
+                            let date = feed.$date_field.clone().ok_or_else(|| {
+                                AppError::DateParse(format!("Missing {}", stringify!($date_field)))
+                            })?;
+                            Ok(WebArticle::new(
+                                self.site_name(),
+                                self.site_url().to_string(),
+                                feed.title.clone(),
+                                feed.link.clone(),
+                                feed.description.clone().unwrap_or_default(),
+                                chrono::DateTime::$date_parser(&date)?.into(),
+                            ))
+                        })
+                        .collect::<AppResult<Vec<WebArticle>>>()
+                }
+                $(
+                    async fn parse_article(
+                        &mut self,
+                        url: &str,
+                    ) -> $crate::shared::errors::AppResult<(
+                        $crate::models::web_article::Html,
+                        $crate::models::web_article::Text,
+                    )> {
+                        use $crate::models::web_article::WebSiteInterface as _;
+
+                        let article = self.extract_article(url).await?;
+                        Ok((article.html, article.text))
+                    }
+                    async fn extract_article(
+                        &mut self,
+                        url: &str,
+                    ) -> $crate::shared::errors::AppResult<$crate::models::extraction::ExtractedArticle> {
+                        use $crate::models::web_article::WebSiteInterface as _;
+
+                        let cookies = self.login().await?;
+                        let body = self.request_article_page(url, &cookies).await?;
+                        let (html, text) = $crate::models::sites::macros::$mode(self, &body, $selector)?;
+                        Ok($crate::models::extraction::ExtractedArticle::from_page(&body, html, text))
+                    }
+                )?
+                $($($extra)*)?
+            }
+        }
+    };
+}
+
+/// HTML の一覧ページから記事を収集するサイトを定義する．`get_articles` は `impl { ... }` に記述する．
+macro_rules! define_scraped_site {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            site_name: $site_name:literal,
+            url: $url:literal,
+            $(content: $mode:ident($selector:literal),)?
+        }
+        impl { $($extra:tt)* }
+    ) => {
+        $crate::models::sites::macros::define_site_common! {
+            $(#[$meta])*
+            $vis struct $name {
+                site_name: $site_name,
+                url: $url,
+            }
+            {
+                async fn login(&mut self) -> $crate::shared::errors::AppResult<$crate::models::web_article::Cookie> {
+                    Ok($crate::models::web_article::Cookie::default())
+                }
+                $(
+                    async fn parse_article(
+                        &mut self,
+                        url: &str,
+                    ) -> $crate::shared::errors::AppResult<(
+                        $crate::models::web_article::Html,
+                        $crate::models::web_article::Text,
+                    )> {
+                        use $crate::models::web_article::WebSiteInterface as _;
+
+                        let article = self.extract_article(url).await?;
+                        Ok((article.html, article.text))
+                    }
+                    async fn extract_article(
+                        &mut self,
+                        url: &str,
+                    ) -> $crate::shared::errors::AppResult<$crate::models::extraction::ExtractedArticle> {
+                        use $crate::models::web_article::WebSiteInterface as _;
+
+                        let cookies = self.login().await?;
+                        let body = self.request_article_page(url, &cookies).await?;
+                        let (html, text) = $crate::models::sites::macros::$mode(self, &body, $selector)?;
+                        Ok($crate::models::extraction::ExtractedArticle::from_page(&body, html, text))
+                    }
+                )?
+                $($extra)*
+            }
+        }
+    };
+}
+
+pub(crate) use define_rss_site;
+pub(crate) use define_scraped_site;
+pub(crate) use define_site_common;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    define_rss_site! {
+        /// テスト用のフィードサイト
+        pub struct MacroTestSite {
+            site_name: "Macro Test Site",
+            url: "https://macro-test.example.com/feed",
+            feed: rss2,
+            date: publish_date => parse_from_rfc2822,
+            content: first_match("article div.body"),
+        }
+    }
+
+    #[test]
+    fn test_define_rss_site_generates_boilerplate() {
+        let site = MacroTestSite::default();
+        assert_eq!(site.site_name(), "Macro Test Site");
+        assert_eq!(site.domain(), "macro-test.example.com");
+        assert_eq!(site.site_url().as_str(), "https://macro-test.example.com/feed");
+    }
+
+    #[test]
+    fn test_first_match_and_all_matches() {
+        let site = MacroTestSite::new();
+        let html = r#"<html><body><article><div class="body"><p>first</p><p>second</p></div></article></body></html>"#;
+
+        let (html_out, text) = first_match(&site, html, "article div.body").unwrap();
+        assert!(html_out.contains("first") && html_out.contains("second"));
+        assert!(text.contains("first"));
+
+        let (html_out, _) = all_matches(&site, html, "article div.body p").unwrap();
+        assert!(html_out.contains("<p>first</p>"));
+        assert!(html_out.contains("<p>second</p>"));
+
+        assert!(matches!(
+            first_match(&site, html, "div.missing"),
+            Err(AppError::SelectorNotFound { selector, .. }) if selector == "div.missing"
+        ));
+    }
+}
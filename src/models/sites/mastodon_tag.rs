@@ -0,0 +1,261 @@
+//! Mastodon のハッシュタグのタイムライン．
+//!
+//! サイト設定ファイルの `[[mastodon]]` で指定したインスタンスの `GET /api/v1/timelines/tag/<タグ>` から投稿を取得し，
+//! リンクを含む投稿をリンク先の記事にする（リンクの無い投稿は除く）．
+//! 記事のタイトルはリンクのプレビュー（`card`）のタイトル，概要は投稿の本文で，本文はリンク先のページから抽出する．
+//! リンク先はハッシュタグに投稿できる誰もが指定できるため，記事のページは SSRF 対策を施したクライアント
+//! （`shared::ssrf::fetch_untrusted_text`）で取得し，内部向けのアドレスには接続せず，大きすぎるページは読み込まない．
+
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::domain_guard::ANY_DOMAIN;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::selectors::selector;
+use crate::shared::ssrf::{MAX_UNTRUSTED_BODY_BYTES, fetch_untrusted_text};
+use crate::shared::text::truncate_text;
+use chrono::DateTime;
+use request::Url;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// 1回の一覧で取得する投稿数（API の上限）
+const LIMIT: usize = 40;
+/// リンクのプレビューが無い場合にタイトルにする投稿の先頭の最大文字数
+const TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Deserialize)]
+struct MastodonAccount {
+    acct: String,
+    #[serde(default)]
+    display_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MastodonCard {
+    url: String,
+    #[serde(default)]
+    title: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MastodonStatus {
+    /// 投稿日時（RFC 3339）
+    created_at: String,
+    /// 投稿の本文（HTML）
+    #[serde(default)]
+    content: String,
+    account: MastodonAccount,
+    #[serde(default)]
+    card: Option<MastodonCard>,
+}
+
+impl MastodonAccount {
+    /// 表示名（未設定の場合はアカウント名）
+    fn display_name(&self) -> String {
+        let name = self.display_name.trim();
+        if name.is_empty() {
+            self.acct.clone()
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// 投稿の本文のテキスト
+fn status_text(content: &str) -> String {
+    let fragment = scraper::Html::parse_fragment(content);
+    fragment
+        .select(selector!("p"))
+        .map(|p| p.text().collect::<String>().trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 投稿のリンク（プレビューのリンク，無ければメンション・ハッシュタグ以外の最初のリンク）
+fn status_link(status: &MastodonStatus) -> Option<Url> {
+    if let Some(card) = &status.card {
+        return Url::parse(&card.url).ok();
+    }
+    let fragment = scraper::Html::parse_fragment(&status.content);
+    fragment
+        .select(selector!("a[href]"))
+        .filter(|a| !a.value().classes().any(|c| c == "mention" || c == "hashtag"))
+        .filter(|a| a.value().attr("rel").is_none_or(|rel| !rel.split_whitespace().any(|r| r == "tag")))
+        .find_map(|a| Url::parse(a.value().attr("href")?).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// タイムラインのレスポンスを記事にする．同じリンクの投稿は最初（最新）のものだけを使う．
+fn parse_timeline(site_name: &str, site_url: &str, json: &str) -> AppResult<Vec<WebArticle>> {
+    let statuses: Vec<MastodonStatus> = serde_json::from_str(json)
+        .map_err(|e| AppError::FeedParse {
+            site: site_name.to_string(),
+            kind: FeedKind::Json,
+            message: format!("Mastodon statuses: {}", e),
+        })?;
+    let mut seen = HashSet::new();
+    let mut articles = Vec::new();
+    for status in statuses {
+        let Some(link) = status_link(&status) else {
+            continue;
+        };
+        if !seen.insert(link.to_string()) {
+            continue;
+        }
+        let text = status_text(&status.content);
+        let title = status
+            .card
+            .as_ref()
+            .map(|card| card.title.trim().to_string())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| truncate_text(&text, TITLE_CHARS));
+        let mut article = WebArticle::new(
+            site_name.to_string(),
+            site_url.to_string(),
+            title,
+            link.to_string(),
+            text,
+            DateTime::parse_from_rfc3339(&status.created_at)?.into(),
+        );
+        article.author = Some(status.account.display_name());
+        articles.push(article);
+    }
+    Ok(articles)
+}
+
+#[derive(Debug, Clone)]
+pub struct MastodonTag {
+    site_name: String,
+    url: Url,
+    pub instance: String,
+    pub hashtag: String,
+}
+
+impl MastodonTag {
+    /// `instance`（`mastodon.social` など）の `hashtag`（`#` は不要）の投稿を取得する
+    pub fn new(instance: &str, hashtag: &str) -> AppResult<Self> {
+        let instance = instance.trim();
+        let hashtag = hashtag.trim().trim_start_matches('#');
+        if instance.is_empty() || instance.contains('/') || hashtag.is_empty() {
+            return Err(AppError::InternalError(format!(
+                "Invalid Mastodon hashtag timeline: {} #{}",
+                instance, hashtag
+            )));
+        }
+        let mut url = Url::parse(&format!("https://{}/api/v1/timelines/tag", instance))?;
+        url.path_segments_mut().unwrap().push(hashtag);
+        url.query_pairs_mut().append_pair("limit", &LIMIT.to_string());
+        Ok(Self {
+            site_name: format!("Mastodon - #{}", hashtag),
+            url,
+            instance: instance.to_string(),
+            hashtag: hashtag.to_string(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for MastodonTag {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    /// リンク先は任意のドメインにある（取得は `parse_article` で `fetch_untrusted_text` を使う）
+    fn allowed_domains(&self) -> Vec<String> {
+        vec![ANY_DOMAIN.to_string()]
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), "").await?;
+        parse_timeline(&self.site_name(), self.site_url().as_str(), &response.text().await?)
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let (_, body) = fetch_untrusted_text(url, MAX_UNTRUSTED_BODY_BYTES).await?;
+        self.extract_content(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMELINE: &str = r#"[
+        {
+            "id": "3",
+            "created_at": "2025-10-14T12:00:00.000Z",
+            "content": "<p>Rust 1.90 is out! <a href=\"https://blog.rust-lang.org/2025/09/18/Rust-1.90.0/\" rel=\"nofollow noopener\">blog.rust-lang.org/2025/09/18/Rus</a> <a href=\"https://mastodon.social/tags/rust\" class=\"mention hashtag\" rel=\"tag\">#<span>rust</span></a></p>",
+            "account": {"acct": "alice@example.social", "display_name": "Alice"},
+            "card": {"url": "https://blog.rust-lang.org/2025/09/18/Rust-1.90.0/", "title": "Announcing Rust 1.90.0"}
+        },
+        {
+            "id": "2",
+            "created_at": "2025-10-14T11:00:00.000Z",
+            "content": "<p>Same link again <a href=\"https://blog.rust-lang.org/2025/09/18/Rust-1.90.0/\">link</a></p>",
+            "account": {"acct": "bob", "display_name": ""},
+            "card": {"url": "https://blog.rust-lang.org/2025/09/18/Rust-1.90.0/", "title": "Announcing Rust 1.90.0"}
+        },
+        {
+            "id": "1",
+            "created_at": "2025-10-14T10:00:00.000Z",
+            "content": "<p>Writing a parser <a href=\"https://example.com/parser\">example.com/parser</a></p>",
+            "account": {"acct": "bob", "display_name": ""},
+            "card": null
+        },
+        {
+            "id": "0",
+            "created_at": "2025-10-14T09:00:00.000Z",
+            "content": "<p>No link, <span class=\"h-card\"><a href=\"https://example.social/@carol\" class=\"u-url mention\">@<span>carol</span></a></span> <a href=\"https://mastodon.social/tags/rust\" class=\"mention hashtag\" rel=\"tag\">#rust</a></p>",
+            "account": {"acct": "dave", "display_name": "Dave"}
+        }
+    ]"#;
+
+    #[test]
+    fn test_parse_timeline() {
+        let site = MastodonTag::new("mastodon.social", "#rust").unwrap();
+        let articles = parse_timeline(&site.site_name(), site.site_url().as_str(), TIMELINE).unwrap();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].site.name, "Mastodon - #rust");
+        assert_eq!(articles[0].title, "Announcing Rust 1.90.0");
+        assert_eq!(articles[0].article_url, "https://blog.rust-lang.org/2025/09/18/Rust-1.90.0/");
+        assert!(articles[0].description.starts_with("Rust 1.90 is out!"));
+        assert_eq!(articles[0].author.as_deref(), Some("Alice"));
+        assert_eq!(articles[1].title, "Writing a parser example.com/parser");
+        assert_eq!(articles[1].article_url, "https://example.com/parser");
+        assert_eq!(articles[1].author.as_deref(), Some("bob"));
+
+        assert!(parse_timeline("Mastodon", site.site_url().as_str(), r#"{"error": "Not found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_site_url() {
+        let site = MastodonTag::new("mastodon.social", "rust").unwrap();
+        assert_eq!(site.site_url().as_str(), "https://mastodon.social/api/v1/timelines/tag/rust?limit=40");
+        assert_eq!(site.hashtag, "rust");
+        let external = Url::parse("https://example.com/parser").unwrap();
+        assert!(site.ensure_allowed_url(&external).is_ok());
+        assert!(MastodonTag::new("", "rust").is_err());
+        assert!(MastodonTag::new("mastodon.social", "#").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parse_article_rejects_private_addresses() {
+        let mut site = MastodonTag::new("mastodon.social", "rust").unwrap();
+        for url in [
+            "http://169.254.169.254/latest/meta-data/",
+            "http://127.0.0.1:8080/admin",
+            "http://192.168.1.1/",
+            "file:///etc/passwd",
+        ] {
+            assert!(matches!(site.parse_article(url).await, Err(AppError::BlockedUrl(_))), "{}", url);
+        }
+    }
+}
@@ -1,9 +1,41 @@
-use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface, detect_login_required};
 use request::Url;
-use scraper::Selector;
+use crate::shared::credentials::global_credentials;
 use crate::shared::errors::{AppError, AppResult};
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://medium.com/tag/{}/archive";
+/// 認証情報のファイルでのサイト名（タグごとのサイトで1つのセッションを共有する）
+pub const CREDENTIALS_SITE: &str = "Medium";
+/// 会員限定の記事を示すマーカー（小文字）
+const MEMBER_ONLY_MARKERS: &[&str] = &["member-only story", "\"islocked\":true"];
+/// 会員限定の記事の本文が途中までしか返されていないことを示すマーカー（小文字）
+const PAYWALL_MARKERS: &[&str] = &[
+    "create an account to read the full story",
+    "available to medium members only",
+    "become a member to read this story",
+    "upgrade to read this story",
+];
+
+/// 一覧・記事ページが会員限定の記事か
+pub fn is_member_only(raw_html: &str) -> bool {
+    let lower = raw_html.to_lowercase();
+    MEMBER_ONLY_MARKERS.iter().any(|marker| lower.contains(marker))
+}
+
+/// 記事ページの本文が会員向けの案内で途切れているか（未ログイン・セッション切れ）
+pub fn is_paywalled(raw_html: &str) -> bool {
+    let lower = raw_html.to_lowercase();
+    PAYWALL_MARKERS.iter().any(|marker| lower.contains(marker))
+}
+
+/// セッションの Cookie は medium.com のページにだけ付ける（独自ドメインの出版物には送らない）
+fn cookie_for<'a>(url: &Url, cookie: &'a str) -> &'a str {
+    match url.host_str() {
+        Some(host) if host == "medium.com" || host.ends_with(".medium.com") => cookie,
+        _ => "",
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Medium {
@@ -44,8 +76,10 @@ impl WebSiteInterface for Medium {
         self.url.domain().unwrap().to_string()
     }
 
+    /// 認証情報のファイルの `[sites.Medium] cookie`（ブラウザでログインしたセッションの `sid` / `uid`）を使う．
+    /// 未設定の場合は未ログインで取得し，会員限定の記事は `requires_login` とする．
     async fn login(&mut self) -> AppResult<Cookie> {
-        Ok(Cookie::default())
+        Ok(global_credentials().cookie(CREDENTIALS_SITE)?.unwrap_or_default())
     }
     async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
         let cookies = self.login().await?;
@@ -53,15 +87,16 @@ impl WebSiteInterface for Medium {
         let mut articles: Vec<WebArticle> = Vec::new();
         // parse html
         let doc = scraper::Html::parse_document(response.text().await?.as_str());
-        let sel = Selector::parse("article").unwrap();
+        let sel = selector!("article");
         for article in doc.select(&sel) {
-            let title_sel = Selector::parse("a h2").unwrap();
+            let member_only = is_member_only(&article.html());
+            let title_sel = selector!("a h2");
             let title_text = match article.select(&title_sel).next() {
                 Some(elem) => elem.text().collect::<Vec<_>>().join(""),
                 None => continue,
             };
             let mut url = Url::parse("https://medium.com").unwrap();
-            let a_sel = Selector::parse("div a").unwrap();
+            let a_sel = selector!("div a");
             let href = match article.select(&a_sel).next() {
                 Some(elem) => match elem.value().attr("href") {
                     Some(h) => h,
@@ -74,7 +109,7 @@ impl WebSiteInterface for Medium {
             } else {
                 url.set_path(href);
             }
-            let date_sel = Selector::parse("span").unwrap();
+            let date_sel = selector!("span");
 
             match article.select(&date_sel).next() {
                 Some(x) => {
@@ -90,12 +125,12 @@ impl WebSiteInterface for Medium {
                 }
             };
             let date = chrono::Local::now();
-            let desc_sel = Selector::parse("a h3").unwrap();
+            let desc_sel = selector!("a h3");
             let desc_text = match article.select(&desc_sel).next() {
                 Some(x) => x.text().collect::<Vec<_>>().join(""),
                 None => "".to_string(),
             };
-            let article = WebArticle::new(
+            let mut article = WebArticle::new(
                 self.site_name(),
                 self.site_url().to_string(),
                 title_text,
@@ -103,6 +138,7 @@ impl WebSiteInterface for Medium {
                 desc_text,
                 date.into(),
             );
+            article.requires_login = member_only && cookies.is_empty();
             articles.push(article);
         }
         Ok(articles)
@@ -111,18 +147,19 @@ impl WebSiteInterface for Medium {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url)?;
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let raw = response.text().await?;
-        if crate::models::web_article::detect_login_required(&raw) {
+        let cookies = cookie_for(&url, &cookies);
+        let raw = self.request_text(url.as_str(), cookies).await?;
+        if is_paywalled(&raw) {
+            if !cookies.is_empty() {
+                tracing::warn!("Medium session cookie was not accepted (expired or not a member): {}", url);
+            }
             return Err(AppError::LoginRequired);
         }
-        let doc = scraper::Html::parse_document(raw.as_str());
-        let sel = match Selector::parse("article") {
-            Ok(s) => s,
-            Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse selector: {}", e)));
-            }
-        };
+        if cookies.is_empty() && detect_login_required(&raw) {
+            return Err(AppError::LoginRequired);
+        }
+        let doc = scraper::Html::parse_document(&raw);
+        let sel = selector!("article");
         let (html, text) = match doc.select(&sel).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -134,3 +171,31 @@ impl WebSiteInterface for Medium {
         Ok((self.trim_text(&html), self.trim_text(&text)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_member_content_detection() {
+        let listing =
+            r#"<article><div><span>Member-only story</span><a href="/p/1"><h2>Title</h2></a></div></article>"#;
+        assert!(is_member_only(listing));
+        assert!(is_member_only(r#"<script>{"Post:1":{"isLocked":true}}</script>"#));
+        assert!(!is_member_only(r#"<article><a href="/p/2"><h2>Free story</h2></a></article>"#));
+
+        let guest =
+            "<article><p>Intro</p><p>The author made this story available to Medium members only.</p></article>";
+        assert!(is_paywalled(guest));
+        assert!(!is_paywalled("<article><p>Member-only story</p><p>Full body for members.</p></article>"));
+    }
+
+    #[test]
+    fn test_cookie_only_for_medium_hosts() {
+        let cookie = "sid=session-id";
+        assert_eq!(cookie_for(&Url::parse("https://medium.com/p/1").unwrap(), cookie), cookie);
+        assert_eq!(cookie_for(&Url::parse("https://user.medium.com/p/1").unwrap(), cookie), cookie);
+        assert_eq!(cookie_for(&Url::parse("https://towardsdatascience.com/p/1").unwrap(), cookie), "");
+        assert_eq!(cookie_for(&Url::parse("https://notmedium.com/p/1").unwrap(), cookie), "");
+    }
+}
@@ -1,92 +1,11 @@
-use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
-use crate::shared::{
-    errors::{AppError, AppResult},
-};
-use chrono::DateTime;
-use feed_parser::parsers;
-use request::Url;
-
-const URL: &str = "https://news.mit.edu/rss/research";
-
-#[derive(Debug, Clone)]
-pub struct MITResearch {
-    site_name: String,
-    url: Url,
-}
-
-impl MITResearch {
-    pub fn new() -> Self {
-        Self {
-            site_name: "MIT Research".to_string(),
-            url: Url::parse(URL).unwrap(),
-        }
-    }
-}
-
-impl Default for MITResearch {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[async_trait::async_trait]
-impl WebSiteInterface for MITResearch {
-
-    fn site_name(&self) -> String {
-        self.site_name.clone()
-    }
-    fn site_url(&self) -> Url {
-        self.url.clone()
-    }
-    fn domain(&self) -> String {
-        self.url.domain().unwrap().to_string()
-    }
-
-    async fn login(&mut self) -> AppResult<Cookie> {
-        Ok(Cookie::default())
-    }
-    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
-        let cookies = self.login().await?;
-        let response = self.request(self.url.as_str(), &cookies).await?;
-        let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
-            Ok(feeds) => feeds,
-            Err(e) => {
-                tracing::error!("Error parsing RSS feed: {}", e);
-                return Err(AppError::ScrapeError("Failed to parse RSS".into()));
-            }
-        };
-        let articles = feeds
-            .iter()
-            .map(|feed| -> AppResult<WebArticle> {
-                let publish_date = feed
-                    .publish_date
-                    .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
-                Ok(WebArticle::new(
-                    self.site_name(),
-                    self.site_url().to_string(),
-                    feed.title.clone(),
-                    feed.link.clone(),
-                    feed.description.clone().unwrap_or("".to_string()),
-                    DateTime::parse_from_rfc2822(&publish_date)?.into(),
-                ))
-            })
-            .collect::<AppResult<Vec<WebArticle>>>()?;
-        Ok(articles)
-    }
-    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
-        let url = Url::parse(url).unwrap();
-        let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector =
-            scraper::Selector::parse("article div.news-article--content--body p").unwrap();
-        let html = document
-            .select(&selector)
-            .map(|x| x.html())
-            .collect::<Vec<_>>()
-            .join("\n");
-        let text = html2md::rewrite_html(&html, false);
-        Ok((self.trim_text(&html), self.trim_text(&text)))
+use super::macros::define_rss_site;
+
+define_rss_site! {
+    pub struct MITResearch {
+        site_name: "MIT Research",
+        url: "https://news.mit.edu/rss/research",
+        feed: rss2,
+        date: publish_date => parse_from_rfc2822,
+        content: all_matches("article div.news-article--content--body p"),
     }
 }
@@ -2,25 +2,31 @@ pub mod ai_db;
 pub mod ai_it_now;
 pub mod ai_news;
 pub mod ai_scholar;
+pub mod anthropic_news;
 pub mod aismiley;
 pub mod aizine;
+pub mod ars_technica;
 pub mod ascii;
 pub mod aws_security_blog;
+pub mod bleeping_computer;
 pub mod business_insider_science;
 pub mod business_insider_technology;
 pub mod canon_malware_center;
+pub mod cncf_blog;
 pub mod codezine;
 pub mod cookpad_techblog;
 pub mod crowdstrike_blog;
-pub mod cyberagent_techblog;
 pub mod cybozu_blog;
 pub mod dena_engineering_blog;
+pub mod developers_io;
+pub mod devto;
+pub mod feeds;
+pub mod generic_feed;
 pub mod gigazine;
 pub mod github_developers_blog;
 pub mod gizmodo;
 pub mod google_developers_blog;
-pub mod gree_techblog;
-pub mod gunosy_techblog;
+pub mod hatena_developer_blog;
 pub mod ipa_security_center;
 pub mod itmedia_at_it;
 pub mod itmedia_enterprise;
@@ -28,15 +34,24 @@ pub mod itmedia_executive;
 pub mod itmedia_general;
 pub mod itmedia_marketing;
 pub mod jpcert;
+pub mod kubernetes_blog;
 pub mod line_techblog;
+pub mod macros;
+pub mod mastodon_tag;
 pub mod medium;
-pub mod mercari_engineering_blog;
 pub mod mit_ai;
 pub mod mit_research;
 pub mod moneyforward_developers_blog;
 pub mod motex;
 pub mod nikkei_xtech;
+pub mod note_topic;
+pub mod nvd_cve;
+pub mod openai_news;
+pub mod package_releases;
+pub mod page_watcher;
+pub mod publickey;
 pub mod qiita_blog;
+pub mod qiita_tag;
 pub mod rust_blog;
 pub mod sakura_internet_techblog;
 pub mod sansan;
@@ -48,6 +63,8 @@ pub mod stockmark_techblog;
 pub mod supership;
 pub mod tech_crunch;
 pub mod techno_edge;
+pub mod the_hacker_news;
+pub mod the_verge;
 pub mod tokyo_univ_engineering;
 pub mod trend_micro_security_advisories;
 pub mod trend_micro_security_news;
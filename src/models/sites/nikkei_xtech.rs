@@ -1,15 +1,81 @@
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::credentials::global_credentials;
 use crate::shared::errors::{AppError, AppResult};
+use crate::shared::proxy::global_proxy_settings;
+use crate::shared::selectors::selector;
 use chrono::DateTime;
 use feed_parser::parsers;
 use request::Url;
+use request::cookie::{CookieStore, Jar};
+use std::sync::Arc;
 
 const URL: &str = "https://xtech.nikkei.com/rss/index.rdf";
+/// ログインページ（NIKKEI ID のログインフォームへリダイレクトされる）
+const LOGIN_URL: &str = "https://xtech.nikkei.com/login/";
+/// ID・パスワードを送信してよいドメイン（サブドメインを含む）
+const LOGIN_DOMAIN: &str = "nikkei.com";
+/// 本文が無料で読める部分で途切れていることを示すマーカー
+const PAYWALL_MARKERS: &[&str] = &[
+    "この記事は有料会員限定です",
+    "ここから先は日経クロステック有料会員の登録が必要です",
+    "有料会員になると全記事をお読みいただけるのはもちろん",
+    "p-article_paywall",
+];
+
+/// 記事ページの本文が無料で読める部分で途切れているか
+pub fn is_paywalled(raw_html: &str) -> bool {
+    PAYWALL_MARKERS.iter().any(|marker| raw_html.contains(marker))
+}
+
+/// ID・パスワードを送信してよいフォームの送信先か（`nikkei.com` とそのサブドメインへの `https` のみ）
+fn is_trusted_login_action(action: &Url) -> bool {
+    action.scheme() == "https"
+        && action.host_str().is_some_and(|host| {
+            let host = host.trim_end_matches('.').to_ascii_lowercase();
+            host == LOGIN_DOMAIN || host.ends_with(&format!(".{}", LOGIN_DOMAIN))
+        })
+}
+
+/// パスワードの入力欄があるフォームの送信先と，ID・パスワードを入れた送信内容（フォームが無ければ `None`）．
+/// フォームの項目名に依存しないよう，hidden の項目はそのまま送り，最初のテキスト入力欄に ID を入れる．
+/// 送信先が `nikkei.com` への `https` でないフォームは，認証情報を送らないよう無視する．
+fn login_form(
+    page_url: &Url,
+    raw_html: &str,
+    username: &str,
+    password: &str,
+) -> Option<(Url, Vec<(String, String)>)> {
+    let doc = scraper::Html::parse_document(raw_html);
+    let form = doc
+        .select(selector!("form"))
+        .find(|form| form.select(selector!("input[type='password']")).next().is_some())?;
+    let action = form.value().attr("action").unwrap_or_default();
+    let action = page_url.join(action).ok().filter(is_trusted_login_action)?;
+    let mut fields = Vec::new();
+    let mut username_filled = false;
+    for input in form.select(selector!("input[name]")) {
+        let name = input.value().attr("name").unwrap_or_default().to_string();
+        let value = input.value().attr("value").unwrap_or_default();
+        match input.value().attr("type").unwrap_or("text").to_ascii_lowercase().as_str() {
+            "password" => fields.push((name, password.to_string())),
+            "text" | "email" if !username_filled => {
+                username_filled = true;
+                fields.push((name, username.to_string()));
+            }
+            "hidden" => fields.push((name, value.to_string())),
+            _ => {}
+        }
+    }
+    username_filled.then_some((action, fields))
+}
 
 #[derive(Debug, Clone)]
 pub struct NikkeiXTech {
     site_name: String,
     url: Url,
+    /// ログインで得たセッションの Cookie（ログインしない・失敗した場合は空文字列）
     cookies: Option<String>,
 }
 
@@ -21,6 +87,29 @@ impl NikkeiXTech {
             url: Url::parse(URL).unwrap(),
         }
     }
+
+    /// NIKKEI ID のログインフォームに ID・パスワードを送信し，セッションの Cookie を返す
+    async fn login_with_password(&self, username: &str, password: &str) -> AppResult<Cookie> {
+        let jar = Arc::new(Jar::default());
+        let settings = global_proxy_settings();
+        let route = settings.route_for(&self.site_name(), self.request_policy().proxy_route()?.as_ref());
+        let builder = request::Client::builder()
+            .cookie_provider(jar.clone())
+            .timeout(std::time::Duration::from_secs(60));
+        let client = route.apply(builder, settings.no_proxy())?.build()?;
+
+        let page = client.get(LOGIN_URL).send().await?.error_for_status()?;
+        let page_url = page.url().clone();
+        let (action, fields) = login_form(&page_url, &page.text().await?, username, password)
+            .ok_or_else(|| AppError::ScrapeError(format!("NIKKEI ID login form not found on {}", page_url)))?;
+        let response = client.post(action).form(&fields).send().await?.error_for_status()?;
+        if login_form(&page_url, &response.text().await?, username, password).is_some() {
+            return Err(AppError::InternalError("NIKKEI ID login failed; check the credentials".into()));
+        }
+        jar.cookies(&self.url)
+            .and_then(|cookies| cookies.to_str().ok().map(str::to_string))
+            .ok_or_else(|| AppError::InternalError("NIKKEI ID login returned no session cookie".into()))
+    }
 }
 
 impl Default for NikkeiXTech {
@@ -40,16 +129,48 @@ impl WebSiteInterface for NikkeiXTech {
     fn domain(&self) -> String {
         self.url.domain().unwrap().to_string()
     }
+    fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+        vec![ExtractionStrategy::css(&[
+            "div.article_body",
+            "article.article div.articleBody",
+            "article.p-article .p-article_body",
+        ])]
+    }
 
+    /// 認証情報のファイルの `[sites."Nikkei XTech"]` の `cookie`，または `username` / `password`（NIKKEI ID）で
+    /// ログインする．結果はインスタンスに保持し，失敗した場合は警告を出して未ログインで取得する．
     async fn login(&mut self) -> AppResult<Cookie> {
-        Ok(Cookie::default())
+        if let Some(cookies) = &self.cookies {
+            return Ok(cookies.clone());
+        }
+        let credentials = global_credentials();
+        let cookies = match credentials.cookie(&self.site_name)? {
+            Some(cookie) => cookie,
+            None => match credentials.login(&self.site_name)? {
+                Some((username, password)) => {
+                    self.login_with_password(&username, &password).await.unwrap_or_else(|e| {
+                        tracing::warn!("Failed to log in to {}: {}", self.site_name, e);
+                        Cookie::default()
+                    })
+                }
+                None => Cookie::default(),
+            },
+        };
+        self.cookies = Some(cookies.clone());
+        Ok(cookies)
     }
     async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
-        let cookies = self.login().await?;
-        let response = self.request(self.url.as_str(), &cookies).await?;
+        // フィードは会員でなくても取得できるため，ログインしない
+        let response = self.request(self.url.as_str(), "").await?;
         let feeds = match parsers::rss1::parse(response.text().await?.as_str()) {
             Ok(feeds) => feeds,
-            Err(e) => return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e))),
+            Err(e) => {
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss1,
+                    message: e.to_string(),
+                });
+            }
         };
         let articles = feeds
             .iter()
@@ -57,7 +178,7 @@ impl WebSiteInterface for NikkeiXTech {
                 let date = feed
                     .date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -70,42 +191,86 @@ impl WebSiteInterface for NikkeiXTech {
             .collect::<AppResult<Vec<WebArticle>>>()?;
         Ok(articles)
     }
+    /// 有料会員限定の記事で全文を取得できない場合は，無料で読める部分を `AppError::PaywallError` で返す
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
-        let url = Url::parse(url).unwrap();
-        let cookies = self.cookies.clone().unwrap_or_default();
-        let response = self.request(url.as_str(), &cookies).await?;
-        let raw = response.text().await?;
+        let url = Url::parse(url)?;
+        let cookies = self.login().await?;
+        let raw = self.request_text(url.as_str(), &cookies).await?;
+        if is_paywalled(&raw) {
+            if !cookies.is_empty() {
+                tracing::warn!("{} session was not accepted (expired or not a subscriber): {}", self.site_name, url);
+            }
+            let preview = self.extract_content(&raw).map(|(_, text)| text).unwrap_or_default();
+            return Err(AppError::PaywallError { preview });
+        }
         if crate::models::web_article::detect_login_required(&raw) {
             return Err(AppError::LoginRequired);
         }
-        let document = scraper::Html::parse_document(raw.as_str());
+        self.extract_content(&raw)
+    }
+}
 
-        let selectors = [
-            "div.article_body",
-            "article.article div.articleBody",
-            "article.p-article .p-article_body",
-        ];
-
-        let mut article_element = None;
-        for sel_str in &selectors {
-            let selector = scraper::Selector::parse(sel_str).unwrap();
-            if let Some(element) = document.select(&selector).next() {
-                article_element = Some(element);
-                break;
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let article = match article_element {
-            Some(article) => article,
-            None => {
-                return Err(AppError::ScrapeError(
-                    "Failed to parse article: no matching selector found for Nikkei XTech".into(),
-                ));
-            }
+    #[test]
+    fn test_is_paywalled() {
+        let preview = r#"<div class="article_body"><p>無料で読める冒頭部分</p>
+            <div class="p-article_paywall">ここから先は日経クロステック有料会員の登録が必要です</div></div>"#;
+        assert!(is_paywalled(preview));
+        assert!(!is_paywalled(r#"<div class="article_body"><p>全文</p></div>"#));
+    }
+
+    #[test]
+    fn test_login_form() {
+        let page_url = Url::parse("https://id.nikkei.com/lounge/nl/login").unwrap();
+        let html = r#"<html><body>
+            <form action="/search"><input type="text" name="q"></form>
+            <form method="post" action="./auth?client=xtech">
+              <input type="hidden" name="csrf" value="token-123">
+              <input type="email" name="LA7010Form01:LA7010Email">
+              <input type="password" name="LA7010Form01:LA7010Password">
+              <input type="checkbox" name="remember" value="on">
+            </form></body></html>"#;
+        let (action, fields) = login_form(&page_url, html, "reader@example.com", "hunter2").unwrap();
+        assert_eq!(action.as_str(), "https://id.nikkei.com/lounge/nl/auth?client=xtech");
+        assert_eq!(
+            fields,
+            [
+                ("csrf".to_string(), "token-123".to_string()),
+                ("LA7010Form01:LA7010Email".to_string(), "reader@example.com".to_string()),
+                ("LA7010Form01:LA7010Password".to_string(), "hunter2".to_string()),
+            ]
+        );
+        assert!(login_form(&page_url, "<form><input name=\"q\"></form>", "id", "pw").is_none());
+    }
+
+    #[test]
+    fn test_login_form_rejects_untrusted_action() {
+        let form = |action: &str| {
+            format!(
+                r#"<form method="post" action="{}"><input type="email" name="id">
+                <input type="password" name="pw"></form>"#,
+                action
+            )
         };
-        let raw_html = article.html().to_string();
-        let html = self.clean_content(&raw_html);
-        let text = html2md::rewrite_html(&html, false);
-        Ok((self.trim_text(&html), self.trim_text(&text)))
+        let page_url = Url::parse("https://id.nikkei.com/lounge/nl/login").unwrap();
+        for action in [
+            "https://attacker.example/collect",
+            "http://id.nikkei.com/lounge/nl/auth",
+            "https://nikkei.com.attacker.example/auth",
+            "https://evilnikkei.com/auth",
+            "//attacker.example/auth",
+        ] {
+            assert!(login_form(&page_url, &form(action), "id", "pw").is_none(), "{}", action);
+        }
+        assert!(login_form(&page_url, &form("https://nikkei.com/auth"), "id", "pw").is_some());
+
+        // ログインページが別のホストへリダイレクトされた場合の相対パスも拒否する
+        let redirected = Url::parse("https://login.attacker.example/").unwrap();
+        assert!(login_form(&redirected, &form("./auth"), "id", "pw").is_none());
+        let plain = Url::parse("http://id.nikkei.com/lounge/nl/login").unwrap();
+        assert!(login_form(&plain, &form("./auth"), "id", "pw").is_none());
     }
 }
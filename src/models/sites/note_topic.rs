@@ -0,0 +1,189 @@
+//! note（note.com）のハッシュタグの新着記事．
+//!
+//! ハッシュタグの RSS（`https://note.com/hashtag/<タグ>/rss`）から記事を取得し，
+//! 各記事のクリエイター名（`<note:creatorName>`）を `WebArticle::author` に入れる．
+//! 有料記事は無料で読める部分だけを `AppError::PaywallError` で返す．
+
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface, detect_login_required};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::regexes;
+use chrono::DateTime;
+use feed_parser::parsers;
+use request::Url;
+use std::collections::HashMap;
+
+const URL: &str = "https://note.com/hashtag/";
+/// 有料記事の本文が無料で読める部分で途切れていることを示すマーカー
+const PAYWALL_MARKERS: &[&str] = &["この続きをみるには", "paywall-line", "購入手続きへ"];
+
+/// 有料記事の本文が無料で読める部分で途切れているか
+pub fn is_paywalled(raw_html: &str) -> bool {
+    PAYWALL_MARKERS.iter().any(|marker| raw_html.contains(marker))
+}
+
+/// RSS の各 `<item>` の記事の URL とクリエイター名
+fn creator_names(xml: &str) -> HashMap<String, String> {
+    regexes::rss_item()
+        .find_iter(xml)
+        .filter_map(|item| {
+            let item = item.as_str();
+            let url = regexes::rss_link().captures(item)?.name("url")?.as_str().trim().to_string();
+            let name = regexes::note_creator_name().captures(item)?.name("name")?.as_str().trim().to_string();
+            (!name.is_empty()).then_some((url, name))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct NoteTopic {
+    site_name: String,
+    url: Url,
+    pub hashtag: String,
+}
+
+impl NoteTopic {
+    /// `hashtag` は `#` を付けずに指定する（例: `機械学習`）
+    pub fn new(hashtag: &str) -> Self {
+        let hashtag = hashtag.trim_start_matches('#');
+        let mut url = Url::parse(URL).unwrap();
+        url.path_segments_mut().unwrap().pop_if_empty().push(hashtag).push("rss");
+        Self {
+            site_name: format!("note Topic - {}", hashtag),
+            url,
+            hashtag: hashtag.to_string(),
+        }
+    }
+}
+
+impl Default for NoteTopic {
+    fn default() -> Self {
+        Self::new("機械学習")
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for NoteTopic {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+        vec![
+            ExtractionStrategy::css(&["div.note-common-styles__textnote-body", "div[data-name='body']"]),
+            ExtractionStrategy::Readability,
+        ]
+    }
+
+    /// note 固有の除外セレクタ
+    fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+        vec![".o-noteLikeV3", ".o-noteContentFooter", ".m-creatorInfo", ".o-noteFollowButton"]
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), "").await?;
+        let xml = response.text().await?;
+        let feeds = match parsers::rss2::parse(&xml) {
+            Ok(feeds) => feeds,
+            Err(e) => {
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
+            }
+        };
+        let creators = creator_names(&xml);
+        feeds
+            .iter()
+            .map(|feed| -> AppResult<WebArticle> {
+                let publish_date = feed
+                    .publish_date
+                    .clone()
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
+                let mut article = WebArticle::new(
+                    self.site_name(),
+                    self.site_url().to_string(),
+                    feed.title.clone(),
+                    feed.link.clone(),
+                    feed.description.clone().unwrap_or_default(),
+                    DateTime::parse_from_rfc2822(&publish_date)?.into(),
+                );
+                article.author = creators.get(feed.link.trim()).cloned();
+                Ok(article)
+            })
+            .collect()
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let url = Url::parse(url)?;
+        let raw = self.request_text(url.as_str(), "").await?;
+        if is_paywalled(&raw) {
+            let preview = self.extract_content(&raw).map(|(_, text)| text).unwrap_or_default();
+            return Err(AppError::PaywallError { preview });
+        }
+        if detect_login_required(&raw) {
+            return Err(AppError::LoginRequired);
+        }
+        self.extract_content(&raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:note="https://note.com" xmlns:media="http://search.yahoo.com/mrss/">
+<channel>
+<title>#機械学習の記事一覧</title>
+<link>https://note.com/hashtag/機械学習</link>
+<item>
+  <title>Transformer をゼロから実装する</title>
+  <description><![CDATA[<p>Attention の仕組みを手を動かして理解する</p>]]></description>
+  <note:creatorImage>https://assets.st-note.com/alice.png</note:creatorImage>
+  <note:creatorName><![CDATA[山田 太郎]]></note:creatorName>
+  <pubDate>Wed, 01 Oct 2025 09:30:00 +0900</pubDate>
+  <link>https://note.com/taro/n/n1234567890ab</link>
+  <guid>https://note.com/taro/n/n1234567890ab</guid>
+</item>
+<item>
+  <title>クリエイター名の無い記事</title>
+  <pubDate>Wed, 01 Oct 2025 10:00:00 +0900</pubDate>
+  <link>https://note.com/anonymous/n/nabcdef123456</link>
+</item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn test_creator_names() {
+        let creators = creator_names(RSS);
+        assert_eq!(creators.len(), 1);
+        assert_eq!(creators["https://note.com/taro/n/n1234567890ab"], "山田 太郎");
+    }
+
+    #[test]
+    fn test_site_url() {
+        let site = NoteTopic::new("#機械学習");
+        assert_eq!(site.hashtag, "機械学習");
+        assert_eq!(site.site_name(), "note Topic - 機械学習");
+        assert_eq!(
+            site.site_url().as_str(),
+            "https://note.com/hashtag/%E6%A9%9F%E6%A2%B0%E5%AD%A6%E7%BF%92/rss"
+        );
+    }
+
+    #[test]
+    fn test_is_paywalled() {
+        assert!(is_paywalled(r#"<div class="paywall-line">ここから先は</div><p>この続きをみるには</p>"#));
+        assert!(!is_paywalled(r#"<div class="note-common-styles__textnote-body"><p>全文</p></div>"#));
+    }
+}
@@ -0,0 +1,411 @@
+//! NVD（National Vulnerability Database）で新しく公開された CVE．
+//!
+//! CVE API 2.0（`https://services.nvd.nist.gov/rest/json/cves/2.0`）から直近 [`LOOKBACK_DAYS`] 日に公開された CVE を取得し，
+//! CVSS の基本値・深刻度と影響を受ける製品を `WebArticleProperty::vulnerability` に入れる．
+//! 記事には取得元として `ArticleTag::Security` のタグを付ける．本文も API のレスポンスから組み立てる（NVD のページはスクレイピングしない）．
+//! API キーは不要だが，認証情報のファイルでサイトに `apiKey` ヘッダーを設定すればレート制限が緩和される．
+
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::tag::ArticleTag;
+use crate::models::web_article::{
+    Cookie, Html, Text, Vulnerability, WebArticle, WebSiteInterface, escape_html_text,
+};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::text::truncate_text;
+use chrono::{Duration, Local, NaiveDateTime, Utc};
+use request::Url;
+use serde::Deserialize;
+
+const API_URL: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
+const DETAIL_URL: &str = "https://nvd.nist.gov/vuln/detail/";
+/// 取得する公開日の範囲（日数）
+const LOOKBACK_DAYS: i64 = 2;
+/// 1回の一覧で取得する CVE 数
+const RESULTS_PER_PAGE: usize = 200;
+/// タイトルに含める説明の最大文字数
+const TITLE_DESCRIPTION_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Deserialize)]
+struct CveResponse {
+    #[serde(default)]
+    vulnerabilities: Vec<CveItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CveItem {
+    cve: Cve,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Cve {
+    id: String,
+    /// 公開日時（UTC，タイムゾーン無し）
+    published: String,
+    #[serde(default)]
+    descriptions: Vec<LangString>,
+    #[serde(default)]
+    metrics: Metrics,
+    #[serde(default)]
+    configurations: Vec<Configuration>,
+    #[serde(default)]
+    references: Vec<Reference>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct LangString {
+    lang: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Metrics {
+    #[serde(rename = "cvssMetricV40", default)]
+    v40: Vec<CvssMetric>,
+    #[serde(rename = "cvssMetricV31", default)]
+    v31: Vec<CvssMetric>,
+    #[serde(rename = "cvssMetricV30", default)]
+    v30: Vec<CvssMetric>,
+    #[serde(rename = "cvssMetricV2", default)]
+    v2: Vec<CvssMetric>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CvssMetric {
+    /// `Primary`（NVD による評価）または `Secondary`（CNA による評価）
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    cvss_data: CvssData,
+    /// CVSS v2 は深刻度が `cvssData` の外にある
+    #[serde(default)]
+    base_severity: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CvssData {
+    base_score: f64,
+    #[serde(default)]
+    base_severity: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Configuration {
+    #[serde(default)]
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Node {
+    #[serde(default)]
+    cpe_match: Vec<CpeMatch>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CpeMatch {
+    vulnerable: bool,
+    criteria: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Reference {
+    url: String,
+}
+
+impl Metrics {
+    /// 新しい CVSS のバージョンから順に，NVD による評価を優先して基本値と深刻度を返す
+    fn score(&self) -> Option<(f64, Option<String>)> {
+        [&self.v40, &self.v31, &self.v30, &self.v2].into_iter().find_map(|metrics| {
+            let metric = metrics
+                .iter()
+                .find(|m| m.kind.as_deref() == Some("Primary"))
+                .or_else(|| metrics.first())?;
+            let severity = metric.cvss_data.base_severity.clone().or_else(|| metric.base_severity.clone());
+            Some((metric.cvss_data.base_score, severity))
+        })
+    }
+}
+
+impl Cve {
+    fn description(&self) -> String {
+        self.descriptions
+            .iter()
+            .find(|d| d.lang == "en")
+            .or_else(|| self.descriptions.first())
+            .map(|d| d.value.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// 影響を受ける製品（CPE の `cpe:2.3:<種別>:<ベンダー>:<製品>:...` の `<ベンダー>:<製品>`，重複は除く）
+    fn affected_products(&self) -> Vec<String> {
+        let mut products: Vec<String> = Vec::new();
+        let matches = self.configurations.iter().flat_map(|c| &c.nodes).flat_map(|n| &n.cpe_match);
+        for cpe in matches.filter(|m| m.vulnerable) {
+            let parts: Vec<&str> = cpe.criteria.split(':').collect();
+            if let [_, _, _, vendor, product, ..] = parts.as_slice() {
+                let product = format!("{}:{}", vendor, product);
+                if !products.contains(&product) {
+                    products.push(product);
+                }
+            }
+        }
+        products
+    }
+
+    fn vulnerability(&self) -> Vulnerability {
+        let (cvss_score, severity) = self.metrics.score().unzip();
+        Vulnerability {
+            cve_id: self.id.clone(),
+            cvss_score,
+            severity: severity.flatten(),
+            affected_products: self.affected_products(),
+        }
+    }
+
+    /// API のレスポンスから組み立てた本文
+    fn to_html(&self) -> Html {
+        let vulnerability = self.vulnerability();
+        let mut html = String::from("<article><h1>");
+        escape_html_text(&self.id, &mut html);
+        html.push_str("</h1><p>");
+        escape_html_text(&self.description(), &mut html);
+        html.push_str("</p>");
+        if let Some(score) = vulnerability.cvss_score {
+            html.push_str(&format!("<p>CVSS: {:.1}", score));
+            if let Some(severity) = &vulnerability.severity {
+                html.push_str(" (");
+                escape_html_text(severity, &mut html);
+                html.push(')');
+            }
+            html.push_str("</p>");
+        }
+        let references: Vec<String> = self.references.iter().map(|r| r.url.clone()).collect();
+        let sections = [("Affected products", &vulnerability.affected_products), ("References", &references)];
+        for (heading, items) in sections {
+            if items.is_empty() {
+                continue;
+            }
+            html.push_str(&format!("<h2>{}</h2><ul>", heading));
+            for item in items {
+                html.push_str("<li>");
+                escape_html_text(item, &mut html);
+                html.push_str("</li>");
+            }
+            html.push_str("</ul>");
+        }
+        html.push_str("</article>");
+        html
+    }
+}
+
+fn parse_response(site_name: &str, json: &str) -> AppResult<Vec<Cve>> {
+    let response: CveResponse = serde_json::from_str(json)
+        .map_err(|e| AppError::FeedParse {
+            site: site_name.to_string(),
+            kind: FeedKind::Json,
+            message: format!("NVD response: {}", e),
+        })?;
+    Ok(response.vulnerabilities.into_iter().map(|item| item.cve).collect())
+}
+
+/// CVE API のレスポンスを記事にする
+fn parse_listing(site_name: &str, site_url: &str, json: &str) -> AppResult<Vec<WebArticle>> {
+    parse_response(site_name, json)?
+        .into_iter()
+        .map(|cve| {
+            let published = NaiveDateTime::parse_from_str(&cve.published, "%Y-%m-%dT%H:%M:%S%.f")?;
+            let description = cve.description();
+            let mut article = WebArticle::new(
+                site_name.to_string(),
+                site_url.to_string(),
+                format!("{}: {}", cve.id, truncate_text(&description, TITLE_DESCRIPTION_CHARS)),
+                format!("{}{}", DETAIL_URL, cve.id),
+                description,
+                published.and_utc().with_timezone(&Local),
+            );
+            article.properties.vulnerability = Some(cve.vulnerability());
+            article.properties.tag_by_source(ArticleTag::Security);
+            Ok(article)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct NvdCve {
+    site_name: String,
+    url: Url,
+}
+
+impl NvdCve {
+    pub fn new() -> Self {
+        Self {
+            site_name: "NVD CVE".to_string(),
+            url: Url::parse(API_URL).unwrap(),
+        }
+    }
+
+    /// 直近 [`LOOKBACK_DAYS`] 日に公開された CVE を取得する URL
+    fn recent_url(&self) -> Url {
+        let end = Utc::now();
+        let start = end - Duration::days(LOOKBACK_DAYS);
+        let format = "%Y-%m-%dT%H:%M:%S%.3fZ";
+        let mut url = self.url.clone();
+        url.query_pairs_mut()
+            .append_pair("pubStartDate", &start.format(format).to_string())
+            .append_pair("pubEndDate", &end.format(format).to_string())
+            .append_pair("resultsPerPage", &RESULTS_PER_PAGE.to_string());
+        url
+    }
+}
+
+impl Default for NvdCve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for NvdCve {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.recent_url().as_str(), "").await?;
+        parse_listing(&self.site_name(), self.site_url().as_str(), &response.text().await?)
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let cve_id = url
+            .strip_prefix(DETAIL_URL)
+            .ok_or_else(|| AppError::ScrapeError(format!("Not an NVD CVE URL: {}", url)))?;
+        let mut api_url = self.url.clone();
+        api_url.query_pairs_mut().append_pair("cveId", cve_id);
+        let body = self.request_text(api_url.as_str(), "").await?;
+        let cve = parse_response(&self.site_name(), &body)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::ScrapeError(format!("CVE not found: {}", cve_id)))?;
+        let html = cve.to_html();
+        let text = html2md::rewrite_html(&html, false);
+        Ok((self.trim_text(&html), self.trim_text(&text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"{
+        "resultsPerPage": 2,
+        "startIndex": 0,
+        "totalResults": 2,
+        "format": "NVD_CVE",
+        "version": "2.0",
+        "vulnerabilities": [
+            {
+                "cve": {
+                    "id": "CVE-2025-12345",
+                    "published": "2025-10-01T12:15:40.123",
+                    "lastModified": "2025-10-02T08:00:00.000",
+                    "vulnStatus": "Analyzed",
+                    "descriptions": [
+                        {"lang": "es", "value": "Desbordamiento de búfer"},
+                        {"lang": "en", "value": "Buffer overflow in Example Server allows remote code execution."}
+                    ],
+                    "metrics": {
+                        "cvssMetricV31": [
+                            {"source": "cna@example.com", "type": "Secondary",
+                             "cvssData": {"version": "3.1", "baseScore": 8.1, "baseSeverity": "HIGH"}},
+                            {"source": "nvd@nist.gov", "type": "Primary",
+                             "cvssData": {"version": "3.1", "baseScore": 9.8, "baseSeverity": "CRITICAL"}}
+                        ],
+                        "cvssMetricV2": [
+                            {"type": "Primary", "cvssData": {"version": "2.0", "baseScore": 7.5},
+                             "baseSeverity": "HIGH"}
+                        ]
+                    },
+                    "configurations": [
+                        {"nodes": [{"operator": "OR", "cpeMatch": [
+                            {"vulnerable": true, "criteria": "cpe:2.3:a:example:server:1.0:*:*:*:*:*:*:*"},
+                            {"vulnerable": true, "criteria": "cpe:2.3:a:example:server:1.1:*:*:*:*:*:*:*"},
+                            {"vulnerable": false, "criteria": "cpe:2.3:o:linux:linux_kernel:-:*:*:*:*:*:*:*"}
+                        ]}]}
+                    ],
+                    "references": [{"url": "https://example.com/advisory", "source": "cna@example.com"}]
+                }
+            },
+            {
+                "cve": {
+                    "id": "CVE-2025-67890",
+                    "published": "2025-10-01T13:00:00.000",
+                    "descriptions": [{"lang": "en", "value": "Awaiting analysis."}],
+                    "metrics": {}
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_listing() {
+        let articles = parse_listing("NVD CVE", API_URL, RESPONSE).unwrap();
+        assert_eq!(articles.len(), 2);
+
+        let article = &articles[0];
+        assert_eq!(
+            article.title,
+            "CVE-2025-12345: Buffer overflow in Example Server allows remote code execution."
+        );
+        assert_eq!(article.article_url, "https://nvd.nist.gov/vuln/detail/CVE-2025-12345");
+        assert_eq!(article.timestamp, "2025-10-01T12:15:40.123Z".parse::<chrono::DateTime<Utc>>().unwrap());
+        assert_eq!(article.properties.is_security_related, Some(true));
+        assert_eq!(article.properties.source_tags, [ArticleTag::Security]);
+        let vulnerability = article.properties.vulnerability.as_ref().unwrap();
+        assert_eq!(vulnerability.cve_id, "CVE-2025-12345");
+        assert_eq!(vulnerability.cvss_score, Some(9.8));
+        assert_eq!(vulnerability.severity.as_deref(), Some("CRITICAL"));
+        assert_eq!(vulnerability.affected_products, ["example:server"]);
+
+        let vulnerability = articles[1].properties.vulnerability.as_ref().unwrap();
+        assert_eq!(vulnerability.cvss_score, None);
+        assert!(vulnerability.affected_products.is_empty());
+
+        assert!(parse_listing("NVD CVE", API_URL, r#"{"message": "Invalid apiKey"}"#).unwrap().is_empty());
+        assert!(parse_listing("NVD CVE", API_URL, "<html>403 Forbidden</html>").is_err());
+    }
+
+    #[test]
+    fn test_cvss_v2_severity_and_html() {
+        let json = r#"{"vulnerabilities": [{"cve": {
+            "id": "CVE-2015-0001",
+            "published": "2015-01-01T00:00:00.000",
+            "descriptions": [{"lang": "en", "value": "Old <script> issue"}],
+            "metrics": {"cvssMetricV2": [{"cvssData": {"baseScore": 5.0}, "baseSeverity": "MEDIUM"}]}
+        }}]}"#;
+        let cve = parse_response("NVD", json).unwrap().remove(0);
+        assert_eq!(cve.metrics.score(), Some((5.0, Some("MEDIUM".to_string()))));
+        let html = cve.to_html();
+        assert!(html.contains("Old &lt;script&gt; issue"));
+        assert!(html.contains("CVSS: 5.0 (MEDIUM)"));
+        assert!(!html.contains("References"));
+    }
+
+    #[test]
+    fn test_recent_url() {
+        let url = NvdCve::default().recent_url();
+        let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert_eq!(query[0].0, "pubStartDate");
+        assert_eq!(query[1].0, "pubEndDate");
+        assert!(query[1].1.ends_with('Z'));
+        assert_eq!(query[2], ("resultsPerPage".to_string(), "200".to_string()));
+    }
+}
@@ -0,0 +1,131 @@
+//! OpenAI のニュース（製品・研究の発表）．
+//!
+//! 公式の RSS（`https://openai.com/news/rss.xml`）から記事を取得する．
+//! 発表の多くは製品のリリースのため，記事には取得元として `ArticleTag::NewProduct` のタグを付ける．
+
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::tag::ArticleTag;
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::errors::{AppError, AppResult};
+use chrono::DateTime;
+use feed_parser::parsers;
+use request::Url;
+
+const URL: &str = "https://openai.com/news/rss.xml";
+
+/// RSS を記事にする
+fn parse_feed(site_name: &str, site_url: &str, xml: &str) -> AppResult<Vec<WebArticle>> {
+    let feeds = match parsers::rss2::parse(xml) {
+        Ok(feeds) => feeds,
+        Err(e) => {
+            return Err(AppError::FeedParse {
+                site: site_name.to_string(),
+                kind: FeedKind::Rss2,
+                message: e.to_string(),
+            });
+        }
+    };
+    feeds
+        .iter()
+        .map(|feed| -> AppResult<WebArticle> {
+            let publish_date = feed
+                .publish_date
+                .clone()
+                .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
+            let mut article = WebArticle::new(
+                site_name.to_string(),
+                site_url.to_string(),
+                feed.title.clone(),
+                feed.link.clone(),
+                feed.description.clone().unwrap_or_default(),
+                DateTime::parse_from_rfc2822(&publish_date)?.into(),
+            );
+            article.properties.tag_by_source(ArticleTag::NewProduct);
+            Ok(article)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenAINews {
+    site_name: String,
+    url: Url,
+}
+
+impl OpenAINews {
+    pub fn new() -> Self {
+        Self {
+            site_name: "OpenAI News".to_string(),
+            url: Url::parse(URL).unwrap(),
+        }
+    }
+}
+
+impl Default for OpenAINews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for OpenAINews {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+        vec![
+            ExtractionStrategy::css(&["main article div[class*='prose']", "main article"]),
+            ExtractionStrategy::JsonLd,
+            ExtractionStrategy::Readability,
+        ]
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), "").await?;
+        parse_feed(&self.site_name(), self.site_url().as_str(), &response.text().await?)
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let url = Url::parse(url)?;
+        let body = self.request_article_page(url.as_str(), "").await?;
+        self.extract_content(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feed_tags_new_product() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>OpenAI News</title>
+<link>https://openai.com/news</link>
+<item>
+  <title>Introducing a new model</title>
+  <description>Our most capable model yet.</description>
+  <link>https://openai.com/index/introducing-a-new-model</link>
+  <pubDate>Wed, 01 Oct 2025 17:00:00 GMT</pubDate>
+</item>
+</channel>
+</rss>"#;
+        let site = OpenAINews::default();
+        let articles = parse_feed(&site.site_name(), site.site_url().as_str(), xml).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].title, "Introducing a new model");
+        assert_eq!(articles[0].article_url, "https://openai.com/index/introducing-a-new-model");
+        assert_eq!(articles[0].properties.is_new_product_related, Some(true));
+        assert_eq!(articles[0].properties.source_tags, [ArticleTag::NewProduct]);
+    }
+}
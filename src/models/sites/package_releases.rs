@@ -0,0 +1,421 @@
+//! crates.io / PyPI のパッケージのリリース．
+//!
+//! サイト設定ファイルの `[[packages]]` で指定したパッケージの新しいバージョンを，1リリースにつき1記事として出力する．
+//! 一覧は crates.io の `GET /api/v1/crates/<名前>/versions`（JSON）と PyPI の `/rss/project/<名前>/releases.xml` から取得する．
+//! 本文は GitHub のリリースノート（`v<バージョン>` などのタグ）を使い，見つからない場合はパッケージの説明にする．
+//! リポジトリはパッケージのメタデータから推定し，`repository` で明示することもできる．
+
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::domain_guard::default_allowed_domains;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::memory::budgeted_text;
+use chrono::DateTime;
+use feed_parser::parsers;
+use request::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use strum::Display;
+
+const CRATES_API_URL: &str = "https://crates.io/api/v1/crates";
+const PYPI_URL: &str = "https://pypi.org";
+const GITHUB_API_URL: &str = "https://api.github.com/repos";
+/// 1回の一覧で出力するリリース数（新しい順）
+const MAX_RELEASES: usize = 10;
+
+/// パッケージのレジストリ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageRegistry {
+    #[strum(serialize = "crates.io")]
+    Crates,
+    #[strum(serialize = "PyPI")]
+    PyPI,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CrateVersions {
+    versions: Vec<CrateVersion>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CrateVersion {
+    num: String,
+    /// 公開日時（RFC 3339）
+    created_at: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CrateInfo {
+    #[serde(rename = "crate")]
+    krate: CrateMetadata,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CrateMetadata {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    repository: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PyPIRelease {
+    info: PyPIInfo,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PyPIInfo {
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    home_page: Option<String>,
+    #[serde(default)]
+    project_urls: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GitHubRelease {
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// `https://github.com/<owner>/<repo>(.git)` から `<owner>/<repo>` を取り出す
+fn github_repository(url: &str) -> Option<String> {
+    let url = Url::parse(url.trim()).ok()?;
+    if url.domain() != Some("github.com") {
+        return None;
+    }
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        [owner, repo, ..] => Some(format!("{}/{}", owner, repo.trim_end_matches(".git"))),
+        _ => None,
+    }
+}
+
+/// PyPI のメタデータのリンク（Source，Repository，Changelog など）から GitHub のリポジトリを探す
+fn pypi_repository(info: &PyPIInfo) -> Option<String> {
+    let mut urls: Vec<&String> = info.project_urls.iter().flat_map(|urls| urls.values()).collect();
+    urls.sort();
+    urls.into_iter()
+        .chain(info.home_page.iter())
+        .find_map(|url| github_repository(url))
+}
+
+/// リリースノートを探す GitHub のタグの候補
+fn release_tags(package: &str, version: &str) -> Vec<String> {
+    vec![
+        format!("v{}", version),
+        version.to_string(),
+        format!("{}-v{}", package, version),
+        format!("{}-{}", package, version),
+    ]
+}
+
+/// crates.io のバージョン一覧を記事にする（yank されたバージョンは除く）
+fn parse_crate_versions(site_name: &str, site_url: &str, package: &str, json: &str) -> AppResult<Vec<WebArticle>> {
+    let response: CrateVersions = serde_json::from_str(json)
+        .map_err(|e| AppError::FeedParse {
+            site: site_name.to_string(),
+            kind: FeedKind::Json,
+            message: format!("crates.io versions: {}", e),
+        })?;
+    let mut articles = response
+        .versions
+        .into_iter()
+        .filter(|version| !version.yanked)
+        .map(|version| -> AppResult<WebArticle> {
+            Ok(WebArticle::new(
+                site_name.to_string(),
+                site_url.to_string(),
+                format!("{} {}", package, version.num),
+                format!("https://crates.io/crates/{}/{}", package, version.num),
+                String::new(),
+                DateTime::parse_from_rfc3339(&version.created_at)?.into(),
+            ))
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    articles.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    articles.truncate(MAX_RELEASES);
+    Ok(articles)
+}
+
+/// PyPI のリリースの RSS を記事にする
+fn parse_pypi_releases(site_name: &str, site_url: &str, package: &str, xml: &str) -> AppResult<Vec<WebArticle>> {
+    let feeds = match parsers::rss2::parse(xml) {
+        Ok(feeds) => feeds,
+        Err(e) => {
+            return Err(AppError::FeedParse {
+                site: site_name.to_string(),
+                kind: FeedKind::Rss2,
+                message: e.to_string(),
+            });
+        }
+    };
+    feeds
+        .iter()
+        .take(MAX_RELEASES)
+        .map(|feed| -> AppResult<WebArticle> {
+            let publish_date = feed
+                .publish_date
+                .clone()
+                .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
+            Ok(WebArticle::new(
+                site_name.to_string(),
+                site_url.to_string(),
+                format!("{} {}", package, feed.title.trim()),
+                feed.link.clone(),
+                feed.description.clone().unwrap_or_default(),
+                DateTime::parse_from_rfc2822(&publish_date)?.into(),
+            ))
+        })
+        .collect()
+}
+
+/// リリースの URL（`https://crates.io/crates/<名前>/<バージョン>`，`https://pypi.org/project/<名前>/<バージョン>/`）のバージョン
+fn version_from_url(url: &Url) -> AppResult<String> {
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    match segments.as_slice() {
+        ["crates" | "project", _, version] => Ok(version.to_string()),
+        _ => Err(AppError::ScrapeError(format!("Not a release URL: {}", url))),
+    }
+}
+
+/// Markdown のリリースノートを HTML と本文にする
+fn render_markdown(markdown: &str) -> (Html, Text) {
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, pulldown_cmark::Parser::new(markdown));
+    (html, markdown.trim().to_string())
+}
+
+#[derive(Debug, Clone)]
+pub struct PackageReleases {
+    site_name: String,
+    url: Url,
+    pub registry: PackageRegistry,
+    pub package: String,
+    /// リリースノートを取得する GitHub のリポジトリ（`<owner>/<repo>`．`None` はメタデータから推定）
+    pub repository: Option<String>,
+}
+
+impl PackageReleases {
+    /// `registry` の `package` のリリースを取得する（サイト名は「crates.io - tokio」など）
+    pub fn new(registry: PackageRegistry, package: &str) -> Self {
+        let url = match registry {
+            PackageRegistry::Crates => format!("{}/{}/versions", CRATES_API_URL, package),
+            PackageRegistry::PyPI => format!("{}/rss/project/{}/releases.xml", PYPI_URL, package),
+        };
+        Self {
+            site_name: format!("{} - {}", registry, package),
+            url: Url::parse(&url).unwrap(),
+            registry,
+            package: package.to_string(),
+            repository: None,
+        }
+    }
+
+    pub fn with_repository(mut self, repository: Option<String>) -> Self {
+        self.repository = repository;
+        self
+    }
+
+    /// パッケージの説明と GitHub のリポジトリ
+    async fn metadata(&self, version: &str) -> AppResult<(String, Option<String>)> {
+        match self.registry {
+            PackageRegistry::Crates => {
+                let url = format!("{}/{}", CRATES_API_URL, self.package);
+                let body = self.request_text(&url, "").await?;
+                let info: CrateInfo = serde_json::from_str(&body)
+                    .map_err(|e| AppError::FeedParse {
+                        site: self.site_name(),
+                        kind: FeedKind::Json,
+                        message: format!("crates.io crate: {}", e),
+                    })?;
+                let repository = info.krate.repository.as_deref().and_then(github_repository);
+                Ok((info.krate.description.unwrap_or_default(), repository))
+            }
+            PackageRegistry::PyPI => {
+                let url = format!("{}/pypi/{}/{}/json", PYPI_URL, self.package, version);
+                let body = self.request_text(&url, "").await?;
+                let release: PyPIRelease = serde_json::from_str(&body)
+                    .map_err(|e| AppError::FeedParse {
+                        site: self.site_name(),
+                        kind: FeedKind::Json,
+                        message: format!("PyPI release: {}", e),
+                    })?;
+                let repository = pypi_repository(&release.info);
+                Ok((release.info.summary.unwrap_or_default(), repository))
+            }
+        }
+    }
+
+    /// GitHub のリリースノート（見つからない場合は `None`）
+    async fn release_notes(&self, repository: &str, version: &str) -> AppResult<Option<String>> {
+        for tag in release_tags(&self.package, version) {
+            let url = format!("{}/{}/releases/tags/{}", GITHUB_API_URL, repository, tag);
+            let response = self.request(&url, "").await?;
+            if !response.status().is_success() {
+                continue;
+            }
+            let release: GitHubRelease = serde_json::from_str(&budgeted_text(response).await?)
+                .map_err(|e| AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Json,
+                    message: format!("GitHub release: {}", e),
+                })?;
+            if let Some(body) = release.body.filter(|body| !body.trim().is_empty()) {
+                return Ok(Some(body));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for PackageReleases {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    /// リリースノートは GitHub の API から取得する
+    fn allowed_domains(&self) -> Vec<String> {
+        let mut domains = default_allowed_domains(&self.site_name(), &self.domain());
+        domains.push("api.github.com".to_string());
+        domains
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), "").await?;
+        let body = response.text().await?;
+        match self.registry {
+            PackageRegistry::Crates => {
+                parse_crate_versions(&self.site_name(), self.site_url().as_str(), &self.package, &body)
+            }
+            PackageRegistry::PyPI => {
+                parse_pypi_releases(&self.site_name(), self.site_url().as_str(), &self.package, &body)
+            }
+        }
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let version = version_from_url(&Url::parse(url)?)?;
+        let (description, repository) = self.metadata(&version).await?;
+        if let Some(repository) = self.repository.clone().or(repository)
+            && let Some(notes) = self.release_notes(&repository, &version).await?
+        {
+            let (html, text) = render_markdown(&notes);
+            return Ok((self.trim_text(&html), self.trim_text(&text)));
+        }
+        if description.trim().is_empty() {
+            return Err(AppError::ScrapeError(format!("No release notes: {}", url)));
+        }
+        let (html, text) = render_markdown(&description);
+        Ok((self.trim_text(&html), self.trim_text(&text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_name_and_url() {
+        let crates = PackageReleases::new(PackageRegistry::Crates, "tokio");
+        assert_eq!(crates.site_name(), "crates.io - tokio");
+        assert_eq!(crates.site_url().as_str(), "https://crates.io/api/v1/crates/tokio/versions");
+        let pypi = PackageReleases::new(PackageRegistry::PyPI, "openai");
+        assert_eq!(pypi.site_name(), "PyPI - openai");
+        assert_eq!(pypi.site_url().as_str(), "https://pypi.org/rss/project/openai/releases.xml");
+        let github = Url::parse("https://api.github.com/repos/tokio-rs/tokio/releases/tags/tokio-1.48.0").unwrap();
+        assert!(crates.ensure_allowed_url(&github).is_ok());
+    }
+
+    #[test]
+    fn test_parse_crate_versions() {
+        let json = r#"{"versions": [
+            {"num": "1.48.0", "created_at": "2025-10-14T12:00:00.123456+00:00", "yanked": false},
+            {"num": "1.47.2", "created_at": "2025-10-01T08:00:00.000000+00:00", "yanked": true},
+            {"num": "1.47.1", "created_at": "2025-09-20T08:00:00.000000+00:00", "yanked": false}
+        ], "meta": {"total": 3}}"#;
+        let site = PackageReleases::new(PackageRegistry::Crates, "tokio");
+        let articles = parse_crate_versions(&site.site_name(), site.site_url().as_str(), "tokio", json).unwrap();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].title, "tokio 1.48.0");
+        assert_eq!(articles[0].article_url, "https://crates.io/crates/tokio/1.48.0");
+        assert_eq!(articles[1].title, "tokio 1.47.1");
+
+        assert!(parse_crate_versions("crates.io - tokio", CRATES_API_URL, "tokio", r#"{"errors": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_pypi_releases() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>PyPI recent updates for openai</title>
+<link>https://pypi.org/project/openai/</link>
+<item>
+  <title>2.3.0</title>
+  <link>https://pypi.org/project/openai/2.3.0/</link>
+  <description>The official Python library for the openai API</description>
+  <pubDate>Tue, 14 Oct 2025 12:00:00 GMT</pubDate>
+</item>
+</channel>
+</rss>"#;
+        let site = PackageReleases::new(PackageRegistry::PyPI, "openai");
+        let articles = parse_pypi_releases(&site.site_name(), site.site_url().as_str(), "openai", xml).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].title, "openai 2.3.0");
+        assert_eq!(articles[0].article_url, "https://pypi.org/project/openai/2.3.0/");
+    }
+
+    #[test]
+    fn test_version_from_url() {
+        let crates = Url::parse("https://crates.io/crates/tokio/1.48.0").unwrap();
+        assert_eq!(version_from_url(&crates).unwrap(), "1.48.0");
+        let pypi = Url::parse("https://pypi.org/project/openai/2.3.0/").unwrap();
+        assert_eq!(version_from_url(&pypi).unwrap(), "2.3.0");
+        assert!(version_from_url(&Url::parse("https://pypi.org/project/openai/").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_github_repository() {
+        assert_eq!(github_repository("https://github.com/tokio-rs/tokio").as_deref(), Some("tokio-rs/tokio"));
+        assert_eq!(
+            github_repository("https://github.com/openai/openai-python.git").as_deref(),
+            Some("openai/openai-python")
+        );
+        assert_eq!(github_repository("https://gitlab.com/owner/repo"), None);
+
+        let info = PyPIInfo {
+            summary: None,
+            home_page: None,
+            project_urls: Some(HashMap::from([
+                ("Homepage".to_string(), "https://example.com".to_string()),
+                ("Repository".to_string(), "https://github.com/openai/openai-python".to_string()),
+            ])),
+        };
+        assert_eq!(pypi_repository(&info).as_deref(), Some("openai/openai-python"));
+        assert_eq!(release_tags("tokio", "1.48.0")[2], "tokio-v1.48.0");
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let (html, text) = render_markdown("## Fixed\n\n- a bug\n");
+        assert!(html.contains("<h2>Fixed</h2>"));
+        assert!(html.contains("<li>a bug</li>"));
+        assert_eq!(text, "## Fixed\n\n- a bug");
+    }
+}
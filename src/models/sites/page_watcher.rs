@@ -0,0 +1,269 @@
+//! 任意のページ（ベンダーのセキュリティ情報，ステータスページ，料金ページなど）の
+//! 指定領域を監視し，内容が変化したときだけ差分を記事として出力するサイト．
+//!
+//! `without_snapshot_updates` のスコープ内（`sites doctor` の診断）では前回の内容と比較するだけで，
+//! スナップショットを更新しない．診断で取得した内容が次回の巡回の比較対象にならないようにするため．
+
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::config::data_dir;
+use crate::shared::errors::{AppError, AppResult};
+use chrono::{DateTime, Local};
+use request::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+tokio::task_local! {
+    static DRY_RUN: ();
+}
+
+/// `future` の実行中，監視対象のページを取得してもスナップショットを更新しない
+pub async fn without_snapshot_updates<F: Future>(future: F) -> F::Output {
+    DRY_RUN.scope((), future).await
+}
+
+fn is_dry_run() -> bool {
+    DRY_RUN.try_with(|_| ()).is_ok()
+}
+
+/// 前回取得した監視領域の内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageSnapshot {
+    pub url: String,
+    pub selector: String,
+    pub hash: String,
+    pub text: String,
+    pub checked_at: DateTime<Local>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PageWatcher {
+    site_name: String,
+    url: Url,
+    selector: String,
+    snapshot_dir: PathBuf,
+}
+
+impl PageWatcher {
+    /// スナップショットは `$CLIPPER_DATA_DIR/watchers` に保存する
+    pub fn new(site_name: &str, url: &str, selector: &str) -> AppResult<Self> {
+        Self::with_snapshot_dir(site_name, url, selector, data_dir().join("watchers"))
+    }
+
+    pub fn with_snapshot_dir(
+        site_name: &str,
+        url: &str,
+        selector: &str,
+        snapshot_dir: PathBuf,
+    ) -> AppResult<Self> {
+        let url = Url::parse(url)?;
+        if url.domain().is_none() {
+            return Err(AppError::InternalError(format!(
+                "Watched URL must have a domain: {}",
+                url
+            )));
+        }
+        Ok(Self {
+            site_name: site_name.to_string(),
+            url,
+            selector: selector.to_string(),
+            snapshot_dir,
+        })
+    }
+
+    fn snapshot_path(&self) -> PathBuf {
+        let key = hex_digest(format!("{}\n{}", self.url, self.selector).as_bytes());
+        self.snapshot_dir.join(format!("{}.json", &key[..16]))
+    }
+
+    fn load_snapshot(&self) -> AppResult<Option<PageSnapshot>> {
+        let path = self.snapshot_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    fn save_snapshot(&self, snapshot: &PageSnapshot) -> AppResult<()> {
+        std::fs::create_dir_all(&self.snapshot_dir).map_err(|e| io_error(&self.snapshot_dir, e))?;
+        let path = self.snapshot_path();
+        std::fs::write(&path, serde_json::to_string_pretty(snapshot)?).map_err(|e| io_error(&path, e))
+    }
+
+    /// 監視領域をクリーンなテキストとして取り出す
+    pub fn extract_region(&self, body: &str) -> AppResult<(Html, Text)> {
+        let fragments = self.select_clean(body, &self.selector)?;
+        if fragments.is_empty() {
+            return Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: self.selector.clone(),
+            });
+        }
+        let html = fragments.join("\n");
+        let text = html2md::rewrite_html(&html, false);
+        Ok((self.trim_text(&html), self.trim_text(&text)))
+    }
+
+    /// 取得した領域を前回のスナップショットと比較する．
+    /// 変化があれば差分の記事を返し，スナップショットを更新する．初回は記録のみ行う．
+    pub fn check(&self, body: &str, now: DateTime<Local>) -> AppResult<Option<WebArticle>> {
+        let (html, text) = self.extract_region(body)?;
+        let hash = hex_digest(text.as_bytes());
+        let previous = self.load_snapshot()?;
+        if previous.as_ref().is_some_and(|p| p.hash == hash) {
+            return Ok(None);
+        }
+        if !is_dry_run() {
+            self.save_snapshot(&PageSnapshot {
+                url: self.url.to_string(),
+                selector: self.selector.clone(),
+                hash: hash.clone(),
+                text: text.clone(),
+                checked_at: now,
+            })?;
+        }
+        let Some(previous) = previous else {
+            return Ok(None);
+        };
+
+        let diff = render_changelog(&previous.text, &text);
+        let mut article = WebArticle::new(
+            self.site_name(),
+            self.site_url().to_string(),
+            format!("{} updated", self.site_name),
+            // 変更ごとに異なる URL にして，保存時に上書きされないようにする
+            format!("{}#change-{}", self.url, &hash[..12]),
+            first_lines(&diff, 5),
+            now,
+        );
+        article.text = diff;
+        article.html = html;
+        Ok(Some(article))
+    }
+}
+
+/// 行単位の差分を `+ 追加行` / `- 削除行` の変更履歴形式にする
+pub fn render_changelog(old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .filter_map(|change| {
+            let sign = match change.tag() {
+                ChangeTag::Insert => "+",
+                ChangeTag::Delete => "-",
+                ChangeTag::Equal => return None,
+            };
+            let line = change.value().trim_end();
+            (!line.trim().is_empty()).then(|| format!("{} {}", sign, line))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn first_lines(text: &str, n: usize) -> String {
+    text.lines().take(n).collect::<Vec<_>>().join("\n")
+}
+
+fn hex_digest(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> AppError {
+    AppError::InternalError(format!("Failed to access watcher snapshot {:?}: {}", path, e))
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for PageWatcher {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let cookies = self.login().await?;
+        let body = self.request_text(self.url.as_str(), &cookies).await?;
+        Ok(self.check(&body, Local::now())?.into_iter().collect())
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        // 変更記事の URL は `#change-...` 付きなので，監視対象ページの現在の内容を返す
+        let mut page = Url::parse(url)?;
+        page.set_fragment(None);
+        let cookies = self.login().await?;
+        let body = self.request_text(page.as_str(), &cookies).await?;
+        self.extract_region(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(items: &[&str]) -> String {
+        let lis: String = items.iter().map(|i| format!("<li>{}</li>", i)).collect();
+        format!("<html><body><nav>Menu</nav><main><ul class=\"bulletins\">{}</ul></main></body></html>", lis)
+    }
+
+    #[test]
+    fn test_page_watcher_emits_only_on_change() {
+        let dir = std::env::temp_dir().join(format!("clipper-watcher-{}", uuid::Uuid::new_v4()));
+        let watcher = PageWatcher::with_snapshot_dir(
+            "Vendor Bulletins",
+            "https://vendor.example.com/security",
+            "main ul.bulletins",
+            dir.clone(),
+        )
+        .unwrap();
+        let now = Local::now();
+
+        // 初回はベースラインの記録のみ
+        assert!(watcher.check(&page(&["VSA-001"]), now).unwrap().is_none());
+        // 変化が無ければ何も出力しない
+        assert!(watcher.check(&page(&["VSA-001"]), now).unwrap().is_none());
+
+        let article = watcher.check(&page(&["VSA-002", "VSA-001"]), now).unwrap().unwrap();
+        assert_eq!(article.title, "Vendor Bulletins updated");
+        assert!(article.article_url.starts_with("https://vendor.example.com/security#change-"));
+        assert!(article.text.contains("+ ") && article.text.contains("VSA-002"));
+        assert!(!article.text.contains("Menu"));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_check_without_snapshot_updates() {
+        let dir = std::env::temp_dir().join(format!("clipper-watcher-{}", uuid::Uuid::new_v4()));
+        let watcher = PageWatcher::with_snapshot_dir(
+            "Status Page",
+            "https://status.example.com/",
+            "main ul.bulletins",
+            dir.clone(),
+        )
+        .unwrap();
+        let now = Local::now();
+
+        without_snapshot_updates(async { watcher.check(&page(&["OK"]), now).unwrap() }).await;
+        assert!(!dir.exists());
+
+        watcher.check(&page(&["OK"]), now).unwrap();
+        let changed = without_snapshot_updates(async { watcher.check(&page(&["Degraded"]), now).unwrap() }).await;
+        assert!(changed.is_some());
+        // 診断で見た変化は，次回の巡回でも変化として検出される
+        assert!(watcher.check(&page(&["Degraded"]), now).unwrap().is_some());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_render_changelog() {
+        let diff = render_changelog("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!(diff, "- b\n+ d");
+    }
+}
@@ -0,0 +1,39 @@
+//! Publickey（publickey1.jp）のエンタープライズ IT・クラウドのニュース．
+
+use super::macros::define_rss_site;
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::web_article::{Html, Text, WebSiteInterface};
+use crate::shared::errors::AppResult;
+
+define_rss_site! {
+    pub struct Publickey {
+        site_name: "Publickey",
+        url: "https://www.publickey1.jp/atom.xml",
+        feed: atom,
+        date: publish_date => parse_from_rfc3339,
+    }
+    impl {
+        fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+            vec![
+                ExtractionStrategy::css(&["div#maincol div.bodytext", "div.entrybody"]),
+                ExtractionStrategy::Readability,
+            ]
+        }
+
+        /// Publickey 固有の除外セレクタ
+        fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+            vec![
+                // 記事末尾の関連記事・カテゴリのリンク
+                "div.relatedentry",
+                "div.entrycategory",
+                // ソーシャルボタン
+                "div.sns_button",
+            ]
+        }
+
+        async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+            let body = self.request_article_page(url, "").await?;
+            self.extract_content(&body)
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Qiita のタグの新着記事．
+//!
+//! Qiita API v2 の `GET /api/v2/tags/<タグ>/items` から記事を取得し，いいね数（`likes_count`）を
+//! `WebArticleProperty::likes` に，ユーザー名を `WebArticle::author` に入れる．
+//! 本文は `GET /api/v2/items/<記事 ID>` の `rendered_body`（HTML）と `body`（Markdown）を使う（記事ページはスクレイピングしない）．
+//! トークンは不要だが，認証情報のファイルでサイトに `Authorization` ヘッダーを設定すればレート制限が緩和される．
+
+use crate::models::request_policy::RequestPolicy;
+use crate::models::sites::generic_feed::FeedKind;
+use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::text::truncate_text;
+use chrono::DateTime;
+use request::Url;
+use serde::Deserialize;
+
+const API_URL: &str = "https://qiita.com/api/v2";
+/// 1回の一覧で取得する記事数
+const PER_PAGE: usize = 20;
+/// 概要にする本文の先頭の最大文字数
+const DESCRIPTION_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Deserialize)]
+struct QiitaUser {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// 一覧・詳細の記事
+#[derive(Debug, Clone, Deserialize)]
+struct QiitaItem {
+    title: String,
+    url: String,
+    /// 作成日時（RFC 3339）
+    created_at: String,
+    #[serde(default)]
+    likes_count: Option<u64>,
+    /// 本文（Markdown）
+    #[serde(default)]
+    body: String,
+    /// 本文（HTML）
+    #[serde(default)]
+    rendered_body: String,
+    user: QiitaUser,
+}
+
+impl QiitaUser {
+    /// 表示名（未設定の場合はユーザー ID）
+    fn display_name(&self) -> String {
+        self.name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(self.id.as_str())
+            .to_string()
+    }
+}
+
+/// 一覧のレスポンスを記事にする（概要は本文の Markdown の先頭）
+fn parse_listing(site_name: &str, site_url: &str, json: &str) -> AppResult<Vec<WebArticle>> {
+    let items: Vec<QiitaItem> = serde_json::from_str(json)
+        .map_err(|e| AppError::FeedParse {
+            site: site_name.to_string(),
+            kind: FeedKind::Json,
+            message: format!("Qiita items: {}", e),
+        })?;
+    items
+        .into_iter()
+        .map(|item| {
+            let mut article = WebArticle::new(
+                site_name.to_string(),
+                site_url.to_string(),
+                item.title,
+                item.url,
+                truncate_text(item.body.trim(), DESCRIPTION_CHARS),
+                DateTime::parse_from_rfc3339(&item.created_at)?.into(),
+            );
+            article.author = Some(item.user.display_name());
+            article.properties.likes = item.likes_count;
+            Ok(article)
+        })
+        .collect()
+}
+
+/// 記事の URL（`https://qiita.com/<ユーザー ID>/items/<記事 ID>`）に対応する API の URL
+fn api_url_for(article_url: &Url) -> AppResult<Url> {
+    let segments: Vec<&str> = article_url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    match segments.as_slice() {
+        [_, "items", id] => Ok(Url::parse(&format!("{}/items/{}", API_URL, id))?),
+        _ => Err(AppError::ScrapeError(format!("Not a Qiita article URL: {}", article_url))),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QiitaTag {
+    site_name: String,
+    url: Url,
+    pub tag: String,
+}
+
+impl QiitaTag {
+    /// `tag`（`LLM`，`Rust` など）の記事を取得する
+    pub fn new(tag: &str) -> Self {
+        let mut url = Url::parse(API_URL).unwrap();
+        url.path_segments_mut().unwrap().push("tags").push(tag).push("items");
+        url.query_pairs_mut().append_pair("per_page", &PER_PAGE.to_string());
+        Self {
+            site_name: format!("Qiita - {}", tag),
+            url,
+            tag: tag.to_string(),
+        }
+    }
+}
+
+impl Default for QiitaTag {
+    fn default() -> Self {
+        Self::new("LLM")
+    }
+}
+
+#[async_trait::async_trait]
+impl WebSiteInterface for QiitaTag {
+    fn site_name(&self) -> String {
+        self.site_name.clone()
+    }
+    fn site_url(&self) -> Url {
+        self.url.clone()
+    }
+    fn domain(&self) -> String {
+        self.url.domain().unwrap().to_string()
+    }
+    fn request_policy(&self) -> RequestPolicy {
+        RequestPolicy::default().with_header("Accept", "application/json")
+    }
+
+    async fn login(&mut self) -> AppResult<Cookie> {
+        Ok(Cookie::default())
+    }
+    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
+        let response = self.request(self.url.as_str(), "").await?;
+        parse_listing(&self.site_name(), self.site_url().as_str(), &response.text().await?)
+    }
+    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+        let api_url = api_url_for(&Url::parse(url)?)?;
+        let body = self.request_text(api_url.as_str(), "").await?;
+        let item: QiitaItem = serde_json::from_str(&body)
+            .map_err(|e| AppError::FeedParse {
+                site: self.site_name(),
+                kind: FeedKind::Json,
+                message: format!("Qiita item: {}", e),
+            })?;
+        if item.body.trim().is_empty() {
+            return Err(AppError::EmptyContent(url.to_string()));
+        }
+        let html = self.clean_content(&item.rendered_body);
+        Ok((self.trim_text(&html), self.trim_text(&item.body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listing() {
+        let json = r##"[
+            {
+                "id": "0123456789abcdef0123",
+                "title": "LLM でコードレビューを自動化する",
+                "url": "https://qiita.com/alice/items/0123456789abcdef0123",
+                "created_at": "2025-10-01T09:30:00+09:00",
+                "likes_count": 42,
+                "stocks_count": 30,
+                "body": "# はじめに\n本文を書きます",
+                "rendered_body": "<h1>はじめに</h1><p>本文</p>",
+                "tags": [{"name": "LLM", "versions": []}],
+                "user": {"id": "alice", "name": "Alice"}
+            },
+            {
+                "title": "名前の無いユーザー",
+                "url": "https://qiita.com/bob/items/abcdef0123456789abcd",
+                "created_at": "2025-10-01T10:00:00+09:00",
+                "user": {"id": "bob", "name": ""}
+            }
+        ]"##;
+        let site = QiitaTag::new("LLM");
+        let articles = parse_listing(&site.site_name(), site.site_url().as_str(), json).unwrap();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].site.name, "Qiita - LLM");
+        assert_eq!(articles[0].title, "LLM でコードレビューを自動化する");
+        assert_eq!(articles[0].author.as_deref(), Some("Alice"));
+        assert_eq!(articles[0].description, "# はじめに\n本文を書きます");
+        assert_eq!(articles[0].properties.likes, Some(42));
+        assert_eq!(articles[0].timestamp, DateTime::parse_from_rfc3339("2025-10-01T09:30:00+09:00").unwrap());
+        assert_eq!(articles[1].author.as_deref(), Some("bob"));
+        assert_eq!(articles[1].properties.likes, None);
+
+        assert!(parse_listing("Qiita", API_URL, r#"{"message": "Not found", "type": "not_found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_api_url_for() {
+        let url = Url::parse("https://qiita.com/alice/items/0123456789abcdef0123").unwrap();
+        assert_eq!(api_url_for(&url).unwrap().as_str(), "https://qiita.com/api/v2/items/0123456789abcdef0123");
+        assert!(api_url_for(&Url::parse("https://qiita.com/alice").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_site_url() {
+        assert_eq!(
+            QiitaTag::new("LLM").site_url().as_str(),
+            "https://qiita.com/api/v2/tags/LLM/items?per_page=20"
+        );
+        assert_eq!(
+            QiitaTag::new("機械学習").site_url().as_str(),
+            "https://qiita.com/api/v2/tags/%E6%A9%9F%E6%A2%B0%E5%AD%A6%E7%BF%92/items?per_page=20"
+        );
+    }
+}
@@ -1,8 +1,10 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use crate::shared::errors::{AppError, AppResult};
 use chrono::DateTime;
 use feed_parser::parsers;
 use request::Url;
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://www.sbbit.jp/rss/HotTopics.rss";
 
@@ -48,7 +50,11 @@ impl WebSiteInterface for Sbbit {
         let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
             Ok(feed) => feed,
             Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
             }
         };
         let articles = feeds
@@ -57,7 +63,7 @@ impl WebSiteInterface for Sbbit {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -73,16 +79,15 @@ impl WebSiteInterface for Sbbit {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let raw = response.text().await?;
+        let raw = self.request_text(url.as_str(), &cookies).await?;
         // ビジネス＋IT is membership-gated; the full text sits behind a 会員登録 /
         // 会員限定 wall. Detect it before extraction and bail out rather than
         // returning a truncated teaser.
         if crate::models::web_article::detect_login_required(&raw) {
             return Err(AppError::LoginRequired);
         }
-        let document = scraper::Html::parse_document(raw.as_str());
-        let selector = scraper::Selector::parse("div.article_note").unwrap();
+        let document = scraper::Html::parse_document(&raw);
+        let selector = selector!("div.article_note");
         match document.select(&selector).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -1,8 +1,8 @@
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use chrono::DateTime;
 use request::Url;
-use scraper::Selector;
 use crate::shared::errors::{AppError, AppResult};
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://stockmark-tech.hatenablog.com/";
 
@@ -50,13 +50,13 @@ impl WebSiteInterface for StockmarkTechBlog {
 
         // parse html
         let mut articles: Vec<WebArticle> = Vec::new();
-        let post_selector = Selector::parse("#main").unwrap();
+        let post_selector = selector!("#main");
         let posts = doc.select(&post_selector);
         for post in posts {
-            let desc_selector = Selector::parse("div.archive-entry-body p.entry-description").unwrap();
-            let title_selector = Selector::parse("div.archive-entry-header").unwrap();
-            let url_selector = Selector::parse("div.archive-entry-header h1 a").unwrap();
-            let date_selector = Selector::parse("div.archive-entry-header div.archive-date").unwrap();
+            let desc_selector = selector!("div.archive-entry-body p.entry-description");
+            let title_selector = selector!("div.archive-entry-header");
+            let url_selector = selector!("div.archive-entry-header h1 a");
+            let date_selector = selector!("div.archive-entry-header div.archive-date");
 
             let title = match post.select(&title_selector).next() {
                 Some(elem) => elem.text().collect(),
@@ -100,16 +100,16 @@ impl WebSiteInterface for StockmarkTechBlog {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let doc = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = Selector::parse("#main div.entry-inner").unwrap();
+        let body = self.request_article_page(url.as_str(), &cookies).await?;
+        let doc = scraper::Html::parse_document(&body);
+        let selector = selector!("#main div.entry-inner");
         let article = match doc.select(&selector).next() {
             Some(article) => article,
             None => {
-                return Err(AppError::ScrapeError(format!(
-                    "Failed to parse article: {:?}",
-                    selector
-                )));
+                return Err(AppError::SelectorNotFound {
+                    site: self.site_name(),
+                    selector: "#main div.entry-inner".into(),
+                });
             }
         };
         let raw_html = article.html().to_string();
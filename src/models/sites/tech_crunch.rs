@@ -1,8 +1,10 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use chrono::DateTime;
 use feed_parser::parsers;
 use request::Url;
 use crate::shared::errors::{AppError, AppResult};
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://techcrunch.com/feed/";
 
@@ -49,7 +51,11 @@ impl WebSiteInterface for TechCrunch {
         let feeds = match parsers::rss1::parse(response.text().await?.as_str()) {
             Ok(feeds) => feeds,
             Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss1,
+                    message: e.to_string(),
+                });
             }
         };
         let articles = feeds
@@ -58,7 +64,7 @@ impl WebSiteInterface for TechCrunch {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -74,16 +80,9 @@ impl WebSiteInterface for TechCrunch {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        // 全体をクリーンにしてからセレクタで選択
-        let cleaned_response = self.clean_content(&response.text().await?);
-        let document = scraper::Html::parse_document(&cleaned_response);
-        let selector = scraper::Selector::parse("main div.entry-content p").unwrap();
-        let html = document
-            .select(&selector)
-            .map(|x| x.html())
-            .collect::<Vec<_>>()
-            .join("\n");
+        let body = self.request_text(url.as_str(), &cookies).await?;
+        // 本文段落の選択と不要要素の除去を1回の DOM パースで行う
+        let html = self.select_clean(&body, "main div.entry-content p")?.join("\n");
         let text = html2md::rewrite_html(&html, false);
         Ok((self.trim_text(&html), self.trim_text(&text)))
     }
@@ -1,8 +1,10 @@
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use crate::shared::errors::{AppError, AppResult};
 use chrono::DateTime;
 use feed_parser::parsers;
 use request::Url;
+use crate::shared::selectors::selector;
 
 const URL: &str = "https://www.techno-edge.net/rss20/index.rdf";
 
@@ -53,7 +55,11 @@ impl WebSiteInterface for TechnoEdge {
             _ => match parsers::rss1::parse(body.as_str()) {
                 Ok(feed) => feed,
                 Err(e) => {
-                    return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
+                    return Err(AppError::FeedParse {
+                        site: self.site_name(),
+                        kind: FeedKind::Rss1,
+                        message: e.to_string(),
+                    });
                 }
             },
         };
@@ -63,7 +69,7 @@ impl WebSiteInterface for TechnoEdge {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -79,9 +85,9 @@ impl WebSiteInterface for TechnoEdge {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = scraper::Selector::parse("article.arti-body").unwrap();
+        let body = self.request_article_page(url.as_str(), &cookies).await?;
+        let document = scraper::Html::parse_document(&body);
+        let selector = selector!("article.arti-body");
         match document.select(&selector).next() {
             Some(elem) => {
                 let raw_html = elem.html().to_string();
@@ -89,7 +95,10 @@ impl WebSiteInterface for TechnoEdge {
                 let text = html2md::rewrite_html(&html, false);
                 Ok((self.trim_text(&html), self.trim_text(&text)))
             }
-            None => Err(AppError::ScrapeError("Failed to parse article text".into())),
+            None => Err(AppError::SelectorNotFound {
+                site: self.site_name(),
+                selector: "article.arti-body".into(),
+            }),
         }
     }
 }
@@ -0,0 +1,71 @@
+//! The Hacker News（thehackernews.com）のセキュリティニュース．
+//!
+//! フィードは FeedBurner で配信されるため，記事の取得には thehackernews.com のドメインも許可する．
+//! 本文の途中に広告・購読の案内が多く挟まるため，サイト固有の除外セレクタで取り除く．
+
+use super::macros::define_rss_site;
+use crate::shared::domain_guard::default_allowed_domains;
+
+define_rss_site! {
+    pub struct TheHackerNews {
+        site_name: "The Hacker News",
+        url: "https://feeds.feedburner.com/TheHackersNews",
+        feed: rss2,
+        date: publish_date => parse_from_rfc2822,
+        content: first_match("div.articlebody"),
+    }
+    impl {
+        fn allowed_domains(&self) -> Vec<String> {
+            let mut domains = default_allowed_domains(&self.site_name(), &self.domain());
+            domains.push("thehackernews.com".to_string());
+            domains
+        }
+
+        /// The Hacker News 固有の除外セレクタ
+        fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+            vec![
+                // 本文中の広告・スポンサーのバナー
+                ".dog_two",
+                ".ad_two",
+                ".check_two",
+                "div[class*='sponsor']",
+                // 購読・フォローの案内
+                ".cf.note-b",
+                ".stophere",
+                ".below-post-box",
+                // 著者・日付の欄（メタデータとして別に取得する）
+                ".postmeta",
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::sites::macros::first_match;
+    use crate::models::web_article::WebSiteInterface;
+
+    #[test]
+    fn test_first_match_removes_inline_ads() {
+        let page = r#"<html><body><div class="main-box"><div class="articlebody clear cf" id="articlebody">
+<p>A critical flaw in Example Gateway is being actively exploited.</p>
+<div class="dog_two clear"><a href="https://ads.example.com"><img src="banner.png"/></a></div>
+<p>Patches are available for versions 1.2 and later.</p>
+<div class="cf note-b">Found this article interesting? Follow us on Twitter.</div>
+</div></div></body></html>"#;
+        let site = TheHackerNews::default();
+        let (html, text) = first_match(&site, page, "div.articlebody").unwrap();
+        assert!(text.contains("actively exploited"));
+        assert!(text.contains("Patches are available"));
+        assert!(!html.contains("banner.png"));
+        assert!(!text.contains("Follow us"));
+    }
+
+    #[test]
+    fn test_allowed_domains_include_article_domain() {
+        let site = TheHackerNews::default();
+        let url = request::Url::parse("https://thehackernews.com/2025/10/example.html").unwrap();
+        assert!(site.ensure_allowed_url(&url).is_ok());
+    }
+}
@@ -0,0 +1,80 @@
+//! The Verge の新着記事．
+//!
+//! フィードは記事の冒頭だけの部分配信のため，本文は記事ページから抽出する（セレクタで見つからなければ Readability 風の抽出）．
+//! 購読者限定の記事は無料で読める部分だけを `AppError::PaywallError` で返す．
+
+use super::macros::define_rss_site;
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::web_article::{Html, Text, WebSiteInterface};
+use crate::shared::errors::{AppError, AppResult};
+
+/// 購読者限定の記事で本文が途切れていることを示すマーカー
+const PAYWALL_MARKERS: &[&str] = &["data-paywall", "duet--article--paywall", "class=\"paywall"];
+
+/// 購読者限定の記事で本文が途切れているか
+pub fn is_paywalled(raw_html: &str) -> bool {
+    PAYWALL_MARKERS.iter().any(|marker| raw_html.contains(marker))
+}
+
+define_rss_site! {
+    pub struct TheVerge {
+        site_name: "The Verge",
+        url: "https://www.theverge.com/rss/index.xml",
+        feed: atom,
+        date: publish_date => parse_from_rfc3339,
+    }
+    impl {
+        fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+            vec![
+                ExtractionStrategy::css(&["div[class*='article-body-component']", "article div.duet--article--body"]),
+                ExtractionStrategy::Readability,
+            ]
+        }
+
+        /// The Verge 固有の除外セレクタ
+        fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+            vec![
+                // 本文中の関連記事・ニュースレターの案内
+                "div[class*='related-link']",
+                "div[class*='newsletter']",
+                // 記事をフォローするボタン
+                "div[class*='follow-topic']",
+            ]
+        }
+
+        async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
+            let body = self.request_article_page(url, "").await?;
+            if is_paywalled(&body) {
+                let preview = self.extract_content(&body).map(|(_, text)| text).unwrap_or_default();
+                return Err(AppError::PaywallError { preview });
+            }
+            self.extract_content(&body)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_content_removes_newsletter_prompts() {
+        let page = r#"<html><body><article>
+<div class="duet--article--article-body-component">
+<p>The new phone ships next month with a bigger battery.</p>
+<div class="duet--article--newsletter-signup">Sign up for the newsletter</div>
+<p>Preorders open on Friday.</p>
+</div>
+</article></body></html>"#;
+        let (html, text) = TheVerge::default().extract_content(page).unwrap();
+        assert!(text.contains("bigger battery"));
+        assert!(text.contains("Preorders open on Friday."));
+        assert!(!html.contains("Sign up for the newsletter"));
+    }
+
+    #[test]
+    fn test_is_paywalled() {
+        assert!(is_paywalled(r#"<div class="duet--article--paywall">Subscribe to continue reading</div>"#));
+        assert!(!is_paywalled(r#"<p>Why every site now has a paywall</p>"#));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::models::extraction::ExtractionStrategy;
+use crate::models::sites::generic_feed::FeedKind;
 use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
 use chrono::DateTime;
 use feed_parser::parsers;
@@ -42,6 +44,13 @@ impl WebSiteInterface for TokyoUniversityEngineering {
     fn domain(&self) -> String {
         self.url.domain().unwrap().to_string()
     }
+    fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+        vec![ExtractionStrategy::css(&[
+            "div.blog-body-1__content",
+            "main div.ly_cont div.blog_title",
+            "div.bl_wysiwyg",
+        ])]
+    }
 
     async fn login(&mut self) -> AppResult<Cookie> {
         Ok(Cookie::default())
@@ -52,7 +61,11 @@ impl WebSiteInterface for TokyoUniversityEngineering {
         let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
             Ok(feeds) => feeds,
             Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
+                return Err(AppError::FeedParse {
+                    site: self.site_name(),
+                    kind: FeedKind::Rss2,
+                    message: e.to_string(),
+                });
             }
         };
         let articles = feeds
@@ -61,7 +74,7 @@ impl WebSiteInterface for TokyoUniversityEngineering {
                 let publish_date = feed
                     .publish_date
                     .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
+                    .ok_or_else(|| AppError::DateParse("Missing publish_date".into()))?;
                 Ok(WebArticle::new(
                     self.site_name(),
                     self.site_url().to_string(),
@@ -77,35 +90,7 @@ impl WebSiteInterface for TokyoUniversityEngineering {
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
         let url = Url::parse(url).unwrap();
         let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-
-        let selectors = [
-            "div.blog-body-1__content",
-            "main div.ly_cont div.blog_title",
-            "div.bl_wysiwyg",
-        ];
-
-        let mut article_element = None;
-        for sel_str in &selectors {
-            let selector = scraper::Selector::parse(sel_str).unwrap();
-            if let Some(element) = document.select(&selector).next() {
-                article_element = Some(element);
-                break;
-            }
-        }
-
-        let article = match article_element {
-            Some(article) => article,
-            None => {
-                return Err(AppError::ScrapeError(
-                    "Failed to find article content: no matching selector found for Tokyo University Engineering".into(),
-                ));
-            }
-        };
-        let raw_html = article.html().to_string();
-        let html = self.clean_content(&raw_html);
-        let text = html2md::rewrite_html(&html, false);
-        Ok((self.trim_text(&html), self.trim_text(&text)))
+        let body = self.request_article_page(url.as_str(), &cookies).await?;
+        self.extract_content(&body)
     }
 }
@@ -1,107 +1,25 @@
-use crate::models::web_article::{Cookie, Html, Text, WebArticle, WebSiteInterface};
-use chrono::DateTime;
-use feed_parser::parsers;
-use request::Url;
-use crate::shared::errors::{AppError, AppResult};
-
-const URL: &str = "https://zenn.dev/feed";
-
-#[derive(Debug, Clone)]
-pub struct ZennTrend {
-    site_name: String,
-    url: Url,
-}
-
-impl ZennTrend {
-    pub fn new() -> Self {
-        Self {
-            site_name: "Zenn Trend".to_string(),
-            url: Url::parse(URL).unwrap(),
+use super::macros::define_rss_site;
+
+define_rss_site! {
+    pub struct ZennTrend {
+        site_name: "Zenn Trend",
+        url: "https://zenn.dev/feed",
+        feed: rss2,
+        date: publish_date => parse_from_rfc2822,
+        content: first_match("article section"),
+    }
+    impl {
+        /// Zenn固有の除外セレクタ
+        fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
+            vec![
+                // いいね・ブックマークボタン
+                ".LikeButton",
+                ".BookmarkButton",
+                // 著者プロフィール
+                ".AuthorProfile",
+                // サポートボタン
+                ".SupportButton",
+            ]
         }
     }
 }
-
-impl Default for ZennTrend {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[async_trait::async_trait]
-impl WebSiteInterface for ZennTrend {
-
-    fn site_name(&self) -> String {
-        self.site_name.clone()
-    }
-    fn site_url(&self) -> Url {
-        self.url.clone()
-    }
-    fn domain(&self) -> String {
-        self.url.domain().unwrap().to_string()
-    }
-
-    /// Zenn固有の除外セレクタ
-    fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
-        vec![
-            // いいね・ブックマークボタン
-            ".LikeButton",
-            ".BookmarkButton",
-            // 著者プロフィール
-            ".AuthorProfile",
-            // サポートボタン
-            ".SupportButton",
-        ]
-    }
-
-    async fn login(&mut self) -> AppResult<Cookie> {
-        Ok(Cookie::default())
-    }
-    async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>> {
-        let cookies = self.login().await?;
-        let response = self.request(self.url.as_str(), &cookies).await?;
-        let feeds = match parsers::rss2::parse(response.text().await?.as_str()) {
-            Ok(feeds) => feeds,
-            Err(e) => {
-                return Err(AppError::ScrapeError(format!("Failed to parse RSS: {}", e)));
-            }
-        };
-        let articles = feeds
-            .iter()
-            .map(|feed| -> AppResult<WebArticle> {
-                let publish_date = feed
-                    .publish_date
-                    .clone()
-                    .ok_or_else(|| AppError::ScrapeError("Missing publish_date".into()))?;
-                Ok(WebArticle::new(
-                    self.site_name(),
-                    self.site_url().to_string(),
-                    feed.title.clone(),
-                    feed.link.clone(),
-                    feed.description.clone().unwrap_or("".to_string()),
-                    DateTime::parse_from_rfc2822(&publish_date)?.into(),
-                ))
-            })
-            .collect::<AppResult<Vec<WebArticle>>>()?;
-        Ok(articles)
-    }
-    async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)> {
-        let url = Url::parse(url).unwrap();
-        let cookies = self.login().await?;
-        let response = self.request(url.as_str(), &cookies).await?;
-        let document = scraper::Html::parse_document(response.text().await?.as_str());
-        let selector = scraper::Selector::parse("article section").unwrap();
-        let article = match document.select(&selector).next() {
-            Some(article) => article,
-            None => {
-                return Err(AppError::ScrapeError(format!(
-                    "Failed to parse article: {:?}",
-                    selector
-                )));
-            }
-        };
-        let raw_html = article.html().to_string();
-        let html = self.clean_content(&raw_html);
-        let text = html2md::rewrite_html(&html, false);
-        Ok((self.trim_text(&html), self.trim_text(&text)))
-    }
-}
@@ -0,0 +1,48 @@
+//! 記事の分類タグ．
+//!
+//! LLM の分類や取得元のサイトで付けたタグは `WebArticleProperty` のフラグとして保存し，
+//! ダイジェストのセクションやブリーフの絞り込みはこのタグで記事を選ぶ．
+
+use crate::models::web_article::{WebArticle, WebArticleProperty};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// 分類で付けたタグ（`WebArticleProperty` のフラグ）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ArticleTag {
+    Ai,
+    Security,
+    NewTechnology,
+    NewProduct,
+    AcademicPaper,
+    It,
+}
+
+impl ArticleTag {
+    pub fn matches(self, article: &WebArticle) -> bool {
+        let properties = &article.properties;
+        let flag = match self {
+            ArticleTag::Ai => properties.is_ai_related,
+            ArticleTag::Security => properties.is_security_related,
+            ArticleTag::NewTechnology => properties.is_new_technology_related,
+            ArticleTag::NewProduct => properties.is_new_product_related,
+            ArticleTag::AcademicPaper => properties.is_new_academic_paper_related,
+            ArticleTag::It => properties.is_it_related,
+        };
+        flag == Some(true)
+    }
+
+    /// タグに対応する `WebArticleProperty` のフラグ
+    pub fn flag_mut(self, properties: &mut WebArticleProperty) -> &mut Option<bool> {
+        match self {
+            ArticleTag::Ai => &mut properties.is_ai_related,
+            ArticleTag::Security => &mut properties.is_security_related,
+            ArticleTag::NewTechnology => &mut properties.is_new_technology_related,
+            ArticleTag::NewProduct => &mut properties.is_new_product_related,
+            ArticleTag::AcademicPaper => &mut properties.is_new_academic_paper_related,
+            ArticleTag::It => &mut properties.is_it_related,
+        }
+    }
+}
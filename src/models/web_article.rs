@@ -1,12 +1,35 @@
+use crate::archive::warc::global_warc_writer;
+use crate::enrichment::deadlines::Deadline;
+use crate::enrichment::license::ArticleLicense;
+use crate::enrichment::robots::{RobotsDirectives, global_robots_policies, is_html};
+use crate::models::tag::ArticleTag;
+use crate::models::extraction::{ExtractedArticle, ExtractionStrategy, extract_with_strategies};
+use crate::models::fairness::SiteCategory;
+use crate::models::fixture::fixture_response;
+use crate::models::ordering::newest_first;
+use crate::models::priority::SitePriority;
+use crate::models::quality::{self, ExtractionMetrics};
+use crate::models::request_policy::RequestPolicy;
+use crate::shared::credentials::global_credentials;
+use crate::shared::domain_guard::{default_allowed_domains, is_allowed_host};
 use crate::shared::errors::{AppError, AppResult};
+use crate::shared::fetch_cache::global_fetch_cache;
+use crate::shared::html_stream::StreamingCleaner;
+use crate::shared::memory::{BudgetedText, DEFAULT_BODY_ESTIMATE, budgeted_text, global_memory_budget};
+use crate::shared::proxy::{ProxyRoute, global_proxy_settings};
+use crate::shared::rate_limit::global_rate_limiter;
+use crate::shared::regexes;
+use crate::shared::retry::global_retry_policy;
+use crate::shared::selectors::{cached_selector, selector};
+use crate::shared::text::global_text_normalizer;
 use chrono::{DateTime, Local};
 use derive_new::new;
-use regex::Regex;
-use request::{Response, Url};
+use request::{Response, StatusCode, Url};
 use scraper::Selector;
 use serde::{Deserialize, Serialize};
 use std::boxed::Box;
-use std::sync::OnceLock;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
 use strum::{Display, EnumString};
 
 pub type Html = String;
@@ -62,6 +85,9 @@ const EXCLUDE_SELECTORS: &[&str] = &[
     ".visually-hidden",
 ];
 
+/// ストリーミングのクリーニングでは残す要素（メタデータの抽出に使うため，本文の抽出後に取り除く）
+const STREAMING_KEEP_SELECTORS: &[&str] = &["header", "footer", "[role='banner']", "[role='contentinfo']"];
+
 /// HTMLから除外対象の要素を削除する
 pub fn clean_html(html: &str) -> String {
     clean_html_with_selectors(html, &[])
@@ -69,13 +95,26 @@ pub fn clean_html(html: &str) -> String {
 
 /// HTMLから除外対象の要素を削除する（サイト固有のセレクタを追加可能）
 pub fn clean_html_with_selectors(html: &str, additional_selectors: &[&str]) -> String {
+    clean_html_with_rules(html, additional_selectors, &[])
+}
+
+/// HTMLから除外対象の要素を削除する．
+/// `exempt_selectors` に含まれる共通除外セレクタは適用しない（記事本文で正当に使われる `aside` や `form` 等）．
+pub fn clean_html_with_rules(
+    html: &str,
+    additional_selectors: &[&str],
+    exempt_selectors: &[&str],
+) -> String {
     let doc = scraper::Html::parse_document(html);
     let mut excluded_fragments: Vec<String> = Vec::new();
 
-    // 共通の除外セレクタを処理
-    for selector_str in EXCLUDE_SELECTORS {
-        if let Ok(selector) = Selector::parse(selector_str) {
-            for elem in doc.select(&selector) {
+    // 共通の除外セレクタを処理（サイトが免除したものを除く）
+    for selector_str in EXCLUDE_SELECTORS
+        .iter()
+        .filter(|s| !exempt_selectors.contains(s))
+    {
+        if let Ok(selector) = cached_selector(selector_str) {
+            for elem in doc.select(selector) {
                 let fragment = elem.html();
                 if !excluded_fragments.contains(&fragment) {
                     excluded_fragments.push(fragment);
@@ -86,8 +125,8 @@ pub fn clean_html_with_selectors(html: &str, additional_selectors: &[&str]) -> S
 
     // サイト固有の除外セレクタを処理
     for selector_str in additional_selectors {
-        if let Ok(selector) = Selector::parse(selector_str) {
-            for elem in doc.select(&selector) {
+        if let Ok(selector) = cached_selector(selector_str) {
+            for elem in doc.select(selector) {
                 let fragment = elem.html();
                 if !excluded_fragments.contains(&fragment) {
                     excluded_fragments.push(fragment);
@@ -106,8 +145,173 @@ pub fn clean_html_with_selectors(html: &str, additional_selectors: &[&str]) -> S
     }
 
     // 連続する空白行を整理
-    let re = Regex::new(r"\n\s*\n\s*\n").unwrap();
-    re.replace_all(&cleaned, "\n\n").to_string()
+    regexes::blank_lines().replace_all(&cleaned, "\n\n").to_string()
+}
+
+/// 終了タグを持たない void 要素
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+pub(crate) fn escape_html_text(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\u{a0}' => out.push_str("&nbsp;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+pub(crate) fn escape_html_attr(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\u{a0}' => out.push_str("&nbsp;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// 除外セレクタにマッチする子孫を飛ばしながら要素を HTML 文字列に書き出す
+fn write_without_excluded(
+    node: ego_tree::NodeRef<'_, scraper::Node>,
+    excludes: &[&Selector],
+    out: &mut String,
+) {
+    match node.value() {
+        scraper::Node::Element(elem) => {
+            let Some(elem_ref) = scraper::ElementRef::wrap(node) else {
+                return;
+            };
+            if excludes.iter().any(|sel| sel.matches(&elem_ref)) {
+                return;
+            }
+            out.push('<');
+            out.push_str(elem.name());
+            for (name, value) in elem.attrs() {
+                out.push(' ');
+                out.push_str(name);
+                out.push_str("=\"");
+                escape_html_attr(value, out);
+                out.push('"');
+            }
+            out.push('>');
+            if VOID_ELEMENTS.contains(&elem.name()) {
+                return;
+            }
+            for child in node.children() {
+                write_without_excluded(child, excludes, out);
+            }
+            out.push_str("</");
+            out.push_str(elem.name());
+            out.push('>');
+        }
+        scraper::Node::Text(text) => {
+            let raw_text_parent = node
+                .parent()
+                .and_then(|p| p.value().as_element().map(|e| matches!(e.name(), "script" | "style")))
+                .unwrap_or(false);
+            if raw_text_parent {
+                out.push_str(text);
+            } else {
+                escape_html_text(text, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 1回の DOM 構築で「本文要素の選択」と「不要要素の除去」を同時に行う．
+///
+/// `clean_html_with_selectors` でページ全体を文字列として掃除してから再パースする代わりに，
+/// 選択した要素をシリアライズする際に除外セレクタにマッチする子孫を書き出さない．
+/// 除外対象の要素の内側にある選択結果（`aside` 内の `p` など）も結果に含めない．
+pub fn select_clean_fragments(
+    html: &str,
+    selector: &str,
+    exclude_selectors: &[&str],
+) -> AppResult<Vec<String>> {
+    let doc = scraper::Html::parse_document(html);
+    let target = cached_selector(selector)?;
+    let excludes: Vec<&Selector> = exclude_selectors
+        .iter()
+        .filter_map(|s| cached_selector(s).ok())
+        .collect();
+
+    let selected: Vec<scraper::ElementRef> = doc
+        .select(target)
+        .filter(|elem| {
+            !elem.ancestors().filter_map(scraper::ElementRef::wrap).any(|ancestor| {
+                excludes.iter().any(|sel| sel.matches(&ancestor))
+            })
+        })
+        .collect();
+    let fragments: Vec<String> = selected
+        .iter()
+        .map(|elem| {
+            let mut out = String::with_capacity(elem.html().len());
+            write_without_excluded(**elem, &excludes, &mut out);
+            out
+        })
+        .filter(|fragment| !fragment.is_empty())
+        .collect();
+
+    if quality::is_collecting() {
+        quality::record(measure_extraction(&doc, &selected, &fragments));
+    }
+    Ok(fragments)
+}
+
+/// ページ全体・選択要素・除去後の断片からテキスト量と画像数を計測する
+fn measure_extraction(
+    doc: &scraper::Html,
+    selected: &[scraper::ElementRef],
+    fragments: &[String],
+) -> ExtractionMetrics {
+    let text_chars = |elem: &scraper::ElementRef| -> usize {
+        elem.text().map(|t| t.trim().chars().count()).sum()
+    };
+    let mut metrics = ExtractionMetrics {
+        page_text_chars: text_chars(&doc.root_element()),
+        selected_text_chars: selected.iter().map(text_chars).sum(),
+        page_images: doc.select(selector!("img")).count(),
+        ..Default::default()
+    };
+    for fragment in fragments {
+        let fragment = scraper::Html::parse_fragment(fragment);
+        metrics.extracted_text_chars += text_chars(&fragment.root_element());
+        metrics.retained_images += fragment.select(selector!("img")).count();
+    }
+    metrics
+}
+
+/// 共通の除外セレクタにサイト固有のセレクタを加えた一覧を返す
+pub fn exclude_selectors_with(additional_selectors: &[&'static str]) -> Vec<&'static str> {
+    effective_exclude_selectors(additional_selectors, &[])
+}
+
+/// 共通の除外セレクタから `exempt_selectors` を除き，サイト固有のセレクタを加えた一覧を返す．
+/// 共通リストに存在しないセレクタを免除しようとした場合は設定ミスとして警告する．
+pub fn effective_exclude_selectors<'a>(
+    additional_selectors: &[&'a str],
+    exempt_selectors: &[&str],
+) -> Vec<&'a str> {
+    for exempt in exempt_selectors {
+        if !EXCLUDE_SELECTORS.contains(exempt) {
+            tracing::warn!("Exempted selector is not in EXCLUDE_SELECTORS: {}", exempt);
+        }
+    }
+    EXCLUDE_SELECTORS
+        .iter()
+        .filter(|s| !exempt_selectors.contains(s))
+        .chain(additional_selectors.iter())
+        .copied()
+        .collect()
 }
 
 /// 本文らしさを判定するためのスコアリング用セレクタ
@@ -149,9 +353,8 @@ fn calculate_link_density(elem: &scraper::ElementRef) -> f64 {
         return 0.0;
     }
 
-    let link_selector = Selector::parse("a").unwrap();
     let link_text_len: usize = elem
-        .select(&link_selector)
+        .select(selector!("a"))
         .map(|a| a.text().collect::<String>().len())
         .sum();
 
@@ -175,8 +378,7 @@ fn calculate_content_score(elem: &scraper::ElementRef) -> f64 {
     score -= link_density * 50.0;
 
     // 段落タグの数（多いほど良い）
-    let p_selector = Selector::parse("p").unwrap();
-    let p_count = elem.select(&p_selector).count();
+    let p_count = elem.select(selector!("p")).count();
     score += (p_count as f64).min(10.0) * 5.0;
 
     // テキスト長ボーナス（一定以上のテキストがある場合）
@@ -207,14 +409,32 @@ fn calculate_content_score(elem: &scraper::ElementRef) -> f64 {
     score
 }
 
-/// Readability風のヒューリスティックで本文を抽出する
+/// Readability風のヒューリスティックで本文を抽出する．
+/// 抽出品質の計測中であれば，フォールバック抽出として記録する．
 pub fn extract_main_content(html: &str) -> Option<String> {
     let doc = scraper::Html::parse_document(html);
+    extract_main_content_from(&doc)
+}
+
+/// パース済みのドキュメントから本文を抽出する（`extract_main_content` と同じく抽出品質を記録する）
+pub(crate) fn extract_main_content_from(doc: &scraper::Html) -> Option<String> {
+    let content = find_main_content(doc);
+    if let Some(content) = content.as_ref().filter(|_| quality::is_collecting()) {
+        let fragment = scraper::Html::parse_fragment(content);
+        let selected: Vec<scraper::ElementRef> = fragment.root_element().child_elements().collect();
+        let mut metrics = measure_extraction(doc, &selected, std::slice::from_ref(content));
+        metrics.used_fallback = true;
+        quality::record(metrics);
+    }
+    content
+}
+
+fn find_main_content(doc: &scraper::Html) -> Option<String> {
 
     // まず、本文らしいセレクタで要素を探す
     for selector_str in CONTENT_SELECTORS {
-        if let Ok(selector) = Selector::parse(selector_str) {
-            if let Some(elem) = doc.select(&selector).next() {
+        if let Ok(selector) = cached_selector(selector_str) {
+            if let Some(elem) = doc.select(selector).next() {
                 let text: String = elem.text().collect();
                 // 十分なテキスト量がある場合は採用
                 if text.len() > 200 {
@@ -225,11 +445,11 @@ pub fn extract_main_content(html: &str) -> Option<String> {
     }
 
     // セレクタで見つからない場合、スコアリングで最適な要素を探す
-    let candidates_selector = Selector::parse("div, section, article, main").unwrap();
+    let candidates_selector = selector!("div, section, article, main");
     let mut best_score = 0.0;
     let mut best_html: Option<String> = None;
 
-    for elem in doc.select(&candidates_selector) {
+    for elem in doc.select(candidates_selector) {
         let text: String = elem.text().collect();
 
         // 最低限のテキスト量がない要素はスキップ
@@ -240,8 +460,8 @@ pub fn extract_main_content(html: &str) -> Option<String> {
         // 非コンテンツ要素はスキップ
         let elem_html = elem.html();
         let is_non_content = NON_CONTENT_SELECTORS.iter().any(|sel| {
-            if let Ok(s) = Selector::parse(sel) {
-                doc.select(&s).any(|e| e.html() == elem_html)
+            if let Ok(s) = cached_selector(sel) {
+                doc.select(s).any(|e| e.html() == elem_html)
             } else {
                 false
             }
@@ -265,8 +485,8 @@ pub fn extract_content_with_fallback(html: &str, primary_selector: &str) -> Opti
     let doc = scraper::Html::parse_document(html);
 
     // まずプライマリセレクタを試す
-    if let Ok(selector) = Selector::parse(primary_selector) {
-        if let Some(elem) = doc.select(&selector).next() {
+    if let Ok(selector) = cached_selector(primary_selector) {
+        if let Some(elem) = doc.select(selector).next() {
             return Some(elem.html());
         }
     }
@@ -275,7 +495,7 @@ pub fn extract_content_with_fallback(html: &str, primary_selector: &str) -> Opti
     extract_main_content(html)
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, Display, EnumString)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
 pub enum Status {
     #[default]
     #[serde(rename = "new")]
@@ -286,7 +506,7 @@ pub enum Status {
     Archived,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebArticleProperty {
     pub summary: Option<String>,
     pub is_new_technology_related: Option<bool>,
@@ -295,6 +515,45 @@ pub struct WebArticleProperty {
     pub is_ai_related: Option<bool>,
     pub is_security_related: Option<bool>,
     pub is_it_related: Option<bool>,
+    /// ページから検出したライセンス・著作権表示
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<ArticleLicense>,
+    /// 適用した robots の指定（`noarchive` / `nosnippet` / `max-snippet`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub robots: Option<RobotsDirectives>,
+    /// 記事の代表画像の URL（`og:image` など）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// キャッシュした代表画像の URL（[`crate::assets`]）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_image: Option<String>,
+    /// 取得元のサイトが付けたタグ（分類し直しても外れない）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_tags: Vec<ArticleTag>,
+    /// 脆弱性情報の記事の CVE（[`crate::models::sites::nvd_cve`]）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vulnerability: Option<Vulnerability>,
+    /// 取得元のサイトでのいいね数（取得できるサイトのみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub likes: Option<u64>,
+    /// 本文から検出した論文投稿などの締切（[`crate::enrichment::deadlines`]）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deadlines: Vec<Deadline>,
+}
+
+/// CVE の構造化データ
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vulnerability {
+    pub cve_id: String,
+    /// CVSS の基本値
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cvss_score: Option<f64>,
+    /// CVSS の深刻度（`CRITICAL`，`HIGH`，`MEDIUM`，`LOW` など）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// 影響を受ける製品（CPE の `<ベンダー>:<製品>`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affected_products: Vec<String>,
 }
 
 impl Default for WebArticleProperty {
@@ -307,16 +566,75 @@ impl Default for WebArticleProperty {
             is_ai_related: Some(false),
             is_security_related: Some(false),
             is_it_related: Some(false),
+            license: None,
+            robots: None,
+            image: None,
+            cached_image: None,
+            source_tags: Vec::new(),
+            vulnerability: None,
+            likes: None,
+            deadlines: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, new, Default, Serialize, Deserialize)]
+impl WebArticleProperty {
+    /// 取得元のサイトとしてタグを付ける
+    pub fn tag_by_source(&mut self, tag: ArticleTag) {
+        if !self.source_tags.contains(&tag) {
+            self.source_tags.push(tag);
+        }
+        *tag.flag_mut(self) = Some(true);
+    }
+}
+
+#[derive(Debug, Clone, new, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WebSite {
     pub name: String,
     pub url: String,
 }
 
+/// 解放済みの項目を取り除く件数の下限
+const MIN_INTERNER_PRUNE: usize = 256;
+
+static SITE_INTERNER: OnceLock<Mutex<SiteInterner>> = OnceLock::new();
+
+/// `WebSite::intern` の登録先．弱参照で保持するため，記事が全て破棄されたサイト
+/// （`POST /clip` で一度だけ取得した任意のホストなど）は解放され，登録数は生きている記事のサイト数に比例する．
+#[derive(Debug, Default)]
+struct SiteInterner {
+    sites: HashMap<(String, String), Weak<WebSite>>,
+    /// 登録数がこれを超えたら解放済みの項目を取り除く（取り除いた後の2倍に更新する）
+    prune_at: usize,
+}
+
+impl WebSite {
+    /// 同じ (name, url) の `WebSite` をプロセス内で1つの `Arc` に集約して返す．
+    /// 1サイトあたり数百〜数万件の `WebArticle` がサイト名・URL の文字列を複製しないようにする．
+    pub fn intern(name: String, url: String) -> Arc<WebSite> {
+        let interner = SITE_INTERNER.get_or_init(|| Mutex::new(SiteInterner::default()));
+        let mut interner = interner.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (name, url);
+        if let Some(site) = interner.sites.get(&key).and_then(Weak::upgrade) {
+            return site;
+        }
+        let site = Arc::new(WebSite::new(key.0.clone(), key.1.clone()));
+        interner.sites.insert(key, Arc::downgrade(&site));
+        if interner.sites.len() > interner.prune_at {
+            interner.sites.retain(|_, site| site.strong_count() > 0);
+            interner.prune_at = (interner.sites.len() * 2).max(MIN_INTERNER_PRUNE);
+        }
+        site
+    }
+
+    /// `intern` の登録数（解放済みでまだ取り除いていない項目を含む）
+    pub fn interned_count() -> usize {
+        SITE_INTERNER
+            .get()
+            .map_or(0, |interner| interner.lock().unwrap_or_else(|e| e.into_inner()).sites.len())
+    }
+}
+
 /// 全文が会員ログイン／ペイウォールの背後にあることを示すマーカー文字列の一覧．
 /// `detect_login_required` で利用する．誤検知を抑えるため，
 /// 単独の「ログイン」「会員」のような一般語ではなく，
@@ -368,7 +686,7 @@ pub fn detect_login_required(raw_html: &str) -> bool {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebArticle {
-    pub site: WebSite,
+    pub site: Arc<WebSite>,
     pub title: String,
     pub article_url: String,
     pub description: String,
@@ -378,6 +696,12 @@ pub struct WebArticle {
     pub html: String,
     #[serde(default)]
     pub requires_login: bool,
+    /// 記事の言語（`ja`，`en` など．判定できなければ `None`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// 記事の著者（note のクリエイターなど，フィードで分かる場合のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
 }
 
 impl WebArticle {
@@ -389,7 +713,7 @@ impl WebArticle {
         description: String,
         timestamp: DateTime<Local>,
     ) -> Self {
-        let reg_cdata = Regex::new(r"<!\[CDATA\[(?<text>.+?)\]\]>").unwrap();
+        let reg_cdata = regexes::cdata();
         let title = reg_cdata
             .captures(&title)
             .and_then(|cap| cap.name("text").map(|m| m.as_str().to_string()))
@@ -400,10 +724,7 @@ impl WebArticle {
             .unwrap_or(description);
         let description = html2md::rewrite_html(&description, false);
         Self {
-            site: WebSite {
-                name: site_name.clone(),
-                url: site_url.clone(),
-            },
+            site: WebSite::intern(site_name, site_url),
             title,
             article_url,
             description,
@@ -412,33 +733,79 @@ impl WebArticle {
             text: "".to_string(),
             html: "".to_string(),
             requires_login: false,
+            lang: None,
+            author: None,
         }
     }
 }
 
-static HTTP_CLIENT: OnceLock<request::Client> = OnceLock::new();
+/// `get_articles_with` で取得する記事の範囲
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FetchOptions {
+    /// この時刻以降の記事のみ
+    pub since: Option<DateTime<Local>>,
+    /// この時刻より前の記事のみ
+    pub until: Option<DateTime<Local>>,
+    /// サイトごとの最大件数（新しい順に残す）
+    pub limit: Option<usize>,
+}
 
-fn shared_client() -> &'static request::Client {
-    HTTP_CLIENT.get_or_init(|| {
-        let mut headers = request::header::HeaderMap::new();
-        headers.insert(
-            request::header::USER_AGENT,
-            format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
-                .parse()
-                .unwrap(),
-        );
+impl FetchOptions {
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none() && self.limit.is_none()
+    }
 
-        request::ClientBuilder::new()
-            .default_headers(headers)
-            .timeout(std::time::Duration::from_secs(60))
-            .pool_max_idle_per_host(10)
-            .tcp_keepalive(std::time::Duration::from_secs(30))
-            .gzip(true)
-            .brotli(true)
-            .deflate(true)
-            .build()
-            .expect("Failed to build HTTP client")
-    })
+    /// 記事の投稿時刻が範囲内か
+    pub fn contains(&self, timestamp: &DateTime<Local>) -> bool {
+        self.since.is_none_or(|since| *timestamp >= since) && self.until.is_none_or(|until| *timestamp < until)
+    }
+
+    /// 範囲外の記事を除き，`limit` を超える場合は新しい順に `limit` 件を残す（元の順序は保つ）
+    pub fn apply(&self, articles: Vec<WebArticle>) -> Vec<WebArticle> {
+        let mut articles: Vec<WebArticle> = articles.into_iter().filter(|a| self.contains(&a.timestamp)).collect();
+        if let Some(limit) = self.limit.filter(|limit| *limit < articles.len()) {
+            let mut newest: Vec<usize> = (0..articles.len()).collect();
+            newest.sort_by(|a, b| newest_first(&articles[*a], &articles[*b]));
+            let keep: HashSet<usize> = newest.into_iter().take(limit).collect();
+            articles = articles
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| keep.contains(i))
+                .map(|(_, article)| article)
+                .collect();
+        }
+        articles
+    }
+}
+
+static HTTP_CLIENTS: OnceLock<Mutex<HashMap<ProxyRoute, request::Client>>> = OnceLock::new();
+
+/// プロキシの経路ごとに共有するクライアント（コネクションプールを経路ごとに再利用する）
+fn shared_client(route: &ProxyRoute) -> AppResult<request::Client> {
+    let clients = HTTP_CLIENTS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut clients = clients.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(client) = clients.get(route) {
+        return Ok(client.clone());
+    }
+
+    let mut headers = request::header::HeaderMap::new();
+    headers.insert(
+        request::header::USER_AGENT,
+        format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+            .parse()
+            .unwrap(),
+    );
+    let builder = request::ClientBuilder::new()
+        .default_headers(headers)
+        .timeout(std::time::Duration::from_secs(60))
+        .pool_max_idle_per_host(10)
+        .tcp_keepalive(std::time::Duration::from_secs(30))
+        .gzip(true)
+        .brotli(true)
+        .deflate(true);
+    let client = route.apply(builder, global_proxy_settings().no_proxy())?.build()?;
+    clients.insert(route.clone(), client.clone());
+    Ok(client)
 }
 
 #[async_trait::async_trait]
@@ -446,12 +813,50 @@ pub trait WebSiteInterface: Send + Sync {
     fn site_name(&self) -> String;
     fn site_url(&self) -> Url;
     async fn get_articles(&mut self) -> AppResult<Vec<WebArticle>>;
+    /// `options` の期間・件数に収まる記事だけを返す．
+    /// デフォルトは `get_articles` の結果を絞り込む．一覧をページ送りするサイトは範囲外に達した時点で打ち切るようオーバーライドできる．
+    async fn get_articles_with(&mut self, options: &FetchOptions) -> AppResult<Vec<WebArticle>> {
+        let articles = self.get_articles().await?;
+        Ok(options.apply(articles))
+    }
     async fn parse_article(&mut self, url: &str) -> AppResult<(Html, Text)>;
+    /// 本文とメタデータ（タイトル，著者，公開日時など）を抽出する．
+    /// デフォルトは `parse_article` の本文から推定する．ページ全体を参照できるサイトは `ExtractedArticle::from_page` を使ってオーバーライドする．
+    async fn extract_article(&mut self, url: &str) -> AppResult<ExtractedArticle> {
+        let (html, text) = self.parse_article(url).await?;
+        Ok(ExtractedArticle::from_content(html, text))
+    }
     async fn login(&mut self) -> AppResult<Cookie>;
     fn domain(&self) -> String;
+    /// 並列取得時の公平なスケジューリングに使うカテゴリ（デフォルトはサイト名から推定）
+    fn category(&self) -> SiteCategory {
+        SiteCategory::from_site_name(&self.site_name())
+    }
+    /// 取得順と時間予算の扱いを決める優先度（デフォルトはサイト名から推定）
+    fn priority(&self) -> SitePriority {
+        SitePriority::from_site_name(&self.site_name())
+    }
+    /// 空白・空行を整える（コードブロックと表の行はそのまま残す）
     fn trim_text(&self, text: &str) -> String {
-        let re = Regex::new(r"\s\s+").unwrap();
-        re.replace_all(text, "\n").to_string()
+        global_text_normalizer().normalize(text)
+    }
+    /// `request` で取得してよいドメイン（サブドメインを含む）．
+    /// デフォルトはサイトのドメイン（`www.` / `rss.` などを除く）と `CLIPPER_ALLOWED_DOMAINS` で追加したドメイン．
+    fn allowed_domains(&self) -> Vec<String> {
+        default_allowed_domains(&self.site_name(), &self.domain())
+    }
+    /// `request` のタイムアウト・User-Agent・Accept-Language・追加ヘッダー（デフォルトは共有クライアントの設定のまま）
+    fn request_policy(&self) -> RequestPolicy {
+        RequestPolicy::default()
+    }
+    /// フィードに紛れ込んだ外部の URL をサイトの Cookie 付きで取得しないよう，許可されたドメインか確認する
+    fn ensure_allowed_url(&self, url: &Url) -> AppResult<()> {
+        let host = url.host_str().unwrap_or_default();
+        if is_allowed_host(host, &self.allowed_domains()) {
+            Ok(())
+        } else {
+            Err(AppError::DisallowedDomain(format!("{} is not allowed for {}", url, self.site_name())))
+        }
     }
     fn get_domain(&self, url: &str) -> AppResult<String> {
         Ok(Url::parse(url)?.domain().unwrap_or_default().to_string())
@@ -461,34 +866,131 @@ pub trait WebSiteInterface: Send + Sync {
     fn site_specific_exclude_selectors(&self) -> Vec<&'static str> {
         vec![]
     }
+    /// 共通の除外セレクタ（`EXCLUDE_SELECTORS`）のうち，このサイトでは適用しないものを返す（デフォルトは空）
+    /// 記事本文で `<aside>` や `<form>` を正当に使うサイト（インタラクティブな記事など）でオーバーライドする
+    fn exempt_exclude_selectors(&self) -> Vec<&'static str> {
+        vec![]
+    }
     /// HTMLから広告・サイドバー等の不要要素を除去してクリーンなコンテンツを返す
     fn clean_content(&self, html: &str) -> String {
         let additional = self.site_specific_exclude_selectors();
-        clean_html_with_selectors(html, &additional)
+        let exempt = self.exempt_exclude_selectors();
+        clean_html_with_rules(html, &additional, &exempt)
     }
-    /// セレクタで抽出を試み，失敗した場合はReadability風ヒューリスティックで抽出
-    fn extract_with_fallback(&self, html: &str, selector: &str) -> Option<String> {
-        let result = extract_content_with_fallback(html, selector);
-        result.map(|content| self.clean_content(&content))
+    /// 本文要素の選択と不要要素の除去を1回の DOM パースで行う
+    fn select_clean(&self, html: &str, selector: &str) -> AppResult<Vec<String>> {
+        let excludes = effective_exclude_selectors(
+            &self.site_specific_exclude_selectors(),
+            &self.exempt_exclude_selectors(),
+        );
+        select_clean_fragments(html, selector, &excludes)
+    }
+    /// 本文の抽出方法（優先順）．デフォルトは Readability 風のヒューリスティック．
+    fn extraction_strategies(&self) -> Vec<ExtractionStrategy> {
+        vec![ExtractionStrategy::Readability]
     }
-    /// Readability風ヒューリスティックで本文を抽出（セレクタなし）
-    fn extract_main_content_heuristic(&self, html: &str) -> Option<String> {
-        extract_main_content(html).map(|content| self.clean_content(&content))
+    /// `extraction_strategies` を順に試して，記事ページの HTML から本文を抽出する
+    fn extract_content(&self, body: &str) -> AppResult<(Html, Text)> {
+        extract_with_strategies(self, &self.extraction_strategies(), body)
     }
     async fn request(&self, url: &str, cookie_str: &str) -> AppResult<Response> {
-        let url = request::Url::parse(url).unwrap();
-
-        let mut request_builder = shared_client().get(url);
-
-        if !cookie_str.is_empty() {
-            request_builder = request_builder.header(request::header::COOKIE, cookie_str);
+        let url = request::Url::parse(url)?;
+        self.ensure_allowed_url(&url)?;
+        // `sites doctor` のフィクスチャ診断中は保存済みの HTML で応答する
+        if let Some(response) = fixture_response(&url) {
+            return Ok(response);
         }
-
-        let response = match request_builder.send().await {
-            Ok(response) => response,
-            Err(e) => return Err(AppError::RequestError(e)),
+        let host = url.host_str().map(str::to_string);
+        // Cookie 付きのリクエスト（ログイン後のページ）はユーザーごとに内容が異なるためキャッシュしない
+        let cache = global_fetch_cache().filter(|_| cookie_str.is_empty());
+        let cached = cache.and_then(|c| c.get(url.as_str()));
+        let policy = self.request_policy();
+        let mut headers = policy.header_map()?;
+        // 認証情報のファイルにあるヘッダー（API トークンなど）はサイトの設定より優先する
+        headers.extend(global_credentials().header_map(&self.site_name())?);
+        let timeout = policy.timeout();
+        let route = global_proxy_settings().route_for(&self.site_name(), policy.proxy_route()?.as_ref());
+        let client = shared_client(&route)?;
+
+        let response = global_retry_policy()
+            .run(url.as_str(), || {
+                let url = url.clone();
+                let host = host.clone();
+                let cached = cached.as_ref();
+                let headers = headers.clone();
+                let client = client.clone();
+                async move {
+                    if let Some(host) = &host {
+                        global_rate_limiter().acquire(host).await;
+                    }
+                    let mut request_builder = client.get(url).headers(headers);
+                    if let Some(timeout) = timeout {
+                        request_builder = request_builder.timeout(timeout);
+                    }
+                    if !cookie_str.is_empty() {
+                        request_builder =
+                            request_builder.header(request::header::COOKIE, cookie_str);
+                    }
+                    if let Some(cache) = cache {
+                        request_builder = cache.conditional(request_builder, cached);
+                    }
+                    request_builder.send().await
+                }
+            })
+            .await?;
+
+        // `304 Not Modified` は保存済みのレスポンスと同じ内容のため保存しない．
+        // `noarchive` を指定しているサイト（ヘッダー・記録済みの記事ページの meta タグ）の HTML も保存しない．
+        // 取得したページ自身の meta タグも，保存するかを決める前に本文から読み取って記録する．
+        let site_name = self.site_name();
+        let policies = global_robots_policies();
+        policies.record(&site_name, &RobotsDirectives::from_headers(response.headers()));
+        let response = match global_warc_writer() {
+            Some(warc) if response.status() == StatusCode::OK && policies.allows_archive(&site_name) => {
+                warc.archive_if(&url, response, |headers, body| {
+                    policies.record(&site_name, &RobotsDirectives::from_html_body(headers, body));
+                    policies.allows_archive(&site_name)
+                })
+                .await?
+            }
+            _ => response,
         };
-        Ok(response)
+        match cache {
+            Some(cache) => cache.resolve(url.as_str(), response, cached).await,
+            None => Ok(response),
+        }
+    }
+    /// グローバルなメモリ予算を確保してからレスポンス本文を取得する．
+    /// 返り値を保持している間は予算が確保されたままになるため，DOM の構築・抽出が終わるまで保持すること．
+    async fn request_text(&self, url: &str, cookie_str: &str) -> AppResult<BudgetedText> {
+        let response = self.request(url, cookie_str).await?;
+        let html = is_html(response.headers());
+        let text = budgeted_text(response).await?;
+        if html {
+            global_robots_policies().record(&self.site_name(), &RobotsDirectives::from_html(&text));
+        }
+        Ok(text)
+    }
+    /// 記事ページを取得し，受信しながら除外対象の要素（共通・サイト固有のセレクタ）を取り除く．
+    /// 数 MB のページでも DOM 全体を構築しないため，`request_text` より `parse_article` のメモリ使用量を抑えられる．
+    /// 著者名や公開日時を含むことがあるヘッダー・フッターはここでは残し，本文の抽出後のクリーニングで取り除く．
+    async fn request_article_page(&self, url: &str, cookie_str: &str) -> AppResult<BudgetedText> {
+        let response = self.request(url, cookie_str).await?;
+        let estimate = response
+            .content_length()
+            .map(|len| len as usize)
+            .unwrap_or(DEFAULT_BODY_ESTIMATE);
+        let mut permit = global_memory_budget().acquire(estimate).await;
+        let excludes = effective_exclude_selectors(
+            &self.site_specific_exclude_selectors(),
+            &self.exempt_exclude_selectors(),
+        );
+        let excludes: Vec<&str> =
+            excludes.into_iter().filter(|s| !STREAMING_KEEP_SELECTORS.contains(s)).collect();
+        let text = StreamingCleaner::new(&excludes).clean_response(response).await?;
+        global_robots_policies().record(&self.site_name(), &RobotsDirectives::from_html(&text));
+        permit.grow_to(text.len());
+        Ok(BudgetedText::new(text, permit))
     }
 }
 
@@ -504,6 +1006,7 @@ impl From<Box<dyn WebSiteInterface>> for WebSite {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::article::ArticleBuilder;
 
     #[test]
     fn test_detect_login_required_japanese_markers() {
@@ -648,6 +1151,76 @@ mod tests {
         assert!(cleaned.contains("Content"));
     }
 
+    #[test]
+    fn test_clean_html_with_rules_exempts_selectors() {
+        let html = r#"<html><body><article><p>Intro</p><aside class="callout">Important note</aside><form class="quiz"><input type="radio"></form></article><nav>Menu</nav></body></html>"#;
+        let cleaned = clean_html_with_rules(html, &[], &["aside", "form"]);
+        assert!(cleaned.contains("Important note"));
+        assert!(cleaned.contains("quiz"));
+        // 免除していない共通セレクタは引き続き適用される
+        assert!(!cleaned.contains("<nav>"));
+    }
+
+    #[test]
+    fn test_effective_exclude_selectors() {
+        let selectors = effective_exclude_selectors(&[".site-widget"], &["aside"]);
+        assert!(!selectors.contains(&"aside"));
+        assert!(selectors.contains(&"nav"));
+        assert!(selectors.contains(&".site-widget"));
+    }
+
+    #[test]
+    fn test_streaming_keep_selectors_are_excluded_later() {
+        for selector in STREAMING_KEEP_SELECTORS {
+            assert!(EXCLUDE_SELECTORS.contains(selector));
+        }
+        let cleaner = StreamingCleaner::new(EXCLUDE_SELECTORS);
+        assert!(cleaner.selectors().iter().any(|s| s == "[class*='ad-']"));
+    }
+
+    #[test]
+    fn test_select_clean_fragments_single_pass() {
+        let html = r#"
+            <html>
+            <body>
+                <main>
+                    <div class="entry-content">
+                        <p>First &amp; <a href="/x?a=1&amp;b=2">linked</a> paragraph.</p>
+                        <div class="ad-banner"><p>Sponsored paragraph</p></div>
+                        <p>Second<script>track()</script> paragraph.<br></p>
+                        <aside><p>Aside paragraph</p></aside>
+                    </div>
+                </main>
+            </body>
+            </html>
+        "#;
+        let excludes = exclude_selectors_with(&[]);
+        let fragments = select_clean_fragments(html, "main div.entry-content p", &excludes).unwrap();
+
+        assert_eq!(fragments.len(), 2);
+        assert_eq!(
+            fragments[0],
+            r#"<p>First &amp; <a href="/x?a=1&amp;b=2">linked</a> paragraph.</p>"#
+        );
+        assert_eq!(fragments[1], "<p>Second paragraph.<br></p>");
+    }
+
+    #[test]
+    fn test_select_clean_fragments_matches_clean_then_select() {
+        let html = r#"<html><body><article><h1>Title</h1><nav>Menu</nav><p>Body text</p><div class="social-share">Share</div></article></body></html>"#;
+
+        let cleaned = clean_html(html);
+        let doc = scraper::Html::parse_document(&cleaned);
+        let two_pass: String = doc.select(selector!("article")).next().unwrap().text().collect();
+
+        let fragments = select_clean_fragments(html, "article", &exclude_selectors_with(&[])).unwrap();
+        let single_pass: String = scraper::Html::parse_fragment(&fragments[0])
+            .root_element()
+            .text()
+            .collect();
+        assert_eq!(two_pass, single_pass);
+    }
+
     #[test]
     fn test_extract_main_content_with_article_tag() {
         let html = r#"
@@ -730,6 +1303,88 @@ mod tests {
         assert!(result.unwrap().contains("Fallback Article"));
     }
 
+    #[test]
+    fn test_fetch_options_apply() {
+        let base = Local::now();
+        let articles: Vec<WebArticle> = [3, 1, 5, 2, 4]
+            .iter()
+            .map(|days| {
+                ArticleBuilder::new(format!("day{}", days))
+                    .with_url(format!("https://example.com/{}", days))
+                    .with_timestamp(base - chrono::Duration::days(*days))
+                    .build()
+            })
+            .collect();
+        let titles = |articles: Vec<WebArticle>| articles.into_iter().map(|a| a.title).collect::<Vec<_>>();
+
+        assert_eq!(titles(FetchOptions::default().apply(articles.clone())).len(), 5);
+        let window = FetchOptions {
+            since: Some(base - chrono::Duration::days(4)),
+            until: Some(base - chrono::Duration::days(1)),
+            limit: None,
+        };
+        assert_eq!(titles(window.apply(articles.clone())), vec!["day3", "day2", "day4"]);
+        let limited = FetchOptions {
+            limit: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(titles(limited.apply(articles)), vec!["day1", "day2"]);
+    }
+
+    #[test]
+    fn test_web_article_shares_interned_site() {
+        let articles: Vec<WebArticle> = (0..1000)
+            .map(|i| {
+                WebArticle::new(
+                    "Interned Site".to_string(),
+                    "https://interned.example.com/feed".to_string(),
+                    format!("title {}", i),
+                    format!("https://interned.example.com/{}", i),
+                    "".to_string(),
+                    chrono::Local::now(),
+                )
+            })
+            .collect();
+
+        // 1000件の記事が同一の WebSite を共有し，サイト文字列の複製は1組だけになる
+        assert!(articles.iter().all(|a| Arc::ptr_eq(&a.site, &articles[0].site)));
+        assert_eq!(Arc::strong_count(&articles[0].site), 1000);
+        assert_eq!(articles[0].site.name, "Interned Site");
+
+        let other = WebSite::intern("Interned Site".into(), "https://other.example.com/".into());
+        assert!(!Arc::ptr_eq(&other, &articles[0].site));
+    }
+
+    #[test]
+    fn test_intern_releases_sites_without_articles() {
+        // `/clip` のように一度だけ使う任意のホストを大量に登録しても，登録数は増え続けない
+        for i in 0..10_000 {
+            let site = WebSite::intern("Clipped".into(), format!("https://host{}.example.com/", i));
+            drop(site);
+        }
+        assert!(WebSite::interned_count() < 2_000);
+
+        let kept = WebSite::intern("Kept".into(), "https://kept.example.com/".into());
+        let again = WebSite::intern("Kept".into(), "https://kept.example.com/".into());
+        assert!(Arc::ptr_eq(&kept, &again));
+    }
+
+    #[test]
+    fn test_web_article_site_serializes_as_plain_object() {
+        let article = WebArticle::new(
+            "Serde Site".to_string(),
+            "https://serde.example.com/".to_string(),
+            "title".to_string(),
+            "https://serde.example.com/1".to_string(),
+            "".to_string(),
+            chrono::Local::now(),
+        );
+        let json = serde_json::to_value(&article).unwrap();
+        assert_eq!(json["site"]["name"], "Serde Site");
+        let restored: WebArticle = serde_json::from_value(json).unwrap();
+        assert_eq!(*restored.site, *article.site);
+    }
+
     #[test]
     fn test_calculate_text_density() {
         // HTMLタグが多いとテキスト密度は低い
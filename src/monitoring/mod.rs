@@ -0,0 +1,314 @@
+//! サイトの健全性の監視．
+//!
+//! 巡回履歴（`FetchRun`）からサイトごとの成功率，記事数，本文の平均文字数の推移を集計し，
+//! セレクタの破損の兆候（突然記事が0件になる，本文が空や「NO HTML」になる，本文が急に短くなる，失敗が続く）を検出する．
+//! サイトのリニューアル（フロントエンドのフレームワークの移行など）でマークアップが変わると，
+//! エラーにならずに記事や本文だけが消えることが多いため，成功率だけでは気付けない変化を拾う．
+
+use crate::models::report::{FetchReport, TextStats};
+use crate::storage::FetchRun;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use strum::Display;
+
+/// セレクタの破損の兆候
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DriftSignal {
+    /// 以前は記事があったのに，取得は成功して記事が0件になった
+    ZeroArticles,
+    /// パースした本文の多くが空（または「NO HTML」などのプレースホルダー）
+    EmptyBodies,
+    /// 本文の平均文字数が過去の平均より大幅に短くなった
+    TextShrunk,
+    /// 以前は成功していたのに，直近の巡回で失敗が続いている
+    RepeatedFailures,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Drift {
+    pub signal: DriftSignal,
+    pub message: String,
+}
+
+/// サイトごとの集計結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SiteTrend {
+    pub site: String,
+    pub runs: usize,
+    pub failures: usize,
+    /// 成功した巡回の平均記事数
+    pub mean_articles: f64,
+    /// 空でない本文の平均文字数（本文をパースした巡回が無ければ `None`）
+    pub mean_text_chars: Option<f64>,
+    pub last_run: DateTime<Local>,
+    /// 直近の巡回で見つかった兆候
+    pub drift: Vec<Drift>,
+}
+
+impl SiteTrend {
+    pub fn success_rate(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.0;
+        }
+        (self.runs - self.failures) as f64 / self.runs as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthMonitor {
+    /// 記事数・本文の長さの変化の判定に必要な過去の巡回数
+    pub min_history: usize,
+    /// 失敗が続いていると判定する連続失敗回数
+    pub failure_streak: usize,
+    /// 本文が空の記事の比率がこれを超えたら本文の欠落と判定する
+    pub empty_body_ratio: f64,
+    /// 本文の平均文字数が過去の平均のこの比率を下回ったら本文の縮小と判定する
+    pub text_shrink_ratio: f64,
+    /// 本文の縮小を判定する過去の平均文字数の下限（もともと本文の短いサイトは対象外）
+    pub min_baseline_chars: f64,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self {
+            min_history: 3,
+            failure_streak: 3,
+            empty_body_ratio: 0.5,
+            text_shrink_ratio: 0.3,
+            min_baseline_chars: 200.0,
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+impl HealthMonitor {
+    /// 古い順の巡回結果をサイトごとに集計する（時間予算で打ち切った巡回は除く．サイト名順）
+    pub fn evaluate(&self, runs: &[FetchRun]) -> Vec<SiteTrend> {
+        let mut sites: BTreeMap<&str, Vec<&FetchRun>> = BTreeMap::new();
+        for run in runs.iter().filter(|run| !run.report.budget_exhausted) {
+            sites.entry(run.report.site.as_str()).or_default().push(run);
+        }
+        sites
+            .into_iter()
+            .filter_map(|(site, runs)| {
+                let (latest, history) = runs.split_last()?;
+                let reports: Vec<&FetchReport> = runs.iter().map(|run| &run.report).collect();
+                let history: Vec<&FetchReport> = history.iter().map(|run| &run.report).collect();
+                let articles: Vec<f64> =
+                    reports.iter().filter(|r| r.is_ok()).map(|r| r.fetched as f64).collect();
+                let mut text = TextStats::default();
+                reports.iter().for_each(|r| text.merge(&r.text));
+                Some(SiteTrend {
+                    site: site.to_string(),
+                    runs: reports.len(),
+                    failures: reports.iter().filter(|r| !r.is_ok()).count(),
+                    mean_articles: mean(&articles).unwrap_or(0.0),
+                    mean_text_chars: text.mean_chars(),
+                    last_run: latest.run_at,
+                    drift: self.detect(&history, &latest.report),
+                })
+            })
+            .collect()
+    }
+
+    /// `latest` を同じサイトの過去の巡回結果（古い順）と比較する
+    pub fn detect(&self, history: &[&FetchReport], latest: &FetchReport) -> Vec<Drift> {
+        let drift = |signal, message: String| Drift { signal, message };
+        let mut drifts = Vec::new();
+
+        let streak = 1 + history.iter().rev().take_while(|r| !r.is_ok()).count();
+        let used_to_work = history.iter().any(|r| r.is_ok());
+        if !latest.is_ok() {
+            if streak >= self.failure_streak && used_to_work {
+                drifts.push(drift(
+                    DriftSignal::RepeatedFailures,
+                    format!("failed {} runs in a row: {}", streak, latest.errors[0]),
+                ));
+            }
+            return drifts;
+        }
+
+        let succeeded: Vec<&&FetchReport> = history.iter().filter(|r| r.is_ok()).collect();
+        let counts: Vec<f64> = succeeded.iter().map(|r| r.fetched as f64).collect();
+        if let Some(count_mean) = mean(&counts)
+            && counts.len() >= self.min_history
+            && latest.fetched == 0
+            && count_mean >= 1.0
+        {
+            drifts.push(drift(
+                DriftSignal::ZeroArticles,
+                format!("no articles found (usually {:.1})", count_mean),
+            ));
+        }
+
+        if latest.text.parsed > 0 && latest.text.empty_ratio() > self.empty_body_ratio {
+            drifts.push(drift(
+                DriftSignal::EmptyBodies,
+                format!("{} of {} parsed bodies were empty", latest.text.empty, latest.text.parsed),
+            ));
+        }
+
+        let lengths: Vec<f64> = succeeded.iter().filter_map(|r| r.text.mean_chars()).collect();
+        if let (Some(observed), Some(baseline)) = (latest.text.mean_chars(), mean(&lengths))
+            && lengths.len() >= self.min_history
+            && baseline >= self.min_baseline_chars
+            && observed < baseline * self.text_shrink_ratio
+        {
+            drifts.push(drift(
+                DriftSignal::TextShrunk,
+                format!("mean body length shrank to {:.0} chars (usually {:.0})", observed, baseline),
+            ));
+        }
+        drifts
+    }
+}
+
+/// 兆候のあるサイトを先頭にしたテキストの一覧
+pub fn render_trends(trends: &[SiteTrend]) -> String {
+    let mut rows: Vec<&SiteTrend> = trends.iter().collect();
+    rows.sort_by_key(|trend| trend.drift.is_empty());
+
+    let mut out = String::from("site\truns\tsuccess\tarticles\ttext_chars\tstatus\n");
+    for trend in rows {
+        let status = if trend.drift.is_empty() {
+            "OK".to_string()
+        } else {
+            trend.drift.iter().map(|d| d.signal.to_string()).collect::<Vec<_>>().join(",")
+        };
+        out.push_str(&format!(
+            "{}\t{}\t{:.1}%\t{:.1}\t{}\t{}\n",
+            trend.site,
+            trend.runs,
+            trend.success_rate() * 100.0,
+            trend.mean_articles,
+            trend.mean_text_chars.map(|c| format!("{:.0}", c)).unwrap_or_else(|| "-".to_string()),
+            status
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::report::FailureKind;
+    use crate::shared::errors::AppError;
+    use chrono::TimeZone;
+
+    fn run(day: u32, site: &str, fetched: usize, texts: &[&str]) -> FetchRun {
+        let mut report = FetchReport::new(site);
+        report.fetched = fetched;
+        texts.iter().for_each(|text| report.text.record(text));
+        FetchRun {
+            run_at: Local.with_ymd_and_hms(2025, 10, day, 9, 0, 0).unwrap(),
+            report,
+        }
+    }
+
+    fn failed(day: u32, site: &str) -> FetchRun {
+        let mut run = run(day, site, 0, &[]);
+        run.report.record_error(&AppError::SelectorNotFound {
+            site: site.to_string(),
+            selector: "article div.body".into(),
+        });
+        run
+    }
+
+    fn signals(trend: &SiteTrend) -> Vec<DriftSignal> {
+        trend.drift.iter().map(|d| d.signal).collect()
+    }
+
+    #[test]
+    fn test_evaluate_stable_site() {
+        let body = "本文".repeat(200);
+        let runs: Vec<FetchRun> = (1..=4).map(|day| run(day, "Stable", 10, &[&body])).collect();
+        let trends = HealthMonitor::default().evaluate(&runs);
+        assert_eq!(trends.len(), 1);
+        assert_eq!(trends[0].runs, 4);
+        assert_eq!(trends[0].success_rate(), 1.0);
+        assert_eq!(trends[0].mean_articles, 10.0);
+        assert_eq!(trends[0].mean_text_chars, Some(400.0));
+        assert!(trends[0].drift.is_empty());
+    }
+
+    #[test]
+    fn test_detect_zero_articles_and_empty_bodies() {
+        let body = "本文".repeat(200);
+        let mut runs: Vec<FetchRun> = (1..=3).map(|day| run(day, "Mercari", 10, &[&body])).collect();
+        runs.push(run(4, "Mercari", 0, &[]));
+        runs.push(run(4, "Medium", 5, &["NO TEXT", "NO TEXT", &body]));
+        let trends = HealthMonitor::default().evaluate(&runs);
+        assert_eq!(trends[0].site, "Medium");
+        assert_eq!(signals(&trends[0]), [DriftSignal::EmptyBodies]);
+        assert_eq!(trends[0].drift[0].message, "2 of 3 parsed bodies were empty");
+        assert_eq!(signals(&trends[1]), [DriftSignal::ZeroArticles]);
+
+        // 過去の巡回が少ない・もともと記事の無いサイトは判定しない
+        let young = [run(1, "New", 3, &[]), run(2, "New", 0, &[])];
+        assert!(HealthMonitor::default().evaluate(&young)[0].drift.is_empty());
+    }
+
+    #[test]
+    fn test_detect_text_shrunk() {
+        let body = "a".repeat(1000);
+        let mut runs: Vec<FetchRun> = (1..=3).map(|day| run(day, "Blog", 5, &[&body])).collect();
+        runs.push(run(4, "Blog", 5, &["Read more"]));
+        let trend = &HealthMonitor::default().evaluate(&runs)[0];
+        assert_eq!(signals(trend), [DriftSignal::TextShrunk]);
+
+        let short = "a".repeat(100);
+        let mut runs: Vec<FetchRun> = (1..=3).map(|day| run(day, "Short", 5, &[&short])).collect();
+        runs.push(run(4, "Short", 5, &["a"]));
+        assert!(HealthMonitor::default().evaluate(&runs)[0].drift.is_empty());
+    }
+
+    #[test]
+    fn test_detect_repeated_failures() {
+        let mut runs = vec![run(1, "Broken", 10, &[]), failed(2, "Broken"), failed(3, "Broken")];
+        assert!(HealthMonitor::default().evaluate(&runs)[0].drift.is_empty());
+        runs.push(failed(4, "Broken"));
+        let trend = &HealthMonitor::default().evaluate(&runs)[0];
+        assert_eq!(signals(trend), [DriftSignal::RepeatedFailures]);
+        assert_eq!(trend.failures, 3);
+        assert_eq!(trend.mean_articles, 10.0);
+        assert!(runs[3].report.has_failure(FailureKind::Selector));
+        assert_eq!(
+            trend.drift[0].message,
+            "failed 3 runs in a row: Scrape Error: selector not found on Broken: article div.body"
+        );
+
+        // 一度も成功していないサイトは設定の誤りの可能性が高いため対象外
+        let never = [failed(1, "Never"), failed(2, "Never"), failed(3, "Never")];
+        assert!(HealthMonitor::default().evaluate(&never)[0].drift.is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_ignores_budget_exhausted_runs() {
+        let mut skipped = run(4, "Slow", 0, &[]);
+        skipped.report.budget_exhausted = true;
+        let mut runs: Vec<FetchRun> = (1..=3).map(|day| run(day, "Slow", 10, &[])).collect();
+        runs.push(skipped);
+        let trend = &HealthMonitor::default().evaluate(&runs)[0];
+        assert_eq!(trend.runs, 3);
+        assert!(trend.drift.is_empty());
+        assert_eq!(trend.mean_text_chars, None);
+    }
+
+    #[test]
+    fn test_render_trends() {
+        let mut runs: Vec<FetchRun> = (1..=3).map(|day| run(day, "A Broken", 10, &[])).collect();
+        runs.push(run(4, "A Broken", 0, &[]));
+        runs.push(run(4, "B Stable", 4, &["abcd"]));
+        let rendered = render_trends(&HealthMonitor::default().evaluate(&runs));
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "site\truns\tsuccess\tarticles\ttext_chars\tstatus");
+        assert_eq!(lines[1], "A Broken\t4\t100.0%\t7.5\t-\tzero_articles");
+        assert_eq!(lines[2], "B Stable\t1\t100.0%\t4.0\t4\tOK");
+    }
+}
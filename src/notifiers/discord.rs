@@ -0,0 +1,175 @@
+//! Discord の Webhook への通知．
+//!
+//! 記事1件を1つの Embed（タイトル，概要，サイトのファビコン，公開日時）にし，1メッセージにつき
+//! 最大10件ずつ送る．Webhook のレート制限に掛からないよう送信の間隔を空け，`429` の場合は指示された時間だけ待って再送する．
+
+use crate::models::web_article::WebArticle;
+use crate::notifiers::Notifier;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::text::truncate_text;
+use chrono::SecondsFormat;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Webhook の URL を指定する環境変数
+pub const DISCORD_WEBHOOK_ENV: &str = "CLIPPER_DISCORD_WEBHOOK_URL";
+
+/// 1メッセージに含められる Embed の上限
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+/// Embed の `title` / `description` の上限（文字数）
+const MAX_TITLE_CHARS: usize = 256;
+const MAX_DESCRIPTION_CHARS: usize = 400;
+/// メッセージの送信間隔（Webhook の上限は 2 秒あたり 5 回程度）
+const SEND_INTERVAL: Duration = Duration::from_millis(500);
+/// `429 Too Many Requests` を受けたときの再送回数
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+/// Embed の左端の色（ブランドカラー）
+const EMBED_COLOR: u32 = 0x5865F2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscordNotifier {
+    webhook: String,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook: impl Into<String>) -> Self {
+        Self {
+            webhook: webhook.into(),
+        }
+    }
+
+    /// `CLIPPER_DISCORD_WEBHOOK_URL` から作成する．未設定の場合は `None`．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        match std::env::var(DISCORD_WEBHOOK_ENV) {
+            Ok(url) if !url.trim().is_empty() => {
+                Ok(Some(Self::new(request::Url::parse(url.trim())?.to_string())))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 送信するメッセージ（最大10件の Embed ずつ）
+    pub fn messages(&self, articles: &[WebArticle]) -> Vec<Value> {
+        articles
+            .chunks(MAX_EMBEDS_PER_MESSAGE)
+            .map(|chunk| json!({ "embeds": chunk.iter().map(embed).collect::<Vec<_>>() }))
+            .collect()
+    }
+
+    async fn send(&self, client: &request::Client, payload: &Value) -> AppResult<()> {
+        for _ in 0..=MAX_RATE_LIMIT_RETRIES {
+            let response = client.post(&self.webhook).json(payload).send().await?;
+            if response.status() == request::StatusCode::TOO_MANY_REQUESTS {
+                let body: Value = response.json().await.unwrap_or_default();
+                tokio::time::sleep(retry_after(&body)).await;
+                continue;
+            }
+            if !response.status().is_success() {
+                return Err(AppError::InternalError(format!(
+                    "Discord webhook returned {}",
+                    response.status()
+                )));
+            }
+            return Ok(());
+        }
+        Err(AppError::InternalError("Discord webhook is rate limited".into()))
+    }
+}
+
+fn embed(article: &WebArticle) -> Value {
+    let mut embed = json!({
+        "title": truncate_text(article.title.trim(), MAX_TITLE_CHARS),
+        "url": article.article_url,
+        "timestamp": article.timestamp.to_rfc3339_opts(SecondsFormat::Secs, false),
+        "color": EMBED_COLOR,
+        "author": { "name": article.site.name, "url": article.site.url },
+    });
+    if let Some(icon) = favicon_url(&article.site.url) {
+        embed["author"]["icon_url"] = json!(icon);
+    }
+    let description = article
+        .properties
+        .summary
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(&article.description)
+        .trim();
+    if !description.is_empty() {
+        embed["description"] = json!(truncate_text(description, MAX_DESCRIPTION_CHARS));
+    }
+    embed
+}
+
+/// サイトのトップの `/favicon.ico`
+fn favicon_url(site_url: &str) -> Option<String> {
+    let url = request::Url::parse(site_url).ok()?;
+    url.host_str()?;
+    Some(format!("{}/favicon.ico", url.origin().ascii_serialization()))
+}
+
+/// `429` のレスポンスの `retry_after`（秒）．無ければ 1 秒．
+fn retry_after(body: &Value) -> Duration {
+    body.get("retry_after")
+        .and_then(Value::as_f64)
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(|secs| Duration::from_secs_f64(secs.min(60.0)))
+        .unwrap_or(Duration::from_secs(1))
+}
+
+#[async_trait::async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    async fn notify(&self, articles: &[WebArticle]) -> AppResult<()> {
+        let client = request::Client::new();
+        for (i, payload) in self.messages(articles).iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(SEND_INTERVAL).await;
+            }
+            self.send(&client, payload).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::{Local, TimeZone};
+
+    fn article(i: usize) -> WebArticle {
+        ArticleBuilder::new(format!("title {}", i))
+            .with_site("Gigazine", "https://gigazine.net/news/rss_2.0/")
+            .with_url(format!("https://gigazine.net/news/{}", i))
+            .with_description("概要")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, 1, 9, 0, 0).unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_messages_batch_embeds() {
+        let notifier = DiscordNotifier::new("https://discord.example.com/api/webhooks/1/abc");
+        let articles: Vec<WebArticle> = (0..23).map(article).collect();
+        let messages = notifier.messages(&articles);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[2]["embeds"].as_array().unwrap().len(), 3);
+
+        let embed = &messages[0]["embeds"][0];
+        assert_eq!(embed["title"], "title 0");
+        assert_eq!(embed["url"], "https://gigazine.net/news/0");
+        assert_eq!(embed["description"], "概要");
+        assert_eq!(embed["author"]["icon_url"], "https://gigazine.net/favicon.ico");
+        assert!(embed["timestamp"].as_str().unwrap().starts_with("2025-10-01T09:00:00"));
+    }
+
+    #[test]
+    fn test_truncate_and_retry_after() {
+        assert_eq!(truncate_text("abcdef", 4), "abc…");
+        assert_eq!(truncate_text("abc", 4), "abc");
+        assert_eq!(retry_after(&json!({ "retry_after": 0.25 })), Duration::from_millis(250));
+        assert_eq!(retry_after(&json!({})), Duration::from_secs(1));
+    }
+}
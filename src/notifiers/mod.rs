@@ -0,0 +1,32 @@
+//! 巡回のたびに新着記事を外部サービスへ通知する．
+
+pub mod discord;
+pub mod email;
+pub mod outbox;
+pub mod slack;
+
+use crate::models::web_article::WebArticle;
+use crate::shared::errors::AppResult;
+use discord::DiscordNotifier;
+use slack::SlackNotifier;
+
+/// 新着記事の通知先
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    /// ログに出す通知先の名前
+    fn name(&self) -> &str;
+    /// 1回の巡回で新たに保存した記事を通知する（記事が無い場合は何もしない）
+    async fn notify(&self, articles: &[WebArticle]) -> AppResult<()>;
+}
+
+/// 環境変数で設定された通知先（未設定のものは含めない）
+pub fn notifiers_from_env() -> AppResult<Vec<Box<dyn Notifier>>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+    if let Some(slack) = SlackNotifier::from_env()? {
+        notifiers.push(Box::new(slack));
+    }
+    if let Some(discord) = DiscordNotifier::from_env()? {
+        notifiers.push(Box::new(discord));
+    }
+    Ok(notifiers)
+}
@@ -0,0 +1,162 @@
+//! 送れなかった通知のアウトボックス．
+//!
+//! 通知先（Slack・Discord など）に送れなかった新着記事は通知先ごとに `$CLIPPER_DATA_DIR/outbox/<name>.jsonl` に溜め，
+//! 次の巡回で新着記事と一緒に送り直す．通知先が止まっている間も新着記事を取りこぼさず，プロセスを再起動しても失わない．
+
+use crate::models::web_article::WebArticle;
+use crate::notifiers::Notifier;
+use crate::shared::errors::{AppError, AppResult};
+use std::path::{Path, PathBuf};
+
+/// 通知先ごとに溜める記事数の上限（超えた場合は古いものから捨てる）
+const MAX_QUEUED_ARTICLES: usize = 500;
+
+/// 通知の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// 溜めていた記事を含めて送った件数
+    Sent(usize),
+    /// 送れなかったためアウトボックスに溜めている件数
+    Queued(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outbox {
+    dir: PathBuf,
+}
+
+impl Outbox {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `data_dir` の `outbox` ディレクトリ
+    pub fn in_data_dir(data_dir: &Path) -> Self {
+        Self::new(data_dir.join("outbox"))
+    }
+
+    fn path_for(&self, notifier: &str) -> PathBuf {
+        let name: String = notifier
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.jsonl", name))
+    }
+
+    /// 通知先に送れずに溜めている記事（古い順）
+    pub fn pending(&self, notifier: &str) -> AppResult<Vec<WebArticle>> {
+        let path = self.path_for(notifier);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<Vec<WebArticle>, _>>()?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(AppError::InternalError(format!("Failed to read {:?}: {}", path, e))),
+        }
+    }
+
+    /// 溜めている記事を置き換える（空の場合はファイルを削除する）
+    fn save(&self, notifier: &str, articles: &[WebArticle]) -> AppResult<()> {
+        let path = self.path_for(notifier);
+        if articles.is_empty() {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(AppError::InternalError(format!("Failed to remove {:?}: {}", path, e)))
+                }
+                _ => Ok(()),
+            };
+        }
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", self.dir, e)))?;
+        let mut content = String::new();
+        for article in articles {
+            content.push_str(&serde_json::to_string(article)?);
+            content.push('\n');
+        }
+        std::fs::write(&path, content)
+            .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", path, e)))
+    }
+
+    /// 溜めていた記事と `articles` をまとめて通知する．送れなかった場合はまとめてアウトボックスに溜める．
+    pub async fn deliver(&self, notifier: &dyn Notifier, articles: &[WebArticle]) -> AppResult<Delivery> {
+        let mut batch = self.pending(notifier.name())?;
+        batch.retain(|a| !articles.iter().any(|b| b.article_url == a.article_url));
+        batch.extend(articles.iter().cloned());
+        if batch.is_empty() {
+            return Ok(Delivery::Sent(0));
+        }
+        match notifier.notify(&batch).await {
+            Ok(()) => {
+                self.save(notifier.name(), &[])?;
+                Ok(Delivery::Sent(batch.len()))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to notify via {}: {}", notifier.name(), e);
+                if batch.len() > MAX_QUEUED_ARTICLES {
+                    let dropped = batch.len() - MAX_QUEUED_ARTICLES;
+                    tracing::warn!("Outbox of {} is full; dropping {} oldest articles", notifier.name(), dropped);
+                    batch.drain(..dropped);
+                }
+                self.save(notifier.name(), &batch)?;
+                Ok(Delivery::Queued(batch.len()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn article(path: &str) -> WebArticle {
+        ArticleBuilder::new(path).with_url(format!("https://example.com/{}", path)).build()
+    }
+
+    /// `available` が偽の間は送信に失敗する通知先
+    #[derive(Default)]
+    struct FlakyNotifier {
+        available: AtomicBool,
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Notifier for FlakyNotifier {
+        fn name(&self) -> &str {
+            "Flaky Webhook"
+        }
+        async fn notify(&self, articles: &[WebArticle]) -> AppResult<()> {
+            if !self.available.load(Ordering::SeqCst) {
+                return Err(AppError::InternalError("503 Service Unavailable".into()));
+            }
+            self.sent.lock().unwrap().extend(articles.iter().map(|a| a.title.clone()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_outbox_queues_until_notifier_recovers() {
+        let dir = std::env::temp_dir().join(format!("clipper-outbox-{}", uuid::Uuid::new_v4()));
+        let outbox = Outbox::new(&dir);
+        let notifier = FlakyNotifier::default();
+
+        let delivery = outbox.deliver(&notifier, &[article("a"), article("b")]).await.unwrap();
+        assert_eq!(delivery, Delivery::Queued(2));
+        assert!(dir.join("flaky_webhook.jsonl").exists());
+        let delivery = outbox.deliver(&notifier, &[article("b"), article("c")]).await.unwrap();
+        assert_eq!(delivery, Delivery::Queued(3));
+
+        notifier.available.store(true, Ordering::SeqCst);
+        let delivery = outbox.deliver(&notifier, &[article("d")]).await.unwrap();
+        assert_eq!(delivery, Delivery::Sent(4));
+        assert_eq!(*notifier.sent.lock().unwrap(), ["a", "b", "c", "d"]);
+        assert!(outbox.pending(notifier.name()).unwrap().is_empty());
+        assert!(!dir.join("flaky_webhook.jsonl").exists());
+        assert_eq!(outbox.deliver(&notifier, &[]).await.unwrap(), Delivery::Sent(0));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
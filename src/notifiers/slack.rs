@@ -0,0 +1,286 @@
+//! Slack の Incoming Webhook への通知．
+//!
+//! 記事はサイトのカテゴリごとに振り分け，カテゴリ用の Webhook（チャンネル）が設定されていればそちらへ，
+//! 無ければデフォルトの Webhook へ送る．
+//! 毎朝1回，前日の記事の概要（モーニングブリーフ）もデフォルトの Webhook へ投稿する．
+
+use crate::enrichment::brief::{MorningBrief, articles_of_day, brief_writer_from_env, write_morning_brief};
+use crate::models::fairness::SiteCategory;
+use crate::models::web_article::WebArticle;
+use crate::notifiers::Notifier;
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::text::truncate_text;
+use crate::storage::ArticleStore;
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// デフォルトの Webhook の URL を指定する環境変数
+pub const SLACK_WEBHOOK_ENV: &str = "CLIPPER_SLACK_WEBHOOK_URL";
+/// カテゴリごとの Webhook を指定する環境変数（例: `security=https://hooks.slack.com/...,ai=https://...`）
+pub const SLACK_CATEGORY_WEBHOOKS_ENV: &str = "CLIPPER_SLACK_CATEGORY_WEBHOOKS";
+/// モーニングブリーフを投稿する時刻（ローカル時刻の時．この時刻以降の最初の巡回で投稿する）
+pub const MORNING_BRIEF_HOUR_ENV: &str = "CLIPPER_MORNING_BRIEF_HOUR";
+pub const DEFAULT_MORNING_BRIEF_HOUR: u32 = 8;
+
+/// 1メッセージに含める記事数の上限（Slack のブロック数の上限 50 を超えないように）
+const MAX_ARTICLES_PER_MESSAGE: usize = 20;
+/// 要約を切り詰める文字数
+const SUMMARY_CHARS: usize = 300;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlackNotifier {
+    default_webhook: Option<String>,
+    category_webhooks: BTreeMap<SiteCategory, String>,
+}
+
+impl SlackNotifier {
+    pub fn new(default_webhook: Option<String>) -> Self {
+        Self {
+            default_webhook,
+            category_webhooks: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_category_webhook(mut self, category: SiteCategory, webhook: impl Into<String>) -> Self {
+        self.category_webhooks.insert(category, webhook.into());
+        self
+    }
+
+    /// `CLIPPER_SLACK_WEBHOOK_URL` / `CLIPPER_SLACK_CATEGORY_WEBHOOKS` から作成する．どちらも未設定の場合は `None`．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let default_webhook = match std::env::var(SLACK_WEBHOOK_ENV) {
+            Ok(url) if !url.trim().is_empty() => Some(request::Url::parse(url.trim())?.to_string()),
+            _ => None,
+        };
+        let mut notifier = Self::new(default_webhook);
+        if let Ok(value) = std::env::var(SLACK_CATEGORY_WEBHOOKS_ENV) {
+            for pair in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let (category, url) = pair
+                    .split_once('=')
+                    .ok_or_else(|| AppError::InternalError(format!("Invalid Slack webhook route: {}", pair)))?;
+                let category = SiteCategory::from_str(category.trim())
+                    .map_err(|e| AppError::EnumParseError(format!("{}: {}", category.trim(), e)))?;
+                notifier = notifier.with_category_webhook(category, request::Url::parse(url.trim())?.to_string());
+            }
+        }
+        if notifier.default_webhook.is_none() && notifier.category_webhooks.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(notifier))
+    }
+
+    /// デフォルトの Webhook
+    pub fn default_webhook(&self) -> Option<&str> {
+        self.default_webhook.as_deref()
+    }
+
+    /// 記事のカテゴリに対応する Webhook（無ければデフォルト）
+    pub fn webhook_for(&self, category: SiteCategory) -> Option<&str> {
+        self.category_webhooks
+            .get(&category)
+            .or(self.default_webhook.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Webhook ごとに送信するメッセージを組み立てる．送信先の無い記事は含めない．
+    pub fn messages(&self, articles: &[WebArticle]) -> Vec<(String, Value)> {
+        let mut routed: BTreeMap<&str, Vec<&WebArticle>> = BTreeMap::new();
+        for article in articles {
+            let category = SiteCategory::from_site_name(&article.site.name);
+            if let Some(webhook) = self.webhook_for(category) {
+                routed.entry(webhook).or_default().push(article);
+            }
+        }
+        routed
+            .into_iter()
+            .flat_map(|(webhook, articles)| {
+                articles
+                    .chunks(MAX_ARTICLES_PER_MESSAGE)
+                    .map(|chunk| (webhook.to_string(), message(chunk)))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// 記事1件を1セクションにしたメッセージ（`text` は通知のプレビューに使われる）
+fn message(articles: &[&WebArticle]) -> Value {
+    let blocks: Vec<Value> = articles
+        .iter()
+        .map(|article| {
+            let mut text = format!(
+                "*<{}|{}>*\n_{}_",
+                article.article_url,
+                escape(article.title.trim()),
+                escape(&article.site.name)
+            );
+            if let Some(summary) = summary(article) {
+                text.push('\n');
+                text.push_str(&escape(&summary));
+            }
+            json!({ "type": "section", "text": { "type": "mrkdwn", "text": text } })
+        })
+        .collect();
+    json!({
+        "text": format!("{} new articles", articles.len()),
+        "blocks": blocks,
+    })
+}
+
+/// モーニングブリーフの投稿（見出し，概要の段落，記事数）
+pub fn brief_message(date: NaiveDate, brief: &MorningBrief, count: usize) -> Value {
+    let title = format!("Morning Brief {}", date.format("%Y-%m-%d"));
+    json!({
+        "text": title,
+        "blocks": [
+            { "type": "header", "text": { "type": "plain_text", "text": title } },
+            { "type": "section", "text": { "type": "mrkdwn", "text": escape(&brief.paragraph()) } },
+            { "type": "context", "elements": [{ "type": "mrkdwn", "text": format!("{} articles", count) }] },
+        ],
+    })
+}
+
+/// `CLIPPER_MORNING_BRIEF_HOUR` を過ぎていて今日まだ投稿していなければ，前日の記事のモーニングブリーフを投稿する．
+/// 投稿時刻は `marker` に記録する．前日の記事が無い場合は投稿せずに記録だけ行う．投稿した場合は `true` を返す．
+pub async fn send_morning_brief_if_due(
+    store: &dyn ArticleStore,
+    webhook: &str,
+    marker: &Path,
+    now: DateTime<Local>,
+) -> AppResult<bool> {
+    let hour: u32 = parse_env_or(MORNING_BRIEF_HOUR_ENV, DEFAULT_MORNING_BRIEF_HOUR)?;
+    let last_sent = std::fs::read_to_string(marker)
+        .ok()
+        .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())
+        .map(|t| t.with_timezone(&Local));
+    if now.hour() < hour || last_sent.is_some_and(|last_sent| last_sent.date_naive() >= now.date_naive()) {
+        return Ok(false);
+    }
+
+    let date = now.date_naive() - Duration::days(1);
+    let articles = articles_of_day(store, date).await?;
+    let brief = write_morning_brief(brief_writer_from_env().as_ref(), date, &articles).await;
+    if let Some(brief) = &brief {
+        let count = articles.iter().filter(|a| a.timestamp.date_naive() == date).count();
+        let response = request::Client::new()
+            .post(webhook)
+            .json(&brief_message(date, brief, count))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(AppError::InternalError(format!("Slack webhook returned {}", response.status())));
+        }
+    }
+    write_marker(marker, now)?;
+    Ok(brief.is_some())
+}
+
+fn write_marker(marker: &Path, now: DateTime<Local>) -> AppResult<()> {
+    if let Some(parent) = marker.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::InternalError(format!("Failed to create {:?}: {}", parent, e)))?;
+    }
+    std::fs::write(marker, now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .map_err(|e| AppError::InternalError(format!("Failed to write {:?}: {}", marker, e)))
+}
+
+/// 要約（無ければ概要）を1行にして切り詰める
+fn summary(article: &WebArticle) -> Option<String> {
+    let summary = article
+        .properties
+        .summary
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(&article.description);
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    if summary.is_empty() {
+        return None;
+    }
+    Some(truncate_text(&summary, SUMMARY_CHARS))
+}
+
+/// Slack の mrkdwn で制御文字として扱われる `&`，`<`，`>` をエスケープする
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[async_trait::async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn notify(&self, articles: &[WebArticle]) -> AppResult<()> {
+        let client = request::Client::new();
+        for (webhook, payload) in self.messages(articles) {
+            let response = client.post(&webhook).json(&payload).send().await?;
+            if !response.status().is_success() {
+                return Err(AppError::InternalError(format!(
+                    "Slack webhook returned {}",
+                    response.status()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article::ArticleBuilder;
+
+    fn article(site: &str, title: &str) -> WebArticle {
+        let mut article = ArticleBuilder::new(title)
+            .with_site(site, "https://example.com")
+            .with_description("概要")
+            .build();
+        article.properties.summary = Some("LLM の要約".into());
+        article
+    }
+
+    #[test]
+    fn test_messages_are_routed_by_category() {
+        let notifier = SlackNotifier::new(Some("https://hooks.example.com/default".into()))
+            .with_category_webhook(SiteCategory::Security, "https://hooks.example.com/security");
+        let articles = vec![article("JPCERT", "脆弱性 <CVE>"), article("Gigazine", "News & more")];
+        let messages = notifier.messages(&articles);
+        assert_eq!(messages.len(), 2);
+
+        let (webhook, payload) = &messages[1];
+        assert_eq!(webhook, "https://hooks.example.com/security");
+        let text = payload["blocks"][0]["text"]["text"].as_str().unwrap();
+        assert!(text.contains("|脆弱性 &lt;CVE&gt;>*"));
+        assert!(text.contains("_JPCERT_"));
+        assert!(text.contains("LLM の要約"));
+        assert_eq!(payload["text"], "1 new articles");
+        assert_eq!(messages[0].0, "https://hooks.example.com/default");
+    }
+
+    #[test]
+    fn test_messages_without_default_webhook_and_chunking() {
+        let notifier =
+            SlackNotifier::default().with_category_webhook(SiteCategory::Security, "https://hooks.example.com/s");
+        assert!(notifier.messages(&[article("Gigazine", "a")]).is_empty());
+
+        let articles: Vec<WebArticle> = (0..45).map(|i| article("JPCERT", &"x".repeat(i + 1))).collect();
+        let messages = notifier.messages(&articles);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[2].1["blocks"].as_array().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_brief_message() {
+        let brief = MorningBrief {
+            sentences: vec!["Rust & friends shipped.".into(), "Nothing else.".into()],
+        };
+        let payload = brief_message(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), &brief, 12);
+        assert_eq!(payload["text"], "Morning Brief 2025-10-01");
+        assert_eq!(payload["blocks"][1]["text"]["text"], "Rust &amp; friends shipped. Nothing else.");
+        assert_eq!(payload["blocks"][2]["elements"][0]["text"], "12 articles");
+    }
+}
@@ -0,0 +1,147 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::MissedTickBehavior;
+use tracing::{Level, event};
+
+/// 一定間隔でジョブを実行するシンプルなスケジューラ．
+/// 前回のジョブが長引いて次の実行時刻を過ぎた場合，溜まった実行はスキップする．
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    interval: Duration,
+    run_on_start: bool,
+    trigger: Arc<Notify>,
+}
+
+impl Scheduler {
+    pub fn new(interval: Duration, run_on_start: bool) -> Self {
+        Self {
+            interval,
+            run_on_start,
+            trigger: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// `notify_one()` で次の実行時刻を待たずにジョブを実行させるハンドル．
+    /// ジョブの実行中に呼ばれた場合は，完了後にもう一度だけ実行する．
+    pub fn trigger(&self) -> Arc<Notify> {
+        self.trigger.clone()
+    }
+
+    /// `shutdown` が完了するまでジョブを繰り返し実行する．
+    /// 実行中のジョブは `shutdown` によって中断されず，完了を待ってから終了する．
+    pub async fn run<F, Fut, S>(&self, mut job: F, shutdown: S)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+        S: Future<Output = ()>,
+    {
+        let start = if self.run_on_start {
+            tokio::time::Instant::now()
+        } else {
+            tokio::time::Instant::now() + self.interval
+        };
+        let mut ticker = tokio::time::interval_at(start, self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        tokio::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    event!(Level::INFO, "Scheduler received shutdown signal");
+                    break;
+                }
+                _ = ticker.tick() => {
+                    event!(Level::INFO, "Scheduler tick: running job");
+                    job().await;
+                }
+                _ = self.trigger.notified() => {
+                    event!(Level::INFO, "Scheduler triggered: running job");
+                    job().await;
+                }
+            }
+        }
+    }
+}
+
+/// Ctrl-C または SIGTERM（`docker stop` 等）を受け取るまで待機する
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(e) => {
+                event!(Level::WARN, "Failed to install SIGTERM handler: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_runs_until_shutdown() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let scheduler = Scheduler::new(Duration::from_secs(60), true);
+
+        let counter = count.clone();
+        scheduler
+            .run(
+                move || {
+                    let counter = counter.clone();
+                    async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                    }
+                },
+                tokio::time::sleep(Duration::from_secs(150)),
+            )
+            .await;
+
+        // t=0, 60, 120 の3回
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_runs_on_trigger() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let scheduler = Scheduler::new(Duration::from_secs(3600), false);
+        scheduler.trigger().notify_one();
+
+        let counter = count.clone();
+        scheduler
+            .run(
+                move || {
+                    let counter = counter.clone();
+                    async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                    }
+                },
+                tokio::time::sleep(Duration::from_secs(10)),
+            )
+            .await;
+
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::shared::errors::{AppError, AppResult};
+use std::collections::HashMap;
+use std::env;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+const DEFAULT_BIND_ADDR: &str = "0.0.0.0:8080";
+const DEFAULT_FETCH_INTERVAL_MINUTES: u64 = 60;
+const DEFAULT_CONCURRENCY: usize = 8;
+/// 読み込む環境変数ファイルを指定する環境変数．サービス定義（`install-service --env-file`）が設定する．
+pub const ENV_FILE_VAR: &str = "CLIPPER_ENV_FILE";
+
+/// `clipper all-in-one` の設定．全て環境変数から読み込み，未設定の項目はデフォルト値を使う．
+///
+/// | 環境変数 | デフォルト |
+/// |---|---|
+/// | `CLIPPER_BIND_ADDR` | `0.0.0.0:8080` |
+/// | `CLIPPER_DATA_DIR` | `$XDG_DATA_HOME/news_clipper`（取得できない場合は `./data`） |
+/// | `CLIPPER_DATABASE_PATH` | `$CLIPPER_DATA_DIR/clipper.sqlite3` |
+/// | `CLIPPER_FETCH_INTERVAL_MINUTES` | `60` |
+/// | `CLIPPER_CONCURRENCY` | `8` |
+/// | `CLIPPER_RUN_ON_START` | `true` |
+#[derive(Debug, Clone)]
+pub struct ServeConfig {
+    pub bind_addr: SocketAddr,
+    pub data_dir: PathBuf,
+    pub database_path: PathBuf,
+    pub fetch_interval: Duration,
+    pub concurrency: usize,
+    pub run_on_start: bool,
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        let data_dir = default_data_dir();
+        Self {
+            bind_addr: DEFAULT_BIND_ADDR.parse().unwrap(),
+            database_path: data_dir.join("clipper.sqlite3"),
+            data_dir,
+            fetch_interval: Duration::from_secs(DEFAULT_FETCH_INTERVAL_MINUTES * 60),
+            concurrency: DEFAULT_CONCURRENCY,
+            run_on_start: true,
+        }
+    }
+}
+
+impl ServeConfig {
+    pub fn from_env() -> AppResult<Self> {
+        Self::from_lookup(|key| env::var(key).ok())
+    }
+
+    /// 環境変数ファイルから読み込んだ値を優先し，ファイルに無い項目は現在の環境変数から読み込む．
+    /// プロセスの環境変数は書き換えない（実行中に `set_var` すると他のスレッドの読み込みと競合するため）．
+    pub fn from_env_file(values: &HashMap<String, String>) -> AppResult<Self> {
+        Self::from_lookup(|key| values.get(key).cloned().or_else(|| env::var(key).ok()))
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> AppResult<Self> {
+        let default = Self::default();
+        let data_dir = lookup("CLIPPER_DATA_DIR").map(PathBuf::from).unwrap_or(default.data_dir);
+        let database_path = lookup("CLIPPER_DATABASE_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| data_dir.join("clipper.sqlite3"));
+        let interval_minutes: u64 =
+            lookup_or(&lookup, "CLIPPER_FETCH_INTERVAL_MINUTES", DEFAULT_FETCH_INTERVAL_MINUTES)?;
+        if interval_minutes == 0 {
+            return Err(AppError::InternalError(
+                "CLIPPER_FETCH_INTERVAL_MINUTES must be greater than 0".into(),
+            ));
+        }
+
+        Ok(Self {
+            bind_addr: lookup_or(&lookup, "CLIPPER_BIND_ADDR", default.bind_addr)?,
+            data_dir,
+            database_path,
+            fetch_interval: Duration::from_secs(interval_minutes * 60),
+            concurrency: lookup_or(&lookup, "CLIPPER_CONCURRENCY", DEFAULT_CONCURRENCY)?.max(1),
+            run_on_start: lookup_or(&lookup, "CLIPPER_RUN_ON_START", default.run_on_start)?,
+        })
+    }
+}
+
+/// `CLIPPER_DATA_DIR`（未設定の場合はデフォルトのデータディレクトリ）
+pub fn data_dir() -> PathBuf {
+    env::var("CLIPPER_DATA_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| default_data_dir())
+}
+
+fn default_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("news_clipper"))
+        .unwrap_or_else(|| PathBuf::from("./data"))
+}
+
+/// `CLIPPER_ENV_FILE` で指定された環境変数ファイルのパス
+pub fn env_file_path() -> Option<PathBuf> {
+    env::var_os(ENV_FILE_VAR).filter(|path| !path.is_empty()).map(PathBuf::from)
+}
+
+/// 環境変数ファイルを読み込み，読み込んだファイルのパスを返す．`path` が `None` の場合は
+/// カレントディレクトリから `.env` を探す．設定済みの環境変数は上書きしない．
+/// 他のスレッドが動き出す前（起動直後）にだけ呼ぶこと．
+pub fn load_env_file(path: Option<&Path>) -> Option<PathBuf> {
+    match path {
+        Some(path) => dotenvy::from_path(path).ok().map(|_| path.to_path_buf()),
+        None => dotenvy::dotenv().ok(),
+    }
+}
+
+/// 環境変数ファイルの値をプロセスの環境変数に反映せずに読み込み，読み込んだファイルのパスと値を返す．
+/// `path` が `None` の場合はカレントディレクトリから親へ `.env` を探す．実行中の再読み込みに使う．
+pub fn read_env_file(path: Option<&Path>) -> Option<(PathBuf, HashMap<String, String>)> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => env::current_dir().ok()?.ancestors().map(|dir| dir.join(".env")).find(|p| p.is_file())?,
+    };
+    let values = dotenvy::from_path_iter(&path).ok()?.collect::<Result<HashMap<_, _>, _>>();
+    match values {
+        Ok(values) => Some((path, values)),
+        Err(e) => {
+            tracing::warn!("Failed to parse {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// 環境変数を読み込んでパースする．未設定の場合は `default` を返す．
+pub fn parse_env_or<T: FromStr>(key: &str, default: T) -> AppResult<T> {
+    lookup_or(&|key| env::var(key).ok(), key, default)
+}
+
+/// `lookup` で読み込んだ `key` の値をパースする．未設定・空の場合は `default` を返す．
+fn lookup_or<T: FromStr>(lookup: &impl Fn(&str) -> Option<String>, key: &str, default: T) -> AppResult<T> {
+    match lookup(key) {
+        Some(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse()
+            .map_err(|_| AppError::InternalError(format!("Invalid value for {}: {}", key, value))),
+        _ => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_or_uses_default_when_unset() {
+        let value: usize = parse_env_or("CLIPPER_TEST_UNSET_VARIABLE", 42).unwrap();
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn test_serve_config_default() {
+        let config = ServeConfig::default();
+        assert_eq!(config.bind_addr.port(), 8080);
+        assert_eq!(config.fetch_interval, Duration::from_secs(3600));
+        assert!(config.database_path.ends_with("clipper.sqlite3"));
+    }
+
+    #[test]
+    fn test_load_env_file_keeps_existing_values() {
+        let dir = std::env::temp_dir().join(format!("clipper-env-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("clipper.env");
+        std::fs::write(&path, "CLIPPER_TEST_RELOADED_VALUE=first\n").unwrap();
+        assert_eq!(load_env_file(Some(&path)), Some(path.clone()));
+        assert_eq!(env::var("CLIPPER_TEST_RELOADED_VALUE").unwrap(), "first");
+
+        std::fs::write(&path, "CLIPPER_TEST_RELOADED_VALUE=second\n").unwrap();
+        load_env_file(Some(&path));
+        assert_eq!(env::var("CLIPPER_TEST_RELOADED_VALUE").unwrap(), "first");
+
+        assert_eq!(load_env_file(Some(&dir.join("missing.env"))), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reload_from_env_file_without_touching_environment() {
+        let dir = std::env::temp_dir().join(format!("clipper-env-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("clipper.env");
+        std::fs::write(
+            &path,
+            "CLIPPER_FETCH_INTERVAL_MINUTES=15\nCLIPPER_CONCURRENCY=3\nCLIPPER_TEST_RELOAD_ONLY=file\n",
+        )
+        .unwrap();
+        let (loaded, values) = read_env_file(Some(&path)).unwrap();
+        assert_eq!(loaded, path);
+        assert_eq!(values["CLIPPER_TEST_RELOAD_ONLY"], "file");
+        assert!(env::var("CLIPPER_TEST_RELOAD_ONLY").is_err());
+
+        let config = ServeConfig::from_env_file(&values).unwrap();
+        assert_eq!(config.fetch_interval, Duration::from_secs(15 * 60));
+        assert_eq!(config.concurrency, 3);
+
+        let invalid = HashMap::from([("CLIPPER_FETCH_INTERVAL_MINUTES".to_string(), "0".to_string())]);
+        assert!(ServeConfig::from_env_file(&invalid).is_err());
+        assert!(read_env_file(Some(&dir.join("missing.env"))).is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
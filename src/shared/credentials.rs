@@ -0,0 +1,357 @@
+//! サイトごとの認証情報（API トークンなどのヘッダー）．
+//!
+//! Qiita・GitHub・Product Hunt などの API を使うサイトのトークンはサイト設定ファイル（リポジトリで共有しうる）とは
+//! 分けて `$CLIPPER_DATA_DIR/credentials.toml` に保存し，`WebSiteInterface::request` が自動で付ける．
+//! 値の `${NAME}` は環境変数で置き換えるため，トークン自体は `.env` に置くこともできる．
+//! `CLIPPER_STORAGE_KEY` を設定している場合は `clipper credentials encrypt` でファイル全体を記事の本文と同じ鍵で
+//! 暗号化でき，読み込み時に復号する（編集するときは `clipper credentials decrypt` で平文に戻す）．
+//!
+//! ```toml
+//! [sites."Qiita Blog".headers]
+//! Authorization = "Bearer ${QIITA_TOKEN}"
+//! Accept = "application/json"
+//!
+//! [sites.Medium]
+//! cookie = "sid=${MEDIUM_SID}; uid=${MEDIUM_UID}"
+//!
+//! [sites."Nikkei XTech"]
+//! username = "${NIKKEI_ID}"
+//! password = "${NIKKEI_PASSWORD}"
+//! ```
+
+use crate::shared::config::data_dir;
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::encryption::{self, StorageCipher};
+use request::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 認証情報のファイルのパスを指定する環境変数（未設定の場合は `$CLIPPER_DATA_DIR/credentials.toml`）
+pub const CREDENTIALS_FILE_ENV: &str = "CLIPPER_CREDENTIALS_FILE";
+const DEFAULT_FILE_NAME: &str = "credentials.toml";
+
+static GLOBAL_CREDENTIALS: OnceLock<Credentials> = OnceLock::new();
+
+/// 1サイトの認証情報
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteCredentials {
+    /// リクエストに付けるヘッダー（`RequestPolicy` のヘッダーより優先する）
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// ログイン済みのセッションの Cookie（`WebSiteInterface::login` で使う）
+    #[serde(default)]
+    pub cookie: Option<String>,
+    /// ログインフォームに入力する ID（`cookie` が無い場合にサイトがログインに使う）
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl fmt::Debug for SiteCredentials {
+    /// ログに値が出ないよう，ヘッダー名だけを表示する
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SiteCredentials")
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .field("cookie", &self.cookie.as_ref().map(|_| "<redacted>"))
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl SiteCredentials {
+    /// ヘッダーの値の `${NAME}` を環境変数で置き換えた `HeaderMap`（値はログに出ないよう機密扱いにする）
+    pub fn header_map(&self) -> AppResult<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let header = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|e| AppError::InternalError(format!("Invalid header name {}: {}", name, e)))?;
+            let expanded = expand_vars(value, |name| std::env::var(name).ok())?;
+            let mut value = HeaderValue::from_str(expanded.trim())
+                .map_err(|e| AppError::InternalError(format!("Invalid value of {} header: {}", name, e)))?;
+            value.set_sensitive(true);
+            headers.insert(header, value);
+        }
+        Ok(headers)
+    }
+
+    /// `${NAME}` を環境変数で置き換えたセッションの Cookie（未設定・空の場合は `None`）
+    pub fn cookie(&self) -> AppResult<Option<String>> {
+        let Some(cookie) = self.cookie.as_deref().map(str::trim).filter(|c| !c.is_empty()) else {
+            return Ok(None);
+        };
+        let expanded = expand_vars(cookie, |name| std::env::var(name).ok())?;
+        Ok(Some(expanded.trim().to_string()).filter(|c| !c.is_empty()))
+    }
+
+    /// `${NAME}` を環境変数で置き換えた ID とパスワード（どちらかが未設定の場合は `None`）
+    pub fn login(&self) -> AppResult<Option<(String, String)>> {
+        let (Some(username), Some(password)) = (&self.username, &self.password) else {
+            return Ok(None);
+        };
+        let lookup = |name: &str| std::env::var(name).ok();
+        let username = expand_vars(username.trim(), lookup)?;
+        let password = expand_vars(password, lookup)?;
+        if username.is_empty() || password.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some((username, password)))
+    }
+}
+
+/// サイト名ごとの認証情報
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Credentials {
+    #[serde(default)]
+    sites: HashMap<String, SiteCredentials>,
+}
+
+impl Credentials {
+    pub fn from_toml_str(content: &str) -> AppResult<Self> {
+        toml::from_str(content).map_err(|e| AppError::InternalError(format!("Invalid credentials file: {}", e)))
+    }
+
+    pub fn with_site(mut self, site_name: impl Into<String>, credentials: SiteCredentials) -> Self {
+        self.sites.insert(site_name.into(), credentials);
+        self
+    }
+
+    pub fn for_site(&self, site_name: &str) -> Option<&SiteCredentials> {
+        self.sites.get(site_name)
+    }
+
+    /// サイトのリクエストに付けるヘッダー（認証情報が無ければ空）
+    pub fn header_map(&self, site_name: &str) -> AppResult<HeaderMap> {
+        self.for_site(site_name)
+            .map(SiteCredentials::header_map)
+            .unwrap_or_else(|| Ok(HeaderMap::new()))
+    }
+
+    /// サイトのセッションの Cookie（認証情報が無ければ `None`）
+    pub fn cookie(&self, site_name: &str) -> AppResult<Option<String>> {
+        self.for_site(site_name).map_or(Ok(None), SiteCredentials::cookie)
+    }
+
+    /// サイトのログインフォームに入力する ID とパスワード（認証情報が無ければ `None`）
+    pub fn login(&self, site_name: &str) -> AppResult<Option<(String, String)>> {
+        self.for_site(site_name).map_or(Ok(None), SiteCredentials::login)
+    }
+
+    /// ファイルを読み込む．暗号化されたファイルは `cipher` で復号する（鍵が無い場合はエラー）．
+    pub fn load(path: &Path, cipher: Option<&StorageCipher>) -> AppResult<Self> {
+        let content = read_file(path)?;
+        warn_if_readable_by_others(path);
+        Self::from_toml_str(&encryption::open(cipher, content)?)
+    }
+
+    /// `CLIPPER_CREDENTIALS_FILE`（未設定の場合は `$CLIPPER_DATA_DIR/credentials.toml`）を読み込む．
+    /// ファイルが無い場合は認証情報無し．
+    pub fn from_env() -> AppResult<Self> {
+        let path = credentials_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load(&path, StorageCipher::from_env()?.as_ref())
+    }
+}
+
+/// `CLIPPER_CREDENTIALS_FILE`（未設定の場合は `$CLIPPER_DATA_DIR/credentials.toml`）
+pub fn credentials_path() -> PathBuf {
+    match std::env::var(CREDENTIALS_FILE_ENV) {
+        Ok(path) if !path.trim().is_empty() => PathBuf::from(path),
+        _ => data_dir().join(DEFAULT_FILE_NAME),
+    }
+}
+
+fn read_file(path: &Path) -> AppResult<String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::InternalError(format!("Failed to read credentials {:?}: {}", path, e)))?;
+    // 暗号化したファイルは1行（末尾の改行はエディタが付けることがある）
+    if encryption::is_encrypted(content.trim()) {
+        return Ok(content.trim().to_string());
+    }
+    Ok(content)
+}
+
+fn write_file(path: &Path, content: &str) -> AppResult<()> {
+    std::fs::write(path, content)
+        .map_err(|e| AppError::InternalError(format!("Failed to write credentials {:?}: {}", path, e)))
+}
+
+/// 認証情報のファイルを暗号化する．暗号化済みの場合は何もせず `false` を返す．
+pub fn encrypt_file(path: &Path, cipher: &StorageCipher) -> AppResult<bool> {
+    let content = read_file(path)?;
+    if encryption::is_encrypted(&content) {
+        return Ok(false);
+    }
+    // 壊れた内容を暗号化して読めなくならないよう，先に検証する
+    Credentials::from_toml_str(&content)?;
+    write_file(path, &format!("{}\n", cipher.encrypt(&content)?))?;
+    Ok(true)
+}
+
+/// 暗号化した認証情報のファイルを平文に戻す．暗号化されていない場合は何もせず `false` を返す．
+pub fn decrypt_file(path: &Path, cipher: &StorageCipher) -> AppResult<bool> {
+    let content = read_file(path)?;
+    if !encryption::is_encrypted(&content) {
+        return Ok(false);
+    }
+    write_file(path, &cipher.decrypt(&content)?)?;
+    Ok(true)
+}
+
+/// プロセス全体で共有する認証情報（読み込みに失敗した場合は警告を出して認証情報無しとする）
+pub fn global_credentials() -> &'static Credentials {
+    GLOBAL_CREDENTIALS.get_or_init(|| {
+        Credentials::from_env().unwrap_or_else(|e| {
+            tracing::warn!("{}; site credentials disabled", e);
+            Credentials::default()
+        })
+    })
+}
+
+/// `${NAME}` を `lookup`（環境変数）の値で置き換える（未設定の場合はエラー）
+fn expand_vars<F: Fn(&str) -> Option<String>>(value: &str, lookup: F) -> AppResult<String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .map(|i| start + i)
+            .ok_or_else(|| AppError::InternalError("Unterminated ${ in credentials".into()))?;
+        let name = &rest[start + 2..end];
+        let replaced = lookup(name).ok_or_else(|| {
+            AppError::InternalError(format!("Environment variable {} for credentials is not set", name))
+        })?;
+        out.push_str(&rest[..start]);
+        out.push_str(&replaced);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(unix)]
+fn warn_if_readable_by_others(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Ok(metadata) = std::fs::metadata(path)
+        && metadata.permissions().mode() & 0o077 != 0
+    {
+        tracing::warn!("Credentials file {:?} is readable by other users; chmod 600 is recommended", path);
+    }
+}
+
+#[cfg(not(unix))]
+fn warn_if_readable_by_others(_path: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use request::header::{ACCEPT, AUTHORIZATION};
+
+    #[test]
+    fn test_credentials_headers() {
+        let credentials = Credentials::from_toml_str(
+            r#"
+[sites."Qiita Blog".headers]
+Authorization = "Bearer secret-token"
+Accept = "application/json"
+"#,
+        )
+        .unwrap();
+        let headers = credentials.header_map("Qiita Blog").unwrap();
+        assert_eq!(headers[AUTHORIZATION], "Bearer secret-token");
+        assert!(headers[AUTHORIZATION].is_sensitive());
+        assert_eq!(headers[ACCEPT], "application/json");
+        assert!(credentials.header_map("Gigazine").unwrap().is_empty());
+        assert!(!format!("{:?}", credentials).contains("secret-token"));
+    }
+
+    #[test]
+    fn test_credentials_cookie() {
+        let credentials = Credentials::from_toml_str(
+            r#"
+[sites.Medium]
+cookie = "sid=session-id; uid=user-id"
+
+[sites.Empty]
+cookie = "  "
+"#,
+        )
+        .unwrap();
+        assert_eq!(credentials.cookie("Medium").unwrap().as_deref(), Some("sid=session-id; uid=user-id"));
+        assert!(credentials.header_map("Medium").unwrap().is_empty());
+        assert_eq!(credentials.cookie("Empty").unwrap(), None);
+        assert_eq!(credentials.cookie("Gigazine").unwrap(), None);
+        assert!(!format!("{:?}", credentials).contains("session-id"));
+    }
+
+    #[test]
+    fn test_credentials_login() {
+        let credentials = Credentials::from_toml_str(
+            r#"
+[sites."Nikkei XTech"]
+username = "reader@example.com"
+password = "hunter2"
+
+[sites.Partial]
+username = "reader@example.com"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            credentials.login("Nikkei XTech").unwrap(),
+            Some(("reader@example.com".to_string(), "hunter2".to_string()))
+        );
+        assert_eq!(credentials.login("Partial").unwrap(), None);
+        assert_eq!(credentials.login("Gigazine").unwrap(), None);
+        let debug = format!("{:?}", credentials);
+        assert!(debug.contains("reader@example.com"));
+        assert!(!debug.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_credentials_errors() {
+        assert!(Credentials::from_toml_str("[sites.\"A\"]\ntoken = \"x\"").is_err());
+        let missing = Credentials::from_toml_str(
+            "[sites.\"A\".headers]\nAuthorization = \"Bearer ${CLIPPER_TEST_UNSET_TOKEN}\"",
+        )
+        .unwrap();
+        assert!(missing.header_map("A").is_err());
+        let lookup = |name: &str| (name == "QIITA_TOKEN").then(|| "secret-token".to_string());
+        assert_eq!(expand_vars("Bearer ${QIITA_TOKEN}", lookup).unwrap(), "Bearer secret-token");
+        assert!(expand_vars("Bearer ${OTHER_TOKEN}", lookup).is_err());
+        assert!(expand_vars("${UNTERMINATED", lookup).is_err());
+        assert_eq!(expand_vars("plain value", lookup).unwrap(), "plain value");
+    }
+
+    #[test]
+    fn test_encrypted_credentials_file() {
+        let dir = std::env::temp_dir().join(format!("clipper-credentials-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(DEFAULT_FILE_NAME);
+        let cipher = StorageCipher::new(&[9u8; 32]).unwrap();
+        std::fs::write(&path, "[sites.Medium]\ncookie = \"sid=session-id\"\n").unwrap();
+
+        assert!(encrypt_file(&path, &cipher).unwrap());
+        assert!(!encrypt_file(&path, &cipher).unwrap());
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("session-id"));
+        let credentials = Credentials::load(&path, Some(&cipher)).unwrap();
+        assert_eq!(credentials.cookie("Medium").unwrap().as_deref(), Some("sid=session-id"));
+        assert!(Credentials::load(&path, None).is_err());
+
+        assert!(decrypt_file(&path, &cipher).unwrap());
+        assert!(!decrypt_file(&path, &cipher).unwrap());
+        assert_eq!(Credentials::load(&path, None).unwrap(), credentials);
+
+        std::fs::write(&path, "not = [valid").unwrap();
+        assert!(encrypt_file(&path, &cipher).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use crate::shared::errors::{AppError, AppResult};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// サイトごとに追加で許可するドメインを指定する環境変数
+/// （例: `Medium=towardsdatascience.com|betterprogramming.pub,AI News=*`）
+pub const ALLOWED_DOMAINS_ENV: &str = "CLIPPER_ALLOWED_DOMAINS";
+
+/// 全てのドメインを許可する指定（外部サイトの記事を集約するサイト用）
+pub const ANY_DOMAIN: &str = "*";
+
+/// フィード・トップページのホスト名から取り除く接頭辞（記事は別のサブドメインにあることが多い）
+const HOST_PREFIXES: &[&str] = &["www.", "rss.", "feeds.", "feed."];
+
+static GLOBAL_EXCEPTIONS: OnceLock<DomainExceptions> = OnceLock::new();
+
+/// サイトのドメインから，記事の URL として許可するドメインを求める（`rss.itmedia.co.jp` → `itmedia.co.jp`）
+pub fn base_domain(domain: &str) -> String {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    HOST_PREFIXES
+        .iter()
+        .find_map(|prefix| domain.strip_prefix(prefix).filter(|rest| rest.contains('.')))
+        .map(str::to_string)
+        .unwrap_or(domain)
+}
+
+/// `host` が許可されたドメインまたはそのサブドメインか
+pub fn is_allowed_host(host: &str, allowed: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed.iter().any(|domain| {
+        let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+        domain == ANY_DOMAIN || host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+/// `WebSiteInterface::allowed_domains` のデフォルト（サイトのドメインと設定された例外）
+pub fn default_allowed_domains(site_name: &str, domain: &str) -> Vec<String> {
+    let mut domains = vec![base_domain(domain)];
+    domains.extend(global_domain_exceptions().for_site(site_name).iter().cloned());
+    domains
+}
+
+/// サイト名ごとに追加で許可するドメイン
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DomainExceptions(HashMap<String, Vec<String>>);
+
+impl DomainExceptions {
+    pub fn for_site(&self, site_name: &str) -> &[String] {
+        self.0.get(site_name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn with_domains(mut self, site_name: impl Into<String>, domains: Vec<String>) -> Self {
+        self.0.entry(site_name.into()).or_default().extend(domains);
+        self
+    }
+
+    /// `CLIPPER_ALLOWED_DOMAINS` から読み込む．未設定の場合は例外無し．
+    pub fn from_env() -> AppResult<Self> {
+        match std::env::var(ALLOWED_DOMAINS_ENV) {
+            Ok(value) if !value.trim().is_empty() => value.parse(),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+impl FromStr for DomainExceptions {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut exceptions = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (site, domains) = pair
+                .rsplit_once('=')
+                .ok_or_else(|| AppError::InternalError(format!("Invalid allowed domains: {}", pair)))?;
+            let domains: Vec<String> = domains
+                .split('|')
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+                .collect();
+            exceptions = exceptions.with_domains(site.trim(), domains);
+        }
+        Ok(exceptions)
+    }
+}
+
+/// プロセス全体で共有するドメインの例外設定
+pub fn global_domain_exceptions() -> &'static DomainExceptions {
+    GLOBAL_EXCEPTIONS.get_or_init(|| {
+        DomainExceptions::from_env().unwrap_or_else(|e| {
+            tracing::warn!("{}; no extra domains are allowed", e);
+            DomainExceptions::default()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_domain() {
+        assert_eq!(base_domain("rss.itmedia.co.jp"), "itmedia.co.jp");
+        assert_eq!(base_domain("www.businessinsider.jp"), "businessinsider.jp");
+        assert_eq!(base_domain("feeds.trendmicro.com"), "trendmicro.com");
+        assert_eq!(base_domain("zenn.dev"), "zenn.dev");
+        assert_eq!(base_domain("www.com"), "www.com");
+    }
+
+    #[test]
+    fn test_is_allowed_host() {
+        let allowed = vec!["itmedia.co.jp".to_string()];
+        assert!(is_allowed_host("www.itmedia.co.jp", &allowed));
+        assert!(is_allowed_host("atmarkit.itmedia.co.jp", &allowed));
+        assert!(is_allowed_host("ITMEDIA.co.jp", &allowed));
+        assert!(!is_allowed_host("evil-itmedia.co.jp", &allowed));
+        assert!(!is_allowed_host("itmedia.co.jp.evil.com", &allowed));
+        assert!(is_allowed_host("anything.example.org", &[ANY_DOMAIN.to_string()]));
+    }
+
+    #[test]
+    fn test_parse_exceptions() {
+        let exceptions: DomainExceptions = "Medium=towardsdatascience.com | betterprogramming.pub, AI News=*"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            exceptions.for_site("Medium"),
+            ["towardsdatascience.com", "betterprogramming.pub"]
+        );
+        assert_eq!(exceptions.for_site("AI News"), ["*"]);
+        assert!(exceptions.for_site("Gigazine").is_empty());
+        assert!("Medium".parse::<DomainExceptions>().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::models::sites::generic_feed::FeedKind;
 use axum::{http::StatusCode, response::IntoResponse};
 use thiserror::Error;
 
@@ -21,11 +22,20 @@ pub enum AppError {
     RssParseError(#[source] feed_parser::parsers::errors::ParseError),
 
     // from request errors
-    #[error("Request Error: {0}")]
-    RequestError(#[from] request::Error),
+    #[error("Request Error: {source} (attempts: {attempts})")]
+    RequestError {
+        #[source]
+        source: request::Error,
+        /// 再試行を含む試行回数
+        attempts: u32,
+    },
     #[error("Request Error - parse error: {0}")]
     ParseError(#[from] url::ParseError),
 
+    // from storage errors
+    #[error("Storage Error: {0}")]
+    StorageError(#[from] rusqlite::Error),
+
     // from serde errors
     #[error("Json Parse Error: {0}")]
     JsonParseError(#[from] serde_json::Error),
@@ -34,17 +44,71 @@ pub enum AppError {
     #[error("DateTime Parse Error: {0}")]
     DateTimeParseError(#[from] chrono::ParseError),
 
-    // from scrape errors
+    // storage encryption errors
+    #[error("Encryption Error: {0}")]
+    EncryptionError(String),
+
+    // from template errors
+    #[error("Template Error: {0}")]
+    TemplateError(#[from] minijinja::Error),
+
+    // from scrape errors (failures not covered by the typed variants below)
     #[error("Scrape Error: {0}")]
     ScrapeError(String),
+    // no element matched the content selector (the page markup may have changed)
+    #[error("Scrape Error: selector not found on {site}: {selector}")]
+    SelectorNotFound { site: String, selector: String },
+    // the feed could not be parsed as the expected format
+    #[error("Scrape Error: failed to parse {kind} feed of {site}: {message}")]
+    FeedParse {
+        site: String,
+        /// フィードの形式（`rss2` や JSON API の `json` など）
+        kind: FeedKind,
+        message: String,
+    },
+    // the article was found but its body was empty
+    #[error("Scrape Error: empty content: {0}")]
+    EmptyContent(String),
+    // a feed item or page had a missing or malformed date
+    #[error("Scrape Error: invalid date: {0}")]
+    DateParse(String),
 
     // from openai-tools errors
     #[error("OpenAI Tools Error: {0}")]
     OpenAIToolError(#[from] openai_tools::common::OpenAIToolError),
+    #[error("LLM Error: {0}")]
+    LlmError(String),
+
+    // article URL outside of the site's allowed domains
+    #[error("Disallowed Domain: {0}")]
+    DisallowedDomain(String),
+
+    // user-supplied URL rejected by the SSRF guard
+    #[error("Blocked URL: {0}")]
+    BlockedUrl(String),
+    // response body of a user-supplied URL over the size limit
+    #[error("Response too large: {0}")]
+    ResponseTooLarge(String),
 
     // article behind a member login / paywall
     #[error("Login required")]
     LoginRequired,
+    // only the free preview of a paywalled article is available
+    #[error("Paywalled: only a free preview is available ({} chars)", preview.chars().count())]
+    PaywallError {
+        /// 無料で読める部分の本文
+        preview: String,
+    },
+
+    // api errors
+    #[error("Not Found: {0}")]
+    NotFound(String),
+    #[error("Invalid share link: {0}")]
+    InvalidShareLink(String),
+    #[error("User required: {0}")]
+    UserRequired(String),
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
 }
 
 fn app_error_to_status_code(error: &AppError) -> StatusCode {
@@ -54,13 +118,38 @@ fn app_error_to_status_code(error: &AppError) -> StatusCode {
         AppError::EnumParseError(_) => StatusCode::BAD_REQUEST,
         AppError::ConvertToUuidError(_) => StatusCode::BAD_REQUEST,
         AppError::RssParseError(_) => StatusCode::BAD_REQUEST,
-        AppError::RequestError(_) => StatusCode::BAD_REQUEST,
+        AppError::RequestError { .. } => StatusCode::BAD_REQUEST,
         AppError::ParseError(_) => StatusCode::BAD_REQUEST,
         AppError::JsonParseError(_) => StatusCode::BAD_REQUEST,
+        AppError::StorageError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        AppError::EncryptionError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        AppError::TemplateError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         AppError::ScrapeError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        AppError::SelectorNotFound { .. } => StatusCode::BAD_GATEWAY,
+        AppError::FeedParse { .. } => StatusCode::BAD_GATEWAY,
+        AppError::EmptyContent(_) => StatusCode::BAD_GATEWAY,
+        AppError::DateParse(_) => StatusCode::BAD_GATEWAY,
         AppError::OpenAIToolError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        AppError::LlmError(_) => StatusCode::BAD_GATEWAY,
         AppError::DateTimeParseError(_) => StatusCode::BAD_REQUEST,
+        AppError::DisallowedDomain(_) => StatusCode::BAD_REQUEST,
+        AppError::BlockedUrl(_) => StatusCode::BAD_REQUEST,
+        AppError::ResponseTooLarge(_) => StatusCode::BAD_GATEWAY,
         AppError::LoginRequired => StatusCode::FORBIDDEN,
+        AppError::PaywallError { .. } => StatusCode::PAYMENT_REQUIRED,
+        AppError::NotFound(_) => StatusCode::NOT_FOUND,
+        AppError::InvalidShareLink(_) => StatusCode::FORBIDDEN,
+        AppError::UserRequired(_) => StatusCode::BAD_REQUEST,
+        AppError::InvalidSignature(_) => StatusCode::UNAUTHORIZED,
+    }
+}
+
+impl From<request::Error> for AppError {
+    fn from(source: request::Error) -> Self {
+        AppError::RequestError {
+            source,
+            attempts: 1,
+        }
     }
 }
 
@@ -0,0 +1,264 @@
+//! `ETag` / `Last-Modified` を使った HTTP レスポンスのキャッシュ．
+//!
+//! 検証子（validator）付きで返されたレスポンスをディスクに保存し，次回は条件付き GET
+//! （`If-None-Match` / `If-Modified-Since`）を送る．`304 Not Modified` の場合は保存済みの本文を返す．
+//! `CLIPPER_STORAGE_KEY` を設定している場合は保存する内容を記事の本文と同じ鍵で暗号化する．
+
+use crate::shared::config::{data_dir, parse_env_or};
+use crate::shared::errors::{AppError, AppResult};
+use crate::storage::encryption::{self, StorageCipher};
+use chrono::{DateTime, Local};
+use request::header::{
+    CONTENT_TYPE, ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use request::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static GLOBAL_CACHE: OnceLock<Option<FetchCache>> = OnceLock::new();
+
+/// 保存されたレスポンス
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+    pub body: String,
+    pub stored_at: DateTime<Local>,
+}
+
+impl CachedResponse {
+    /// 保存済みの本文から `200 OK` のレスポンスを組み立てる
+    pub fn to_response(&self) -> Response {
+        let mut builder = axum::http::Response::builder().status(StatusCode::OK.as_u16());
+        if let Some(content_type) = &self.content_type {
+            builder = builder.header(CONTENT_TYPE.as_str(), content_type.as_str());
+        }
+        if let Some(etag) = &self.etag {
+            builder = builder.header(ETAG.as_str(), etag.as_str());
+        }
+        if let Some(last_modified) = &self.last_modified {
+            builder = builder.header(LAST_MODIFIED.as_str(), last_modified.as_str());
+        }
+        Response::from(builder.body(self.body.clone()).expect("cached headers are valid"))
+    }
+}
+
+/// URL をキーにしたディスク上のレスポンスキャッシュ
+#[derive(Debug, Clone)]
+pub struct FetchCache {
+    dir: PathBuf,
+    cipher: Option<StorageCipher>,
+}
+
+impl FetchCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, cipher: None }
+    }
+
+    /// 保存する内容を `cipher` で暗号化する（`None` の場合は平文）
+    pub fn with_cipher(mut self, cipher: Option<StorageCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// `CLIPPER_HTTP_CACHE=false` で無効化できる．保存先は `$CLIPPER_DATA_DIR/http-cache`．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        if !parse_env_or("CLIPPER_HTTP_CACHE", true)? {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(data_dir().join("http-cache")).with_cipher(StorageCipher::from_env()?)))
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        let digest: String = Sha256::digest(url.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.dir.join(format!("{}.json", digest))
+    }
+
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        let content = std::fs::read_to_string(self.path_for(url)).ok()?;
+        let content = match encryption::open(self.cipher.as_ref(), content) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("{}; ignoring HTTP cache for {}", e, url);
+                return None;
+            }
+        };
+        serde_json::from_str::<CachedResponse>(&content)
+            .ok()
+            .filter(|cached| cached.url == url)
+    }
+
+    pub fn put(&self, cached: &CachedResponse) -> AppResult<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+        let path = self.path_for(&cached.url);
+        let content = encryption::seal(self.cipher.as_ref(), &serde_json::to_string(cached)?)?;
+        std::fs::write(&path, content).map_err(|e| io_error(&path, e))
+    }
+
+    /// `url` のキャッシュを削除する．削除した場合は `true`．
+    pub fn remove(&self, url: &str) -> AppResult<bool> {
+        let path = self.path_for(url);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(io_error(&path, e)),
+        }
+    }
+
+    /// 保存済みの検証子を条件付きリクエストのヘッダとして付ける
+    pub fn conditional(&self, builder: RequestBuilder, cached: Option<&CachedResponse>) -> RequestBuilder {
+        let Some(cached) = cached else {
+            return builder;
+        };
+        let mut builder = builder;
+        if let Some(etag) = cached.etag.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            builder = builder.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(lm) = cached.last_modified.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            builder = builder.header(IF_MODIFIED_SINCE, lm);
+        }
+        builder
+    }
+
+    /// レスポンスをキャッシュと照合する．
+    /// `304` なら保存済みの本文を，検証子付きの `200` なら保存したうえで同じ内容のレスポンスを返す．
+    pub async fn resolve(
+        &self,
+        url: &str,
+        response: Response,
+        cached: Option<CachedResponse>,
+    ) -> AppResult<Response> {
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                tracing::debug!("HTTP cache hit (304): {}", url);
+                return Ok(cached.to_response());
+            }
+            return Ok(response);
+        }
+        if response.status() != StatusCode::OK {
+            return Ok(response);
+        }
+        let headers = response.headers().clone();
+        let etag = header_string(&headers, ETAG);
+        let last_modified = header_string(&headers, LAST_MODIFIED);
+        if etag.is_none() && last_modified.is_none() {
+            return Ok(response);
+        }
+
+        let entry = CachedResponse {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            content_type: header_string(&headers, CONTENT_TYPE),
+            body: response.text().await?,
+            stored_at: Local::now(),
+        };
+        if let Err(e) = self.put(&entry) {
+            tracing::warn!("{}", e);
+        }
+        Ok(entry.to_response())
+    }
+}
+
+fn header_string(headers: &HeaderMap, name: request::header::HeaderName) -> Option<String> {
+    headers.get(name)?.to_str().ok().map(str::to_string)
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> AppError {
+    AppError::InternalError(format!("Failed to access HTTP cache {:?}: {}", path, e))
+}
+
+/// プロセス全体で共有するキャッシュ（無効化されている場合は `None`）
+pub fn global_fetch_cache() -> Option<&'static FetchCache> {
+    GLOBAL_CACHE
+        .get_or_init(|| {
+            FetchCache::from_env().unwrap_or_else(|e| {
+                tracing::warn!("{}; HTTP cache disabled", e);
+                None
+            })
+        })
+        .as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache() -> (FetchCache, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("clipper-http-cache-{}", uuid::Uuid::new_v4()));
+        (FetchCache::new(dir.clone()), dir)
+    }
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> Response {
+        let mut builder = axum::http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        Response::from(builder.body(body.to_string()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_cache_stores_and_serves_not_modified() {
+        let (cache, dir) = temp_cache();
+        let url = "https://example.com/feed.xml";
+
+        let fresh = response(200, &[("etag", "\"v1\""), ("content-type", "application/xml")], "<rss/>");
+        let resolved = cache.resolve(url, fresh, None).await.unwrap();
+        assert_eq!(resolved.text().await.unwrap(), "<rss/>");
+
+        let cached = cache.get(url).unwrap();
+        assert_eq!(cached.etag.as_deref(), Some("\"v1\""));
+
+        let not_modified = response(304, &[], "");
+        let resolved = cache.resolve(url, not_modified, Some(cached)).await.unwrap();
+        assert_eq!(resolved.status(), StatusCode::OK);
+        assert_eq!(resolved.text().await.unwrap(), "<rss/>");
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_cache_skips_responses_without_validators() {
+        let (cache, dir) = temp_cache();
+        let url = "https://example.com/no-validators";
+        let resolved = cache.resolve(url, response(200, &[], "body"), None).await.unwrap();
+        assert_eq!(resolved.text().await.unwrap(), "body");
+        assert!(cache.get(url).is_none());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_cache_remove() {
+        let (cache, dir) = temp_cache();
+        let url = "https://example.com/article";
+        cache.resolve(url, response(200, &[("etag", "\"v1\"")], "body"), None).await.unwrap();
+        assert!(cache.remove(url).unwrap());
+        assert!(cache.get(url).is_none());
+        assert!(!cache.remove(url).unwrap());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_cache_encrypts_entries() {
+        let (cache, dir) = temp_cache();
+        let cipher = StorageCipher::new(&[7u8; 32]).unwrap();
+        let cache = cache.with_cipher(Some(cipher));
+        let url = "https://example.com/secret";
+        cache.resolve(url, response(200, &[("etag", "\"v1\"")], "社外秘の本文"), None).await.unwrap();
+
+        let stored = std::fs::read_to_string(cache.path_for(url)).unwrap();
+        assert!(encryption::is_encrypted(&stored));
+        assert!(!stored.contains("社外秘"));
+        assert_eq!(cache.get(url).unwrap().body, "社外秘の本文");
+        // 鍵が無い場合は読めないのでキャッシュ無しとして扱う
+        assert!(FetchCache::new(dir.clone()).get(url).is_none());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,188 @@
+use crate::shared::errors::{AppError, AppResult};
+use encoding_rs::{Encoding, UTF_8};
+use lol_html::html_content::Element;
+use lol_html::{AsciiCompatibleEncoding, ElementContentHandlers, HtmlRewriter, Selector, Settings};
+use request::Response;
+use request::header::CONTENT_TYPE;
+use std::borrow::Cow;
+
+/// 書き換えスレッドへ渡すチャンクのバッファ数（ダウンロードが先行しすぎないよう小さく保つ）
+const CHANNEL_CHUNKS: usize = 8;
+
+/// ダウンロード中の HTML から除外対象の要素を取り除くストリーミングのクリーナ．
+///
+/// `lol_html` で受信したチャンクを逐次書き換えるため，数 MB のページでも DOM 全体を構築せずに
+/// `<script>` や広告などの不要な要素を捨てられる．`lol_html` が解釈できないセレクタはここでは適用せず，
+/// 本文の抽出後に行う DOM ベースのクリーニング（`clean_content` など）に任せる．
+/// JSON-LD（`<script type="application/ld+json">`）はメタデータの抽出に使うため常に残す．
+#[derive(Debug, Clone, Default)]
+pub struct StreamingCleaner {
+    selectors: Vec<String>,
+}
+
+impl StreamingCleaner {
+    pub fn new(selectors: &[&str]) -> Self {
+        let selectors = selectors
+            .iter()
+            .filter(|s| s.parse::<Selector>().is_ok())
+            .map(|s| s.to_string())
+            .collect();
+        Self { selectors }
+    }
+
+    /// ストリーミングで適用されるセレクタ
+    pub fn selectors(&self) -> &[String] {
+        &self.selectors
+    }
+
+    /// メモリ上の HTML を書き換える（UTF-8）
+    pub fn clean(&self, html: &str) -> AppResult<String> {
+        let output = rewrite(&self.selectors, UTF_8, [html.as_bytes()])?;
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+
+    /// レスポンス本文を受信しながら書き換え，除外後の HTML を返す．
+    ///
+    /// 文字コードは `Content-Type` の charset に従う（無ければ UTF-8）．
+    /// ASCII 互換でない文字コード（UTF-16 など）はストリーミングできないため，そのまま全体を読み込む．
+    pub async fn clean_response(&self, mut response: Response) -> AppResult<String> {
+        let encoding = response_encoding(&response);
+        if AsciiCompatibleEncoding::new(encoding).is_none() {
+            return Ok(response.text().await?);
+        }
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(CHANNEL_CHUNKS);
+        let selectors = self.selectors.clone();
+        let worker = tokio::task::spawn_blocking(move || {
+            rewrite(&selectors, encoding, std::iter::from_fn(|| rx.blocking_recv()))
+        });
+        while let Some(chunk) = response.chunk().await? {
+            // 書き換えスレッドが失敗して受信側が閉じた場合は，残りを読まずに結果のエラーを返す
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+        drop(tx);
+
+        let output = worker
+            .await
+            .map_err(|e| AppError::InternalError(format!("HTML rewriting task failed: {}", e)))??;
+        let (text, _, _) = encoding.decode(&output);
+        Ok(text.into_owned())
+    }
+}
+
+/// `Content-Type` の charset から文字コードを判定する（不明な場合は UTF-8）
+pub(crate) fn response_encoding(response: &Response) -> &'static Encoding {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(charset_label)
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8)
+}
+
+fn charset_label(content_type: &str) -> Option<&str> {
+    content_type.split(';').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+fn is_json_ld(element: &Element) -> bool {
+    element.tag_name() == "script"
+        && element
+            .get_attribute("type")
+            .is_some_and(|t| t.trim().eq_ignore_ascii_case("application/ld+json"))
+}
+
+/// チャンクを順に `lol_html` に流し込み，セレクタに一致する要素を取り除いた HTML を返す
+fn rewrite<I>(selectors: &[String], encoding: &'static Encoding, chunks: I) -> AppResult<Vec<u8>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let encoding = AsciiCompatibleEncoding::new(encoding)
+        .ok_or_else(|| AppError::InternalError(format!("Unsupported encoding: {}", encoding.name())))?;
+    let handlers = selectors
+        .iter()
+        .filter_map(|s| s.parse::<Selector>().ok())
+        .map(|selector| {
+            let handler = ElementContentHandlers::default().element(|el| {
+                if !is_json_ld(el) {
+                    el.remove();
+                }
+                Ok(())
+            });
+            (Cow::Owned(selector), handler)
+        })
+        .collect();
+
+    let mut output = Vec::new();
+    let mut rewriter = HtmlRewriter::new(
+        Settings {
+            element_content_handlers: handlers,
+            encoding,
+            ..Settings::new()
+        },
+        |chunk: &[u8]| output.extend_from_slice(chunk),
+    );
+    for chunk in chunks {
+        rewriter
+            .write(chunk.as_ref())
+            .map_err(|e| AppError::ScrapeError(format!("Failed to rewrite HTML: {}", e)))?;
+    }
+    rewriter
+        .end()
+        .map_err(|e| AppError::ScrapeError(format!("Failed to rewrite HTML: {}", e)))?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_cleaner_removes_excluded_elements() {
+        let cleaner = StreamingCleaner::new(&["script", "nav", "[class*='ad-']"]);
+        let html = r#"<html><body><nav>Menu</nav><article><p>Body</p><div class="ad-box">Buy</div></article><script>track()</script></body></html>"#;
+        let cleaned = cleaner.clean(html).unwrap();
+        assert!(cleaned.contains("<p>Body</p>"));
+        assert!(!cleaned.contains("Menu"));
+        assert!(!cleaned.contains("Buy"));
+        assert!(!cleaned.contains("track()"));
+    }
+
+    #[test]
+    fn test_streaming_cleaner_keeps_json_ld() {
+        let cleaner = StreamingCleaner::new(&["script"]);
+        let html = r#"<head><script type="application/ld+json">{"@type":"NewsArticle"}</script><script>x()</script></head>"#;
+        let cleaned = cleaner.clean(html).unwrap();
+        assert!(cleaned.contains("NewsArticle"));
+        assert!(!cleaned.contains("x()"));
+    }
+
+    #[test]
+    fn test_streaming_cleaner_handles_split_chunks() {
+        let selectors = vec!["aside".to_string()];
+        let chunks = ["<p>Keep</p><as", "ide>Drop</as", "ide><p>Tail</p>"];
+        let output = rewrite(&selectors, UTF_8, chunks).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "<p>Keep</p><p>Tail</p>");
+    }
+
+    #[test]
+    fn test_streaming_cleaner_skips_unsupported_selectors() {
+        let cleaner = StreamingCleaner::new(&["nav", "div:has(> p)", "p::before"]);
+        assert_eq!(cleaner.selectors(), ["nav".to_string()]);
+    }
+
+    #[test]
+    fn test_charset_label() {
+        assert_eq!(charset_label("text/html; charset=Shift_JIS"), Some("Shift_JIS"));
+        assert_eq!(charset_label("text/html;Charset=\"utf-8\""), Some("utf-8"));
+        assert_eq!(charset_label("text/html"), None);
+    }
+}
@@ -85,3 +85,4 @@ macro_rules! define_id {
 
 define_id!(WebSiteId);
 define_id!(WebArticleId);
+define_id!(AnnotationId);
@@ -0,0 +1,198 @@
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::AppResult;
+use request::Response;
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{Level, event};
+
+/// 予算を管理する単位（1 KiB）
+const UNIT_BYTES: usize = 1024;
+/// Content-Length が無いレスポンスの見積もりサイズ
+pub const DEFAULT_BODY_ESTIMATE: usize = 512 * 1024;
+const DEFAULT_BUDGET_MB: usize = 256;
+
+static GLOBAL_BUDGET: OnceLock<MemoryBudget> = OnceLock::new();
+
+/// 処理中の HTML ドキュメントが占有するメモリ量の上限．
+///
+/// 本文のバイト数を重みとしたセマフォで，予算を超える場合は空きが出るまで待機する．
+/// 予算より大きい単一のドキュメントは予算全体を確保して処理する（永久に待たない）．
+#[derive(Debug, Clone)]
+pub struct MemoryBudget {
+    semaphore: Arc<Semaphore>,
+    total_units: u32,
+}
+
+impl MemoryBudget {
+    pub fn new(budget_bytes: usize) -> Self {
+        let total_units = bytes_to_units(budget_bytes).max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(total_units as usize)),
+            total_units,
+        }
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.total_units as usize * UNIT_BYTES
+    }
+
+    pub fn available_bytes(&self) -> usize {
+        self.semaphore.available_permits() * UNIT_BYTES
+    }
+
+    /// `bytes` 分の予算を確保する．空きが無い場合は解放されるまで待機する．
+    pub async fn acquire(&self, bytes: usize) -> MemoryPermit {
+        let units = bytes_to_units(bytes).clamp(1, self.total_units);
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_many_owned(units)
+            .await
+            .expect("memory budget semaphore is never closed");
+        MemoryPermit {
+            semaphore: self.semaphore.clone(),
+            permits: vec![permit],
+            units,
+            total_units: self.total_units,
+        }
+    }
+}
+
+/// 確保済みの予算．ドロップ時に解放される．
+#[derive(Debug)]
+pub struct MemoryPermit {
+    semaphore: Arc<Semaphore>,
+    permits: Vec<OwnedSemaphorePermit>,
+    units: u32,
+    total_units: u32,
+}
+
+impl MemoryPermit {
+    pub fn bytes(&self) -> usize {
+        self.units as usize * UNIT_BYTES
+    }
+
+    /// 実際の本文サイズが見積もりを超えた場合に予算を追加で確保する．
+    /// 待機するとデッドロックの恐れがあるため，空きが無ければ超過を許容して警告のみ出す．
+    pub fn grow_to(&mut self, bytes: usize) {
+        let wanted = bytes_to_units(bytes).min(self.total_units);
+        if wanted <= self.units {
+            return;
+        }
+        let extra = wanted - self.units;
+        match self.semaphore.clone().try_acquire_many_owned(extra) {
+            Ok(permit) => {
+                self.permits.push(permit);
+                self.units = wanted;
+            }
+            Err(_) => {
+                event!(
+                    Level::WARN,
+                    "Memory budget exceeded: document of {} bytes overcommits the budget",
+                    bytes
+                );
+            }
+        }
+    }
+}
+
+/// 予算を確保したまま保持されるレスポンス本文
+#[derive(Debug)]
+pub struct BudgetedText {
+    text: String,
+    _permit: MemoryPermit,
+}
+
+impl BudgetedText {
+    pub fn new(text: String, permit: MemoryPermit) -> Self {
+        Self {
+            text,
+            _permit: permit,
+        }
+    }
+}
+
+impl Deref for BudgetedText {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.text
+    }
+}
+
+/// グローバルなメモリ予算を確保してからレスポンス本文を読み込む．
+/// 状態コードを確認してから本文を読む場合など，`WebSiteInterface::request_text` を使えないときに使う．
+pub async fn budgeted_text(response: Response) -> AppResult<BudgetedText> {
+    let estimate = response
+        .content_length()
+        .map(|len| len as usize)
+        .unwrap_or(DEFAULT_BODY_ESTIMATE);
+    let mut permit = global_memory_budget().acquire(estimate).await;
+    let text = response.text().await?;
+    permit.grow_to(text.len());
+    Ok(BudgetedText::new(text, permit))
+}
+
+/// プロセス全体で共有するメモリ予算（`CLIPPER_MEMORY_BUDGET_MB`，デフォルト 256MB）
+pub fn global_memory_budget() -> &'static MemoryBudget {
+    GLOBAL_BUDGET.get_or_init(|| {
+        let mb = parse_env_or("CLIPPER_MEMORY_BUDGET_MB", DEFAULT_BUDGET_MB).unwrap_or(DEFAULT_BUDGET_MB);
+        MemoryBudget::new(mb.max(1) * 1024 * 1024)
+    })
+}
+
+fn bytes_to_units(bytes: usize) -> u32 {
+    bytes.div_ceil(UNIT_BYTES).min(u32::MAX as usize) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_memory_budget_acquire_and_release() {
+        let budget = MemoryBudget::new(10 * 1024);
+        let permit = budget.acquire(4 * 1024).await;
+        assert_eq!(permit.bytes(), 4 * 1024);
+        assert_eq!(budget.available_bytes(), 6 * 1024);
+        drop(permit);
+        assert_eq!(budget.available_bytes(), 10 * 1024);
+    }
+
+    #[tokio::test]
+    async fn test_memory_budget_oversized_document_takes_whole_budget() {
+        let budget = MemoryBudget::new(10 * 1024);
+        let permit = budget.acquire(100 * 1024).await;
+        assert_eq!(permit.bytes(), 10 * 1024);
+        assert_eq!(budget.available_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_memory_budget_waits_for_release() {
+        let budget = MemoryBudget::new(10 * 1024);
+        let first = budget.acquire(8 * 1024).await;
+
+        let waiting = tokio::time::timeout(Duration::from_millis(50), budget.acquire(4 * 1024)).await;
+        assert!(waiting.is_err(), "acquire should wait while budget is exhausted");
+
+        drop(first);
+        let second = tokio::time::timeout(Duration::from_millis(50), budget.acquire(4 * 1024)).await;
+        assert!(second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_memory_permit_grow_to() {
+        let budget = MemoryBudget::new(10 * 1024);
+        let mut permit = budget.acquire(2 * 1024).await;
+        permit.grow_to(5 * 1024);
+        assert_eq!(permit.bytes(), 5 * 1024);
+        assert_eq!(budget.available_bytes(), 5 * 1024);
+
+        // 空きが足りなくても待機せずに超過を許容する
+        let _other = budget.acquire(5 * 1024).await;
+        permit.grow_to(8 * 1024);
+        assert_eq!(permit.bytes(), 5 * 1024);
+    }
+}
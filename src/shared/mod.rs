@@ -1,6 +1,22 @@
+pub mod config;
+pub mod credentials;
+pub mod domain_guard;
 pub mod env;
 pub mod errors;
+pub mod fetch_cache;
+pub mod html_stream;
 pub mod id;
 pub mod logger;
+pub mod memory;
+pub mod progress;
+pub mod proxy;
+pub mod rate_limit;
+pub mod regexes;
+pub mod retry;
+pub mod selectors;
+pub mod sink;
+pub mod ssrf;
+pub mod template;
+pub mod text;
 pub mod utils;
 pub mod webdriver;
@@ -0,0 +1,165 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::collections::HashMap;
+use strum::Display;
+
+/// サイト単位の取得状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum SiteState {
+    Queued,
+    Fetching,
+    Parsing,
+    Done,
+    Failed,
+}
+
+impl SiteState {
+    fn is_finished(&self) -> bool {
+        matches!(self, SiteState::Done | SiteState::Failed)
+    }
+}
+
+/// 全サイト巡回時のライブ進捗表示．
+///
+/// サイトごとに1行のスピナーと，全体の完了数を示すプログレスバーを表示する．
+/// 端末に接続されていない場合（cron や CI からの実行）は indicatif が描画を抑止する．
+#[derive(Debug)]
+pub struct FetchProgress {
+    multi: MultiProgress,
+    overall: ProgressBar,
+    bars: HashMap<String, ProgressBar>,
+}
+
+impl FetchProgress {
+    pub fn new(site_names: &[String]) -> Self {
+        Self::with_draw_target(site_names, ProgressDrawTarget::stderr())
+    }
+
+    /// 描画を行わない進捗表示を作成する（非対話実行やテスト用）
+    pub fn hidden(site_names: &[String]) -> Self {
+        Self::with_draw_target(site_names, ProgressDrawTarget::hidden())
+    }
+
+    fn with_draw_target(site_names: &[String], target: ProgressDrawTarget) -> Self {
+        let multi = MultiProgress::with_draw_target(target);
+        let overall = multi.add(ProgressBar::new(site_names.len() as u64));
+        overall.set_style(
+            ProgressStyle::with_template("[{elapsed_precise}] [{wide_bar}] {pos}/{len} sites ({msg})")
+                .unwrap()
+                .progress_chars("█▓▒░"),
+        );
+        overall.set_message("0 articles");
+
+        let site_style = ProgressStyle::with_template("{spinner:.green} {prefix:<40} {msg}")
+            .unwrap()
+            .tick_chars("⠋⠙⠚⠉ ");
+        let bars = site_names
+            .iter()
+            .map(|name| {
+                let pb = multi.add(ProgressBar::new_spinner());
+                pb.set_style(site_style.clone());
+                pb.set_prefix(name.clone());
+                pb.set_message(SiteState::Queued.to_string());
+                (name.clone(), pb)
+            })
+            .collect();
+
+        Self {
+            multi,
+            overall,
+            bars,
+        }
+    }
+
+    /// サイトの状態を更新する．Done / Failed になったサイトは全体の完了数に加算される．
+    pub fn set_state(&self, site_name: &str, state: SiteState) {
+        let Some(pb) = self.bars.get(site_name) else {
+            return;
+        };
+        if pb.is_finished() {
+            return;
+        }
+        if state.is_finished() {
+            pb.finish_with_message(state.to_string());
+            self.overall.inc(1);
+        } else {
+            pb.enable_steady_tick(std::time::Duration::from_millis(120));
+            pb.set_message(state.to_string());
+        }
+    }
+
+    /// 記事の取得件数・パース件数を表示に反映する
+    pub fn set_counts(&self, site_name: &str, state: SiteState, fetched: usize, parsed: usize) {
+        let Some(pb) = self.bars.get(site_name) else {
+            return;
+        };
+        let msg = format!("{} (fetched: {}, parsed: {})", state, fetched, parsed);
+        if state.is_finished() {
+            if !pb.is_finished() {
+                pb.finish_with_message(msg);
+                self.overall.inc(1);
+            }
+        } else {
+            pb.set_message(msg);
+        }
+    }
+
+    /// 失敗理由を添えてサイトを Failed にする
+    pub fn fail(&self, site_name: &str, reason: &str) {
+        if let Some(pb) = self.bars.get(site_name)
+            && !pb.is_finished()
+        {
+            pb.finish_with_message(format!("{}: {}", SiteState::Failed, reason));
+            self.overall.inc(1);
+        }
+    }
+
+    /// 全体の記事件数を更新する
+    pub fn set_total_articles(&self, total: usize) {
+        self.overall.set_message(format!("{} articles", total));
+    }
+
+    pub fn finished_sites(&self) -> u64 {
+        self.overall.position()
+    }
+
+    /// 進捗表示を終了し，画面から消去する
+    pub fn finish(&self) {
+        self.overall.finish();
+        let _ = self.multi.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_progress_counts_finished_sites() {
+        let names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let progress = FetchProgress::hidden(&names);
+
+        progress.set_state("A", SiteState::Fetching);
+        progress.set_state("A", SiteState::Parsing);
+        assert_eq!(progress.finished_sites(), 0);
+
+        progress.set_counts("A", SiteState::Done, 10, 8);
+        progress.fail("B", "timeout");
+        assert_eq!(progress.finished_sites(), 2);
+
+        // 完了済みのサイトは二重に数えない
+        progress.set_state("A", SiteState::Done);
+        progress.fail("B", "timeout");
+        assert_eq!(progress.finished_sites(), 2);
+
+        // 未登録のサイトは無視する
+        progress.set_state("unknown", SiteState::Done);
+        assert_eq!(progress.finished_sites(), 2);
+    }
+
+    #[test]
+    fn test_site_state_display() {
+        assert_eq!(SiteState::Queued.to_string(), "queued");
+        assert_eq!(SiteState::Failed.to_string(), "failed");
+    }
+}
@@ -0,0 +1,199 @@
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+
+const WINDOW: Duration = Duration::from_secs(60);
+const DEFAULT_MIN_DELAY_MS: u64 = 500;
+const DEFAULT_MAX_PER_MINUTE: u32 = 60;
+
+static GLOBAL_LIMITER: OnceLock<DomainRateLimiter> = OnceLock::new();
+
+/// ドメインごとのリクエスト制限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RatePolicy {
+    /// 同じドメインへの連続したリクエストの最小間隔
+    pub min_delay: Duration,
+    /// 直近1分間のリクエスト数の上限
+    pub max_per_minute: u32,
+}
+
+impl Default for RatePolicy {
+    fn default() -> Self {
+        Self {
+            min_delay: Duration::from_millis(DEFAULT_MIN_DELAY_MS),
+            max_per_minute: DEFAULT_MAX_PER_MINUTE,
+        }
+    }
+}
+
+impl RatePolicy {
+    /// `min_delay_ms/max_per_minute` 形式（例: `1000/30`）をパースする
+    fn parse(value: &str) -> AppResult<Self> {
+        let invalid = || AppError::InternalError(format!("Invalid rate limit: {}", value));
+        let (delay, per_minute) = value.split_once('/').ok_or_else(invalid)?;
+        Ok(Self {
+            min_delay: Duration::from_millis(delay.trim().parse().map_err(|_| invalid())?),
+            max_per_minute: per_minute.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct DomainState {
+    last: Option<Instant>,
+    /// 直近1分間に割り当てたリクエスト時刻
+    recent: VecDeque<Instant>,
+}
+
+/// ドメインごとに最小間隔と1分あたりの上限を守るようリクエストの開始時刻を割り当てる．
+///
+/// 呼び出し時に次の実行可能時刻を予約してから待機するため，同じドメインへの並列リクエストも順番に間隔が空く．
+#[derive(Debug, Default)]
+pub struct DomainRateLimiter {
+    default_policy: RatePolicy,
+    overrides: HashMap<String, RatePolicy>,
+    states: Mutex<HashMap<String, DomainState>>,
+}
+
+impl DomainRateLimiter {
+    pub fn new(default_policy: RatePolicy) -> Self {
+        Self {
+            default_policy,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_override(mut self, domain: &str, policy: RatePolicy) -> Self {
+        self.overrides.insert(domain.to_string(), policy);
+        self
+    }
+
+    /// 環境変数から作成する．
+    ///
+    /// | 環境変数 | デフォルト |
+    /// |---|---|
+    /// | `CLIPPER_RATE_MIN_DELAY_MS` | `500` |
+    /// | `CLIPPER_RATE_MAX_PER_MINUTE` | `60` |
+    /// | `CLIPPER_RATE_LIMITS` | ドメインごとの上書き（例: `www.itmedia.co.jp=1000/30,zenn.dev=2000/20`） |
+    pub fn from_env() -> AppResult<Self> {
+        let mut limiter = Self::new(RatePolicy {
+            min_delay: Duration::from_millis(parse_env_or(
+                "CLIPPER_RATE_MIN_DELAY_MS",
+                DEFAULT_MIN_DELAY_MS,
+            )?),
+            max_per_minute: parse_env_or("CLIPPER_RATE_MAX_PER_MINUTE", DEFAULT_MAX_PER_MINUTE)?,
+        });
+        if let Ok(overrides) = std::env::var("CLIPPER_RATE_LIMITS") {
+            for entry in overrides.split(',').filter(|e| !e.trim().is_empty()) {
+                let (domain, policy) = entry.split_once('=').ok_or_else(|| {
+                    AppError::InternalError(format!("Invalid CLIPPER_RATE_LIMITS entry: {}", entry))
+                })?;
+                limiter = limiter.with_override(domain.trim(), RatePolicy::parse(policy)?);
+            }
+        }
+        Ok(limiter)
+    }
+
+    pub fn policy_for(&self, domain: &str) -> RatePolicy {
+        self.overrides.get(domain).copied().unwrap_or(self.default_policy)
+    }
+
+    /// `domain` へのリクエストを開始してよい時刻まで待機する
+    pub async fn acquire(&self, domain: &str) {
+        let slot = self.reserve(domain, Instant::now());
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// 次にリクエストを開始できる時刻を予約して返す
+    fn reserve(&self, domain: &str, now: Instant) -> Instant {
+        let policy = self.policy_for(domain);
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        let state = states.entry(domain.to_string()).or_default();
+
+        let mut slot = match state.last {
+            Some(last) => now.max(last + policy.min_delay),
+            None => now,
+        };
+        let max = policy.max_per_minute.max(1) as usize;
+        while state.recent.front().is_some_and(|t| *t + WINDOW <= slot) {
+            state.recent.pop_front();
+        }
+        if state.recent.len() >= max {
+            // 上限に達している場合は，窓内で max 件前のリクエストから1分後まで待つ
+            slot = slot.max(state.recent[state.recent.len() - max] + WINDOW);
+            while state.recent.front().is_some_and(|t| *t + WINDOW <= slot) {
+                state.recent.pop_front();
+            }
+        }
+        state.recent.push_back(slot);
+        state.last = Some(slot);
+        slot
+    }
+}
+
+/// プロセス全体で共有するレート制限（環境変数の設定が不正な場合はデフォルト値を使う）
+pub fn global_rate_limiter() -> &'static DomainRateLimiter {
+    GLOBAL_LIMITER.get_or_init(|| {
+        DomainRateLimiter::from_env().unwrap_or_else(|e| {
+            tracing::warn!("{}; using default rate limits", e);
+            DomainRateLimiter::new(RatePolicy::default())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_policy_parse() {
+        let policy = RatePolicy::parse("1000/30").unwrap();
+        assert_eq!(policy.min_delay, Duration::from_secs(1));
+        assert_eq!(policy.max_per_minute, 30);
+        assert!(RatePolicy::parse("1000").is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reserve_enforces_min_delay_per_domain() {
+        let limiter = DomainRateLimiter::new(RatePolicy {
+            min_delay: Duration::from_secs(1),
+            max_per_minute: 100,
+        });
+        let now = Instant::now();
+        assert_eq!(limiter.reserve("a.example.com", now), now);
+        assert_eq!(limiter.reserve("a.example.com", now), now + Duration::from_secs(1));
+        assert_eq!(limiter.reserve("a.example.com", now), now + Duration::from_secs(2));
+        // 別ドメインは独立
+        assert_eq!(limiter.reserve("b.example.com", now), now);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reserve_enforces_max_per_minute() {
+        let limiter = DomainRateLimiter::new(RatePolicy::default()).with_override(
+            "zenn.dev",
+            RatePolicy {
+                min_delay: Duration::ZERO,
+                max_per_minute: 2,
+            },
+        );
+        let now = Instant::now();
+        assert_eq!(limiter.reserve("zenn.dev", now), now);
+        assert_eq!(limiter.reserve("zenn.dev", now), now);
+        assert_eq!(limiter.reserve("zenn.dev", now), now + WINDOW);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_waits() {
+        let limiter = DomainRateLimiter::new(RatePolicy {
+            min_delay: Duration::from_secs(5),
+            max_per_minute: 100,
+        });
+        let start = Instant::now();
+        limiter.acquire("example.com").await;
+        limiter.acquire("example.com").await;
+        assert!(start.elapsed() >= Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,174 @@
+//! ホットパスで使う正規表現．呼び出しごとにコンパイルしないよう，全てプロセス内で1回だけコンパイルする．
+//!
+//! `cargo bench --bench regexes` で呼び出しごとにコンパイルする場合との差を計測できる．
+
+use regex::Regex;
+
+/// 初回呼び出し時に1回だけコンパイルした `&'static Regex` を返す関数を定義する
+macro_rules! static_regex {
+    ($(#[$meta:meta])* $name:ident, $pattern:literal) => {
+        $(#[$meta])*
+        pub fn $name() -> &'static Regex {
+            static REGEX: ::std::sync::OnceLock<Regex> = ::std::sync::OnceLock::new();
+            REGEX.get_or_init(|| Regex::new($pattern).expect(concat!("invalid regex: ", $pattern)))
+        }
+    };
+}
+
+static_regex!(
+    /// `<![CDATA[...]]>` で囲まれたテキスト（`text` に中身）
+    cdata,
+    r"<!\[CDATA\[(?<text>.+?)\]\]>"
+);
+static_regex!(
+    /// 2文字以上連続する空白
+    repeated_whitespace,
+    r"\s\s+"
+);
+static_regex!(
+    /// 3行以上連続する空行
+    blank_lines,
+    r"\n\s*\n\s*\n"
+);
+static_regex!(
+    /// Business Insider の「5h ago」形式の相対時刻
+    hours_ago,
+    r"[\d]{1,2}h ago"
+);
+static_regex!(
+    /// 「会場: ○○」「Venue: ○○」「開催地：○○」形式の開催地
+    venue,
+    r"(?i)(?:venue|location|会場|開催地|開催場所)\s*[:：]\s*([^\n。．]+)"
+);
+static_regex!(
+    /// 「2025年3月14日」「3月14日」形式の日付
+    ja_date,
+    r"(?:(\d{4})\s*年\s*)?(\d{1,2})\s*月\s*(\d{1,2})\s*日"
+);
+static_regex!(
+    /// 「2025-03-14」「2025/3/14」形式の日付
+    iso_date,
+    r"\b(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})\b"
+);
+static_regex!(
+    /// 「March 14th, 2025」形式の日付
+    en_date,
+    r"(?i)\b(january|february|march|april|may|june|july|august|september|october|november|december)\s+(\d{1,2})(?:st|nd|rd|th)?,?\s+(\d{4})\b"
+);
+static_regex!(
+    /// 「© 2025 Example Inc.」「Copyright (c) Example」形式の著作権表示
+    copyright_line,
+    r"(?i)(?:©|\(c\)|copyright\b)[^\n]{2,160}"
+);
+static_regex!(
+    /// `<img>` タグ全体
+    img_tag,
+    r"(?i)<img\b[^>]*>"
+);
+static_regex!(
+    /// タグ内の `src` 属性（`value` に引用符を含む値）
+    src_attribute,
+    r#"(?i)\ssrc\s*=\s*(?<value>"[^"]*"|'[^']*'|[^\s>]+)"#
+);
+static_regex!(
+    /// タグ内の `srcset` / `data-srcset` 属性（前の空白を含む）
+    srcset_attribute,
+    r#"(?i)\s(?:data-)?srcset\s*=\s*(?:"[^"]*"|'[^']*'|[^\s>]+)"#
+);
+static_regex!(
+    /// CVE 番号（`CVE-2025-12345`）
+    cve_id,
+    r"(?i)\bCVE-\d{4}-\d{4,}\b"
+);
+static_regex!(
+    /// RSS の `<item>` 要素
+    rss_item,
+    r"(?s)<item\b.*?</item>"
+);
+static_regex!(
+    /// `<item>` の `<link>`（`url` に中身）
+    rss_link,
+    r"(?s)<link>\s*(?:<!\[CDATA\[)?\s*(?<url>[^<\]]+?)\s*(?:\]\]>)?\s*</link>"
+);
+static_regex!(
+    /// note の RSS の `<note:creatorName>`（`name` に中身）
+    note_creator_name,
+    r"(?s)<note:creatorName>\s*(?:<!\[CDATA\[)?(?<name>.*?)(?:\]\]>)?\s*</note:creatorName>"
+);
+static_regex!(
+    /// `<item>` の `<category>`（`name` に中身）
+    rss_category,
+    r"(?s)<category\b[^>]*>\s*(?:<!\[CDATA\[)?(?<name>.*?)(?:\]\]>)?\s*</category>"
+);
+static_regex!(
+    /// サイトマップの `<url>` 要素
+    sitemap_url,
+    r"(?s)<url\b.*?</url>"
+);
+static_regex!(
+    /// サイトマップインデックスの `<sitemap>` 要素
+    sitemap_child,
+    r"(?s)<sitemap\b.*?</sitemap>"
+);
+static_regex!(
+    /// `<url>` / `<sitemap>` の `<loc>`（`url` に中身）
+    sitemap_loc,
+    r"(?s)<loc>\s*(?:<!\[CDATA\[)?\s*(?<url>[^<\]]+?)\s*(?:\]\]>)?\s*</loc>"
+);
+static_regex!(
+    /// `<url>` / `<sitemap>` の `<lastmod>`（`date` に中身）
+    sitemap_lastmod,
+    r"<lastmod>\s*(?<date>[^<]+?)\s*</lastmod>"
+);
+static_regex!(
+    /// ニュースサイトマップの `<news:title>`（`title` に中身）
+    news_title,
+    r"(?s)<news:title>\s*(?:<!\[CDATA\[)?(?<title>.*?)(?:\]\]>)?\s*</news:title>"
+);
+static_regex!(
+    /// ニュースサイトマップの `<news:publication_date>`（`date` に中身）
+    news_publication_date,
+    r"<news:publication_date>\s*(?<date>[^<]+?)\s*</news:publication_date>"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_regex_returns_same_instance() {
+        assert!(std::ptr::eq(cdata(), cdata()));
+        assert!(std::ptr::eq(en_date(), en_date()));
+    }
+
+    #[test]
+    fn test_all_regexes_compile() {
+        for re in [
+            cdata(),
+            repeated_whitespace(),
+            blank_lines(),
+            hours_ago(),
+            venue(),
+            ja_date(),
+            iso_date(),
+            en_date(),
+            copyright_line(),
+            cve_id(),
+            img_tag(),
+            src_attribute(),
+            srcset_attribute(),
+            rss_item(),
+            rss_link(),
+            note_creator_name(),
+            rss_category(),
+            sitemap_url(),
+            sitemap_child(),
+            sitemap_loc(),
+            sitemap_lastmod(),
+            news_title(),
+            news_publication_date(),
+        ] {
+            assert!(!re.as_str().is_empty());
+        }
+    }
+}
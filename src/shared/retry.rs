@@ -0,0 +1,175 @@
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::{AppError, AppResult};
+use request::{Response, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::{Level, event};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 500;
+const DEFAULT_MAX_DELAY_MS: u64 = 10_000;
+
+static GLOBAL_POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+/// 一時的な失敗（タイムアウト，接続エラー，429，5xx）に対する再試行の設定
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// 初回を含む最大試行回数
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// 待機時間に加える揺らぎの割合（0.0〜1.0）
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 環境変数から作成する（`CLIPPER_RETRY_MAX_ATTEMPTS`，`CLIPPER_RETRY_BASE_DELAY_MS`，`CLIPPER_RETRY_MAX_DELAY_MS`）
+    pub fn from_env() -> AppResult<Self> {
+        Ok(Self {
+            max_attempts: parse_env_or("CLIPPER_RETRY_MAX_ATTEMPTS", DEFAULT_MAX_ATTEMPTS)?.max(1),
+            base_delay: Duration::from_millis(parse_env_or(
+                "CLIPPER_RETRY_BASE_DELAY_MS",
+                DEFAULT_BASE_DELAY_MS,
+            )?),
+            max_delay: Duration::from_millis(parse_env_or(
+                "CLIPPER_RETRY_MAX_DELAY_MS",
+                DEFAULT_MAX_DELAY_MS,
+            )?),
+            ..Default::default()
+        })
+    }
+
+    /// `attempt` 回目（1始まり）の失敗後に待機する時間（指数バックオフ + ジッター）
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let jitter = exp.mul_f64(self.jitter.clamp(0.0, 1.0) * random_unit());
+        (exp + jitter).min(self.max_delay)
+    }
+
+    /// 一時的な失敗であれば再試行しながら `send` を実行する．
+    /// 最終的に失敗した場合は試行回数を含む `AppError::RequestError` を返す．
+    pub async fn run<F, Fut>(&self, url: &str, mut send: F) -> AppResult<Response>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Response, request::Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let (error, retry_after) = match send().await {
+                Ok(response) if is_retryable_status(response.status()) => {
+                    let retry_after = parse_retry_after(&response);
+                    match response.error_for_status() {
+                        Ok(response) => return Ok(response),
+                        Err(e) => (e, retry_after),
+                    }
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if is_retryable_error(&e) => (e, None),
+                Err(e) => return Err(AppError::RequestError { source: e, attempts: attempt }),
+            };
+            if attempt >= self.max_attempts {
+                return Err(AppError::RequestError { source: error, attempts: attempt });
+            }
+            let wait = retry_after.unwrap_or_else(|| self.backoff(attempt)).min(self.max_delay);
+            event!(
+                Level::WARN,
+                "Request to {} failed (attempt {}/{}): {}; retrying in {:?}",
+                url,
+                attempt,
+                self.max_attempts,
+                error,
+                wait
+            );
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+}
+
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn is_retryable_error(error: &request::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+/// `Retry-After` ヘッダ（秒数指定のみ）を読む
+fn parse_retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(request::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// 0.0〜1.0 の乱数（ジッター用．暗号学的な品質は不要）
+fn random_unit() -> f64 {
+    let value = RandomState::new().build_hasher().finish();
+    (value >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// プロセス全体で共有する再試行の設定
+pub fn global_retry_policy() -> &'static RetryPolicy {
+    GLOBAL_POLICY.get_or_init(|| {
+        RetryPolicy::from_env().unwrap_or_else(|e| {
+            tracing::warn!("{}; using default retry policy", e);
+            RetryPolicy::default()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_exponentially_and_is_capped() {
+        let policy = RetryPolicy {
+            jitter: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(3), Duration::from_millis(2000));
+        assert_eq!(policy.backoff(20), Duration::from_millis(10_000));
+    }
+
+    #[test]
+    fn test_backoff_jitter_stays_in_range() {
+        let policy = RetryPolicy::default();
+        for _ in 0..100 {
+            let wait = policy.backoff(2);
+            assert!(wait >= Duration::from_millis(1000) && wait <= Duration::from_millis(1500));
+        }
+    }
+
+    #[test]
+    fn test_is_retryable_status() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::OK));
+    }
+}
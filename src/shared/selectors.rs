@@ -0,0 +1,80 @@
+use crate::shared::errors::{AppError, AppResult};
+use scraper::Selector;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+static SELECTOR_CACHE: OnceLock<RwLock<HashMap<String, &'static Selector>>> = OnceLock::new();
+
+/// CSS セレクタ文字列リテラル（またはセレクタ文字列の定数）を呼び出し箇所ごとに1回だけパースし，
+/// `&'static Selector` を返す．
+///
+/// ```ignore
+/// for li in doc.select(selector!("ul.list li")) { ... }
+/// const CONTENT: &str = "article div.body";
+/// let content = doc.select(selector!(CONTENT)).next();
+/// ```
+macro_rules! selector {
+    ($css:literal) => {{
+        static SELECTOR: ::std::sync::OnceLock<::scraper::Selector> = ::std::sync::OnceLock::new();
+        SELECTOR.get_or_init(|| {
+            ::scraper::Selector::parse($css).expect(concat!("invalid CSS selector: ", $css))
+        })
+    }};
+    ($css:ident) => {{
+        static SELECTOR: ::std::sync::OnceLock<::scraper::Selector> = ::std::sync::OnceLock::new();
+        SELECTOR.get_or_init(|| {
+            ::scraper::Selector::parse($css).unwrap_or_else(|_| panic!("invalid CSS selector: {}", $css))
+        })
+    }};
+}
+pub(crate) use selector;
+
+/// 実行時に決まるセレクタ文字列（除外セレクタ一覧，設定ファイル由来など）をパースしてキャッシュする．
+/// 同じ文字列は2回目以降パースされない．
+pub fn cached_selector(css: &str) -> AppResult<&'static Selector> {
+    let cache = SELECTOR_CACHE.get_or_init(|| RwLock::new(HashMap::new()));
+    if let Some(selector) = cache.read().unwrap_or_else(|e| e.into_inner()).get(css) {
+        return Ok(selector);
+    }
+
+    let parsed = Selector::parse(css)
+        .map_err(|e| AppError::ScrapeError(format!("Failed to parse selector ({}): {}", css, e)))?;
+    let mut map = cache.write().unwrap_or_else(|e| e.into_inner());
+    // 異なる文字列ごとに1回だけリークする（セレクタの種類は有限）
+    let selector = *map
+        .entry(css.to_string())
+        .or_insert_with(|| Box::leak(Box::new(parsed)));
+    Ok(selector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector_macro_returns_same_instance() {
+        fn get() -> &'static Selector {
+            selector!("div.content p")
+        }
+        assert!(std::ptr::eq(get(), get()));
+
+        const CONTENT: &str = "div.content p";
+        fn get_const() -> &'static Selector {
+            selector!(CONTENT)
+        }
+        assert!(std::ptr::eq(get_const(), get_const()));
+        assert_eq!(get_const(), get());
+    }
+
+    #[test]
+    fn test_cached_selector_reuses_parsed_selector() {
+        let a = cached_selector("article .body").unwrap();
+        let b = cached_selector("article .body").unwrap();
+        assert!(std::ptr::eq(a, b));
+    }
+
+    #[test]
+    fn test_cached_selector_invalid() {
+        assert!(cached_selector("div[").is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! 生成したレポートの配信先．
+
+use crate::shared::config::data_dir;
+use crate::shared::errors::{AppError, AppResult};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// レポートの配信先（`CLIPPER_REPORT_SINK`）
+///
+/// | 値 | 配信先 |
+/// |---|---|
+/// | `file`（デフォルト） | `$CLIPPER_DATA_DIR/reports/<name>.md` |
+/// | `file:<dir>` | `<dir>/<name>.md` |
+/// | `stdout` | 標準出力 |
+/// | `http(s)://...` | Webhook に `{"text": "..."}` を POST（Slack 互換） |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportSink {
+    Stdout,
+    Directory(PathBuf),
+    Webhook(String),
+}
+
+impl FromStr for ReportSink {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "stdout" => Ok(Self::Stdout),
+            "file" => Ok(Self::Directory(data_dir().join("reports"))),
+            _ if s.starts_with("file:") => Ok(Self::Directory(PathBuf::from(&s["file:".len()..]))),
+            _ if s.starts_with("http://") || s.starts_with("https://") => {
+                Ok(Self::Webhook(request::Url::parse(s)?.to_string()))
+            }
+            _ => Err(AppError::InternalError(format!("Invalid report sink: {}", s))),
+        }
+    }
+}
+
+impl ReportSink {
+    pub fn from_env() -> AppResult<Self> {
+        match std::env::var("CLIPPER_REPORT_SINK") {
+            Ok(value) if !value.trim().is_empty() => value.parse(),
+            _ => "file".parse(),
+        }
+    }
+
+    /// `name` はファイル名（拡張子なし）として使う
+    pub async fn deliver(&self, name: &str, content: &str) -> AppResult<()> {
+        match self {
+            Self::Stdout => {
+                println!("{}", content);
+                Ok(())
+            }
+            Self::Directory(dir) => {
+                let path = dir.join(format!("{}.md", name));
+                std::fs::create_dir_all(dir)
+                    .and_then(|_| std::fs::write(&path, content))
+                    .map_err(|e| AppError::InternalError(format!("Failed to write report {:?}: {}", path, e)))
+            }
+            Self::Webhook(url) => {
+                let response = request::Client::new()
+                    .post(url)
+                    .json(&serde_json::json!({ "text": content }))
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    return Err(AppError::InternalError(format!(
+                        "Report webhook returned {}",
+                        response.status()
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_sink_from_str() {
+        assert_eq!("stdout".parse::<ReportSink>().unwrap(), ReportSink::Stdout);
+        assert_eq!(
+            "file:/tmp/reports".parse::<ReportSink>().unwrap(),
+            ReportSink::Directory(PathBuf::from("/tmp/reports"))
+        );
+        assert!(matches!(
+            "https://hooks.example.com/T000".parse::<ReportSink>().unwrap(),
+            ReportSink::Webhook(_)
+        ));
+        assert!("ftp://example.com".parse::<ReportSink>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_report_sink_writes_file() {
+        let dir = std::env::temp_dir().join(format!("clipper-reports-{}", uuid::Uuid::new_v4()));
+        ReportSink::Directory(dir.clone()).deliver("weekly", "# Report").await.unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("weekly.md")).unwrap(), "# Report");
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
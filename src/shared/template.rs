@@ -0,0 +1,40 @@
+//! minijinja によるテンプレートの描画．
+//!
+//! 組み込みのテンプレートは環境変数で指定したファイルで差し替えられる．
+
+use crate::shared::errors::{AppError, AppResult};
+use minijinja::Environment;
+use serde::Serialize;
+
+/// `source` を `context` で描画する
+pub fn render_template<S: Serialize>(source: &str, context: S) -> AppResult<String> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    Ok(env.render_str(source, context)?)
+}
+
+/// `env_key` にファイルパスが設定されていればその内容を，未設定なら `default` を返す
+pub fn load_template(env_key: &str, default: &str) -> AppResult<String> {
+    match std::env::var(env_key) {
+        Ok(path) if !path.trim().is_empty() => std::fs::read_to_string(path.trim()).map_err(|e| {
+            AppError::InternalError(format!("Failed to read template {} ({}): {}", path, env_key, e))
+        }),
+        _ => Ok(default.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let rendered = render_template(
+            "{% for site in sites %}- {{ site }}\n{% endfor %}",
+            serde_json::json!({ "sites": ["Gigazine", "ITmedia"] }),
+        )
+        .unwrap();
+        assert_eq!(rendered, "- Gigazine\n- ITmedia\n");
+        assert!(render_template("{% for %}", ()).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! 抽出した本文の空白と改行を整える．
+//!
+//! コードブロック（` ``` ` / `~~~` / `<pre>`）と Markdown の表の行は書式を保つためそのまま残し，
+//! それ以外の行では全角スペースを含む連続した空白を1つにまとめ，空行の連続を1行にする．
+
+use crate::shared::config::parse_env_or;
+use crate::shared::errors::AppResult;
+use std::sync::OnceLock;
+
+/// 折り返し幅（表示幅，全角は2）を指定する環境変数．`0` または未設定の場合は折り返さない．
+pub const TEXT_WIDTH_ENV: &str = "CLIPPER_TEXT_WIDTH";
+
+const FULL_WIDTH_SPACE: char = '\u{3000}';
+
+static GLOBAL_NORMALIZER: OnceLock<TextNormalizer> = OnceLock::new();
+
+/// 本文の正規化の設定
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextNormalizer {
+    /// 1行の最大表示幅．`None` の場合は折り返さない．
+    pub line_width: Option<usize>,
+}
+
+impl TextNormalizer {
+    pub fn with_line_width(line_width: usize) -> Self {
+        Self {
+            line_width: (line_width > 0).then_some(line_width),
+        }
+    }
+
+    /// `CLIPPER_TEXT_WIDTH` から作成する
+    pub fn from_env() -> AppResult<Self> {
+        Ok(Self::with_line_width(parse_env_or(TEXT_WIDTH_ENV, 0)?))
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        let mut out: Vec<String> = Vec::new();
+        let mut pending_blank = false;
+        let mut fence: Option<&str> = None;
+        let mut in_pre = false;
+
+        for line in text.lines() {
+            let trimmed = line.trim();
+            // 整形済みの領域の中の行はそのまま残す
+            if let Some(marker) = fence {
+                out.push(line.to_string());
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+                continue;
+            }
+            if in_pre {
+                out.push(line.to_string());
+                in_pre = pre_state_after(line, true);
+                continue;
+            }
+
+            if trimmed.is_empty() {
+                pending_blank = !out.is_empty();
+                continue;
+            }
+            if pending_blank {
+                out.push(String::new());
+                pending_blank = false;
+            }
+
+            if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+                fence = Some(marker);
+                out.push(line.trim_end().to_string());
+            } else if contains_pre(line) {
+                in_pre = pre_state_after(line, false);
+                out.push(line.trim_end().to_string());
+            } else if trimmed.starts_with('|') {
+                // Markdown の表の行（列の揃えを崩さない）
+                out.push(trimmed.to_string());
+            } else {
+                let collapsed = collapse_spaces(trimmed);
+                match self.line_width {
+                    Some(width) => out.extend(wrap(&collapsed, width)),
+                    None => out.push(collapsed),
+                }
+            }
+        }
+        out.join("\n")
+    }
+}
+
+/// プロセス全体で共有する正規化の設定
+pub fn global_text_normalizer() -> &'static TextNormalizer {
+    GLOBAL_NORMALIZER.get_or_init(|| {
+        TextNormalizer::from_env().unwrap_or_else(|e| {
+            tracing::warn!("{}; text will not be wrapped", e);
+            TextNormalizer::default()
+        })
+    })
+}
+
+/// 行に `<pre>` の開始タグが含まれるか
+fn contains_pre(line: &str) -> bool {
+    pre_tags(line).any(|opens| opens)
+}
+
+/// 行の終わりの時点で `<pre>` の中にいるか（`in_pre` は行の始めの状態）
+fn pre_state_after(line: &str, in_pre: bool) -> bool {
+    pre_tags(line).fold(in_pre, |_, opens| opens)
+}
+
+/// 行に含まれる `<pre>`（`true`）と `</pre>`（`false`）を出現順に返す
+fn pre_tags(line: &str) -> impl Iterator<Item = bool> + '_ {
+    let lower = line.to_ascii_lowercase();
+    let mut tags = Vec::new();
+    let mut rest = lower.as_str();
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let (closing, name) = match rest.strip_prefix('/') {
+            Some(name) => (true, name),
+            None => (false, rest),
+        };
+        if let Some(after) = name.strip_prefix("pre")
+            && after.starts_with(|c: char| c == '>' || c.is_ascii_whitespace())
+        {
+            tags.push(!closing);
+        }
+    }
+    tags.into_iter()
+}
+
+/// 連続する空白を半角スペース1つにする．単独の全角スペースは語の区切りとして残す．
+fn collapse_spaces(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut run: Vec<char> = Vec::new();
+    let flush = |run: &mut Vec<char>, out: &mut String| {
+        match run.as_slice() {
+            [] => {}
+            [FULL_WIDTH_SPACE] => out.push(FULL_WIDTH_SPACE),
+            _ => out.push(' '),
+        }
+        run.clear();
+    };
+    for c in line.chars() {
+        if c.is_whitespace() {
+            run.push(c);
+        } else {
+            flush(&mut run, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut run, &mut out);
+    out
+}
+
+/// 全角文字（CJK，全角英数・記号，ハングル）の表示幅を2として返す
+pub fn char_width(c: char) -> usize {
+    match c {
+        '\u{1100}'..='\u{115F}'
+        | '\u{2E80}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}' => 2,
+        _ => 1,
+    }
+}
+
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// 表示幅 `width` で折り返す．半角の語は空白の位置で，全角文字は任意の文字の間で改行する．
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+    for token in tokens(line) {
+        let token_width = display_width(token);
+        if current_width + token_width > width && !current.trim().is_empty() {
+            lines.push(current.trim_end().to_string());
+            current.clear();
+            current_width = 0;
+        }
+        if current.is_empty() && token.trim().is_empty() {
+            continue;
+        }
+        if token_width > width {
+            // 1語が幅を超える場合は文字単位で分割する
+            for c in token.chars() {
+                if current_width + char_width(c) > width && !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                    current_width = 0;
+                }
+                current.push(c);
+                current_width += char_width(c);
+            }
+        } else {
+            current.push_str(token);
+            current_width += token_width;
+        }
+    }
+    if !current.trim().is_empty() {
+        lines.push(current.trim_end().to_string());
+    }
+    lines
+}
+
+/// 省略を示す記号
+pub const ELLIPSIS: char = '…';
+/// 英語などの語の途中で切れる場合に，語の先頭まで戻してよい範囲（`max_chars` に対する割合）
+const WORD_BOUNDARY_BACKOFF: f64 = 0.3;
+/// 省略記号の直前に残さない読点・区切り
+const TRAILING_PUNCTUATION: &[char] = &['、', '，', ',', '・', ':', ';', '：', '；', '(', '（', '「', '『'];
+
+/// 概要・要約を `max_chars` 文字（省略記号を含む）以内に切り詰める．収まる場合はそのまま返す．
+///
+/// 文字単位で切るためマルチバイト文字が壊れることはなく，結合文字（濁点・異体字セレクタなど）は直前の文字と一緒に扱う．
+/// 日本語などの全角文字は任意の位置で，英語などの半角の語は語の途中で切れないよう直前の空白の位置で切る
+/// （1語が長すぎる場合は語の途中で切る）．末尾の空白・読点を取り除いてから「…」を付ける．
+pub fn truncate_text(text: &str, max_chars: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= max_chars {
+        return text.to_string();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+    let mut end = max_chars - 1;
+    // 結合文字を直前の文字から切り離さない
+    while end > 0 && is_combining(chars[end]) {
+        end -= 1;
+    }
+    if end > 0 && is_spaced_word_char(chars[end - 1]) && is_spaced_word_char(chars[end]) {
+        let min_end = end - ((max_chars as f64 * WORD_BOUNDARY_BACKOFF) as usize).min(end);
+        if let Some(space) = (min_end..end).rev().find(|&i| chars[i].is_whitespace()) {
+            end = space;
+        }
+    }
+    let mut out: String = chars[..end].iter().collect();
+    let trimmed = out.trim_end_matches(|c: char| c.is_whitespace() || TRAILING_PUNCTUATION.contains(&c));
+    out.truncate(trimmed.len());
+    out.push(ELLIPSIS);
+    out
+}
+
+/// 空白で区切って書く語を構成する文字（半角の英数字）
+fn is_spaced_word_char(c: char) -> bool {
+    c.is_alphanumeric() && char_width(c) == 1
+}
+
+/// 直前の文字と組み合わせて表示する文字
+fn is_combining(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{200D}'
+            | '\u{3099}'..='\u{309A}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{E0100}'..='\u{E01EF}'
+    )
+}
+
+/// 折り返しの単位に分割する（半角の語，空白，全角文字1つ）
+fn tokens(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = i + c.len_utf8();
+        let is_word_char = !c.is_whitespace() && char_width(c) == 1;
+        let continues = chars
+            .peek()
+            .is_some_and(|(_, next)| is_word_char && !next.is_whitespace() && char_width(*next) == 1);
+        if !continues {
+            tokens.push(&line[start..end]);
+            start = end;
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_collapses_spaces_and_blank_lines() {
+        let text = "  タイトル   です \n\n\n\n本文\u{3000}\u{3000}は\tここ\u{3000}から．  \n\n";
+        assert_eq!(
+            TextNormalizer::default().normalize(text),
+            "タイトル です\n\n本文 は ここ\u{3000}から．"
+        );
+    }
+
+    #[test]
+    fn test_normalize_preserves_code_blocks_and_tables() {
+        let text = "説明\n\n```rust\nfn main() {\n    println!(\"a  b\");\n\n\n}\n```\n| col  | value |\n|------|-------|\n|  a   |   1   |";
+        assert_eq!(TextNormalizer::default().normalize(text), text);
+
+        let html = "<p>before   text</p>\n<pre class=\"code\">let  x = 1;\n\n\n    y</pre>\n<p>after    text</p>";
+        assert_eq!(
+            TextNormalizer::default().normalize(html),
+            "<p>before text</p>\n<pre class=\"code\">let  x = 1;\n\n\n    y</pre>\n<p>after text</p>"
+        );
+        assert!(!contains_pre("<preview>"));
+    }
+
+    #[test]
+    fn test_normalize_wraps_to_line_width() {
+        let normalizer = TextNormalizer::with_line_width(10);
+        assert_eq!(normalizer.normalize("the quick brown fox jumps"), "the quick\nbrown fox\njumps");
+        assert_eq!(normalizer.normalize("日本語の文章を折り返す"), "日本語の文\n章を折り返\nす");
+        assert_eq!(normalizer.normalize("abcdefghijklmn"), "abcdefghij\nklmn");
+        assert_eq!(TextNormalizer::with_line_width(0), TextNormalizer::default());
+    }
+
+    #[test]
+    fn test_truncate_text_japanese() {
+        assert_eq!(truncate_text("日本語の文章を切り詰める", 6), "日本語の文…");
+        assert_eq!(truncate_text("今日は、晴れです", 5), "今日は…");
+        assert_eq!(truncate_text("短い", 6), "短い");
+        // 濁点の結合文字を基底の文字から切り離さない
+        assert_eq!(truncate_text("あか\u{3099}い", 3), "あ…");
+        assert_eq!(truncate_text("本文", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_text_english_word_boundaries() {
+        assert_eq!(truncate_text("The quick brown fox jumps", 12), "The quick…");
+        assert_eq!(truncate_text("Hello, wonderful world", 10), "Hello…");
+        assert_eq!(truncate_text("supercalifragilistic", 8), "superca…");
+        assert_eq!(truncate_text("abcdef", 4), "abc…");
+        assert!(truncate_text(&"word ".repeat(50), 40).chars().count() <= 40);
+    }
+}
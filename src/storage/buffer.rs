@@ -0,0 +1,145 @@
+//! ストアに保存できなかった記事の一時的な保持．
+//!
+//! データベースに接続できない間に取得した記事はメモリに保持し，次の巡回で新しい記事より先に保存し直す．
+//! 同じ URL の記事は新しく取得したもので置き換え，上限を超えた場合は古いものから捨てる．
+//! プロセスを再起動すると保持していた記事は失われる．
+
+use crate::models::web_article::WebArticle;
+use crate::shared::errors::AppResult;
+use crate::storage::ArticleStore;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 保持する記事数の上限
+const MAX_BUFFERED_ARTICLES: usize = 5000;
+/// 保存に失敗したときに，メモリに保持する前に保存し直す回数と間隔
+const WRITE_RETRIES: u32 = 2;
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Default)]
+pub struct WriteBuffer {
+    articles: Mutex<Vec<WebArticle>>,
+}
+
+impl WriteBuffer {
+    pub fn len(&self) -> usize {
+        self.articles.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 記事を保持する（同じ URL の記事は置き換える）
+    pub fn push(&self, articles: &[WebArticle]) {
+        let mut buffered = self.articles.lock().unwrap_or_else(|e| e.into_inner());
+        buffered.retain(|a| !articles.iter().any(|b| b.article_url == a.article_url));
+        buffered.extend(articles.iter().cloned());
+        if buffered.len() > MAX_BUFFERED_ARTICLES {
+            let dropped = buffered.len() - MAX_BUFFERED_ARTICLES;
+            tracing::warn!("Write buffer is full; dropping {} oldest articles", dropped);
+            buffered.drain(..dropped);
+        }
+    }
+
+    fn take(&self) -> Vec<WebArticle> {
+        std::mem::take(&mut *self.articles.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// 保持していた記事と `articles` をまとめて保存する．失敗した場合は何度か保存し直し，
+    /// それでも失敗した場合は全てを保持してエラーを返す（保持していた記事も失わない）．
+    pub async fn write(&self, store: &dyn ArticleStore, articles: &[WebArticle]) -> AppResult<usize> {
+        let mut batch = self.take();
+        batch.retain(|a| !articles.iter().any(|b| b.article_url == a.article_url));
+        let buffered = batch.len();
+        batch.extend(articles.iter().cloned());
+        let mut attempt = 0;
+        loop {
+            match store.upsert_many(&batch).await {
+                Ok(saved) => {
+                    if buffered > 0 {
+                        tracing::info!("Saved {} articles buffered while the store was unavailable", buffered);
+                    }
+                    return Ok(saved);
+                }
+                Err(e) if attempt < WRITE_RETRIES => {
+                    attempt += 1;
+                    tracing::warn!("Failed to save articles (attempt {}): {}", attempt, e);
+                    tokio::time::sleep(RETRY_INTERVAL * attempt).await;
+                }
+                Err(e) => {
+                    self.push(&batch);
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::triage::Triage;
+    use crate::models::web_article::Status;
+    use crate::shared::errors::AppError;
+    use crate::shared::id::WebArticleId;
+    use crate::storage::sqlite::SqliteStore;
+    use crate::storage::{ArticleQuery, StoredArticle};
+    use crate::testing::article::ArticleBuilder;
+
+    fn article(path: &str) -> WebArticle {
+        ArticleBuilder::new(path).with_url(format!("https://example.com/{}", path)).build()
+    }
+
+    /// 接続できないストアの代わり
+    struct UnavailableStore;
+
+    fn unavailable() -> AppError {
+        AppError::InternalError("database is locked".into())
+    }
+
+    #[async_trait::async_trait]
+    impl ArticleStore for UnavailableStore {
+        async fn upsert(&self, _article: &WebArticle) -> AppResult<WebArticleId> {
+            Err(unavailable())
+        }
+        async fn get(&self, _id: WebArticleId) -> AppResult<Option<StoredArticle>> {
+            Err(unavailable())
+        }
+        async fn get_by_url(&self, _article_url: &str) -> AppResult<Option<StoredArticle>> {
+            Err(unavailable())
+        }
+        async fn query(&self, _query: &ArticleQuery) -> AppResult<Vec<StoredArticle>> {
+            Err(unavailable())
+        }
+        async fn set_status(&self, _article_url: &str, _status: Status) -> AppResult<bool> {
+            Err(unavailable())
+        }
+        async fn set_triage(&self, _article_url: &str, _triage: &Triage) -> AppResult<bool> {
+            Err(unavailable())
+        }
+        async fn soft_delete(&self, _article_urls: &[String]) -> AppResult<usize> {
+            Err(unavailable())
+        }
+        async fn deleted_articles(&self) -> AppResult<Vec<StoredArticle>> {
+            Err(unavailable())
+        }
+        async fn purge_deleted(&self) -> AppResult<Vec<String>> {
+            Err(unavailable())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_write_buffer_retries_on_next_write() {
+        let buffer = WriteBuffer::default();
+        assert!(buffer.write(&UnavailableStore, &[article("a"), article("b")]).await.is_err());
+        assert_eq!(buffer.len(), 2);
+        assert!(buffer.write(&UnavailableStore, &[article("b"), article("c")]).await.is_err());
+        assert_eq!(buffer.len(), 3);
+
+        let store = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(buffer.write(&store, &[article("d")]).await.unwrap(), 4);
+        assert!(buffer.is_empty());
+        assert_eq!(store.query(&ArticleQuery::default()).await.unwrap().len(), 4);
+    }
+}
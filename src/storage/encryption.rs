@@ -0,0 +1,185 @@
+//! 保存する記事の本文の暗号化（AES-256-GCM）．
+//!
+//! 有料記事や社内向けの記事を共有ホストに保存する場合に，`CLIPPER_STORAGE_KEY`（または KMS などから
+//! 復号した鍵を置いたファイルを `CLIPPER_STORAGE_KEY_FILE`）で 32 バイトの鍵を base64 で指定すると有効になる．
+//! 暗号化した値は `enc:v1:` から始まり，接頭辞の無い値（鍵を設定する前に保存した記事）は平文としてそのまま読む．
+//! 同じ鍵で WARC のレコード，HTTP キャッシュ（`shared::fetch_cache`），few-shot の例
+//! （`enrichment::few_shot`）と認証情報のファイル（`shared::credentials`）も暗号化する．
+
+use crate::shared::errors::{AppError, AppResult};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// 鍵（base64 でエンコードした 32 バイト）を指定する環境変数
+pub const STORAGE_KEY_ENV: &str = "CLIPPER_STORAGE_KEY";
+/// 鍵を保存したファイルのパスを指定する環境変数（`CLIPPER_STORAGE_KEY` が優先）
+pub const STORAGE_KEY_FILE_ENV: &str = "CLIPPER_STORAGE_KEY_FILE";
+
+/// 暗号化した値の接頭辞（形式を変える場合はバージョンを上げる）
+const PREFIX: &str = "enc:v1:";
+const KEY_BYTES: usize = 32;
+const NONCE_BYTES: usize = 12;
+
+/// 保存する値の暗号化と復号
+#[derive(Clone)]
+pub struct StorageCipher {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for StorageCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageCipher").finish_non_exhaustive()
+    }
+}
+
+impl StorageCipher {
+    pub fn new(key: &[u8]) -> AppResult<Self> {
+        if key.len() != KEY_BYTES {
+            return Err(AppError::EncryptionError(format!(
+                "storage key must be {} bytes, got {}",
+                KEY_BYTES,
+                key.len()
+            )));
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        })
+    }
+
+    /// base64 でエンコードした鍵から作成する
+    pub fn from_base64(encoded: &str) -> AppResult<Self> {
+        let key = STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| AppError::EncryptionError(format!("invalid storage key: {}", e)))?;
+        Self::new(&key)
+    }
+
+    /// `CLIPPER_STORAGE_KEY` / `CLIPPER_STORAGE_KEY_FILE` から作成する．どちらも未設定の場合は暗号化しない（`None`）．
+    pub fn from_env() -> AppResult<Option<Self>> {
+        if let Ok(key) = std::env::var(STORAGE_KEY_ENV)
+            && !key.trim().is_empty()
+        {
+            return Self::from_base64(&key).map(Some);
+        }
+        match std::env::var(STORAGE_KEY_FILE_ENV) {
+            Ok(path) if !path.trim().is_empty() => {
+                let key = std::fs::read_to_string(path.trim()).map_err(|e| {
+                    AppError::EncryptionError(format!("Failed to read {}: {}", path.trim(), e))
+                })?;
+                Self::from_base64(&key).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 値を暗号化する．空文字列は「値が無い」ことを表すため暗号化しない．
+    pub fn encrypt(&self, plaintext: &str) -> AppResult<String> {
+        if plaintext.is_empty() {
+            return Ok(String::new());
+        }
+        self.encrypt_bytes(plaintext.as_bytes())
+    }
+
+    /// UTF-8 とは限らないバイト列（WARC のレコードなど）を `encrypt` と同じ形式で暗号化する
+    pub fn encrypt_bytes(&self, plaintext: &[u8]) -> AppResult<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| AppError::EncryptionError("failed to encrypt".into()))?;
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", PREFIX, STANDARD.encode(payload)))
+    }
+
+    /// `encrypt` した値を復号する．接頭辞の無い値は平文としてそのまま返す．
+    pub fn decrypt(&self, value: &str) -> AppResult<String> {
+        if !is_encrypted(value) {
+            return Ok(value.to_string());
+        }
+        String::from_utf8(self.decrypt_bytes(value)?).map_err(|e| AppError::EncryptionError(e.to_string()))
+    }
+
+    /// `encrypt_bytes` した値を復号する
+    pub fn decrypt_bytes(&self, value: &str) -> AppResult<Vec<u8>> {
+        let encoded = value
+            .strip_prefix(PREFIX)
+            .ok_or_else(|| AppError::EncryptionError("value is not encrypted".into()))?;
+        let payload = STANDARD
+            .decode(encoded)
+            .map_err(|e| AppError::EncryptionError(format!("malformed encrypted value: {}", e)))?;
+        if payload.len() < NONCE_BYTES {
+            return Err(AppError::EncryptionError("malformed encrypted value".into()));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_BYTES);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| AppError::EncryptionError("failed to decrypt (wrong key?)".into()))
+    }
+}
+
+/// 暗号化されているか
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// 鍵があれば暗号化する
+pub fn seal(cipher: Option<&StorageCipher>, value: &str) -> AppResult<String> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(value),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// 鍵があれば復号する．暗号化された値を鍵無しで読もうとした場合はエラーにする．
+pub fn open(cipher: Option<&StorageCipher>, value: String) -> AppResult<String> {
+    match cipher {
+        Some(cipher) => cipher.decrypt(&value),
+        None if is_encrypted(&value) => Err(AppError::EncryptionError(format!(
+            "value is encrypted but {} is not set",
+            STORAGE_KEY_ENV
+        ))),
+        None => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> StorageCipher {
+        StorageCipher::new(&[7u8; KEY_BYTES]).unwrap()
+    }
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let cipher = cipher();
+        let sealed = cipher.encrypt("本文 full text").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.contains("full text"));
+        // 同じ平文でも nonce が異なるため暗号文は一致しない
+        assert_ne!(sealed, cipher.encrypt("本文 full text").unwrap());
+        assert_eq!(cipher.decrypt(&sealed).unwrap(), "本文 full text");
+        assert_eq!(cipher.encrypt("").unwrap(), "");
+        assert_eq!(cipher.decrypt("legacy plaintext").unwrap(), "legacy plaintext");
+
+        let bytes = [0xffu8, 0x00, 0x80, b'a'];
+        let sealed = cipher.encrypt_bytes(&bytes).unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(cipher.decrypt_bytes(&sealed).unwrap(), bytes);
+        assert!(cipher.decrypt_bytes("plain").is_err());
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_key_and_missing_key() {
+        let sealed = cipher().encrypt("secret").unwrap();
+        let other = StorageCipher::new(&[8u8; KEY_BYTES]).unwrap();
+        assert!(matches!(other.decrypt(&sealed), Err(AppError::EncryptionError(_))));
+        assert!(open(None, sealed).is_err());
+        assert_eq!(open(None, "plain".into()).unwrap(), "plain");
+        assert!(StorageCipher::new(b"short").is_err());
+        assert!(StorageCipher::from_base64(&STANDARD.encode([1u8; KEY_BYTES])).is_ok());
+    }
+}
@@ -0,0 +1,121 @@
+//! 記事の永続化．
+
+pub mod buffer;
+pub mod encryption;
+pub mod removal;
+pub mod sqlite;
+
+use crate::alerts::triage::{Triage, TriageState};
+use crate::models::annotation::{Annotation, Annotations, Star, Visibility};
+use crate::models::report::FetchReport;
+use crate::models::web_article::{Status, WebArticle};
+use crate::shared::errors::AppResult;
+use crate::shared::id::{AnnotationId, WebArticleId};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// 保存済みの記事
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredArticle {
+    pub id: WebArticleId,
+    pub status: Status,
+    #[serde(flatten)]
+    pub article: WebArticle,
+    /// セキュリティ関連の記事の担当者と対応状況
+    #[serde(default)]
+    pub triage: Triage,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    /// 書き出しのときに読み込む共有の注釈（[`load_highlights`]）．記事の保存・検索では読み書きしない．
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<Annotation>,
+}
+
+impl AsRef<WebArticle> for StoredArticle {
+    fn as_ref(&self) -> &WebArticle {
+        &self.article
+    }
+}
+
+/// 記事の検索条件（未指定の項目は絞り込まない）
+#[derive(Debug, Clone, Default)]
+pub struct ArticleQuery {
+    pub site: Option<String>,
+    pub since: Option<DateTime<Local>>,
+    pub until: Option<DateTime<Local>>,
+    pub status: Option<Status>,
+    /// 記事の言語（`ja`，`en` など）
+    pub lang: Option<String>,
+    pub triage: Option<TriageState>,
+    pub assignee: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[async_trait::async_trait]
+pub trait ArticleStore: Send + Sync {
+    /// `article_url` をキーに記事を追加・更新する．
+    /// 既存の記事のステータスは維持し，空の本文で既存の本文を上書きしない．
+    async fn upsert(&self, article: &WebArticle) -> AppResult<WebArticleId>;
+    async fn upsert_many(&self, articles: &[WebArticle]) -> AppResult<usize> {
+        for article in articles {
+            self.upsert(article).await?;
+        }
+        Ok(articles.len())
+    }
+    async fn get(&self, id: WebArticleId) -> AppResult<Option<StoredArticle>>;
+    async fn get_by_url(&self, article_url: &str) -> AppResult<Option<StoredArticle>>;
+    /// 新しい順（公開日時の降順，同じ時刻はサイト名，URL の順）に返す
+    async fn query(&self, query: &ArticleQuery) -> AppResult<Vec<StoredArticle>>;
+    async fn set_status(&self, article_url: &str, status: Status) -> AppResult<bool>;
+    async fn set_triage(&self, article_url: &str, triage: &Triage) -> AppResult<bool>;
+    /// 記事を論理削除し，削除した件数を返す．論理削除した記事は取得・検索の結果に含めず，再取得しても復活しない．
+    async fn soft_delete(&self, article_urls: &[String]) -> AppResult<usize>;
+    /// 物理削除する前の論理削除した記事（キャッシュした画像など，記事に紐づくファイルを消すために使う）
+    async fn deleted_articles(&self) -> AppResult<Vec<StoredArticle>>;
+    /// 論理削除した記事を物理削除し，削除した記事の URL を返す
+    async fn purge_deleted(&self) -> AppResult<Vec<String>>;
+}
+
+/// 1回の巡回における1サイト分の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchRun {
+    pub run_at: DateTime<Local>,
+    pub report: FetchReport,
+}
+
+/// 巡回結果の履歴（運用レポートの集計に使う）
+#[async_trait::async_trait]
+pub trait FetchHistoryStore: Send + Sync {
+    async fn record_run(&self, run_at: DateTime<Local>, reports: &[FetchReport]) -> AppResult<()>;
+    /// `since` 以上 `until` 未満の巡回結果を古い順に返す
+    async fn runs_between(&self, since: DateTime<Local>, until: DateTime<Local>) -> AppResult<Vec<FetchRun>>;
+}
+
+/// 記事の注釈とスター．共有（`Visibility::Shared`）のものはチーム全員に，
+/// 個人用（`Visibility::Personal`）のものは作成したユーザーだけに見える．
+/// `user` が `None`（匿名）の場合は共有のものだけを扱う．
+#[async_trait::async_trait]
+pub trait AnnotationStore: Send + Sync {
+    /// 注釈を追加・更新する（`id` をキーとする）
+    async fn save_annotation(&self, annotation: &Annotation) -> AppResult<()>;
+    async fn get_annotation(&self, id: AnnotationId, user: Option<&str>) -> AppResult<Option<Annotation>>;
+    /// `user` から見える記事の注釈
+    async fn annotations(&self, article_url: &str, user: Option<&str>) -> AppResult<Annotations>;
+    /// `user` から見える注釈を削除する．削除できれば `true`．
+    async fn remove_annotation(&self, id: AnnotationId, user: Option<&str>) -> AppResult<bool>;
+    /// スターを付ける（付いていれば何もしない）
+    async fn star(&self, star: &Star) -> AppResult<()>;
+    /// `user` のスター（共有のスターは誰でも外せる）を外す．外せれば `true`．
+    async fn unstar(&self, article_url: &str, visibility: Visibility, user: Option<&str>) -> AppResult<bool>;
+    /// `user` から見えるスターを新しい順に返す
+    async fn stars(&self, visibility: Option<Visibility>, user: Option<&str>) -> AppResult<Vec<Star>>;
+}
+
+/// 書き出す記事に共有の注釈（ハイライトとメモ）を読み込む．個人用の注釈は書き出さない．
+pub async fn load_highlights(store: &dyn AnnotationStore, articles: &mut [StoredArticle]) -> AppResult<()> {
+    for stored in articles.iter_mut() {
+        let annotations = store.annotations(&stored.article.article_url, None).await?;
+        stored.highlights = annotations.as_slice().to_vec();
+    }
+    Ok(())
+}
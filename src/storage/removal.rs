@@ -0,0 +1,251 @@
+//! 掲載元から削除を求められた場合などに，特定のドメイン・条件に一致する記事を削除する．
+//!
+//! まず論理削除（API や検索の結果から隠す）し，確認後に物理削除（データベースの行と HTTP キャッシュの本文，
+//! WARC に保存したレスポンス，キャッシュした画像，few-shot 例と関連度の採点結果を消す）する．
+
+use crate::archive::warc::WarcWriter;
+use crate::assets::{AssetStore, cached_asset_keys};
+use crate::enrichment::few_shot::ExampleStore;
+use crate::enrichment::relevance::RelevanceCache;
+use crate::filters::{ArticleFilter, FilterConfig};
+use crate::models::web_article::WebArticle;
+use crate::shared::domain_guard::is_allowed_host;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::fetch_cache::FetchCache;
+use crate::storage::{ArticleQuery, ArticleStore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 削除する記事の条件．`domain`（サブドメインを含む）と `filter` の両方を指定した場合は両方に一致する記事が対象．
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemovalRequest {
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub filter: Option<FilterConfig>,
+}
+
+impl RemovalRequest {
+    pub fn domain(domain: impl Into<String>) -> Self {
+        Self {
+            domain: Some(domain.into()),
+            filter: None,
+        }
+    }
+
+    /// 条件に一致する記事を判定する関数を作る．条件が1つも無い場合は全件削除を防ぐためエラーにする．
+    pub fn matcher(&self) -> AppResult<impl Fn(&WebArticle) -> bool + use<>> {
+        let domain = self.domain.as_deref().map(str::trim).filter(|d| !d.is_empty());
+        if domain.is_none() && self.filter.as_ref().is_none_or(|f| *f == FilterConfig::default()) {
+            return Err(AppError::InternalError("Removal requires a domain or a filter".into()));
+        }
+        let domains: Vec<String> = domain.into_iter().map(str::to_string).collect();
+        let filter = self.filter.as_ref().map(FilterConfig::build).transpose()?;
+        Ok(move |article: &WebArticle| {
+            let host = request::Url::parse(&article.article_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            (domains.is_empty() || is_allowed_host(&host, &domains))
+                && filter.as_ref().is_none_or(|filter| filter.keep(article))
+        })
+    }
+}
+
+/// 条件に一致する記事を論理削除し，削除した件数を返す
+pub async fn soft_delete_matching(store: &dyn ArticleStore, request: &RemovalRequest) -> AppResult<usize> {
+    let matches = request.matcher()?;
+    let urls: Vec<String> = store
+        .query(&ArticleQuery::default())
+        .await?
+        .into_iter()
+        .filter(|stored| matches(&stored.article))
+        .map(|stored| stored.article.article_url)
+        .collect();
+    if urls.is_empty() {
+        return Ok(0);
+    }
+    store.soft_delete(&urls).await
+}
+
+/// 論理削除した記事を物理削除し，HTTP キャッシュに残った本文，WARC のレコード，キャッシュした画像，
+/// 記事の本文を含む few-shot 例，記事の URL を含む関連度の採点結果も削除する．
+/// 画像は残っている記事が参照していないものだけを削除する．削除した件数を返す．
+pub async fn purge_deleted(
+    store: &dyn ArticleStore,
+    cache: Option<&FetchCache>,
+    warc: Option<&WarcWriter>,
+    assets: Option<&dyn AssetStore>,
+    examples: Option<&ExampleStore>,
+    relevance: Option<&RelevanceCache>,
+) -> AppResult<usize> {
+    let deleted_assets: HashSet<String> = match assets {
+        Some(_) => store.deleted_articles().await?.iter().flat_map(|s| cached_asset_keys(&s.article)).collect(),
+        None => HashSet::new(),
+    };
+    let urls = store.purge_deleted().await?;
+    if let Some(assets) = assets
+        && !deleted_assets.is_empty()
+    {
+        let remaining: HashSet<String> = store
+            .query(&ArticleQuery::default())
+            .await?
+            .iter()
+            .flat_map(|s| cached_asset_keys(&s.article))
+            .collect();
+        for key in deleted_assets.difference(&remaining) {
+            assets.remove(key).await?;
+        }
+    }
+    if let Some(cache) = cache {
+        for url in &urls {
+            cache.remove(url)?;
+        }
+    }
+    if let Some(warc) = warc {
+        warc.remove_urls(&urls)?;
+    }
+    if let Some(examples) = examples {
+        examples.remove_urls(&urls)?;
+    }
+    if let Some(relevance) = relevance {
+        relevance.remove_urls(&urls)?;
+    }
+    Ok(urls.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::warc::{WarcRecord, read_records};
+    use crate::assets::{LocalAssetStore, asset_key};
+    use crate::enrichment::classifier::Classification;
+    use crate::enrichment::few_shot::{FEW_SHOT_EXAMPLES_FILE, LabeledExample};
+    use crate::enrichment::relevance::{InterestProfile, RELEVANCE_CACHE_FILE, RelevanceScore};
+    use crate::storage::sqlite::SqliteStore;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::Local;
+    use request::StatusCode;
+    use request::header::HeaderMap;
+
+    fn article(url: &str, title: &str) -> WebArticle {
+        ArticleBuilder::new(title).with_url(url).build()
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_and_purge_by_domain() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store
+            .upsert_many(&[
+                article("https://news.publisher.example/1", "a"),
+                article("https://publisher.example/2", "b"),
+                article("https://other.example/3", "c"),
+            ])
+            .await
+            .unwrap();
+
+        let removed = soft_delete_matching(&store, &RemovalRequest::domain("publisher.example")).await.unwrap();
+        assert_eq!(removed, 2);
+        let remaining = store.query(&ArticleQuery::default()).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].article.article_url, "https://other.example/3");
+
+        assert_eq!(purge_deleted(&store, None, None, None, None, None).await.unwrap(), 2);
+        assert_eq!(purge_deleted(&store, None, None, None, None, None).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_purge_removes_warc_records() {
+        let dir = std::env::temp_dir().join(format!("clipper-removal-warc-{}", uuid::Uuid::new_v4()));
+        let warc = WarcWriter::new(dir.clone());
+        let store = SqliteStore::open_in_memory().unwrap();
+        let urls = ["https://publisher.example/1", "https://other.example/2"];
+        store.upsert_many(&urls.map(|url| article(url, "a"))).await.unwrap();
+        for url in urls {
+            warc.write(&WarcRecord::response(url, StatusCode::OK, &HeaderMap::new(), b"<p>body</p>")).unwrap();
+        }
+
+        soft_delete_matching(&store, &RemovalRequest::domain("publisher.example")).await.unwrap();
+        assert_eq!(purge_deleted(&store, None, Some(&warc), None, None, None).await.unwrap(), 1);
+        let records = read_records(&warc.path_for(Local::now())).unwrap();
+        let uris: Vec<&str> = records.iter().filter_map(|r| r.target_uri.as_deref()).collect();
+        assert_eq!(uris, vec!["https://other.example/2"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_purge_removes_few_shot_examples_and_relevance_scores() {
+        let dir = std::env::temp_dir().join(format!("clipper-removal-enrichment-{}", uuid::Uuid::new_v4()));
+        let examples = ExampleStore::open(dir.join(FEW_SHOT_EXAMPLES_FILE), None).unwrap();
+        let relevance = RelevanceCache::open(dir.join(RELEVANCE_CACHE_FILE)).unwrap();
+        let profile = InterestProfile::new("default", "Rust のツール");
+        let articles = [article("https://publisher.example/1", "a"), article("https://other.example/2", "b")];
+        let store = SqliteStore::open_in_memory().unwrap();
+        store.upsert_many(&articles).await.unwrap();
+        for article in &articles {
+            examples.add(LabeledExample::new(article, Classification::default())).unwrap();
+            let score = RelevanceScore {
+                score: 5,
+                reason: "関心に近い".into(),
+            };
+            relevance.insert(&article.article_url, &profile, score).unwrap();
+        }
+
+        soft_delete_matching(&store, &RemovalRequest::domain("publisher.example")).await.unwrap();
+        assert_eq!(purge_deleted(&store, None, None, None, Some(&examples), Some(&relevance)).await.unwrap(), 1);
+        let urls: Vec<String> = examples.examples().into_iter().map(|e| e.article_url).collect();
+        assert_eq!(urls, vec!["https://other.example/2"]);
+        assert!(relevance.get("https://publisher.example/1", &profile).is_none());
+
+        // 再起動後（ファイルから読み直しても）残らない
+        let examples = ExampleStore::open(dir.join(FEW_SHOT_EXAMPLES_FILE), None).unwrap();
+        let relevance = RelevanceCache::open(dir.join(RELEVANCE_CACHE_FILE)).unwrap();
+        assert_eq!(examples.len(), 1);
+        assert!(relevance.get("https://publisher.example/1", &profile).is_none());
+        assert!(relevance.get("https://other.example/2", &profile).is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_purge_removes_cached_assets_not_used_by_remaining_articles() {
+        let dir = std::env::temp_dir().join(format!("clipper-removal-assets-{}", uuid::Uuid::new_v4()));
+        let assets = LocalAssetStore::new(&dir, "/assets");
+        let only_deleted = asset_key("https://publisher.example/photo.png");
+        let shared = asset_key("https://cdn.example/logo.png");
+        let photo = assets.put(&only_deleted, "png", b"png").await.unwrap();
+        let logo = assets.put(&shared, "png", b"png").await.unwrap();
+        let mut deleted = article("https://publisher.example/1", "a");
+        deleted.html = format!(r#"<img src="{}"><img src="{}">"#, photo, logo);
+        let mut kept = article("https://other.example/2", "b");
+        kept.properties.cached_image = Some(format!("/assets/{}.png", shared));
+        let store = SqliteStore::open_in_memory().unwrap();
+        store.upsert_many(&[deleted, kept]).await.unwrap();
+
+        soft_delete_matching(&store, &RemovalRequest::domain("publisher.example")).await.unwrap();
+        assert_eq!(purge_deleted(&store, None, None, Some(&assets), None, None).await.unwrap(), 1);
+        assert_eq!(assets.lookup(&only_deleted).await.unwrap(), None);
+        assert!(assets.lookup(&shared).await.unwrap().is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_removal_requires_condition_and_applies_filter() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        assert!(soft_delete_matching(&store, &RemovalRequest::default()).await.is_err());
+
+        store
+            .upsert_many(&[article("https://a.example/1", "Rust 1.90"), article("https://a.example/2", "Go")])
+            .await
+            .unwrap();
+        let request = RemovalRequest {
+            domain: Some("a.example".into()),
+            filter: Some(FilterConfig {
+                include: vec!["rust".into()],
+                ..Default::default()
+            }),
+        };
+        assert_eq!(soft_delete_matching(&store, &request).await.unwrap(), 1);
+        assert!(store.get_by_url("https://a.example/2").await.unwrap().is_some());
+    }
+}
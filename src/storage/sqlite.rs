@@ -0,0 +1,870 @@
+use crate::alerts::triage::{Triage, TriageState};
+use crate::models::annotation::{Annotation, Annotations, Star, Visibility};
+use crate::models::report::FetchReport;
+use crate::models::web_article::{Status, WebArticle, WebArticleProperty, WebSite};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::id::{AnnotationId, WebArticleId};
+use crate::storage::encryption::{self, StorageCipher};
+use crate::storage::{AnnotationStore, ArticleQuery, ArticleStore, FetchHistoryStore, FetchRun, StoredArticle};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use rusqlite::types::Value;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS articles (
+    id             TEXT PRIMARY KEY,
+    article_url    TEXT NOT NULL UNIQUE,
+    site_name      TEXT NOT NULL,
+    site_url       TEXT NOT NULL,
+    title          TEXT NOT NULL,
+    description    TEXT NOT NULL,
+    timestamp      TEXT NOT NULL,
+    text           TEXT NOT NULL,
+    html           TEXT NOT NULL,
+    requires_login INTEGER NOT NULL,
+    properties     TEXT NOT NULL,
+    status         TEXT NOT NULL,
+    created_at     TEXT NOT NULL,
+    updated_at     TEXT NOT NULL,
+    deleted_at     TEXT,
+    lang           TEXT,
+    triage         TEXT NOT NULL DEFAULT 'untriaged',
+    assignee       TEXT,
+    author         TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_articles_site_name ON articles (site_name);
+CREATE INDEX IF NOT EXISTS idx_articles_timestamp ON articles (timestamp);
+CREATE INDEX IF NOT EXISTS idx_articles_status ON articles (status);
+CREATE TABLE IF NOT EXISTS fetch_runs (
+    id      INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_at  TEXT NOT NULL,
+    site    TEXT NOT NULL,
+    report  TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_fetch_runs_run_at ON fetch_runs (run_at);
+CREATE TABLE IF NOT EXISTS annotations (
+    id          TEXT PRIMARY KEY,
+    article_url TEXT NOT NULL,
+    visibility  TEXT NOT NULL,
+    owner       TEXT,
+    annotation  TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_annotations_article_url ON annotations (article_url);
+CREATE TABLE IF NOT EXISTS stars (
+    article_url TEXT NOT NULL,
+    visibility  TEXT NOT NULL,
+    -- 共有のスターは空文字列，個人用のスターはユーザー（記事・公開範囲ごとに1つ）
+    holder      TEXT NOT NULL,
+    owner       TEXT,
+    created_at  TEXT NOT NULL,
+    PRIMARY KEY (article_url, visibility, holder)
+);
+"#;
+
+const COLUMNS: &str = "id, article_url, site_name, site_url, title, description, timestamp, text, html, \
+                       requires_login, properties, status, created_at, updated_at, lang, triage, assignee, author";
+
+/// SQLite による `ArticleStore` の実装．
+/// rusqlite は同期 API のため，クエリは `spawn_blocking` で実行する．
+/// 暗号化の鍵を設定した場合，記事の本文（`text`，`html`）は暗号化して保存する．
+#[derive(Debug, Clone)]
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+    cipher: Option<Arc<StorageCipher>>,
+}
+
+impl SqliteStore {
+    /// `CLIPPER_STORAGE_KEY` / `CLIPPER_STORAGE_KEY_FILE` が設定されていれば本文を暗号化する
+    pub fn open(path: &Path) -> AppResult<Self> {
+        Ok(Self::open_plain(path)?.with_cipher(StorageCipher::from_env()?))
+    }
+
+    /// 鍵の設定に関わらず暗号化せずに開く
+    pub fn open_plain(path: &Path) -> AppResult<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                AppError::InternalError(format!("Failed to create {:?}: {}", parent, e))
+            })?;
+        }
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> AppResult<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> AppResult<Self> {
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA)?;
+        migrate(&conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            cipher: None,
+        })
+    }
+
+    pub fn with_cipher(mut self, cipher: Option<StorageCipher>) -> Self {
+        self.cipher = cipher.map(Arc::new);
+        self
+    }
+
+    /// 接続をロックしてブロッキングスレッドで `f` を実行する
+    pub async fn with_conn<T, F>(&self, f: F) -> AppResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> AppResult<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+            f(&mut conn)
+        })
+        .await
+        .map_err(|e| AppError::InternalError(format!("Storage task failed: {}", e)))?
+    }
+}
+
+/// 既存のデータベースに後から追加した列を加える
+fn migrate(conn: &Connection) -> AppResult<()> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('articles')")?;
+    let columns = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+    if !columns.iter().any(|c| c == "deleted_at") {
+        conn.execute_batch("ALTER TABLE articles ADD COLUMN deleted_at TEXT")?;
+    }
+    if !columns.iter().any(|c| c == "lang") {
+        conn.execute_batch("ALTER TABLE articles ADD COLUMN lang TEXT")?;
+    }
+    if !columns.iter().any(|c| c == "triage") {
+        conn.execute_batch(
+            "ALTER TABLE articles ADD COLUMN triage TEXT NOT NULL DEFAULT 'untriaged';
+             ALTER TABLE articles ADD COLUMN assignee TEXT;",
+        )?;
+    }
+    if !columns.iter().any(|c| c == "author") {
+        conn.execute_batch("ALTER TABLE articles ADD COLUMN author TEXT")?;
+    }
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_articles_lang ON articles (lang)")?;
+    Ok(())
+}
+
+fn upsert_article(
+    conn: &Connection,
+    cipher: Option<&StorageCipher>,
+    article: &WebArticle,
+) -> AppResult<WebArticleId> {
+    let now = to_sql_time(&Local::now());
+    let id: String = conn.query_row(
+        r#"
+        INSERT INTO articles (id, article_url, site_name, site_url, title, description, timestamp,
+                              text, html, requires_login, properties, status, created_at, updated_at, lang, author)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?13, ?14, ?15)
+        ON CONFLICT(article_url) DO UPDATE SET
+            site_name      = excluded.site_name,
+            site_url       = excluded.site_url,
+            title          = excluded.title,
+            description    = excluded.description,
+            timestamp      = excluded.timestamp,
+            text           = CASE WHEN excluded.text = '' THEN articles.text ELSE excluded.text END,
+            html           = CASE WHEN excluded.html = '' THEN articles.html ELSE excluded.html END,
+            requires_login = excluded.requires_login,
+            properties     = excluded.properties,
+            updated_at     = excluded.updated_at,
+            lang           = COALESCE(excluded.lang, articles.lang),
+            author         = COALESCE(excluded.author, articles.author)
+        RETURNING id
+        "#,
+        params![
+            WebArticleId::new().to_string(),
+            article.article_url,
+            article.site.name,
+            article.site.url,
+            article.title,
+            article.description,
+            to_sql_time(&article.timestamp),
+            encryption::seal(cipher, &article.text)?,
+            encryption::seal(cipher, &article.html)?,
+            article.requires_login,
+            serde_json::to_string(&article.properties)?,
+            Status::New.to_string(),
+            now,
+            article.lang,
+            article.author,
+        ],
+        |row| row.get(0),
+    )?;
+    WebArticleId::from_str(&id)
+}
+
+/// 文字列比較で時刻順になるよう UTC・秒精度に揃えて保存する
+fn to_sql_time(time: &DateTime<Local>) -> String {
+    time.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_time(value: String) -> rusqlite::Result<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(&value)
+        .map(|t| t.with_timezone(&Local))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn row_to_stored(row: &Row, cipher: Option<&StorageCipher>) -> rusqlite::Result<StoredArticle> {
+    let conversion = |idx: usize, e: Box<dyn std::error::Error + Send + Sync>| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, e)
+    };
+    let id: String = row.get(0)?;
+    let properties: String = row.get(10)?;
+    let status: String = row.get(11)?;
+    let triage: String = row.get(15)?;
+    let article = WebArticle {
+        site: WebSite::intern(row.get(2)?, row.get(3)?),
+        title: row.get(4)?,
+        article_url: row.get(1)?,
+        description: row.get(5)?,
+        properties: serde_json::from_str::<WebArticleProperty>(&properties)
+            .map_err(|e| conversion(10, Box::new(e)))?,
+        timestamp: parse_time(row.get(6)?)?,
+        text: encryption::open(cipher, row.get(7)?).map_err(|e| conversion(7, Box::new(e)))?,
+        html: encryption::open(cipher, row.get(8)?).map_err(|e| conversion(8, Box::new(e)))?,
+        requires_login: row.get(9)?,
+        lang: row.get(14)?,
+        author: row.get(17)?,
+    };
+    Ok(StoredArticle {
+        id: WebArticleId::from_str(&id).map_err(|e| conversion(0, e.to_string().into()))?,
+        status: Status::from_str(&status).map_err(|e| conversion(11, Box::new(e)))?,
+        article,
+        triage: Triage {
+            state: TriageState::from_str(&triage).map_err(|e| conversion(15, Box::new(e)))?,
+            assignee: row.get(16)?,
+        },
+        created_at: parse_time(row.get(12)?)?,
+        updated_at: parse_time(row.get(13)?)?,
+        highlights: Vec::new(),
+    })
+}
+
+fn select_one(
+    conn: &Connection,
+    cipher: Option<&StorageCipher>,
+    column: &str,
+    value: String,
+) -> AppResult<Option<StoredArticle>> {
+    let sql = format!("SELECT {} FROM articles WHERE {} = ?1 AND deleted_at IS NULL", COLUMNS, column);
+    Ok(conn.query_row(&sql, [value], |row| row_to_stored(row, cipher)).optional()?)
+}
+
+#[async_trait::async_trait]
+impl ArticleStore for SqliteStore {
+    async fn upsert(&self, article: &WebArticle) -> AppResult<WebArticleId> {
+        let article = article.clone();
+        let cipher = self.cipher.clone();
+        self.with_conn(move |conn| upsert_article(conn, cipher.as_deref(), &article)).await
+    }
+
+    async fn upsert_many(&self, articles: &[WebArticle]) -> AppResult<usize> {
+        let articles = articles.to_vec();
+        let cipher = self.cipher.clone();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            for article in &articles {
+                upsert_article(&tx, cipher.as_deref(), article)?;
+            }
+            tx.commit()?;
+            Ok(articles.len())
+        })
+        .await
+    }
+
+    async fn get(&self, id: WebArticleId) -> AppResult<Option<StoredArticle>> {
+        let cipher = self.cipher.clone();
+        self.with_conn(move |conn| select_one(conn, cipher.as_deref(), "id", id.to_string())).await
+    }
+
+    async fn get_by_url(&self, article_url: &str) -> AppResult<Option<StoredArticle>> {
+        let article_url = article_url.to_string();
+        let cipher = self.cipher.clone();
+        self.with_conn(move |conn| select_one(conn, cipher.as_deref(), "article_url", article_url)).await
+    }
+
+    async fn query(&self, query: &ArticleQuery) -> AppResult<Vec<StoredArticle>> {
+        let query = query.clone();
+        let cipher = self.cipher.clone();
+        self.with_conn(move |conn| {
+            let mut conditions: Vec<&str> = vec!["deleted_at IS NULL"];
+            let mut values: Vec<Value> = Vec::new();
+            if let Some(site) = query.site {
+                conditions.push("site_name = ?");
+                values.push(Value::Text(site));
+            }
+            if let Some(since) = query.since {
+                conditions.push("timestamp >= ?");
+                values.push(Value::Text(to_sql_time(&since)));
+            }
+            if let Some(until) = query.until {
+                conditions.push("timestamp < ?");
+                values.push(Value::Text(to_sql_time(&until)));
+            }
+            if let Some(status) = query.status {
+                conditions.push("status = ?");
+                values.push(Value::Text(status.to_string()));
+            }
+            if let Some(lang) = query.lang {
+                conditions.push("lang = ?");
+                values.push(Value::Text(lang));
+            }
+            if let Some(triage) = query.triage {
+                conditions.push("triage = ?");
+                values.push(Value::Text(triage.to_string()));
+            }
+            if let Some(assignee) = query.assignee {
+                conditions.push("assignee = ?");
+                values.push(Value::Text(assignee));
+            }
+            let mut sql = format!("SELECT {} FROM articles WHERE ", COLUMNS);
+            sql.push_str(&conditions.join(" AND "));
+            // `models::ordering::newest_first` と同じ順（時刻は UTC の RFC 3339 で保存しているため文字列の順で比べられる）
+            sql.push_str(" ORDER BY timestamp DESC, site_name ASC, article_url ASC");
+            if let Some(limit) = query.limit {
+                sql.push_str(&format!(" LIMIT {}", limit));
+            }
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+                row_to_stored(row, cipher.as_deref())
+            })?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        })
+        .await
+    }
+
+    async fn set_status(&self, article_url: &str, status: Status) -> AppResult<bool> {
+        let article_url = article_url.to_string();
+        self.with_conn(move |conn| {
+            let updated = conn.execute(
+                "UPDATE articles SET status = ?1, updated_at = ?2 WHERE article_url = ?3",
+                params![status.to_string(), to_sql_time(&Local::now()), article_url],
+            )?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn set_triage(&self, article_url: &str, triage: &Triage) -> AppResult<bool> {
+        let article_url = article_url.to_string();
+        let triage = triage.clone();
+        self.with_conn(move |conn| {
+            let updated = conn.execute(
+                "UPDATE articles SET triage = ?1, assignee = ?2, updated_at = ?3
+                 WHERE article_url = ?4 AND deleted_at IS NULL",
+                params![triage.state.to_string(), triage.assignee, to_sql_time(&Local::now()), article_url],
+            )?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn soft_delete(&self, article_urls: &[String]) -> AppResult<usize> {
+        let article_urls = article_urls.to_vec();
+        self.with_conn(move |conn| {
+            let now = to_sql_time(&Local::now());
+            let tx = conn.transaction()?;
+            let mut deleted = 0;
+            for article_url in &article_urls {
+                deleted += tx.execute(
+                    "UPDATE articles SET deleted_at = ?1, updated_at = ?1 WHERE article_url = ?2 AND deleted_at IS NULL",
+                    params![now, article_url],
+                )?;
+            }
+            tx.commit()?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    async fn deleted_articles(&self) -> AppResult<Vec<StoredArticle>> {
+        let cipher = self.cipher.clone();
+        self.with_conn(move |conn| {
+            let sql = format!("SELECT {} FROM articles WHERE deleted_at IS NOT NULL", COLUMNS);
+            let mut stmt = conn.prepare(&sql)?;
+            let articles = stmt
+                .query_map([], |row| row_to_stored(row, cipher.as_deref()))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(articles)
+        })
+        .await
+    }
+
+    async fn purge_deleted(&self) -> AppResult<Vec<String>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare("DELETE FROM articles WHERE deleted_at IS NOT NULL RETURNING article_url")?;
+            let urls = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<String>, _>>()?;
+            drop(stmt);
+            for url in &urls {
+                conn.execute("DELETE FROM annotations WHERE article_url = ?1", [url])?;
+                conn.execute("DELETE FROM stars WHERE article_url = ?1", [url])?;
+            }
+            // 削除した本文がファイルに残らないようにする
+            conn.execute_batch("VACUUM")?;
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+            Ok(urls)
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl FetchHistoryStore for SqliteStore {
+    async fn record_run(&self, run_at: DateTime<Local>, reports: &[FetchReport]) -> AppResult<()> {
+        let reports = reports.to_vec();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            for report in &reports {
+                tx.execute(
+                    "INSERT INTO fetch_runs (run_at, site, report) VALUES (?1, ?2, ?3)",
+                    params![to_sql_time(&run_at), report.site, serde_json::to_string(report)?],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn runs_between(&self, since: DateTime<Local>, until: DateTime<Local>) -> AppResult<Vec<FetchRun>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT run_at, report FROM fetch_runs WHERE run_at >= ?1 AND run_at < ?2 ORDER BY run_at, id",
+            )?;
+            let rows = stmt.query_map(params![to_sql_time(&since), to_sql_time(&until)], |row| {
+                Ok((parse_time(row.get(0)?)?, row.get::<_, String>(1)?))
+            })?;
+            let mut runs = Vec::new();
+            for row in rows {
+                let (run_at, report) = row?;
+                runs.push(FetchRun {
+                    run_at,
+                    report: serde_json::from_str(&report)?,
+                });
+            }
+            Ok(runs)
+        })
+        .await
+    }
+}
+
+/// 共有のもの，または `?{n}` のユーザーのものに絞り込む条件
+fn visible_condition(n: usize) -> String {
+    format!("(visibility = '{}' OR owner = ?{})", Visibility::Shared, n)
+}
+
+fn star_holder(star: &Star) -> AppResult<String> {
+    match (star.visibility, &star.owner) {
+        (Visibility::Shared, _) => Ok(String::new()),
+        (Visibility::Personal, Some(owner)) => Ok(owner.clone()),
+        (Visibility::Personal, None) => Err(AppError::InternalError("Personal star requires an owner".into())),
+    }
+}
+
+#[async_trait::async_trait]
+impl AnnotationStore for SqliteStore {
+    async fn save_annotation(&self, annotation: &Annotation) -> AppResult<()> {
+        if annotation.visibility == Visibility::Personal && annotation.owner.is_none() {
+            return Err(AppError::InternalError("Personal annotation requires an owner".into()));
+        }
+        let annotation = annotation.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO annotations (id, article_url, visibility, owner, annotation) VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(id) DO UPDATE SET visibility = excluded.visibility, owner = excluded.owner,
+                 annotation = excluded.annotation",
+                params![
+                    annotation.id.to_string(),
+                    annotation.article_url,
+                    annotation.visibility.to_string(),
+                    annotation.owner,
+                    serde_json::to_string(&annotation)?
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_annotation(&self, id: AnnotationId, user: Option<&str>) -> AppResult<Option<Annotation>> {
+        let user = user.map(str::to_string);
+        self.with_conn(move |conn| {
+            let sql = format!("SELECT annotation FROM annotations WHERE id = ?1 AND {}", visible_condition(2));
+            let json: Option<String> =
+                conn.query_row(&sql, params![id.to_string(), user], |row| row.get(0)).optional()?;
+            Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+        })
+        .await
+    }
+
+    async fn annotations(&self, article_url: &str, user: Option<&str>) -> AppResult<Annotations> {
+        let article_url = article_url.to_string();
+        let user = user.map(str::to_string);
+        self.with_conn(move |conn| {
+            let sql = format!(
+                "SELECT annotation FROM annotations WHERE article_url = ?1 AND {}",
+                visible_condition(2)
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params![article_url, user], |row| row.get::<_, String>(0))?;
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(serde_json::from_str(&row?)?);
+            }
+            Ok(Annotations::new(items))
+        })
+        .await
+    }
+
+    async fn remove_annotation(&self, id: AnnotationId, user: Option<&str>) -> AppResult<bool> {
+        let user = user.map(str::to_string);
+        self.with_conn(move |conn| {
+            let sql = format!("DELETE FROM annotations WHERE id = ?1 AND {}", visible_condition(2));
+            Ok(conn.execute(&sql, params![id.to_string(), user])? > 0)
+        })
+        .await
+    }
+
+    async fn star(&self, star: &Star) -> AppResult<()> {
+        let holder = star_holder(star)?;
+        let star = star.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO stars (article_url, visibility, holder, owner, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    star.article_url,
+                    star.visibility.to_string(),
+                    holder,
+                    star.owner,
+                    to_sql_time(&star.created_at)
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn unstar(&self, article_url: &str, visibility: Visibility, user: Option<&str>) -> AppResult<bool> {
+        let holder = match visibility {
+            Visibility::Shared => String::new(),
+            Visibility::Personal => match user {
+                Some(user) => user.to_string(),
+                None => return Ok(false),
+            },
+        };
+        let article_url = article_url.to_string();
+        self.with_conn(move |conn| {
+            let deleted = conn.execute(
+                "DELETE FROM stars WHERE article_url = ?1 AND visibility = ?2 AND holder = ?3",
+                params![article_url, visibility.to_string(), holder],
+            )?;
+            Ok(deleted > 0)
+        })
+        .await
+    }
+
+    async fn stars(&self, visibility: Option<Visibility>, user: Option<&str>) -> AppResult<Vec<Star>> {
+        let user = user.map(str::to_string);
+        self.with_conn(move |conn| {
+            let sql = format!(
+                "SELECT article_url, visibility, owner, created_at FROM stars
+                 WHERE {} AND (?2 IS NULL OR visibility = ?2) ORDER BY created_at DESC",
+                visible_condition(1)
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params![user, visibility.map(|v| v.to_string())], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    parse_time(row.get(3)?)?,
+                ))
+            })?;
+            let mut stars = Vec::new();
+            for row in rows {
+                let (article_url, visibility, owner, created_at) = row?;
+                stars.push(Star {
+                    article_url,
+                    visibility: Visibility::from_str(&visibility)
+                        .map_err(|e| AppError::EnumParseError(e.to_string()))?,
+                    owner,
+                    created_at,
+                });
+            }
+            Ok(stars)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ordering::sort_newest_first;
+    use crate::models::report::FailureKind;
+    use crate::testing::article::ArticleBuilder;
+    use chrono::TimeZone;
+
+    fn article(site: &str, url: &str, day: u32) -> WebArticle {
+        ArticleBuilder::new(format!("{} title", url))
+            .with_site(site, format!("https://{}.example.com", site))
+            .with_url(url)
+            .with_description("description")
+            .with_timestamp(Local.with_ymd_and_hms(2025, 10, day, 9, 0, 0).unwrap())
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_upsert_keeps_status_and_text() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let mut first = article("a", "https://a.example.com/1", 1);
+        first.text = "full text".into();
+        let id = store.upsert(&first).await.unwrap();
+        assert!(store.set_status(&first.article_url, Status::Archived).await.unwrap());
+
+        // フィードから再取得した（本文の無い）記事で上書きしても本文とステータスは残る
+        let mut refetched = article("a", "https://a.example.com/1", 1);
+        refetched.title = "updated title".into();
+        assert_eq!(store.upsert(&refetched).await.unwrap(), id);
+
+        let stored = store.get(id).await.unwrap().unwrap();
+        assert_eq!(stored.article.title, "updated title");
+        assert_eq!(stored.article.text, "full text");
+        assert_eq!(stored.status, Status::Archived);
+        assert_eq!(stored.article.site.name, "a");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_query() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let mut english = article("b", "https://b.example.com/3", 3);
+        english.lang = Some("en".into());
+        english.author = Some("Alice".into());
+        store
+            .upsert_many(&[
+                article("a", "https://a.example.com/1", 1),
+                article("a", "https://a.example.com/2", 2),
+                english,
+            ])
+            .await
+            .unwrap();
+        store.set_status("https://a.example.com/2", Status::Archived).await.unwrap();
+
+        let all = store.query(&ArticleQuery::default()).await.unwrap();
+        let urls: Vec<_> = all.iter().map(|a| a.article.article_url.as_str()).collect();
+        assert_eq!(urls, vec!["https://b.example.com/3", "https://a.example.com/2", "https://a.example.com/1"]);
+
+        let by_site = store
+            .query(&ArticleQuery { site: Some("a".into()), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(by_site.len(), 2);
+
+        let window = store
+            .query(&ArticleQuery {
+                since: Some(Local.with_ymd_and_hms(2025, 10, 2, 0, 0, 0).unwrap()),
+                until: Some(Local.with_ymd_and_hms(2025, 10, 3, 0, 0, 0).unwrap()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(window.len(), 1);
+
+        let by_lang = store
+            .query(&ArticleQuery { lang: Some("en".into()), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(by_lang.len(), 1);
+        assert_eq!(by_lang[0].article.lang.as_deref(), Some("en"));
+        assert_eq!(by_lang[0].article.author.as_deref(), Some("Alice"));
+        assert_eq!(all[1].article.author, None);
+
+        let new_only = store
+            .query(&ArticleQuery { status: Some(Status::New), limit: Some(1), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(new_only[0].article.article_url, "https://b.example.com/3");
+        assert!(store.get_by_url("https://missing.example.com").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_query_breaks_ties_by_site_and_url() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store
+            .upsert_many(&[
+                article("b", "https://b.example.com/1", 1),
+                article("a", "https://a.example.com/2", 1),
+                article("a", "https://a.example.com/1", 1),
+                article("c", "https://c.example.com/1", 2),
+            ])
+            .await
+            .unwrap();
+        let all = store.query(&ArticleQuery::default()).await.unwrap();
+        let urls: Vec<_> = all.iter().map(|a| a.article.article_url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://c.example.com/1",
+                "https://a.example.com/1",
+                "https://a.example.com/2",
+                "https://b.example.com/1",
+            ]
+        );
+        let mut sorted = all.clone();
+        sorted.reverse();
+        sort_newest_first(&mut sorted);
+        let resorted: Vec<_> = sorted.iter().map(|a| a.article.article_url.as_str()).collect();
+        assert_eq!(resorted, urls);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_encrypts_bodies() {
+        let store = SqliteStore::open_in_memory()
+            .unwrap()
+            .with_cipher(Some(StorageCipher::new(&[3u8; 32]).unwrap()));
+        let mut first = article("a", "https://a.example.com/1", 1);
+        first.text = "confidential text".into();
+        first.html = "<p>confidential html</p>".into();
+        let id = store.upsert(&first).await.unwrap();
+
+        let raw: (String, String) = store
+            .with_conn(move |conn| {
+                Ok(conn.query_row("SELECT text, html FROM articles WHERE id = ?1", [id.to_string()], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?)
+            })
+            .await
+            .unwrap();
+        assert!(encryption::is_encrypted(&raw.0) && encryption::is_encrypted(&raw.1));
+        assert!(!raw.0.contains("confidential"));
+
+        // 本文の無い記事で上書きしても暗号化した本文は残る
+        store.upsert(&article("a", "https://a.example.com/1", 1)).await.unwrap();
+        let stored = store.get(id).await.unwrap().unwrap();
+        assert_eq!(stored.article.text, "confidential text");
+        assert_eq!(stored.article.html, "<p>confidential html</p>");
+
+        // 鍵の無いストアからは読めない
+        let plain = SqliteStore { cipher: None, ..store.clone() };
+        assert!(plain.get(id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_soft_delete_and_purge() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store
+            .upsert_many(&[article("a", "https://a.example.com/1", 1), article("b", "https://b.example.com/2", 2)])
+            .await
+            .unwrap();
+        let urls = vec!["https://a.example.com/1".to_string()];
+        assert_eq!(store.soft_delete(&urls).await.unwrap(), 1);
+        assert_eq!(store.soft_delete(&urls).await.unwrap(), 0);
+        assert!(store.get_by_url(&urls[0]).await.unwrap().is_none());
+        assert_eq!(store.query(&ArticleQuery::default()).await.unwrap().len(), 1);
+
+        // 再取得しても削除済みのまま
+        store.upsert(&article("a", "https://a.example.com/1", 1)).await.unwrap();
+        assert!(store.get_by_url(&urls[0]).await.unwrap().is_none());
+
+        let deleted = store.deleted_articles().await.unwrap();
+        assert_eq!(deleted.iter().map(|s| s.article.article_url.clone()).collect::<Vec<_>>(), urls);
+        assert_eq!(store.purge_deleted().await.unwrap(), urls);
+        assert!(store.deleted_articles().await.unwrap().is_empty());
+        assert!(store.purge_deleted().await.unwrap().is_empty());
+        let remaining: i64 = store
+            .with_conn(|conn| Ok(conn.query_row("SELECT COUNT(*) FROM articles", [], |row| row.get(0))?))
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_fetch_history() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let day = |d| Local.with_ymd_and_hms(2025, 10, d, 9, 0, 0).unwrap();
+        let mut failed = FetchReport::new("b");
+        failed.record_error(&AppError::ScrapeError("timeout".into()));
+        store.record_run(day(1), &[FetchReport::new("a"), failed]).await.unwrap();
+        store.record_run(day(8), &[FetchReport::new("a")]).await.unwrap();
+
+        let runs = store.runs_between(day(1), day(8)).await.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].report.site, "b");
+        assert_eq!(runs[1].report.errors[0].kind, FailureKind::Parse);
+        assert_eq!(runs[1].report.errors[0].message, "Scrape Error: timeout");
+        assert_eq!(runs[0].run_at, day(1));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_triage() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store.upsert(&article("a", "https://a.example.com/1", 1)).await.unwrap();
+        store.upsert(&article("a", "https://a.example.com/2", 2)).await.unwrap();
+        let triage = Triage {
+            state: TriageState::InProgress,
+            assignee: Some("alice".into()),
+        };
+        assert!(store.set_triage("https://a.example.com/1", &triage).await.unwrap());
+        assert!(!store.set_triage("https://a.example.com/missing", &triage).await.unwrap());
+
+        let stored = store.get_by_url("https://a.example.com/1").await.unwrap().unwrap();
+        assert_eq!(stored.triage, triage);
+        // 再取得しても担当者と状態は維持する
+        store.upsert(&article("a", "https://a.example.com/1", 1)).await.unwrap();
+        let stored = store.get_by_url("https://a.example.com/1").await.unwrap().unwrap();
+        assert_eq!(stored.triage, triage);
+
+        let assigned = store
+            .query(&ArticleQuery { assignee: Some("alice".into()), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(assigned.len(), 1);
+        let untriaged = store
+            .query(&ArticleQuery { triage: Some(TriageState::Untriaged), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(untriaged[0].article.article_url, "https://a.example.com/2");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_annotations_and_stars() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let text = "共有のメモと個人のメモ";
+        let shared = Annotation::new("https://example.com/1", text, 0, 2, Some("triaged".into())).unwrap();
+        let personal = Annotation::new("https://example.com/1", text, 6, 8, None)
+            .unwrap()
+            .with_visibility(Visibility::Personal, Some("alice".into()));
+        store.save_annotation(&shared).await.unwrap();
+        store.save_annotation(&personal).await.unwrap();
+        let orphan = personal.clone().with_visibility(Visibility::Personal, None);
+        assert!(store.save_annotation(&orphan).await.is_err());
+
+        assert_eq!(store.annotations("https://example.com/1", Some("alice")).await.unwrap().len(), 2);
+        assert_eq!(store.annotations("https://example.com/1", Some("bob")).await.unwrap().len(), 1);
+        assert_eq!(store.annotations("https://example.com/1", None).await.unwrap().len(), 1);
+        assert!(store.get_annotation(personal.id, Some("bob")).await.unwrap().is_none());
+        assert!(!store.remove_annotation(personal.id, Some("bob")).await.unwrap());
+        assert!(store.remove_annotation(personal.id, Some("alice")).await.unwrap());
+
+        let url = "https://example.com/1";
+        store.star(&Star::new(url, Visibility::Shared, Some("alice".into()))).await.unwrap();
+        store.star(&Star::new(url, Visibility::Shared, Some("bob".into()))).await.unwrap();
+        store.star(&Star::new(url, Visibility::Personal, Some("alice".into()))).await.unwrap();
+        assert!(store.star(&Star::new(url, Visibility::Personal, None)).await.is_err());
+        assert_eq!(store.stars(None, Some("alice")).await.unwrap().len(), 2);
+        let shared_stars = store.stars(Some(Visibility::Shared), Some("bob")).await.unwrap();
+        assert_eq!(shared_stars.len(), 1);
+        // 先にスターを付けたユーザーが残る
+        assert_eq!(shared_stars[0].owner.as_deref(), Some("alice"));
+        assert!(store.stars(Some(Visibility::Personal), Some("bob")).await.unwrap().is_empty());
+        assert!(!store.unstar(url, Visibility::Personal, Some("bob")).await.unwrap());
+        assert!(store.unstar(url, Visibility::Shared, Some("bob")).await.unwrap());
+        assert_eq!(store.stars(None, Some("alice")).await.unwrap().len(), 1);
+    }
+}
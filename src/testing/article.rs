@@ -0,0 +1,127 @@
+//! テスト用の記事の組み立て．
+//!
+//! `WebArticle::new` は引数が多く，テストごとに複製すると URL が重複しやすいため，必要な項目だけを指定して
+//! 組み立てる．URL を指定しない記事には記事ごとに異なる URL を付ける．
+
+use crate::models::web_article::{Status, WebArticle};
+use crate::shared::id::WebArticleId;
+use crate::storage::StoredArticle;
+use chrono::{DateTime, Local};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ARTICLE: AtomicUsize = AtomicUsize::new(1);
+
+/// テスト用の記事．指定しない項目はサイト `Example`（`https://example.com`），記事ごとに異なる URL，
+/// 空の概要・本文，現在時刻になる．
+#[derive(Debug, Clone)]
+pub struct ArticleBuilder {
+    site_name: String,
+    site_url: String,
+    title: String,
+    url: Option<String>,
+    description: String,
+    timestamp: DateTime<Local>,
+    text: String,
+    html: String,
+}
+
+impl ArticleBuilder {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            site_name: "Example".into(),
+            site_url: "https://example.com".into(),
+            title: title.into(),
+            url: None,
+            description: String::new(),
+            timestamp: Local::now(),
+            text: String::new(),
+            html: String::new(),
+        }
+    }
+
+    pub fn with_site(mut self, name: impl Into<String>, url: impl Into<String>) -> Self {
+        self.site_name = name.into();
+        self.site_url = url.into();
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: DateTime<Local>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    pub fn with_html(mut self, html: impl Into<String>) -> Self {
+        self.html = html.into();
+        self
+    }
+
+    pub fn build(self) -> WebArticle {
+        let url = self.url.unwrap_or_else(|| {
+            let id = NEXT_ARTICLE.fetch_add(1, Ordering::Relaxed);
+            format!("{}/articles/{}", self.site_url.trim_end_matches('/'), id)
+        });
+        let mut article = WebArticle::new(
+            self.site_name,
+            self.site_url,
+            self.title,
+            url,
+            self.description,
+            self.timestamp,
+        );
+        article.text = self.text;
+        article.html = self.html;
+        article
+    }
+
+    /// 記事の公開日時に保存した未読（`Status::New`）の記事
+    pub fn stored(self) -> StoredArticle {
+        let article = self.build();
+        StoredArticle {
+            id: WebArticleId::new(),
+            status: Status::New,
+            created_at: article.timestamp,
+            updated_at: article.timestamp,
+            article,
+            triage: Default::default(),
+            highlights: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_article_builder_assigns_distinct_urls() {
+        let first = ArticleBuilder::new("同じ長さ").build();
+        let second = ArticleBuilder::new("同じ長さ").build();
+        assert_ne!(first.article_url, second.article_url);
+        assert!(first.article_url.starts_with("https://example.com/articles/"));
+
+        let stored = ArticleBuilder::new("Title")
+            .with_site("Gigazine", "https://gigazine.net")
+            .with_url("https://gigazine.net/news/1")
+            .with_text("本文")
+            .stored();
+        assert_eq!(stored.article.site.name, "Gigazine");
+        assert_eq!(stored.article.article_url, "https://gigazine.net/news/1");
+        assert_eq!(stored.article.text, "本文");
+        assert_eq!(stored.status, Status::New);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::shared::errors::{AppError, AppResult};
+use axum::Router;
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// フィクスチャの本文でサーバーの URL（`http://localhost:<port>`）に置き換える文字列
+pub const BASE_PLACEHOLDER: &str = "{base}";
+
+type Routes = Arc<HashMap<String, (&'static str, String)>>;
+
+/// パスと返す内容
+#[derive(Debug, Clone, Copy)]
+pub struct Fixture {
+    pub path: &'static str,
+    pub content_type: &'static str,
+    pub body: &'static str,
+}
+
+impl Fixture {
+    pub const fn feed(path: &'static str, body: &'static str) -> Self {
+        Self {
+            path,
+            content_type: "application/xml; charset=utf-8",
+            body,
+        }
+    }
+
+    pub const fn page(path: &'static str, body: &'static str) -> Self {
+        Self {
+            path,
+            content_type: "text/html; charset=utf-8",
+            body,
+        }
+    }
+}
+
+/// 記録したフィード・記事ページを返すローカルのサーバー．登録していないパスは 404 を返す．
+/// サイトの URL にはドメインが必要なため，`127.0.0.1` ではなく `localhost` の URL を使う．
+/// 値を破棄するとサーバーを止める．
+pub struct FixtureServer {
+    base_url: String,
+    handle: JoinHandle<()>,
+}
+
+impl FixtureServer {
+    /// 空いているポートでサーバーを起動する
+    pub async fn start(fixtures: &[Fixture]) -> AppResult<Self> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to bind fixture server: {}", e)))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| AppError::InternalError(format!("Failed to get fixture server address: {}", e)))?
+            .port();
+        let base_url = format!("http://localhost:{}", port);
+        let routes: HashMap<String, (&'static str, String)> = fixtures
+            .iter()
+            .map(|f| (f.path.to_string(), (f.content_type, f.body.replace(BASE_PLACEHOLDER, &base_url))))
+            .collect();
+        let router = Router::new().fallback(serve_fixture).with_state(Arc::new(routes));
+        let handle = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router).await {
+                tracing::error!("Fixture server stopped: {}", e);
+            }
+        });
+        Ok(Self { base_url, handle })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+impl Drop for FixtureServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn serve_fixture(State(routes): State<Routes>, uri: Uri) -> Response {
+    match routes.get(uri.path()) {
+        Some((content_type, body)) => ([(CONTENT_TYPE, *content_type)], body.clone()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixture_server_serves_registered_paths() {
+        let server = FixtureServer::start(&[Fixture::feed("/feed.xml", "<link>{base}/posts/1</link>")])
+            .await
+            .unwrap();
+        let response = request::get(server.url("/feed.xml")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/xml; charset=utf-8");
+        assert_eq!(response.text().await.unwrap(), format!("<link>{}/posts/1</link>", server.base_url()));
+
+        let missing = request::get(server.url("/posts/1")).await.unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,9 @@
+//! テスト用のユーティリティ．
+//!
+//! `article` は各モジュールのテストで使う記事の組み立て（`ArticleBuilder`）．
+//! `fixture_server` は記録したフィード・記事ページを返すローカルのサーバーで，`pipeline` はそのサーバーに対して
+//! 取得・本文の抽出・保存・書き出しを通して実行する End-to-End テスト．インターネットに接続せずに巡回の組み立てを検証する．
+
+pub mod article;
+pub mod fixture_server;
+mod pipeline;
@@ -0,0 +1,108 @@
+//! 代表的なサイト（RSS 2.0 + 本文セレクタ，Atom + Readability，フィードが消えたサイト）のフィクスチャと，
+//! それを使った取得→本文の抽出→保存→書き出しの End-to-End テスト．
+
+use crate::models::sites::generic_feed::{FeedKind, GenericFeedSite};
+use crate::models::web_article::WebSiteInterface;
+use crate::shared::errors::AppResult;
+use crate::testing::fixture_server::Fixture;
+
+/// 記録したフィード・記事ページ
+pub const FIXTURES: &[Fixture] = &[
+    Fixture::feed("/tech/feed.xml", include_str!("fixtures/tech_blog/feed.xml")),
+    Fixture::page(
+        "/tech/posts/async-runtime.html",
+        include_str!("fixtures/tech_blog/async-runtime.html"),
+    ),
+    Fixture::page("/tech/posts/sqlite-wal.html", include_str!("fixtures/tech_blog/sqlite-wal.html")),
+    Fixture::feed("/news/atom.xml", include_str!("fixtures/news/atom.xml")),
+    Fixture::page(
+        "/news/2025/10/14/reasoning-benchmark",
+        include_str!("fixtures/news/reasoning-benchmark.html"),
+    ),
+];
+
+/// フィクスチャのサーバー（`base_url`）を取得先とするサイト一覧
+pub fn fixture_sites(base_url: &str) -> AppResult<Vec<Box<dyn WebSiteInterface>>> {
+    Ok(vec![
+        Box::new(
+            GenericFeedSite::new("Fixture Tech Blog", &format!("{}/tech/feed.xml", base_url), FeedKind::Rss2)?
+                .with_content_selector("div.entry-content"),
+        ),
+        Box::new(GenericFeedSite::new(
+            "Fixture News",
+            &format!("{}/news/atom.xml", base_url),
+            FeedKind::Atom,
+        )?),
+        // フィードを返さなくなったサイト（404）
+        Box::new(GenericFeedSite::new(
+            "Fixture Gone",
+            &format!("{}/gone/feed.xml", base_url),
+            FeedKind::Rss2,
+        )?),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::commands::{ExportFormat, render_export};
+    use crate::export::jsonl::ArticleRecord;
+    use crate::models::{FetchSchedule, fetch_and_parse_with, fetch_articles_fairly};
+    use crate::storage::sqlite::SqliteStore;
+    use crate::storage::{ArticleQuery, ArticleStore};
+    use crate::testing::fixture_server::FixtureServer;
+
+    #[tokio::test]
+    async fn test_pipeline_against_fixture_server() {
+        let server = FixtureServer::start(FIXTURES).await.unwrap();
+        let base_url = server.base_url().to_string();
+
+        // 取得：失敗したサイトがあっても他のサイトの記事は返す
+        let outcome = fetch_articles_fairly(fixture_sites(&base_url).unwrap(), 2, FetchSchedule::default()).await;
+        let failed: Vec<&str> = outcome.errors().map(|(site, _)| site).collect();
+        assert_eq!(failed, ["Fixture Gone"]);
+        assert_eq!(outcome.articles.len(), 3);
+        assert!(outcome.articles.iter().all(|a| a.article_url.starts_with(&base_url)));
+
+        // 本文の抽出：ナビゲーション・広告・サイドバーを除く
+        let mut articles = outcome.articles;
+        let report = fetch_and_parse_with(&mut articles, 2, || fixture_sites(&base_url)).await.unwrap();
+        assert_eq!(report.parsed, 3, "{:?}", report.errors);
+        assert!(report.errors.is_empty());
+        // 記事ページの抽出品質もサイトごとに計測する
+        assert_eq!(report.quality.values().map(|q| q.articles).sum::<usize>(), 3, "{:?}", report.quality);
+        let post = articles.iter().find(|a| a.article_url.ends_with("/async-runtime.html")).unwrap();
+        assert!(post.text.contains("tokio に移行しました"));
+        assert!(post.text.contains("tokio::time::timeout"));
+        for boilerplate in ["Sponsored", "Share on X", "Related posts", "About", "Copyright"] {
+            assert!(!post.html.contains(boilerplate), "{} should be removed", boilerplate);
+        }
+        let story = articles.iter().find(|a| a.site.name == "Fixture News").unwrap();
+        assert!(story.text.contains("reasoning benchmark"));
+        assert!(!story.text.contains("Most read"));
+
+        // 保存：本文ごと保存し，新しい順に取り出せる
+        let store = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(store.upsert_many(&articles).await.unwrap(), 3);
+        let stored = store.query(&ArticleQuery::default()).await.unwrap();
+        let titles: Vec<&str> = stored.iter().map(|s| s.article.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "Open source model tops new reasoning benchmark",
+                "Rust の非同期ランタイムを移行した話",
+                "SQLite の WAL モードで書き込みを並列化する",
+            ]
+        );
+
+        // 書き出し
+        let jsonl = render_export(ExportFormat::Jsonl, &stored, None).unwrap();
+        let records: Vec<ArticleRecord> = jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|r| r.id.is_some() && !r.text.trim().is_empty()));
+        let markdown = render_export(ExportFormat::Md, &stored, None).unwrap();
+        assert!(markdown.contains("## Fixture Tech Blog"));
+        assert!(markdown.contains(&format!("({}/news/2025/10/14/reasoning-benchmark)", base_url)));
+        assert!(!markdown.contains("Fixture Gone"));
+    }
+}